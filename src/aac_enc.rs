@@ -0,0 +1,162 @@
+//! Multichannel AAC-LC encoding on top of the raw fdk-aac API.
+//!
+//! The `fdk-aac` crate's encoder only exposes mono and stereo channel modes,
+//! so layouts above stereo (3.0 through 5.1, and front-wide 7.1) open the library
+//! directly. Input is interleaved in WAV channel order; fdk is told so via
+//! `AACENC_CHANNELORDER` and reorders to MPEG element order itself. The
+//! output is raw access units for the MP4 writer in `wave.rs`.
+
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::ptr;
+
+use anyhow::Result;
+use fdk_aac_sys as sys;
+
+/// WAV channel mask of the only 7.1 layout the encoder accepts: front L/R,
+/// C, LFE, back Ls/Rs and the front-of-centre Lc/Rc pair. That is MPEG-4
+/// channel configuration 7, the only 8-channel configuration the `mp4`
+/// crate can signal; other 7.1 layouts need a PCE it cannot write, so the
+/// M4A export folds them to 5.1 first.
+pub const FRONT_WIDE_7_1_MASK: u32 = 0xFF;
+
+/// `CHANNEL_MODE` values from `FDK_audio.h`, keyed by channel count.
+/// 8 channels are `MODE_1_2_2_2_1`, see [`FRONT_WIDE_7_1_MASK`].
+fn channel_mode(channels: usize) -> Option<u32> {
+    match channels {
+        3 => Some(3),
+        4 => Some(4),
+        5 => Some(5),
+        6 => Some(6),
+        8 => Some(7),
+        _ => None,
+    }
+}
+
+/// Channel counts [`MultichannelAacEncoder`] accepts.
+pub fn supports_channels(channels: usize) -> bool {
+    channel_mode(channels).is_some()
+}
+
+pub struct MultichannelAacEncoder {
+    handle: sys::HANDLE_AACENCODER,
+}
+
+// SAFETY: the encoder handle is only used through `&mut self`.
+unsafe impl Send for MultichannelAacEncoder {}
+
+impl Drop for MultichannelAacEncoder {
+    fn drop(&mut self) {
+        unsafe {
+            sys::aacEncClose(&mut self.handle);
+        }
+    }
+}
+
+fn check(code: sys::AACENC_ERROR, what: &str) -> Result<()> {
+    if code == sys::AACENC_ERROR_AACENC_OK {
+        Ok(())
+    } else {
+        anyhow::bail!("aac {what}: fdk error {code:#x}")
+    }
+}
+
+impl MultichannelAacEncoder {
+    pub fn new(sample_rate: u32, channels: usize, bitrate: u32) -> Result<Self> {
+        let Some(mode) = channel_mode(channels) else {
+            anyhow::bail!("aac: unsupported channel count {channels}");
+        };
+        let mut handle: sys::HANDLE_AACENCODER = ptr::null_mut();
+        check(
+            unsafe { sys::aacEncOpen(&mut handle, 0, channels as u32) },
+            "encoder open",
+        )?;
+        let encoder = Self { handle };
+        let params = [
+            (sys::AACENC_PARAM_AACENC_AOT, 2), // AAC-LC
+            (sys::AACENC_PARAM_AACENC_SAMPLERATE, sample_rate),
+            (sys::AACENC_PARAM_AACENC_CHANNELMODE, mode),
+            (sys::AACENC_PARAM_AACENC_CHANNELORDER, 1), // WAV order
+            (sys::AACENC_PARAM_AACENC_BITRATEMODE, 0),  // CBR
+            (sys::AACENC_PARAM_AACENC_BITRATE, bitrate),
+            (sys::AACENC_PARAM_AACENC_TRANSMUX, 0), // raw access units
+            (sys::AACENC_PARAM_AACENC_AFTERBURNER, 1),
+        ];
+        for (param, value) in params {
+            check(
+                unsafe { sys::aacEncoder_SetParam(encoder.handle, param, value) },
+                "encoder parameter",
+            )?;
+        }
+        // A null call applies the parameters.
+        check(
+            unsafe {
+                sys::aacEncEncode(
+                    encoder.handle,
+                    ptr::null(),
+                    ptr::null(),
+                    ptr::null(),
+                    ptr::null_mut(),
+                )
+            },
+            "encoder init",
+        )?;
+        Ok(encoder)
+    }
+
+    pub fn info(&self) -> Result<sys::AACENC_InfoStruct> {
+        let mut info = unsafe { std::mem::zeroed::<sys::AACENC_InfoStruct>() };
+        check(
+            unsafe { sys::aacEncInfo(self.handle, &mut info) },
+            "encoder info",
+        )?;
+        Ok(info)
+    }
+
+    /// Encodes interleaved samples (an empty slice flushes). Returns the
+    /// number of input samples consumed and output bytes written.
+    pub fn encode(&mut self, input: &[i16], output: &mut [u8]) -> Result<(usize, usize)> {
+        let mut in_ptr = input.as_ptr() as *mut c_void;
+        let mut in_id = sys::AACENC_BufferIdentifier_IN_AUDIO_DATA as c_int;
+        let mut in_size = std::mem::size_of_val(input) as c_int;
+        let mut in_el_size = std::mem::size_of::<i16>() as c_int;
+        let in_desc = sys::AACENC_BufDesc {
+            numBufs: 1,
+            bufs: &mut in_ptr,
+            bufferIdentifiers: &mut in_id,
+            bufSizes: &mut in_size,
+            bufElSizes: &mut in_el_size,
+        };
+        let mut out_ptr = output.as_mut_ptr() as *mut c_void;
+        let mut out_id = sys::AACENC_BufferIdentifier_OUT_BITSTREAM_DATA as c_int;
+        let mut out_size = output.len() as c_int;
+        let mut out_el_size: c_int = 1;
+        let out_desc = sys::AACENC_BufDesc {
+            numBufs: 1,
+            bufs: &mut out_ptr,
+            bufferIdentifiers: &mut out_id,
+            bufSizes: &mut out_size,
+            bufElSizes: &mut out_el_size,
+        };
+        let in_args = sys::AACENC_InArgs {
+            // -1 asks the encoder to flush its delay line.
+            numInSamples: if input.is_empty() {
+                -1
+            } else {
+                input.len() as c_int
+            },
+            numAncBytes: 0,
+        };
+        let mut out_args = unsafe { std::mem::zeroed::<sys::AACENC_OutArgs>() };
+        let code =
+            unsafe { sys::aacEncEncode(self.handle, &in_desc, &out_desc, &in_args, &mut out_args) };
+        if code == sys::AACENC_ERROR_AACENC_ENCODE_EOF {
+            return Ok((0, 0));
+        }
+        check(code, "encode")?;
+        Ok((
+            out_args.numInSamples.max(0) as usize,
+            out_args.numOutBytes.max(0) as usize,
+        ))
+    }
+}
@@ -0,0 +1,580 @@
+//! Audio Definition Model (ITU-R BS.2076) metadata carried by BW64 files.
+//!
+//! `chna` maps each audio track of the WAVE file to an `audioTrackUID` and
+//! its track / pack format; `axml` holds the ADM tree
+//! (audioProgramme → audioContent → audioObject → audioPackFormat …).
+//! This module reads both and resolves per-channel object, pack and
+//! channel names for the editor lanes. IDs that refer to the common
+//! definitions (BS.2094) without defining them in `axml` fall back to the
+//! built-in names below.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::Result;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader as XmlReader;
+use serde::{Deserialize, Serialize};
+
+/// `chna` entry: track index (1-based), UID, track and pack format refs.
+pub const CHNA_ENTRY_LEN: usize = 40;
+/// Upper bound on ADM elements kept from one `axml` payload.
+const MAX_ADM_ELEMENTS: usize = 100_000;
+/// Largest `axml` / `chna` payload read for lane names.
+const MAX_ADM_CHUNK_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChnaTrack {
+    /// 1-based index of the track (channel) in the `data` chunk.
+    pub track_index: u16,
+    pub uid: String,
+    pub track_format_ref: String,
+    pub pack_format_ref: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Chna {
+    pub num_tracks: u16,
+    pub num_uids: u16,
+    pub tracks: Vec<ChnaTrack>,
+}
+
+impl Chna {
+    /// Entries mapped onto 0-based channel `channel`.
+    pub fn tracks_for_channel(&self, channel: usize) -> impl Iterator<Item = &ChnaTrack> {
+        self.tracks
+            .iter()
+            .filter(move |track| track.track_index as usize == channel + 1)
+    }
+}
+
+fn fixed_ascii(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Parse a `chna` payload. Entries with track index 0 are unused slots and
+/// are dropped.
+pub fn parse_chna(payload: &[u8]) -> Option<Chna> {
+    let header = payload.get(0..4)?;
+    let num_tracks = u16::from_le_bytes([header[0], header[1]]);
+    let num_uids = u16::from_le_bytes([header[2], header[3]]);
+    let tracks = payload[4..]
+        .chunks_exact(CHNA_ENTRY_LEN)
+        .filter_map(parse_chna_entry)
+        .collect();
+    Some(Chna {
+        num_tracks,
+        num_uids,
+        tracks,
+    })
+}
+
+/// One 40-byte `chna` entry; `None` for short or unused (index 0) slots.
+pub fn parse_chna_entry(entry: &[u8]) -> Option<ChnaTrack> {
+    let entry = entry.get(..CHNA_ENTRY_LEN)?;
+    let track_index = u16::from_le_bytes([entry[0], entry[1]]);
+    (track_index != 0).then(|| ChnaTrack {
+        track_index,
+        uid: fixed_ascii(&entry[2..14]),
+        track_format_ref: fixed_ascii(&entry[14..28]),
+        pack_format_ref: fixed_ascii(&entry[28..39]),
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdmKind {
+    AudioProgramme,
+    AudioContent,
+    AudioObject,
+    AudioPackFormat,
+    AudioChannelFormat,
+    AudioStreamFormat,
+    AudioTrackFormat,
+    AudioTrackUid,
+}
+
+impl AdmKind {
+    fn from_element(local: &str) -> Option<Self> {
+        Some(match local {
+            "audioProgramme" => Self::AudioProgramme,
+            "audioContent" => Self::AudioContent,
+            "audioObject" => Self::AudioObject,
+            "audioPackFormat" => Self::AudioPackFormat,
+            "audioChannelFormat" => Self::AudioChannelFormat,
+            "audioStreamFormat" => Self::AudioStreamFormat,
+            "audioTrackFormat" => Self::AudioTrackFormat,
+            "audioTrackUID" => Self::AudioTrackUid,
+            _ => return None,
+        })
+    }
+
+    pub fn element_name(self) -> &'static str {
+        match self {
+            Self::AudioProgramme => "audioProgramme",
+            Self::AudioContent => "audioContent",
+            Self::AudioObject => "audioObject",
+            Self::AudioPackFormat => "audioPackFormat",
+            Self::AudioChannelFormat => "audioChannelFormat",
+            Self::AudioStreamFormat => "audioStreamFormat",
+            Self::AudioTrackFormat => "audioTrackFormat",
+            Self::AudioTrackUid => "audioTrackUID",
+        }
+    }
+
+    fn id_attribute(self) -> &'static str {
+        match self {
+            Self::AudioProgramme => "audioProgrammeID",
+            Self::AudioContent => "audioContentID",
+            Self::AudioObject => "audioObjectID",
+            Self::AudioPackFormat => "audioPackFormatID",
+            Self::AudioChannelFormat => "audioChannelFormatID",
+            Self::AudioStreamFormat => "audioStreamFormatID",
+            Self::AudioTrackFormat => "audioTrackFormatID",
+            Self::AudioTrackUid => "UID",
+        }
+    }
+
+    fn name_attribute(self) -> Option<&'static str> {
+        Some(match self {
+            Self::AudioProgramme => "audioProgrammeName",
+            Self::AudioContent => "audioContentName",
+            Self::AudioObject => "audioObjectName",
+            Self::AudioPackFormat => "audioPackFormatName",
+            Self::AudioChannelFormat => "audioChannelFormatName",
+            Self::AudioStreamFormat => "audioStreamFormatName",
+            Self::AudioTrackFormat => "audioTrackFormatName",
+            Self::AudioTrackUid => return None,
+        })
+    }
+}
+
+/// One ADM element with its `…IDRef` / `audioTrackUIDRef` children.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdmElement {
+    pub kind: AdmKind,
+    pub id: String,
+    pub name: String,
+    pub type_definition: Option<String>,
+    /// (reference element name, referenced ID) in document order.
+    pub refs: Vec<(String, String)>,
+}
+
+impl AdmElement {
+    pub fn refs_to<'a>(&'a self, element: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.refs
+            .iter()
+            .filter(move |(name, _)| name == element)
+            .map(|(_, id)| id.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Adm {
+    pub elements: Vec<AdmElement>,
+}
+
+impl Adm {
+    pub fn of_kind(&self, kind: AdmKind) -> impl Iterator<Item = &AdmElement> {
+        self.elements.iter().filter(move |e| e.kind == kind)
+    }
+
+    pub fn find(&self, kind: AdmKind, id: &str) -> Option<&AdmElement> {
+        self.of_kind(kind).find(|e| e.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Name of `id`, falling back to the common definitions.
+    fn name_of(&self, kind: AdmKind, id: &str) -> Option<String> {
+        self.find(kind, id)
+            .map(|e| e.name.clone())
+            .filter(|name| !name.is_empty())
+            .or_else(|| common_definition_name(id).map(str::to_string))
+    }
+
+    /// Channel format of a track format: through its stream format when the
+    /// file defines them, otherwise by the common-definition numbering
+    /// (`AT_xxxxxxxx_nn` → `AC_xxxxxxxx`).
+    fn channel_format_of_track(&self, track_format: &str) -> Option<String> {
+        let stream = self
+            .find(AdmKind::AudioTrackFormat, track_format)
+            .and_then(|track| track.refs_to("audioStreamFormatIDRef").next())
+            .and_then(|stream| self.find(AdmKind::AudioStreamFormat, stream));
+        if let Some(channel) = stream.and_then(|s| s.refs_to("audioChannelFormatIDRef").next()) {
+            return Some(channel.to_string());
+        }
+        let digits = track_format.strip_prefix("AT_")?.get(..8)?;
+        Some(format!("AC_{digits}"))
+    }
+
+    /// The audioObject that lists `uid` among its `audioTrackUIDRef`s.
+    fn object_for_uid(&self, uid: &str) -> Option<&AdmElement> {
+        self.of_kind(AdmKind::AudioObject).find(|object| {
+            object
+                .refs_to("audioTrackUIDRef")
+                .any(|r| r.eq_ignore_ascii_case(uid))
+        })
+    }
+
+    /// Object / pack / channel names for each of `channels` channels.
+    pub fn lanes(&self, chna: &Chna, channels: usize) -> Vec<AdmLane> {
+        (0..channels)
+            .map(|channel| {
+                let Some(track) = chna.tracks_for_channel(channel).next() else {
+                    return AdmLane::default();
+                };
+                let uid = self.find(AdmKind::AudioTrackUid, &track.uid);
+                let pack_ref = uid
+                    .and_then(|u| u.refs_to("audioPackFormatIDRef").next())
+                    .map(str::to_string)
+                    .or_else(|| {
+                        (!track.pack_format_ref.is_empty()).then(|| track.pack_format_ref.clone())
+                    });
+                let channel_ref = uid
+                    .and_then(|u| u.refs_to("audioChannelFormatIDRef").next())
+                    .map(str::to_string)
+                    .or_else(|| self.channel_format_of_track(&track.track_format_ref));
+                AdmLane {
+                    object: self.object_for_uid(&track.uid).map(|o| o.name.clone()),
+                    pack: pack_ref.and_then(|id| self.name_of(AdmKind::AudioPackFormat, &id)),
+                    channel: channel_ref
+                        .and_then(|id| self.name_of(AdmKind::AudioChannelFormat, &id)),
+                }
+            })
+            .collect()
+    }
+
+    /// audioObjects with their pack names and the channels their track
+    /// UIDs occupy.
+    pub fn object_summaries(&self, chna: Option<&Chna>) -> Vec<AdmObjectSummary> {
+        self.of_kind(AdmKind::AudioObject)
+            .map(|object| {
+                let mut channels: Vec<u16> = object
+                    .refs_to("audioTrackUIDRef")
+                    .flat_map(|uid| {
+                        chna.into_iter()
+                            .flat_map(|c| c.tracks.iter())
+                            .filter(move |t| t.uid.eq_ignore_ascii_case(uid))
+                            .map(|t| t.track_index)
+                    })
+                    .collect();
+                channels.sort_unstable();
+                channels.dedup();
+                AdmObjectSummary {
+                    id: object.id.clone(),
+                    name: object.name.clone(),
+                    packs: object
+                        .refs_to("audioPackFormatIDRef")
+                        .map(|id| {
+                            self.name_of(AdmKind::AudioPackFormat, id)
+                                .unwrap_or_else(|| id.to_string())
+                        })
+                        .collect(),
+                    track_uids: object
+                        .refs_to("audioTrackUIDRef")
+                        .map(str::to_string)
+                        .collect(),
+                    channels,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Names of one channel's ADM assignment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdmLane {
+    pub object: Option<String>,
+    pub pack: Option<String>,
+    pub channel: Option<String>,
+}
+
+impl AdmLane {
+    pub fn is_empty(&self) -> bool {
+        self.object.is_none() && self.pack.is_none() && self.channel.is_none()
+    }
+
+    /// `Object / pack`, the part shown next to the speaker label.
+    pub fn label(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.object.as_deref(), self.pack.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" / "))
+    }
+
+    /// Object, pack and channel format on separate lines (hover text).
+    pub fn describe(&self) -> String {
+        [
+            ("Object", &self.object),
+            ("Pack", &self.pack),
+            ("Channel", &self.channel),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| format!("{key}: {v}")))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
+
+/// One audioObject as listed by `item metadata summary`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdmObjectSummary {
+    pub id: String,
+    pub name: String,
+    pub packs: Vec<String>,
+    pub track_uids: Vec<String>,
+    /// 1-based track indices from `chna`.
+    pub channels: Vec<u16>,
+}
+
+/// Parse the ADM elements of an `axml` payload. Unknown elements (block
+/// formats, frame headers, …) are skipped; DTDs are never expanded.
+pub fn parse_axml(bytes: &[u8]) -> Result<Adm> {
+    let mut reader = XmlReader::from_reader(Cursor::new(bytes));
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut adm = Adm::default();
+    // Open ADM element (index, depth) and open reference element name.
+    let mut current: Option<(usize, usize)> = None;
+    let mut open_ref: Option<(String, String)> = None;
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(event) => {
+                depth += 1;
+                let name = String::from_utf8_lossy(event.name().as_ref()).into_owned();
+                let local = name.rsplit(':').next().unwrap_or(&name).to_string();
+                if let Some(kind) = AdmKind::from_element(&local) {
+                    if adm.elements.len() >= MAX_ADM_ELEMENTS {
+                        anyhow::bail!("ADM element limit exceeded");
+                    }
+                    let element = read_element(&reader, &event, kind);
+                    adm.elements.push(element);
+                    current = Some((adm.elements.len() - 1, depth));
+                } else if current.is_some() && is_reference_element(&local) {
+                    open_ref = Some((local, String::new()));
+                }
+            }
+            Event::Empty(event) => {
+                let name = String::from_utf8_lossy(event.name().as_ref()).into_owned();
+                let local = name.rsplit(':').next().unwrap_or(&name);
+                if let Some(kind) = AdmKind::from_element(local) {
+                    if adm.elements.len() >= MAX_ADM_ELEMENTS {
+                        anyhow::bail!("ADM element limit exceeded");
+                    }
+                    let element = read_element(&reader, &event, kind);
+                    adm.elements.push(element);
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, value)) = open_ref.as_mut() {
+                    let decoded = text
+                        .xml_content()
+                        .map(|value| value.into_owned())
+                        .unwrap_or_else(|_| String::from_utf8_lossy(text.as_ref()).to_string());
+                    value.push_str(&decoded);
+                }
+            }
+            Event::End(_) => {
+                if let Some((element, value)) = open_ref.take() {
+                    if let Some((index, _)) = current {
+                        let value = value.trim();
+                        if !value.is_empty() {
+                            adm.elements[index].refs.push((element, value.to_string()));
+                        }
+                    }
+                } else if current.is_some_and(|(_, open_depth)| open_depth == depth) {
+                    current = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(adm)
+}
+
+fn is_reference_element(local: &str) -> bool {
+    local.ends_with("IDRef") || local == "audioTrackUIDRef"
+}
+
+fn read_element(
+    reader: &XmlReader<Cursor<&[u8]>>,
+    event: &quick_xml::events::BytesStart<'_>,
+    kind: AdmKind,
+) -> AdmElement {
+    let mut element = AdmElement {
+        kind,
+        id: String::new(),
+        name: String::new(),
+        type_definition: None,
+        refs: Vec::new(),
+    };
+    for attribute in event.attributes().with_checks(false).flatten() {
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        let value = attribute
+            .decode_and_unescape_value(reader.decoder())
+            .map(|value| value.into_owned())
+            .unwrap_or_else(|_| String::from_utf8_lossy(attribute.value.as_ref()).to_string());
+        if key == kind.id_attribute() {
+            element.id = value;
+        } else if Some(key.as_str()) == kind.name_attribute() {
+            element.name = value;
+        } else if key == "typeDefinition" {
+            element.type_definition = Some(value);
+        }
+    }
+    element
+}
+
+/// Names of the BS.2094 common-definition packs and DirectSpeakers
+/// channels that files reference without defining.
+fn common_definition_name(id: &str) -> Option<&'static str> {
+    let id = id.to_ascii_uppercase();
+    Some(match id.as_str() {
+        "AP_00010001" => "mono",
+        "AP_00010002" => "stereo",
+        "AP_00010003" => "5.1",
+        "AC_00010001" => "FrontLeft",
+        "AC_00010002" => "FrontRight",
+        "AC_00010003" => "FrontCentre",
+        "AC_00010004" => "LowFrequencyEffects",
+        "AC_00010005" => "SurroundLeft",
+        "AC_00010006" => "SurroundRight",
+        "AC_00010007" => "FrontLeftOfCentre",
+        "AC_00010008" => "FrontRightOfCentre",
+        "AC_00010009" => "BackCentre",
+        "AC_0001000A" => "SideLeft",
+        "AC_0001000B" => "SideRight",
+        "AC_0001000C" => "TopCentre",
+        "AC_0001000D" => "TopFrontLeft",
+        "AC_0001000E" => "TopFrontCentre",
+        "AC_0001000F" => "TopFrontRight",
+        "AC_00010010" => "TopBackLeft",
+        "AC_00010011" => "TopBackCentre",
+        "AC_00010012" => "TopBackRight",
+        _ => return None,
+    })
+}
+
+/// `chna`, parsed `axml` and `fmt ` channel count of a RIFF/RF64/BW64 file
+/// that has a `chna` chunk.
+pub fn read_adm(path: &Path) -> Option<(Chna, Option<Adm>, usize)> {
+    let chunks = crate::wav_stream::read_wave_metadata_chunks(path, MAX_ADM_CHUNK_BYTES)
+        .ok()
+        .flatten()?;
+    let payload_of = |id: &[u8; 4]| {
+        chunks
+            .iter()
+            .find(|(chunk_id, _)| chunk_id == id)
+            .map(|(_, payload)| payload.as_slice())
+    };
+    let chna = parse_chna(payload_of(b"chna")?)?;
+    let channels = payload_of(b"fmt ")
+        .and_then(|fmt| fmt.get(2..4))
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)?;
+    let adm = payload_of(b"axml").and_then(|xml| parse_axml(xml).ok());
+    Some((chna, adm, channels))
+}
+
+/// Per-channel ADM names, or `None` when the file carries no `chna` or
+/// none of its tracks resolve to a name.
+pub fn read_adm_lanes(path: &Path) -> Option<Vec<AdmLane>> {
+    let (chna, adm, channels) = read_adm(path)?;
+    let lanes = adm.unwrap_or_default().lanes(&chna, channels);
+    lanes.iter().any(|lane| !lane.is_empty()).then_some(lanes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chna_entry(index: u16, uid: &str, track: &str, pack: &str) -> Vec<u8> {
+        let mut entry = index.to_le_bytes().to_vec();
+        for (text, len) in [(uid, 12), (track, 14), (pack, 11)] {
+            let mut field = text.as_bytes().to_vec();
+            field.resize(len, 0);
+            entry.extend_from_slice(&field);
+        }
+        entry.push(0);
+        entry
+    }
+
+    const AXML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ebuCoreMain xmlns="urn:ebu:metadata-schema:ebuCore_2015"><coreMetadata><format><audioFormatExtended>
+  <audioProgramme audioProgrammeID="APR_1001" audioProgrammeName="Main">
+    <audioContentIDRef>ACO_1001</audioContentIDRef>
+  </audioProgramme>
+  <audioContent audioContentID="ACO_1001" audioContentName="Bed">
+    <audioObjectIDRef>AO_1001</audioObjectIDRef>
+  </audioContent>
+  <audioObject audioObjectID="AO_1001" audioObjectName="Music &amp; FX">
+    <audioPackFormatIDRef>AP_00010002</audioPackFormatIDRef>
+    <audioTrackUIDRef>ATU_00000001</audioTrackUIDRef>
+    <audioTrackUIDRef>ATU_00000002</audioTrackUIDRef>
+  </audioObject>
+  <audioObject audioObjectID="AO_1002" audioObjectName="Dialogue">
+    <audioPackFormatIDRef>AP_00031001</audioPackFormatIDRef>
+    <audioTrackUIDRef>ATU_00000003</audioTrackUIDRef>
+  </audioObject>
+  <audioPackFormat audioPackFormatID="AP_00031001" audioPackFormatName="Dialogue object" typeDefinition="Objects">
+    <audioChannelFormatIDRef>AC_00031001</audioChannelFormatIDRef>
+  </audioPackFormat>
+  <audioChannelFormat audioChannelFormatID="AC_00031001" audioChannelFormatName="DialogueCh" typeDefinition="Objects"/>
+  <audioTrackFormat audioTrackFormatID="AT_00031001_01" audioTrackFormatName="t">
+    <audioStreamFormatIDRef>AS_00031001</audioStreamFormatIDRef>
+  </audioTrackFormat>
+  <audioStreamFormat audioStreamFormatID="AS_00031001" audioStreamFormatName="s">
+    <audioChannelFormatIDRef>AC_00031001</audioChannelFormatIDRef>
+  </audioStreamFormat>
+</audioFormatExtended></format></coreMetadata></ebuCoreMain>"#;
+
+    #[test]
+    fn chna_and_axml_resolve_object_pack_and_channel_per_lane() {
+        let mut chna = Vec::new();
+        chna.extend_from_slice(&3u16.to_le_bytes());
+        chna.extend_from_slice(&3u16.to_le_bytes());
+        chna.extend(chna_entry(
+            1,
+            "ATU_00000001",
+            "AT_00010001_01",
+            "AP_00010002",
+        ));
+        chna.extend(chna_entry(
+            2,
+            "ATU_00000002",
+            "AT_00010002_01",
+            "AP_00010002",
+        ));
+        chna.extend(chna_entry(
+            3,
+            "ATU_00000003",
+            "AT_00031001_01",
+            "AP_00031001",
+        ));
+        let chna = parse_chna(&chna).unwrap();
+        assert_eq!(chna.tracks.len(), 3);
+        assert_eq!(chna.tracks[2].track_format_ref, "AT_00031001_01");
+
+        let adm = parse_axml(AXML.as_bytes()).unwrap();
+        assert_eq!(adm.of_kind(AdmKind::AudioObject).count(), 2);
+        let lanes = adm.lanes(&chna, 4);
+        assert_eq!(lanes[0].object.as_deref(), Some("Music & FX"));
+        assert_eq!(lanes[0].pack.as_deref(), Some("stereo"));
+        assert_eq!(lanes[1].channel.as_deref(), Some("FrontRight"));
+        assert_eq!(
+            lanes[2].label().as_deref(),
+            Some("Dialogue / Dialogue object")
+        );
+        assert_eq!(lanes[2].channel.as_deref(), Some("DialogueCh"));
+        assert!(lanes[3].is_empty());
+
+        let objects = adm.object_summaries(Some(&chna));
+        assert_eq!(objects[0].channels, [1, 2]);
+        assert_eq!(objects[1].packs, ["Dialogue object"]);
+    }
+}
@@ -0,0 +1,805 @@
+//! Realtime analysis behind the Analyzer panel: an FFT / 1/3-octave RTA with
+//! power averaging and peak hold, goniometer points and a stereo correlation
+//! history. Like `meter.rs` these are plain state machines — the meter thread
+//! feeds them from the `MeterTap` ring (see `audio.rs`) and publishes an
+//! [`AnalyzerFrame`] for the UI; the audio callback never runs any of it.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+use realfft::num_complex::Complex;
+use realfft::{RealFftPlanner, RealToComplex};
+
+/// Display floor of the RTA, in dBFS.
+pub const ANALYZER_DB_FLOOR: f32 = -96.0;
+/// Lowest frequency on the FFT display's log axis.
+pub const ANALYZER_MIN_HZ: f32 = 20.0;
+/// Nominal (ISO 266) 1/3-octave band centres, 20 Hz - 20 kHz.
+pub const THIRD_OCTAVE_CENTERS_HZ: [f32; 31] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1_000.0, 1_250.0, 1_600.0, 2_000.0, 2_500.0, 3_150.0, 4_000.0, 5_000.0,
+    6_300.0, 8_000.0, 10_000.0, 12_500.0, 16_000.0, 20_000.0,
+];
+/// Correlation readings kept for the history strip (one per processed chunk).
+pub const CORRELATION_HISTORY_LEN: usize = 256;
+/// Upper bound on goniometer points published per frame.
+pub const GONIOMETER_MAX_POINTS: usize = 1_024;
+/// RTA window length; the FFT size is the next power of two.
+const FFT_WINDOW_SECS: f32 = 0.17;
+/// How long a timed peak sits still before it starts falling.
+const PEAK_HOLD_SECS: f32 = 2.0;
+const PEAK_FALL_DB_PER_SEC: f32 = 24.0;
+
+/// Exponential power averaging of the RTA.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnalyzerAveraging {
+    Off,
+    #[default]
+    Fast,
+    Slow,
+}
+
+impl AnalyzerAveraging {
+    pub const ALL: [AnalyzerAveraging; 3] = [
+        AnalyzerAveraging::Off,
+        AnalyzerAveraging::Fast,
+        AnalyzerAveraging::Slow,
+    ];
+
+    pub fn prefs_name(self) -> &'static str {
+        match self {
+            AnalyzerAveraging::Off => "off",
+            AnalyzerAveraging::Fast => "fast",
+            AnalyzerAveraging::Slow => "slow",
+        }
+    }
+
+    pub fn from_prefs_name(s: &str) -> Option<AnalyzerAveraging> {
+        match s.trim() {
+            "off" => Some(AnalyzerAveraging::Off),
+            "fast" => Some(AnalyzerAveraging::Fast),
+            "slow" => Some(AnalyzerAveraging::Slow),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnalyzerAveraging::Off => "Off",
+            AnalyzerAveraging::Fast => "Fast (125 ms)",
+            AnalyzerAveraging::Slow => "Slow (1 s)",
+        }
+    }
+
+    fn time_constant_secs(self) -> f32 {
+        match self {
+            AnalyzerAveraging::Off => 0.0,
+            AnalyzerAveraging::Fast => 0.125,
+            AnalyzerAveraging::Slow => 1.0,
+        }
+    }
+
+    fn from_u8(v: u8) -> AnalyzerAveraging {
+        match v {
+            0 => AnalyzerAveraging::Off,
+            2 => AnalyzerAveraging::Slow,
+            _ => AnalyzerAveraging::Fast,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            AnalyzerAveraging::Off => 0,
+            AnalyzerAveraging::Fast => 1,
+            AnalyzerAveraging::Slow => 2,
+        }
+    }
+}
+
+/// Peak-hold trace of the RTA.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnalyzerPeakHold {
+    Off,
+    /// Hold for two seconds, then fall back toward the live trace.
+    #[default]
+    Timed,
+    /// Hold until reset (or until playback stops).
+    Infinite,
+}
+
+impl AnalyzerPeakHold {
+    pub const ALL: [AnalyzerPeakHold; 3] = [
+        AnalyzerPeakHold::Off,
+        AnalyzerPeakHold::Timed,
+        AnalyzerPeakHold::Infinite,
+    ];
+
+    pub fn prefs_name(self) -> &'static str {
+        match self {
+            AnalyzerPeakHold::Off => "off",
+            AnalyzerPeakHold::Timed => "timed",
+            AnalyzerPeakHold::Infinite => "infinite",
+        }
+    }
+
+    pub fn from_prefs_name(s: &str) -> Option<AnalyzerPeakHold> {
+        match s.trim() {
+            "off" => Some(AnalyzerPeakHold::Off),
+            "timed" => Some(AnalyzerPeakHold::Timed),
+            "infinite" => Some(AnalyzerPeakHold::Infinite),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnalyzerPeakHold::Off => "Off",
+            AnalyzerPeakHold::Timed => "2 s",
+            AnalyzerPeakHold::Infinite => "Infinite",
+        }
+    }
+
+    fn from_u8(v: u8) -> AnalyzerPeakHold {
+        match v {
+            0 => AnalyzerPeakHold::Off,
+            2 => AnalyzerPeakHold::Infinite,
+            _ => AnalyzerPeakHold::Timed,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            AnalyzerPeakHold::Off => 0,
+            AnalyzerPeakHold::Timed => 1,
+            AnalyzerPeakHold::Infinite => 2,
+        }
+    }
+}
+
+/// One published analysis result. Levels are dBFS, scaled so a full-scale
+/// sine reads 0 dB both in its FFT bin and in its 1/3-octave band.
+#[derive(Clone, Debug, Default)]
+pub struct AnalyzerFrame {
+    pub sample_rate: u32,
+    /// Averaged level per FFT bin, DC to Nyquist.
+    pub fft_db: Vec<f32>,
+    /// Peak-hold trace over `fft_db`; empty when peak hold is off.
+    pub fft_peak_db: Vec<f32>,
+    /// Averaged level per [`THIRD_OCTAVE_CENTERS_HZ`] band (bands above
+    /// Nyquist sit at the floor).
+    pub third_octave_db: Vec<f32>,
+    pub third_octave_peak_db: Vec<f32>,
+    /// Front L/R of the latest chunk in mid/side space, unscaled:
+    /// x = (L-R)/sqrt2, y = (L+R)/sqrt2.
+    pub goniometer: Vec<(f32, f32)>,
+    /// Correlation of the latest chunk in [-1, 1].
+    pub correlation: f32,
+    /// Recent correlation readings, oldest first.
+    pub correlation_history: Vec<f32>,
+}
+
+/// UI <-> meter thread handoff: the panel's controls plus the latest frame.
+/// The thread only analyzes while `enabled` is set.
+pub struct AnalyzerShared {
+    enabled: AtomicBool,
+    averaging: AtomicU8,
+    peak_hold: AtomicU8,
+    reset_peaks: AtomicBool,
+    frame: ArcSwapOption<AnalyzerFrame>,
+}
+
+impl Default for AnalyzerShared {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            averaging: AtomicU8::new(AnalyzerAveraging::default().to_u8()),
+            peak_hold: AtomicU8::new(AnalyzerPeakHold::default().to_u8()),
+            reset_peaks: AtomicBool::new(false),
+            frame: ArcSwapOption::from(None),
+        }
+    }
+}
+
+impl AnalyzerShared {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.frame.store(None);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_options(&self, averaging: AnalyzerAveraging, peak_hold: AnalyzerPeakHold) {
+        self.averaging.store(averaging.to_u8(), Ordering::Relaxed);
+        self.peak_hold.store(peak_hold.to_u8(), Ordering::Relaxed);
+    }
+
+    pub fn options(&self) -> (AnalyzerAveraging, AnalyzerPeakHold) {
+        (
+            AnalyzerAveraging::from_u8(self.averaging.load(Ordering::Relaxed)),
+            AnalyzerPeakHold::from_u8(self.peak_hold.load(Ordering::Relaxed)),
+        )
+    }
+
+    pub fn request_peak_reset(&self) {
+        self.reset_peaks.store(true, Ordering::Relaxed);
+    }
+
+    /// One-shot: true once per [`Self::request_peak_reset`].
+    pub fn take_peak_reset(&self) -> bool {
+        self.reset_peaks.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn publish(&self, frame: AnalyzerFrame) {
+        self.frame.store(Some(Arc::new(frame)));
+    }
+
+    pub fn clear(&self) {
+        self.frame.store(None);
+    }
+
+    pub fn latest(&self) -> Option<Arc<AnalyzerFrame>> {
+        self.frame.load_full()
+    }
+}
+
+/// Per-bin (or per-band) peak-hold state.
+#[derive(Default)]
+struct PeakTrace {
+    db: Vec<f32>,
+    age: Vec<f32>,
+}
+
+impl PeakTrace {
+    fn update(&mut self, live: &[f32], mode: AnalyzerPeakHold, dt: f32) -> Vec<f32> {
+        if mode == AnalyzerPeakHold::Off {
+            self.clear();
+            return Vec::new();
+        }
+        if self.db.len() != live.len() {
+            self.db = live.to_vec();
+            self.age = vec![0.0; live.len()];
+        }
+        for ((peak, age), live) in self.db.iter_mut().zip(self.age.iter_mut()).zip(live) {
+            if *live >= *peak {
+                *peak = *live;
+                *age = 0.0;
+                continue;
+            }
+            *age += dt;
+            if mode == AnalyzerPeakHold::Timed && *age > PEAK_HOLD_SECS {
+                *peak = (*peak - PEAK_FALL_DB_PER_SEC * dt).max(*live);
+            }
+        }
+        self.db.clone()
+    }
+
+    fn clear(&mut self) {
+        self.db.clear();
+        self.age.clear();
+    }
+}
+
+pub struct Analyzer {
+    sample_rate: u32,
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    /// Amplitude scale putting a full-scale sine at 1.0 in its bin.
+    scale: f32,
+    /// Equivalent noise bandwidth of the window, in bins.
+    enbw: f32,
+    history: VecDeque<f32>,
+    input: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    avg_power: Vec<f32>,
+    primed: bool,
+    fft_peaks: PeakTrace,
+    band_peaks: PeakTrace,
+    correlation: VecDeque<f32>,
+}
+
+impl Analyzer {
+    pub fn new(sample_rate: u32) -> Self {
+        let sample_rate = sample_rate.max(1);
+        let n = fft_size(sample_rate);
+        let denom = (n - 1) as f32;
+        let window: Vec<f32> = (0..n)
+            .map(|i| 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / denom).cos())
+            .collect();
+        let sum: f32 = window.iter().sum();
+        let sum_sq: f32 = window.iter().map(|w| w * w).sum();
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(n);
+        let spectrum = fft.make_output_vec();
+        Self {
+            sample_rate,
+            fft,
+            scale: 2.0 / sum,
+            enbw: n as f32 * sum_sq / (sum * sum),
+            window,
+            history: VecDeque::with_capacity(n),
+            input: vec![0.0; n],
+            avg_power: vec![0.0; spectrum.len()],
+            spectrum,
+            primed: false,
+            fft_peaks: PeakTrace::default(),
+            band_peaks: PeakTrace::default(),
+            correlation: VecDeque::with_capacity(CORRELATION_HISTORY_LEN),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.primed = false;
+        self.reset_peaks();
+        self.correlation.clear();
+    }
+
+    pub fn reset_peaks(&mut self) {
+        self.fft_peaks.clear();
+        self.band_peaks.clear();
+    }
+
+    /// Analyze one chunk of tap channels. The RTA runs on the mono mix of all
+    /// channels over the last FFT window; the goniometer and correlation use
+    /// the first two (front L/R).
+    pub fn process(
+        &mut self,
+        chans: &[&[f32]],
+        averaging: AnalyzerAveraging,
+        peak_hold: AnalyzerPeakHold,
+    ) -> AnalyzerFrame {
+        let frames = chans.iter().map(|ch| ch.len()).min().unwrap_or(0);
+        let dt = frames as f32 / self.sample_rate as f32;
+        let n = self.window.len();
+        let inv = 1.0 / chans.len().max(1) as f32;
+        for i in 0..frames {
+            self.history
+                .push_back(chans.iter().map(|ch| ch[i]).sum::<f32>() * inv);
+        }
+        while self.history.len() > n {
+            self.history.pop_front();
+        }
+
+        // Right-align the history so a short start reads as leading silence.
+        let pad = n - self.history.len();
+        self.input[..pad].fill(0.0);
+        for (slot, (v, w)) in self.input[pad..]
+            .iter_mut()
+            .zip(self.history.iter().zip(&self.window[pad..]))
+        {
+            *slot = v * w;
+        }
+        if self
+            .fft
+            .process(&mut self.input, &mut self.spectrum)
+            .is_ok()
+        {
+            let tau = averaging.time_constant_secs();
+            let k = if !self.primed || tau <= 0.0 {
+                1.0
+            } else {
+                1.0 - (-dt / tau).exp()
+            };
+            for (avg, c) in self.avg_power.iter_mut().zip(&self.spectrum) {
+                let power = (c.norm() * self.scale).powi(2);
+                *avg += (power - *avg) * k;
+            }
+            self.primed = true;
+        }
+
+        let fft_db: Vec<f32> = self.avg_power.iter().map(|&p| power_to_db(p)).collect();
+        let third_octave_db = third_octave_levels(&self.avg_power, self.bin_hz(), self.enbw);
+        let fft_peak_db = self.fft_peaks.update(&fft_db, peak_hold, dt);
+        let third_octave_peak_db = self.band_peaks.update(&third_octave_db, peak_hold, dt);
+
+        let l = chans.first().map(|ch| &ch[..frames]).unwrap_or(&[]);
+        let r = chans.get(1).map(|ch| &ch[..frames]).unwrap_or(l);
+        let correlation = stereo_correlation(l, r);
+        if frames > 0 {
+            if self.correlation.len() == CORRELATION_HISTORY_LEN {
+                self.correlation.pop_front();
+            }
+            self.correlation.push_back(correlation);
+        }
+
+        AnalyzerFrame {
+            sample_rate: self.sample_rate,
+            fft_db,
+            fft_peak_db,
+            third_octave_db,
+            third_octave_peak_db,
+            goniometer: goniometer_points(l, r, GONIOMETER_MAX_POINTS),
+            correlation,
+            correlation_history: self.correlation.iter().copied().collect(),
+        }
+    }
+
+    fn bin_hz(&self) -> f32 {
+        self.sample_rate as f32 / self.window.len() as f32
+    }
+}
+
+/// RTA FFT size for `sample_rate` (8192 at 44.1/48 kHz).
+pub fn fft_size(sample_rate: u32) -> usize {
+    ((sample_rate.max(1) as f32 * FFT_WINDOW_SECS) as usize)
+        .next_power_of_two()
+        .clamp(2_048, 32_768)
+}
+
+fn power_to_db(power: f32) -> f32 {
+    (10.0 * power.max(1.0e-12).log10()).max(ANALYZER_DB_FLOOR)
+}
+
+/// Band power of each 1/3-octave band: bin powers summed with fractional
+/// bin overlap, divided by the window's noise bandwidth so a sine reads its
+/// own level. Bands narrower than a couple of bins (the lowest few at 8k
+/// FFT) read approximately.
+fn third_octave_levels(power: &[f32], bin_hz: f32, enbw: f32) -> Vec<f32> {
+    let last = power.len().saturating_sub(1);
+    let nyquist = last as f32 * bin_hz;
+    let half_band = 2f32.powf(1.0 / 6.0);
+    THIRD_OCTAVE_CENTERS_HZ
+        .iter()
+        .map(|&fc| {
+            let lo = fc / half_band;
+            let hi = (fc * half_band).min(nyquist);
+            if power.is_empty() || lo >= nyquist {
+                return ANALYZER_DB_FLOOR;
+            }
+            let p0 = lo / bin_hz;
+            let p1 = hi / bin_hz;
+            let first = ((p0 + 0.5).floor() as usize).min(last);
+            let end = ((p1 + 0.5).floor() as usize).min(last);
+            let mut sum = 0.0f32;
+            for (k, p) in power.iter().enumerate().take(end + 1).skip(first) {
+                let overlap = (p1.min(k as f32 + 0.5) - p0.max(k as f32 - 0.5)).max(0.0);
+                sum += p * overlap;
+            }
+            power_to_db(sum / enbw)
+        })
+        .collect()
+}
+
+/// Map FFT bins (DC..Nyquist) onto `cols` log-spaced display columns from
+/// [`ANALYZER_MIN_HZ`] to Nyquist, taking the loudest bin per column and
+/// interpolating where a column is narrower than a bin.
+pub fn fft_log_columns(db: &[f32], sample_rate: u32, cols: usize, out: &mut Vec<f32>) {
+    out.clear();
+    out.resize(cols, ANALYZER_DB_FLOOR);
+    if cols == 0 || db.len() < 2 {
+        return;
+    }
+    let nyquist = sample_rate.max(1) as f32 * 0.5;
+    let bin_hz = nyquist / (db.len() - 1) as f32;
+    let f_lo = ANALYZER_MIN_HZ.min(nyquist * 0.25);
+    let ratio = (nyquist / f_lo).max(1.0001);
+    for (x, slot) in out.iter_mut().enumerate() {
+        let p0 = f_lo * ratio.powf(x as f32 / cols as f32) / bin_hz;
+        let p1 = f_lo * ratio.powf((x + 1) as f32 / cols as f32) / bin_hz;
+        *slot = if p1 - p0 <= 1.0 {
+            let pc = 0.5 * (p0 + p1);
+            let i = (pc.floor() as usize).min(db.len() - 2);
+            let frac = (pc - i as f32).clamp(0.0, 1.0);
+            db[i] + (db[i + 1] - db[i]) * frac
+        } else {
+            let b0 = (p0.ceil() as usize).min(db.len() - 1);
+            let b1 = (p1.floor() as usize).clamp(b0, db.len() - 1);
+            db[b0..=b1]
+                .iter()
+                .fold(ANALYZER_DB_FLOOR, |acc, v| acc.max(*v))
+        };
+    }
+}
+
+/// Decimated mid/side points of `l`/`r` (see [`AnalyzerFrame::goniometer`]).
+fn goniometer_points(l: &[f32], r: &[f32], max_points: usize) -> Vec<(f32, f32)> {
+    let n = l.len().min(r.len());
+    if n == 0 || max_points == 0 {
+        return Vec::new();
+    }
+    let step = n.div_ceil(max_points);
+    (0..n)
+        .step_by(step)
+        .map(|i| {
+            (
+                (l[i] - r[i]) * std::f32::consts::FRAC_1_SQRT_2,
+                (l[i] + r[i]) * std::f32::consts::FRAC_1_SQRT_2,
+            )
+        })
+        .collect()
+}
+
+/// Zero-lag correlation of two channels in [-1, 1]. Near-silence maps to
+/// 0 (neutral) rather than an arbitrary sign.
+pub fn stereo_correlation(l: &[f32], r: &[f32]) -> f32 {
+    let n = l.len().min(r.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mut ll = 0.0f64;
+    let mut rr = 0.0f64;
+    let mut lr = 0.0f64;
+    for i in 0..n {
+        let a = l[i] as f64;
+        let b = r[i] as f64;
+        ll += a * a;
+        rr += b * b;
+        lr += a * b;
+    }
+    let denom = (ll * rr).sqrt();
+    if denom < 1.0e-10 {
+        return 0.0;
+    }
+    (lr / denom).clamp(-1.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, amp: f32, sr: u32, secs: f32) -> Vec<f32> {
+        (0..(sr as f32 * secs) as usize)
+            .map(|i| (i as f32 / sr as f32 * freq * std::f32::consts::TAU).sin() * amp)
+            .collect()
+    }
+
+    /// Feed `l`/`r` in 50 ms chunks, like the meter thread does.
+    fn run(
+        analyzer: &mut Analyzer,
+        l: &[f32],
+        r: &[f32],
+        averaging: AnalyzerAveraging,
+        peak_hold: AnalyzerPeakHold,
+    ) -> AnalyzerFrame {
+        let chunk = analyzer.sample_rate() as usize / 20;
+        let mut frame = AnalyzerFrame::default();
+        for (l, r) in l.chunks(chunk).zip(r.chunks(chunk)) {
+            frame = analyzer.process(&[l, r], averaging, peak_hold);
+        }
+        frame
+    }
+
+    fn band_index(fc: f32) -> usize {
+        THIRD_OCTAVE_CENTERS_HZ
+            .iter()
+            .position(|&c| c == fc)
+            .expect("nominal band")
+    }
+
+    #[test]
+    fn full_scale_sine_reads_zero_db_in_bin_and_band() {
+        let sr = 48_000;
+        let tone = sine(1_000.0, 1.0, sr, 0.5);
+        let mut analyzer = Analyzer::new(sr);
+        let frame = run(
+            &mut analyzer,
+            &tone,
+            &tone,
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Off,
+        );
+        assert_eq!(frame.fft_db.len(), fft_size(sr) / 2 + 1);
+        let (bin, peak) = frame
+            .fft_db
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let bin_hz = sr as f32 / fft_size(sr) as f32;
+        assert!((bin as f32 * bin_hz - 1_000.0).abs() <= bin_hz, "bin {bin}");
+        assert!(peak.abs() <= 1.5, "FFT peak {peak} dB");
+        let band = frame.third_octave_db[band_index(1_000.0)];
+        assert!(band.abs() <= 0.5, "1 kHz band {band} dB");
+        assert!(frame.third_octave_db[band_index(630.0)] < -40.0);
+        assert!(frame.third_octave_db[band_index(2_000.0)] < -40.0);
+        assert!(frame.fft_peak_db.is_empty() && frame.third_octave_peak_db.is_empty());
+
+        let mut cols = Vec::new();
+        fft_log_columns(&frame.fft_db, sr, 300, &mut cols);
+        let (col, _) = cols
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let col_hz =
+            ANALYZER_MIN_HZ * (24_000.0f32 / ANALYZER_MIN_HZ).powf((col as f32 + 0.5) / 300.0);
+        assert!(
+            (col_hz - 1_000.0).abs() / 1_000.0 < 0.05,
+            "column {col} = {col_hz} Hz"
+        );
+    }
+
+    #[test]
+    fn averaging_slows_the_response_to_a_level_step() {
+        let sr = 48_000;
+        let quiet = sine(1_000.0, 0.01, sr, 0.5);
+        let loud = sine(1_000.0, 1.0, sr, 0.1);
+        let band = band_index(1_000.0);
+        let after_step = |averaging| {
+            let mut analyzer = Analyzer::new(sr);
+            run(
+                &mut analyzer,
+                &quiet,
+                &quiet,
+                averaging,
+                AnalyzerPeakHold::Off,
+            );
+            run(
+                &mut analyzer,
+                &loud,
+                &loud,
+                averaging,
+                AnalyzerPeakHold::Off,
+            )
+            .third_octave_db[band]
+        };
+        let off = after_step(AnalyzerAveraging::Off);
+        let fast = after_step(AnalyzerAveraging::Fast);
+        let slow = after_step(AnalyzerAveraging::Slow);
+        assert!(
+            off > fast && fast > slow,
+            "off {off} fast {fast} slow {slow}"
+        );
+        assert!(slow < -3.0, "1 s averaging must lag a 100 ms step: {slow}");
+    }
+
+    #[test]
+    fn peak_hold_holds_then_falls_unless_infinite() {
+        let sr = 48_000;
+        let loud = sine(1_000.0, 1.0, sr, 0.3);
+        let silence = vec![0.0f32; sr as usize * 4];
+        let band = band_index(1_000.0);
+        let mut timed = Analyzer::new(sr);
+        let mut infinite = Analyzer::new(sr);
+        for (analyzer, mode) in [
+            (&mut timed, AnalyzerPeakHold::Timed),
+            (&mut infinite, AnalyzerPeakHold::Infinite),
+        ] {
+            run(analyzer, &loud, &loud, AnalyzerAveraging::Off, mode);
+            let held = run(
+                analyzer,
+                &silence[..sr as usize],
+                &silence[..sr as usize],
+                AnalyzerAveraging::Off,
+                mode,
+            );
+            assert!(
+                held.third_octave_peak_db[band] > -1.0,
+                "{mode:?} within hold"
+            );
+        }
+        let timed = run(
+            &mut timed,
+            &silence,
+            &silence,
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Timed,
+        );
+        assert!(
+            timed.third_octave_peak_db[band] < -40.0,
+            "timed peak must fall"
+        );
+        let kept = run(
+            &mut infinite,
+            &silence,
+            &silence,
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Infinite,
+        );
+        assert!(
+            kept.third_octave_peak_db[band] > -1.0,
+            "infinite peak stays"
+        );
+        infinite.reset_peaks();
+        let cleared = run(
+            &mut infinite,
+            &silence[..4_800],
+            &silence[..4_800],
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Infinite,
+        );
+        assert!(
+            cleared.third_octave_peak_db[band] < -40.0,
+            "reset clears peaks"
+        );
+    }
+
+    #[test]
+    fn goniometer_and_correlation_follow_phase() {
+        let sr = 48_000;
+        let l = sine(440.0, 0.5, sr, 0.2);
+        let inverted: Vec<f32> = l.iter().map(|v| -v).collect();
+        let mut analyzer = Analyzer::new(sr);
+        let mono = run(
+            &mut analyzer,
+            &l,
+            &l,
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Off,
+        );
+        assert!(mono.correlation > 0.99);
+        assert!(mono.goniometer.iter().all(|(x, _)| x.abs() < 1e-6));
+        assert!(mono.goniometer.iter().any(|(_, y)| y.abs() > 0.5));
+        assert!(mono.goniometer.len() <= GONIOMETER_MAX_POINTS);
+        let anti = run(
+            &mut analyzer,
+            &l,
+            &inverted,
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Off,
+        );
+        assert!(anti.correlation < -0.99);
+        assert!(anti.goniometer.iter().all(|(_, y)| y.abs() < 1e-6));
+        // 4 chunks of each, oldest first.
+        assert_eq!(anti.correlation_history.len(), 8);
+        assert!(anti.correlation_history[0] > 0.99);
+        assert!(*anti.correlation_history.last().unwrap() < -0.99);
+        assert_eq!(stereo_correlation(&[0.0; 512], &[0.0; 512]), 0.0);
+    }
+
+    #[test]
+    fn correlation_history_is_bounded() {
+        let mut analyzer = Analyzer::new(48_000);
+        let chunk = vec![0.1f32; 64];
+        let mut frame = AnalyzerFrame::default();
+        for _ in 0..CORRELATION_HISTORY_LEN + 10 {
+            frame = analyzer.process(
+                &[chunk.as_slice(), chunk.as_slice()],
+                AnalyzerAveraging::Off,
+                AnalyzerPeakHold::Off,
+            );
+        }
+        assert_eq!(frame.correlation_history.len(), CORRELATION_HISTORY_LEN);
+        analyzer.reset();
+        let frame = analyzer.process(
+            &[chunk.as_slice(), chunk.as_slice()],
+            AnalyzerAveraging::Off,
+            AnalyzerPeakHold::Off,
+        );
+        assert_eq!(frame.correlation_history.len(), 1);
+    }
+
+    #[test]
+    fn shared_options_roundtrip_and_reset_is_one_shot() {
+        let shared = AnalyzerShared::default();
+        assert!(!shared.is_enabled());
+        assert_eq!(
+            shared.options(),
+            (AnalyzerAveraging::Fast, AnalyzerPeakHold::Timed)
+        );
+        shared.set_options(AnalyzerAveraging::Slow, AnalyzerPeakHold::Infinite);
+        assert_eq!(
+            shared.options(),
+            (AnalyzerAveraging::Slow, AnalyzerPeakHold::Infinite)
+        );
+        shared.request_peak_reset();
+        assert!(shared.take_peak_reset());
+        assert!(!shared.take_peak_reset());
+        shared.set_enabled(true);
+        shared.publish(AnalyzerFrame::default());
+        assert!(shared.latest().is_some());
+        shared.set_enabled(false);
+        assert!(shared.latest().is_none(), "disabling drops the last frame");
+        for mode in AnalyzerAveraging::ALL {
+            assert_eq!(
+                AnalyzerAveraging::from_prefs_name(mode.prefs_name()),
+                Some(mode)
+            );
+        }
+        for mode in AnalyzerPeakHold::ALL {
+            assert_eq!(
+                AnalyzerPeakHold::from_prefs_name(mode.prefs_name()),
+                Some(mode)
+            );
+        }
+    }
+}
@@ -23,12 +23,15 @@ type HeavyOverlayMessage = (
 );
 
 mod app_init;
+mod artwork_ops;
 mod audio_ops;
 mod audition_ops;
 mod auto_trim;
 mod auto_trim_ops;
 mod bwf_ops;
+mod cart_ops;
 mod capture;
+pub mod catalog;
 pub mod channel_routing_ops;
 mod cli_ops;
 mod cli_workspace;
@@ -79,6 +82,7 @@ mod metadata_list_ops;
 mod music_ai_ops;
 mod music_onnx;
 mod native_drag;
+mod playlist_ops;
 mod plugin_ops;
 pub mod plugin_preset_ops;
 mod preview;
@@ -97,6 +101,8 @@ mod spectrogram;
 mod spectrogram_jobs;
 mod startup;
 mod tab_ops;
+mod tag_ops;
+mod template_ops;
 mod temp_audio_ops;
 mod theme_ops;
 pub(crate) mod threading;
@@ -108,6 +114,7 @@ mod transcript_ai_ops;
 mod transcript_onnx;
 mod transcript_ops;
 mod types;
+mod ucs_ops;
 mod ui;
 pub mod watch;
 mod world_edit_ops;
@@ -342,6 +349,7 @@ pub(super) enum PlaybackSourceKind {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum PlaybackTransportKind {
     Buffer,
+    /// Played in place from the file: mapped WAV or chunk-streamed FLAC.
     ExactStreamWav,
 }
 
@@ -640,6 +648,7 @@ pub struct WavesPreviewer {
     metadata_summary_prefetch_cursor: usize,
     metadata_summary_errors: rustc_hash::FxHashMap<PathBuf, String>,
     metadata_cache_hits: u64,
+    metadata_fulltext: Option<metadata_list_ops::MetadataFulltextSearch>,
     // persisted per-column widths (prefs.txt); key = column id in table.rs
     list_col_widths: std::collections::BTreeMap<String, f32>,
     // widths observed while rendering the current frame's header
@@ -691,6 +700,7 @@ pub struct WavesPreviewer {
     batch_loudnorm_state: Option<BatchLoudnormState>,
     show_loudnorm_dialog: bool,
     loudnorm_dialog_target: f32,
+    loudnorm_dialog_tolerance: f32,
     inspection_cfg: crate::app::inspection::InspectionConfig,
     editor_clip_c_was_down: bool,
     editor_clip_x_was_down: bool,
@@ -752,6 +762,9 @@ pub struct WavesPreviewer {
     /// Without a floor here, a file that correctly starts on a zero sample
     /// would always report NG.
     blank_min_ms: f32,
+    /// Layout used for BS.1770 weighting of files whose channel count matches
+    /// it; other files use their declared layout.
+    loudness_layout_override: Option<crate::channel_layout::ChannelLayout>,
     // Spectral selection edit (RX-style): edge fade lengths for mute/play.
     spectral_edit_time_fade_ms: f32,
     spectral_edit_freq_fade_hz: f32,
@@ -779,6 +792,8 @@ pub struct WavesPreviewer {
     variation_audition: Option<types::VariationAuditionState>,
     variation_audition_advancing: bool,
     mix_audition_state: Option<types::MixAuditionState>,
+    playlist_playback: Option<types::PlaylistPlaybackState>,
+    playlist_options: types::PlaylistOptions,
     duplicate_scan_state: Option<duplicate_ops::DuplicateScanState>,
     duplicate_report: Option<duplicate_ops::DuplicateReportState>,
     show_duplicates_window: bool,
@@ -791,10 +806,20 @@ pub struct WavesPreviewer {
     dup_allow_offset: bool,
     show_engine_export_dialog: bool,
     engine_export_profile: engine_export::EngineProfile,
+    show_catalog_export_dialog: bool,
+    catalog_export_format: catalog::CatalogFormat,
+    catalog_export_measure: bool,
+    catalog_export_state: Option<catalog::CatalogExportState>,
     show_bwf_dialog: bool,
     bwf_fields: crate::wave::BextFields,
     bwf_info: crate::wave::InfoFields,
     bwf_ixml: crate::wave::IxmlFields,
+    ucs_editor: types::UcsEditorState,
+    tag_editor: types::TagEditorState,
+    metadata_templates: types::MetadataTemplateState,
+    cart_editor: types::CartEditorState,
+    artwork_dialog: types::ArtworkDialogState,
+    analyzer_panel: types::AnalyzerPanelState,
     list_preview_prefetch_tx: Option<std::sync::mpsc::Sender<ListPreviewPrefetchResult>>,
     list_preview_prefetch_rx: Option<std::sync::mpsc::Receiver<ListPreviewPrefetchResult>>,
     list_preview_prefetch_inflight: HashSet<PathBuf>,
@@ -1220,6 +1245,7 @@ impl WavesPreviewer {
         self.playback_session.last_applied_master_gain_db = f32::NAN;
         self.playback_session.last_applied_file_gain_db = f32::NAN;
         self.playback_refresh_rate_for_current_source();
+        self.push_meter_layout_to_audio();
     }
 
     pub(super) fn playback_mark_buffer_source(
@@ -1264,6 +1290,7 @@ impl WavesPreviewer {
         self.playback_session.last_applied_master_gain_db = f32::NAN;
         self.playback_session.last_applied_file_gain_db = f32::NAN;
         self.playback_refresh_rate_for_current_source();
+        self.push_meter_layout_to_audio();
     }
 
     pub(super) fn playback_refresh_rate_for_current_source(&mut self) {
@@ -1839,7 +1866,11 @@ impl WavesPreviewer {
         };
         let mut thumb = Vec::new();
         build_minmax(&mut thumb, &mono, 128);
-        let loudness = crate::wave::loudness_metrics_from_multi(channels, sample_rate).ok();
+        // In-memory audio carries no declared layout: weight by the default.
+        let loudness_layout = LoudnessLayout::resolve(channels.len(), None, None);
+        let loudness =
+            crate::wave::loudness_metrics_for_layout(channels, sample_rate, loudness_layout.layout)
+                .ok();
         let lufs_i = loudness.map(|l| l.lufs_i);
         let bpm = None;
         let duration_secs = if sample_rate > 0 {
@@ -1865,7 +1896,9 @@ impl WavesPreviewer {
             lufs_i,
             lufs_m_max: loudness.and_then(|l| l.lufs_m_max),
             lufs_s_max: loudness.and_then(|l| l.lufs_s_max),
+            lra_lu: loudness.and_then(|l| l.lra_lu),
             true_peak_db: loudness.and_then(|l| l.true_peak_db),
+            loudness_layout: loudness.map(|_| loudness_layout),
             bpm,
             silence_lead_ms: None,
             silence_tail_ms: None,
@@ -2206,6 +2239,9 @@ impl WavesPreviewer {
         if cols.lufs_m {
             header.push("LUFS-M".to_string());
         }
+        if cols.lra {
+            header.push("LRA".to_string());
+        }
         if cols.bpm {
             header.push("BPM".to_string());
         }
@@ -2329,6 +2365,11 @@ impl WavesPreviewer {
                     .map(|db| db + item.pending_gain_db);
                 row.push(adj.map(|db| format!("{:.1}", db)).unwrap_or_default());
             }
+            if cols.lra {
+                // A level change shifts every block equally; LRA is gain-invariant.
+                let lra = meta.and_then(|m| m.lra_lu);
+                row.push(lra.map(|lu| format!("{:.1}", lu)).unwrap_or_default());
+            }
             if cols.bpm {
                 let bpm = meta
                     .and_then(|m| m.bpm)
@@ -2394,7 +2435,7 @@ impl WavesPreviewer {
             Vec::new()
         };
         let needs_peak = cols.peak;
-        let needs_lufs = cols.lufs || cols.dbtp || cols.lufs_s || cols.lufs_m;
+        let needs_lufs = cols.lufs || cols.dbtp || cols.lufs_s || cols.lufs_m || cols.lra;
         let needs_meta = cols.length
             || cols.channels
             || cols.sample_rate
@@ -2741,6 +2782,8 @@ impl WavesPreviewer {
             active_tool: tab.active_tool,
             plugin_fx_draft: tab.plugin_fx_draft.clone(),
             show_waveform_overlay: tab.show_waveform_overlay,
+            channel_layout: tab.channel_layout,
+            adm_lanes: tab.adm_lanes.clone(),
             dirty: tab.dirty,
             approx_bytes,
             markers: tab.markers.clone(),
@@ -2849,6 +2892,8 @@ impl WavesPreviewer {
             tab.active_tool = state.active_tool;
             tab.plugin_fx_draft = state.plugin_fx_draft;
             tab.show_waveform_overlay = state.show_waveform_overlay;
+            tab.channel_layout = state.channel_layout;
+            tab.adm_lanes = state.adm_lanes;
             tab.markers = state.markers;
             tab.regions = state.regions;
             tab.markers_committed = state.markers_committed;
@@ -196,6 +196,7 @@ impl WavesPreviewer {
             metadata_summary_prefetch_cursor: 0,
             metadata_summary_errors: Default::default(),
             metadata_cache_hits: 0,
+            metadata_fulltext: None,
             list_col_widths: Default::default(),
             list_col_widths_seen: Vec::new(),
             list_table_ui_id: None,
@@ -227,6 +228,7 @@ impl WavesPreviewer {
             batch_loudnorm_state: None,
             show_loudnorm_dialog: false,
             loudnorm_dialog_target: -14.0,
+            loudnorm_dialog_tolerance: 1.0,
             inspection_cfg: Default::default(),
             editor_clip_c_was_down: false,
             editor_clip_x_was_down: false,
@@ -266,6 +268,7 @@ impl WavesPreviewer {
             zero_cross_epsilon: 1.0e-4,
             blank_threshold_dbfs: crate::app::inspection::DEFAULT_BLANK_THRESHOLD_DBFS,
             blank_min_ms: crate::app::inspection::DEFAULT_BLANK_MIN_MS,
+            loudness_layout_override: None,
             spectral_edit_time_fade_ms: 8.0,
             spectral_edit_freq_fade_hz: 80.0,
             editor_play_selection_state: None,
@@ -286,6 +289,8 @@ impl WavesPreviewer {
             list_play_pending: false,
             variation_audition: None,
             mix_audition_state: None,
+            playlist_playback: None,
+            playlist_options: crate::app::types::PlaylistOptions::default(),
             variation_audition_advancing: false,
             duplicate_scan_state: None,
             duplicate_report: None,
@@ -296,10 +301,20 @@ impl WavesPreviewer {
             dup_allow_offset: true,
             show_engine_export_dialog: false,
             engine_export_profile: crate::app::engine_export::EngineProfile::Unity,
+            show_catalog_export_dialog: false,
+            catalog_export_format: crate::app::catalog::CatalogFormat::Sqlite,
+            catalog_export_measure: true,
+            catalog_export_state: None,
             show_bwf_dialog: false,
             bwf_fields: crate::wave::BextFields::default(),
             bwf_info: crate::wave::InfoFields::default(),
             bwf_ixml: crate::wave::IxmlFields::default(),
+            ucs_editor: crate::app::types::UcsEditorState::default(),
+            tag_editor: crate::app::types::TagEditorState::default(),
+            metadata_templates: crate::app::types::MetadataTemplateState::default(),
+            cart_editor: crate::app::types::CartEditorState::default(),
+            artwork_dialog: crate::app::types::ArtworkDialogState::default(),
+            analyzer_panel: crate::app::types::AnalyzerPanelState::default(),
             list_preview_prefetch_tx: None,
             list_preview_prefetch_rx: None,
             list_preview_prefetch_inflight: HashSet::new(),
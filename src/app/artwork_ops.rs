@@ -0,0 +1,164 @@
+//! Cover art writes over the list selection: set from an image file or the
+//! clipboard (optionally resized / re-encoded), or strip all artwork.
+
+use std::path::{Path, PathBuf};
+
+use crate::app::types::{MediaSource, ToastSeverity};
+use crate::artwork::PreparedArtwork;
+
+impl crate::app::WavesPreviewer {
+    /// Real files among `paths` whose container can carry written artwork.
+    pub(super) fn artwork_targets(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .filter(|path| {
+                self.item_for_path(path)
+                    .map(|item| item.source == MediaSource::File)
+                    .unwrap_or(true)
+                    && path.is_file()
+                    && crate::artwork::artwork_write_supported(path)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// "Artwork" submenu shared by the list row menu and the metadata
+    /// inspector.
+    pub(super) fn artwork_menu_contents(&mut self, ui: &mut egui::Ui, targets: &[PathBuf]) {
+        let enabled = !targets.is_empty();
+        if ui
+            .add_enabled(enabled, egui::Button::new("Set from Image File..."))
+            .clicked()
+        {
+            self.open_artwork_dialog_from_file(targets.to_vec());
+            ui.close();
+        }
+        let clipboard = ui
+            .add_enabled(enabled, egui::Button::new("Set from Clipboard"))
+            .on_hover_text("Bitmap or copied image file");
+        if clipboard.clicked() {
+            self.open_artwork_dialog_from_clipboard(targets.to_vec());
+            ui.close();
+        }
+        if ui
+            .add_enabled(enabled, egui::Button::new("Strip All Artwork"))
+            .clicked()
+        {
+            self.write_artwork_to_paths(targets, None);
+            ui.close();
+        }
+        if !enabled {
+            ui.label(egui::RichText::new("MP3 / WAV / M4A / FLAC files only").weak());
+        }
+    }
+
+    pub(super) fn open_artwork_dialog_from_file(&mut self, targets: Vec<PathBuf>) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(
+                "image",
+                &["png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"],
+            )
+            .pick_file()
+        else {
+            return;
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                let label = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default()
+                    .to_string();
+                self.open_artwork_dialog(targets, bytes, label);
+            }
+            Err(err) => self.push_toast(
+                ToastSeverity::Warning,
+                format!("Artwork: read {} failed: {err}", path.display()),
+            ),
+        }
+    }
+
+    pub(super) fn open_artwork_dialog_from_clipboard(&mut self, targets: Vec<PathBuf>) {
+        match self.get_clipboard_image() {
+            Some(bytes) => self.open_artwork_dialog(targets, bytes, "Clipboard".to_string()),
+            None => self.push_toast(
+                ToastSeverity::Warning,
+                "Artwork: the clipboard holds no image",
+            ),
+        }
+    }
+
+    fn open_artwork_dialog(&mut self, targets: Vec<PathBuf>, source: Vec<u8>, label: String) {
+        let state = &mut self.artwork_dialog;
+        state.source_size = image::load_from_memory(&source)
+            .ok()
+            .map(|image| (image.width(), image.height()));
+        state.error = state
+            .source_size
+            .is_none()
+            .then(|| "The image could not be decoded.".to_string());
+        state.targets = targets;
+        state.source = source;
+        state.source_label = label;
+        state.show_dialog = true;
+    }
+
+    pub(super) fn apply_artwork_dialog(&mut self) {
+        let prepared = crate::artwork::prepare_artwork(
+            &self.artwork_dialog.source,
+            &self.artwork_dialog.options,
+        );
+        match prepared {
+            Ok(artwork) => {
+                let targets = self.artwork_dialog.targets.clone();
+                self.write_artwork_to_paths(&targets, Some(&artwork));
+                self.artwork_dialog.show_dialog = false;
+                self.artwork_dialog.source.clear();
+            }
+            Err(err) => self.artwork_dialog.error = Some(format!("{err:#}")),
+        }
+    }
+
+    /// Embed `artwork` (or strip all artwork for `None`) in place and refresh
+    /// the cover art thumbnails and metadata views of the written files.
+    pub(super) fn write_artwork_to_paths(
+        &mut self,
+        paths: &[PathBuf],
+        artwork: Option<&PreparedArtwork>,
+    ) {
+        let mut written = 0usize;
+        let mut first_error = None;
+        for path in paths {
+            match crate::artwork::write_artwork(path, artwork) {
+                Ok(()) => {
+                    written += 1;
+                    self.refresh_artwork_for_path(path);
+                }
+                Err(err) => {
+                    first_error.get_or_insert_with(|| format!("{err:#}"));
+                }
+            }
+        }
+        let verb = if artwork.is_some() { "set" } else { "stripped" };
+        match first_error {
+            None => self.push_toast(
+                ToastSeverity::Info,
+                format!("Artwork {verb}: {written} file(s)"),
+            ),
+            Some(error) => self.push_toast(
+                ToastSeverity::Warning,
+                format!(
+                    "Artwork {verb}: {written} file(s), {} failed ({error})",
+                    paths.len() - written
+                ),
+            ),
+        }
+    }
+
+    fn refresh_artwork_for_path(&mut self, path: &Path) {
+        self.clear_meta_for_path(path);
+        self.meta_inflight.remove(path);
+        self.queue_meta_for_path(&path.to_path_buf(), true);
+        self.invalidate_metadata_for_path(path);
+    }
+}
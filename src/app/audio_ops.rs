@@ -295,6 +295,61 @@ impl WavesPreviewer {
         self.playback_session.last_applied_file_gain_db = f32::NAN;
         self.playback_refresh_rate_for_current_source();
         self.apply_effective_volume();
+        self.push_analyzer_settings_to_audio();
+        self.push_meter_layout_to_audio();
+    }
+
+    /// Hand the Analyzer panel's state to the meter thread. A replaced engine
+    /// starts a fresh thread, so this runs again from
+    /// `sync_after_audio_engine_replaced`.
+    pub(super) fn push_analyzer_settings_to_audio(&self) {
+        let analyzer = &self.audio.shared.analyzer;
+        analyzer.set_options(self.analyzer_panel.averaging, self.analyzer_panel.peak_hold);
+        analyzer.set_enabled(self.analyzer_panel.open);
+    }
+
+    /// Hand the audible source's BS.1770 layout (declared mask or override)
+    /// to the realtime meter. Runs on every source change, when the override
+    /// changes, and after the engine is replaced.
+    pub(super) fn push_meter_layout_to_audio(&self) {
+        let channels = self.audio.current_source_channels();
+        let declared = match &self.playback_session.source {
+            super::PlaybackSourceKind::EditorTab(path) => self
+                .tabs
+                .iter()
+                .find(|t| t.path == *path)
+                .map(|t| t.channel_layout()),
+            super::PlaybackSourceKind::ListPreview(path) => self
+                .item_for_path(path)
+                .and_then(|item| item.meta.as_ref())
+                .and_then(|meta| meta.loudness_layout)
+                .filter(|l| !l.overridden)
+                .map(|l| l.layout)
+                .or_else(|| {
+                    (channels > 2)
+                        .then(|| crate::audio_io::read_channel_layout(path))
+                        .flatten()
+                }),
+            _ => None,
+        };
+        let layout = (channels > 0).then(|| {
+            crate::app::types::LoudnessLayout::resolve(
+                channels,
+                declared,
+                self.loudness_layout_override,
+            )
+            .layout
+        });
+        self.audio.set_meter_layout(layout);
+    }
+
+    pub(super) fn set_analyzer_panel_open(&mut self, open: bool) {
+        if self.analyzer_panel.open == open {
+            return;
+        }
+        self.analyzer_panel.open = open;
+        self.push_analyzer_settings_to_audio();
+        self.save_prefs();
     }
 
     pub(super) fn apply_audio_output_device_selection(
@@ -34,6 +34,7 @@ impl crate::app::WavesPreviewer {
             state.cursor = (Self::variation_rng_next(&mut state.rng) as usize) % state.paths.len();
         }
         let first = state.paths[state.cursor].clone();
+        self.cancel_playlist();
         self.variation_audition = Some(state);
         if !self.variation_play_path(&first) {
             self.variation_audition = None;
@@ -44,7 +45,7 @@ impl crate::app::WavesPreviewer {
         self.variation_audition = None;
     }
 
-    fn variation_rng_next(rng: &mut u64) -> u64 {
+    pub(super) fn variation_rng_next(rng: &mut u64) -> u64 {
         *rng = rng
             .wrapping_mul(6364136223846793005)
             .wrapping_add(1442695040888963407);
@@ -289,29 +290,8 @@ impl crate::app::WavesPreviewer {
         std::thread::spawn(move || {
             let mut decoded: Vec<Vec<Vec<f32>>> = Vec::with_capacity(sources.len());
             for (logical_path, asset) in &sources {
-                let decoded_asset = match &asset.backing {
-                    crate::audio_asset::AudioBacking::ResidentBuffer(audio) => {
-                        Ok((audio.channels.clone(), asset.sample_rate.max(1)))
-                    }
-                    backing => backing
-                        .file_path()
-                        .ok_or_else(|| anyhow::anyhow!("asset has no readable backing"))
-                        .and_then(crate::audio_io::decode_audio_multi),
-                };
-                match decoded_asset {
-                    Ok((chans, sr)) => {
-                        let chans = if sr != out_sr {
-                            crate::wave::resample_channels_quality(
-                                &chans,
-                                sr,
-                                out_sr,
-                                crate::wave::ResampleQuality::Fast,
-                            )
-                        } else {
-                            chans
-                        };
-                        decoded.push(chans);
-                    }
+                match Self::decode_asset_at_rate(asset, out_sr) {
+                    Ok(chans) => decoded.push(chans),
                     Err(err) => {
                         let _ = tx.send(Err(format!("{}: {err}", logical_path.display())));
                         return;
@@ -324,6 +304,33 @@ impl crate::app::WavesPreviewer {
         self.mix_audition_state = Some(crate::app::types::MixAuditionState { rx, count });
     }
 
+    /// Decode an asset (resident buffer or file backing) and resample it to
+    /// `out_sr`. Runs on audition workers, never on the UI thread.
+    pub(super) fn decode_asset_at_rate(
+        asset: &crate::audio_asset::AudioAssetDescriptor,
+        out_sr: u32,
+    ) -> anyhow::Result<Vec<Vec<f32>>> {
+        let (chans, sr) = match &asset.backing {
+            crate::audio_asset::AudioBacking::ResidentBuffer(audio) => {
+                (audio.channels.clone(), asset.sample_rate.max(1))
+            }
+            backing => backing
+                .file_path()
+                .ok_or_else(|| anyhow::anyhow!("asset has no readable backing"))
+                .and_then(crate::audio_io::decode_audio_multi)?,
+        };
+        Ok(if sr != out_sr {
+            crate::wave::resample_channels_quality(
+                &chans,
+                sr,
+                out_sr,
+                crate::wave::ResampleQuality::Fast,
+            )
+        } else {
+            chans
+        })
+    }
+
     /// Cancel a pending mix: dropping the receiver makes the worker's send
     /// fail, so it exits after the file it is currently decoding.
     pub(super) fn cancel_mix_audition(&mut self) {
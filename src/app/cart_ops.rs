@@ -0,0 +1,462 @@
+//! BWF `cart` chunk (AES46) editing for broadcast deliveries: field writes
+//! over the list selection, post-timers from/to markers, list undo.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use egui::RichText;
+
+use crate::app::types::{
+    CartTimerSource, ColumnKey, ListUndoAction, ListUndoActionKind, MetadataWriteUndoEntry,
+    ToastSeverity,
+};
+use crate::markers::MarkerEntry;
+use crate::wave::{CartField, CartFields, CartTimer, CART_POST_TIMER_SLOTS};
+
+const CART_UNDO_CHUNKS: [[u8; 4]; 1] = [*b"cart"];
+
+/// Normalized keys "Show Cart Columns" adds to the list.
+const CART_LIST_COLUMNS: [(&str, &str); 7] = [
+    ("cart.title", "Cart Title"),
+    ("cart.artist", "Cart Artist"),
+    ("cart.cut_id", "Cut ID"),
+    ("cart.category", "Cart Category"),
+    ("cart.start_date", "Cart Start"),
+    ("cart.end_date", "Cart End"),
+    ("cart.out_cue", "Out Cue"),
+];
+
+fn is_wav_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false)
+}
+
+fn file_sample_rate(path: &Path) -> anyhow::Result<u32> {
+    Ok(crate::audio_io::read_audio_info(path)?.sample_rate.max(1))
+}
+
+/// Post-timers for the markers whose labels are usage IDs, in time order.
+pub(super) fn cart_timers_from_markers(markers: &[MarkerEntry]) -> Vec<CartTimer> {
+    let mut timers: Vec<CartTimer> = markers
+        .iter()
+        .filter_map(|marker| {
+            Some(CartTimer {
+                usage: crate::wave::cart_timer_usage_from_label(&marker.label)?,
+                sample: u32::try_from(marker.sample).ok()?,
+            })
+        })
+        .collect();
+    timers.sort_by_key(|timer| timer.sample);
+    timers.truncate(CART_POST_TIMER_SLOTS);
+    timers
+}
+
+/// Replace the timer-labelled markers with one marker per post-timer; other
+/// markers stay.
+pub(super) fn merge_cart_timer_markers(
+    markers: Vec<MarkerEntry>,
+    timers: &[CartTimer],
+) -> Vec<MarkerEntry> {
+    let mut merged: Vec<MarkerEntry> = markers
+        .into_iter()
+        .filter(|marker| crate::wave::cart_timer_usage_from_label(&marker.label).is_none())
+        .collect();
+    merged.extend(timers.iter().map(|timer| MarkerEntry {
+        sample: timer.sample as usize,
+        label: crate::wave::cart_timer_marker_label(&timer.usage),
+    }));
+    merged.sort_by_key(|marker| marker.sample);
+    merged
+}
+
+/// The cart `path` should carry: its current chunk (or a new one) with the
+/// `edited` fields taken from `values` and timers from `source`.
+pub(super) fn build_cart_for_path(
+    path: &Path,
+    values: &CartFields,
+    edited: &HashSet<CartField>,
+    source: CartTimerSource,
+) -> anyhow::Result<CartFields> {
+    let mut cart = crate::wave::read_wav_cart(path)?.unwrap_or_default();
+    for field in CartField::ALL {
+        if edited.contains(&field) {
+            *cart.get_mut(field) = values.get(field).to_string();
+        }
+    }
+    match source {
+        CartTimerSource::Keep => {}
+        CartTimerSource::Table => cart.post_timers = values.post_timers.clone(),
+        CartTimerSource::Markers => {
+            let sr = file_sample_rate(path)?;
+            let markers = crate::markers::read_markers(path, sr, sr)?;
+            cart.post_timers = cart_timers_from_markers(&markers);
+        }
+    }
+    Ok(cart)
+}
+
+/// Write `path`'s post-timers as markers. Returns how many timers it had.
+pub(super) fn copy_cart_timers_to_markers(path: &Path) -> anyhow::Result<usize> {
+    let Some(cart) = crate::wave::read_wav_cart(path)? else {
+        return Ok(0);
+    };
+    let sr = file_sample_rate(path)?;
+    let markers = crate::markers::read_markers(path, sr, sr)?;
+    let merged = merge_cart_timer_markers(markers, &cart.post_timers);
+    crate::markers::write_markers(path, sr, sr, &merged)?;
+    Ok(cart.post_timers.len())
+}
+
+impl crate::app::WavesPreviewer {
+    pub(super) fn open_cart_dialog(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .selected_paths()
+            .into_iter()
+            .filter(|path| is_wav_path(path))
+            .collect();
+        // Prefill from the first selected WAV that already carries a cart.
+        let fields = paths
+            .iter()
+            .find_map(|path| crate::wave::read_wav_cart(path).ok().flatten())
+            .unwrap_or_default();
+        let state = &mut self.cart_editor;
+        state.paths = paths;
+        state.fields = fields;
+        state.edited.clear();
+        state.timer_source = CartTimerSource::Keep;
+        state.show_dialog = true;
+    }
+
+    /// Write (or with `remove`, drop) the cart chunk of every dialog file and
+    /// record one list undo step.
+    pub(super) fn apply_cart_dialog(&mut self, remove: bool) {
+        let selection_before = self.capture_list_selection_snapshot();
+        let state = self.cart_editor.clone();
+        let mut entries = Vec::new();
+        let mut failures: Vec<String> = Vec::new();
+        for path in &state.paths {
+            let written =
+                crate::wave::snapshot_wav_chunks(path, &CART_UNDO_CHUNKS).and_then(|before| {
+                    if remove {
+                        crate::wave::write_wav_cart(path, None)?;
+                    } else {
+                        let cart = build_cart_for_path(
+                            path,
+                            &state.fields,
+                            &state.edited,
+                            state.timer_source,
+                        )?;
+                        crate::wave::write_wav_cart(path, Some(&cart))?;
+                    }
+                    Ok((
+                        before,
+                        crate::wave::snapshot_wav_chunks(path, &CART_UNDO_CHUNKS)?,
+                    ))
+                });
+            match written {
+                Ok((before, after)) => entries.push(MetadataWriteUndoEntry {
+                    before_path: path.clone(),
+                    after_path: path.clone(),
+                    before,
+                    after,
+                }),
+                Err(err) => failures.push(format!("{}: {err:#}", path.display())),
+            }
+            self.invalidate_metadata_for_path(path);
+        }
+        let written = entries.len();
+        if !entries.is_empty() {
+            let selection_after = self.capture_list_selection_snapshot();
+            self.push_list_undo_action(ListUndoAction {
+                kind: ListUndoActionKind::MetadataWrite { entries },
+                before: selection_before,
+                after: selection_after,
+            });
+        }
+        let severity = if failures.is_empty() {
+            ToastSeverity::Info
+        } else {
+            ToastSeverity::Warning
+        };
+        let verb = if remove { "removed from" } else { "wrote" };
+        let mut message = format!("Cart chunk: {verb} {written} file(s)");
+        if let Some(first) = failures.first() {
+            message.push_str(&format!(", {} failed ({first})", failures.len()));
+        }
+        self.push_toast(severity, message);
+    }
+
+    fn copy_cart_timers_to_markers_for_dialog(&mut self) {
+        let paths = self.cart_editor.paths.clone();
+        let mut files = 0usize;
+        let mut timers = 0usize;
+        let mut failures: Vec<String> = Vec::new();
+        for path in &paths {
+            match copy_cart_timers_to_markers(path) {
+                Ok(0) => {}
+                Ok(count) => {
+                    files += 1;
+                    timers += count;
+                    self.invalidate_metadata_for_path(path);
+                }
+                Err(err) => failures.push(format!("{}: {err:#}", path.display())),
+            }
+        }
+        let severity = if failures.is_empty() {
+            ToastSeverity::Info
+        } else {
+            ToastSeverity::Warning
+        };
+        let mut message = format!("Cart post-timers: {timers} marker(s) in {files} file(s)");
+        if let Some(first) = failures.first() {
+            message.push_str(&format!(", {} failed ({first})", failures.len()));
+        }
+        self.push_toast(severity, message);
+    }
+
+    fn show_cart_list_columns(&mut self) {
+        for (key, label) in CART_LIST_COLUMNS {
+            self.add_metadata_list_column(ColumnKey::Normalized(key.to_string()), label);
+        }
+    }
+
+    pub(crate) fn ui_cart_dialog(&mut self, ctx: &egui::Context) {
+        if !self.cart_editor.show_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut write_clicked = false;
+        let mut remove_clicked = false;
+        let mut markers_clicked = false;
+        let mut columns_clicked = false;
+        let mut close_clicked = false;
+        let total = self.cart_editor.paths.len();
+        let scroll_target = self.begin_floating_scroll_surface("cart_chunk_window");
+        let scroll_guard = self.pointer_scroll_input_guard(scroll_target, ctx);
+        let state = &mut self.cart_editor;
+        let shown = egui::Window::new("Edit Cart Chunk (AES46)")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{total} selected WAV file(s). Only fields you edit are written; the rest keep each file's value."
+                ));
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("cart_chunk_fields")
+                            .num_columns(2)
+                            .spacing([8.0, 3.0])
+                            .show(ui, |ui| {
+                                for field in CartField::ALL {
+                                    let mut label = RichText::new(field.label());
+                                    if state.edited.contains(&field) {
+                                        label = label.strong();
+                                    }
+                                    ui.label(label);
+                                    let value = state.fields.get_mut(field);
+                                    let mut edit = if field == CartField::TagText {
+                                        egui::TextEdit::multiline(value).desired_rows(2)
+                                    } else {
+                                        egui::TextEdit::singleline(value)
+                                    }
+                                    .desired_width(360.0);
+                                    if let (_, Some(len)) = field.layout() {
+                                        edit = edit.char_limit(len);
+                                    }
+                                    if ui.add(edit).changed() {
+                                        state.edited.insert(field);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Post-timers:");
+                    ui.radio_value(&mut state.timer_source, CartTimerSource::Keep, "Keep");
+                    ui.radio_value(&mut state.timer_source, CartTimerSource::Table, "Table");
+                    ui.radio_value(
+                        &mut state.timer_source,
+                        CartTimerSource::Markers,
+                        "From markers",
+                    )
+                    .on_hover_text("Markers labelled SEGs, INTe, AUDs, ... or cart:XXXX, per file");
+                });
+                if state.timer_source == CartTimerSource::Table {
+                    let mut remove_timer = None;
+                    egui::Grid::new("cart_chunk_timers")
+                        .num_columns(3)
+                        .spacing([8.0, 3.0])
+                        .show(ui, |ui| {
+                            ui.label(RichText::new("Usage").strong());
+                            ui.label(RichText::new("Sample").strong());
+                            ui.label("");
+                            ui.end_row();
+                            for (idx, timer) in state.fields.post_timers.iter_mut().enumerate() {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut timer.usage)
+                                        .char_limit(4)
+                                        .desired_width(60.0),
+                                );
+                                ui.add(egui::DragValue::new(&mut timer.sample).speed(100.0));
+                                if ui.small_button("✕").on_hover_text("Remove timer").clicked() {
+                                    remove_timer = Some(idx);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    if let Some(idx) = remove_timer {
+                        state.fields.post_timers.remove(idx);
+                    }
+                    if ui
+                        .add_enabled(
+                            state.fields.post_timers.len() < CART_POST_TIMER_SLOTS,
+                            egui::Button::new("Add Timer"),
+                        )
+                        .clicked()
+                    {
+                        state.fields.post_timers.push(CartTimer {
+                            usage: "SEGs".to_string(),
+                            sample: 0,
+                        });
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(total > 0, egui::Button::new(format!("Write ({total})")))
+                        .on_hover_text("Write the cart chunk in place (undo: list undo)")
+                        .clicked()
+                    {
+                        write_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(total > 0, egui::Button::new("Remove Cart"))
+                        .clicked()
+                    {
+                        remove_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(total > 0, egui::Button::new("Timers → Markers"))
+                        .on_hover_text("Replace the usage-ID markers of each file with its post-timers")
+                        .clicked()
+                    {
+                        markers_clicked = true;
+                    }
+                    if ui
+                        .button("Show Cart Columns")
+                        .on_hover_text("Add title, artist, cut ID, category, dates and out cue to the list")
+                        .clicked()
+                    {
+                        columns_clicked = true;
+                    }
+                    if ui.button("Close").clicked() {
+                        close_clicked = true;
+                    }
+                });
+            });
+        drop(scroll_guard);
+        if let Some(shown) = shown.as_ref() {
+            self.register_scroll_surface(scroll_target, &shown.response);
+        }
+        if write_clicked {
+            self.apply_cart_dialog(false);
+        }
+        if remove_clicked {
+            self.apply_cart_dialog(true);
+        }
+        if markers_clicked {
+            self.copy_cart_timers_to_markers_for_dialog();
+        }
+        if columns_clicked {
+            self.show_cart_list_columns();
+        }
+        if !open || close_clicked {
+            self.cart_editor.show_dialog = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_markers_round_trip_and_keep_other_markers() {
+        let markers = vec![
+            MarkerEntry {
+                sample: 9600,
+                label: "SEGs".to_string(),
+            },
+            MarkerEntry {
+                sample: 100,
+                label: "Verse".to_string(),
+            },
+            MarkerEntry {
+                sample: 4800,
+                label: "cart:ab".to_string(),
+            },
+        ];
+        let timers = cart_timers_from_markers(&markers);
+        assert_eq!(
+            timers,
+            vec![
+                CartTimer {
+                    usage: "ab".to_string(),
+                    sample: 4800,
+                },
+                CartTimer {
+                    usage: "SEGs".to_string(),
+                    sample: 9600,
+                },
+            ]
+        );
+        let moved = [CartTimer {
+            usage: "INTe".to_string(),
+            sample: 2400,
+        }];
+        let merged = merge_cart_timer_markers(markers, &moved);
+        let labels: Vec<(usize, &str)> = merged
+            .iter()
+            .map(|marker| (marker.sample, marker.label.as_str()))
+            .collect();
+        assert_eq!(labels, vec![(100, "Verse"), (2400, "INTe")]);
+    }
+
+    #[test]
+    fn short_user_markers_are_not_timers() {
+        let markers = vec![
+            MarkerEntry {
+                sample: 100,
+                label: "VOX1".to_string(),
+            },
+            MarkerEntry {
+                sample: 200,
+                label: "SFX2".to_string(),
+            },
+            MarkerEntry {
+                sample: 300,
+                label: "AUDs".to_string(),
+            },
+        ];
+        let timers = cart_timers_from_markers(&markers);
+        assert_eq!(
+            timers,
+            vec![CartTimer {
+                usage: "AUDs".to_string(),
+                sample: 300,
+            }]
+        );
+        let merged = merge_cart_timer_markers(markers, &[]);
+        let labels: Vec<&str> = merged.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(labels, vec!["VOX1", "SFX2"]);
+    }
+}
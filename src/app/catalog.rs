@@ -0,0 +1,1195 @@
+//! Library catalog export/import (Soundminer / BaseHead style hand-off).
+//!
+//! A catalog is a self-contained description of a set of files: technical
+//! metadata, the normalized fields of [`crate::metadata::MetadataSummary`],
+//! UCS fields, markers/regions/loops, loudness, waveform peaks, artwork
+//! thumbnails and the list's external data columns. It is written either as
+//! an SQLite database (every table below) or as a flat CSV (one row per
+//! file, no peaks or artwork). Schema version 1 (`catalog_info.schema_version`):
+//!
+//! - `catalog_info(key, value)`: `schema_version`, `generator`, `created_at`
+//!   (RFC 3339), `root` (the folder `rel_path` is relative to), `file_count`.
+//! - `files(id, path, rel_path, name, format, size_bytes, modified,
+//!   sample_rate, channels, bits_per_sample, total_frames, duration_sec,
+//!   bit_rate_bps, lufs_i, lufs_s_max, lufs_m_max, true_peak_db, peak_db,
+//!   ucs_cat_id, ucs_category, ucs_subcategory, ucs_fx_name, ucs_creator_id,
+//!   ucs_source_id, description)`.
+//! - `fields(file_id, key, value)`: resolved normalized summary values.
+//! - `markers(file_id, kind, label, start_frame, end_frame)`: `kind` is
+//!   `marker`, `region` or `loop`; `end_frame` is NULL for markers.
+//! - `peaks(file_id, bins, data)`: `bins` (min, max) pairs of a mono mixdown
+//!   as little-endian `f32`.
+//! - `artwork(file_id, mime, width, height, data)`: PNG thumbnail.
+//! - `external(file_id, column, value)`: external data columns.
+//!
+//! The importer ([`load_catalog_table`]) turns either form back into the
+//! flat table, keyed by the `File` column, so a catalog loads as an
+//! external data source and repopulates the external columns.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::app::types::ToastSeverity;
+use crate::metadata::ucs::UcsFields;
+
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// Fixed leading columns of the flat table; `fields` keys and external
+/// columns follow.
+const FLAT_COLUMNS: [&str; 26] = [
+    "File",
+    "Path",
+    "Format",
+    "Duration",
+    "SampleRate",
+    "Channels",
+    "Bits",
+    "Frames",
+    "SizeBytes",
+    "Modified",
+    "LUFS-I",
+    "LUFS-S Max",
+    "LUFS-M Max",
+    "TruePeak",
+    "Peak",
+    "CatID",
+    "Category",
+    "SubCategory",
+    "FXName",
+    "CreatorID",
+    "SourceID",
+    "Description",
+    "Markers",
+    "Regions",
+    "Loop",
+    "CatalogSchema",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatalogFormat {
+    Sqlite,
+    Csv,
+}
+
+impl CatalogFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            CatalogFormat::Sqlite => "SQLite (full catalog)",
+            CatalogFormat::Csv => "CSV (flat table, no peaks/artwork)",
+        }
+    }
+
+    pub fn default_extension(self) -> &'static str {
+        match self {
+            CatalogFormat::Sqlite => "sqlite",
+            CatalogFormat::Csv => "csv",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sqlite" | "db" => Some(CatalogFormat::Sqlite),
+            "csv" => Some(CatalogFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// `.csv` is the flat table, anything else SQLite.
+    pub fn from_path(path: &Path) -> Self {
+        let is_csv = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            CatalogFormat::Csv
+        } else {
+            CatalogFormat::Sqlite
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CatalogOptions {
+    /// Decode files without known measurements for loudness and peaks.
+    pub measure: bool,
+    pub peak_bins: usize,
+    /// Longest side of the artwork thumbnail in pixels.
+    pub artwork_size: u32,
+    /// Loudness weighting layout for files with its channel count (see
+    /// [`crate::app::types::LoudnessLayout`]).
+    pub loudness_layout_override: Option<crate::channel_layout::ChannelLayout>,
+}
+
+impl Default for CatalogOptions {
+    fn default() -> Self {
+        Self {
+            measure: true,
+            peak_bins: 256,
+            artwork_size: 128,
+            loudness_layout_override: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CatalogLoudness {
+    pub lufs_i: Option<f32>,
+    pub lufs_s_max: Option<f32>,
+    pub lufs_m_max: Option<f32>,
+    pub true_peak_db: Option<f32>,
+    pub peak_db: Option<f32>,
+}
+
+/// Measurements the caller already has (the list's full-decode metadata),
+/// used instead of decoding the file again.
+#[derive(Clone, Debug, Default)]
+pub struct CatalogKnown {
+    pub loudness: CatalogLoudness,
+    pub peaks: Vec<(f32, f32)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogMarker {
+    /// `marker`, `region` or `loop`.
+    pub kind: String,
+    pub label: String,
+    pub start_frame: u64,
+    pub end_frame: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CatalogArtwork {
+    pub width: u32,
+    pub height: u32,
+    pub png: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CatalogEntry {
+    pub path: PathBuf,
+    pub rel_path: String,
+    pub name: String,
+    pub format: String,
+    pub size_bytes: u64,
+    pub modified: Option<String>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub total_frames: Option<u64>,
+    pub duration_secs: Option<f64>,
+    pub bit_rate_bps: Option<u32>,
+    pub loudness: CatalogLoudness,
+    pub ucs: UcsFields,
+    pub fields: Vec<(String, String)>,
+    pub markers: Vec<CatalogMarker>,
+    pub peaks: Vec<(f32, f32)>,
+    pub artwork: Option<CatalogArtwork>,
+    pub external: Vec<(String, String)>,
+}
+
+/// Gather one file's catalog entry. `external` is the file's external data
+/// row in column order.
+pub fn collect_entry(
+    path: &Path,
+    options: &CatalogOptions,
+    known: Option<&CatalogKnown>,
+    external: Vec<(String, String)>,
+) -> Result<CatalogEntry> {
+    let info = crate::audio_io::read_audio_info(path)?;
+    let stat = std::fs::metadata(path).with_context(|| format!("stat {}", path.display()))?;
+    let mut entry = CatalogEntry {
+        path: path.to_path_buf(),
+        rel_path: file_name(path),
+        name: file_name(path),
+        format: path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase(),
+        size_bytes: stat.len(),
+        modified: stat.modified().ok().map(|time| {
+            chrono::DateTime::<chrono::Utc>::from(time)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        }),
+        sample_rate: info.sample_rate,
+        channels: info.channels,
+        bits_per_sample: info.bits_per_sample,
+        total_frames: info.total_frames,
+        duration_secs: info.duration_secs.map(f64::from).or_else(|| {
+            info.total_frames
+                .map(|frames| frames as f64 / info.sample_rate.max(1) as f64)
+        }),
+        bit_rate_bps: info.bit_rate_bps,
+        external,
+        ..CatalogEntry::default()
+    };
+    let document = crate::metadata::inspect_path(
+        path,
+        crate::metadata::InspectOptions {
+            budget: crate::metadata::ScanBudget::selected(),
+            decode_xml: true,
+            decode_values: true,
+            cancel: None,
+        },
+    );
+    if let Ok(document) = document {
+        let summary = crate::metadata::summary_from_document(
+            &document,
+            &crate::metadata::SummaryRequest {
+                fields: Vec::new(),
+                include_raw: false,
+            },
+        );
+        entry.fields = summary
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let value = field.resolved.as_ref()?.display();
+                (!value.trim().is_empty()).then(|| (field.key.clone(), value))
+            })
+            .collect();
+        entry.ucs = UcsFields::from_document(&document);
+    }
+    entry.markers = read_catalog_markers(path, info.sample_rate);
+    match known.filter(|known| known.loudness.lufs_i.is_some() && !known.peaks.is_empty()) {
+        Some(known) => {
+            entry.loudness = known.loudness;
+            entry.peaks = known.peaks.clone();
+        }
+        None if options.measure => {
+            let layout = crate::app::types::LoudnessLayout::resolve(
+                info.channels as usize,
+                Some(info.channel_layout),
+                options.loudness_layout_override,
+            );
+            measure(path, options.peak_bins, layout.layout, &mut entry)?
+        }
+        None => {
+            if let Some(known) = known {
+                entry.loudness = known.loudness;
+                entry.peaks = known.peaks.clone();
+            }
+        }
+    }
+    entry.artwork = artwork_thumbnail(path, options.artwork_size);
+    Ok(entry)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn read_catalog_markers(path: &Path, sample_rate: u32) -> Vec<CatalogMarker> {
+    let mut out = Vec::new();
+    for marker in crate::markers::read_markers(path, sample_rate, sample_rate).unwrap_or_default() {
+        out.push(CatalogMarker {
+            kind: "marker".to_string(),
+            label: marker.label,
+            start_frame: marker.sample as u64,
+            end_frame: None,
+        });
+    }
+    for region in crate::markers::read_regions(path, sample_rate, sample_rate).unwrap_or_default() {
+        out.push(CatalogMarker {
+            kind: "region".to_string(),
+            label: region.label,
+            start_frame: region.start as u64,
+            end_frame: Some(region.end as u64),
+        });
+    }
+    if let Some((start, end)) = crate::loop_markers::read_loop_markers(path) {
+        out.push(CatalogMarker {
+            kind: "loop".to_string(),
+            label: String::new(),
+            start_frame: start,
+            end_frame: Some(end),
+        });
+    }
+    out
+}
+
+fn measure(
+    path: &Path,
+    bins: usize,
+    layout: crate::channel_layout::ChannelLayout,
+    entry: &mut CatalogEntry,
+) -> Result<()> {
+    let (chans, sr) = crate::audio_io::decode_audio_multi(path)?;
+    let len = chans.first().map(Vec::len).unwrap_or(0);
+    let mut mono = vec![0.0f32; len];
+    let mut peak_abs = 0.0f32;
+    for chan in &chans {
+        for (acc, &sample) in mono.iter_mut().zip(chan) {
+            *acc += sample / chans.len() as f32;
+            peak_abs = peak_abs.max(sample.abs());
+        }
+    }
+    crate::wave::build_minmax(&mut entry.peaks, &mono, bins);
+    let loudness = crate::wave::loudness_metrics_for_layout(&chans, sr, layout).ok();
+    entry.loudness = CatalogLoudness {
+        lufs_i: loudness
+            .as_ref()
+            .map(|metrics| metrics.lufs_i)
+            .filter(|value| value.is_finite()),
+        lufs_s_max: loudness.as_ref().and_then(|metrics| metrics.lufs_s_max),
+        lufs_m_max: loudness.as_ref().and_then(|metrics| metrics.lufs_m_max),
+        true_peak_db: loudness.as_ref().and_then(|metrics| metrics.true_peak_db),
+        peak_db: (peak_abs > 0.0).then(|| 20.0 * peak_abs.log10()),
+    };
+    Ok(())
+}
+
+fn artwork_thumbnail(path: &Path, size: u32) -> Option<CatalogArtwork> {
+    let bytes = crate::audio_io::read_embedded_artwork(path)?;
+    let thumb = image::load_from_memory(&bytes).ok()?.thumbnail(size, size);
+    let mut png = std::io::Cursor::new(Vec::new());
+    thumb.write_to(&mut png, image::ImageFormat::Png).ok()?;
+    Some(CatalogArtwork {
+        width: thumb.width(),
+        height: thumb.height(),
+        png: png.into_inner(),
+    })
+}
+
+/// Deepest folder containing every path; `rel_path` is relative to it.
+pub fn common_root(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut root = paths.first()?.parent()?.to_path_buf();
+    for path in &paths[1..] {
+        while !path.starts_with(&root) {
+            root = root.parent()?.to_path_buf();
+        }
+    }
+    Some(root)
+}
+
+/// Set every entry's `rel_path` (with `/` separators) against `root`.
+pub fn relativize(entries: &mut [CatalogEntry], root: &Path) {
+    for entry in entries {
+        if let Ok(rel) = entry.path.strip_prefix(root) {
+            entry.rel_path = rel
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+    }
+}
+
+/// Write `entries` to `out` (replacing it) in the given format.
+pub fn write_catalog(
+    out: &Path,
+    format: CatalogFormat,
+    entries: &[CatalogEntry],
+    root: Option<&Path>,
+) -> Result<()> {
+    let mut part = out.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let _ = std::fs::remove_file(&part);
+    match format {
+        CatalogFormat::Sqlite => write_sqlite(&part, entries, root),
+        CatalogFormat::Csv => write_csv(&part, entries),
+    }
+    .with_context(|| format!("write catalog {}", out.display()))?;
+    std::fs::rename(&part, out).with_context(|| format!("replace {}", out.display()))
+}
+
+fn write_sqlite(path: &Path, entries: &[CatalogEntry], root: Option<&Path>) -> Result<()> {
+    let mut connection = Connection::open(path)?;
+    connection.execute_batch(
+        "CREATE TABLE catalog_info (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         CREATE TABLE files (
+             id INTEGER PRIMARY KEY,
+             path TEXT NOT NULL,
+             rel_path TEXT NOT NULL,
+             name TEXT NOT NULL,
+             format TEXT NOT NULL,
+             size_bytes INTEGER NOT NULL,
+             modified TEXT,
+             sample_rate INTEGER NOT NULL,
+             channels INTEGER NOT NULL,
+             bits_per_sample INTEGER NOT NULL,
+             total_frames INTEGER,
+             duration_sec REAL,
+             bit_rate_bps INTEGER,
+             lufs_i REAL,
+             lufs_s_max REAL,
+             lufs_m_max REAL,
+             true_peak_db REAL,
+             peak_db REAL,
+             ucs_cat_id TEXT NOT NULL,
+             ucs_category TEXT NOT NULL,
+             ucs_subcategory TEXT NOT NULL,
+             ucs_fx_name TEXT NOT NULL,
+             ucs_creator_id TEXT NOT NULL,
+             ucs_source_id TEXT NOT NULL,
+             description TEXT NOT NULL
+         );
+         CREATE TABLE fields (
+             file_id INTEGER NOT NULL REFERENCES files(id),
+             key TEXT NOT NULL,
+             value TEXT NOT NULL,
+             PRIMARY KEY (file_id, key)
+         );
+         CREATE TABLE markers (
+             file_id INTEGER NOT NULL REFERENCES files(id),
+             kind TEXT NOT NULL,
+             label TEXT NOT NULL,
+             start_frame INTEGER NOT NULL,
+             end_frame INTEGER
+         );
+         CREATE TABLE peaks (
+             file_id INTEGER PRIMARY KEY REFERENCES files(id),
+             bins INTEGER NOT NULL,
+             data BLOB NOT NULL
+         );
+         CREATE TABLE artwork (
+             file_id INTEGER PRIMARY KEY REFERENCES files(id),
+             mime TEXT NOT NULL,
+             width INTEGER NOT NULL,
+             height INTEGER NOT NULL,
+             data BLOB NOT NULL
+         );
+         CREATE TABLE external (
+             file_id INTEGER NOT NULL REFERENCES files(id),
+             column TEXT NOT NULL,
+             value TEXT NOT NULL,
+             PRIMARY KEY (file_id, column)
+         );
+         CREATE INDEX files_name ON files(name);",
+    )?;
+    let tx = connection.transaction()?;
+    for (key, value) in [
+        ("schema_version", CATALOG_SCHEMA_VERSION.to_string()),
+        (
+            "generator",
+            format!("NeoWaves {}", env!("CARGO_PKG_VERSION")),
+        ),
+        (
+            "created_at",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        ),
+        (
+            "root",
+            root.map(|root| root.display().to_string())
+                .unwrap_or_default(),
+        ),
+        ("file_count", entries.len().to_string()),
+    ] {
+        tx.execute(
+            "INSERT INTO catalog_info (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+    }
+    for (idx, entry) in entries.iter().enumerate() {
+        let id = idx as i64 + 1;
+        tx.execute(
+            "INSERT INTO files VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                 ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
+            params![
+                id,
+                entry.path.display().to_string(),
+                entry.rel_path,
+                entry.name,
+                entry.format,
+                entry.size_bytes as i64,
+                entry.modified,
+                entry.sample_rate,
+                entry.channels,
+                entry.bits_per_sample,
+                entry.total_frames.map(|frames| frames as i64),
+                entry.duration_secs,
+                entry.bit_rate_bps,
+                entry.loudness.lufs_i,
+                entry.loudness.lufs_s_max,
+                entry.loudness.lufs_m_max,
+                entry.loudness.true_peak_db,
+                entry.loudness.peak_db,
+                entry.ucs.cat_id,
+                entry.ucs.category,
+                entry.ucs.subcategory,
+                entry.ucs.fx_name,
+                entry.ucs.creator_id,
+                entry.ucs.source_id,
+                entry.ucs.description,
+            ],
+        )?;
+        for (key, value) in &entry.fields {
+            tx.execute(
+                "INSERT OR REPLACE INTO fields (file_id, key, value) VALUES (?1, ?2, ?3)",
+                params![id, key, value],
+            )?;
+        }
+        for marker in &entry.markers {
+            tx.execute(
+                "INSERT INTO markers (file_id, kind, label, start_frame, end_frame)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    id,
+                    marker.kind,
+                    marker.label,
+                    marker.start_frame as i64,
+                    marker.end_frame.map(|frame| frame as i64)
+                ],
+            )?;
+        }
+        if !entry.peaks.is_empty() {
+            let data = entry
+                .peaks
+                .iter()
+                .flat_map(|(min, max)| min.to_le_bytes().into_iter().chain(max.to_le_bytes()))
+                .collect::<Vec<u8>>();
+            tx.execute(
+                "INSERT INTO peaks (file_id, bins, data) VALUES (?1, ?2, ?3)",
+                params![id, entry.peaks.len() as i64, data],
+            )?;
+        }
+        if let Some(artwork) = &entry.artwork {
+            tx.execute(
+                "INSERT INTO artwork (file_id, mime, width, height, data)
+                 VALUES (?1, 'image/png', ?2, ?3, ?4)",
+                params![id, artwork.width, artwork.height, artwork.png],
+            )?;
+        }
+        for (column, value) in &entry.external {
+            tx.execute(
+                "INSERT OR REPLACE INTO external (file_id, column, value) VALUES (?1, ?2, ?3)",
+                params![id, column, value],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn write_csv(path: &Path, entries: &[CatalogEntry]) -> Result<()> {
+    let (headers, rows) = flat_table(entries);
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(&headers)?;
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn opt_text<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn opt_db(value: Option<f32>) -> String {
+    value.map(|value| format!("{value:.2}")).unwrap_or_default()
+}
+
+/// The catalog as one row per file: [`FLAT_COLUMNS`], then every
+/// normalized field key (sorted), then the external columns in first-seen
+/// order (skipping names already taken).
+pub fn flat_table(entries: &[CatalogEntry]) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut headers: Vec<String> = FLAT_COLUMNS.iter().map(|c| c.to_string()).collect();
+    let mut field_keys = entries
+        .iter()
+        .flat_map(|entry| entry.fields.iter().map(|(key, _)| key.clone()))
+        .collect::<Vec<_>>();
+    field_keys.sort();
+    field_keys.dedup();
+    headers.extend(field_keys.iter().cloned());
+    let mut external_columns: Vec<String> = Vec::new();
+    for entry in entries {
+        for (column, _) in &entry.external {
+            if !headers.contains(column) && !external_columns.contains(column) {
+                external_columns.push(column.clone());
+            }
+        }
+    }
+    headers.extend(external_columns.iter().cloned());
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            let join_markers = |kind: &str| {
+                entry
+                    .markers
+                    .iter()
+                    .filter(|marker| marker.kind == kind)
+                    .map(|marker| {
+                        let mut text = marker.start_frame.to_string();
+                        if let Some(end) = marker.end_frame {
+                            text.push_str(&format!("-{end}"));
+                        }
+                        if !marker.label.is_empty() {
+                            text.push_str(&format!(" {}", marker.label));
+                        }
+                        text
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            let mut row = vec![
+                entry.name.clone(),
+                entry.rel_path.clone(),
+                entry.format.clone(),
+                opt_text(entry.duration_secs.map(|secs| format!("{secs:.3}"))),
+                entry.sample_rate.to_string(),
+                entry.channels.to_string(),
+                entry.bits_per_sample.to_string(),
+                opt_text(entry.total_frames),
+                entry.size_bytes.to_string(),
+                entry.modified.clone().unwrap_or_default(),
+                opt_db(entry.loudness.lufs_i),
+                opt_db(entry.loudness.lufs_s_max),
+                opt_db(entry.loudness.lufs_m_max),
+                opt_db(entry.loudness.true_peak_db),
+                opt_db(entry.loudness.peak_db),
+                entry.ucs.cat_id.clone(),
+                entry.ucs.category.clone(),
+                entry.ucs.subcategory.clone(),
+                entry.ucs.fx_name.clone(),
+                entry.ucs.creator_id.clone(),
+                entry.ucs.source_id.clone(),
+                entry.ucs.description.clone(),
+                join_markers("marker"),
+                join_markers("region"),
+                join_markers("loop"),
+                CATALOG_SCHEMA_VERSION.to_string(),
+            ];
+            let fields: HashMap<&str, &str> = entry
+                .fields
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            row.extend(
+                field_keys
+                    .iter()
+                    .map(|key| fields.get(key.as_str()).unwrap_or(&"").to_string()),
+            );
+            let external: HashMap<&str, &str> = entry
+                .external
+                .iter()
+                .map(|(column, value)| (column.as_str(), value.as_str()))
+                .collect();
+            row.extend(
+                external_columns
+                    .iter()
+                    .map(|column| external.get(column.as_str()).unwrap_or(&"").to_string()),
+            );
+            row
+        })
+        .collect();
+    (headers, rows)
+}
+
+/// Read an SQLite catalog back as the flat table (peaks and artwork are not
+/// part of it). A CSV catalog already is the flat table.
+pub fn load_catalog_table(path: &Path) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let connection = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("open catalog {}", path.display()))?;
+    let version: Option<String> = connection
+        .query_row(
+            "SELECT value FROM catalog_info WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("not a NeoWaves catalog (no catalog_info table)")?;
+    let version = version
+        .and_then(|version| version.trim().parse::<u32>().ok())
+        .context("catalog has no schema_version")?;
+    if version == 0 || version > CATALOG_SCHEMA_VERSION {
+        bail!(
+            "catalog schema {version} is not supported (this build reads up to {CATALOG_SCHEMA_VERSION})"
+        );
+    }
+    let mut statement = connection.prepare(
+        "SELECT id, path, rel_path, name, format, size_bytes, modified, sample_rate, channels,
+             bits_per_sample, total_frames, duration_sec, bit_rate_bps, lufs_i, lufs_s_max,
+             lufs_m_max, true_peak_db, peak_db, ucs_cat_id, ucs_category, ucs_subcategory,
+             ucs_fx_name, ucs_creator_id, ucs_source_id, description
+         FROM files ORDER BY id",
+    )?;
+    let mut ids = Vec::new();
+    let mut entries = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                CatalogEntry {
+                    path: PathBuf::from(row.get::<_, String>(1)?),
+                    rel_path: row.get(2)?,
+                    name: row.get(3)?,
+                    format: row.get(4)?,
+                    size_bytes: row.get::<_, i64>(5)? as u64,
+                    modified: row.get(6)?,
+                    sample_rate: row.get(7)?,
+                    channels: row.get(8)?,
+                    bits_per_sample: row.get(9)?,
+                    total_frames: row.get::<_, Option<i64>>(10)?.map(|frames| frames as u64),
+                    duration_secs: row.get(11)?,
+                    bit_rate_bps: row.get(12)?,
+                    loudness: CatalogLoudness {
+                        lufs_i: row.get(13)?,
+                        lufs_s_max: row.get(14)?,
+                        lufs_m_max: row.get(15)?,
+                        true_peak_db: row.get(16)?,
+                        peak_db: row.get(17)?,
+                    },
+                    ucs: UcsFields {
+                        cat_id: row.get(18)?,
+                        category: row.get(19)?,
+                        subcategory: row.get(20)?,
+                        fx_name: row.get(21)?,
+                        creator_id: row.get(22)?,
+                        source_id: row.get(23)?,
+                        description: row.get(24)?,
+                    },
+                    ..CatalogEntry::default()
+                },
+            ))
+        })?
+        .map(|row| {
+            row.map(|(id, entry)| {
+                ids.push(id);
+                entry
+            })
+        })
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(idx, id)| (*id, idx)).collect();
+    let mut fields = connection.prepare("SELECT file_id, key, value FROM fields ORDER BY rowid")?;
+    for row in fields.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))? {
+        let (id, key, value): (i64, String, String) = row?;
+        if let Some(&idx) = index.get(&id) {
+            entries[idx].fields.push((key, value));
+        }
+    }
+    let mut markers = connection.prepare(
+        "SELECT file_id, kind, label, start_frame, end_frame FROM markers ORDER BY rowid",
+    )?;
+    for row in markers.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            CatalogMarker {
+                kind: row.get(1)?,
+                label: row.get(2)?,
+                start_frame: row.get::<_, i64>(3)? as u64,
+                end_frame: row.get::<_, Option<i64>>(4)?.map(|frame| frame as u64),
+            },
+        ))
+    })? {
+        let (id, marker) = row?;
+        if let Some(&idx) = index.get(&id) {
+            entries[idx].markers.push(marker);
+        }
+    }
+    let mut external =
+        connection.prepare("SELECT file_id, column, value FROM external ORDER BY rowid")?;
+    for row in external.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))? {
+        let (id, column, value): (i64, String, String) = row?;
+        if let Some(&idx) = index.get(&id) {
+            entries[idx].external.push((column, value));
+        }
+    }
+    Ok(flat_table(&entries))
+}
+
+/// One file to catalog plus what the caller already knows about it.
+#[derive(Clone, Debug)]
+pub struct CatalogSource {
+    pub path: PathBuf,
+    pub known: Option<CatalogKnown>,
+    pub external: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub struct CatalogExportReport {
+    pub written: usize,
+    pub skipped: Vec<(PathBuf, String)>,
+    pub cancelled: bool,
+}
+
+/// Collect every source and write the catalog. Unreadable files are
+/// skipped and reported; nothing is written when cancelled.
+pub fn export_catalog(
+    sources: &[CatalogSource],
+    options: &CatalogOptions,
+    out: &Path,
+    format: CatalogFormat,
+    cancel: Option<&AtomicBool>,
+    mut progress: impl FnMut(usize),
+) -> Result<CatalogExportReport> {
+    let mut report = CatalogExportReport::default();
+    let mut entries = Vec::with_capacity(sources.len());
+    for (idx, source) in sources.iter().enumerate() {
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            report.cancelled = true;
+            return Ok(report);
+        }
+        match collect_entry(
+            &source.path,
+            options,
+            source.known.as_ref(),
+            source.external.clone(),
+        ) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => report
+                .skipped
+                .push((source.path.clone(), format!("{err:#}"))),
+        }
+        progress(idx + 1);
+    }
+    if entries.is_empty() {
+        bail!(
+            "no readable audio files in the target set ({} skipped)",
+            report.skipped.len()
+        );
+    }
+    let paths = entries
+        .iter()
+        .map(|entry| entry.path.clone())
+        .collect::<Vec<_>>();
+    let root = common_root(&paths);
+    if let Some(root) = &root {
+        relativize(&mut entries, root);
+    }
+    write_catalog(out, format, &entries, root.as_deref())?;
+    report.written = entries.len();
+    Ok(report)
+}
+
+pub(super) enum CatalogExportMsg {
+    Progress(usize),
+    Done(Result<CatalogExportReport, String>),
+}
+
+pub(super) struct CatalogExportState {
+    pub total: usize,
+    pub done: usize,
+    pub rx: std::sync::mpsc::Receiver<CatalogExportMsg>,
+    pub cancel: Arc<AtomicBool>,
+    pub out_path: PathBuf,
+}
+
+impl crate::app::WavesPreviewer {
+    /// Catalog sources for the selection (or the whole list).
+    pub(super) fn catalog_sources(&self) -> Vec<CatalogSource> {
+        self.inspection_target_paths()
+            .into_iter()
+            .map(|path| self.catalog_source_for_path(path))
+            .collect()
+    }
+
+    /// `path` with the list's loudness/peaks and external data row.
+    pub(super) fn catalog_source_for_path(&self, path: PathBuf) -> CatalogSource {
+        let known = self.meta_for_path(&path).map(|meta| CatalogKnown {
+            loudness: CatalogLoudness {
+                lufs_i: meta.lufs_i,
+                lufs_s_max: meta.lufs_s_max,
+                lufs_m_max: meta.lufs_m_max,
+                true_peak_db: meta.true_peak_db,
+                peak_db: meta.peak_db,
+            },
+            peaks: meta.thumb.clone(),
+        });
+        let external = self
+            .external_row_for_path(&path)
+            .map(|row| {
+                self.external_headers
+                    .iter()
+                    .filter_map(|column| {
+                        row.get(column).map(|value| (column.clone(), value.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        CatalogSource {
+            path,
+            known,
+            external,
+        }
+    }
+
+    /// Format + measurement options, then a background export with progress
+    /// in the activity bar.
+    pub(crate) fn ui_catalog_export_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_catalog_export_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut do_export = false;
+        let target_count = self.inspection_target_paths().len();
+        let running = self.catalog_export_state.is_some();
+        let scroll_target = self.begin_floating_scroll_surface("catalog_export_window");
+        let scroll_guard = self.pointer_scroll_input_guard(scroll_target, ctx);
+        let shown = egui::Window::new("Export Library Catalog")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Catalogs {target_count} file(s) (selection, or the whole list when nothing is selected): technical info, metadata fields, UCS, markers, loudness, peaks and artwork."
+                ));
+                ui.separator();
+                for format in [CatalogFormat::Sqlite, CatalogFormat::Csv] {
+                    ui.radio_value(&mut self.catalog_export_format, format, format.label());
+                }
+                ui.checkbox(
+                    &mut self.catalog_export_measure,
+                    "Measure loudness and peaks of files not analyzed yet",
+                )
+                .on_hover_text("Decodes those files; otherwise their loudness and peaks stay empty");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(target_count > 0 && !running, egui::Button::new("Export..."))
+                        .clicked()
+                    {
+                        do_export = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.show_catalog_export_dialog = false;
+                    }
+                });
+            });
+        drop(scroll_guard);
+        if let Some(shown) = shown.as_ref() {
+            self.register_scroll_surface(scroll_target, &shown.response);
+        }
+        if do_export {
+            let format = self.catalog_export_format;
+            let Some(out_path) = rfd::FileDialog::new()
+                .set_file_name(format!("library_catalog.{}", format.default_extension()))
+                .add_filter(format.default_extension(), &[format.default_extension()])
+                .save_file()
+            else {
+                return;
+            };
+            self.show_catalog_export_dialog = false;
+            self.start_catalog_export(out_path, format);
+        } else if !open {
+            self.show_catalog_export_dialog = false;
+        }
+    }
+
+    pub(super) fn start_catalog_export(&mut self, out_path: PathBuf, format: CatalogFormat) {
+        let sources = self.catalog_sources();
+        let options = CatalogOptions {
+            measure: self.catalog_export_measure,
+            loudness_layout_override: self.loudness_layout_override,
+            ..CatalogOptions::default()
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let total = sources.len();
+        let worker_cancel = Arc::clone(&cancel);
+        let worker_out = out_path.clone();
+        std::thread::spawn(move || {
+            crate::app::threading::lower_current_thread_priority();
+            let progress_tx = tx.clone();
+            let result = export_catalog(
+                &sources,
+                &options,
+                &worker_out,
+                format,
+                Some(&worker_cancel),
+                |done| {
+                    let _ = progress_tx.send(CatalogExportMsg::Progress(done));
+                },
+            )
+            .map_err(|err| format!("{err:#}"));
+            let _ = tx.send(CatalogExportMsg::Done(result));
+        });
+        self.catalog_export_state = Some(CatalogExportState {
+            total,
+            done: 0,
+            rx,
+            cancel,
+            out_path,
+        });
+    }
+
+    pub(super) fn cancel_catalog_export(&mut self) {
+        if let Some(state) = &self.catalog_export_state {
+            state.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn drain_catalog_export(&mut self, ctx: &egui::Context) {
+        let mut finished = None;
+        if let Some(state) = &mut self.catalog_export_state {
+            loop {
+                match state.rx.try_recv() {
+                    Ok(CatalogExportMsg::Progress(done)) => state.done = done,
+                    Ok(CatalogExportMsg::Done(result)) => {
+                        finished = Some(result);
+                        break;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        finished = Some(Err("catalog export stopped".to_string()));
+                        break;
+                    }
+                }
+            }
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        let Some(result) = finished else {
+            return;
+        };
+        let Some(state) = self.catalog_export_state.take() else {
+            return;
+        };
+        match result {
+            Ok(report) if report.cancelled => {
+                self.push_toast(ToastSeverity::Info, "Catalog export cancelled");
+            }
+            Ok(report) => {
+                let mut message = format!(
+                    "Cataloged {} file(s) to {}",
+                    report.written,
+                    state.out_path.display()
+                );
+                let severity = match report.skipped.first() {
+                    Some((path, err)) => {
+                        message.push_str(&format!(
+                            ", skipped {} ({}: {err})",
+                            report.skipped.len(),
+                            path.display()
+                        ));
+                        ToastSeverity::Warning
+                    }
+                    None => ToastSeverity::Info,
+                };
+                self.push_toast(severity, message);
+            }
+            Err(err) => {
+                self.push_toast(
+                    ToastSeverity::Error,
+                    format!("Catalog export failed: {err}"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> CatalogEntry {
+        CatalogEntry {
+            path: PathBuf::from(format!("/library/rain/{name}")),
+            rel_path: format!("rain/{name}"),
+            name: name.to_string(),
+            format: "wav".to_string(),
+            size_bytes: 1024,
+            sample_rate: 48_000,
+            channels: 2,
+            bits_per_sample: 24,
+            total_frames: Some(96_000),
+            duration_secs: Some(2.0),
+            loudness: CatalogLoudness {
+                lufs_i: Some(-23.04),
+                true_peak_db: Some(-1.5),
+                ..CatalogLoudness::default()
+            },
+            ucs: UcsFields {
+                cat_id: "RAINMisc".to_string(),
+                fx_name: "Tin Roof".to_string(),
+                ..UcsFields::default()
+            },
+            fields: vec![("bwf.description".to_string(), "Heavy rain".to_string())],
+            markers: vec![
+                CatalogMarker {
+                    kind: "marker".to_string(),
+                    label: "Hit".to_string(),
+                    start_frame: 4_800,
+                    end_frame: None,
+                },
+                CatalogMarker {
+                    kind: "loop".to_string(),
+                    label: String::new(),
+                    start_frame: 0,
+                    end_frame: Some(96_000),
+                },
+            ],
+            peaks: vec![(-0.5, 0.5); 4],
+            artwork: Some(CatalogArtwork {
+                width: 1,
+                height: 1,
+                png: vec![0x89, b'P', b'N', b'G'],
+            }),
+            external: vec![
+                ("Scene".to_string(), "12A".to_string()),
+                ("File".to_string(), "shadowed".to_string()),
+            ],
+            ..CatalogEntry::default()
+        }
+    }
+
+    fn cell<'a>(headers: &[String], row: &'a [String], column: &str) -> &'a str {
+        let idx = headers.iter().position(|h| h == column).expect(column);
+        &row[idx]
+    }
+
+    #[test]
+    fn flat_table_has_fixed_field_and_external_columns() {
+        let (headers, rows) = flat_table(&[entry("a.wav")]);
+        assert_eq!(headers[0], "File");
+        let row = &rows[0];
+        assert_eq!(cell(&headers, row, "File"), "a.wav");
+        assert_eq!(cell(&headers, row, "LUFS-I"), "-23.04");
+        assert_eq!(cell(&headers, row, "CatID"), "RAINMisc");
+        assert_eq!(cell(&headers, row, "Markers"), "4800 Hit");
+        assert_eq!(cell(&headers, row, "Loop"), "0-96000");
+        assert_eq!(cell(&headers, row, "bwf.description"), "Heavy rain");
+        assert_eq!(cell(&headers, row, "Scene"), "12A");
+        assert_eq!(headers.iter().filter(|h| *h == "File").count(), 1);
+    }
+
+    #[test]
+    fn sqlite_catalog_round_trips_through_the_importer() {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_catalog_test_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("library.sqlite");
+        let entries = [entry("a.wav"), entry("b.wav")];
+        write_catalog(&out, CatalogFormat::Sqlite, &entries, None).unwrap();
+        let connection = Connection::open(&out).unwrap();
+        let (bins, blob): (i64, Vec<u8>) = connection
+            .query_row(
+                "SELECT bins, data FROM peaks WHERE file_id = 2",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(bins, 4);
+        assert_eq!(blob.len(), 4 * 8);
+        assert_eq!(
+            f32::from_le_bytes(blob[4..8].try_into().unwrap()),
+            0.5,
+            "max of the first bin"
+        );
+        drop(connection);
+        assert_eq!(load_catalog_table(&out).unwrap(), flat_table(&entries));
+
+        Connection::open(&out)
+            .unwrap()
+            .execute(
+                "UPDATE catalog_info SET value = '99' WHERE key = 'schema_version'",
+                [],
+            )
+            .unwrap();
+        assert!(load_catalog_table(&out).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
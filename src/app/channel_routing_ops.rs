@@ -54,7 +54,13 @@ impl WavesPreviewer {
                 return;
             }
             let undo_state = Self::capture_undo_state_labeled(tab, "Channel Routing");
+            let layout = draft.routed_layout(&tab.channel_layout());
+            let adm_lanes = tab
+                .adm_lanes()
+                .and_then(|lanes| draft.routed_adm_lanes(lanes));
             tab.ch_samples = route_channels(&tab.ch_samples, &draft);
+            tab.channel_layout = Some(layout);
+            tab.adm_lanes = adm_lanes;
             tab.dirty = true;
             Self::editor_reset_per_channel_state(tab);
             Self::editor_clamp_ranges(tab);
@@ -190,4 +196,38 @@ mod tests {
         assert!(d.is_identity());
         assert_eq!(d.in_count, 2);
     }
+
+    #[test]
+    fn routed_layout_keeps_speakers_of_single_source_outputs() {
+        use crate::channel_layout::ChannelLayout;
+        let side_51 = ChannelLayout::new(6, 0x60F);
+        // Drop the LFE: L R C Ls Rs keep their side-surround positions.
+        let no_lfe = draft(6, &[&[0], &[1], &[2], &[4], &[5]]);
+        let layout = no_lfe.routed_layout(&side_51);
+        assert_eq!(layout.mask(), 0x607);
+        assert_eq!(layout.labels(), ["L", "R", "C", "Ls", "Rs"]);
+        // A fold-down mixes several inputs per output: default stereo.
+        let fold = draft(6, &[&[0, 2, 4], &[1, 2, 5]]);
+        assert_eq!(fold.routed_layout(&side_51), ChannelLayout::default_for(2));
+        // Identity wiring is a no-op for the layout too.
+        assert_eq!(
+            ChannelRoutingDraft::identity(6).routed_layout(&side_51),
+            side_51
+        );
+    }
+
+    #[test]
+    fn routed_adm_lanes_follow_single_source_outputs() {
+        use crate::adm::AdmLane;
+        let lane = |object: &str| AdmLane {
+            object: Some(object.to_string()),
+            ..AdmLane::default()
+        };
+        let input = [lane("Dialogue"), lane("Music"), lane("FX")];
+        let swapped = draft(3, &[&[2], &[0, 1]]);
+        let routed = swapped.routed_adm_lanes(&input).unwrap();
+        assert_eq!(routed[0].object.as_deref(), Some("FX"));
+        assert!(routed[1].is_empty());
+        assert!(draft(3, &[&[0, 1, 2]]).routed_adm_lanes(&input).is_none());
+    }
 }
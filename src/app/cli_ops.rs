@@ -34,18 +34,18 @@ use crate::audio_io::{
 };
 use crate::cli::{
     BatchCommand, BatchExportArgs, BatchLoudnessApplyArgs, BatchLoudnessCommand,
-    BatchLoudnessPlanArgs, CliCommand, CliCursorSnap, CliEffectGraphSpectrumMode,
-    CliLoopXfadeShape, CliRoot, CliSpectralViewMode, CliToggle, DebugCommand, DebugScreenshotArgs,
-    DebugSummaryArgs, EditorCommand, EditorCursorCommand, EditorCursorGetArgs,
-    EditorCursorNudgeArgs, EditorCursorSetArgs, EditorInspectArgs, EditorLoopApplyArgs,
-    EditorLoopClearArgs, EditorLoopCommand, EditorLoopGetArgs, EditorLoopModeArgs,
-    EditorLoopRepeatArgs, EditorLoopSetArgs, EditorLoopXfadeArgs, EditorMarkersAddArgs,
-    EditorMarkersApplyArgs, EditorMarkersClearArgs, EditorMarkersCommand, EditorMarkersListArgs,
-    EditorMarkersRemoveArgs, EditorMarkersSetArgs, EditorPlaybackCommand, EditorPlaybackPlayArgs,
-    EditorSelectionClearArgs, EditorSelectionCommand, EditorSelectionGetArgs,
-    EditorSelectionSetArgs, EditorSourceArgs, EditorToolApplyArgs, EditorToolCommand,
-    EditorToolGetArgs, EditorToolSetArgs, EditorViewCommand, EditorViewGetArgs, EditorViewSetArgs,
-    EffectGraphCommand, EffectGraphEdgeCommand, EffectGraphEdgeConnectArgs,
+    BatchLoudnessPlanArgs, CliCommand, CliCursorSnap, CliDither, CliDownmix,
+    CliEffectGraphSpectrumMode, CliLoopXfadeShape, CliRoot, CliSpectralViewMode, CliToggle,
+    DebugCommand, DebugScreenshotArgs, DebugSummaryArgs, EditorCommand, EditorCursorCommand,
+    EditorCursorGetArgs, EditorCursorNudgeArgs, EditorCursorSetArgs, EditorInspectArgs,
+    EditorLoopApplyArgs, EditorLoopClearArgs, EditorLoopCommand, EditorLoopGetArgs,
+    EditorLoopModeArgs, EditorLoopRepeatArgs, EditorLoopSetArgs, EditorLoopXfadeArgs,
+    EditorMarkersAddArgs, EditorMarkersApplyArgs, EditorMarkersClearArgs, EditorMarkersCommand,
+    EditorMarkersListArgs, EditorMarkersRemoveArgs, EditorMarkersSetArgs, EditorPlaybackCommand,
+    EditorPlaybackPlayArgs, EditorSelectionClearArgs, EditorSelectionCommand,
+    EditorSelectionGetArgs, EditorSelectionSetArgs, EditorSourceArgs, EditorToolApplyArgs,
+    EditorToolCommand, EditorToolGetArgs, EditorToolSetArgs, EditorViewCommand, EditorViewGetArgs,
+    EditorViewSetArgs, EffectGraphCommand, EffectGraphEdgeCommand, EffectGraphEdgeConnectArgs,
     EffectGraphEdgeDisconnectArgs, EffectGraphExportArgs, EffectGraphImportArgs,
     EffectGraphInspectArgs, EffectGraphListArgs, EffectGraphNewArgs, EffectGraphNodeAddArgs,
     EffectGraphNodeCommand, EffectGraphNodeRemoveArgs, EffectGraphNodeSetArgs, EffectGraphRefArgs,
@@ -55,24 +55,28 @@ use crate::cli::{
     ExternalRenderArgs, ExternalRowsArgs, ExternalSourceAddArgs, ExternalSourceClearArgs,
     ExternalSourceCommand, ExternalSourceListArgs, ExternalSourceReloadArgs,
     ExternalSourceRemoveArgs, ItemArtworkArgs, ItemCommand, ItemInspectArgs, ItemMetaArgs,
-    ItemMetadataCommand, ItemMetadataInspectArgs, ItemMetadataPayloadCommand,
+    ItemMetadataCartArgs, ItemMetadataChunkCommand, ItemMetadataChunkExportArgs,
+    ItemMetadataChunkInsertArgs, ItemMetadataChunkListArgs, ItemMetadataChunkMoveArgs,
+    ItemMetadataChunkRemoveArgs, ItemMetadataChunkSelectorArgs, ItemMetadataChunkWriteArgs,
+    ItemMetadataCommand, ItemMetadataDiffArgs, ItemMetadataInspectArgs, ItemMetadataPayloadCommand,
     ItemMetadataPayloadExtractArgs, ItemMetadataPayloadHashArgs, ItemMetadataPayloadReadArgs,
-    ItemMetadataPayloadSearchArgs, ItemMetadataPayloadSelectorArgs, ItemMetadataSummaryArgs,
-    ListColumnsArgs, ListCommand, ListQueryArgs, ListRenderArgs, ListSaveQueryArgs, ListSearchArgs,
-    ListSelectArgs, ListSortArgs, ListSourceArgs, MusicAiAnalyzeArgs, MusicAiApplyMarkersArgs,
-    MusicAiCommand, MusicAiExportStemsArgs, MusicAiInspectArgs, MusicAiModelCommand,
-    MusicAiModelDownloadArgs, MusicAiModelStatusArgs, MusicAiModelUninstallArgs, PluginCommand,
-    PluginListArgs, PluginProbeArgs, PluginScanArgs, PluginSearchPathAddArgs,
-    PluginSearchPathCommand, PluginSearchPathListArgs, PluginSearchPathRemoveArgs,
-    PluginSearchPathResetArgs, PluginSessionApplyArgs, PluginSessionChainAddArgs,
-    PluginSessionChainCommand, PluginSessionChainListArgs, PluginSessionChainMoveArgs,
-    PluginSessionChainRemoveArgs, PluginSessionChainSetArgs, PluginSessionClearArgs,
-    PluginSessionCommand, PluginSessionInspectArgs, PluginSessionPreviewArgs, PluginSessionSetArgs,
-    RenderCommand, RenderEditorArgs, RenderListArgs, RenderSpectrumArgs, RenderWaveformArgs,
-    SessionCommand, SessionInspectArgs, SessionNewArgs, TranscriptBatchCommand,
-    TranscriptBatchGenerateArgs, TranscriptCommand, TranscriptConfigCommand,
-    TranscriptConfigGetArgs, TranscriptConfigSetArgs, TranscriptExportSrtArgs,
-    TranscriptGenerateArgs, TranscriptInspectArgs, TranscriptModelCommand,
+    ItemMetadataPayloadSearchArgs, ItemMetadataPayloadSelectorArgs, ItemMetadataRepairArgs,
+    ItemMetadataSetArgs, ItemMetadataSummaryArgs, ItemMetadataTemplateApplyArgs,
+    ItemMetadataTemplateCommand, ItemMetadataTemplateListArgs, ListColumnsArgs, ListCommand,
+    ListQueryArgs, ListRenderArgs, ListSaveQueryArgs, ListSearchArgs, ListSelectArgs, ListSortArgs,
+    ListSourceArgs, MusicAiAnalyzeArgs, MusicAiApplyMarkersArgs, MusicAiCommand,
+    MusicAiExportStemsArgs, MusicAiInspectArgs, MusicAiModelCommand, MusicAiModelDownloadArgs,
+    MusicAiModelStatusArgs, MusicAiModelUninstallArgs, PluginCommand, PluginListArgs,
+    PluginProbeArgs, PluginScanArgs, PluginSearchPathAddArgs, PluginSearchPathCommand,
+    PluginSearchPathListArgs, PluginSearchPathRemoveArgs, PluginSearchPathResetArgs,
+    PluginSessionApplyArgs, PluginSessionChainAddArgs, PluginSessionChainCommand,
+    PluginSessionChainListArgs, PluginSessionChainMoveArgs, PluginSessionChainRemoveArgs,
+    PluginSessionChainSetArgs, PluginSessionClearArgs, PluginSessionCommand,
+    PluginSessionInspectArgs, PluginSessionPreviewArgs, PluginSessionSetArgs, RenderCommand,
+    RenderEditorArgs, RenderListArgs, RenderSpectrumArgs, RenderWaveformArgs, SessionCommand,
+    SessionInspectArgs, SessionNewArgs, TranscriptBatchCommand, TranscriptBatchGenerateArgs,
+    TranscriptCommand, TranscriptConfigCommand, TranscriptConfigGetArgs, TranscriptConfigSetArgs,
+    TranscriptExportSrtArgs, TranscriptGenerateArgs, TranscriptInspectArgs, TranscriptModelCommand,
     TranscriptModelDownloadArgs, TranscriptModelStatusArgs, TranscriptModelUninstallArgs,
 };
 use crate::loop_markers;
@@ -269,6 +273,37 @@ fn cli_command_name(command: &CliCommand) -> &'static str {
         CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Payload(
             ItemMetadataPayloadCommand::Extract(_),
         ))) => "item.metadata.payload.extract",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Set(_))) => "item.metadata.set",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Repair(_))) => {
+            "item.metadata.repair"
+        }
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Chunk(
+            ItemMetadataChunkCommand::List(_),
+        ))) => "item.metadata.chunk.list",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Chunk(
+            ItemMetadataChunkCommand::Remove(_),
+        ))) => "item.metadata.chunk.remove",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Chunk(
+            ItemMetadataChunkCommand::Insert(_),
+        ))) => "item.metadata.chunk.insert",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Chunk(
+            ItemMetadataChunkCommand::Move(_),
+        ))) => "item.metadata.chunk.move",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Chunk(
+            ItemMetadataChunkCommand::Export(_),
+        ))) => "item.metadata.chunk.export",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Diff(_))) => {
+            "item.metadata.diff"
+        }
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Template(
+            ItemMetadataTemplateCommand::List(_),
+        ))) => "item.metadata.template.list",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Template(
+            ItemMetadataTemplateCommand::Apply(_),
+        ))) => "item.metadata.template.apply",
+        CliCommand::Item(ItemCommand::Metadata(ItemMetadataCommand::Cart(_))) => {
+            "item.metadata.cart"
+        }
         CliCommand::Item(ItemCommand::Artwork(_)) => "item.artwork",
         CliCommand::List(ListCommand::Columns(_)) => "list.columns",
         CliCommand::List(ListCommand::Query(_)) => "list.query",
@@ -285,6 +320,7 @@ fn cli_command_name(command: &CliCommand) -> &'static str {
         CliCommand::Batch(BatchCommand::Export(_)) => "batch.export",
         CliCommand::Batch(BatchCommand::Inspect(_)) => "batch.inspect",
         CliCommand::Batch(BatchCommand::EngineExport(_)) => "batch.engine_export",
+        CliCommand::Batch(BatchCommand::CatalogExport(_)) => "batch.catalog_export",
         CliCommand::List(ListCommand::Render(_)) => "list.render",
         CliCommand::Editor(EditorCommand::Inspect(_)) => "editor.inspect",
         CliCommand::Editor(EditorCommand::View(EditorViewCommand::Get(_))) => "editor.view.get",
@@ -525,6 +561,31 @@ fn dispatch_item_metadata(command: ItemMetadataCommand) -> Result<CliCommandOutp
         ItemMetadataCommand::Payload(ItemMetadataPayloadCommand::Extract(args)) => {
             item_metadata_payload_extract(args)
         }
+        ItemMetadataCommand::Set(args) => item_metadata_set(args),
+        ItemMetadataCommand::Repair(args) => item_metadata_repair(args),
+        ItemMetadataCommand::Chunk(ItemMetadataChunkCommand::List(args)) => {
+            item_metadata_chunk_list(args)
+        }
+        ItemMetadataCommand::Chunk(ItemMetadataChunkCommand::Remove(args)) => {
+            item_metadata_chunk_remove(args)
+        }
+        ItemMetadataCommand::Chunk(ItemMetadataChunkCommand::Insert(args)) => {
+            item_metadata_chunk_insert(args)
+        }
+        ItemMetadataCommand::Chunk(ItemMetadataChunkCommand::Move(args)) => {
+            item_metadata_chunk_move(args)
+        }
+        ItemMetadataCommand::Chunk(ItemMetadataChunkCommand::Export(args)) => {
+            item_metadata_chunk_export(args)
+        }
+        ItemMetadataCommand::Diff(args) => item_metadata_diff(args),
+        ItemMetadataCommand::Template(ItemMetadataTemplateCommand::List(args)) => {
+            item_metadata_template_list(args)
+        }
+        ItemMetadataCommand::Template(ItemMetadataTemplateCommand::Apply(args)) => {
+            item_metadata_template_apply(args)
+        }
+        ItemMetadataCommand::Cart(args) => item_metadata_cart(args),
     }
 }
 
@@ -547,6 +608,7 @@ fn dispatch_batch(command: BatchCommand) -> Result<CliCommandOutput> {
         BatchCommand::Export(args) => batch_export(args),
         BatchCommand::Inspect(args) => batch_inspect(args),
         BatchCommand::EngineExport(args) => batch_engine_export(args),
+        BatchCommand::CatalogExport(args) => batch_catalog_export(args),
     }
 }
 
@@ -581,6 +643,54 @@ fn batch_engine_export(args: crate::cli::BatchEngineExportArgs) -> Result<CliCom
     })
 }
 
+fn batch_catalog_export(args: crate::cli::BatchCatalogExportArgs) -> Result<CliCommandOutput> {
+    use crate::app::catalog::{export_catalog, CatalogFormat, CatalogOptions};
+    let format = match &args.format {
+        Some(name) => CatalogFormat::from_cli_name(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown catalog format \"{name}\" (sqlite|csv)"))?,
+        None => CatalogFormat::from_path(&args.output),
+    };
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "{} already exists (pass --overwrite to replace it)",
+            args.output.display()
+        );
+    }
+    let session = load_session(&args.session)?;
+    let filter = resolve_query_filter(&args.filter)?;
+    let paths = matched_session_entries(&session, &filter)?
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect::<Vec<_>>();
+    let mut workspace = CliWorkspace::load(&args.session)?;
+    workspace.wait_for_external_loads()?;
+    let sources = paths
+        .into_iter()
+        .map(|path| workspace.app.catalog_source_for_path(path))
+        .collect::<Vec<_>>();
+    let options = CatalogOptions {
+        measure: !args.no_measure,
+        ..CatalogOptions::default()
+    };
+    let report = export_catalog(&sources, &options, &args.output, format, None, |_| {})?;
+    let skipped = report
+        .skipped
+        .iter()
+        .map(|(path, err)| format!("{}: {err}", path.display()))
+        .collect::<Vec<_>>();
+    Ok(CliCommandOutput {
+        result: json!({
+            "query_id": filter.query_id,
+            "format": format.default_extension(),
+            "schema_version": crate::app::catalog::CATALOG_SCHEMA_VERSION,
+            "output": args.output.display().to_string(),
+            "entries": report.written,
+            "skipped": skipped,
+        }),
+        warnings: skipped.iter().map(|s| format!("skipped {s}")).collect(),
+    })
+}
+
 fn dispatch_editor(command: EditorCommand) -> Result<CliCommandOutput> {
     match command {
         EditorCommand::Inspect(args) => editor_inspect(args),
@@ -811,14 +921,29 @@ fn item_inspect(args: ItemInspectArgs) -> Result<CliCommandOutput> {
     let path = absolute_existing_path(&args.input)?;
     let info = read_audio_info(&path)?;
     let markers = read_markers_in_file_space(&path, &info)?;
+    let mut result = json!({
+        "path": pathbuf_to_string(&path),
+        "meta": audio_info_json(&info),
+        "markers": markers.iter().map(marker_json).collect::<Vec<_>>(),
+        "loop_region": read_loop_range_usize(&path),
+        "artwork_embedded": read_embedded_artwork(&path).is_some(),
+    });
+    if args.loudness || args.loudness_report.is_some() {
+        let report =
+            crate::app::inspection::loudness_report_for_path(&path).context("measure loudness")?;
+        let path_text = pathbuf_to_string(&path);
+        if let Some(out) = args.loudness_report.as_deref() {
+            crate::app::inspection::write_loudness_report(
+                out,
+                &[(path_text.clone(), report.clone())],
+            )
+            .context("write loudness report")?;
+            result["loudness_report_path"] = json!(absolute_string(out)?);
+        }
+        result["loudness"] = crate::app::inspection::loudness_report_json(&path_text, &report);
+    }
     Ok(CliCommandOutput {
-        result: json!({
-            "path": pathbuf_to_string(&path),
-            "meta": audio_info_json(&info),
-            "markers": markers.iter().map(marker_json).collect::<Vec<_>>(),
-            "loop_region": read_loop_range_usize(&path),
-            "artwork_embedded": read_embedded_artwork(&path).is_some(),
-        }),
+        result,
         warnings: Vec::new(),
     })
 }
@@ -998,6 +1123,601 @@ fn item_metadata_payload_extract(args: ItemMetadataPayloadExtractArgs) -> Result
     })
 }
 
+fn item_metadata_repair(args: ItemMetadataRepairArgs) -> Result<CliCommandOutput> {
+    let path = absolute_existing_path(&args.input)?;
+    let options = crate::metadata::repair::RepairOptions {
+        keep_unknown_chunks: !args.drop_unknown,
+    };
+    let plan = crate::metadata::repair::plan_repair(&path, &options)?;
+    let output = match (&args.output, args.dry_run) {
+        (Some(output), false) => {
+            let output = absolute_output_path(output)?;
+            crate::metadata::repair::write_repair(
+                &path,
+                &plan,
+                &output,
+                args.overwrite,
+                None,
+                None,
+            )?;
+            Some(output)
+        }
+        _ => None,
+    };
+    let mut result = serde_json::to_value(&plan.report)?;
+    result["path"] = json!(pathbuf_to_string(&path));
+    result["dry_run"] = json!(args.dry_run);
+    result["output"] = output
+        .as_deref()
+        .map(|output| json!(pathbuf_to_string(output)))
+        .unwrap_or(Value::Null);
+    Ok(CliCommandOutput {
+        result,
+        warnings: Vec::new(),
+    })
+}
+
+fn inspect_metadata_chunks(
+    input: &Path,
+) -> Result<(
+    PathBuf,
+    crate::metadata::MetadataDocument,
+    Vec<crate::metadata::chunk_edit::ChunkEntry>,
+)> {
+    let path = absolute_existing_path(input)?;
+    let document = crate::metadata::inspect_path(
+        &path,
+        crate::metadata::InspectOptions {
+            decode_xml: false,
+            decode_values: false,
+            ..Default::default()
+        },
+    )?;
+    let entries = crate::metadata::chunk_edit::chunk_entries(&document)?;
+    Ok((path, document, entries))
+}
+
+fn find_metadata_chunk(
+    entries: &[crate::metadata::chunk_edit::ChunkEntry],
+    id: &str,
+    occurrence: usize,
+) -> Result<usize> {
+    let id = crate::metadata::chunk_edit::parse_chunk_id(id)?;
+    let id = String::from_utf8_lossy(&id).into_owned();
+    entries
+        .iter()
+        .filter(|entry| entry.id == id)
+        .nth(occurrence)
+        .map(|entry| entry.index)
+        .ok_or_else(|| anyhow::anyhow!("no {id:?} chunk (occurrence {occurrence})"))
+}
+
+fn resolve_metadata_chunk(
+    selector: &ItemMetadataChunkSelectorArgs,
+) -> Result<(
+    PathBuf,
+    crate::metadata::MetadataDocument,
+    Vec<crate::metadata::chunk_edit::ChunkEntry>,
+    usize,
+)> {
+    let (path, document, entries) = inspect_metadata_chunks(&selector.input)?;
+    let index = match (&selector.id, selector.index) {
+        (Some(id), _) => find_metadata_chunk(&entries, id, selector.occurrence)?,
+        (None, Some(index)) if index < entries.len() => index,
+        (None, Some(index)) => bail!("chunk index {index} is out of range (0..{})", entries.len()),
+        (None, None) => bail!("select a chunk with --index or --id"),
+    };
+    Ok((path, document, entries, index))
+}
+
+fn write_metadata_chunk_edits(
+    path: &Path,
+    edits: &[crate::metadata::chunk_edit::ChunkEdit],
+    write: &ItemMetadataChunkWriteArgs,
+) -> Result<CliCommandOutput> {
+    let output = match (&write.output, write.in_place) {
+        (Some(output), false) => {
+            crate::metadata::chunk_edit::ChunkEditOutput::Copy(absolute_output_path(output)?)
+        }
+        (None, true) => crate::metadata::chunk_edit::ChunkEditOutput::InPlace,
+        _ => bail!("choose exactly one of --output or --in-place"),
+    };
+    let report = crate::metadata::chunk_edit::apply_chunk_edits(
+        path,
+        edits,
+        &output,
+        write.overwrite,
+        None,
+        None,
+    )?;
+    let mut result = serde_json::to_value(&report)?;
+    result["path"] = json!(pathbuf_to_string(path));
+    let warnings = if report.output_container != report.source_container {
+        vec![format!(
+            "the edited file exceeds 4 GiB and was written as {:?}",
+            report.output_container
+        )]
+    } else {
+        Vec::new()
+    };
+    Ok(CliCommandOutput { result, warnings })
+}
+
+fn item_metadata_chunk_list(args: ItemMetadataChunkListArgs) -> Result<CliCommandOutput> {
+    let (path, document, entries) = inspect_metadata_chunks(&args.input)?;
+    Ok(CliCommandOutput {
+        result: json!({
+            "path": pathbuf_to_string(&path),
+            "container": document.container,
+            "chunks": entries,
+        }),
+        warnings: Vec::new(),
+    })
+}
+
+fn item_metadata_chunk_remove(args: ItemMetadataChunkRemoveArgs) -> Result<CliCommandOutput> {
+    let (path, _, _, index) = resolve_metadata_chunk(&args.selector)?;
+    write_metadata_chunk_edits(
+        &path,
+        &[crate::metadata::chunk_edit::ChunkEdit::Remove { index }],
+        &args.write,
+    )
+}
+
+fn item_metadata_chunk_insert(args: ItemMetadataChunkInsertArgs) -> Result<CliCommandOutput> {
+    let (path, _, entries) = inspect_metadata_chunks(&args.input)?;
+    let id = crate::metadata::chunk_edit::parse_chunk_id(&args.id)?;
+    let payload = match (&args.file, &args.hex) {
+        (Some(file), None) => {
+            let file = absolute_existing_path(file)?;
+            std::fs::read(&file).with_context(|| format!("read {}", file.display()))?
+        }
+        (None, Some(hex)) => crate::metadata::chunk_edit::parse_hex_payload(hex)?,
+        _ => bail!("choose exactly one of --file or --hex"),
+    };
+    let index = match (args.at, &args.before) {
+        (_, Some(before)) => find_metadata_chunk(&entries, before, 0)?,
+        (Some(at), None) => at,
+        (None, None) => entries.len(),
+    };
+    write_metadata_chunk_edits(
+        &path,
+        &[crate::metadata::chunk_edit::ChunkEdit::Insert { index, id, payload }],
+        &args.write,
+    )
+}
+
+fn item_metadata_chunk_move(args: ItemMetadataChunkMoveArgs) -> Result<CliCommandOutput> {
+    let (path, _, entries, from) = resolve_metadata_chunk(&args.selector)?;
+    let to = match (args.to, &args.before) {
+        (_, Some(before)) => {
+            let before = find_metadata_chunk(&entries, before, 0)?;
+            if before > from {
+                before - 1
+            } else {
+                before
+            }
+        }
+        (Some(to), None) => to,
+        (None, None) => bail!("give a destination with --to or --before"),
+    };
+    write_metadata_chunk_edits(
+        &path,
+        &[crate::metadata::chunk_edit::ChunkEdit::Move { from, to }],
+        &args.write,
+    )
+}
+
+fn item_metadata_chunk_export(args: ItemMetadataChunkExportArgs) -> Result<CliCommandOutput> {
+    let (path, document, entries, index) = resolve_metadata_chunk(&args.selector)?;
+    let payload = crate::metadata::chunk_edit::chunk_payload(&document, index)?;
+    let output = absolute_output_path(&args.output)?;
+    crate::metadata::extract_payload(&path, payload, &output, args.overwrite, None)?;
+    Ok(CliCommandOutput {
+        result: json!({
+            "path": pathbuf_to_string(&path),
+            "index": index,
+            "id": entries[index].id,
+            "offset": payload.file_offset.to_string(),
+            "length": payload.length.to_string(),
+            "output": pathbuf_to_string(&output),
+        }),
+        warnings: Vec::new(),
+    })
+}
+
+fn item_metadata_diff(args: ItemMetadataDiffArgs) -> Result<CliCommandOutput> {
+    let (left, right) = match &args.right {
+        Some(right) => (
+            absolute_existing_path(&args.left)?,
+            absolute_existing_path(right)?,
+        ),
+        None => {
+            let right = absolute_existing_path(&args.left)?;
+            let backup = crate::metadata::chunk_edit::backup_path(&right);
+            if !backup.is_file() {
+                bail!(
+                    "no backup to compare against: {} (pass RIGHT explicitly)",
+                    backup.display()
+                );
+            }
+            (backup, right)
+        }
+    };
+    let options = crate::metadata::diff::DiffOptions {
+        hash_audio: args.hash_audio,
+    };
+    let left = crate::metadata::diff::DiffSide::inspect(&left, options, None)?;
+    let right = crate::metadata::diff::DiffSide::inspect(&right, options, None)?;
+    let mut diff = crate::metadata::diff::diff_documents(&left, &right);
+    if args.fail_on_diff && !diff.identical {
+        bail!(
+            "metadata differs: fields +{} -{} ~{}, nodes +{} -{} ~{} moved {}",
+            diff.field_counts.added,
+            diff.field_counts.removed,
+            diff.field_counts.changed,
+            diff.node_counts.added,
+            diff.node_counts.removed,
+            diff.node_counts.changed,
+            diff.node_counts.moved
+        );
+    }
+    if args.only_changes {
+        diff.retain_changes();
+    }
+    let mut warnings = Vec::new();
+    for side in [&diff.left, &diff.right] {
+        if side.completion != crate::metadata::Completion::Complete {
+            warnings.push(format!(
+                "{}: inspection is {:?}; the diff may be incomplete",
+                side.label, side.completion
+            ));
+        }
+    }
+    Ok(CliCommandOutput {
+        result: serde_json::to_value(&diff)?,
+        warnings,
+    })
+}
+
+fn item_metadata_template_list(args: ItemMetadataTemplateListArgs) -> Result<CliCommandOutput> {
+    let library = WavesPreviewer::load_metadata_template_library(args.library.as_deref())?;
+    let mut warnings = Vec::new();
+    for template in &library {
+        if let Err(err) = template.compile() {
+            warnings.push(format!("{}: {err:#}", template.name));
+        }
+    }
+    Ok(CliCommandOutput {
+        result: json!({
+            "templates": library,
+            "tokens": crate::metadata_template::TEMPLATE_TOKENS
+                .iter()
+                .map(|(token, help)| json!({ "token": token, "help": help }))
+                .collect::<Vec<_>>(),
+        }),
+        warnings,
+    })
+}
+
+fn item_metadata_template_apply(args: ItemMetadataTemplateApplyArgs) -> Result<CliCommandOutput> {
+    use crate::metadata_template::{MetadataTemplate, TemplateField, TemplateTarget};
+    let mut template = match &args.template {
+        Some(name) => {
+            let library = WavesPreviewer::load_metadata_template_library(args.library.as_deref())?;
+            let names = library
+                .iter()
+                .map(|template| template.name.clone())
+                .collect::<Vec<_>>();
+            library
+                .into_iter()
+                .find(|template| template.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("no template named `{name}` (have: {})", names.join(", "))
+                })?
+        }
+        None => MetadataTemplate {
+            name: "--set".to_string(),
+            fields: Vec::new(),
+        },
+    };
+    for raw in &args.set {
+        let Some((target, expression)) = raw.split_once('=') else {
+            bail!("--set expects TARGET=EXPR, got `{raw}`");
+        };
+        let key = TemplateTarget::parse(target)?;
+        template
+            .fields
+            .retain(|field| TemplateTarget::parse(&field.target).ok().as_ref() != Some(&key));
+        template.fields.push(TemplateField {
+            target: target.trim().to_string(),
+            expression: expression.to_string(),
+        });
+    }
+    let compiled = template.compile()?;
+    let (paths, workspace) = match &args.session {
+        Some(session_path) => {
+            let session = load_session(session_path)?;
+            let filter = resolve_query_filter(&args.filter)?;
+            let paths = matched_session_entries(&session, &filter)?
+                .into_iter()
+                .map(|entry| entry.path)
+                .collect::<Vec<_>>();
+            let mut workspace = CliWorkspace::load(session_path)?;
+            workspace.wait_for_external_loads()?;
+            (paths, Some(workspace))
+        }
+        None => (
+            args.input
+                .iter()
+                .map(|path| absolute_existing_path(path))
+                .collect::<Result<Vec<_>>>()?,
+            None,
+        ),
+    };
+    let count = paths.len();
+    let mut files = Vec::new();
+    let mut written_paths = Vec::new();
+    let mut skipped_paths = Vec::new();
+    let mut failed_paths = Vec::new();
+    let mut warnings = Vec::new();
+    for (idx, path) in paths.iter().enumerate() {
+        let context = match &workspace {
+            Some(workspace) => workspace
+                .app
+                .metadata_template_context(path, idx + 1, count),
+            None => {
+                let mut context =
+                    crate::metadata_template::TemplateContext::for_path(path, idx + 1, count);
+                context.transcript = super::template_ops::sidecar_transcript_text(path);
+                context
+            }
+        };
+        let rendered = compiled.render(&context);
+        for field in rendered.iter().filter(|field| field.applies) {
+            for token in &field.missing {
+                warnings.push(format!(
+                    "{}: {}: no value for `{{{token}}}`",
+                    path.display(),
+                    field.target
+                ));
+            }
+            for warning in &field.warnings {
+                warnings.push(format!("{}: {}: {warning}", path.display(), field.target));
+            }
+        }
+        let status = if !rendered.iter().any(|field| field.applies) {
+            skipped_paths.push(pathbuf_to_string(path));
+            "skipped"
+        } else if args.dry_run {
+            "planned"
+        } else {
+            match compiled.write(path, &rendered) {
+                Ok(()) => {
+                    written_paths.push(pathbuf_to_string(path));
+                    "written"
+                }
+                Err(err) => {
+                    failed_paths.push(json!({
+                        "path": pathbuf_to_string(path),
+                        "error": format!("{err:#}"),
+                    }));
+                    "failed"
+                }
+            }
+        };
+        files.push(json!({
+            "path": pathbuf_to_string(path),
+            "status": status,
+            "fields": rendered,
+        }));
+    }
+    Ok(CliCommandOutput {
+        result: json!({
+            "template": template,
+            "dry_run": args.dry_run,
+            "files": files,
+            "written_paths": written_paths,
+            "skipped_paths": skipped_paths,
+            "failed_paths": failed_paths,
+        }),
+        warnings,
+    })
+}
+
+fn item_metadata_set(args: ItemMetadataSetArgs) -> Result<CliCommandOutput> {
+    let path = absolute_existing_path(&args.input)?;
+    let Some(format) = crate::tags::tag_format(&path) else {
+        bail!(
+            "tag editing supports MP3, M4A, FLAC, Ogg and Opus: {}",
+            path.display()
+        );
+    };
+    let mut changes = Vec::new();
+    for raw in &args.set {
+        let Some((key, value)) = raw.split_once('=') else {
+            bail!("--set expects KEY=VALUE, got `{raw}`");
+        };
+        changes.push(crate::tags::TagChange {
+            key: crate::tags::TagKey::parse(key),
+            value: Some(value.to_string()),
+        });
+    }
+    for key in &args.remove {
+        changes.push(crate::tags::TagChange {
+            key: crate::tags::TagKey::parse(key),
+            value: None,
+        });
+    }
+    if changes.is_empty() {
+        bail!("nothing to do: pass --set KEY=VALUE and/or --remove KEY");
+    }
+    for change in &changes {
+        if let crate::tags::TagKey::Custom(key) = &change.key {
+            if let Some(error) = crate::tags::custom_key_error(format, key) {
+                bail!("{error}");
+            }
+        }
+    }
+    let before = crate::tags::read_tags(&path)?;
+    let mut planned = before.clone();
+    planned.apply(&changes);
+    let effective = crate::tags::diff_tags(&before, &planned);
+    let after = if args.dry_run || effective.is_empty() {
+        planned
+    } else {
+        crate::tags::write_tags(&path, &effective)?;
+        crate::tags::read_tags(&path)?
+    };
+    Ok(CliCommandOutput {
+        result: json!({
+            "path": pathbuf_to_string(&path),
+            "format": format.label(),
+            "dry_run": args.dry_run,
+            "changed": effective.iter().map(|change| json!({
+                "key": tag_key_json(&change.key),
+                "value": change.value,
+            })).collect::<Vec<_>>(),
+            "before": audio_tags_json(&before),
+            "after": audio_tags_json(&after),
+        }),
+        warnings: Vec::new(),
+    })
+}
+
+fn item_metadata_cart(args: ItemMetadataCartArgs) -> Result<CliCommandOutput> {
+    use crate::app::types::CartTimerSource;
+    use crate::wave::{CartField, CartFields, CartTimer, CART_POST_TIMER_SLOTS};
+
+    let path = absolute_existing_path(&args.input)?;
+    if !path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"))
+    {
+        bail!(
+            "cart chunks are supported in WAV files only: {}",
+            path.display()
+        );
+    }
+    let mut values = CartFields::default();
+    let mut edited = std::collections::HashSet::new();
+    for raw in &args.set {
+        let Some((key, value)) = raw.split_once('=') else {
+            bail!("--set expects KEY=VALUE, got `{raw}`");
+        };
+        let Some(field) = CartField::from_key(key) else {
+            let known: Vec<&str> = CartField::ALL.iter().map(|field| field.key()).collect();
+            bail!("unknown cart field `{key}` (known: {})", known.join(", "));
+        };
+        *values.get_mut(field) = value.to_string();
+        edited.insert(field);
+    }
+    for raw in &args.timers {
+        let Some((usage, sample)) = raw.split_once('=') else {
+            bail!("--timer expects USAGE=SAMPLE, got `{raw}`");
+        };
+        if usage.is_empty() || usage.len() > 4 || !usage.is_ascii() {
+            bail!("post-timer usage must be 1-4 ASCII characters, got `{usage}`");
+        }
+        let sample = sample.trim().parse::<u32>().map_err(|_| {
+            anyhow::anyhow!("--timer sample must be a sample count, got `{sample}`")
+        })?;
+        values.post_timers.push(CartTimer {
+            usage: usage.to_string(),
+            sample,
+        });
+    }
+    if values.post_timers.len() > CART_POST_TIMER_SLOTS {
+        bail!("a cart chunk holds at most {CART_POST_TIMER_SLOTS} post-timers");
+    }
+    let source = if !args.timers.is_empty() {
+        CartTimerSource::Table
+    } else if args.timers_from_markers {
+        CartTimerSource::Markers
+    } else {
+        CartTimerSource::Keep
+    };
+    let sample_rate = crate::audio_io::read_audio_info(&path)?.sample_rate;
+    let before = crate::wave::read_wav_cart(&path)?;
+    let changed = args.remove || !edited.is_empty() || source != CartTimerSource::Keep;
+    let after = if args.remove {
+        None
+    } else if changed {
+        Some(super::cart_ops::build_cart_for_path(
+            &path, &values, &edited, source,
+        )?)
+    } else {
+        before.clone()
+    };
+    if changed && !args.dry_run {
+        crate::wave::write_wav_cart(&path, after.as_ref())?;
+    }
+    let mut warnings = Vec::new();
+    let mut markers_written = 0usize;
+    if args.markers_from_timers {
+        match &after {
+            Some(cart) if args.dry_run => markers_written = cart.post_timers.len(),
+            Some(_) => markers_written = super::cart_ops::copy_cart_timers_to_markers(&path)?,
+            None => warnings.push("no cart chunk: markers left unchanged".to_string()),
+        }
+    }
+    Ok(CliCommandOutput {
+        result: json!({
+            "path": pathbuf_to_string(&path),
+            "sample_rate": sample_rate,
+            "dry_run": args.dry_run,
+            "changed": changed,
+            "before": before.as_ref().map(cart_json),
+            "after": after.as_ref().map(cart_json),
+            "markers_written": markers_written,
+        }),
+        warnings,
+    })
+}
+
+fn cart_json(cart: &crate::wave::CartFields) -> Value {
+    let mut out = serde_json::Map::new();
+    out.insert("version".to_string(), json!(cart.version));
+    for field in crate::wave::CartField::ALL {
+        out.insert(field.key().to_string(), json!(cart.get(field)));
+    }
+    out.insert("level_reference".to_string(), json!(cart.level_reference));
+    out.insert(
+        "post_timers".to_string(),
+        json!(cart
+            .post_timers
+            .iter()
+            .map(|timer| json!({ "usage": timer.usage, "sample": timer.sample }))
+            .collect::<Vec<_>>()),
+    );
+    Value::Object(out)
+}
+
+fn tag_key_json(key: &crate::tags::TagKey) -> String {
+    match key {
+        crate::tags::TagKey::Field(field) => field.key().to_string(),
+        crate::tags::TagKey::Custom(name) => format!("custom:{name}"),
+    }
+}
+
+fn audio_tags_json(tags: &crate::tags::AudioTags) -> Value {
+    let mut out = serde_json::Map::new();
+    for field in crate::tags::TagField::ALL {
+        out.insert(field.key().to_string(), json!(tags.get(field)));
+    }
+    out.insert(
+        "custom".to_string(),
+        Value::Object(
+            tags.custom
+                .iter()
+                .map(|(key, value)| (key.clone(), json!(value)))
+                .collect(),
+        ),
+    );
+    Value::Object(out)
+}
+
 fn metadata_document_json(document: &crate::metadata::MetadataDocument) -> Value {
     json!({
         "schema_version": document.schema_version,
@@ -1064,6 +1784,7 @@ fn metadata_summary_json(summary: &crate::metadata::MetadataSummary) -> Value {
         "raw_fields": summary.raw_fields,
         "unknown_nodes": summary.unknown_nodes,
         "coverage": summary.coverage,
+        "adm_objects": summary.adm_objects,
         "diagnostics": summary.diagnostics.iter().map(metadata_diagnostic_json).collect::<Vec<_>>(),
     })
 }
@@ -1105,6 +1826,9 @@ fn metadata_diagnostic_json(value: &crate::metadata::MetadataDiagnostic) -> Valu
 
 fn item_artwork(args: ItemArtworkArgs) -> Result<CliCommandOutput> {
     let path = absolute_existing_path(&args.input)?;
+    if args.set.is_some() || args.strip {
+        return item_artwork_write(&path, args);
+    }
     let Some(bytes) = read_embedded_artwork(&path) else {
         return Ok(CliCommandOutput {
             result: json!({
@@ -1131,6 +1855,47 @@ fn item_artwork(args: ItemArtworkArgs) -> Result<CliCommandOutput> {
     })
 }
 
+fn item_artwork_write(path: &Path, args: ItemArtworkArgs) -> Result<CliCommandOutput> {
+    if !crate::artwork::artwork_write_supported(path) {
+        bail!(
+            "artwork editing supports MP3, WAV, M4A and FLAC: {}",
+            path.display()
+        );
+    }
+    let had_artwork = read_embedded_artwork(path).is_some();
+    let artwork = match &args.set {
+        Some(image_path) => {
+            let image_path = absolute_existing_path(image_path)?;
+            let encoding = crate::artwork::ArtworkEncoding::parse(&args.encode)
+                .with_context(|| format!("unknown --encode value: {}", args.encode))?;
+            let options = crate::artwork::ArtworkOptions {
+                max_edge: args.max_size,
+                encoding,
+                jpeg_quality: args.quality,
+            };
+            let bytes = std::fs::read(&image_path)
+                .with_context(|| format!("read image: {}", image_path.display()))?;
+            Some(crate::artwork::prepare_artwork(&bytes, &options)?)
+        }
+        None => None,
+    };
+    crate::artwork::write_artwork(path, artwork.as_ref())?;
+    Ok(CliCommandOutput {
+        result: json!({
+            "path": pathbuf_to_string(path),
+            "action": if artwork.is_some() { "set" } else { "strip" },
+            "had_artwork": had_artwork,
+            "artwork": artwork.as_ref().map(|artwork| json!({
+                "mime": artwork.mime,
+                "width": artwork.width,
+                "height": artwork.height,
+                "bytes": artwork.data.len(),
+            })),
+        }),
+        warnings: Vec::new(),
+    })
+}
+
 fn list_columns(_args: ListColumnsArgs) -> Result<CliCommandOutput> {
     let columns = vec![
         ColumnDescriptor {
@@ -1223,6 +1988,11 @@ fn list_columns(_args: ListColumnsArgs) -> Result<CliCommandOutput> {
             description: "Max momentary LUFS",
             enabled_by_default: false,
         },
+        ColumnDescriptor {
+            key: "lra",
+            description: "Loudness range in LU (EBU Tech 3342)",
+            enabled_by_default: false,
+        },
         ColumnDescriptor {
             key: "silence_lead",
             description: "Leading silence in ms (-60 dBFS)",
@@ -1294,11 +2064,13 @@ fn list_query(args: ListQueryArgs) -> Result<CliCommandOutput> {
     let mut warnings = Vec::new();
     let mut rows = list_rows_from_source(&source, args.include_overlays, &mut warnings)?;
     populate_list_metadata_columns(&mut rows, &columns, &mut warnings);
+    let metadata_hits = metadata_fulltext_hits(&rows, filter.query.as_deref(), &mut warnings);
     apply_list_query_filter_sort(
         &mut rows,
         filter.query.as_deref(),
         filter.sort_key.as_deref(),
         filter.sort_dir.as_deref(),
+        &metadata_hits,
     );
     let total = rows.len();
     let rows = slice_rows(rows, args.offset, args.limit);
@@ -1375,7 +2147,8 @@ fn list_select(args: ListSelectArgs) -> Result<CliCommandOutput> {
             .iter()
             .map(|entry| list_row_for_entry(entry, Some(&session), false, &mut Vec::new()))
             .collect::<Result<Vec<_>>>()?;
-        apply_list_query_filter_sort(&mut rows, Some(query), None, None);
+        let metadata_hits = metadata_fulltext_hits(&rows, Some(query), &mut Vec::new());
+        apply_list_query_filter_sort(&mut rows, Some(query), None, None, &metadata_hits);
         let row = rows
             .get(args.index)
             .context("list select index out of range for query result")?;
@@ -1446,6 +2219,10 @@ fn batch_inspect(args: crate::cli::BatchInspectArgs) -> Result<CliCommandOutput>
         check_loudness: !args.no_loudness,
         target_lufs: args.target_lufs,
         lufs_tolerance_lu: args.lufs_tolerance.max(0.0),
+        check_loudness_range: args.max_lra.is_some(),
+        max_lra_lu: args.max_lra.unwrap_or(15.0).max(0.0),
+        check_short_term: args.max_short_term.is_some(),
+        max_short_term_lufs: args.max_short_term.unwrap_or(-9.0),
         check_silence: !args.no_silence,
         silence_threshold_dbfs: args.silence_threshold_dbfs,
         max_leading_silence_ms: args.max_leading_silence_ms.max(0.0),
@@ -1454,6 +2231,7 @@ fn batch_inspect(args: crate::cli::BatchInspectArgs) -> Result<CliCommandOutput>
         require_loop: args.require_loop,
         check_naming: args.naming_pattern.is_some(),
         naming_pattern: args.naming_pattern.clone().unwrap_or_default(),
+        loudness_layout_override: None,
     };
     let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let mut rows: Vec<crate::app::inspection::InspectionRow> = entries
@@ -1473,6 +2251,21 @@ fn batch_inspect(args: crate::cli::BatchInspectArgs) -> Result<CliCommandOutput>
         crate::app::inspection::write_batch_inspection_report(report, &rows, &cfg)
             .map_err(|e| anyhow::anyhow!("write inspection report: {e}"))?;
     }
+    let mut warnings_out = Vec::new();
+    if let Some(out) = args.loudness_report.as_deref() {
+        let mut reports = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            match crate::app::inspection::loudness_report_for_path(&entry.path) {
+                Ok(report) => reports.push((pathbuf_to_string(&entry.path), report)),
+                Err(err) => warnings_out.push(format!(
+                    "loudness report skipped {}: {err:#}",
+                    entry.path.display()
+                )),
+            }
+        }
+        crate::app::inspection::write_loudness_report(out, &reports)
+            .map_err(|e| anyhow::anyhow!("write loudness report: {e}"))?;
+    }
     let errors = rows
         .iter()
         .filter(|r| r.severity == Some(IssueSeverity::Error))
@@ -1492,14 +2285,21 @@ fn batch_inspect(args: crate::cli::BatchInspectArgs) -> Result<CliCommandOutput>
                 "silence_threshold_dbfs": cfg.silence_threshold_dbfs,
                 "max_leading_silence_ms": cfg.max_leading_silence_ms,
                 "max_trailing_silence_ms": cfg.max_trailing_silence_ms,
+                "max_lra_lu": cfg.check_loudness_range.then_some(cfg.max_lra_lu),
+                "max_short_term_lufs": cfg.check_short_term.then_some(cfg.max_short_term_lufs),
                 "require_loop": cfg.require_loop,
             },
             "counts": { "error": errors, "warning": warnings, "pass": passed },
             "matched_paths": rows.iter().map(|row| row.path.clone()).collect::<Vec<_>>(),
             "rows": rows,
             "report_path": args.report.as_deref().map(absolute_string).transpose()?,
+            "loudness_report_path": args
+                .loudness_report
+                .as_deref()
+                .map(absolute_string)
+                .transpose()?,
         }),
-        warnings: Vec::new(),
+        warnings: warnings_out,
     })
 }
 
@@ -1586,6 +2386,8 @@ fn batch_export(args: BatchExportArgs) -> Result<CliCommandOutput> {
     if args.overwrite == args.output_dir.is_some() {
         bail!("batch export requires exactly one of --overwrite or --output-dir");
     }
+    apply_cli_downmix(args.downmix);
+    apply_cli_dither(args.dither, args.dither_auto_blank);
     let session = load_session(&args.session)?;
     let filter = resolve_query_filter(&args.filter)?;
     let matched = matched_session_entries(&session, &filter)?;
@@ -2806,6 +3608,8 @@ fn render_list(args: RenderListArgs) -> Result<CliCommandOutput> {
 }
 
 fn export_file(args: ExportFileArgs) -> Result<CliCommandOutput> {
+    apply_cli_downmix(args.downmix);
+    apply_cli_dither(args.dither, args.dither_auto_blank);
     match (args.input.as_deref(), args.session.as_deref()) {
         (Some(input), None) => export_file_from_input(input, &args),
         (None, Some(session_path)) => export_file_from_session(session_path, &args),
@@ -2813,11 +3617,72 @@ fn export_file(args: ExportFileArgs) -> Result<CliCommandOutput> {
     }
 }
 
+/// Publishes a `--downmix` choice to the encoders for this CLI run.
+fn apply_cli_downmix(downmix: Option<CliDownmix>) {
+    let Some(downmix) = downmix else {
+        return;
+    };
+    let mut opts = crate::wave::codec_export_options();
+    opts.downmix = match downmix {
+        CliDownmix::Itu => crate::wave::DownmixMode::Itu,
+        CliDownmix::FirstTwo => crate::wave::DownmixMode::FirstTwo,
+    };
+    crate::wave::set_codec_export_options(opts);
+}
+
+fn apply_cli_dither(dither: Option<CliDither>, auto_blank: bool) {
+    if dither.is_none() && !auto_blank {
+        return;
+    }
+    let mut opts = crate::wave::codec_export_options();
+    if let Some(dither) = dither {
+        opts.dither_mode = match dither {
+            CliDither::Off => crate::wave::DitherMode::Off,
+            CliDither::Tpdf => crate::wave::DitherMode::Tpdf,
+            CliDither::TpdfHighpass => crate::wave::DitherMode::TpdfHighpass,
+            CliDither::TpdfNoiseShaped => crate::wave::DitherMode::TpdfNoiseShaped,
+            CliDither::Shaped => crate::wave::DitherMode::TpdfShaped,
+        };
+    }
+    if auto_blank {
+        opts.dither_auto_blank = true;
+    }
+    crate::wave::set_codec_export_options(opts);
+}
+
 fn export_verify_loop_tags(args: ExportVerifyLoopTagsArgs) -> Result<CliCommandOutput> {
     let input = absolute_existing_path(&args.input)?;
     let info = read_audio_info(&input)?;
     let markers = read_markers_in_file_space(&input, &info)?;
     let loop_region = read_loop_range_usize(&input);
+    let gapless = crate::gapless::read_gapless_info(&input);
+    let (decoded, decoded_sr) = decode_audio_multi(&input)?;
+    let decoded_frames = decoded.first().map(Vec::len).unwrap_or(0);
+    let mut warnings = Vec::new();
+    let loop_within_bounds = loop_region.map(|(_, end)| end <= decoded_frames);
+    if loop_within_bounds == Some(false) {
+        warnings.push(format!(
+            "loop end is past the decoded length ({decoded_frames} frames)"
+        ));
+    }
+    let reference = match args.reference.as_deref() {
+        Some(reference) => {
+            let reference = absolute_existing_path(reference)?;
+            let (reference_chans, reference_sr) = decode_audio_multi(&reference)?;
+            let check =
+                verify_sample_alignment(&decoded, decoded_sr, &reference_chans, reference_sr);
+            if !check.sample_exact {
+                warnings.push(format!(
+                    "not sample-exact against {}: offset={:?} length_delta={}",
+                    reference.display(),
+                    check.alignment_offset,
+                    check.length_delta
+                ));
+            }
+            Some((reference, check))
+        }
+        None => None,
+    };
     Ok(CliCommandOutput {
         result: json!({
             "path": pathbuf_to_string(&input),
@@ -2827,11 +3692,97 @@ fn export_verify_loop_tags(args: ExportVerifyLoopTagsArgs) -> Result<CliCommandO
             "marker_count": markers.len(),
             "loop_region": loop_region,
             "has_loop_region": loop_region.is_some(),
+            "decoded_frames": decoded_frames,
+            "loop_within_bounds": loop_within_bounds,
+            "gapless": gapless.map(|g| json!({
+                "priming": g.priming,
+                "padding": g.padding,
+                "valid_frames": g.valid_frames,
+            })),
+            "reference": reference.as_ref().map(|(path, check)| json!({
+                "path": pathbuf_to_string(path),
+                "frames": check.reference_frames,
+                "sample_rate": check.reference_sample_rate,
+                "alignment_offset": check.alignment_offset,
+                "length_delta": check.length_delta,
+                "sample_exact": check.sample_exact,
+            })),
+            "sample_exact": reference.as_ref().map(|(_, check)| check.sample_exact),
         }),
-        warnings: Vec::new(),
+        warnings,
     })
 }
 
+struct SampleAlignmentCheck {
+    reference_frames: usize,
+    reference_sample_rate: u32,
+    /// Lag (in frames) of the decoded file against the reference; positive
+    /// means leftover priming. `None` when the rates differ or either side
+    /// is silent.
+    alignment_offset: Option<i64>,
+    length_delta: i64,
+    sample_exact: bool,
+}
+
+/// Cross-correlates the mono mixdowns over a window starting at the first
+/// audible reference sample and picks the best lag within +/-4096 frames.
+fn verify_sample_alignment(
+    decoded: &[Vec<f32>],
+    decoded_sr: u32,
+    reference: &[Vec<f32>],
+    reference_sr: u32,
+) -> SampleAlignmentCheck {
+    const MAX_LAG: i64 = 4096;
+    const WINDOW: usize = 8192;
+    let mono = |chans: &[Vec<f32>]| -> Vec<f32> {
+        let len = chans.iter().map(Vec::len).min().unwrap_or(0);
+        (0..len)
+            .map(|i| chans.iter().map(|c| c[i]).sum::<f32>() / chans.len() as f32)
+            .collect()
+    };
+    let decoded = mono(decoded);
+    let reference = mono(reference);
+    let length_delta = decoded.len() as i64 - reference.len() as i64;
+    let onset = reference.iter().position(|v| v.abs() > 1.0e-4);
+    let alignment_offset = match onset {
+        Some(onset) if decoded_sr == reference_sr => {
+            let window = &reference[onset..(onset + WINDOW).min(reference.len())];
+            let mut best: Option<(i64, f64)> = None;
+            for lag in -MAX_LAG..=MAX_LAG {
+                let mut dot = 0.0f64;
+                let mut energy = 0.0f64;
+                for (i, &r) in window.iter().enumerate() {
+                    let at = onset as i64 + i as i64 + lag;
+                    if at < 0 || at >= decoded.len() as i64 {
+                        continue;
+                    }
+                    let d = decoded[at as usize] as f64;
+                    dot += d * r as f64;
+                    energy += d * d;
+                }
+                if energy <= 0.0 {
+                    continue;
+                }
+                let score = dot / energy.sqrt();
+                if best.map(|(_, s)| score > s).unwrap_or(true) {
+                    best = Some((lag, score));
+                }
+            }
+            best.map(|(lag, _)| lag)
+        }
+        _ => None,
+    };
+    SampleAlignmentCheck {
+        reference_frames: reference.len(),
+        reference_sample_rate: reference_sr,
+        alignment_offset,
+        length_delta,
+        sample_exact: decoded_sr == reference_sr
+            && alignment_offset == Some(0)
+            && length_delta == 0,
+    }
+}
+
 fn export_file_from_input(input: &Path, args: &ExportFileArgs) -> Result<CliCommandOutput> {
     if args.overwrite {
         bail!("--overwrite is only supported with --session");
@@ -3622,24 +4573,107 @@ fn resolve_list_overlay(
     Ok(None)
 }
 
+/// Row paths whose file metadata matches `query` as a full-text query
+/// (`desc:`/`ucs:`/`tags:`/`name:` qualifiers, quoted phrases, all terms
+/// required). Rows are summarized through the shared metadata cache first,
+/// which keeps its FTS index current; without a cache the fresh summaries are
+/// matched in memory.
+fn metadata_fulltext_hits(
+    rows: &[Map<String, Value>],
+    query: Option<&str>,
+    warnings: &mut Vec<String>,
+) -> HashSet<String> {
+    let mut hits = HashSet::new();
+    let query = crate::metadata::fulltext::FulltextQuery::parse(query.unwrap_or_default());
+    if query.is_empty() {
+        return hits;
+    }
+    let mut cache = crate::metadata::cache::default_cache_path()
+        .as_deref()
+        .and_then(|path| crate::metadata::cache::MetadataCache::open(path).ok());
+    let mut row_paths = HashSet::new();
+    for row in rows {
+        let Some(raw) = row.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        let path = PathBuf::from(raw);
+        if !path.is_file() {
+            continue;
+        }
+        let result = if let Some(cache) = cache.as_mut() {
+            cache
+                .get_or_scan(
+                    &path,
+                    crate::metadata::SummaryRequest::default(),
+                    crate::metadata::ScanBudget::list(),
+                    None,
+                )
+                .map(|lookup| lookup.summary)
+        } else {
+            crate::metadata::summarize_path(
+                &path,
+                crate::metadata::SummaryRequest::default(),
+                crate::metadata::ScanBudget::list(),
+                None,
+            )
+        };
+        match result {
+            Ok(summary) if cache.is_none() => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default();
+                let document =
+                    crate::metadata::fulltext::FulltextDocument::from_summary(&name, &summary);
+                if query.matches(&document) {
+                    hits.insert(raw.to_string());
+                }
+            }
+            Ok(_) => {
+                row_paths.insert(raw.to_string());
+            }
+            Err(error) => warnings.push(format!(
+                "{}: metadata summary failed: {error:#}",
+                path.display()
+            )),
+        }
+    }
+    if let Some(cache) = cache.as_ref() {
+        match cache.search_fulltext(&query) {
+            Ok(paths) => hits.extend(
+                paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .filter(|path| row_paths.contains(path)),
+            ),
+            Err(error) => warnings.push(format!("metadata full-text search failed: {error:#}")),
+        }
+    }
+    hits
+}
+
 fn apply_list_query_filter_sort(
     rows: &mut Vec<Map<String, Value>>,
     query: Option<&str>,
     sort_key: Option<&str>,
     sort_dir: Option<&str>,
+    metadata_hits: &HashSet<String>,
 ) {
     if let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) {
         let query = query.to_ascii_lowercase();
         rows.retain(|row| {
-            row.values().any(|value| {
-                value
-                    .as_str()
-                    .map(|value| value.to_ascii_lowercase().contains(&query))
-                    .unwrap_or_else(|| {
-                        matches!(value, Value::Number(_) | Value::Bool(_))
-                            && value.to_string().to_ascii_lowercase().contains(&query)
-                    })
-            })
+            row.get("path")
+                .and_then(Value::as_str)
+                .is_some_and(|path| metadata_hits.contains(path))
+                || row.values().any(|value| {
+                    value
+                        .as_str()
+                        .map(|value| value.to_ascii_lowercase().contains(&query))
+                        .unwrap_or_else(|| {
+                            matches!(value, Value::Number(_) | Value::Bool(_))
+                                && value.to_string().to_ascii_lowercase().contains(&query)
+                        })
+                })
         });
     }
     if let Some(sort_key) = sort_key {
@@ -3717,11 +4751,13 @@ fn matched_session_entries(
         .iter()
         .map(|entry| list_row_for_entry(entry, Some(session), false, &mut Vec::new()))
         .collect::<Result<Vec<_>>>()?;
+    let metadata_hits = metadata_fulltext_hits(&rows, filter.query.as_deref(), &mut Vec::new());
     apply_list_query_filter_sort(
         &mut rows,
         filter.query.as_deref(),
         filter.sort_key.as_deref(),
         filter.sort_dir.as_deref(),
+        &metadata_hits,
     );
     let entry_map: HashMap<String, SessionListEntry> = entries
         .into_iter()
@@ -3832,7 +4868,12 @@ fn measure_file_loudness_and_peak(path: &Path) -> (Option<f32>, Option<f32>, Opt
         .flat_map(|channel| channel.iter().copied())
         .fold(0.0f32, |acc, sample| acc.max(sample.abs()));
     let peak_db = (peak > 0.0).then_some(20.0 * peak.log10());
-    match wave::lufs_integrated_from_multi(&channels, sample_rate.max(1)) {
+    let layout = crate::app::types::LoudnessLayout::resolve(
+        channels.len(),
+        crate::audio_io::read_channel_layout(path),
+        None,
+    );
+    match wave::lufs_integrated_for_layout(&channels, sample_rate.max(1), layout.layout) {
         Ok(lufs) if lufs.is_finite() => (Some(lufs), peak_db, None),
         Ok(_) => (
             None,
@@ -4613,6 +5654,7 @@ fn parse_list_column_config(raw: &str) -> Result<ListColumnConfig> {
         dbtp: false,
         lufs_s: false,
         lufs_m: false,
+        lra: false,
         bpm: false,
         created_at: false,
         modified_at: false,
@@ -4644,6 +5686,7 @@ fn parse_list_column_config(raw: &str) -> Result<ListColumnConfig> {
             "dbtp" => cfg.dbtp = true,
             "lufs_s" => cfg.lufs_s = true,
             "lufs_m" => cfg.lufs_m = true,
+            "lra" => cfg.lra = true,
             "bpm" => cfg.bpm = true,
             "silence_lead" => cfg.silence_lead = true,
             "silence_tail" => cfg.silence_tail = true,
@@ -4680,6 +5723,7 @@ fn project_list_columns_from_config(cfg: ListColumnConfig) -> ProjectListColumns
         dbtp: cfg.dbtp,
         lufs_s: cfg.lufs_s,
         lufs_m: cfg.lufs_m,
+        lra: cfg.lra,
         bpm: cfg.bpm,
         created_at: cfg.created_at,
         modified_at: cfg.modified_at,
@@ -4826,7 +5870,7 @@ fn is_exact_stream_playback_candidate(path: &Path, dirty: bool) -> bool {
         && path
             .extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("wav"))
+            .map(|ext| ext.eq_ignore_ascii_case("wav") || ext.eq_ignore_ascii_case("flac"))
             .unwrap_or(false)
 }
 
@@ -4837,8 +5881,8 @@ fn play_exact_stream(
     rate: f32,
 ) -> Result<()> {
     engine
-        .set_streaming_wav_path(path)
-        .with_context(|| format!("open streaming wav: {}", path.display()))?;
+        .set_streaming_path(path)
+        .with_context(|| format!("open exact stream: {}", path.display()))?;
     engine.set_rate(rate);
     engine.set_loop_enabled(false);
     engine.seek_to_sample(range.0);
@@ -5261,6 +6305,7 @@ fn render_gui_session_screenshot(
             ViewMode::Tempogram => "tempogram",
             ViewMode::Chromagram => "chromagram",
             ViewMode::World => "world",
+            ViewMode::Loudness => "loudness",
         }));
     }
     if let Some(flag) = waveform_overlay {
@@ -5332,6 +6377,7 @@ fn view_mode_name(mode: crate::cli::CliViewMode) -> &'static str {
         crate::cli::CliViewMode::Tempogram => "tempogram",
         crate::cli::CliViewMode::Chromagram => "chromagram",
         crate::cli::CliViewMode::World => "world",
+        crate::cli::CliViewMode::Loudness => "loudness",
     }
 }
 
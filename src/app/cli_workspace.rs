@@ -495,13 +495,14 @@ impl CliWorkspace {
         Option<(usize, usize)>,
     )> {
         let tab_idx = self.ensure_target_tab_loaded(requested)?;
-        let (src, mut channels, buffer_sr, bit_depth, current_markers, current_loop) = {
+        let (src, mut channels, buffer_sr, bit_depth, layout, current_markers, current_loop) = {
             let tab = self.app.tabs.get(tab_idx).context("missing target tab")?;
             (
                 tab.path.clone(),
                 tab.ch_samples.clone(),
                 tab.buffer_sample_rate.max(1),
                 self.app.bit_depth_override.get(&tab.path).copied(),
+                tab.channel_layout(),
                 marker_override.unwrap_or_else(|| tab.markers.clone()),
                 loop_override.or(tab.loop_region),
             )
@@ -525,8 +526,14 @@ impl CliWorkspace {
         };
         if !overwrite {
             ensure_parent_dir(&dst)?;
-            crate::wave::export_channels_audio_with_depth(&channels, buffer_sr, &dst, bit_depth)
-                .with_context(|| format!("export audio: {} -> {}", src.display(), dst.display()))?;
+            crate::wave::export_channels_audio_with_layout(
+                &channels,
+                buffer_sr,
+                &dst,
+                bit_depth,
+                Some(layout),
+            )
+            .with_context(|| format!("export audio: {} -> {}", src.display(), dst.display()))?;
             crate::wave::copy_audio_metadata_from_source(&src, &dst).with_context(|| {
                 format!("copy metadata: {} -> {}", src.display(), dst.display())
             })?;
@@ -537,6 +544,7 @@ impl CliWorkspace {
                 &dst,
                 self.app.export_cfg.backup_bak,
                 bit_depth,
+                Some(layout),
             )
             .with_context(|| format!("overwrite audio: {}", dst.display()))?;
         }
@@ -66,6 +66,27 @@ impl super::WavesPreviewer {
         Vec::new()
     }
 
+    /// Image bytes on the clipboard: a bitmap (as BMP file bytes), else the
+    /// first copied image file.
+    #[cfg(windows)]
+    pub(super) fn get_clipboard_image(&self) -> Option<Vec<u8>> {
+        use clipboard_win::formats::Bitmap;
+        if let Ok(bytes) = clipboard_win::get_clipboard::<Vec<u8>, _>(Bitmap) {
+            if !bytes.is_empty() {
+                return Some(bytes);
+            }
+        }
+        self.get_clipboard_files().into_iter().find_map(|path| {
+            let bytes = std::fs::read(path).ok()?;
+            image::guess_format(&bytes).ok().map(|_| bytes)
+        })
+    }
+
+    #[cfg(not(windows))]
+    pub(super) fn get_clipboard_image(&self) -> Option<Vec<u8>> {
+        None
+    }
+
     #[cfg(windows)]
     fn get_clipboard_text(&self) -> Option<String> {
         use clipboard_win::formats::Unicode;
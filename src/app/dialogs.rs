@@ -100,6 +100,7 @@ impl WavesPreviewer {
         {
             rfd::FileDialog::new()
                 .add_filter("CSV/Excel", &["csv", "xlsx", "xls"])
+                .add_filter("Library Catalog", &["sqlite", "db"])
                 .pick_file()
         }
     }
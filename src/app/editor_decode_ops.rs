@@ -205,7 +205,7 @@ impl super::WavesPreviewer {
                 });
                 let mut full_source_channels: Vec<Vec<f32>> = Vec::new();
                 let mut last_progress_emit_at: Option<std::time::Instant> = None;
-                if let Ok(Some(overview_proxy)) = crate::audio_io::build_wav_proxy_preview(
+                if let Ok(Some(overview_proxy)) = crate::audio_io::build_proxy_preview(
                     &decode_path_for_thread,
                     crate::audio_io::EDITOR_PROXY_OVERVIEW_MAX_TOTAL_SAMPLES,
                 ) {
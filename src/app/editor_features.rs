@@ -2,7 +2,7 @@ use std::path::{Path, PathBuf};
 
 use super::types::{
     AnalysisProgress, EditorAnalysisKey, EditorAnalysisKind, EditorFeatureAnalysisData,
-    EditorFeatureAnalysisJobMsg, ViewMode,
+    EditorFeatureAnalysisJobMsg, LoudnessLayout, ViewMode,
 };
 
 impl super::WavesPreviewer {
@@ -151,6 +151,54 @@ impl super::WavesPreviewer {
         });
     }
 
+    /// Momentary / short-term loudness and true peak over time. Runs on the
+    /// individual channels (BS.1770 weights of `layout`), not the mixdown.
+    fn queue_loudness_data(
+        &mut self,
+        path: PathBuf,
+        channels: std::sync::Arc<Vec<Vec<f32>>>,
+        samples_len: usize,
+        sample_rate: u32,
+        layout: crate::channel_layout::ChannelLayout,
+        generation: u64,
+    ) {
+        self.ensure_feature_analysis_channel();
+        let Some(tx) = self.editor_feature_tx.as_ref().cloned() else {
+            return;
+        };
+        let key = EditorAnalysisKey {
+            path: path.clone(),
+            kind: EditorAnalysisKind::Loudness,
+        };
+        let cancel = self
+            .editor_feature_cancel
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)));
+        std::thread::spawn(move || {
+            super::threading::lower_current_thread_priority();
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                return;
+            }
+            let chans: Vec<Vec<f32>> = channels
+                .iter()
+                .map(|ch| ch[..ch.len().min(samples_len)].to_vec())
+                .collect();
+            let Ok(data) = crate::wave::loudness_timeline_for_layout(&chans, sample_rate, layout)
+            else {
+                return;
+            };
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                return;
+            }
+            let _ = tx.send(EditorFeatureAnalysisJobMsg::LoudnessDone {
+                path,
+                generation,
+                data,
+            });
+        });
+    }
+
     /// WORLD (DIO/Harvest + CheapTrick + D4C) analysis of a mono buffer,
     /// packaged for the editor feature pipeline. The frame period grows
     /// with clip length so long files stay bounded in frames and cost.
@@ -213,6 +261,7 @@ impl super::WavesPreviewer {
             ViewMode::Tempogram => EditorAnalysisKind::Tempogram,
             ViewMode::Chromagram => EditorAnalysisKind::Chromagram,
             ViewMode::World => EditorAnalysisKind::World,
+            ViewMode::Loudness => EditorAnalysisKind::Loudness,
             _ => return,
         };
         let key = EditorAnalysisKey {
@@ -229,6 +278,12 @@ impl super::WavesPreviewer {
         let channels = tab.ch_samples_arc.clone();
         let samples_len = tab.samples_len;
         let sample_rate = tab.buffer_sample_rate.max(1);
+        let loudness_layout = LoudnessLayout::resolve(
+            tab.ch_samples.len(),
+            Some(tab.channel_layout()),
+            self.loudness_layout_override,
+        )
+        .layout;
         self.editor_feature_progress.insert(
             key.clone(),
             AnalysisProgress {
@@ -264,6 +319,16 @@ impl super::WavesPreviewer {
             EditorAnalysisKind::World => {
                 self.queue_world_data(key.path, channels, samples_len, sample_rate, generation);
             }
+            EditorAnalysisKind::Loudness => {
+                self.queue_loudness_data(
+                    key.path,
+                    channels,
+                    samples_len,
+                    sample_rate,
+                    loudness_layout,
+                    generation,
+                );
+            }
             EditorAnalysisKind::Spectrogram => {}
         }
     }
@@ -277,7 +342,7 @@ impl super::WavesPreviewer {
             ViewMode::Spectrogram | ViewMode::Log | ViewMode::Mel => {
                 self.queue_spectrogram_for_tab(tab_idx);
             }
-            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World => {
+            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World | ViewMode::Loudness => {
                 self.queue_feature_analysis_for_tab(tab_idx);
             }
         }
@@ -348,6 +413,21 @@ impl super::WavesPreviewer {
                         EditorFeatureAnalysisData::World(data),
                     );
                 }
+                EditorFeatureAnalysisJobMsg::LoudnessDone {
+                    path,
+                    generation,
+                    data,
+                } => {
+                    let key = EditorAnalysisKey {
+                        path,
+                        kind: EditorAnalysisKind::Loudness,
+                    };
+                    self.finish_feature_analysis(
+                        key,
+                        generation,
+                        EditorFeatureAnalysisData::Loudness(data),
+                    );
+                }
             }
             ctx.request_repaint();
         }
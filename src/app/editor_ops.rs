@@ -3346,6 +3346,12 @@ impl crate::app::WavesPreviewer {
             self.audio.stop();
         }
         let ch = tab.ch_samples.clone();
+        let loudness_layout = crate::app::types::LoudnessLayout::resolve(
+            ch.len(),
+            Some(tab.channel_layout()),
+            self.loudness_layout_override,
+        )
+        .layout;
         let buffer_sr = tab.buffer_sample_rate.max(1);
         let sr = self.audio.shared.out_sample_rate;
         let (tx, rx) = mpsc::channel::<EditorApplyResult>();
@@ -3392,7 +3398,7 @@ impl crate::app::WavesPreviewer {
                     }
                 }
                 ToolKind::Loudness => {
-                    let lufs = crate::wave::lufs_integrated_from_multi(&ch, sr)
+                    let lufs = crate::wave::lufs_integrated_for_layout(&ch, sr, loudness_layout)
                         .unwrap_or(f32::NEG_INFINITY);
                     if lufs.is_finite() {
                         let gain_db = param - lufs;
@@ -446,6 +446,7 @@ impl super::WavesPreviewer {
             ViewMode::Tempogram => EditorAnalysisKind::Tempogram,
             ViewMode::Chromagram => EditorAnalysisKind::Chromagram,
             ViewMode::World => EditorAnalysisKind::World,
+            ViewMode::Loudness => EditorAnalysisKind::Loudness,
             _ => return None,
         };
         Some(EditorAnalysisKey {
@@ -477,6 +478,8 @@ impl super::WavesPreviewer {
                     return None;
                 }
             }
+            // Curves are painted directly from the analysis; nothing to raster.
+            ViewMode::Loudness => return None,
         };
         Some(EditorViewportRenderKey {
             kind: kind.clone(),
@@ -696,6 +699,7 @@ impl super::WavesPreviewer {
                     view_mode: hint.view_mode,
                 })
             }
+            ViewMode::Loudness => None,
         }
     }
 
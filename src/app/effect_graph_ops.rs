@@ -12,7 +12,7 @@ use super::types::{
     AppliedEffectGraphStamp, CachedEdit, EffectGraphApplyPostprocessJob,
     EffectGraphApplyPostprocessResult, EffectGraphAudioBus, EffectGraphBitDepth,
     EffectGraphChannelFlowHint, EffectGraphChannelLayout, EffectGraphChannelLayoutEntry,
-    EffectGraphCombineMode, EffectGraphDebugPreview, EffectGraphDebugViewState,
+    EffectGraphCombineMode, EffectGraphDebugPreview, EffectGraphDebugViewState, EffectGraphDither,
     EffectGraphDocument, EffectGraphEdge, EffectGraphInputPreviewResult, EffectGraphLibraryEntry,
     EffectGraphNode, EffectGraphNodeData, EffectGraphNodeKind, EffectGraphNodeRunPhase,
     EffectGraphNodeRunStatus, EffectGraphPendingAction, EffectGraphPlaybackTarget,
@@ -57,6 +57,9 @@ struct EffectGraphWorkerInput {
     /// Physical current-revision backing used only by decoders.
     decode_path: PathBuf,
     input_bus: Option<EffectGraphAudioBus>,
+    /// Speaker layout the open tab declares; read from `decode_path` when
+    /// `None`.
+    declared_layout: Option<crate::channel_layout::ChannelLayout>,
     bit_depth: Option<crate::wave::WavBitDepth>,
     monitor_sr: u32,
     resample_quality: crate::wave::ResampleQuality,
@@ -1030,11 +1033,18 @@ fn node_parameter_summary(data: &EffectGraphNodeData) -> String {
         } => {
             format!("Silence trim / {pre_roll_ms:.0}ms pre / {post_roll_ms:.0}ms post")
         }
-        EffectGraphNodeData::BitDepth { depth } => match depth {
-            EffectGraphBitDepth::Pcm16 => "16-bit".to_string(),
-            EffectGraphBitDepth::Pcm24 => "24-bit".to_string(),
-            EffectGraphBitDepth::Float32 => "32-bit float".to_string(),
-        },
+        EffectGraphNodeData::BitDepth { depth, dither, .. } => {
+            let depth_label = match depth {
+                EffectGraphBitDepth::Pcm16 => "16-bit",
+                EffectGraphBitDepth::Pcm24 => "24-bit",
+                EffectGraphBitDepth::Float32 => "32-bit float",
+            };
+            if *depth == EffectGraphBitDepth::Float32 || *dither == EffectGraphDither::Off {
+                depth_label.to_string()
+            } else {
+                format!("{depth_label} / {}", dither.to_wave_dither_mode().label())
+            }
+        }
         EffectGraphNodeData::Resampler {
             target_sample_rate,
             quality,
@@ -2803,9 +2813,12 @@ fn adaptive_combine_channels_by_layout(
     ))
 }
 
+/// `source_layout` is the input's BS.1770 layout; Loudness nodes use it while
+/// their bus keeps the input's channel count.
 fn run_effect_graph_document_internal<F>(
     document: &EffectGraphDocument,
     input_bus: EffectGraphAudioBus,
+    source_layout: Option<crate::channel_layout::ChannelLayout>,
     run_mode: EffectGraphRunMode,
     resample_quality: crate::wave::ResampleQuality,
     execution_flavor: EffectGraphExecutionFlavor,
@@ -3111,7 +3124,17 @@ where
                         channel_layout: bus.channel_layout.clone(),
                     }
                 } else {
-                    match crate::wave::lufs_integrated_from_multi(&bus.channels, bus.sample_rate) {
+                    let layout = super::types::LoudnessLayout::resolve(
+                        bus.channels.len(),
+                        source_layout,
+                        None,
+                    )
+                    .layout;
+                    match crate::wave::lufs_integrated_for_layout(
+                        &bus.channels,
+                        bus.sample_rate,
+                        layout,
+                    ) {
                         Ok(measured_lufs) if measured_lufs.is_finite() => {
                             let gain_db = *target_lufs - measured_lufs;
                             let gain = 10.0f32.powf(gain_db / 20.0);
@@ -3406,7 +3429,11 @@ where
                 };
                 output_buses.insert(make_port_key(&node.id, "out"), processed_bus);
             }
-            EffectGraphNodeData::BitDepth { depth } => {
+            EffectGraphNodeData::BitDepth {
+                depth,
+                dither,
+                auto_blank,
+            } => {
                 let mut bus =
                     effect_graph_input_bus_for_port(&node.id, "in", &input_sources, &output_buses)
                         .ok_or_else(|| {
@@ -3415,9 +3442,12 @@ where
                                 format!("{} input is missing", node.id),
                             )
                         })?;
-                crate::wave::quantize_channels_in_place(
+                crate::wave::quantize_channels_dithered_in_place(
                     &mut bus.channels,
                     depth.to_wave_bit_depth(),
+                    dither.to_wave_dither_mode(),
+                    *auto_blank,
+                    bus.sample_rate,
                 );
                 output_buses.insert(make_port_key(&node.id, "out"), bus);
             }
@@ -3679,6 +3709,7 @@ where
     run_effect_graph_document_internal(
         document,
         input_bus,
+        None,
         run_mode,
         resample_quality,
         EffectGraphExecutionFlavor::AudioRender,
@@ -3710,18 +3741,29 @@ pub(crate) fn effect_graph_test_document_for_cli(
         let (channels, sample_rate) = embedded_effect_graph_sample_channels()?;
         dense_audio_bus(channels, sample_rate.max(1))
     };
+    let source_layout = input_path.map(|path| {
+        super::types::LoudnessLayout::resolve(
+            input_bus.channels.len(),
+            crate::audio_io::read_channel_layout(path),
+            None,
+        )
+        .layout
+    });
     let mut debug_preview = None;
-    let output_bus = run_effect_graph_document(
+    let output_bus = run_effect_graph_document_internal(
         document,
         input_bus,
+        source_layout,
         EffectGraphRunMode::TestPreview,
         crate::wave::ResampleQuality::Good,
+        EffectGraphExecutionFlavor::AudioRender,
         |event| {
             if let EffectGraphRuntimeEvent::NodeDebugPreview { preview, .. } = event {
                 debug_preview = Some(preview);
             }
         },
-    )?;
+    )
+    .map_err(|err| err.message)?;
     let per_channel_peak_db = output_bus
         .channels
         .iter()
@@ -3766,6 +3808,7 @@ fn predict_effect_graph_output_format(
     let output_bus = run_effect_graph_document_internal(
         document,
         format_only_audio_bus(input_bus.channels.len(), input_bus.sample_rate),
+        None,
         EffectGraphRunMode::ApplyToListSelection,
         resample_quality,
         EffectGraphExecutionFlavor::FormatOnly,
@@ -4412,6 +4455,16 @@ impl WavesPreviewer {
         Arc::new(AudioBuffer::from_channels(monitor_channels))
     }
 
+    fn effect_graph_tab_channel_layout(
+        &self,
+        path: &Path,
+    ) -> Option<crate::channel_layout::ChannelLayout> {
+        self.tabs
+            .iter()
+            .find(|tab| tab.path.as_path() == path)
+            .and_then(|tab| tab.channel_layout)
+    }
+
     fn build_effect_graph_worker_inputs(&self, paths: &[PathBuf]) -> Vec<EffectGraphWorkerInput> {
         let monitor_sr = self.audio.shared.out_sample_rate.max(1);
         let resample_quality = Self::to_wave_resample_quality(self.src_quality);
@@ -4425,6 +4478,7 @@ impl WavesPreviewer {
                 EffectGraphWorkerInput {
                     bit_depth: self.bit_depth_override.get(&path).copied(),
                     input_bus: self.resident_effect_graph_audio_bus_for_path(&path),
+                    declared_layout: self.effect_graph_tab_channel_layout(&path),
                     monitor_sr,
                     path,
                     decode_path,
@@ -4605,6 +4659,7 @@ impl WavesPreviewer {
                 },
             );
         }
+        let layout_override = self.loudness_layout_override;
         std::thread::spawn(move || {
             let total = inputs.len();
             let _ = tx.send(EffectGraphWorkerEvent::RunStarted { mode, total });
@@ -4637,6 +4692,15 @@ impl WavesPreviewer {
                     }
                     dense_audio_bus(channels, in_sr.max(1))
                 };
+                let declared_layout = input
+                    .declared_layout
+                    .or_else(|| crate::audio_io::read_channel_layout(&input.decode_path));
+                let source_layout = super::types::LoudnessLayout::resolve(
+                    input_bus.channels.len(),
+                    declared_layout,
+                    layout_override,
+                )
+                .layout;
                 let started = Instant::now();
                 let input_bus_for_summary = if mode == EffectGraphRunMode::TestPreview {
                     Some(input_bus.clone())
@@ -4646,6 +4710,7 @@ impl WavesPreviewer {
                 let result = run_effect_graph_document_internal(
                     &document,
                     input_bus,
+                    Some(source_layout),
                     mode,
                     input.resample_quality,
                     EffectGraphExecutionFlavor::AudioRender,
@@ -4958,6 +5023,9 @@ impl WavesPreviewer {
         self.invalidate_effect_graph_input_preview();
         let (input_bus, target_path, worker_path) =
             self.effect_graph_resolve_test_input_source()?;
+        let declared_layout = target_path
+            .as_deref()
+            .and_then(|path| self.effect_graph_tab_channel_layout(path));
         self.effect_graph.tester.target_path = target_path.clone();
         if let Some(path) = target_path {
             self.effect_graph.tester.target_path_input = path.display().to_string();
@@ -5010,6 +5078,7 @@ impl WavesPreviewer {
                 path: worker_path.clone(),
                 decode_path: worker_path,
                 input_bus,
+                declared_layout,
                 bit_depth: None,
                 monitor_sr,
                 resample_quality,
@@ -5142,6 +5211,8 @@ impl WavesPreviewer {
                 plugin_fx_draft: tab.plugin_fx_draft.clone(),
                 plugin_fx_chain: tab.plugin_fx_chain.clone(),
                 show_waveform_overlay: tab.show_waveform_overlay,
+                channel_layout: tab.channel_layout,
+                adm_lanes: tab.adm_lanes.clone(),
                 applied_effect_graph: template_stamp.clone(),
             }
         } else if let Some(existing) = self.edited_cache.get(path).cloned() {
@@ -5190,6 +5261,8 @@ impl WavesPreviewer {
                 plugin_fx_draft: existing.plugin_fx_draft.clone(),
                 plugin_fx_chain: existing.plugin_fx_chain.clone(),
                 show_waveform_overlay: existing.show_waveform_overlay,
+                channel_layout: existing.channel_layout,
+                adm_lanes: existing.adm_lanes.clone(),
                 applied_effect_graph: template_stamp.clone(),
             }
         } else {
@@ -5237,6 +5310,8 @@ impl WavesPreviewer {
                 plugin_fx_draft: super::types::PluginFxDraft::default(),
                 plugin_fx_chain: super::types::PluginFxChainDraft::default(),
                 show_waveform_overlay: false,
+                channel_layout: crate::audio_io::read_channel_layout(path),
+                adm_lanes: crate::adm::read_adm_lanes(path),
                 applied_effect_graph: template_stamp.clone(),
             }
         };
@@ -6642,6 +6717,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn effect_graph_runtime_loudness_weights_by_source_layout() {
+        use crate::channel_layout::ChannelLayout;
+
+        let tone = (0..(48_000 * 2))
+            .map(|index| {
+                let phase = index as f32 * std::f32::consts::TAU * 220.0 / 48_000.0;
+                phase.sin() * 0.05
+            })
+            .collect::<Vec<_>>();
+        // Only the fourth channel carries signal: LFE in default 5.1, front
+        // left of center in a declared FL FR FC FLC FRC BC layout.
+        let mut channels = vec![vec![0.0f32; tone.len()]; 6];
+        channels[3] = tone;
+        let doc = doc_with_nodes(
+            vec![
+                EffectGraphNode {
+                    id: "input".to_string(),
+                    ui_pos: [0.0, 0.0],
+                    ui_size: [200.0, 100.0],
+                    data: EffectGraphNodeData::Input,
+                },
+                EffectGraphNode {
+                    id: "loudness".to_string(),
+                    ui_pos: [100.0, 0.0],
+                    ui_size: [200.0, 100.0],
+                    data: EffectGraphNodeData::Loudness { target_lufs: -14.0 },
+                },
+                EffectGraphNode {
+                    id: "output".to_string(),
+                    ui_pos: [200.0, 0.0],
+                    ui_size: [200.0, 100.0],
+                    data: EffectGraphNodeData::Output,
+                },
+            ],
+            vec![
+                edge("a", "input", "out", "loudness", "in"),
+                edge("b", "loudness", "out", "output", "in"),
+            ],
+        );
+        let run = |layout: Option<ChannelLayout>| {
+            run_effect_graph_document_internal(
+                &doc,
+                test_bus(channels.clone(), 48_000),
+                layout,
+                EffectGraphRunMode::TestPreview,
+                crate::wave::ResampleQuality::Good,
+                EffectGraphExecutionFlavor::AudioRender,
+                |_| {},
+            )
+            .expect("runtime ok")
+        };
+        // The default layout only sees LFE, which is unweighted: pass-through.
+        assert_eq!(run(None).channels, channels);
+        let declared = ChannelLayout::new(6, 0x1C7);
+        let out = run(Some(declared));
+        let out_lufs =
+            crate::wave::lufs_integrated_for_layout(&out.channels, out.sample_rate, declared)
+                .expect("output lufs");
+        assert!(
+            (out_lufs - (-14.0)).abs() < 0.35,
+            "expected about -14 LUFS, got {out_lufs}"
+        );
+    }
+
     #[test]
     fn effect_graph_mono_mix_downmixes_while_ignoring_selected_channels() {
         let doc = doc_with_nodes(
@@ -6850,6 +6990,8 @@ mod tests {
                     ui_size: [200.0, 100.0],
                     data: EffectGraphNodeData::BitDepth {
                         depth: crate::app::types::EffectGraphBitDepth::Pcm16,
+                        dither: crate::app::types::EffectGraphDither::Off,
+                        auto_blank: false,
                     },
                 },
                 EffectGraphNode {
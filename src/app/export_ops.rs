@@ -211,6 +211,7 @@ impl super::WavesPreviewer {
             write_markers: bool,
             write_loop_markers: bool,
             format_override: Option<String>,
+            channel_layout: Option<crate::channel_layout::ChannelLayout>,
         }
         let cfg = self.export_cfg.clone();
         // Encoders run on worker threads; publish the configured lossy-codec
@@ -383,6 +384,7 @@ impl super::WavesPreviewer {
                 let mut markers: Vec<crate::markers::MarkerEntry> = Vec::new();
                 let mut loop_region: Option<(usize, usize)> = None;
                 let mut ch_samples: Option<Vec<Vec<f32>>> = None;
+                let mut channel_layout = None;
                 let mut max_file_samples: Option<u64> = None;
                 let sr_override = self.sample_rate_override.get(&p).copied();
                 let bit_override = self.bit_depth_override.get(&p).copied();
@@ -392,6 +394,7 @@ impl super::WavesPreviewer {
                     loop_markers_dirty = tab.loop_markers_dirty;
                     markers = tab.markers.clone();
                     loop_region = tab.loop_region;
+                    channel_layout = Some(tab.channel_layout());
                     if dirty_audio
                         || markers_dirty
                         || loop_markers_dirty
@@ -442,6 +445,7 @@ impl super::WavesPreviewer {
                     loop_markers_dirty = cached.loop_markers_dirty;
                     markers = cached.markers.clone();
                     loop_region = cached.loop_region;
+                    channel_layout = cached.channel_layout;
                     if dirty_audio
                         || markers_dirty
                         || loop_markers_dirty
@@ -534,6 +538,7 @@ impl super::WavesPreviewer {
                         write_markers,
                         write_loop_markers,
                         format_override: path_format_override,
+                        channel_layout,
                     });
                     edit_sources.push(p);
                 } else if db.abs() > 0.0001 {
@@ -791,6 +796,9 @@ impl super::WavesPreviewer {
                         }
                     }
                     let format_changed = dst != task.src;
+                    let channel_layout = task
+                        .channel_layout
+                        .or_else(|| crate::audio_io::read_channel_layout(&task.src));
                     let res = match save_mode {
                         SaveMode::Overwrite if format_changed => {
                             // Converting in place (e.g. foo.wav -> foo.mp3):
@@ -804,13 +812,15 @@ impl super::WavesPreviewer {
                                     &dst,
                                     false,
                                     task.wav_bit_depth,
+                                    channel_layout,
                                 )
                             } else {
-                                crate::wave::export_channels_audio_with_depth(
+                                crate::wave::export_channels_audio_with_layout(
                                     &channels,
                                     task.target_sr,
                                     &dst,
                                     task.wav_bit_depth,
+                                    channel_layout,
                                 )
                             };
                             if write.is_ok() {
@@ -839,13 +849,15 @@ impl super::WavesPreviewer {
                                 &dst,
                                 cfg.backup_bak,
                                 task.wav_bit_depth,
+                                channel_layout,
                             )
                         }
-                        SaveMode::NewFile => crate::wave::export_channels_audio_with_depth(
+                        SaveMode::NewFile => crate::wave::export_channels_audio_with_layout(
                             &channels,
                             task.target_sr,
                             &dst,
                             task.wav_bit_depth,
+                            channel_layout,
                         ),
                     };
                     if res.is_err() {
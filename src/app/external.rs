@@ -48,6 +48,7 @@ pub fn spawn_load_table(cfg: ExternalLoadConfig, tx: Sender<ExternalLoadMsg>) {
             match ext.as_str() {
                 "csv" => load_csv_with_progress(&cfg, &tx),
                 "xlsx" | "xls" => load_excel_with_progress(&cfg, &tx),
+                "sqlite" | "db" => load_catalog_with_progress(&cfg, &tx),
                 _ => Err("Unsupported data source.".to_string()),
             }
         })();
@@ -126,6 +127,23 @@ fn load_csv_with_progress(
     })
 }
 
+/// A library catalog (see `catalog`) keyed by its `File` column; header and
+/// data row settings do not apply.
+fn load_catalog_with_progress(
+    cfg: &ExternalLoadConfig,
+    tx: &Sender<ExternalLoadMsg>,
+) -> Result<ExternalTable, String> {
+    let (headers, rows) =
+        super::catalog::load_catalog_table(&cfg.path).map_err(|e| format!("{e:#}"))?;
+    let _ = tx.send(ExternalLoadMsg::Progress { rows: rows.len() });
+    Ok(ExternalTable {
+        headers,
+        rows,
+        sheet_names: Vec::new(),
+        sheet_name: None,
+    })
+}
+
 fn normalize_header(raw: &str, idx: usize) -> String {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -194,7 +194,9 @@ impl WavesPreviewer {
         self.drain_plugin_jobs(ctx);
         self.poll_plugin_auto_preview(ctx);
         self.poll_variation_audition(ctx);
+        self.poll_playlist_playback(ctx);
         self.drain_duplicate_scan(ctx);
+        self.drain_catalog_export(ctx);
         self.drain_transcript_model_download_results(ctx);
         self.drain_transcript_ai_results(ctx);
         self.drain_music_model_download_results(ctx);
@@ -206,6 +208,7 @@ impl WavesPreviewer {
             "drain_metadata_summary_updates",
             self.drain_metadata_summary_updates(ctx)
         );
+        self.poll_metadata_fulltext_search(ctx);
         self.drain_external_load_results(ctx);
         self.check_csv_export_completion();
         self.tick_bulk_resample();
@@ -233,6 +236,7 @@ impl WavesPreviewer {
     fn run_frame_workspace(&mut self, ui: &mut egui::Ui) -> Option<PathBuf> {
         let ctx = ui.ctx().clone();
         self.ui_top_bar(ui);
+        self.ui_analyzer_dock(ui);
         self.handle_dropped_files(&ctx);
         let mut activate_path: Option<PathBuf> = None;
         egui::CentralPanel::default().show_inside(ui, |ui| {
@@ -595,12 +599,19 @@ impl WavesPreviewer {
         self.ui_plugin_manager_window(ctx);
         self.ui_duplicates_window(ctx);
         self.ui_engine_export_dialog(ctx);
+        self.ui_catalog_export_dialog(ctx);
         self.ui_bwf_dialog(ctx);
+        self.ui_cart_dialog(ctx);
+        self.ui_ucs_batch_dialog(ctx);
+        self.ui_tag_editor_dialog(ctx);
+        self.ui_metadata_template_dialog(ctx);
+        self.ui_artwork_dialog(ctx);
         self.ui_inspection_dialog(ctx);
         self.ui_loudnorm_dialog(ctx);
         self.ui_transcription_settings_window(ctx);
         self.ui_external_data_window(ctx);
         self.ui_transcript_window(ctx);
+        self.ui_analyzer_window(ctx);
         self.ui_inspection_window(ctx);
         self.ui_list_art_window(ctx);
         self.ui_tool_palette_window(ctx);
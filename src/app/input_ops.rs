@@ -271,7 +271,8 @@ impl super::WavesPreviewer {
                         ViewMode::Mel => ViewMode::Tempogram,
                         ViewMode::Tempogram => ViewMode::Chromagram,
                         ViewMode::Chromagram => ViewMode::World,
-                        ViewMode::World => ViewMode::Waveform,
+                        ViewMode::World => ViewMode::Loudness,
+                        ViewMode::Loudness => ViewMode::Waveform,
                     };
                     if let Some(tab) = self.tabs.get_mut(tab_idx) {
                         tab.set_leaf_view_mode(next);
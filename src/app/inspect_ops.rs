@@ -94,6 +94,28 @@ impl WavesPreviewer {
                             .suffix(" LU"),
                     );
                 });
+                ui.checkbox(&mut cfg.check_loudness_range, "Loudness range (EBU Tech 3342)");
+                ui.horizontal(|ui| {
+                    ui.add_enabled(
+                        cfg.check_loudness_range,
+                        egui::DragValue::new(&mut cfg.max_lra_lu)
+                            .range(1.0..=40.0)
+                            .speed(0.1)
+                            .prefix("LRA > ")
+                            .suffix(" LU"),
+                    );
+                });
+                ui.checkbox(&mut cfg.check_short_term, "Max short-term loudness");
+                ui.horizontal(|ui| {
+                    ui.add_enabled(
+                        cfg.check_short_term,
+                        egui::DragValue::new(&mut cfg.max_short_term_lufs)
+                            .range(-36.0..=0.0)
+                            .speed(0.1)
+                            .prefix("S max > ")
+                            .suffix(" LUFS"),
+                    );
+                });
                 ui.checkbox(&mut cfg.check_silence, "Leading/trailing silence");
                 ui.horizontal(|ui| {
                     ui.add_enabled(
@@ -183,10 +205,11 @@ impl WavesPreviewer {
         }
     }
 
-    pub(super) fn begin_inspection_run(&mut self, paths: Vec<PathBuf>, cfg: InspectionConfig) {
+    pub(super) fn begin_inspection_run(&mut self, paths: Vec<PathBuf>, mut cfg: InspectionConfig) {
         if paths.is_empty() || self.inspection_run_state.is_some() {
             return;
         }
+        cfg.loudness_layout_override = self.loudness_layout_override;
         // Snapshot cached facts on the UI thread so workers never touch app
         // state. peak_db only counts when it came from a full decode.
         let jobs: VecDeque<(PathBuf, f32, CachedAudioFacts)> = paths
@@ -194,7 +217,16 @@ impl WavesPreviewer {
             .map(|path| {
                 let mut facts = CachedAudioFacts::default();
                 if let Some(meta) = self.meta_for_path(path) {
-                    facts.lufs_i = meta.lufs_i;
+                    // Loudness weighted under another layout is re-measured.
+                    let layout_current = !meta
+                        .loudness_layout
+                        .is_some_and(|l| l.is_stale(cfg.loudness_layout_override));
+                    if layout_current {
+                        facts.lufs_i = meta.lufs_i;
+                        facts.lufs_s_max = meta.lufs_s_max;
+                        facts.lufs_m_max = meta.lufs_m_max;
+                        facts.lra_lu = meta.lra_lu;
+                    }
                     facts.true_peak_db = meta.true_peak_db;
                     if !meta.peak_db_estimate {
                         facts.peak_db = meta.peak_db;
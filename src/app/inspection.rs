@@ -12,7 +12,7 @@ use std::sync::Arc;
 
 use serde::Serialize;
 
-use super::types::{BlankPadScan, EdgeSamples};
+use super::types::{BlankPadScan, EdgeSamples, LoudnessLayout};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct InspectionConfig {
@@ -22,6 +22,12 @@ pub struct InspectionConfig {
     pub check_loudness: bool,
     pub target_lufs: f32,
     pub lufs_tolerance_lu: f32,
+    /// Loudness range (EBU Tech 3342) above `max_lra_lu` warns.
+    pub check_loudness_range: bool,
+    pub max_lra_lu: f32,
+    /// Effective max short-term loudness above `max_short_term_lufs` warns.
+    pub check_short_term: bool,
+    pub max_short_term_lufs: f32,
     pub check_silence: bool,
     pub silence_threshold_dbfs: f32,
     pub max_leading_silence_ms: f32,
@@ -33,6 +39,9 @@ pub struct InspectionConfig {
     pub check_naming: bool,
     /// Regex the file stem (name without extension) must match.
     pub naming_pattern: String,
+    /// Copy of the app's `loudness_layout_override`; applied by
+    /// [`LoudnessLayout::resolve`].
+    pub loudness_layout_override: Option<crate::channel_layout::ChannelLayout>,
 }
 
 impl Default for InspectionConfig {
@@ -43,6 +52,11 @@ impl Default for InspectionConfig {
             check_loudness: true,
             target_lufs: -14.0,
             lufs_tolerance_lu: 1.0,
+            check_loudness_range: false,
+            max_lra_lu: 15.0,
+            check_short_term: false,
+            // 5 LU above the default target (EBU R128 s1 uses the same gap).
+            max_short_term_lufs: -9.0,
             check_silence: true,
             silence_threshold_dbfs: DEFAULT_SILENCE_THRESHOLD_DBFS,
             max_leading_silence_ms: 100.0,
@@ -51,6 +65,7 @@ impl Default for InspectionConfig {
             require_loop: false,
             check_naming: false,
             naming_pattern: "^(se|bgm|vo|amb|ui)_[a-z0-9_]+$".to_string(),
+            loudness_layout_override: None,
         }
     }
 }
@@ -67,6 +82,8 @@ pub enum InspectionIssueKind {
     DecodeError,
     TruePeakOver,
     LoudnessOutOfRange,
+    LoudnessRangeOver,
+    ShortTermOver,
     LeadingSilence,
     TrailingSilence,
     LoopInvalid,
@@ -98,6 +115,10 @@ pub struct InspectionRow {
     pub pending_gain_db: f32,
     pub effective_lufs: Option<f32>,
     pub effective_true_peak_db: Option<f32>,
+    /// Gain-invariant, so never adjusted by `pending_gain_db`.
+    pub lra_lu: Option<f32>,
+    pub effective_lufs_s_max: Option<f32>,
+    pub effective_lufs_m_max: Option<f32>,
     /// True when `effective_true_peak_db` is a plain sample-peak fallback.
     pub true_peak_is_sample_peak: bool,
     pub leading_silence_ms: Option<f32>,
@@ -116,6 +137,9 @@ pub struct InspectionRow {
 #[derive(Clone, Copy, Debug, Default)]
 pub struct CachedAudioFacts {
     pub lufs_i: Option<f32>,
+    pub lufs_s_max: Option<f32>,
+    pub lufs_m_max: Option<f32>,
+    pub lra_lu: Option<f32>,
     pub true_peak_db: Option<f32>,
     /// Full-decode sample peak only (never the header-pass estimate).
     pub peak_db: Option<f32>,
@@ -365,6 +389,38 @@ pub fn check_naming_issue(cfg: &InspectionConfig, file_stem: &str) -> Option<Ins
     }
 }
 
+/// Loudness range and max short-term checks. Both values come from the same
+/// full decode as `lufs_i`; an LRA of `None` (file under 3 s) never flags.
+pub fn check_loudness_range_issues(
+    cfg: &InspectionConfig,
+    lra_lu: Option<f32>,
+    effective_lufs_s_max: Option<f32>,
+) -> Vec<InspectionIssue> {
+    let mut issues = Vec::new();
+    if cfg.check_loudness_range {
+        if let Some(lra) = lra_lu.filter(|&lra| lra > cfg.max_lra_lu) {
+            issues.push(InspectionIssue {
+                kind: InspectionIssueKind::LoudnessRangeOver,
+                severity: IssueSeverity::Warning,
+                message: format!("loudness range {lra:.1} LU above {:.1} LU", cfg.max_lra_lu),
+            });
+        }
+    }
+    if cfg.check_short_term {
+        if let Some(st) = effective_lufs_s_max.filter(|&st| st > cfg.max_short_term_lufs) {
+            issues.push(InspectionIssue {
+                kind: InspectionIssueKind::ShortTermOver,
+                severity: IssueSeverity::Warning,
+                message: format!(
+                    "max short-term {st:+.1} LUFS above {:+.1} LUFS",
+                    cfg.max_short_term_lufs
+                ),
+            });
+        }
+    }
+    issues
+}
+
 /// Inspect one file. Decodes only when an enabled check needs data the
 /// cached facts don't provide (silence always needs a decode).
 pub fn inspect_file(
@@ -385,6 +441,9 @@ pub fn inspect_file(
         .unwrap_or_default();
 
     let mut lufs = cached.lufs_i;
+    let mut lufs_s_max = cached.lufs_s_max;
+    let mut lufs_m_max = cached.lufs_m_max;
+    let mut lra_lu = cached.lra_lu;
     let tp_db = cached.true_peak_db;
     let mut sample_peak_db = cached.peak_db;
     let mut total_frames = cached.total_frames;
@@ -392,8 +451,12 @@ pub fn inspect_file(
     let mut trailing_ms = None;
     let mut decode_error: Option<String> = None;
 
+    // A cached integrated value means the full loudness pass already ran, so
+    // a missing LRA there is "too short", not "not measured".
+    let needs_range = (cfg.check_loudness_range || cfg.check_short_term) && cached.lufs_i.is_none();
     let needs_decode = cfg.check_silence
         || (cfg.check_loudness && lufs.is_none())
+        || needs_range
         || (cfg.check_true_peak && tp_db.is_none() && sample_peak_db.is_none());
 
     if needs_decode && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
@@ -406,8 +469,20 @@ pub fn inspect_file(
                     leading_ms = Some(lead);
                     trailing_ms = Some(trail);
                 }
-                if cfg.check_loudness && lufs.is_none() {
-                    lufs = crate::wave::lufs_integrated_from_multi(&chans, sr).ok();
+                if needs_range || (cfg.check_loudness && lufs.is_none()) {
+                    let layout = LoudnessLayout::resolve(
+                        chans.len(),
+                        crate::audio_io::read_channel_layout(path),
+                        cfg.loudness_layout_override,
+                    );
+                    if let Ok(metrics) =
+                        crate::wave::loudness_metrics_for_layout(&chans, sr, layout.layout)
+                    {
+                        lufs = lufs.or(metrics.lufs_i);
+                        lufs_s_max = lufs_s_max.or(metrics.lufs_s_max);
+                        lufs_m_max = lufs_m_max.or(metrics.lufs_m_max);
+                        lra_lu = lra_lu.or(metrics.lra_lu);
+                    }
                 }
                 if cfg.check_true_peak && tp_db.is_none() && sample_peak_db.is_none() {
                     let peak = chans
@@ -444,6 +519,8 @@ pub fn inspect_file(
     };
 
     let effective_lufs = lufs.map(|v| v + pending_gain_db);
+    let effective_lufs_s_max = lufs_s_max.map(|v| v + pending_gain_db);
+    let effective_lufs_m_max = lufs_m_max.map(|v| v + pending_gain_db);
     let tp_is_sample_peak = tp_db.is_none();
     let effective_tp_db = tp_db
         .or(sample_peak_db)
@@ -460,6 +537,11 @@ pub fn inspect_file(
         loop_status,
         decode_error.as_deref(),
     );
+    issues.extend(check_loudness_range_issues(
+        cfg,
+        lra_lu,
+        effective_lufs_s_max,
+    ));
     let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
     if let Some(issue) = check_naming_issue(cfg, stem) {
         issues.push(issue);
@@ -473,6 +555,9 @@ pub fn inspect_file(
         pending_gain_db,
         effective_lufs,
         effective_true_peak_db: effective_tp_db,
+        lra_lu,
+        effective_lufs_s_max,
+        effective_lufs_m_max,
         true_peak_is_sample_peak: tp_is_sample_peak,
         leading_silence_ms: leading_ms,
         trailing_silence_ms: trailing_ms,
@@ -524,6 +609,8 @@ pub fn write_batch_inspection_report(
                 "target_lufs": cfg.target_lufs,
                 "lufs_tolerance_lu": cfg.lufs_tolerance_lu,
                 "tp_ceiling_db": cfg.tp_ceiling_db,
+                "max_lra_lu": cfg.check_loudness_range.then_some(cfg.max_lra_lu),
+                "max_short_term_lufs": cfg.check_short_term.then_some(cfg.max_short_term_lufs),
                 "rows": rows,
             });
             std::fs::write(path, serde_json::to_string_pretty(&body)?)?;
@@ -532,13 +619,13 @@ pub fn write_batch_inspection_report(
             let mut out = String::new();
             out.push_str(
                 "severity,file,folder,path,pending_gain_db,effective_lufs,effective_true_peak_db,\
-                 leading_silence_ms,trailing_silence_ms,loop_status,loop_start,loop_end,\
+                 lra_lu,effective_lufs_s_max,effective_lufs_m_max,leading_silence_ms,trailing_silence_ms,loop_status,loop_start,loop_end,\
                  total_frames,issues\n",
             );
             for row in rows {
                 let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
                 out.push_str(&format!(
-                    "{},{},{},{},{:.2},{},{},{},{},{:?},{},{},{},{}\n",
+                    "{},{},{},{},{:.2},{},{},{},{},{},{},{},{:?},{},{},{},{}\n",
                     severity_label(row.severity),
                     quote(&row.file),
                     quote(&row.folder),
@@ -546,6 +633,9 @@ pub fn write_batch_inspection_report(
                     row.pending_gain_db,
                     fmt_opt(row.effective_lufs),
                     fmt_opt(row.effective_true_peak_db),
+                    fmt_opt(row.lra_lu),
+                    fmt_opt(row.effective_lufs_s_max),
+                    fmt_opt(row.effective_lufs_m_max),
                     fmt_opt(row.leading_silence_ms),
                     fmt_opt(row.trailing_silence_ms),
                     row.loop_status,
@@ -563,16 +653,17 @@ pub fn write_batch_inspection_report(
         }
         "md" => {
             let mut out = String::from(
-                "| Severity | File | LUFS | dBTP | Lead ms | Trail ms | Loop | Issues |\n\
-                 |---|---|---|---|---|---|---|---|\n",
+                "| Severity | File | LUFS | dBTP | LRA | Lead ms | Trail ms | Loop | Issues |\n\
+                 |---|---|---|---|---|---|---|---|---|\n",
             );
             for row in rows {
                 out.push_str(&format!(
-                    "| {} | {} | {} | {} | {} | {} | {:?} | {} |\n",
+                    "| {} | {} | {} | {} | {} | {} | {} | {:?} | {} |\n",
                     severity_label(row.severity),
                     row.file,
                     fmt_opt(row.effective_lufs),
                     fmt_opt(row.effective_true_peak_db),
+                    fmt_opt(row.lra_lu),
                     fmt_opt(row.leading_silence_ms),
                     fmt_opt(row.trailing_silence_ms),
                     row.loop_status,
@@ -601,6 +692,152 @@ pub fn write_batch_inspection_report(
     Ok(())
 }
 
+/// Full loudness report of one file (decode + EBU R128 / Tech 3342 pass),
+/// weighted by the layout the file declares. Values are the file's own,
+/// without any pending list gain.
+pub fn loudness_report_for_path(path: &Path) -> anyhow::Result<crate::wave::LoudnessReport> {
+    let (chans, sr) = crate::audio_io::decode_audio_multi(path)?;
+    let layout = LoudnessLayout::resolve(
+        chans.len(),
+        crate::audio_io::read_channel_layout(path),
+        None,
+    );
+    crate::wave::loudness_report_for_layout(&chans, sr, layout.layout)
+}
+
+pub fn loudness_report_json(path: &str, report: &crate::wave::LoudnessReport) -> serde_json::Value {
+    let metrics = &report.metrics;
+    serde_json::json!({
+        "path": path,
+        "integrated_lufs": metrics.lufs_i,
+        "max_short_term_lufs": metrics.lufs_s_max,
+        "max_momentary_lufs": metrics.lufs_m_max,
+        "loudness_range_lu": metrics.lra_lu,
+        "lra_low_lufs": report.lra_low_lufs,
+        "lra_high_lufs": report.lra_high_lufs,
+        "true_peak_dbtp": metrics.true_peak_db,
+        "short_term_blocks": report.short_term_blocks,
+        "short_term_histogram": report
+            .short_term_histogram
+            .iter()
+            .map(|(lufs, count)| serde_json::json!({ "lufs": lufs, "count": count }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Write per-file loudness reports as JSON / CSV / text depending on the
+/// extension. CSV carries the histogram in one cell as `lufs:count;...`;
+/// text draws it as a bar chart.
+pub fn write_loudness_report(
+    path: &Path,
+    reports: &[(String, crate::wave::LoudnessReport)],
+) -> anyhow::Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+    let out = match ext.as_str() {
+        "json" => {
+            let body = serde_json::json!({
+                "histogram_bin_lu": crate::wave::LOUDNESS_HISTOGRAM_BIN_LU,
+                "files": reports
+                    .iter()
+                    .map(|(file, report)| loudness_report_json(file, report))
+                    .collect::<Vec<_>>(),
+            });
+            serde_json::to_string_pretty(&body)?
+        }
+        "csv" => {
+            let mut out = String::from(
+                "path,integrated_lufs,max_short_term_lufs,max_momentary_lufs,loudness_range_lu,\
+                 lra_low_lufs,lra_high_lufs,true_peak_dbtp,short_term_blocks,short_term_histogram\n",
+            );
+            for (file, report) in reports {
+                let metrics = &report.metrics;
+                let histogram = report
+                    .short_term_histogram
+                    .iter()
+                    .map(|(lufs, count)| format!("{lufs:.0}:{count}"))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                out.push_str(&format!(
+                    "\"{}\",{},{},{},{},{},{},{},{},{}\n",
+                    file.replace('"', "\"\""),
+                    fmt_opt(metrics.lufs_i),
+                    fmt_opt(metrics.lufs_s_max),
+                    fmt_opt(metrics.lufs_m_max),
+                    fmt_opt(metrics.lra_lu),
+                    fmt_opt(report.lra_low_lufs),
+                    fmt_opt(report.lra_high_lufs),
+                    fmt_opt(metrics.true_peak_db),
+                    report.short_term_blocks,
+                    histogram,
+                ));
+            }
+            out
+        }
+        _ => {
+            const BAR_WIDTH: u32 = 40;
+            let db = |v: Option<f32>, unit: &str| {
+                v.map(|v| format!("{v:+.1} {unit}"))
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            let mut out = String::new();
+            for (file, report) in reports {
+                let metrics = &report.metrics;
+                out.push_str(&format!("{file}\n"));
+                out.push_str(&format!(
+                    "  Integrated       {}\n",
+                    db(metrics.lufs_i, "LUFS")
+                ));
+                out.push_str(&format!(
+                    "  Max short-term   {}\n",
+                    db(metrics.lufs_s_max, "LUFS")
+                ));
+                out.push_str(&format!(
+                    "  Max momentary    {}\n",
+                    db(metrics.lufs_m_max, "LUFS")
+                ));
+                match (metrics.lra_lu, report.lra_low_lufs, report.lra_high_lufs) {
+                    (Some(lra), Some(low), Some(high)) => out.push_str(&format!(
+                        "  Loudness range   {lra:.1} LU ({low:+.1} to {high:+.1} LUFS)\n"
+                    )),
+                    _ => out.push_str("  Loudness range   - (shorter than 3 s)\n"),
+                }
+                out.push_str(&format!(
+                    "  True peak        {}\n",
+                    db(metrics.true_peak_db, "dBTP")
+                ));
+                out.push_str(&format!(
+                    "  Short-term histogram ({:.0} LU bins, {} blocks):\n",
+                    crate::wave::LOUDNESS_HISTOGRAM_BIN_LU,
+                    report.short_term_blocks
+                ));
+                let peak = report
+                    .short_term_histogram
+                    .iter()
+                    .map(|(_, count)| *count)
+                    .max()
+                    .unwrap_or(0)
+                    .max(1);
+                for (lufs, count) in &report.short_term_histogram {
+                    let bar = (count * BAR_WIDTH).div_ceil(peak) as usize;
+                    out.push_str(&format!(
+                        "    {lufs:>4.0} LUFS | {:<width$} {count}\n",
+                        "#".repeat(bar),
+                        width = BAR_WIDTH as usize
+                    ));
+                }
+                out.push('\n');
+            }
+            out
+        }
+    };
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -746,6 +983,33 @@ mod tests {
             .any(|i| i.kind == InspectionIssueKind::LoopMissing));
     }
 
+    #[test]
+    fn loudness_range_and_short_term_thresholds() {
+        let mut cfg = InspectionConfig::default();
+        // Both checks are opt-in.
+        assert!(check_loudness_range_issues(&cfg, Some(40.0), Some(0.0)).is_empty());
+        cfg.check_loudness_range = true;
+        cfg.check_short_term = true;
+        let at_limit =
+            check_loudness_range_issues(&cfg, Some(cfg.max_lra_lu), Some(cfg.max_short_term_lufs));
+        assert!(at_limit.is_empty(), "{at_limit:?}");
+        let over = check_loudness_range_issues(
+            &cfg,
+            Some(cfg.max_lra_lu + 0.1),
+            Some(cfg.max_short_term_lufs + 0.1),
+        );
+        let kinds: Vec<_> = over.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                InspectionIssueKind::LoudnessRangeOver,
+                InspectionIssueKind::ShortTermOver
+            ]
+        );
+        // Too short for an LRA: nothing to flag.
+        assert!(check_loudness_range_issues(&cfg, None, None).is_empty());
+    }
+
     #[test]
     fn naming_rule_matches_violates_and_reports_bad_pattern() {
         let mut cfg = InspectionConfig::default();
@@ -87,7 +87,7 @@ impl super::WavesPreviewer {
     }
 
     pub fn test_audio_is_streaming_wav(&self, path: &Path) -> bool {
-        self.audio.is_streaming_wav_path(path)
+        self.audio.is_streaming_path(path)
     }
 
     pub fn test_set_auto_play_list_nav(&mut self, enabled: bool) {
@@ -1122,6 +1122,7 @@ impl super::WavesPreviewer {
             SortKey::TruePeak => "TruePeak",
             SortKey::LufsShort => "LufsShort",
             SortKey::LufsMomentary => "LufsMomentary",
+            SortKey::LoudnessRange => "LoudnessRange",
             SortKey::SilenceLead => "SilenceLead",
             SortKey::SilenceTail => "SilenceTail",
             SortKey::EdgeZero => "EdgeZero",
@@ -2484,7 +2485,9 @@ impl super::WavesPreviewer {
             lufs_i: None,
             lufs_m_max: None,
             lufs_s_max: None,
+            lra_lu: None,
             true_peak_db: None,
+            loudness_layout: None,
             bpm: None,
             silence_lead_ms: None,
             silence_tail_ms: None,
@@ -42,7 +42,7 @@ impl super::WavesPreviewer {
             .unwrap_or_default();
         // List preview prioritizes continuity over maximum SRC fidelity.
         match ext.as_str() {
-            "mp3" | "m4a" | "ogg" => SrcQuality::Fast,
+            "mp3" | "m4a" | "ogg" | "opus" => SrcQuality::Fast,
             _ => match self.src_quality {
                 SrcQuality::Best => SrcQuality::Good,
                 q => q,
@@ -566,6 +566,7 @@ impl WavesPreviewer {
             SortKey::TruePeak => cols.dbtp,
             SortKey::LufsShort => cols.lufs_s,
             SortKey::LufsMomentary => cols.lufs_m,
+            SortKey::LoudnessRange => cols.lra,
             SortKey::Bpm => cols.bpm,
             SortKey::SilenceLead => cols.silence_lead,
             SortKey::SilenceTail => cols.silence_tail,
@@ -613,6 +614,8 @@ impl WavesPreviewer {
             SortKey::LufsShort
         } else if cols.lufs_m {
             SortKey::LufsMomentary
+        } else if cols.lra {
+            SortKey::LoudnessRange
         } else if cols.bpm {
             SortKey::Bpm
         } else if cols.created_at {
@@ -229,7 +229,7 @@ impl crate::app::WavesPreviewer {
         self.refresh_filter_then_sort();
     }
 
-    fn push_list_undo_action(&mut self, action: ListUndoAction) {
+    pub(super) fn push_list_undo_action(&mut self, action: ListUndoAction) {
         self.list_redo_stack.clear();
         self.list_undo_stack.push(action);
         while self.list_undo_stack.len() > 20 {
@@ -287,6 +287,31 @@ impl crate::app::WavesPreviewer {
                     self.restore_list_selection_snapshot(&action.after);
                 }
             }
+            ListUndoActionKind::MetadataWrite { entries } => {
+                self.apply_metadata_write_entries(entries, undo);
+                if undo {
+                    self.restore_list_selection_snapshot(&action.before);
+                } else {
+                    self.restore_list_selection_snapshot(&action.after);
+                }
+            }
+            ListUndoActionKind::TagWrite { entries } => {
+                self.apply_tag_write_entries(entries, undo);
+                if undo {
+                    self.restore_list_selection_snapshot(&action.before);
+                } else {
+                    self.restore_list_selection_snapshot(&action.after);
+                }
+            }
+            ListUndoActionKind::TemplateWrite { chunks, tags } => {
+                self.apply_metadata_write_entries(chunks, undo);
+                self.apply_tag_write_entries(tags, undo);
+                if undo {
+                    self.restore_list_selection_snapshot(&action.before);
+                } else {
+                    self.restore_list_selection_snapshot(&action.after);
+                }
+            }
         }
     }
 
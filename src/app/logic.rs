@@ -85,11 +85,11 @@ impl super::WavesPreviewer {
             return false;
         };
         self.audio.stop();
-        match self.audio.set_streaming_wav_path(&stream_path) {
+        match self.audio.set_streaming_path(&stream_path) {
             Ok(()) => {
                 let source_sr = self
                     .audio
-                    .streaming_wav_sample_rate()
+                    .streaming_sample_rate()
                     .or_else(|| self.cached_source_sample_rate_for_path(path))
                     .unwrap_or(self.audio.shared.out_sample_rate.max(1));
                 self.playing_path = Some(path.to_path_buf());
@@ -125,7 +125,7 @@ impl super::WavesPreviewer {
             .map(|s| s.to_ascii_lowercase())
             .unwrap_or_default();
         let base = match ext.as_str() {
-            "mp3" | "m4a" | "ogg" => LIST_PLAY_PREFIX_SECS_COMPRESSED_BASE,
+            "mp3" | "m4a" | "ogg" | "opus" => LIST_PLAY_PREFIX_SECS_COMPRESSED_BASE,
             _ => LIST_PLAY_PREFIX_SECS_BASE,
         };
         if let Some(dur) = self
@@ -148,7 +148,7 @@ impl super::WavesPreviewer {
         let Some(tab) = self.tabs.get(tab_idx) else {
             return false;
         };
-        (self.editor_stream_transport_eligible(tab) && self.audio.is_streaming_wav_path(&tab.path))
+        (self.editor_stream_transport_eligible(tab) && self.audio.is_streaming_path(&tab.path))
             || (!tab.loading && !tab.ch_samples.is_empty())
     }
 
@@ -189,7 +189,7 @@ impl super::WavesPreviewer {
             .and_then(|s| s.to_str())
             .map(|s| s.to_ascii_lowercase())
             .unwrap_or_default();
-        if ext != "wav" {
+        if ext != "wav" && ext != "flac" {
             return false;
         }
         if self.has_pending_gain(path) {
@@ -244,10 +244,10 @@ impl super::WavesPreviewer {
             return false;
         };
         let target = ProcessingTarget::EditorTab(tab_path.clone());
-        if self.audio.is_streaming_wav_path(&stream_path) {
+        if self.audio.is_streaming_path(&stream_path) {
             let source_sr = self
                 .audio
-                .streaming_wav_sample_rate()
+                .streaming_sample_rate()
                 .or_else(|| self.cached_source_sample_rate_for_path(&tab_path))
                 .unwrap_or(self.audio.shared.out_sample_rate.max(1));
             self.invalidate_processing_for_target(&target, "editor exact stream retained");
@@ -262,11 +262,11 @@ impl super::WavesPreviewer {
             self.apply_effective_volume();
             return true;
         }
-        match self.audio.set_streaming_wav_path(&stream_path) {
+        match self.audio.set_streaming_path(&stream_path) {
             Ok(()) => {
                 let source_sr = self
                     .audio
-                    .streaming_wav_sample_rate()
+                    .streaming_sample_rate()
                     .or_else(|| self.cached_source_sample_rate_for_path(&tab_path))
                     .unwrap_or(self.audio.shared.out_sample_rate.max(1));
                 self.invalidate_processing_for_target(&target, "editor exact stream activated");
@@ -375,7 +375,7 @@ impl super::WavesPreviewer {
                     ));
                 }
                 if self.mode == RateMode::Speed
-                    && self.audio.is_streaming_wav_path(path)
+                    && self.audio.is_streaming_path(path)
                     && self.editor_stream_transport_eligible(tab)
                 {
                     return Some("editor exact stream active".to_string());
@@ -395,7 +395,7 @@ impl super::WavesPreviewer {
                         path.display()
                     ));
                 }
-                if self.audio.is_streaming_wav_path(path)
+                if self.audio.is_streaming_path(path)
                     && self.exact_stream_path_eligible_cached(path)
                 {
                     return Some("list exact stream active".to_string());
@@ -802,7 +802,11 @@ impl super::WavesPreviewer {
 
     pub(super) fn editor_decode_strategy(path: &Path) -> EditorDecodeStrategy {
         match path.extension().and_then(|s| s.to_str()) {
-            Some(ext) if ext.eq_ignore_ascii_case("mp3") || ext.eq_ignore_ascii_case("ogg") => {
+            Some(ext)
+                if ext.eq_ignore_ascii_case("mp3")
+                    || ext.eq_ignore_ascii_case("ogg")
+                    || ext.eq_ignore_ascii_case("opus") =>
+            {
                 EditorDecodeStrategy::CompressedProgressiveFull
             }
             _ => EditorDecodeStrategy::StreamingOverviewFinalAudio,
@@ -1188,6 +1192,8 @@ impl super::WavesPreviewer {
                     plugin_fx_draft: tab.plugin_fx_draft.clone(),
                     plugin_fx_chain: tab.plugin_fx_chain.clone(),
                     show_waveform_overlay: tab.show_waveform_overlay,
+                    channel_layout: tab.channel_layout,
+                    adm_lanes: tab.adm_lanes.clone(),
                     applied_effect_graph: None,
                 },
             )
@@ -1532,6 +1538,7 @@ impl super::WavesPreviewer {
                     | SortKey::TruePeak
                     | SortKey::LufsShort
                     | SortKey::LufsMomentary
+                    | SortKey::LoudnessRange
                     | SortKey::SilenceLead
                     | SortKey::SilenceTail
                     | SortKey::EdgeZero
@@ -2509,8 +2516,10 @@ impl super::WavesPreviewer {
     pub(super) fn apply_filter_from_search(&mut self) {
         // Preserve selection index if possible
         let selected_idx = self.selected.and_then(|i| self.files.get(i).copied());
+        self.sync_metadata_fulltext_search();
         let query = self.search_query.trim().to_string();
-        // Search spans display name, folder, transcript, meta summary, and external fields.
+        // Search spans display name, folder, transcript, meta summary, external
+        // fields and metadata full-text (index hits plus in-memory summaries).
         if query.is_empty() {
             self.files = self.items.iter().map(|item| item.id).collect();
         } else {
@@ -74,6 +74,14 @@ impl WavesPreviewer {
                             .speed(0.1)
                             .suffix(" LUFS"),
                     );
+                    ui.label("\u{00B1}");
+                    ui.add(
+                        egui::DragValue::new(&mut self.loudnorm_dialog_tolerance)
+                            .range(0.0..=6.0)
+                            .speed(0.1)
+                            .suffix(" LU"),
+                    )
+                    .on_hover_text("Tolerance band drawn around the target in the editor Loudness view");
                 });
                 ui.label(
                     egui::RichText::new(
@@ -11,6 +11,26 @@ impl super::WavesPreviewer {
         self.lufs_recalc_deadline.insert(path, dl);
     }
 
+    /// Change the loudness layout override. List rows re-measure lazily;
+    /// gain-adjusted values were measured under the old layout, so they are
+    /// recalculated.
+    pub(super) fn set_loudness_layout_override(
+        &mut self,
+        layout: Option<crate::channel_layout::ChannelLayout>,
+    ) {
+        if self.loudness_layout_override == layout {
+            return;
+        }
+        self.loudness_layout_override = layout;
+        self.push_loudness_layout_to_meta_pool();
+        self.push_meter_layout_to_audio();
+        let paths: Vec<PathBuf> = self.lufs_override.keys().cloned().collect();
+        for path in paths {
+            self.schedule_lufs_for_path(path);
+        }
+        self.save_prefs();
+    }
+
     pub(super) fn drain_lufs_recalc_results(&mut self) {
         let Some(rx) = self.lufs_rx2.take() else {
             return;
@@ -57,6 +77,7 @@ impl super::WavesPreviewer {
         let (tx, rx) = mpsc::channel();
         self.lufs_rx2 = Some(rx);
         self.lufs_worker_busy = true;
+        let layout_override = self.loudness_layout_override;
         std::thread::spawn(move || {
             crate::app::threading::lower_current_thread_priority();
             let started = std::time::Instant::now();
@@ -69,7 +90,12 @@ impl super::WavesPreviewer {
                         *v *= gain;
                     }
                 }
-                crate::wave::lufs_integrated_from_multi(&chans, sr)
+                let layout = crate::app::types::LoudnessLayout::resolve(
+                    chans.len(),
+                    crate::audio_io::read_channel_layout(&path),
+                    layout_override,
+                );
+                crate::wave::lufs_integrated_for_layout(&chans, sr, layout.layout)
             })();
             let val = match res {
                 Ok(v) => v,
@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 
 use super::transcript;
-use super::types::{FileMeta, SampleValueKind, Transcript};
+use super::types::{FileMeta, LoudnessLayout, SampleValueKind, Transcript};
 use crate::audio_io;
+use crate::channel_layout::ChannelLayout;
 
 fn map_sample_value_kind(kind: audio_io::SampleValueKind) -> SampleValueKind {
     match kind {
@@ -154,6 +155,9 @@ struct MetaQueue {
     /// `MetaTask` on purpose: the queue dedupes tasks by path, so a payload
     /// carrying the threshold would silently keep whichever copy landed first.
     blank_threshold_bits: AtomicU32,
+    /// Loudness layout override packed by [`ChannelLayout::pack`]; kept out
+    /// of `MetaTask` for the same reason as the threshold.
+    loudness_layout_bits: AtomicU64,
 }
 
 pub struct MetaPool {
@@ -218,6 +222,14 @@ impl MetaPool {
             .blank_threshold_bits
             .store(dbfs.to_bits(), Ordering::Relaxed);
     }
+
+    /// Layout override future full decodes weight loudness by. Rows measured
+    /// under another override detect it via [`LoudnessLayout::is_stale`].
+    pub fn set_loudness_layout_override(&self, layout: Option<ChannelLayout>) {
+        self.shared
+            .loudness_layout_bits
+            .store(ChannelLayout::pack(layout), Ordering::Relaxed);
+    }
 }
 
 impl Drop for MetaPool {
@@ -268,7 +280,9 @@ fn header_meta(path: &PathBuf) -> Result<FileMeta, FileMeta> {
                 lufs_i: None,
                 lufs_m_max: None,
                 lufs_s_max: None,
+                lra_lu: None,
                 true_peak_db: None,
+                loudness_layout: None,
                 bpm: audio_io::read_audio_bpm(path),
                 silence_lead_ms: None,
                 silence_tail_ms: None,
@@ -297,7 +311,9 @@ fn header_meta(path: &PathBuf) -> Result<FileMeta, FileMeta> {
             lufs_i: None,
             lufs_m_max: None,
             lufs_s_max: None,
+            lra_lu: None,
             true_peak_db: None,
+            loudness_layout: None,
             bpm: None,
             silence_lead_ms: None,
             silence_tail_ms: None,
@@ -314,7 +330,11 @@ fn header_meta(path: &PathBuf) -> Result<FileMeta, FileMeta> {
     }
 }
 
-fn decode_full_meta(path: &PathBuf, blank_threshold_dbfs: f32) -> Option<FileMeta> {
+fn decode_full_meta(
+    path: &PathBuf,
+    blank_threshold_dbfs: f32,
+    loudness_layout_override: Option<ChannelLayout>,
+) -> Option<FileMeta> {
     let info = audio_io::read_audio_info(path).ok();
     if let Ok((chans, sr, decode_errors)) = audio_io::decode_audio_multi_with_errors(path) {
         // Mono mixdown for RMS/thumbnail
@@ -370,7 +390,13 @@ fn decode_full_meta(path: &PathBuf, blank_threshold_dbfs: f32) -> Option<FileMet
         };
         let mut thumb = Vec::new();
         crate::wave::build_minmax(&mut thumb, &mono, 128);
-        let loudness = crate::wave::loudness_metrics_from_multi(&chans, sr).ok();
+        let loudness_layout = LoudnessLayout::resolve(
+            chans.len(),
+            info.as_ref().map(|info| info.channel_layout),
+            loudness_layout_override,
+        );
+        let loudness =
+            crate::wave::loudness_metrics_for_layout(&chans, sr, loudness_layout.layout).ok();
         let lufs_i = loudness.map(|l| l.lufs_i);
         // Same threshold the batch inspection defaults to; two linear scans
         // over already-decoded channels, so it's computed unconditionally.
@@ -428,7 +454,9 @@ fn decode_full_meta(path: &PathBuf, blank_threshold_dbfs: f32) -> Option<FileMet
             lufs_i,
             lufs_m_max: loudness.and_then(|l| l.lufs_m_max),
             lufs_s_max: loudness.and_then(|l| l.lufs_s_max),
+            lra_lu: loudness.and_then(|l| l.lra_lu),
             true_peak_db: loudness.and_then(|l| l.true_peak_db),
+            loudness_layout: loudness.map(|_| loudness_layout),
             bpm,
             silence_lead_ms: Some(silence_lead_ms),
             silence_tail_ms: Some(silence_tail_ms),
@@ -503,7 +531,9 @@ fn decode_full_meta(path: &PathBuf, blank_threshold_dbfs: f32) -> Option<FileMet
             lufs_i: None,
             lufs_m_max: None,
             lufs_s_max: None,
+            lra_lu: None,
             true_peak_db: None,
+            loudness_layout: None,
             bpm,
             silence_lead_ms: None,
             silence_tail_ms: None,
@@ -542,6 +572,7 @@ pub fn spawn_meta_pool(workers: usize) -> (MetaPool, std::sync::mpsc::Receiver<M
         blank_threshold_bits: AtomicU32::new(
             crate::app::inspection::DEFAULT_BLANK_THRESHOLD_DBFS.to_bits(),
         ),
+        loudness_layout_bits: AtomicU64::new(0),
     });
     let worker_count = workers.max(1);
     for _ in 0..worker_count {
@@ -596,7 +627,15 @@ pub fn spawn_meta_pool(workers: usize) -> (MetaPool, std::sync::mpsc::Receiver<M
                 // BlankPadScan is exactly the one the scan used.
                 let blank_threshold =
                     f32::from_bits(shared.blank_threshold_bits.load(Ordering::Relaxed));
-                run_meta_task(task, &cancel, &tx, blank_threshold);
+                let loudness_layout_override =
+                    ChannelLayout::unpack(shared.loudness_layout_bits.load(Ordering::Relaxed));
+                run_meta_task(
+                    task,
+                    &cancel,
+                    &tx,
+                    blank_threshold,
+                    loudness_layout_override,
+                );
                 let mut guard = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
                 guard.running.remove(&task_path_owned);
             }
@@ -610,6 +649,7 @@ fn run_meta_task(
     cancel: &AtomicBool,
     tx: &std::sync::mpsc::Sender<MetaUpdate>,
     blank_threshold_dbfs: f32,
+    loudness_layout_override: Option<ChannelLayout>,
 ) {
     let (p, do_header, do_decode) = match task {
         MetaTask::Header(path) => (path, true, true),
@@ -658,7 +698,7 @@ fn run_meta_task(
             return;
         }
         // Stage 2: decode and compute RMS/thumbnail/LUFS(I)
-        if let Some(full) = decode_full_meta(&p, blank_threshold_dbfs) {
+        if let Some(full) = decode_full_meta(&p, blank_threshold_dbfs, loudness_layout_override) {
             let _ = tx.send(MetaUpdate::Full(p.clone(), full));
         } else if let Some(mut header_meta) = header_meta_opt {
             header_meta.decode_error = Some("Decode failed".to_string());
@@ -667,7 +707,9 @@ fn run_meta_task(
             header_meta.lufs_i = None;
             header_meta.lufs_m_max = None;
             header_meta.lufs_s_max = None;
+            header_meta.lra_lu = None;
             header_meta.true_peak_db = None;
+            header_meta.loudness_layout = None;
             header_meta.edge_abs = None;
             header_meta.blank_pad = None;
             header_meta.thumb.clear();
@@ -29,6 +29,7 @@ impl super::WavesPreviewer {
                 | crate::app::types::SortKey::TruePeak
                 | crate::app::types::SortKey::LufsShort
                 | crate::app::types::SortKey::LufsMomentary
+                | crate::app::types::SortKey::LoudnessRange
                 | crate::app::types::SortKey::SilenceLead
                 | crate::app::types::SortKey::SilenceTail
                 | crate::app::types::SortKey::EdgeZero
@@ -58,6 +59,7 @@ impl super::WavesPreviewer {
         // Only pool construction site, so a pool recreated mid-session keeps
         // measuring Blank Pad at the user's threshold rather than the default.
         pool.set_blank_threshold_dbfs(self.blank_threshold_dbfs);
+        pool.set_loudness_layout_override(self.loudness_layout_override);
         self.meta_pool = Some(pool);
         self.meta_rx = Some(rx);
         self.meta_inflight.clear();
@@ -91,6 +93,14 @@ impl super::WavesPreviewer {
         }
     }
 
+    /// Publish the loudness layout override. As with the Blank Pad threshold,
+    /// rows measured under another override re-queue themselves.
+    pub(super) fn push_loudness_layout_to_meta_pool(&mut self) {
+        if let Some(pool) = self.meta_pool.as_ref() {
+            pool.set_loudness_layout_override(self.loudness_layout_override);
+        }
+    }
+
     pub(super) fn ensure_meta_pool(&mut self) {
         if self.meta_pool.is_none() {
             self.reset_meta_pool();
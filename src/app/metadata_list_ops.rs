@@ -1,8 +1,11 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 
 use crate::app::sort_filter_jobs::OwnedKey;
 use crate::app::types::{ColumnKey, MetadataListColumn};
-use crate::metadata::cache::{MetadataColumnDefinition, SummaryPriority};
+use crate::metadata::cache::{FulltextSearchResult, MetadataColumnDefinition, SummaryPriority};
+use crate::metadata::fulltext::FulltextQuery;
 use crate::metadata::{Completion, MetadataSummary, MetadataValue, ScanBudget, SummaryRequest};
 
 use super::WavesPreviewer;
@@ -15,6 +18,20 @@ pub(super) struct MetadataCell {
     pub conflict: bool,
 }
 
+/// Full-text state for the current (non-regex) search box query. Index hits
+/// cover files summarized in earlier sessions; summaries in memory are
+/// matched directly.
+pub(super) struct MetadataFulltextSearch {
+    query: String,
+    parsed: FulltextQuery,
+    hits: HashSet<PathBuf>,
+    pending: Option<mpsc::Receiver<FulltextSearchResult>>,
+    /// Rows still to visit with an all-fields summary request so the index
+    /// covers the whole list.
+    prefetch_remaining: usize,
+    requery: bool,
+}
+
 impl WavesPreviewer {
     fn metadata_column_registry_path() -> Option<PathBuf> {
         let base = std::env::var_os("APPDATA").or_else(|| std::env::var_os("LOCALAPPDATA"))?;
@@ -117,6 +134,10 @@ impl WavesPreviewer {
 
     fn metadata_summary_request(&self) -> Option<SummaryRequest> {
         let mut request = SummaryRequest::default();
+        let fulltext = self
+            .metadata_fulltext
+            .as_ref()
+            .is_some_and(|search| search.prefetch_remaining > 0);
         for (_, column) in self.visible_metadata_columns() {
             match &column.key {
                 ColumnKey::Normalized(key) => {
@@ -128,6 +149,11 @@ impl WavesPreviewer {
                 ColumnKey::Builtin(_) => {}
             }
         }
+        if fulltext {
+            // An empty field list asks for every normalized field.
+            request.fields.clear();
+            return Some(request);
+        }
         (!request.fields.is_empty() || request.include_raw).then_some(request)
     }
 
@@ -175,6 +201,25 @@ impl WavesPreviewer {
         }
     }
 
+    /// Forget cached summaries and inspector documents for a file whose
+    /// metadata was rewritten in place, so list cells and open inspectors
+    /// rescan it.
+    pub(super) fn invalidate_metadata_for_path(&mut self, path: &Path) {
+        self.metadata_summary_cache.remove(path);
+        self.metadata_summary_inflight.remove(path);
+        self.metadata_summary_errors.remove(path);
+        for tab_idx in 0..self.tabs.len() {
+            if self.tabs[tab_idx].path == path {
+                let tab = &mut self.tabs[tab_idx];
+                if let Some(document) = tab.metadata_document.clone() {
+                    tab.metadata_presave = Some(document);
+                }
+                self.reset_metadata_tab(tab_idx);
+            }
+        }
+        self.queue_metadata_summary_for_path(path, SummaryPriority::Selected);
+    }
+
     pub(super) fn pump_metadata_summary_prefetch(&mut self) {
         if !self.is_list_workspace_active()
             || self.scan_in_progress
@@ -200,6 +245,12 @@ impl WavesPreviewer {
             .collect::<Vec<_>>();
         self.metadata_summary_prefetch_cursor =
             (self.metadata_summary_prefetch_cursor + count) % self.items.len();
+        if let Some(search) = self.metadata_fulltext.as_mut() {
+            if search.prefetch_remaining > 0 {
+                search.prefetch_remaining = search.prefetch_remaining.saturating_sub(count);
+                search.requery = search.prefetch_remaining == 0;
+            }
+        }
         for path in paths {
             if self.metadata_summary_inflight.len() >= 512 {
                 break;
@@ -351,15 +402,92 @@ impl WavesPreviewer {
         }
     }
 
+    /// Start (or keep) the full-text search for the current search box text.
+    /// Called before every filter pass; only a changed query hits the index.
+    pub(super) fn sync_metadata_fulltext_search(&mut self) {
+        let query = self.search_query.trim();
+        if query.is_empty() || self.search_use_regex {
+            self.metadata_fulltext = None;
+            return;
+        }
+        if self
+            .metadata_fulltext
+            .as_ref()
+            .is_some_and(|search| search.query == query)
+        {
+            return;
+        }
+        let parsed = FulltextQuery::parse(query);
+        if parsed.is_empty() {
+            self.metadata_fulltext = None;
+            return;
+        }
+        let pending = self
+            .metadata_summary_pool
+            .as_ref()
+            .map(|pool| pool.search(parsed.clone()));
+        self.metadata_fulltext = Some(MetadataFulltextSearch {
+            query: query.to_string(),
+            parsed,
+            hits: HashSet::new(),
+            pending,
+            prefetch_remaining: self.items.len(),
+            requery: false,
+        });
+    }
+
+    pub(super) fn poll_metadata_fulltext_search(&mut self, ctx: &egui::Context) {
+        let inflight_empty = self.metadata_summary_inflight.is_empty();
+        let Some(search) = self.metadata_fulltext.as_mut() else {
+            return;
+        };
+        if search.requery && inflight_empty && search.pending.is_none() {
+            // The prefetch pass indexed the rest of the list; pick up files
+            // that were summarized but already evicted from memory.
+            search.requery = false;
+            search.pending = self
+                .metadata_summary_pool
+                .as_ref()
+                .map(|pool| pool.search(search.parsed.clone()));
+        }
+        let Some(rx) = search.pending.as_ref() else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(paths)) => {
+                search.pending = None;
+                search.hits = paths.into_iter().collect();
+                self.schedule_search_refresh();
+                ctx.request_repaint();
+            }
+            Ok(Err(error)) => {
+                search.pending = None;
+                self.debug_log(format!("metadata full-text search failed: {error}"));
+            }
+            Err(mpsc::TryRecvError::Empty) => ctx.request_repaint(),
+            Err(mpsc::TryRecvError::Disconnected) => search.pending = None,
+        }
+    }
+
     pub(super) fn metadata_search_matches(
         &self,
         path: &Path,
         query_lower: &str,
         regex: Option<&regex::Regex>,
     ) -> bool {
+        let fulltext = self.metadata_fulltext.as_ref().filter(|_| regex.is_none());
+        if fulltext.is_some_and(|search| search.hits.contains(path)) {
+            return true;
+        }
         let Some(summary) = self.metadata_summary_cache.peek(path) else {
             return false;
         };
+        if let Some(search) = fulltext {
+            let document = self.metadata_summary_cache.document(path);
+            if document.is_some_and(|document| search.parsed.matches(document)) {
+                return true;
+            }
+        }
         let values = summary
             .fields
             .iter()
@@ -379,9 +507,10 @@ impl WavesPreviewer {
 
 fn summary_satisfies(summary: &MetadataSummary, request: &SummaryRequest) -> bool {
     let all = summary.coverage.iter().any(|key| key == "__all__");
-    (all || request
-        .fields
-        .iter()
-        .all(|field| summary.coverage.contains(field)))
+    (all || !request.fields.is_empty()
+        && request
+            .fields
+            .iter()
+            .all(|field| summary.coverage.contains(field)))
         && (!request.include_raw || summary.coverage.iter().any(|key| key == "__raw__"))
 }
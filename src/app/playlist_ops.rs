@@ -0,0 +1,596 @@
+//! Gapless list playlist: rows play back to back from offline-rendered
+//! buffers. A worker decodes the next row (SR-aligned, per-file gain and
+//! the crossfade with the previous row baked in) while the current one
+//! plays; the engine swaps the queued buffer in at the exact end of the
+//! current one. Like the variation audition, an explicit stop (Space,
+//! another source, Cancel) ends the playlist.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::app::types::{MediaSource, PlaylistPlaybackState, PlaylistSegmentResult, ToastSeverity};
+use crate::audio::AudioBuffer;
+
+impl crate::app::WavesPreviewer {
+    pub(crate) const PLAYLIST_MAX_CROSSFADE_MS: u32 = 10_000;
+
+    /// Play the selected rows (two or more) in list order.
+    pub(super) fn start_playlist_from_selection(&mut self) {
+        let paths = self.selected_paths();
+        if paths.len() < 2 {
+            self.push_toast(
+                ToastSeverity::Warning,
+                "Playlist: select two or more files first",
+            );
+            return;
+        }
+        self.start_playlist(paths);
+    }
+
+    /// Play from the selected row (or the top) to the end of the list.
+    pub(super) fn start_playlist_from_here(&mut self) {
+        let first = self.selected.unwrap_or(0);
+        let paths: Vec<PathBuf> = (first..self.files.len())
+            .filter_map(|row| self.path_for_row(row).cloned())
+            .collect();
+        self.start_playlist(paths);
+    }
+
+    fn start_playlist(&mut self, paths: Vec<PathBuf>) {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|p| {
+                self.item_for_path(p)
+                    .map(|item| item.source != MediaSource::External)
+                    .unwrap_or(true)
+            })
+            .collect();
+        if paths.is_empty() {
+            self.push_toast(ToastSeverity::Warning, "Playlist: no playable rows");
+            return;
+        }
+        self.cancel_variation_audition();
+        self.cancel_playlist();
+        self.audio.stop();
+        let mut rng = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(1)
+            .max(1);
+        let order = if self.playlist_options.shuffle {
+            Self::playlist_shuffled_order(paths.len(), &mut rng, None)
+        } else {
+            (0..paths.len()).collect()
+        };
+        let first = order[0];
+        let count = paths.len();
+        self.playlist_playback = Some(PlaylistPlaybackState {
+            paths,
+            order,
+            render_cursor: 0,
+            render_rx: None,
+            carry_tail: Vec::new(),
+            current: None,
+            queued: None,
+            handoffs_seen: self.audio.queued_handoff_count(),
+            awaiting_segment: true,
+            item_started: false,
+            played: 0,
+            rng,
+        });
+        self.debug_log(format!("playlist: {count} rows"));
+        self.spawn_playlist_render(first);
+    }
+
+    pub(super) fn cancel_playlist(&mut self) {
+        if self.playlist_playback.take().is_some() {
+            self.audio.clear_queued_buffer();
+        }
+    }
+
+    /// Random permutation of `0..len` (Fisher-Yates); `avoid_first` keeps a
+    /// repeat pass from opening with the row that just ended.
+    pub(crate) fn playlist_shuffled_order(
+        len: usize,
+        rng: &mut u64,
+        avoid_first: Option<usize>,
+    ) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..len).collect();
+        for i in (1..len).rev() {
+            let j = (Self::variation_rng_next(rng) as usize) % (i + 1);
+            order.swap(i, j);
+        }
+        if len >= 2 && avoid_first == Some(order[0]) {
+            order.swap(0, len - 1);
+        }
+        order
+    }
+
+    /// Build one playlist segment: the carried `tail` of the previous row
+    /// crossfades (equal power) into the head of `body`, and up to
+    /// `xfade_out` frames at the end of `body` are withheld as the tail for
+    /// the next segment. Back-to-back segments therefore add up to the rows'
+    /// total length minus the overlaps, with no gap. An empty body passes
+    /// the tail through unfaded (nothing left to fade into).
+    pub(crate) fn playlist_compose_segment(
+        tail: Vec<Vec<f32>>,
+        body: Vec<Vec<f32>>,
+        xfade_out: usize,
+    ) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+        let body_len = body.first().map_or(0, |c| c.len());
+        if body_len == 0 {
+            return (tail, Vec::new());
+        }
+        let overlap = tail.first().map_or(0, |c| c.len());
+        let withhold = xfade_out.min(body_len.saturating_sub(overlap));
+        let seg_len = overlap.max(body_len - withhold);
+        let out_ch = body.len().max(tail.len());
+        let mut segment = Vec::with_capacity(out_ch);
+        let mut next_tail = Vec::with_capacity(if withhold > 0 { out_ch } else { 0 });
+        for ci in 0..out_ch {
+            let src = &body[ci.min(body.len() - 1)];
+            let mut out = vec![0.0f32; seg_len];
+            if seg_len > overlap {
+                out[overlap..].copy_from_slice(&src[overlap..seg_len]);
+            }
+            if overlap > 0 {
+                let prev = &tail[ci.min(tail.len() - 1)];
+                for (i, o) in out[..overlap].iter_mut().enumerate() {
+                    let t = (i as f32 + 0.5) / overlap as f32 * std::f32::consts::FRAC_PI_2;
+                    let incoming = src.get(i).copied().unwrap_or(0.0);
+                    *o = prev.get(i).copied().unwrap_or(0.0) * t.cos() + incoming * t.sin();
+                }
+            }
+            segment.push(out);
+            if withhold > 0 {
+                next_tail.push(src[body_len - withhold..].to_vec());
+            }
+        }
+        (segment, next_tail)
+    }
+
+    /// Next row to render: walks the order, then starts a new pass when
+    /// repeating (reshuffled when shuffling). `None` at the end of the list.
+    fn playlist_advance_cursor(&mut self) -> Option<usize> {
+        let options = self.playlist_options;
+        let state = self.playlist_playback.as_mut()?;
+        let len = state.paths.len();
+        if state.render_cursor + 1 < state.order.len() {
+            state.render_cursor += 1;
+        } else if options.repeat_all && len > 0 {
+            let last = state.order.last().copied();
+            state.order = if options.shuffle {
+                Self::playlist_shuffled_order(len, &mut state.rng, last)
+            } else {
+                (0..len).collect()
+            };
+            state.render_cursor = 0;
+        } else {
+            return None;
+        }
+        state.order.get(state.render_cursor).copied()
+    }
+
+    /// Decode + render `path_index` on a worker. The row's tail is withheld
+    /// for a crossfade only when another row is going to follow it.
+    fn spawn_playlist_render(&mut self, path_index: usize) {
+        let repeat_all = self.playlist_options.repeat_all;
+        let crossfade_ms = self
+            .playlist_options
+            .crossfade_ms
+            .min(Self::PLAYLIST_MAX_CROSSFADE_MS);
+        let Some(path) = self
+            .playlist_playback
+            .as_ref()
+            .and_then(|s| s.paths.get(path_index).cloned())
+        else {
+            return;
+        };
+        let asset = self
+            .item_for_path(&path)
+            .map(|item| item.audio_asset.clone())
+            .unwrap_or_else(|| crate::audio_asset::AudioAssetDescriptor::external(path.clone()));
+        let gain = super::helpers::db_to_amp(self.pending_gain_db_for_path(&path));
+        let out_sr = self.audio.shared.out_sample_rate.max(1);
+        let Some(state) = self.playlist_playback.as_mut() else {
+            return;
+        };
+        let has_next = state.render_cursor + 1 < state.order.len() || repeat_all;
+        let xfade_out = if has_next {
+            (u64::from(crossfade_ms) * u64::from(out_sr) / 1000) as usize
+        } else {
+            0
+        };
+        let tail = std::mem::take(&mut state.carry_tail);
+        let (tx, rx) = std::sync::mpsc::channel();
+        state.render_rx = Some(rx);
+        std::thread::spawn(move || {
+            let result = match Self::decode_asset_at_rate(&asset, out_sr) {
+                Ok(mut body) => {
+                    if (gain - 1.0).abs() > f32::EPSILON {
+                        for s in body.iter_mut().flatten() {
+                            *s *= gain;
+                        }
+                    }
+                    let (segment, tail) = Self::playlist_compose_segment(tail, body, xfade_out);
+                    PlaylistSegmentResult {
+                        path_index,
+                        segment: Ok(segment),
+                        tail,
+                    }
+                }
+                Err(err) => PlaylistSegmentResult {
+                    path_index,
+                    segment: Err(format!("{}: {err}", path.display())),
+                    tail,
+                },
+            };
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Render the row after the one just rendered; when the list is done,
+    /// flush a tail still owed by a row whose successor never arrived.
+    fn playlist_render_next(&mut self) {
+        if let Some(next) = self.playlist_advance_cursor() {
+            self.spawn_playlist_render(next);
+            return;
+        }
+        let Some(state) = self.playlist_playback.as_mut() else {
+            return;
+        };
+        if state.carry_tail.is_empty() {
+            return;
+        }
+        let tail = std::mem::take(&mut state.carry_tail);
+        let index = state
+            .queued
+            .as_ref()
+            .or(state.current.as_ref())
+            .map_or(0, |(i, _)| *i);
+        self.playlist_accept_segment(index, tail);
+    }
+
+    /// Start a segment from silence: the first row, or the next row after
+    /// the previous segment ran out before it was ready.
+    fn playlist_start_segment(&mut self, path_index: usize, buffer: Arc<AudioBuffer>) {
+        self.audio.stop();
+        self.audio.set_samples_buffer(Arc::clone(&buffer));
+        self.playback_mark_buffer_source(
+            crate::app::PlaybackSourceKind::ToolPreview,
+            self.audio.shared.out_sample_rate.max(1),
+        );
+        self.audio.set_loop_enabled(false);
+        self.audio.seek_to_sample(0);
+        self.audio.play();
+        let handoffs = self.audio.queued_handoff_count();
+        if let Some(state) = self.playlist_playback.as_mut() {
+            state.current = Some((path_index, buffer));
+            state.handoffs_seen = handoffs;
+            state.awaiting_segment = false;
+            state.item_started = false;
+            state.played += 1;
+        }
+        self.playlist_show_row(path_index);
+    }
+
+    /// Play a rendered segment now if the transport is idle, otherwise
+    /// queue it behind the current one (the next render waits for the
+    /// handoff, so at most one segment is ever queued).
+    fn playlist_accept_segment(&mut self, path_index: usize, segment: Vec<Vec<f32>>) {
+        let buffer = Arc::new(AudioBuffer::from_channels(segment));
+        let awaiting = self
+            .playlist_playback
+            .as_ref()
+            .map(|s| s.awaiting_segment)
+            .unwrap_or(false);
+        if awaiting {
+            self.playlist_start_segment(path_index, buffer);
+            self.playlist_render_next();
+        } else {
+            self.audio.queue_next_buffer(Arc::clone(&buffer));
+            if let Some(state) = self.playlist_playback.as_mut() {
+                state.queued = Some((path_index, buffer));
+            }
+        }
+    }
+
+    /// Highlight the playing row without loading it (loading would replace
+    /// the playlist buffer).
+    fn playlist_show_row(&mut self, path_index: usize) {
+        let Some(path) = self
+            .playlist_playback
+            .as_ref()
+            .and_then(|s| s.paths.get(path_index).cloned())
+        else {
+            return;
+        };
+        if let Some(row) = self.row_for_path(&path) {
+            self.selected = Some(row);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    fn drain_playlist_render(&mut self) {
+        let received = {
+            let Some(rx) = self
+                .playlist_playback
+                .as_ref()
+                .and_then(|s| s.render_rx.as_ref())
+            else {
+                return;
+            };
+            rx.try_recv()
+        };
+        let result = match received {
+            Ok(result) => result,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.cancel_playlist();
+                self.push_toast(ToastSeverity::Error, "Playlist: render worker vanished");
+                return;
+            }
+        };
+        if let Some(state) = self.playlist_playback.as_mut() {
+            state.render_rx = None;
+            state.carry_tail = result.tail;
+        }
+        match result.segment {
+            Ok(segment) if segment.first().is_some_and(|c| !c.is_empty()) => {
+                self.playlist_accept_segment(result.path_index, segment);
+            }
+            Ok(_) => self.playlist_render_next(),
+            Err(msg) => {
+                self.debug_log(format!("playlist: skipped {msg}"));
+                self.push_toast(ToastSeverity::Warning, format!("Playlist: skipped {msg}"));
+                self.playlist_render_next();
+            }
+        }
+    }
+
+    /// The engine still plays one of our segments (anything else replacing
+    /// the source, e.g. selecting a row or opening a tab, ends the playlist).
+    fn playlist_owns_transport(&self) -> bool {
+        let Some(state) = &self.playlist_playback else {
+            return false;
+        };
+        if state.current.is_none() {
+            return true;
+        }
+        let Some(loaded) = self.audio.shared.samples.load_full() else {
+            return false;
+        };
+        [&state.current, &state.queued]
+            .into_iter()
+            .flatten()
+            .any(|(_, buf)| Arc::ptr_eq(buf, &loaded))
+    }
+
+    /// Per-frame driver: follow the engine's handoffs, keep one segment
+    /// rendered ahead, and tell a natural end from a user stop.
+    pub(super) fn poll_playlist_playback(&mut self, ctx: &egui::Context) {
+        if self.playlist_playback.is_none() {
+            return;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        let handoffs = self.audio.queued_handoff_count();
+        let mut handed_off = None;
+        if let Some(state) = self.playlist_playback.as_mut() {
+            if handoffs != state.handoffs_seen {
+                state.handoffs_seen = handoffs;
+                if let Some(next) = state.queued.take() {
+                    handed_off = Some(next.0);
+                    state.current = Some(next);
+                    state.item_started = true;
+                    state.played += 1;
+                }
+            }
+        }
+        if let Some(path_index) = handed_off {
+            // Re-sync the playhead/timeline to the new segment length.
+            self.playback_mark_buffer_source(
+                crate::app::PlaybackSourceKind::ToolPreview,
+                self.audio.shared.out_sample_rate.max(1),
+            );
+            self.playlist_show_row(path_index);
+            self.playlist_render_next();
+        }
+        if !self.playlist_owns_transport() {
+            self.debug_log("playlist: source replaced, stopping".to_string());
+            self.cancel_playlist();
+            return;
+        }
+        self.drain_playlist_render();
+        self.playlist_step();
+    }
+
+    /// One stopped-playback decision step (separated from the frame poll
+    /// so tests can drive it deterministically).
+    pub(super) fn playlist_step(&mut self) {
+        let playing = self
+            .audio
+            .shared
+            .playing
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let Some(state) = self.playlist_playback.as_mut() else {
+            return;
+        };
+        if state.current.is_none() || state.awaiting_segment {
+            return;
+        }
+        if playing {
+            state.item_started = true;
+            return;
+        }
+        if !state.item_started {
+            return;
+        }
+        let len = self.audio.current_source_len();
+        let pos = self
+            .audio
+            .shared
+            .play_pos
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if len == 0 || pos + 2 < len {
+            // Stopped mid-segment: an explicit user stop ends the playlist.
+            self.cancel_playlist();
+            return;
+        }
+        // Natural end without a handoff: the next segment was queued too
+        // late (start it now) or is still rendering (the drain starts it).
+        if let Some((path_index, buffer)) = state.queued.take() {
+            self.audio.clear_queued_buffer();
+            self.playlist_start_segment(path_index, buffer);
+            self.playlist_render_next();
+        } else if state.render_rx.is_some() {
+            state.awaiting_segment = true;
+        } else {
+            let played = state.played;
+            self.playlist_playback = None;
+            self.push_toast(
+                ToastSeverity::Info,
+                format!("Playlist finished ({played} rows)"),
+            );
+        }
+    }
+
+    #[cfg(feature = "kittest")]
+    pub fn test_start_playlist_from_here(&mut self) -> bool {
+        self.start_playlist_from_here();
+        self.playlist_playback.is_some()
+    }
+
+    #[cfg(feature = "kittest")]
+    pub fn test_playlist_progress(&self) -> Option<(Option<usize>, usize, bool)> {
+        self.playlist_playback.as_ref().map(|s| {
+            (
+                s.current.as_ref().map(|(i, _)| *i),
+                s.played,
+                s.queued.is_some(),
+            )
+        })
+    }
+
+    /// Stand in for the audio callback reaching the end of the segment.
+    #[cfg(feature = "kittest")]
+    pub fn test_playlist_simulate_handoff(&mut self) -> bool {
+        crate::audio::AudioEngine::take_queued_handoff(&self.audio.shared).is_some()
+    }
+
+    /// Stop the transport at the end (`natural`) or in the middle of the
+    /// current segment and run one decision step; true if still running.
+    #[cfg(feature = "kittest")]
+    pub fn test_playlist_simulate_stop(&mut self, natural: bool) -> bool {
+        if let Some(state) = self.playlist_playback.as_mut() {
+            state.item_started = true;
+        }
+        let pos = if natural {
+            self.audio.current_source_len()
+        } else {
+            0
+        };
+        self.audio
+            .shared
+            .playing
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.audio
+            .shared
+            .play_pos
+            .store(pos, std::sync::atomic::Ordering::Relaxed);
+        self.playlist_step();
+        self.playlist_playback.is_some()
+    }
+
+    #[cfg(feature = "kittest")]
+    pub fn test_set_playlist_options(
+        &mut self,
+        repeat_all: bool,
+        shuffle: bool,
+        crossfade_ms: u32,
+    ) {
+        self.playlist_options = crate::app::types::PlaylistOptions {
+            repeat_all,
+            shuffle,
+            crossfade_ms,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::WavesPreviewer;
+
+    fn constant(len: usize, value: f32) -> Vec<Vec<f32>> {
+        vec![vec![value; len]]
+    }
+
+    #[test]
+    fn butt_splice_is_bit_exact_and_gapless() {
+        let a = vec![vec![0.1f32, 0.2, 0.3]];
+        let b = vec![vec![0.4f32, 0.5]];
+        let (seg_a, tail) = WavesPreviewer::playlist_compose_segment(Vec::new(), a.clone(), 0);
+        assert_eq!(seg_a, a);
+        assert!(tail.is_empty());
+        let (seg_b, tail) = WavesPreviewer::playlist_compose_segment(tail, b.clone(), 0);
+        assert_eq!(seg_b, b);
+        assert!(tail.is_empty());
+    }
+
+    #[test]
+    fn crossfade_overlaps_rows_at_constant_power() {
+        let (seg_a, tail) =
+            WavesPreviewer::playlist_compose_segment(Vec::new(), constant(10, 1.0), 4);
+        assert_eq!(seg_a[0].len(), 6, "the crossfade frames are withheld");
+        assert_eq!(tail[0].len(), 4);
+        let (seg_b, tail) = WavesPreviewer::playlist_compose_segment(tail, constant(8, 1.0), 0);
+        assert!(tail.is_empty());
+        assert_eq!(seg_a[0].len() + seg_b[0].len(), 10 + 8 - 4);
+        // Equal power: a correlated constant peaks at sqrt(2) mid-fade, and
+        // the gains' squares always sum to one.
+        for (i, v) in seg_b[0][..4].iter().enumerate() {
+            let t = (i as f32 + 0.5) / 4.0 * std::f32::consts::FRAC_PI_2;
+            assert!((v - (t.cos() + t.sin())).abs() < 1e-6);
+            assert!((t.cos().powi(2) + t.sin().powi(2) - 1.0).abs() < 1e-6);
+        }
+        assert!(seg_b[0][4..].iter().all(|v| *v == 1.0));
+    }
+
+    #[test]
+    fn crossfade_widens_channels_and_handles_short_rows() {
+        let stereo_tail = vec![vec![1.0f32; 4], vec![-1.0f32; 4]];
+        let (seg, tail) =
+            WavesPreviewer::playlist_compose_segment(stereo_tail, constant(2, 0.5), 4);
+        assert_eq!(seg.len(), 2, "mono row widens to the stereo tail");
+        assert_eq!(
+            seg[0].len(),
+            4,
+            "a row shorter than the fade keeps the fade length"
+        );
+        assert!(tail.is_empty(), "nothing left to withhold");
+        let (seg, tail) =
+            WavesPreviewer::playlist_compose_segment(constant(3, 0.25), Vec::new(), 4);
+        assert_eq!(
+            seg,
+            constant(3, 0.25),
+            "an empty row passes the tail through"
+        );
+        assert!(tail.is_empty());
+    }
+
+    #[test]
+    fn shuffled_order_is_a_permutation_that_avoids_the_last_row() {
+        let mut rng = 99u64;
+        for _ in 0..50 {
+            let order = WavesPreviewer::playlist_shuffled_order(5, &mut rng, Some(3));
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+            assert_ne!(order[0], 3);
+        }
+        assert!(WavesPreviewer::playlist_shuffled_order(0, &mut rng, None).is_empty());
+        assert_eq!(
+            WavesPreviewer::playlist_shuffled_order(1, &mut rng, Some(0)),
+            vec![0]
+        );
+    }
+}
@@ -35,6 +35,7 @@ enum LongPreviewJobKind {
     Loudness {
         target_lufs: f32,
         out_sample_rate: u32,
+        layout: crate::channel_layout::ChannelLayout,
     },
     Reverse {
         range: Option<(usize, usize)>,
@@ -703,7 +704,7 @@ impl WavesPreviewer {
         path: &Path,
         fallback_channels: &[Vec<f32>],
     ) -> Option<Vec<Vec<(f32, f32)>>> {
-        if let Ok(Some(proxy)) = crate::audio_io::build_wav_proxy_preview(
+        if let Ok(Some(proxy)) = crate::audio_io::build_proxy_preview(
             path,
             crate::audio_io::EDITOR_PROXY_OVERVIEW_MAX_TOTAL_SAMPLES,
         ) {
@@ -761,10 +762,12 @@ impl WavesPreviewer {
             LongPreviewJobKind::Loudness {
                 target_lufs,
                 out_sample_rate,
+                layout,
             } => {
-                let lufs = crate::wave::lufs_integrated_from_multi(
+                let lufs = crate::wave::lufs_integrated_for_layout(
                     fallback_channels,
                     out_sample_rate.max(1),
+                    layout,
                 )
                 .ok()?;
                 if !lufs.is_finite() {
@@ -1031,9 +1034,10 @@ impl WavesPreviewer {
                 LongPreviewJobKind::Loudness {
                     target_lufs,
                     out_sample_rate,
+                    layout,
                 } => {
                     if let Ok(lufs) =
-                        crate::wave::lufs_integrated_from_multi(&playback, out_sample_rate)
+                        crate::wave::lufs_integrated_for_layout(&playback, out_sample_rate, layout)
                     {
                         if lufs.is_finite() {
                             let gain = db_to_amp(target_lufs - lufs);
@@ -1418,6 +1422,12 @@ impl WavesPreviewer {
             .round() as usize;
         let declick_sensitivity = st.declick_sensitivity;
         let declip_sensitivity = st.declip_sensitivity;
+        let loudness_layout = crate::app::types::LoudnessLayout::resolve(
+            tab.ch_samples.len(),
+            Some(tab.channel_layout()),
+            self.loudness_layout_override,
+        )
+        .layout;
         let dehum_config = crate::app::dehum::DehumConfig {
             base_hz: st.dehum_hz.clamp(20.0, 400.0),
             harmonics: st.dehum_harmonics.clamp(1, 16),
@@ -1669,14 +1679,17 @@ impl WavesPreviewer {
                         LongPreviewJobKind::Loudness {
                             target_lufs: st.loudness_target_lufs,
                             out_sample_rate,
+                            layout: loudness_layout,
                         },
                         None,
                     );
                     return;
                 }
-                if let Ok(lufs) =
-                    crate::wave::lufs_integrated_from_multi(&ch_samples, out_sample_rate)
-                {
+                if let Ok(lufs) = crate::wave::lufs_integrated_for_layout(
+                    &ch_samples,
+                    out_sample_rate,
+                    loudness_layout,
+                ) {
                     if !lufs.is_finite() {
                         return;
                     }
@@ -363,6 +363,8 @@ pub struct ProjectListColumns {
     #[serde(default)]
     pub lufs_m: bool,
     #[serde(default)]
+    pub lra: bool,
+    #[serde(default)]
     pub bpm: bool,
     #[serde(default)]
     pub created_at: bool,
@@ -1710,6 +1712,7 @@ pub fn project_metadata_sub_view_string(view: MetadataSubView) -> String {
     match view {
         MetadataSubView::Structure => "structure",
         MetadataSubView::Hex => "hex",
+        MetadataSubView::Ucs => "ucs",
     }
     .to_string()
 }
@@ -1717,6 +1720,7 @@ pub fn project_metadata_sub_view_string(view: MetadataSubView) -> String {
 pub fn metadata_sub_view_from_project(value: Option<&str>) -> MetadataSubView {
     match value.map(|raw| raw.trim().to_ascii_lowercase()) {
         Some(value) if value == "hex" => MetadataSubView::Hex,
+        Some(value) if value == "ucs" => MetadataSubView::Ucs,
         _ => MetadataSubView::Structure,
     }
 }
@@ -1735,6 +1739,7 @@ pub fn project_other_sub_view_string(view: EditorOtherSubView) -> String {
         EditorOtherSubView::World => "world",
         EditorOtherSubView::Tempogram => "tempogram",
         EditorOtherSubView::Chromagram => "chromagram",
+        EditorOtherSubView::Loudness => "loudness",
     }
     .to_string()
 }
@@ -1763,6 +1768,7 @@ pub fn primary_view_from_project(
         Some(v) if v == "chromagram" => EditorOtherSubView::Chromagram,
         Some(v) if v == "world" || v == "f0" => EditorOtherSubView::World,
         Some(v) if v == "tempogram" => EditorOtherSubView::Tempogram,
+        Some(v) if v == "loudness" => EditorOtherSubView::Loudness,
         _ => EditorOtherSubView::from_mode(legacy_mode),
     };
     (primary_view, spec_view, other_view)
@@ -1811,7 +1817,9 @@ pub fn missing_file_meta(path: &Path) -> FileMeta {
         lufs_i: None,
         lufs_m_max: None,
         lufs_s_max: None,
+        lra_lu: None,
         true_peak_db: None,
+        loudness_layout: None,
         bpm: None,
         silence_lead_ms: None,
         silence_tail_ms: None,
@@ -2218,6 +2226,11 @@ wave = true
                 EditorOtherSubView::Chromagram,
             )
         );
+        let loudness = project_other_sub_view_string(EditorOtherSubView::Loudness);
+        assert_eq!(
+            primary_view_from_project(Some("other"), None, Some(&loudness), "Waveform").2,
+            EditorOtherSubView::Loudness
+        );
     }
 
     #[test]
@@ -12,6 +12,8 @@ use rustfft::{num_complex::Complex, Fft, FftPlanner};
 use std::cell::RefCell;
 use std::sync::Arc;
 
+pub use crate::analyzer::stereo_correlation;
+
 /// Display floor for the analyzer, in dBFS.
 pub const SPECTRUM_DB_FLOOR: f32 = -84.0;
 /// Lowest frequency shown on the log axis.
@@ -239,30 +241,6 @@ pub fn goniometer_points_into(l: &[f32], r: &[f32], max_points: usize, out: &mut
     }
 }
 
-/// Zero-lag correlation of two channels in [-1, 1]. Near-silence maps to
-/// 0 (neutral) rather than an arbitrary sign.
-pub fn stereo_correlation(l: &[f32], r: &[f32]) -> f32 {
-    let n = l.len().min(r.len());
-    if n == 0 {
-        return 0.0;
-    }
-    let mut ll = 0.0f64;
-    let mut rr = 0.0f64;
-    let mut lr = 0.0f64;
-    for i in 0..n {
-        let a = l[i] as f64;
-        let b = r[i] as f64;
-        ll += a * a;
-        rr += b * b;
-        lr += a * b;
-    }
-    let denom = (ll * rr).sqrt();
-    if denom < 1.0e-10 {
-        return 0.0;
-    }
-    (lr / denom).clamp(-1.0, 1.0) as f32
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -722,6 +722,7 @@ impl super::WavesPreviewer {
                     dst: asset_dst.clone(),
                     source: SessionSidecarSource::Buffer(audio.clone()),
                     sample_rate,
+                    channel_layout: None,
                     label: "managed virtual asset",
                 });
                 sidecar_audio = Some(rel_path(&asset_dst, base_dir));
@@ -730,6 +731,7 @@ impl super::WavesPreviewer {
                     dst: asset_dst.clone(),
                     source: SessionSidecarSource::File(source_path.to_path_buf()),
                     sample_rate,
+                    channel_layout: None,
                     label: "managed virtual asset",
                 });
                 sidecar_audio = Some(rel_path(&asset_dst, base_dir));
@@ -890,6 +892,7 @@ impl super::WavesPreviewer {
                 super::types::SortKey::TruePeak => "TruePeak".to_string(),
                 super::types::SortKey::LufsShort => "LufsShort".to_string(),
                 super::types::SortKey::LufsMomentary => "LufsMomentary".to_string(),
+                super::types::SortKey::LoudnessRange => "LoudnessRange".to_string(),
                 super::types::SortKey::Bpm => "Bpm".to_string(),
                 super::types::SortKey::SilenceLead => "SilenceLead".to_string(),
                 super::types::SortKey::SilenceTail => "SilenceTail".to_string(),
@@ -936,6 +939,7 @@ impl super::WavesPreviewer {
                 dbtp: self.list_columns.dbtp,
                 lufs_s: self.list_columns.lufs_s,
                 lufs_m: self.list_columns.lufs_m,
+                lra: self.list_columns.lra,
                 bpm: self.list_columns.bpm,
                 created_at: self.list_columns.created_at,
                 modified_at: self.list_columns.modified_at,
@@ -1012,6 +1016,7 @@ impl super::WavesPreviewer {
                     dst: dst.clone(),
                     source: SessionSidecarSource::Channels(tab.ch_samples_arc.clone()),
                     sample_rate: sidecar_sr,
+                    channel_layout: Some(tab.channel_layout()),
                     label: "edited audio",
                 });
                 edited_audio = Some(dst);
@@ -1025,6 +1030,7 @@ impl super::WavesPreviewer {
                             overlay.channels.clone(),
                         )),
                         sample_rate: self.audio.shared.out_sample_rate,
+                        channel_layout: None,
                         label: "preview audio",
                     });
                     preview_audio = Some(dst);
@@ -1057,6 +1063,7 @@ impl super::WavesPreviewer {
                     cached.ch_samples.clone(),
                 )),
                 sample_rate: sidecar_sr,
+                channel_layout: cached.channel_layout,
                 label: "cached audio",
             });
             cached_edits.push(ProjectEdit {
@@ -1202,6 +1209,12 @@ impl super::WavesPreviewer {
                     let channels = job.source.channels();
                     let len = channels.first().map(Vec::len).unwrap_or(0);
                     crate::wave::export_selection_wav(channels, job.sample_rate, (0, len), &stage)
+                        .and_then(|()| match job.channel_layout {
+                            Some(layout) if layout.channels() as usize == channels.len() => {
+                                crate::wave::write_wav_channel_layout(&stage, layout)
+                            }
+                            _ => Ok(()),
+                        })
                 }
             };
             if let Err(error) = result {
@@ -1386,6 +1399,7 @@ impl super::WavesPreviewer {
             dbtp: project.app.list_columns.dbtp,
             lufs_s: project.app.list_columns.lufs_s,
             lufs_m: project.app.list_columns.lufs_m,
+            lra: project.app.list_columns.lra,
             bpm: project.app.list_columns.bpm,
             created_at: project.app.list_columns.created_at,
             modified_at: project.app.list_columns.modified_at,
@@ -1465,6 +1479,7 @@ impl super::WavesPreviewer {
             "TruePeak" => super::types::SortKey::TruePeak,
             "LufsShort" => super::types::SortKey::LufsShort,
             "LufsMomentary" => super::types::SortKey::LufsMomentary,
+            "LoudnessRange" => super::types::SortKey::LoudnessRange,
             "Bpm" => super::types::SortKey::Bpm,
             "SilenceLead" => super::types::SortKey::SilenceLead,
             "SilenceTail" => super::types::SortKey::SilenceTail,
@@ -1949,9 +1964,11 @@ impl super::WavesPreviewer {
         for edit in project.cached_edits.iter() {
             let path = resolve_path(&edit.path, &base_dir);
             let edited = load_sidecar_audio(&project_path, &edit.edited_audio).ok();
-            let Some((chans, sr, _)) = edited else {
+            let Some((chans, sr, sidecar)) = edited else {
                 continue;
             };
+            let channel_layout = crate::audio_io::read_channel_layout(&sidecar);
+            let adm_lanes = crate::adm::read_adm_lanes(&path);
             let (chans, buffer_sr) = self.normalize_loaded_sidecar_buffer(
                 &path,
                 chans,
@@ -2018,6 +2035,8 @@ impl super::WavesPreviewer {
                     time_sig_numerator: edit.time_sig_numerator,
                     time_sig_denominator: edit.time_sig_denominator,
                     extra_selections: vec![],
+                    channel_layout,
+                    adm_lanes,
                     applied_effect_graph: edit.applied_effect_graph.as_ref().map(|stamp| {
                         super::types::AppliedEffectGraphStamp {
                             template_id: stamp.template_id.clone(),
@@ -2036,7 +2055,9 @@ impl super::WavesPreviewer {
             } else {
                 None
             };
-            if let Some((chans, sr, _)) = edited {
+            if let Some((chans, sr, sidecar)) = edited {
+                let channel_layout = crate::audio_io::read_channel_layout(&sidecar);
+                let adm_lanes = crate::adm::read_adm_lanes(&tab_path);
                 let (chans, buffer_sr) = self.normalize_loaded_sidecar_buffer(
                     &tab_path,
                     chans,
@@ -2103,6 +2124,8 @@ impl super::WavesPreviewer {
                         time_sig_numerator: tab.time_sig_numerator,
                         time_sig_denominator: tab.time_sig_denominator,
                         extra_selections: vec![],
+                        channel_layout,
+                        adm_lanes,
                         applied_effect_graph: None,
                     },
                 );
@@ -2358,7 +2381,7 @@ impl super::WavesPreviewer {
                     .and_then(|s| s.to_str())
                     .map(|s| {
                         let s = s.to_ascii_lowercase();
-                        s == "csv" || s == "xlsx" || s == "xls"
+                        s == "csv" || s == "xlsx" || s == "xls" || s == "sqlite"
                     })
                     .unwrap_or(false);
                 if is_project && project_path.is_none() {
@@ -144,6 +144,12 @@ impl WavesPreviewer {
                     .filter(|v| v.is_finite())
                     .map(|v| v as f64),
             ),
+            // Gain-invariant, so no pending gain here.
+            SortKey::LoudnessRange => OwnedKey::Num(
+                m.and_then(|m| m.lra_lu)
+                    .filter(|v| v.is_finite())
+                    .map(|v| v as f64),
+            ),
             SortKey::Bpm => OwnedKey::Num(
                 m.and_then(|m| m.bpm)
                     .filter(|v| v.is_finite() && *v > 0.0)
@@ -437,6 +443,7 @@ impl WavesPreviewer {
     /// synchronously; large lists filter in per-frame slices and adopt the
     /// result (then re-sort) when done.
     pub(super) fn refresh_filter_then_sort(&mut self) {
+        self.sync_metadata_fulltext_search();
         let query = self.search_query.trim().to_string();
         if query.is_empty() || self.items.len() <= LIST_JOB_SYNC_THRESHOLD {
             self.filter_job = None;
@@ -85,6 +85,8 @@ impl super::WavesPreviewer {
                 tab.loop_mode = cached.loop_mode;
                 tab.plugin_fx_draft = cached.plugin_fx_draft;
                 tab.plugin_fx_chain = cached.plugin_fx_chain;
+                tab.channel_layout = cached.channel_layout;
+                tab.adm_lanes = cached.adm_lanes;
                 self.tabs.push(tab);
                 self.workspace_view = crate::app::types::WorkspaceView::Editor;
                 self.active_tab = Some(self.tabs.len() - 1);
@@ -129,6 +131,8 @@ impl super::WavesPreviewer {
                 tab.buffer_sample_rate = out_sr;
                 tab.samples_len_visual = visual_len;
                 tab.loading_waveform_minmax = self.initial_editor_loading_overview(&source_path);
+                tab.channel_layout = crate::audio_io::read_channel_layout(&source_path);
+                tab.adm_lanes = crate::adm::read_adm_lanes(&source_path);
                 tab.active_tool = initial_tool;
                 tab.tool_state = crate::app::types::ToolState::default_values();
                 self.tabs.push(tab);
@@ -254,6 +258,8 @@ impl super::WavesPreviewer {
             tab.loop_mode = cached.loop_mode;
             tab.plugin_fx_draft = cached.plugin_fx_draft;
             tab.plugin_fx_chain = cached.plugin_fx_chain;
+            tab.channel_layout = cached.channel_layout;
+            tab.adm_lanes = cached.adm_lanes;
             self.tabs.push(tab);
             self.workspace_view = crate::app::types::WorkspaceView::Editor;
             self.active_tab = Some(self.tabs.len() - 1);
@@ -297,6 +303,8 @@ impl super::WavesPreviewer {
         tab.buffer_sample_rate = self.audio.shared.out_sample_rate.max(1);
         tab.samples_len_visual = estimated_visual_frames.unwrap_or(0);
         tab.loading_waveform_minmax = initial_loading_overview;
+        tab.channel_layout = crate::audio_io::read_channel_layout(path);
+        tab.adm_lanes = crate::adm::read_adm_lanes(path);
         tab.bpm_value = default_bpm;
         tab.active_tool = initial_tool;
         tab.tool_state = crate::app::types::ToolState::default_values();
@@ -0,0 +1,197 @@
+//! Tag editor: ID3v2 / MP4 / Vorbis comment text tags over the list
+//! selection, written in place with list undo.
+
+use std::path::PathBuf;
+
+use crate::app::types::{
+    ListUndoAction, ListUndoActionKind, TagEditorRow, TagWriteUndoEntry, ToastSeverity,
+};
+use crate::tags::{AudioTags, TagField, TagKey};
+
+impl crate::app::WavesPreviewer {
+    pub(super) fn open_tag_editor(&mut self) {
+        let rows: Vec<TagEditorRow> = self
+            .selected_paths()
+            .into_iter()
+            .map(|path| {
+                let format = crate::tags::tag_format(&path);
+                let (stored, error) = match format {
+                    Some(_) => match crate::tags::read_tags(&path) {
+                        Ok(tags) => (tags, None),
+                        Err(err) => (AudioTags::default(), Some(format!("{err:#}"))),
+                    },
+                    None => (
+                        AudioTags::default(),
+                        Some("not a taggable format (WAV: use Edit BWF Metadata)".to_string()),
+                    ),
+                };
+                TagEditorRow {
+                    draft: stored.clone(),
+                    path,
+                    format,
+                    stored,
+                    error,
+                }
+            })
+            .collect();
+        let mut columns: Vec<String> = Vec::new();
+        for row in &rows {
+            for (key, _) in &row.stored.custom {
+                if !columns.iter().any(|c| c.eq_ignore_ascii_case(key)) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+        self.tag_editor.rows = rows;
+        self.tag_editor.custom_columns = columns;
+        self.tag_editor.new_column.clear();
+        self.tag_editor.fill = AudioTags::default();
+        self.tag_editor.show_dialog = true;
+    }
+
+    /// Column keys of the tag table: standard fields, then custom keys.
+    pub(super) fn tag_editor_columns(&self) -> Vec<TagKey> {
+        TagField::ALL
+            .into_iter()
+            .map(TagKey::Field)
+            .chain(
+                self.tag_editor
+                    .custom_columns
+                    .iter()
+                    .map(|key| TagKey::Custom(key.clone())),
+            )
+            .collect()
+    }
+
+    /// Copy every non-empty "Set all" cell into the drafts of taggable rows.
+    pub(super) fn fill_tag_editor_rows(&mut self) {
+        let columns = self.tag_editor_columns();
+        let fill = std::mem::take(&mut self.tag_editor.fill);
+        for row in self
+            .tag_editor
+            .rows
+            .iter_mut()
+            .filter(|row| row.format.is_some())
+        {
+            for key in &columns {
+                let value = fill.value(key);
+                if !value.is_empty() {
+                    row.draft.set(key, value);
+                }
+            }
+        }
+    }
+
+    pub(super) fn apply_tag_editor(&mut self) {
+        let plan: Vec<(PathBuf, AudioTags, AudioTags)> = self
+            .tag_editor
+            .rows
+            .iter()
+            .filter(|row| row.format.is_some() && row.draft != row.stored)
+            .map(|row| (row.path.clone(), row.stored.clone(), row.draft.clone()))
+            .collect();
+        let results = self.write_tag_edits(plan);
+        for (path, result) in results {
+            let Some(row) = self.tag_editor.rows.iter_mut().find(|row| row.path == path) else {
+                continue;
+            };
+            match result {
+                Ok(tags) => {
+                    row.stored = tags.clone();
+                    row.draft = tags;
+                    row.error = None;
+                }
+                Err(error) => row.error = Some(error),
+            }
+        }
+    }
+
+    /// Write `before -> after` for each file (only the differing keys) and
+    /// record one list undo step for the files that were written.
+    fn write_tag_edits(
+        &mut self,
+        plan: Vec<(PathBuf, AudioTags, AudioTags)>,
+    ) -> Vec<(PathBuf, Result<AudioTags, String>)> {
+        let selection_before = self.capture_list_selection_snapshot();
+        let mut entries = Vec::new();
+        let mut results = Vec::new();
+        let mut failures = 0usize;
+        for (path, before, after) in plan {
+            let changes = crate::tags::diff_tags(&before, &after);
+            let written = crate::tags::write_tags(&path, &changes)
+                .and_then(|()| crate::tags::read_tags(&path));
+            match written {
+                Ok(tags) => {
+                    self.invalidate_metadata_for_path(&path);
+                    entries.push(TagWriteUndoEntry {
+                        path: path.clone(),
+                        before,
+                        after: tags.clone(),
+                    });
+                    results.push((path, Ok(tags)));
+                }
+                Err(err) => {
+                    failures += 1;
+                    results.push((path, Err(format!("{err:#}"))));
+                }
+            }
+        }
+        let written = entries.len();
+        if !entries.is_empty() {
+            let selection_after = self.capture_list_selection_snapshot();
+            self.push_list_undo_action(ListUndoAction {
+                kind: ListUndoActionKind::TagWrite { entries },
+                before: selection_before,
+                after: selection_after,
+            });
+        }
+        let (severity, message) = if failures == 0 {
+            (
+                ToastSeverity::Info,
+                format!("Tags: wrote {written} file(s)"),
+            )
+        } else {
+            (
+                ToastSeverity::Warning,
+                format!("Tags: wrote {written} file(s), {failures} failed"),
+            )
+        };
+        self.push_toast(severity, message);
+        results
+    }
+
+    /// Undo/redo of [`ListUndoActionKind::TagWrite`].
+    pub(super) fn apply_tag_write_entries(&mut self, entries: &[TagWriteUndoEntry], undo: bool) {
+        let mut failed = 0usize;
+        for entry in entries.iter().rev() {
+            let (from, to) = if undo {
+                (&entry.after, &entry.before)
+            } else {
+                (&entry.before, &entry.after)
+            };
+            let changes = crate::tags::diff_tags(from, to);
+            if crate::tags::write_tags(&entry.path, &changes).is_err() {
+                failed += 1;
+            }
+            self.invalidate_metadata_for_path(&entry.path);
+            if let Some(row) = self
+                .tag_editor
+                .rows
+                .iter_mut()
+                .find(|row| row.path == entry.path)
+            {
+                row.stored = to.clone();
+                row.draft = to.clone();
+            }
+        }
+        if failed > 0 {
+            self.push_toast(
+                ToastSeverity::Warning,
+                format!(
+                    "Tags {}: {failed} file(s) could not be restored",
+                    if undo { "undo" } else { "redo" }
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Metadata templates over the list selection: the template library in the
+//! prefs folder, a per-file preview, and in-place writes with list undo.
+
+use std::path::{Path, PathBuf};
+
+use crate::app::types::{
+    ListUndoAction, ListUndoActionKind, MetadataTemplatePreviewRow, MetadataWriteUndoEntry,
+    TagWriteUndoEntry, ToastSeverity,
+};
+use crate::metadata_template::{MetadataTemplate, TemplateContext};
+
+/// Chunks a template write can touch in a WAV; snapshotted for undo.
+const TEMPLATE_UNDO_CHUNKS: [[u8; 4]; 2] = [*b"bext", *b"iXML"];
+
+/// Transcript text of the `<stem>.srt` next to `path`, if any.
+pub(super) fn sidecar_transcript_text(path: &Path) -> Option<String> {
+    super::transcript::srt_path_for_audio(path)
+        .and_then(|srt| super::transcript::load_srt(&srt))
+        .map(|transcript| transcript.full_text)
+}
+
+impl crate::app::WavesPreviewer {
+    pub(super) fn metadata_template_library_path() -> Option<PathBuf> {
+        let base = std::env::var_os("APPDATA").or_else(|| std::env::var_os("LOCALAPPDATA"))?;
+        Some(
+            PathBuf::from(base)
+                .join("NeoWaves")
+                .join("metadata-templates.json"),
+        )
+    }
+
+    /// Saved templates, or the seed library when none are saved (or there is
+    /// no prefs folder).
+    pub(super) fn load_metadata_template_library(
+        path: Option<&Path>,
+    ) -> anyhow::Result<Vec<MetadataTemplate>> {
+        match path
+            .map(Path::to_path_buf)
+            .or_else(Self::metadata_template_library_path)
+        {
+            Some(path) => crate::metadata_template::load_library(&path),
+            None => Ok(crate::metadata_template::default_templates()),
+        }
+    }
+
+    pub(super) fn save_metadata_template_library(&mut self) {
+        let Some(path) = Self::metadata_template_library_path() else {
+            self.metadata_templates.library_dirty = false;
+            return;
+        };
+        match crate::metadata_template::save_library(&path, &self.metadata_templates.library) {
+            Ok(()) => self.metadata_templates.library_dirty = false,
+            Err(err) => self.push_toast(
+                ToastSeverity::Warning,
+                format!("Metadata templates: {err:#}"),
+            ),
+        }
+    }
+
+    pub(super) fn open_metadata_template_dialog(&mut self) {
+        if self.metadata_templates.library.is_empty() {
+            match Self::load_metadata_template_library(None) {
+                Ok(library) => self.metadata_templates.library = library,
+                Err(err) => {
+                    self.push_toast(
+                        ToastSeverity::Warning,
+                        format!("Metadata templates: {err:#}"),
+                    );
+                    self.metadata_templates.library = crate::metadata_template::default_templates();
+                }
+            }
+        }
+        let last = self.metadata_templates.library.len().saturating_sub(1);
+        self.metadata_templates.selected = self.metadata_templates.selected.min(last);
+        self.metadata_templates.paths = self.selected_paths();
+        self.metadata_templates.preview.clear();
+        self.metadata_templates.error = None;
+        self.metadata_templates.show_dialog = true;
+    }
+
+    /// Token values of `path`: file info plus the list's external data row
+    /// and transcript (falling back to the `.srt` sidecar).
+    pub(super) fn metadata_template_context(
+        &self,
+        path: &Path,
+        index: usize,
+        count: usize,
+    ) -> TemplateContext {
+        let mut context = TemplateContext::for_path(path, index, count);
+        if let Some(row) = self.external_row_for_path(path) {
+            context.external = row;
+        }
+        context.transcript = self
+            .transcript_for_path(path)
+            .map(|transcript| transcript.full_text.clone())
+            .or_else(|| sidecar_transcript_text(path));
+        context
+    }
+
+    /// Render the edited template for every target file.
+    pub(super) fn build_metadata_template_preview(&mut self) {
+        self.metadata_templates.preview.clear();
+        let Some(template) = self
+            .metadata_templates
+            .library
+            .get(self.metadata_templates.selected)
+        else {
+            return;
+        };
+        let compiled = match template.compile() {
+            Ok(compiled) => compiled,
+            Err(err) => {
+                self.metadata_templates.error = Some(format!("{err:#}"));
+                return;
+            }
+        };
+        self.metadata_templates.error = None;
+        let paths = self.metadata_templates.paths.clone();
+        let count = paths.len();
+        self.metadata_templates.preview = paths
+            .into_iter()
+            .enumerate()
+            .map(|(idx, path)| {
+                let context = self.metadata_template_context(&path, idx + 1, count);
+                let fields = compiled.render(&context);
+                let error = (!fields.iter().any(|field| field.applies))
+                    .then(|| "no target applies to this format".to_string());
+                MetadataTemplatePreviewRow {
+                    path,
+                    fields,
+                    error,
+                }
+            })
+            .collect();
+    }
+
+    /// Write the previewed values and record one list undo step.
+    pub(super) fn apply_metadata_template(&mut self) {
+        self.build_metadata_template_preview();
+        let Some(compiled) = self
+            .metadata_templates
+            .library
+            .get(self.metadata_templates.selected)
+            .and_then(|template| template.compile().ok())
+        else {
+            return;
+        };
+        let selection_before = self.capture_list_selection_snapshot();
+        let rows = self.metadata_templates.preview.clone();
+        let mut chunks = Vec::new();
+        let mut tags = Vec::new();
+        let mut skipped = 0usize;
+        let mut failures: Vec<String> = Vec::new();
+        for row in rows {
+            if row.error.is_some() {
+                skipped += 1;
+                continue;
+            }
+            let path = row.path;
+            if crate::tags::tag_format(&path).is_some() {
+                let written = crate::tags::read_tags(&path).and_then(|before| {
+                    compiled.write(&path, &row.fields)?;
+                    Ok((before, crate::tags::read_tags(&path)?))
+                });
+                match written {
+                    Ok((before, after)) => tags.push(TagWriteUndoEntry {
+                        path: path.clone(),
+                        before,
+                        after,
+                    }),
+                    Err(err) => failures.push(format!("{}: {err:#}", path.display())),
+                }
+            } else {
+                let written = crate::wave::snapshot_wav_chunks(&path, &TEMPLATE_UNDO_CHUNKS)
+                    .and_then(|before| {
+                        compiled.write(&path, &row.fields)?;
+                        Ok((
+                            before,
+                            crate::wave::snapshot_wav_chunks(&path, &TEMPLATE_UNDO_CHUNKS)?,
+                        ))
+                    });
+                match written {
+                    Ok((before, after)) => chunks.push(MetadataWriteUndoEntry {
+                        before_path: path.clone(),
+                        after_path: path.clone(),
+                        before,
+                        after,
+                    }),
+                    Err(err) => failures.push(format!("{}: {err:#}", path.display())),
+                }
+            }
+            self.invalidate_metadata_for_path(&path);
+        }
+        let written = chunks.len() + tags.len();
+        if written > 0 {
+            let selection_after = self.capture_list_selection_snapshot();
+            self.push_list_undo_action(ListUndoAction {
+                kind: ListUndoActionKind::TemplateWrite { chunks, tags },
+                before: selection_before,
+                after: selection_after,
+            });
+        }
+        let severity = if failures.is_empty() {
+            ToastSeverity::Info
+        } else {
+            ToastSeverity::Warning
+        };
+        let mut message = format!("Metadata template: wrote {written} file(s)");
+        if skipped > 0 {
+            message.push_str(&format!(", skipped {skipped}"));
+        }
+        if let Some(first) = failures.first() {
+            message.push_str(&format!(", {} failed ({first})", failures.len()));
+        }
+        self.push_toast(severity, message);
+        self.build_metadata_template_preview();
+    }
+}
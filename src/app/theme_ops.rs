@@ -5,9 +5,10 @@ use std::sync::Arc;
 use egui::{Color32, FontData, FontDefinitions, FontFamily, FontId, TextStyle, Visuals};
 
 use super::types::{
-    ConflictPolicy, EditorHorizontalZoomAnchorMode, EditorPauseResumeMode, ExportConfig,
-    ItemBgMode, ListColumnConfig, SaveMode, SpectrogramConfig, SpectrogramScale, SrcQuality,
-    ThemeMode, TranscriptComputeTarget, TranscriptModelVariant, TranscriptPerfMode, WindowFunction,
+    AnalyzerDock, ConflictPolicy, EditorHorizontalZoomAnchorMode, EditorPauseResumeMode,
+    ExportConfig, ItemBgMode, ListColumnConfig, RtaMode, SaveMode, SpectrogramConfig,
+    SpectrogramScale, SrcQuality, ThemeMode, TranscriptComputeTarget, TranscriptModelVariant,
+    TranscriptPerfMode, WindowFunction,
 };
 use super::WavesPreviewer;
 
@@ -64,6 +65,8 @@ impl WavesPreviewer {
         self.blank_threshold_dbfs = super::inspection::DEFAULT_BLANK_THRESHOLD_DBFS;
         self.blank_min_ms = super::inspection::DEFAULT_BLANK_MIN_MS;
         self.push_blank_threshold_to_meta_pool();
+        self.loudness_layout_override = None;
+        self.push_loudness_layout_to_meta_pool();
         self.transcript_ai_cfg = super::types::TranscriptAiConfig::default();
         self.sanitize_transcript_ai_config();
         self.refresh_transcript_ai_status();
@@ -578,6 +581,14 @@ impl WavesPreviewer {
                 if let Ok(v) = rest.trim().parse::<f32>() {
                     self.export_cfg.codec.ogg_quality = v.clamp(-0.2, 1.0);
                 }
+            } else if let Some(rest) = line.strip_prefix("export_opus_kbps=") {
+                if let Ok(v) = rest.trim().parse::<u32>() {
+                    self.export_cfg.codec.opus_bitrate_kbps = v.clamp(6, 512);
+                }
+            } else if let Some(rest) = line.strip_prefix("export_opus_complexity=") {
+                if let Ok(v) = rest.trim().parse::<u8>() {
+                    self.export_cfg.codec.opus_complexity = v.min(10);
+                }
             } else if let Some(rest) = line.strip_prefix("inspect_cfg=") {
                 // key=value pairs separated by commas; unknown keys ignored.
                 for part in rest.split(',') {
@@ -593,6 +604,10 @@ impl WavesPreviewer {
                         "loud" => cfg.check_loudness = b,
                         "target" => cfg.target_lufs = f.unwrap_or(cfg.target_lufs),
                         "tol" => cfg.lufs_tolerance_lu = f.unwrap_or(cfg.lufs_tolerance_lu),
+                        "lra" => cfg.check_loudness_range = b,
+                        "lra_max" => cfg.max_lra_lu = f.unwrap_or(cfg.max_lra_lu),
+                        "st" => cfg.check_short_term = b,
+                        "st_max" => cfg.max_short_term_lufs = f.unwrap_or(cfg.max_short_term_lufs),
                         "sil" => cfg.check_silence = b,
                         "sil_db" => {
                             cfg.silence_threshold_dbfs = f.unwrap_or(cfg.silence_threshold_dbfs)
@@ -609,6 +624,52 @@ impl WavesPreviewer {
                         _ => {}
                     }
                 }
+            } else if let Some(rest) = line.strip_prefix("analyzer_panel=") {
+                // Same key:value list as inspect_cfg; unknown keys ignored.
+                let panel = &mut self.analyzer_panel;
+                for part in rest.split(',') {
+                    let Some((k, v)) = part.split_once(':') else {
+                        continue;
+                    };
+                    let b = matches!(v.trim(), "1" | "true");
+                    match k.trim() {
+                        "open" => panel.open = b,
+                        "dock" => {
+                            panel.dock = AnalyzerDock::from_prefs_name(v).unwrap_or(panel.dock)
+                        }
+                        "mode" => {
+                            panel.rta_mode = RtaMode::from_prefs_name(v).unwrap_or(panel.rta_mode)
+                        }
+                        "avg" => {
+                            panel.averaging = crate::analyzer::AnalyzerAveraging::from_prefs_name(v)
+                                .unwrap_or(panel.averaging)
+                        }
+                        "hold" => {
+                            panel.peak_hold = crate::analyzer::AnalyzerPeakHold::from_prefs_name(v)
+                                .unwrap_or(panel.peak_hold)
+                        }
+                        "auto_gain" => panel.goniometer_auto_gain = b,
+                        _ => {}
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("playlist=") {
+                let options = &mut self.playlist_options;
+                for part in rest.split(',') {
+                    let Some((k, v)) = part.split_once(':') else {
+                        continue;
+                    };
+                    let b = matches!(v.trim(), "1" | "true");
+                    match k.trim() {
+                        "repeat" => options.repeat_all = b,
+                        "shuffle" => options.shuffle = b,
+                        "xfade_ms" => {
+                            if let Ok(ms) = v.trim().parse::<u32>() {
+                                options.crossfade_ms = ms.min(Self::PLAYLIST_MAX_CROSSFADE_MS);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
             } else if let Some(rest) = line.strip_prefix("inspect_naming=") {
                 // Own key: the regex may contain commas/colons that would
                 // break the inspect_cfg key:value list.
@@ -617,6 +678,12 @@ impl WavesPreviewer {
                 if let Ok(v) = rest.trim().parse::<f32>() {
                     self.loudnorm_dialog_target = v.clamp(-36.0, 0.0);
                 }
+            } else if let Some(rest) = line.strip_prefix("loudnorm_tolerance=") {
+                if let Ok(v) = rest.trim().parse::<f32>() {
+                    self.loudnorm_dialog_tolerance = v.clamp(0.0, 6.0);
+                }
+            } else if let Some(rest) = line.strip_prefix("loudness_layout=") {
+                self.loudness_layout_override = parse_loudness_layout_pref(rest.trim());
             } else if let Some(rest) = line.strip_prefix("export_dither=") {
                 // Legacy boolean key (pre dither-mode): on -> flat TPDF.
                 self.export_cfg.codec.dither_mode =
@@ -629,9 +696,16 @@ impl WavesPreviewer {
                 if let Some(mode) = crate::wave::DitherMode::from_prefs_name(rest) {
                     self.export_cfg.codec.dither_mode = mode;
                 }
+            } else if let Some(rest) = line.strip_prefix("export_downmix=") {
+                if let Some(mode) = crate::wave::DownmixMode::from_prefs_name(rest) {
+                    self.export_cfg.codec.downmix = mode;
+                }
             } else if let Some(rest) = line.strip_prefix("export_dither_24bit=") {
                 self.export_cfg.codec.dither_24bit =
                     matches!(rest.trim(), "1" | "true" | "yes" | "on");
+            } else if let Some(rest) = line.strip_prefix("export_dither_auto_blank=") {
+                self.export_cfg.codec.dither_auto_blank =
+                    matches!(rest.trim(), "1" | "true" | "yes" | "on");
             } else if let Some(rest) = line.strip_prefix("recent_session=") {
                 let raw = rest.trim().trim_matches('"');
                 if !raw.is_empty() {
@@ -706,6 +780,9 @@ impl WavesPreviewer {
         self.set_recent_sessions_from_prefs(recent_sessions);
         self.sanitize_transcript_ai_config();
         self.push_blank_threshold_to_meta_pool();
+        self.push_loudness_layout_to_meta_pool();
+        self.push_analyzer_settings_to_audio();
+        self.push_meter_layout_to_audio();
     }
 
     pub(super) fn save_prefs(&self) {
@@ -755,9 +832,10 @@ impl WavesPreviewer {
             let c = &self.inspection_cfg;
             let b = |v: bool| if v { "1" } else { "0" };
             format!(
-                "tp:{},tp_db:{:.2},loud:{},target:{:.2},tol:{:.2},sil:{},sil_db:{:.1},lead_ms:{:.1},trail_ms:{:.1},loop:{},req_loop:{},naming:{}",
+                "tp:{},tp_db:{:.2},loud:{},target:{:.2},tol:{:.2},lra:{},lra_max:{:.1},st:{},st_max:{:.1},sil:{},sil_db:{:.1},lead_ms:{:.1},trail_ms:{:.1},loop:{},req_loop:{},naming:{}",
                 b(c.check_true_peak), c.tp_ceiling_db, b(c.check_loudness), c.target_lufs,
-                c.lufs_tolerance_lu, b(c.check_silence), c.silence_threshold_dbfs,
+                c.lufs_tolerance_lu, b(c.check_loudness_range), c.max_lra_lu,
+                b(c.check_short_term), c.max_short_term_lufs, b(c.check_silence), c.silence_threshold_dbfs,
                 c.max_leading_silence_ms, c.max_trailing_silence_ms, b(c.check_loop), b(c.require_loop),
                 b(c.check_naming)
             )
@@ -866,6 +944,8 @@ list_col_widths={}\n\
 inspect_cfg={}\n\
 inspect_naming={}\n\
 loudnorm_target={:.2}\n\
+loudnorm_tolerance={:.2}\n\
+loudness_layout={}\n\
 transcript_ai_opt_in={}\n\
 transcript_language={}\n\
 transcript_task={}\n\
@@ -890,9 +970,13 @@ export_srt={}\n\
 export_mp3_kbps={}\n\
 export_aac_kbps={}\n\
 export_ogg_quality={:.2}\n\
+export_opus_kbps={}\n\
+export_opus_complexity={}\n\
 export_dither={}\n\
 export_dither_mode={}\n\
 export_dither_24bit={}\n\
+export_dither_auto_blank={}\n\
+export_downmix={}\n\
 zoo_enabled={}\n\
 zoo_walk_enabled={}\n\
 zoo_voice_enabled={}\n\
@@ -938,6 +1022,8 @@ zoo_flip_manual={}\n",
             inspect_cfg,
             inspect_naming,
             self.loudnorm_dialog_target,
+            self.loudnorm_dialog_tolerance,
+            loudness_layout_pref(self.loudness_layout_override),
             transcript_ai_opt_in,
             self.transcript_ai_cfg.language,
             self.transcript_ai_cfg.task,
@@ -968,6 +1054,8 @@ zoo_flip_manual={}\n",
             self.export_cfg.codec.mp3_bitrate_kbps,
             self.export_cfg.codec.aac_bitrate_kbps,
             self.export_cfg.codec.ogg_quality,
+            self.export_cfg.codec.opus_bitrate_kbps,
+            self.export_cfg.codec.opus_complexity,
             // Legacy boolean kept for older builds reading the same prefs.
             if self.export_cfg.codec.dither_mode != crate::wave::DitherMode::Off {
                 "1"
@@ -980,6 +1068,12 @@ zoo_flip_manual={}\n",
             } else {
                 "0"
             },
+            if self.export_cfg.codec.dither_auto_blank {
+                "1"
+            } else {
+                "0"
+            },
+            self.export_cfg.codec.downmix.prefs_name(),
             zoo_enabled,
             zoo_walk_enabled,
             zoo_voice_enabled,
@@ -1021,6 +1115,28 @@ zoo_flip_manual={}\n",
             out.push_str(if self.watch_folder_enabled { "1" } else { "0" });
             out.push('\n');
         }
+        {
+            let panel = &self.analyzer_panel;
+            let b = |v: bool| if v { "1" } else { "0" };
+            out.push_str(&format!(
+                "analyzer_panel=open:{},dock:{},mode:{},avg:{},hold:{},auto_gain:{}\n",
+                b(panel.open),
+                panel.dock.prefs_name(),
+                panel.rta_mode.prefs_name(),
+                panel.averaging.prefs_name(),
+                panel.peak_hold.prefs_name(),
+                b(panel.goniometer_auto_gain)
+            ));
+        }
+        {
+            let options = &self.playlist_options;
+            out.push_str(&format!(
+                "playlist=repeat:{},shuffle:{},xfade_ms:{}\n",
+                if options.repeat_all { "1" } else { "0" },
+                if options.shuffle { "1" } else { "0" },
+                options.crossfade_ms
+            ));
+        }
         {
             out.push_str("list_col_order=");
             let names: Vec<&str> = self.list_column_order.iter().map(|c| c.name()).collect();
@@ -1051,6 +1167,26 @@ zoo_flip_manual={}\n",
     }
 }
 
+/// `file` (no override) or `<channels>:<mask>` with a hex mask.
+fn loudness_layout_pref(layout: Option<crate::channel_layout::ChannelLayout>) -> String {
+    match layout {
+        Some(layout) => format!("{}:{:#x}", layout.channels(), layout.mask()),
+        None => "file".to_string(),
+    }
+}
+
+fn parse_loudness_layout_pref(value: &str) -> Option<crate::channel_layout::ChannelLayout> {
+    let (channels, mask) = value.split_once(':')?;
+    let channels = channels.trim().parse::<u16>().ok().filter(|&ch| ch > 0)?;
+    let mask = mask.trim();
+    let mask = mask
+        .strip_prefix("0x")
+        .or_else(|| mask.strip_prefix("0X"))
+        .unwrap_or(mask);
+    let mask = u32::from_str_radix(mask, 16).ok()?;
+    Some(crate::channel_layout::ChannelLayout::new(channels, mask))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1287,6 +1423,109 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn loudness_layout_prefs_roundtrip() {
+        use crate::channel_layout::ChannelLayout;
+        let dir = temp_dir("loudness_layout_prefs");
+        let prefs = dir.join("prefs.txt");
+        let mut app =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        assert_eq!(app.loudness_layout_override, None);
+        app.loudness_layout_override = Some(ChannelLayout::new(6, 0x60F));
+        app.save_prefs_to_path(&prefs);
+
+        let mut loaded =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        loaded.load_prefs_from_path(&prefs);
+        assert_eq!(
+            loaded.loudness_layout_override,
+            Some(ChannelLayout::new(6, 0x60F))
+        );
+
+        std::fs::write(&prefs, "loudness_layout=file\n").expect("write prefs");
+        loaded.load_prefs_from_path(&prefs);
+        assert_eq!(loaded.loudness_layout_override, None);
+        std::fs::write(&prefs, "loudness_layout=0:0x3\n").expect("write prefs");
+        loaded.load_prefs_from_path(&prefs);
+        assert_eq!(loaded.loudness_layout_override, None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn analyzer_panel_prefs_roundtrip_and_reach_the_meter_thread() {
+        use crate::analyzer::{AnalyzerAveraging, AnalyzerPeakHold};
+        let dir = temp_dir("analyzer_panel_prefs");
+        let prefs = dir.join("prefs.txt");
+        let mut app =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        assert!(!app.analyzer_panel.open);
+        app.analyzer_panel.open = true;
+        app.analyzer_panel.dock = AnalyzerDock::Bottom;
+        app.analyzer_panel.rta_mode = RtaMode::Fft;
+        app.analyzer_panel.averaging = AnalyzerAveraging::Slow;
+        app.analyzer_panel.peak_hold = AnalyzerPeakHold::Infinite;
+        app.analyzer_panel.goniometer_auto_gain = false;
+        app.save_prefs_to_path(&prefs);
+
+        let mut loaded =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        loaded.load_prefs_from_path(&prefs);
+        let panel = &loaded.analyzer_panel;
+        assert!(panel.open);
+        assert_eq!(panel.dock, AnalyzerDock::Bottom);
+        assert_eq!(panel.rta_mode, RtaMode::Fft);
+        assert_eq!(panel.averaging, AnalyzerAveraging::Slow);
+        assert_eq!(panel.peak_hold, AnalyzerPeakHold::Infinite);
+        assert!(!panel.goniometer_auto_gain);
+        let shared = &loaded.audio.shared.analyzer;
+        assert!(shared.is_enabled());
+        assert_eq!(
+            shared.options(),
+            (AnalyzerAveraging::Slow, AnalyzerPeakHold::Infinite)
+        );
+
+        std::fs::write(&prefs, "analyzer_panel=open:0,dock:sideways,bogus\n").expect("write prefs");
+        loaded.load_prefs_from_path(&prefs);
+        assert!(!loaded.analyzer_panel.open);
+        assert_eq!(loaded.analyzer_panel.dock, AnalyzerDock::Bottom);
+        assert!(!loaded.audio.shared.analyzer.is_enabled());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn playlist_options_prefs_roundtrip() {
+        use crate::app::types::PlaylistOptions;
+        let dir = temp_dir("playlist_prefs");
+        let prefs = dir.join("prefs.txt");
+        let mut app =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        assert_eq!(app.playlist_options, PlaylistOptions::default());
+        app.playlist_options = PlaylistOptions {
+            repeat_all: true,
+            shuffle: true,
+            crossfade_ms: 750,
+        };
+        app.save_prefs_to_path(&prefs);
+
+        let mut loaded =
+            WavesPreviewer::new_headless(crate::StartupConfig::default()).expect("headless app");
+        loaded.load_prefs_from_path(&prefs);
+        assert_eq!(loaded.playlist_options, app.playlist_options);
+
+        std::fs::write(&prefs, "playlist=shuffle:0,xfade_ms:999999,bogus\n").expect("write prefs");
+        loaded.load_prefs_from_path(&prefs);
+        assert!(
+            loaded.playlist_options.repeat_all,
+            "unlisted keys keep their value"
+        );
+        assert!(!loaded.playlist_options.shuffle);
+        assert_eq!(
+            loaded.playlist_options.crossfade_ms,
+            WavesPreviewer::PLAYLIST_MAX_CROSSFADE_MS
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn keymap_override_prefs_roundtrip() {
         use crate::app::keymap::{Action, Mods};
@@ -432,6 +432,7 @@ pub enum SortKey {
     TruePeak,
     LufsShort,
     LufsMomentary,
+    LoudnessRange,
     Bpm,
     SilenceLead,
     SilenceTail,
@@ -490,6 +491,36 @@ pub enum ListUndoActionKind {
         before: Vec<ListUndoItem>,
         after: Vec<ListUndoItem>,
     },
+    /// In-place metadata writes (UCS editor); undo puts the saved chunks
+    /// back and reverts any rename.
+    MetadataWrite {
+        entries: Vec<MetadataWriteUndoEntry>,
+    },
+    /// In-place tag edits (tag editor); undo writes the previous values back.
+    TagWrite {
+        entries: Vec<TagWriteUndoEntry>,
+    },
+    /// Metadata template writes: bext/iXML chunk snapshots for WAV files and
+    /// tag values for the tag formats, undone together.
+    TemplateWrite {
+        chunks: Vec<MetadataWriteUndoEntry>,
+        tags: Vec<TagWriteUndoEntry>,
+    },
+}
+
+#[derive(Clone)]
+pub struct TagWriteUndoEntry {
+    pub path: PathBuf,
+    pub before: crate::tags::AudioTags,
+    pub after: crate::tags::AudioTags,
+}
+
+#[derive(Clone)]
+pub struct MetadataWriteUndoEntry {
+    pub before_path: PathBuf,
+    pub after_path: PathBuf,
+    pub before: crate::wave::WavChunkSnapshot,
+    pub after: crate::wave::WavChunkSnapshot,
 }
 
 #[derive(Clone)]
@@ -521,6 +552,7 @@ pub enum ColumnId {
     Dbtp,
     LufsS,
     LufsM,
+    Lra,
     SilenceLead,
     SilenceTail,
     EdgeZero,
@@ -603,6 +635,7 @@ impl ColumnId {
         ColumnId::Dbtp,
         ColumnId::LufsS,
         ColumnId::LufsM,
+        ColumnId::Lra,
         ColumnId::SilenceLead,
         ColumnId::SilenceTail,
         ColumnId::EdgeZero,
@@ -636,6 +669,7 @@ impl ColumnId {
             ColumnId::Dbtp => "dbtp",
             ColumnId::LufsS => "lufs_s",
             ColumnId::LufsM => "lufs_m",
+            ColumnId::Lra => "lra",
             ColumnId::SilenceLead => "silence_lead",
             ColumnId::SilenceTail => "silence_tail",
             ColumnId::EdgeZero => "edge_zero",
@@ -674,6 +708,7 @@ impl ColumnId {
             ColumnId::Dbtp => "dBTP",
             ColumnId::LufsS => "LUFS-S",
             ColumnId::LufsM => "LUFS-M",
+            ColumnId::Lra => "LRA",
             ColumnId::SilenceLead => "Silence Head",
             ColumnId::SilenceTail => "Silence Tail",
             ColumnId::EdgeZero => "Edge Zero",
@@ -710,6 +745,7 @@ impl ColumnId {
             ColumnId::Dbtp => cols.dbtp,
             ColumnId::LufsS => cols.lufs_s,
             ColumnId::LufsM => cols.lufs_m,
+            ColumnId::Lra => cols.lra,
             ColumnId::SilenceLead => cols.silence_lead,
             ColumnId::SilenceTail => cols.silence_tail,
             ColumnId::EdgeZero => cols.edge_zero,
@@ -744,6 +780,7 @@ impl ColumnId {
             ColumnId::Dbtp => cols.dbtp = enabled,
             ColumnId::LufsS => cols.lufs_s = enabled,
             ColumnId::LufsM => cols.lufs_m = enabled,
+            ColumnId::Lra => cols.lra = enabled,
             ColumnId::SilenceLead => cols.silence_lead = enabled,
             ColumnId::SilenceTail => cols.silence_tail = enabled,
             ColumnId::EdgeZero => cols.edge_zero = enabled,
@@ -791,6 +828,8 @@ pub struct ListColumnConfig {
     pub dbtp: bool,
     pub lufs_s: bool,
     pub lufs_m: bool,
+    /// Loudness range (EBU Tech 3342), full-decode metadata.
+    pub lra: bool,
     pub bpm: bool,
     pub created_at: bool,
     pub modified_at: bool,
@@ -827,6 +866,7 @@ impl Default for ListColumnConfig {
             dbtp: false,
             lufs_s: false,
             lufs_m: false,
+            lra: false,
             bpm: false,
             created_at: false,
             modified_at: false,
@@ -901,6 +941,7 @@ pub enum ViewMode {
     Tempogram,
     Chromagram,
     World,
+    Loudness,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
@@ -917,6 +958,215 @@ pub enum MetadataSubView {
     #[default]
     Structure,
     Hex,
+    Ucs,
+}
+
+/// UCS write-back editor state shared by the Metadata Inspector UCS view
+/// and the batch dialog.
+#[derive(Clone, Debug, Default)]
+pub struct UcsEditorState {
+    pub show_batch_dialog: bool,
+    pub batch_paths: Vec<PathBuf>,
+    /// Batch patch: blank fields keep each file's current value.
+    pub batch_fields: crate::metadata::ucs::UcsFields,
+    pub batch_preview: Vec<UcsBatchPreviewRow>,
+    pub targets: crate::wave::UcsWriteTargets,
+    /// Also rename files to `CatID_FXName_CreatorID_SourceID.ext`.
+    pub rename_files: bool,
+}
+
+/// Tag editor table over the list selection (MP3 / M4A / FLAC / Ogg / Opus).
+#[derive(Clone, Debug, Default)]
+pub struct TagEditorState {
+    pub show_dialog: bool,
+    pub rows: Vec<TagEditorRow>,
+    /// Custom key columns: union of the rows' keys plus ones added in the UI.
+    pub custom_columns: Vec<String>,
+    pub new_column: String,
+    /// "Set all" row: a non-empty cell is copied into every row's draft.
+    pub fill: crate::tags::AudioTags,
+}
+
+#[derive(Clone, Debug)]
+pub struct TagEditorRow {
+    pub path: PathBuf,
+    pub format: Option<crate::tags::TagFormat>,
+    /// Values on disk when the dialog was opened or last written.
+    pub stored: crate::tags::AudioTags,
+    pub draft: crate::tags::AudioTags,
+    pub error: Option<String>,
+}
+
+/// Where "Edit Cart Chunk" takes the post-timers it writes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CartTimerSource {
+    /// Leave each file's timers as they are.
+    #[default]
+    Keep,
+    /// The timer table in the dialog, for every file.
+    Table,
+    /// Each file's own markers labelled with a usage ID (`SEGs`, `cart:XXXX`).
+    Markers,
+}
+
+/// Cart chunk (AES46) dialog over the list selection.
+#[derive(Clone, Debug, Default)]
+pub struct CartEditorState {
+    pub show_dialog: bool,
+    pub paths: Vec<PathBuf>,
+    pub fields: crate::wave::CartFields,
+    /// Fields touched in the dialog; only these overwrite each file's value.
+    pub edited: std::collections::HashSet<crate::wave::CartField>,
+    pub timer_source: CartTimerSource,
+}
+
+/// Metadata template dialog: the template library, the template being
+/// edited and a per-file preview over the list selection.
+#[derive(Clone, Debug, Default)]
+pub struct MetadataTemplateState {
+    pub show_dialog: bool,
+    pub library: Vec<crate::metadata_template::MetadataTemplate>,
+    /// Index of the template being edited.
+    pub selected: usize,
+    /// Library edits not yet saved to the prefs folder.
+    pub library_dirty: bool,
+    pub paths: Vec<PathBuf>,
+    pub preview: Vec<MetadataTemplatePreviewRow>,
+    /// Why the edited template does not compile.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MetadataTemplatePreviewRow {
+    pub path: PathBuf,
+    pub fields: Vec<crate::metadata_template::RenderedField>,
+    pub error: Option<String>,
+}
+
+/// Where the Analyzer panel sits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnalyzerDock {
+    #[default]
+    Right,
+    Bottom,
+    Floating,
+}
+
+impl AnalyzerDock {
+    pub const ALL: [AnalyzerDock; 3] = [
+        AnalyzerDock::Right,
+        AnalyzerDock::Bottom,
+        AnalyzerDock::Floating,
+    ];
+
+    pub fn prefs_name(self) -> &'static str {
+        match self {
+            AnalyzerDock::Right => "right",
+            AnalyzerDock::Bottom => "bottom",
+            AnalyzerDock::Floating => "floating",
+        }
+    }
+
+    pub fn from_prefs_name(s: &str) -> Option<AnalyzerDock> {
+        match s.trim() {
+            "right" => Some(AnalyzerDock::Right),
+            "bottom" => Some(AnalyzerDock::Bottom),
+            "floating" => Some(AnalyzerDock::Floating),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnalyzerDock::Right => "Dock right",
+            AnalyzerDock::Bottom => "Dock bottom",
+            AnalyzerDock::Floating => "Window",
+        }
+    }
+}
+
+/// RTA display: 1/3-octave bars or the log-frequency FFT trace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RtaMode {
+    #[default]
+    ThirdOctave,
+    Fft,
+}
+
+impl RtaMode {
+    pub fn prefs_name(self) -> &'static str {
+        match self {
+            RtaMode::ThirdOctave => "third",
+            RtaMode::Fft => "fft",
+        }
+    }
+
+    pub fn from_prefs_name(s: &str) -> Option<RtaMode> {
+        match s.trim() {
+            "third" => Some(RtaMode::ThirdOctave),
+            "fft" => Some(RtaMode::Fft),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RtaMode::ThirdOctave => "1/3 oct",
+            RtaMode::Fft => "FFT",
+        }
+    }
+}
+
+/// Analyzer panel (RTA, goniometer, correlation history). The analysis runs
+/// on the audio meter thread, and only while `open` is set.
+#[derive(Clone, Debug)]
+pub struct AnalyzerPanelState {
+    pub open: bool,
+    pub dock: AnalyzerDock,
+    pub rta_mode: RtaMode,
+    pub averaging: crate::analyzer::AnalyzerAveraging,
+    pub peak_hold: crate::analyzer::AnalyzerPeakHold,
+    /// Scale the goniometer to the signal instead of to full scale.
+    pub goniometer_auto_gain: bool,
+    /// FFT display columns (live, peak), reused between frames.
+    pub columns: (Vec<f32>, Vec<f32>),
+}
+
+impl Default for AnalyzerPanelState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            dock: AnalyzerDock::default(),
+            rta_mode: RtaMode::default(),
+            averaging: crate::analyzer::AnalyzerAveraging::default(),
+            peak_hold: crate::analyzer::AnalyzerPeakHold::default(),
+            goniometer_auto_gain: true,
+            columns: (Vec::new(), Vec::new()),
+        }
+    }
+}
+
+/// "Set Artwork" dialog: a loaded source image and how to embed it into
+/// the target files.
+#[derive(Clone, Debug, Default)]
+pub struct ArtworkDialogState {
+    pub show_dialog: bool,
+    pub targets: Vec<PathBuf>,
+    pub source: Vec<u8>,
+    /// File name or "Clipboard".
+    pub source_label: String,
+    /// Decoded source size, for the resize hint.
+    pub source_size: Option<(u32, u32)>,
+    pub options: crate::artwork::ArtworkOptions,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct UcsBatchPreviewRow {
+    pub path: PathBuf,
+    pub fields: crate::metadata::ucs::UcsFields,
+    pub new_name: Result<String, String>,
+    pub error: Option<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
@@ -938,8 +1188,11 @@ pub struct MetadataHexPage {
 pub enum MetadataActionResult {
     Search(Vec<u64>),
     Hash(String),
+    Repaired(std::path::PathBuf, crate::metadata::repair::RepairReport),
+    ChunksEdited(crate::metadata::chunk_edit::ChunkEditReport),
     Extracted(std::path::PathBuf),
     CopyHex(String),
+    Diffed(crate::metadata::diff::MetadataDiff),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
@@ -956,6 +1209,7 @@ pub enum EditorOtherSubView {
     #[default]
     Tempogram,
     Chromagram,
+    Loudness,
 }
 
 impl EditorPrimaryView {
@@ -963,7 +1217,9 @@ impl EditorPrimaryView {
         match mode {
             ViewMode::Waveform => Self::Wave,
             ViewMode::Spectrogram | ViewMode::Log | ViewMode::Mel => Self::Spec,
-            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World => Self::Other,
+            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World | ViewMode::Loudness => {
+                Self::Other
+            }
         }
     }
 
@@ -1001,6 +1257,7 @@ impl EditorOtherSubView {
             ViewMode::Chromagram => Self::Chromagram,
             ViewMode::Tempogram => Self::Tempogram,
             ViewMode::World => Self::World,
+            ViewMode::Loudness => Self::Loudness,
             _ => Self::Tempogram,
         }
     }
@@ -1010,6 +1267,7 @@ impl EditorOtherSubView {
             Self::World => ViewMode::World,
             Self::Tempogram => ViewMode::Tempogram,
             Self::Chromagram => ViewMode::Chromagram,
+            Self::Loudness => ViewMode::Loudness,
         }
     }
 }
@@ -1245,6 +1503,41 @@ impl ChannelRoutingDraft {
     pub fn total_links(&self) -> usize {
         self.sources.iter().map(|s| s.len()).sum()
     }
+
+    /// Speaker layout of the routed result. Outputs fed by exactly one input
+    /// keep that input's speaker; anything else (mixes, silent outputs,
+    /// reordering) falls back to the default layout for the output count.
+    pub fn routed_layout(
+        &self,
+        input: &crate::channel_layout::ChannelLayout,
+    ) -> crate::channel_layout::ChannelLayout {
+        let speakers: Vec<Option<u32>> = self
+            .sources
+            .iter()
+            .map(|srcs| match srcs.as_slice() {
+                [src] => input.speaker_bit(*src),
+                _ => None,
+            })
+            .collect();
+        crate::channel_layout::ChannelLayout::from_speakers(&speakers)
+    }
+
+    /// ADM names of the routed result: single-input outputs keep their
+    /// input's names, mixed or silent outputs have none.
+    pub fn routed_adm_lanes(
+        &self,
+        input: &[crate::adm::AdmLane],
+    ) -> Option<Vec<crate::adm::AdmLane>> {
+        let lanes: Vec<crate::adm::AdmLane> = self
+            .sources
+            .iter()
+            .map(|srcs| match srcs.as_slice() {
+                [src] => input.get(*src).cloned().unwrap_or_default(),
+                _ => crate::adm::AdmLane::default(),
+            })
+            .collect();
+        lanes.iter().any(|lane| !lane.is_empty()).then_some(lanes)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -1431,6 +1724,57 @@ pub struct VariationAuditionState {
     pub rng: u64,
 }
 
+/// Gapless list playlist options (persisted in prefs).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PlaylistOptions {
+    /// Start over after the last row (with a fresh order when shuffling).
+    pub repeat_all: bool,
+    pub shuffle: bool,
+    /// Equal-power crossfade between consecutive rows; 0 = butt splice.
+    pub crossfade_ms: u32,
+}
+
+/// One playlist segment rendered by the worker (channels at the output
+/// rate, crossfade with the previous row already baked in).
+pub struct PlaylistSegmentResult {
+    /// Index into `PlaylistPlaybackState::paths`.
+    pub path_index: usize,
+    pub segment: Result<Vec<Vec<f32>>, String>,
+    /// Withheld end of this row, mixed into the head of the next segment
+    /// (on a decode error: the incoming tail, handed back unused).
+    pub tail: Vec<Vec<f32>>,
+}
+
+/// Running gapless playlist: the next row renders in the background while
+/// the current one plays and is queued on the engine for a sample-accurate
+/// handoff at the end.
+pub struct PlaylistPlaybackState {
+    pub paths: Vec<std::path::PathBuf>,
+    /// Play order of the current pass (indices into `paths`).
+    pub order: Vec<usize>,
+    /// Position in `order` of the row most recently sent to the renderer.
+    pub render_cursor: usize,
+    pub render_rx: Option<std::sync::mpsc::Receiver<PlaylistSegmentResult>>,
+    /// End of the last rendered row, owed to the head of the next segment.
+    pub carry_tail: Vec<Vec<f32>>,
+    /// Segment playing now and the one queued behind it, with their row
+    /// indices. Held here so the callback's handoff only drops a refcount.
+    pub current: Option<(usize, std::sync::Arc<crate::audio::AudioBuffer>)>,
+    pub queued: Option<(usize, std::sync::Arc<crate::audio::AudioBuffer>)>,
+    /// Engine handoff counter as of the last poll.
+    pub handoffs_seen: usize,
+    /// Nothing is playing yet, or the current segment ended before the next
+    /// one was ready: the drain starts the next segment directly.
+    pub awaiting_segment: bool,
+    /// The current segment was actually heard playing (see
+    /// `VariationAuditionState::item_started`).
+    pub item_started: bool,
+    /// Rows started so far (for the "Playlist 3/12" display).
+    pub played: usize,
+    /// LCG state for shuffling.
+    pub rng: u64,
+}
+
 /// In-app audio clipboard for editor cut/copy/paste-insert (sample data at
 /// the source tab's buffer rate; adapted on paste).
 #[derive(Clone, Debug)]
@@ -2217,6 +2561,19 @@ pub struct EditorTab {
     pub metadata_action_cancel: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
     pub metadata_action_progress: Option<std::sync::Arc<std::sync::atomic::AtomicU64>>,
     pub metadata_action_total: u64,
+    pub metadata_repair_keep_unknown: bool,
+    /// Output path and report of the last "Repair to Copy" run.
+    pub metadata_repair_report: Option<(PathBuf, crate::metadata::repair::RepairReport)>,
+    /// Chunk editor "Insert" inputs and whether writes replace the source
+    /// (keeping `<name>.bak`) instead of saving a copy.
+    pub metadata_chunk_insert_id: String,
+    pub metadata_chunk_insert_hex: String,
+    pub metadata_chunk_in_place: bool,
+    /// Document as it was before the last in-place write, kept across the
+    /// rescan so the inspector can diff against the pre-save state.
+    pub metadata_presave: Option<std::sync::Arc<crate::metadata::MetadataDocument>>,
+    pub metadata_diff: Option<crate::metadata::diff::MetadataDiff>,
+    pub metadata_diff_only_changes: bool,
     pub metadata_artwork_requested: Option<crate::metadata::NodeId>,
     pub metadata_artwork_rx: Option<
         std::sync::mpsc::Receiver<
@@ -2224,6 +2581,9 @@ pub struct EditorTab {
         >,
     >,
     pub metadata_artwork_texture: Option<(crate::metadata::NodeId, egui::TextureHandle)>,
+    /// Unsaved UCS editor values; loaded from the scanned document when
+    /// `None`.
+    pub metadata_ucs_draft: Option<crate::metadata::ucs::UcsFields>,
     pub show_waveform_overlay: bool, // draw waveform overlay in feature views
     pub channel_view: ChannelView,   // Mixdown / All / Custom
     pub bpm_enabled: bool,           // grid toggle in editor
@@ -2254,6 +2614,12 @@ pub struct EditorTab {
     pub plugin_fx_draft: PluginFxDraft,
     pub plugin_fx_chain: PluginFxChainDraft,
     pub channel_routing_draft: ChannelRoutingDraft,
+    /// Speaker layout the file declares (WAVE_FORMAT_EXTENSIBLE mask); see
+    /// [`EditorTab::channel_layout`] for the effective one.
+    pub channel_layout: Option<crate::channel_layout::ChannelLayout>,
+    /// ADM object / pack names per channel from a BW64 `chna`/`axml`; see
+    /// [`EditorTab::adm_lanes`].
+    pub adm_lanes: Option<Vec<crate::adm::AdmLane>>,
     pub pending_loop_unwrap: Option<u32>,
     pub undo_stack: Vec<EditorUndoState>,
     pub undo_bytes: usize,
@@ -2471,11 +2837,22 @@ impl EditorTab {
             metadata_action_cancel: None,
             metadata_action_progress: None,
             metadata_action_total: 0,
+            metadata_repair_keep_unknown: true,
+            metadata_repair_report: None,
+            metadata_chunk_insert_id: String::new(),
+            metadata_chunk_insert_hex: String::new(),
+            metadata_chunk_in_place: false,
+            metadata_presave: None,
+            metadata_diff: None,
+            metadata_diff_only_changes: true,
             metadata_artwork_requested: None,
             metadata_artwork_rx: None,
             metadata_artwork_texture: None,
+            metadata_ucs_draft: None,
             show_waveform_overlay: false,
             channel_view: ChannelView::mixdown(),
+            channel_layout: None,
+            adm_lanes: None,
             bpm_enabled: false,
             bpm_value: 120.0,
             bpm_user_set: false,
@@ -2554,6 +2931,22 @@ impl EditorTab {
             EditorPrimaryView::Metadata => {}
         }
     }
+
+    /// Effective speaker layout: the declared one while it still matches the
+    /// channel count, otherwise the default layout for `ch_samples`.
+    pub fn channel_layout(&self) -> crate::channel_layout::ChannelLayout {
+        let channels = self.ch_samples.len().min(u16::MAX as usize) as u16;
+        self.channel_layout
+            .filter(|layout| layout.channels() == channels)
+            .unwrap_or_else(|| crate::channel_layout::ChannelLayout::default_for(channels))
+    }
+
+    /// ADM names per lane, while they still match the channel count.
+    pub fn adm_lanes(&self) -> Option<&[crate::adm::AdmLane]> {
+        self.adm_lanes
+            .as_deref()
+            .filter(|lanes| lanes.len() == self.ch_samples.len())
+    }
 }
 
 /// Absolute value of the very first and very last frame, maxed across
@@ -2582,6 +2975,49 @@ pub struct BlankPadScan {
     pub all_silent: bool,
 }
 
+/// Speaker layout the loudness columns were weighted by (BS.1770 channel
+/// weights). Whether the user override supplied it rides along, as with
+/// [`BlankPadScan`], so a changed override is detected per row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoudnessLayout {
+    pub layout: crate::channel_layout::ChannelLayout,
+    pub overridden: bool,
+}
+
+impl LoudnessLayout {
+    /// Layout for a `channels`-channel file: the override when it has that
+    /// many channels, else the declared layout when it does, else the
+    /// default for the channel count.
+    pub fn resolve(
+        channels: usize,
+        declared: Option<crate::channel_layout::ChannelLayout>,
+        override_layout: Option<crate::channel_layout::ChannelLayout>,
+    ) -> Self {
+        let count = channels.min(u16::MAX as usize) as u16;
+        if let Some(layout) = override_layout.filter(|layout| layout.channels() == count) {
+            return Self {
+                layout,
+                overridden: true,
+            };
+        }
+        Self {
+            layout: declared
+                .filter(|layout| layout.channels() == count)
+                .unwrap_or_else(|| crate::channel_layout::ChannelLayout::default_for(count)),
+            overridden: false,
+        }
+    }
+
+    /// True when a measurement taken with this layout no longer matches
+    /// what `override_layout` would pick.
+    pub fn is_stale(&self, override_layout: Option<crate::channel_layout::ChannelLayout>) -> bool {
+        match override_layout.filter(|layout| layout.channels() == self.layout.channels()) {
+            Some(layout) => !self.overridden || self.layout != layout,
+            None => self.overridden,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FileMeta {
     pub channels: u16,
@@ -2602,8 +3038,12 @@ pub struct FileMeta {
     pub lufs_m_max: Option<f32>,
     /// Maximum short-term loudness (3 s, ungated), full decode only.
     pub lufs_s_max: Option<f32>,
+    /// Loudness range (EBU Tech 3342), full decode only.
+    pub lra_lu: Option<f32>,
     /// True peak (BS.1770-4 Annex 2, oversampled), full decode only.
     pub true_peak_db: Option<f32>,
+    /// Layout the loudness values above were weighted by, full decode only.
+    pub loudness_layout: Option<LoudnessLayout>,
     pub bpm: Option<f32>,
     /// Leading/trailing silence (-60 dBFS threshold) in ms, full decode only.
     pub silence_lead_ms: Option<f32>,
@@ -2725,6 +3165,7 @@ pub enum EditorAnalysisKind {
     Tempogram,
     Chromagram,
     World,
+    Loudness,
 }
 
 #[derive(Clone, Debug)]
@@ -2783,6 +3224,7 @@ pub enum EditorFeatureAnalysisData {
     // Arc so render requests and jobs can share the (potentially large)
     // analysis without deep-cloning it on the UI thread.
     World(Arc<WorldFeatureData>),
+    Loudness(crate::wave::LoudnessTimeline),
 }
 
 pub enum EditorFeatureAnalysisJobMsg {
@@ -2801,6 +3243,11 @@ pub enum EditorFeatureAnalysisJobMsg {
         generation: u64,
         data: Arc<WorldFeatureData>,
     },
+    LoudnessDone {
+        path: PathBuf,
+        generation: u64,
+        data: crate::wave::LoudnessTimeline,
+    },
 }
 
 pub struct AnalysisProgress {
@@ -2933,6 +3380,8 @@ pub struct SessionSidecarJob {
     pub dst: PathBuf,
     pub source: SessionSidecarSource,
     pub sample_rate: u32,
+    /// Speaker layout to declare in the sidecar WAV (edited tab/cache audio).
+    pub channel_layout: Option<crate::channel_layout::ChannelLayout>,
     pub label: &'static str,
 }
 
@@ -3111,6 +3560,8 @@ pub struct EditorUndoState {
     pub active_tool: ToolKind,
     pub plugin_fx_draft: PluginFxDraft,
     pub show_waveform_overlay: bool,
+    pub channel_layout: Option<crate::channel_layout::ChannelLayout>,
+    pub adm_lanes: Option<Vec<crate::adm::AdmLane>>,
     pub dirty: bool,
     pub approx_bytes: usize,
     pub markers: Vec<MarkerEntry>,
@@ -3161,6 +3612,8 @@ pub struct CachedEdit {
     pub plugin_fx_draft: PluginFxDraft,
     pub plugin_fx_chain: PluginFxChainDraft,
     pub show_waveform_overlay: bool,
+    pub channel_layout: Option<crate::channel_layout::ChannelLayout>,
+    pub adm_lanes: Option<Vec<crate::adm::AdmLane>>,
     pub applied_effect_graph: Option<AppliedEffectGraphStamp>,
 }
 
@@ -3571,6 +4024,41 @@ impl EffectGraphBitDepth {
     }
 }
 
+/// Node-local dither choice for the [`EffectGraphNodeData::BitDepth`] node.
+/// Mirrors `wave::DitherMode` for the same reason as [`EffectGraphBitDepth`];
+/// defaults to `Off` so graphs saved before dither existed keep quantizing by
+/// plain rounding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectGraphDither {
+    #[default]
+    Off,
+    Tpdf,
+    TpdfHighpass,
+    TpdfNoiseShaped,
+    TpdfShaped,
+}
+
+impl EffectGraphDither {
+    pub const ALL: [EffectGraphDither; 5] = [
+        Self::Off,
+        Self::Tpdf,
+        Self::TpdfHighpass,
+        Self::TpdfNoiseShaped,
+        Self::TpdfShaped,
+    ];
+
+    pub fn to_wave_dither_mode(self) -> crate::wave::DitherMode {
+        match self {
+            Self::Off => crate::wave::DitherMode::Off,
+            Self::Tpdf => crate::wave::DitherMode::Tpdf,
+            Self::TpdfHighpass => crate::wave::DitherMode::TpdfHighpass,
+            Self::TpdfNoiseShaped => crate::wave::DitherMode::TpdfNoiseShaped,
+            Self::TpdfShaped => crate::wave::DitherMode::TpdfShaped,
+        }
+    }
+}
+
 /// Node-local resample quality for the [`EffectGraphNodeData::Resampler`]
 /// node. Kept separate from `wave::ResampleQuality` (not serde-enabled) for
 /// the same reason as [`EffectGraphBitDepth`].
@@ -3645,6 +4133,11 @@ pub enum EffectGraphNodeData {
     },
     BitDepth {
         depth: EffectGraphBitDepth,
+        #[serde(default)]
+        dither: EffectGraphDither,
+        /// Emit exact zeros (no dither) over runs of digital silence.
+        #[serde(default)]
+        auto_blank: bool,
     },
     Resampler {
         target_sample_rate: u32,
@@ -3761,6 +4254,8 @@ impl EffectGraphNodeData {
             },
             EffectGraphNodeKind::BitDepth => Self::BitDepth {
                 depth: EffectGraphBitDepth::Pcm16,
+                dither: EffectGraphDither::Off,
+                auto_blank: false,
             },
             EffectGraphNodeKind::Resampler => Self::Resampler {
                 target_sample_rate: 48_000,
@@ -5246,6 +5741,32 @@ impl Default for RecordingTabState {
     }
 }
 
+#[cfg(test)]
+mod loudness_layout_tests {
+    use super::LoudnessLayout;
+    use crate::channel_layout::ChannelLayout;
+
+    #[test]
+    fn override_wins_only_for_its_channel_count_and_marks_stale_rows() {
+        let side = ChannelLayout::new(8, 0xFF);
+        let declared = LoudnessLayout::resolve(8, Some(side), None);
+        assert_eq!(declared.layout, side);
+        assert!(!declared.overridden);
+        let seven = ChannelLayout::default_for(8);
+        let forced = LoudnessLayout::resolve(8, Some(side), Some(seven));
+        assert_eq!(forced.layout, seven);
+        assert!(forced.overridden);
+        // A 7.1 override leaves stereo files on their own layout.
+        let stereo = LoudnessLayout::resolve(2, None, Some(seven));
+        assert_eq!(stereo.layout, ChannelLayout::default_for(2));
+        assert!(!stereo.is_stale(Some(seven)));
+        assert!(declared.is_stale(Some(seven)));
+        assert!(!forced.is_stale(Some(seven)));
+        assert!(forced.is_stale(None));
+        assert!(!declared.is_stale(None));
+    }
+}
+
 #[cfg(test)]
 mod playback_timeline_tests {
     use super::PlaybackTimelineMap;
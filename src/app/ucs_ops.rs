@@ -0,0 +1,235 @@
+//! UCS write-back: validated UCS fields written into WAV iXML (ASWG/USER)
+//! and bext, optional UCS renames, and list undo for both.
+
+use std::path::{Path, PathBuf};
+
+use crate::app::types::{
+    ListUndoAction, ListUndoActionKind, MetadataWriteUndoEntry, ToastSeverity, UcsBatchPreviewRow,
+};
+use crate::metadata::ucs::{UcsFields, UcsIssueLevel};
+
+/// Chunks a UCS write can touch; snapshotted for undo.
+const UCS_UNDO_CHUNKS: [[u8; 4]; 2] = [*b"bext", *b"iXML"];
+
+fn is_wav_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false)
+}
+
+/// UCS file name for `path` (same extension), or why none can be built.
+pub(super) fn ucs_file_name(path: &Path, fields: &UcsFields) -> Result<String, String> {
+    let stem = fields.filename_stem()?;
+    Ok(match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if !ext.is_empty() => format!("{stem}.{ext}"),
+        _ => stem,
+    })
+}
+
+fn first_error(fields: &UcsFields) -> Option<String> {
+    fields
+        .validate()
+        .into_iter()
+        .find(|issue| issue.level == UcsIssueLevel::Error)
+        .map(|issue| issue.message)
+}
+
+impl crate::app::WavesPreviewer {
+    pub(super) fn open_ucs_batch_dialog(&mut self) {
+        self.ucs_editor.batch_paths = self.selected_paths();
+        self.ucs_editor.batch_fields = UcsFields::default();
+        self.ucs_editor.batch_preview.clear();
+        self.ucs_editor.show_batch_dialog = true;
+    }
+
+    /// Resolve the batch patch against every target file's current values.
+    pub(super) fn build_ucs_batch_preview(&mut self) {
+        let patch = self.ucs_editor.batch_fields.clone();
+        let rename = self.ucs_editor.rename_files;
+        self.ucs_editor.batch_preview = self
+            .ucs_editor
+            .batch_paths
+            .iter()
+            .map(|path| {
+                if !is_wav_path(path) {
+                    return UcsBatchPreviewRow {
+                        path: path.clone(),
+                        fields: UcsFields::default(),
+                        new_name: Err("not a WAV file".to_string()),
+                        error: Some("not a WAV file".to_string()),
+                    };
+                }
+                match crate::wave::read_wav_ucs(path) {
+                    Ok(current) => {
+                        let fields = current.overlay(&patch).resolved();
+                        let new_name = if rename {
+                            ucs_file_name(path, &fields)
+                        } else {
+                            Err("rename disabled".to_string())
+                        };
+                        let error = first_error(&fields)
+                            .or_else(|| rename.then(|| new_name.as_ref().err().cloned()).flatten());
+                        UcsBatchPreviewRow {
+                            path: path.clone(),
+                            fields,
+                            new_name,
+                            error,
+                        }
+                    }
+                    Err(err) => UcsBatchPreviewRow {
+                        path: path.clone(),
+                        fields: UcsFields::default(),
+                        new_name: Err(err.to_string()),
+                        error: Some(err.to_string()),
+                    },
+                }
+            })
+            .collect();
+    }
+
+    pub(super) fn apply_ucs_batch(&mut self) {
+        self.build_ucs_batch_preview();
+        let mut plan = Vec::new();
+        let mut skipped = 0usize;
+        for row in &self.ucs_editor.batch_preview {
+            if row.error.is_some() {
+                skipped += 1;
+            } else {
+                plan.push((row.path.clone(), row.fields.clone()));
+            }
+        }
+        self.write_ucs_fields(plan, skipped);
+        self.ucs_editor.show_batch_dialog = false;
+    }
+
+    /// Write each `(path, fields)` entry (renaming when enabled) and record
+    /// one list undo step for the whole run. Returns the final paths of the
+    /// files that were written.
+    pub(super) fn write_ucs_fields(
+        &mut self,
+        plan: Vec<(PathBuf, UcsFields)>,
+        skipped: usize,
+    ) -> Vec<PathBuf> {
+        let targets = self.ucs_editor.targets;
+        let rename = self.ucs_editor.rename_files;
+        let selection_before = self.capture_list_selection_snapshot();
+        let mut entries = Vec::new();
+        let mut failures: Vec<String> = Vec::new();
+        for (path, fields) in plan {
+            let fields = fields.resolved();
+            if let Some(error) = first_error(&fields) {
+                failures.push(format!("{}: {error}", path.display()));
+                continue;
+            }
+            let new_name = if rename {
+                match ucs_file_name(&path, &fields) {
+                    Ok(name) => Some(name),
+                    Err(error) => {
+                        failures.push(format!("{}: {error}", path.display()));
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+            let before = match crate::wave::snapshot_wav_chunks(&path, &UCS_UNDO_CHUNKS) {
+                Ok(snapshot) => snapshot,
+                Err(error) => {
+                    failures.push(format!("{}: {error}", path.display()));
+                    continue;
+                }
+            };
+            let written = crate::wave::write_wav_ucs(&path, &fields, targets)
+                .and_then(|()| crate::wave::snapshot_wav_chunks(&path, &UCS_UNDO_CHUNKS));
+            let after = match written {
+                Ok(snapshot) => snapshot,
+                Err(error) => {
+                    failures.push(format!("{}: {error}", path.display()));
+                    continue;
+                }
+            };
+            let mut after_path = path.clone();
+            if let Some(name) = new_name {
+                match self.rename_file_path(&path, &name) {
+                    Ok(renamed) => after_path = renamed,
+                    Err(error) => {
+                        failures.push(format!("{}: metadata written, {error}", path.display()))
+                    }
+                }
+            }
+            self.invalidate_metadata_for_path(&after_path);
+            entries.push(MetadataWriteUndoEntry {
+                before_path: path,
+                after_path,
+                before,
+                after,
+            });
+        }
+        let written: Vec<PathBuf> = entries.iter().map(|e| e.after_path.clone()).collect();
+        if !entries.is_empty() {
+            let selection_after = self.capture_list_selection_snapshot();
+            self.push_list_undo_action(ListUndoAction {
+                kind: ListUndoActionKind::MetadataWrite { entries },
+                before: selection_before,
+                after: selection_after,
+            });
+        }
+        let severity = if failures.is_empty() {
+            ToastSeverity::Info
+        } else {
+            ToastSeverity::Warning
+        };
+        let mut message = format!("UCS metadata: wrote {} file(s)", written.len());
+        if skipped > 0 {
+            message.push_str(&format!(", skipped {skipped}"));
+        }
+        if let Some(first) = failures.first() {
+            message.push_str(&format!(", {} failed ({first})", failures.len()));
+        }
+        self.push_toast(severity, message);
+        written
+    }
+
+    /// Undo/redo of [`ListUndoActionKind::MetadataWrite`]: restore the saved
+    /// chunks and move renamed files back (or forward again).
+    pub(super) fn apply_metadata_write_entries(
+        &mut self,
+        entries: &[MetadataWriteUndoEntry],
+        undo: bool,
+    ) {
+        let mut failed = 0usize;
+        for entry in entries.iter().rev() {
+            let (from, to, snapshot) = if undo {
+                (&entry.after_path, &entry.before_path, &entry.before)
+            } else {
+                (&entry.before_path, &entry.after_path, &entry.after)
+            };
+            if from != to {
+                if to.exists() {
+                    failed += 1;
+                    continue;
+                }
+                if std::fs::rename(from, to).is_err() {
+                    failed += 1;
+                    continue;
+                }
+                self.replace_path_in_state(from, to);
+            }
+            if crate::wave::restore_wav_chunks(to, snapshot).is_err() {
+                failed += 1;
+            }
+            self.invalidate_metadata_for_path(to);
+        }
+        self.refresh_filter_then_sort();
+        if failed > 0 {
+            self.push_toast(
+                ToastSeverity::Warning,
+                format!(
+                    "UCS metadata {}: {failed} file(s) could not be restored",
+                    if undo { "undo" } else { "redo" }
+                ),
+            );
+        }
+    }
+}
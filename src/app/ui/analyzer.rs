@@ -0,0 +1,504 @@
+use egui::{Color32, Stroke};
+
+use crate::analyzer::{
+    fft_log_columns, AnalyzerAveraging, AnalyzerFrame, AnalyzerPeakHold, ANALYZER_DB_FLOOR,
+    ANALYZER_MIN_HZ, CORRELATION_HISTORY_LEN, THIRD_OCTAVE_CENTERS_HZ,
+};
+use crate::app::types::{AnalyzerDock, RtaMode};
+
+const PANEL_BG: Color32 = Color32::from_rgb(16, 18, 23);
+const GRID: Color32 = Color32::from_rgb(34, 39, 48);
+const LABEL: Color32 = Color32::from_rgb(120, 132, 150);
+const PEAK: Color32 = Color32::from_rgb(235, 200, 90);
+
+impl crate::app::WavesPreviewer {
+    /// Docked Analyzer panel. Runs before the workspace's CentralPanel so
+    /// the panel takes its space from the workspace.
+    pub(in crate::app) fn ui_analyzer_dock(&mut self, ui: &mut egui::Ui) {
+        if !self.analyzer_panel.open {
+            return;
+        }
+        match self.analyzer_panel.dock {
+            AnalyzerDock::Right => {
+                egui::Panel::right("analyzer_panel")
+                    .resizable(true)
+                    .default_size(340.0)
+                    .size_range(egui::Rangef::new(240.0, 720.0))
+                    .show_inside(ui, |ui| self.ui_analyzer_contents(ui));
+            }
+            AnalyzerDock::Bottom => {
+                egui::Panel::bottom("analyzer_panel_bottom")
+                    .resizable(true)
+                    .default_size(230.0)
+                    .size_range(egui::Rangef::new(160.0, 520.0))
+                    .show_inside(ui, |ui| self.ui_analyzer_contents(ui));
+            }
+            AnalyzerDock::Floating => {}
+        }
+    }
+
+    /// The Analyzer as a floating window (dock = Window).
+    pub(in crate::app) fn ui_analyzer_window(&mut self, ctx: &egui::Context) {
+        if !self.analyzer_panel.open || self.analyzer_panel.dock != AnalyzerDock::Floating {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Analyzer")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([460.0, 380.0])
+            .show(ctx, |ui| self.ui_analyzer_contents(ui));
+        if !open {
+            self.set_analyzer_panel_open(false);
+        }
+    }
+
+    fn ui_analyzer_contents(&mut self, ui: &mut egui::Ui) {
+        let mut options_changed = false;
+        let mut prefs_changed = false;
+        let mut reset_peaks = false;
+        let mut close = false;
+        let state = &mut self.analyzer_panel;
+        ui.horizontal_wrapped(|ui| {
+            for mode in [RtaMode::ThirdOctave, RtaMode::Fft] {
+                if ui
+                    .selectable_label(state.rta_mode == mode, mode.label())
+                    .clicked()
+                {
+                    state.rta_mode = mode;
+                    prefs_changed = true;
+                }
+            }
+            egui::ComboBox::from_id_salt("analyzer_averaging")
+                .selected_text(format!("Avg: {}", state.averaging.label()))
+                .show_ui(ui, |ui| {
+                    for averaging in AnalyzerAveraging::ALL {
+                        options_changed |= ui
+                            .selectable_value(&mut state.averaging, averaging, averaging.label())
+                            .changed();
+                    }
+                });
+            egui::ComboBox::from_id_salt("analyzer_peak_hold")
+                .selected_text(format!("Hold: {}", state.peak_hold.label()))
+                .show_ui(ui, |ui| {
+                    for hold in AnalyzerPeakHold::ALL {
+                        options_changed |= ui
+                            .selectable_value(&mut state.peak_hold, hold, hold.label())
+                            .changed();
+                    }
+                });
+            if ui.button("Reset peaks").clicked() {
+                reset_peaks = true;
+            }
+            prefs_changed |= ui
+                .checkbox(&mut state.goniometer_auto_gain, "Auto gain")
+                .on_hover_text("Scale the goniometer to the signal instead of to full scale")
+                .changed();
+            egui::ComboBox::from_id_salt("analyzer_dock")
+                .selected_text(state.dock.label())
+                .show_ui(ui, |ui| {
+                    for dock in AnalyzerDock::ALL {
+                        prefs_changed |= ui
+                            .selectable_value(&mut state.dock, dock, dock.label())
+                            .changed();
+                    }
+                });
+            if state.dock != AnalyzerDock::Floating
+                && ui.small_button("x").on_hover_text("Close").clicked()
+            {
+                close = true;
+            }
+        });
+        ui.separator();
+
+        let frame = self.audio.shared.analyzer.latest();
+        let frame = frame.as_deref();
+        let state = &mut self.analyzer_panel;
+        let size = ui.available_size().max(egui::vec2(160.0, 140.0));
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let gap = 6.0;
+        let strip_h = (rect.height() * 0.18).clamp(28.0, 56.0);
+        let (rta_rect, gonio_rect, strip_rect) = if rect.width() > rect.height() * 1.3 {
+            // Wide (bottom dock): [RTA over correlation][goniometer].
+            let side = rect.height().min(rect.width() * 0.4);
+            let gonio = egui::Rect::from_min_size(
+                egui::pos2(rect.right() - side, rect.top()),
+                egui::vec2(side, side),
+            );
+            let left =
+                egui::Rect::from_min_max(rect.min, egui::pos2(gonio.left() - gap, rect.bottom()));
+            let strip = egui::Rect::from_min_max(
+                egui::pos2(left.left(), left.bottom() - strip_h),
+                left.max,
+            );
+            let rta =
+                egui::Rect::from_min_max(left.min, egui::pos2(left.right(), strip.top() - gap));
+            (rta, gonio, strip)
+        } else {
+            // Tall (right dock / window): RTA, goniometer, correlation.
+            let side = rect
+                .width()
+                .min((rect.height() - strip_h - gap * 2.0) * 0.5);
+            let rta = egui::Rect::from_min_size(
+                rect.min,
+                egui::vec2(rect.width(), rect.height() - side - strip_h - gap * 2.0),
+            );
+            let gonio = egui::Rect::from_center_size(
+                egui::pos2(rect.center().x, rta.bottom() + gap + side * 0.5),
+                egui::vec2(side, side),
+            );
+            let strip = egui::Rect::from_min_max(
+                egui::pos2(rect.left(), rect.bottom() - strip_h),
+                rect.max,
+            );
+            (rta, gonio, strip)
+        };
+        draw_rta(
+            &painter,
+            rta_rect,
+            frame,
+            state.rta_mode,
+            &mut state.columns,
+        );
+        draw_goniometer(&painter, gonio_rect, frame, state.goniometer_auto_gain);
+        draw_correlation_strip(&painter, strip_rect, frame);
+        if frame.is_none() {
+            painter.text(
+                rta_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Not playing",
+                egui::FontId::proportional(12.0),
+                LABEL,
+            );
+        }
+        let playing = self
+            .audio
+            .shared
+            .playing
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if frame.is_some() || playing {
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(30));
+        }
+
+        if reset_peaks {
+            self.audio.shared.analyzer.request_peak_reset();
+        }
+        if options_changed {
+            self.push_analyzer_settings_to_audio();
+        }
+        if close {
+            self.set_analyzer_panel_open(false);
+        } else if options_changed || prefs_changed {
+            self.save_prefs();
+        }
+    }
+}
+
+/// Log-frequency x position of `hz` inside `rect` (same axis as
+/// [`fft_log_columns`]).
+fn freq_x(rect: egui::Rect, hz: f32, sample_rate: u32) -> f32 {
+    let nyquist = sample_rate.max(1) as f32 * 0.5;
+    let f_lo = ANALYZER_MIN_HZ.min(nyquist * 0.25);
+    let t = (hz / f_lo).ln() / (nyquist / f_lo).max(1.0001).ln();
+    rect.left() + t.clamp(0.0, 1.0) * rect.width()
+}
+
+fn draw_rta(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    frame: Option<&AnalyzerFrame>,
+    mode: RtaMode,
+    columns: &mut (Vec<f32>, Vec<f32>),
+) {
+    let font = egui::FontId::monospace(9.0);
+    painter.rect_filled(rect, 4.0, PANEL_BG);
+    let plot = egui::Rect::from_min_max(
+        rect.min + egui::vec2(26.0, 14.0),
+        rect.max - egui::vec2(4.0, 12.0),
+    );
+    let y_of = |db: f32| {
+        let t = ((db - ANALYZER_DB_FLOOR) / -ANALYZER_DB_FLOOR).clamp(0.0, 1.0);
+        plot.bottom() - t * plot.height()
+    };
+    for step in 0..=7 {
+        let db = -12.0 * step as f32;
+        let y = y_of(db);
+        painter.line_segment(
+            [egui::pos2(plot.left(), y), egui::pos2(plot.right(), y)],
+            Stroke::new(1.0, GRID),
+        );
+        painter.text(
+            egui::pos2(plot.left() - 3.0, y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{db:.0}"),
+            font.clone(),
+            LABEL,
+        );
+    }
+    let sr = frame.map(|f| f.sample_rate).unwrap_or(48_000);
+    for (hz, label) in [
+        (50.0f32, "50"),
+        (100.0, "100"),
+        (200.0, "200"),
+        (500.0, "500"),
+        (1_000.0, "1k"),
+        (2_000.0, "2k"),
+        (5_000.0, "5k"),
+        (10_000.0, "10k"),
+        (20_000.0, "20k"),
+    ] {
+        if hz >= sr as f32 * 0.5 {
+            continue;
+        }
+        let x = freq_x(plot, hz, sr);
+        painter.line_segment(
+            [egui::pos2(x, plot.top()), egui::pos2(x, plot.bottom())],
+            Stroke::new(1.0, GRID),
+        );
+        painter.text(
+            egui::pos2(x, plot.bottom() + 1.0),
+            egui::Align2::CENTER_TOP,
+            label,
+            font.clone(),
+            LABEL,
+        );
+    }
+    let title = match mode {
+        RtaMode::ThirdOctave => "RTA 1/3 OCT",
+        RtaMode::Fft => "RTA FFT",
+    };
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        title,
+        font,
+        LABEL,
+    );
+    let Some(frame) = frame else {
+        return;
+    };
+    match mode {
+        RtaMode::ThirdOctave => {
+            let half_band = 2f32.powf(1.0 / 6.0);
+            for (i, (&fc, &db)) in THIRD_OCTAVE_CENTERS_HZ
+                .iter()
+                .zip(&frame.third_octave_db)
+                .enumerate()
+            {
+                if fc / half_band >= sr as f32 * 0.5 {
+                    break;
+                }
+                let x0 = freq_x(plot, fc / half_band, sr) + 1.0;
+                let x1 = (freq_x(plot, fc * half_band, sr) - 1.0).max(x0 + 1.0);
+                let t = i as f32 / THIRD_OCTAVE_CENTERS_HZ.len() as f32;
+                let norm = ((db - ANALYZER_DB_FLOOR) / -ANALYZER_DB_FLOOR).clamp(0.0, 1.0);
+                // Same blue -> red sweep as the editor mini meter.
+                let color: Color32 =
+                    egui::ecolor::Hsva::new(0.62 - 0.62 * t, 0.78, 0.55 + 0.45 * norm, 1.0).into();
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(x0, y_of(db)),
+                        egui::pos2(x1, plot.bottom()),
+                    ),
+                    0.0,
+                    color,
+                );
+                if let Some(&peak) = frame.third_octave_peak_db.get(i) {
+                    let y = y_of(peak);
+                    painter.line_segment(
+                        [egui::pos2(x0, y), egui::pos2(x1, y)],
+                        Stroke::new(1.5, PEAK),
+                    );
+                }
+            }
+        }
+        RtaMode::Fft => {
+            let cols = (plot.width().max(8.0) as usize).min(2_048);
+            let (live, peak) = columns;
+            fft_log_columns(&frame.fft_db, sr, cols, live);
+            fft_log_columns(&frame.fft_peak_db, sr, cols, peak);
+            let trace = |values: &[f32]| -> Vec<egui::Pos2> {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(x, &db)| {
+                        let px = plot.left() + (x as f32 + 0.5) / cols as f32 * plot.width();
+                        egui::pos2(px, y_of(db))
+                    })
+                    .collect()
+            };
+            if !frame.fft_peak_db.is_empty() {
+                painter.add(egui::Shape::line(
+                    trace(peak),
+                    Stroke::new(1.0, PEAK.gamma_multiply(0.7)),
+                ));
+            }
+            painter.add(egui::Shape::line(
+                trace(live),
+                Stroke::new(1.5, Color32::from_rgb(96, 200, 235)),
+            ));
+        }
+    }
+}
+
+fn draw_goniometer(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    frame: Option<&AnalyzerFrame>,
+    auto_gain: bool,
+) {
+    let font = egui::FontId::monospace(9.0);
+    painter.rect_filled(rect, 4.0, PANEL_BG);
+    let radius = (rect.width().min(rect.height()) * 0.5 - 12.0).max(8.0);
+    let center = rect.center();
+    let guide = Stroke::new(1.0, GRID);
+    painter.circle_stroke(center, radius, guide);
+    let diag = radius * std::f32::consts::FRAC_1_SQRT_2;
+    // L / R axes at 45 degrees, M vertical, S horizontal.
+    painter.line_segment(
+        [
+            egui::pos2(center.x - diag, center.y - diag),
+            egui::pos2(center.x + diag, center.y + diag),
+        ],
+        guide,
+    );
+    painter.line_segment(
+        [
+            egui::pos2(center.x + diag, center.y - diag),
+            egui::pos2(center.x - diag, center.y + diag),
+        ],
+        guide,
+    );
+    painter.line_segment(
+        [
+            egui::pos2(center.x, center.y - radius),
+            egui::pos2(center.x, center.y + radius),
+        ],
+        guide,
+    );
+    painter.line_segment(
+        [
+            egui::pos2(center.x - radius, center.y),
+            egui::pos2(center.x + radius, center.y),
+        ],
+        guide,
+    );
+    for (pos, align, label) in [
+        (
+            egui::pos2(center.x - diag - 2.0, center.y - diag - 2.0),
+            egui::Align2::RIGHT_BOTTOM,
+            "L",
+        ),
+        (
+            egui::pos2(center.x + diag + 2.0, center.y - diag - 2.0),
+            egui::Align2::LEFT_BOTTOM,
+            "R",
+        ),
+        (
+            egui::pos2(center.x, center.y - radius - 1.0),
+            egui::Align2::CENTER_BOTTOM,
+            "M",
+        ),
+    ] {
+        painter.text(pos, align, label, font.clone(), LABEL);
+    }
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        "GONIO",
+        font,
+        LABEL,
+    );
+    let Some(frame) = frame else {
+        return;
+    };
+    // Points are (L-R)/sqrt2, (L+R)/sqrt2: full-scale mono reaches sqrt2.
+    let peak = frame
+        .goniometer
+        .iter()
+        .fold(0.0f32, |acc, (x, y)| acc.max(x.abs()).max(y.abs()));
+    let full_scale = radius * std::f32::consts::FRAC_1_SQRT_2;
+    let scale = if auto_gain && peak > 1.0e-4 {
+        (radius * 0.9 / peak).min(full_scale * 16.0)
+    } else {
+        full_scale
+    };
+    let points: Vec<egui::Pos2> = frame
+        .goniometer
+        .iter()
+        .map(|&(x, y)| {
+            let pt = egui::vec2(x, -y) * scale;
+            let pt = if pt.length() > radius {
+                pt.normalized() * radius
+            } else {
+                pt
+            };
+            center + pt
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        Stroke::new(1.0, Color32::from_rgba_unmultiplied(96, 220, 200, 110)),
+    ));
+}
+
+fn correlation_color(corr: f32) -> Color32 {
+    if corr >= 0.0 {
+        crate::app::helpers::lerp_color(
+            Color32::from_rgb(235, 200, 90),
+            Color32::from_rgb(88, 200, 120),
+            corr,
+        )
+    } else {
+        crate::app::helpers::lerp_color(
+            Color32::from_rgb(235, 200, 90),
+            Color32::from_rgb(240, 100, 100),
+            -corr,
+        )
+    }
+}
+
+/// Correlation history, newest at the right edge: +1 up, -1 down.
+fn draw_correlation_strip(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    frame: Option<&AnalyzerFrame>,
+) {
+    let font = egui::FontId::monospace(9.0);
+    painter.rect_filled(rect, 4.0, PANEL_BG);
+    let plot = rect.shrink2(egui::vec2(4.0, 3.0));
+    let mid = plot.center().y;
+    painter.line_segment(
+        [egui::pos2(plot.left(), mid), egui::pos2(plot.right(), mid)],
+        Stroke::new(1.0, GRID),
+    );
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        "CORR",
+        font.clone(),
+        LABEL,
+    );
+    let Some(frame) = frame else {
+        return;
+    };
+    let step = plot.width() / CORRELATION_HISTORY_LEN as f32;
+    let start = plot.right() - frame.correlation_history.len() as f32 * step;
+    for (i, &corr) in frame.correlation_history.iter().enumerate() {
+        let x = start + (i as f32 + 0.5) * step;
+        let y = mid - corr.clamp(-1.0, 1.0) * plot.height() * 0.5;
+        painter.line_segment(
+            [egui::pos2(x, mid), egui::pos2(x, y)],
+            Stroke::new(step.max(1.0), correlation_color(corr)),
+        );
+    }
+    painter.text(
+        rect.right_top() + egui::vec2(-4.0, 2.0),
+        egui::Align2::RIGHT_TOP,
+        format!("{:+.2}", frame.correlation),
+        font,
+        correlation_color(frame.correlation),
+    );
+}
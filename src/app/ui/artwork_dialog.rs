@@ -0,0 +1,108 @@
+use egui::{Color32, RichText};
+
+use crate::artwork::ArtworkEncoding;
+
+impl crate::app::WavesPreviewer {
+    /// Resize / re-encode options for a loaded image before it is embedded
+    /// as the front cover of every target file.
+    pub(crate) fn ui_artwork_dialog(&mut self, ctx: &egui::Context) {
+        if !self.artwork_dialog.show_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut apply_clicked = false;
+        let mut close_clicked = false;
+        egui::Window::new("Set Artwork")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                let state = &mut self.artwork_dialog;
+                ui.label(format!(
+                    "Source: {}{}",
+                    state.source_label,
+                    state
+                        .source_size
+                        .map(|(w, h)| format!(" ({w}x{h})"))
+                        .unwrap_or_default()
+                ));
+                ui.label(format!(
+                    "Replaces all embedded pictures in {} file(s) with a front cover.",
+                    state.targets.len()
+                ));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let mut resize = state.options.max_edge.is_some();
+                    if ui.checkbox(&mut resize, "Fit within").changed() {
+                        state.options.max_edge = resize.then_some(600);
+                    }
+                    let mut edge = state.options.max_edge.unwrap_or(600);
+                    if ui
+                        .add_enabled(
+                            resize,
+                            egui::DragValue::new(&mut edge)
+                                .range(16..=4096)
+                                .suffix(" px"),
+                        )
+                        .changed()
+                    {
+                        state.options.max_edge = Some(edge);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Encoding:");
+                    egui::ComboBox::from_id_salt("artwork_encoding")
+                        .selected_text(state.options.encoding.label())
+                        .show_ui(ui, |ui| {
+                            for encoding in [
+                                ArtworkEncoding::Keep,
+                                ArtworkEncoding::Jpeg,
+                                ArtworkEncoding::Png,
+                            ] {
+                                ui.selectable_value(
+                                    &mut state.options.encoding,
+                                    encoding,
+                                    encoding.label(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Keep: JPEG/PNG as-is unless resized; other formats become PNG",
+                        );
+                    ui.add_enabled(
+                        state.options.encoding == ArtworkEncoding::Jpeg,
+                        egui::Slider::new(&mut state.options.jpeg_quality, 40..=100)
+                            .text("quality"),
+                    );
+                });
+                if let Some(error) = &state.error {
+                    ui.colored_label(Color32::LIGHT_RED, RichText::new(error));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let can_apply = state.source_size.is_some() && !state.targets.is_empty();
+                    if ui
+                        .add_enabled(
+                            can_apply,
+                            egui::Button::new(format!("Apply to {} file(s)", state.targets.len())),
+                        )
+                        .clicked()
+                    {
+                        apply_clicked = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close_clicked = true;
+                    }
+                });
+            });
+        if apply_clicked {
+            self.apply_artwork_dialog();
+        }
+        if close_clicked || !open {
+            self.artwork_dialog.show_dialog = false;
+            self.artwork_dialog.source.clear();
+        }
+    }
+}
@@ -9,6 +9,7 @@ use egui::{Color32, Sense, Stroke};
 
 use crate::app::types::{ChannelRoutingDraft, CHANNEL_ROUTING_MAX_OUT};
 use crate::app::WavesPreviewer;
+use crate::channel_layout::ChannelLayout;
 
 const PIN_R: f32 = 6.0;
 const PIN_HALO_R: f32 = 9.0;
@@ -100,25 +101,15 @@ pub(crate) fn pin_geometry_id() -> egui::Id {
     egui::Id::new("channel_routing_pin_geometry")
 }
 
-/// `L`/`R` only for exactly-stereo, otherwise `Ch N` — the same rule the editor
-/// toolbar and meter strip already use.
-pub(super) fn channel_label(count: usize, idx: usize) -> String {
-    match (count, idx) {
-        (2, 0) => "L".to_string(),
-        (2, 1) => "R".to_string(),
-        _ => format!("Ch {}", idx + 1),
-    }
-}
-
 impl WavesPreviewer {
     /// Draws the matrix and mutates `draft` in place. Returns true when the
     /// user asked to apply.
     pub(super) fn ui_channel_routing_patchbay(
         ui: &mut egui::Ui,
         draft: &mut ChannelRoutingDraft,
-        in_count: usize,
+        input_layout: ChannelLayout,
     ) -> bool {
-        let in_count = in_count.max(1);
+        let in_count = (input_layout.channels() as usize).max(1);
         draft.reseed_if_stale(in_count);
 
         ui.horizontal(|ui| {
@@ -391,12 +382,15 @@ impl WavesPreviewer {
             painter.text(
                 egui::pos2(pos.x - PIN_HALO_R - 5.0, pos.y),
                 egui::Align2::RIGHT_CENTER,
-                channel_label(in_count, i),
+                input_layout.label(i),
                 font.clone(),
                 text_color,
             );
         }
 
+        // Outputs are named by the layout the routing would produce, so a
+        // dropped LFE shows the surrounds keeping their positions.
+        let output_layout = draft.routed_layout(&input_layout);
         for (o, pos) in out_pos.iter().enumerate() {
             let hovered = hit == Some(RoutingHit::OutPin(o));
             painter.circle_filled(*pos, PIN_HALO_R, COLOR_BG);
@@ -418,12 +412,13 @@ impl WavesPreviewer {
                 ),
             );
             let src_count = draft.sources.get(o).map(|s| s.len()).unwrap_or(0);
+            let name = output_layout.label(o);
             let label = if src_count > 1 {
-                format!("{} (mix {})", channel_label(draft.out_count, o), src_count)
+                format!("{name} (mix {src_count})")
             } else if src_count == 0 {
-                format!("{} (silent)", channel_label(draft.out_count, o))
+                format!("{name} (silent)")
             } else {
-                channel_label(draft.out_count, o)
+                name
             };
             painter.text(
                 egui::pos2(pos.x + PIN_HALO_R + 5.0, pos.y),
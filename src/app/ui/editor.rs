@@ -349,9 +349,74 @@ impl crate::app::WavesPreviewer {
             ViewMode::Tempogram => "Tempogram",
             ViewMode::Chromagram => "Chromagram",
             ViewMode::World => "World (F0/Env)",
+            ViewMode::Loudness => "Loudness",
         }
     }
 
+    /// Level axis of the Loudness lane; LUFS and dBTP share it.
+    const LOUDNESS_LANE_DB_RANGE: (f32, f32) = (-60.0, 3.0);
+    /// How close (px) a click must land to a loudness curve to pick a peak.
+    const LOUDNESS_LANE_HIT_PX: f32 = 10.0;
+
+    fn loudness_lane_y(db: f32, lane_rect: egui::Rect) -> f32 {
+        let (lo, hi) = Self::LOUDNESS_LANE_DB_RANGE;
+        let frac = ((db.max(lo) - lo) / (hi - lo)).clamp(0.0, 1.0);
+        lane_rect.bottom() - frac * lane_rect.height()
+    }
+
+    /// Display sample a loudness block is drawn at: the middle of its window.
+    fn loudness_lane_block_center(
+        data: &crate::wave::LoudnessTimeline,
+        idx: usize,
+        window_sec: f32,
+    ) -> f64 {
+        idx as f64 * data.hop_frames() + window_sec as f64 * data.sample_rate as f64 * 0.5
+    }
+
+    /// Measurement window of the momentary or short-term peak nearest to a
+    /// click on the Loudness lane: the loudest block drawn within
+    /// [`Self::LOUDNESS_LANE_HIT_PX`] of the pointer, if its curve point is
+    /// that close vertically too.
+    fn loudness_lane_peak_window(
+        data: &crate::wave::LoudnessTimeline,
+        lane_rect: egui::Rect,
+        start: usize,
+        end: usize,
+        pos: egui::Pos2,
+    ) -> Option<(usize, usize)> {
+        let visible_len = end.saturating_sub(start).max(1) as f64;
+        let samples_per_px = visible_len / lane_rect.width().max(1.0) as f64;
+        let pointer = start as f64 + (pos.x - lane_rect.left()) as f64 * samples_per_px;
+        let radius = Self::LOUDNESS_LANE_HIT_PX as f64 * samples_per_px;
+        let hop = data.hop_frames().max(1.0);
+        let mut best: Option<(f32, usize, f32)> = None;
+        for (values, window_sec) in [
+            (
+                &data.momentary_lufs,
+                crate::wave::LOUDNESS_MOMENTARY_WINDOW_SEC,
+            ),
+            (
+                &data.short_term_lufs,
+                crate::wave::LOUDNESS_SHORT_TERM_WINDOW_SEC,
+            ),
+        ] {
+            let offset = Self::loudness_lane_block_center(data, 0, window_sec);
+            let lo = ((pointer - radius - offset) / hop).ceil().max(0.0) as usize;
+            let hi = (((pointer + radius - offset) / hop).floor() + 1.0).max(0.0) as usize;
+            let Some((idx, value)) = (lo..hi.min(values.len()))
+                .map(|idx| (idx, values[idx]))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                continue;
+            };
+            let dy = (Self::loudness_lane_y(value, lane_rect) - pos.y).abs();
+            if dy <= Self::LOUDNESS_LANE_HIT_PX && best.is_none_or(|(best_dy, _, _)| dy < best_dy) {
+                best = Some((dy, idx, window_sec));
+            }
+        }
+        best.map(|(_, idx, window_sec)| data.block_range(idx, window_sec))
+    }
+
     fn editor_set_view_offset(tab: &mut EditorTab, new_view: usize) {
         tab.view_offset = new_view;
         tab.view_offset_exact = new_view as f64;
@@ -1780,6 +1845,7 @@ impl crate::app::WavesPreviewer {
         let slot_w = bars_rect.width() / n_ch.max(1) as f32;
         let bar_w = (slot_w - 2.0).clamp(2.0, 18.0);
         let show_ch_labels = slot_w >= 9.0 && n_ch > 1;
+        let meter_layout = tab.channel_layout();
         let mut max_peak_db = meter_floor;
         for c in 0..n_ch {
             let take = level_n.min(end);
@@ -1833,10 +1899,10 @@ impl crate::app::WavesPreviewer {
                 Stroke::new(1.0, Color32::from_rgb(255, 196, 72)),
             );
             if show_ch_labels {
-                let label = match (n_ch, c) {
-                    (2, 0) => "L".to_string(),
-                    (2, 1) => "R".to_string(),
-                    _ => format!("{}", c + 1),
+                let label = if meter_layout.channels() as usize == n_ch {
+                    meter_layout.label(c)
+                } else {
+                    format!("{}", c + 1)
                 };
                 painter.text(
                     egui::pos2(bar_rect.center().x, bars_rect.bottom() + 1.0),
@@ -2455,6 +2521,7 @@ impl crate::app::WavesPreviewer {
             ViewMode::Tempogram => Some(EditorAnalysisKind::Tempogram),
             ViewMode::Chromagram => Some(EditorAnalysisKind::Chromagram),
             ViewMode::World => Some(EditorAnalysisKind::World),
+            ViewMode::Loudness => Some(EditorAnalysisKind::Loudness),
             _ => None,
         };
         let feature_key = feature_kind.map(|kind| EditorAnalysisKey {
@@ -2480,6 +2547,10 @@ impl crate::app::WavesPreviewer {
             Some(EditorFeatureAnalysisData::Chromagram(data)) => Some(data.clone()),
             _ => None,
         };
+        let loudness_data = match feature_cache.as_deref() {
+            Some(EditorFeatureAnalysisData::Loudness(data)) => Some(data),
+            _ => None,
+        };
         let world_feature_cache = match feature_cache.as_deref() {
             Some(EditorFeatureAnalysisData::World(_)) => feature_cache.clone(),
             _ => None,
@@ -2508,6 +2579,8 @@ impl crate::app::WavesPreviewer {
         let mut request_preview_refresh = false;
         let mut requested_channel_view: Option<ChannelView> = None;
         let channel_count = self.tabs[tab_idx].ch_samples.len();
+        let channel_layout = self.tabs[tab_idx].channel_layout();
+        let adm_lanes = self.tabs[tab_idx].adm_lanes().map(<[_]>::to_vec);
         ui.horizontal_wrapped(|ui| {
             let tab = &mut self.tabs[tab_idx];
             // Loop mode toggles (kept): Off / OnWhole / Marker
@@ -2541,6 +2614,7 @@ impl crate::app::WavesPreviewer {
                         ui.selectable_value(&mut selected_view, ViewMode::Tempogram, "Tempogram");
                         ui.selectable_value(&mut selected_view, ViewMode::Chromagram, "Chromagram");
                         ui.selectable_value(&mut selected_view, ViewMode::World, "World (F0/Env)");
+                        ui.selectable_value(&mut selected_view, ViewMode::Loudness, "Loudness");
                         ui.separator();
                         if ui.selectable_label(false, "Metadata").clicked() {
                             tab.primary_view = EditorPrimaryView::Metadata;
@@ -2567,7 +2641,7 @@ impl crate::app::WavesPreviewer {
             if channel_count > 0 {
                 let mut view = tab.channel_view.clone();
                 let mut view_changed = false;
-                ui.label("Ch:");
+                ui.label("Ch:").on_hover_text(channel_layout.describe());
                 if ui
                     .selectable_label(view.mode == ChannelViewMode::Mixdown, "Mix")
                     .clicked()
@@ -2585,9 +2659,18 @@ impl crate::app::WavesPreviewer {
                 ui.menu_button("Channels", |ui| {
                     let mut selection_changed = false;
                     for idx in 0..channel_count {
-                        let label = format!("Ch {}", idx + 1);
+                        let adm_lane = adm_lanes.as_ref().and_then(|lanes| lanes.get(idx));
+                        let label = match adm_lane.and_then(|lane| lane.label()) {
+                            Some(adm) => format!("{}  {adm}", channel_layout.label(idx)),
+                            None => channel_layout.label(idx),
+                        };
                         let mut selected = view.selected.contains(&idx);
-                        if ui.checkbox(&mut selected, label).changed() {
+                        let checkbox = ui.checkbox(&mut selected, label);
+                        let checkbox = match adm_lane {
+                            Some(lane) if !lane.is_empty() => checkbox.on_hover_text(lane.describe()),
+                            _ => checkbox,
+                        };
+                        if checkbox.changed() {
                             selection_changed = true;
                             if selected {
                                 if !view.selected.contains(&idx) {
@@ -2625,12 +2708,7 @@ impl crate::app::WavesPreviewer {
                     ui.label("Playback mute / solo");
                     for idx in 0..channel_count {
                         ui.horizontal(|ui| {
-                            let name = match (channel_count, idx) {
-                                (2, 0) => "L".to_string(),
-                                (2, 1) => "R".to_string(),
-                                _ => format!("Ch {}", idx + 1),
-                            };
-                            ui.label(name);
+                            ui.label(channel_layout.label(idx));
                             if ui
                                 .selectable_label(tab.ch_muted[idx], "M")
                                 .on_hover_text("Mute this channel during playback")
@@ -3115,13 +3193,16 @@ impl crate::app::WavesPreviewer {
         let spectro_loading = self.spectro_inflight.contains(&tab_path);
         let analysis_loading = match current_view {
             ViewMode::Spectrogram | ViewMode::Log | ViewMode::Mel => spectro_loading,
-            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World => feature_loading,
+            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World | ViewMode::Loudness => {
+                feature_loading
+            }
             ViewMode::Waveform => false,
         };
         let mut cancel_feature_analysis = false;
         let mut pending_tempogram_refresh = false;
         let mut pending_chromagram_refresh = false;
         let mut pending_world_refresh = false;
+        let mut pending_loudness_refresh = false;
         let mut pending_world_resynth = false;
         let mut pending_world_f0_method: Option<crate::app::types::WorldF0Method> = None;
         let mut apply_estimated_bpm: Option<f32> = None;
@@ -3275,7 +3356,10 @@ impl crate::app::WavesPreviewer {
                         let mut visible_channels = tab.channel_view.visible_indices(channel_count);
                         let force_feature_mixdown = matches!(
                             view_mode,
-                            ViewMode::Tempogram | ViewMode::Chromagram | ViewMode::World
+                            ViewMode::Tempogram
+                                | ViewMode::Chromagram
+                                | ViewMode::World
+                                | ViewMode::Loudness
                         );
                         let use_mixdown = force_feature_mixdown
                             || tab.channel_view.mode == ChannelViewMode::Mixdown
@@ -4298,6 +4382,170 @@ impl crate::app::WavesPreviewer {
                             }
                         }
                     }
+                    ViewMode::Loudness => {
+                        let lane_rect = egui::Rect::from_min_size(
+                            egui::pos2(wave_left, rect.top()),
+                            egui::vec2(wave_w, h),
+                        );
+                        let fid = TextStyle::Monospace.resolve(ui.style());
+                        let y_for_db = |db: f32| Self::loudness_lane_y(db, lane_rect);
+                        // Level grid and axis labels along the left gutter.
+                        let tick_col = Color32::from_rgb(140, 150, 165);
+                        let grid_stroke = egui::Stroke::new(1.0, Color32::from_rgb(40, 44, 52));
+                        for db in [0.0f32, -6.0, -12.0, -18.0, -24.0, -36.0, -48.0] {
+                            let y = y_for_db(db);
+                            painter.line_segment(
+                                [egui::pos2(lane_rect.left(), y), egui::pos2(lane_rect.right(), y)],
+                                grid_stroke,
+                            );
+                            painter.line_segment(
+                                [egui::pos2(wave_left - 6.0, y), egui::pos2(wave_left - 2.0, y)],
+                                egui::Stroke::new(1.0, tick_col),
+                            );
+                            painter.text(
+                                egui::pos2(rect.left() + 2.0, y),
+                                egui::Align2::LEFT_CENTER,
+                                format!("{db:.0}"),
+                                fid.clone(),
+                                tick_col,
+                            );
+                        }
+                        // Loudness normalize target and its tolerance band.
+                        let target = self.loudnorm_dialog_target;
+                        let tolerance = self.loudnorm_dialog_tolerance.max(0.0);
+                        let target_col = Color32::from_rgb(110, 210, 140);
+                        painter.rect_filled(
+                            egui::Rect::from_x_y_ranges(
+                                lane_rect.x_range(),
+                                y_for_db(target + tolerance)..=y_for_db(target - tolerance),
+                            ),
+                            0.0,
+                            Color32::from_rgba_unmultiplied(110, 210, 140, 28),
+                        );
+                        let target_y = y_for_db(target);
+                        painter.line_segment(
+                            [
+                                egui::pos2(lane_rect.left(), target_y),
+                                egui::pos2(lane_rect.right(), target_y),
+                            ],
+                            egui::Stroke::new(1.0, target_col),
+                        );
+                        painter.text(
+                            egui::pos2(lane_rect.left() + 6.0, target_y - 2.0),
+                            egui::Align2::LEFT_BOTTOM,
+                            format!("target {target:.1} LUFS \u{00B1}{tolerance:.1}"),
+                            fid.clone(),
+                            target_col,
+                        );
+                        if let Some(data) = loudness_data {
+                            let hop = data.hop_frames().max(1.0);
+                            let visible_len = end.saturating_sub(start).max(1) as f64;
+                            let max_points = (wave_w.max(32.0) as usize) * 2;
+                            // Keep the loudest block of each decimation group
+                            // so short peaks survive on long clips.
+                            let draw_curve = |values: &[f32],
+                                              offset: f64,
+                                              color: Color32,
+                                              width: f32| {
+                                let first =
+                                    ((start as f64 - offset) / hop).floor().max(0.0) as usize;
+                                let last = ((((end as f64 - offset) / hop).ceil() + 1.0).max(0.0)
+                                    as usize)
+                                    .min(values.len());
+                                if first >= last {
+                                    return;
+                                }
+                                let group = ((last - first) / max_points.max(1)).max(1);
+                                let mut points = Vec::with_capacity((last - first) / group + 1);
+                                let mut idx = first;
+                                while idx < last {
+                                    let group_end = (idx + group).min(last);
+                                    let (peak_idx, peak) = (idx..group_end)
+                                        .map(|j| (j, values[j]))
+                                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                                        .unwrap_or((idx, f32::NEG_INFINITY));
+                                    let sample = peak_idx as f64 * hop + offset;
+                                    let x = wave_left
+                                        + ((sample - start as f64) / visible_len) as f32 * wave_w;
+                                    points.push(egui::pos2(
+                                        x.clamp(lane_rect.left(), lane_rect.right()),
+                                        y_for_db(peak),
+                                    ));
+                                    idx = group_end;
+                                }
+                                let halo = Color32::from_rgba_unmultiplied(8, 10, 14, 210);
+                                for (pass_color, pass_width) in
+                                    [(halo, width + 2.6), (color, width)]
+                                {
+                                    painter.add(egui::Shape::line(
+                                        points.clone(),
+                                        egui::Stroke::new(pass_width, pass_color),
+                                    ));
+                                }
+                            };
+                            let momentary_col = Color32::from_rgb(110, 190, 255);
+                            let short_term_col = Color32::from_rgb(255, 190, 80);
+                            let true_peak_col = Color32::from_rgb(235, 95, 95);
+                            let m_offset = Self::loudness_lane_block_center(
+                                data,
+                                0,
+                                crate::wave::LOUDNESS_MOMENTARY_WINDOW_SEC,
+                            );
+                            let s_offset = Self::loudness_lane_block_center(
+                                data,
+                                0,
+                                crate::wave::LOUDNESS_SHORT_TERM_WINDOW_SEC,
+                            );
+                            let tp_offset = hop * 0.5;
+                            draw_curve(&data.true_peak_db, tp_offset, true_peak_col, 1.0);
+                            draw_curve(&data.momentary_lufs, m_offset, momentary_col, 1.4);
+                            draw_curve(&data.short_term_lufs, s_offset, short_term_col, 2.2);
+                            // Live readout of the blocks drawn at the playhead.
+                            let value_at = |values: &[f32], offset: f64| {
+                                let idx = ((playhead_display_now as f64 - offset) / hop)
+                                    .round()
+                                    .max(0.0) as usize;
+                                values
+                                    .get(idx.min(values.len().saturating_sub(1)))
+                                    .copied()
+                                    .filter(|v| *v > Self::LOUDNESS_LANE_DB_RANGE.0)
+                                    .map(|v| format!("{v:6.1}"))
+                                    .unwrap_or_else(|| "    --".to_string())
+                            };
+                            for (row, (label, values, offset, color, unit)) in [
+                                ("M ", &data.momentary_lufs, m_offset, momentary_col, "LUFS"),
+                                ("S ", &data.short_term_lufs, s_offset, short_term_col, "LUFS"),
+                                ("TP", &data.true_peak_db, tp_offset, true_peak_col, "dBTP"),
+                            ]
+                            .into_iter()
+                            .enumerate()
+                            {
+                                painter.text(
+                                    egui::pos2(
+                                        lane_rect.right() - 8.0,
+                                        lane_rect.top() + 6.0 + row as f32 * 14.0,
+                                    ),
+                                    egui::Align2::RIGHT_TOP,
+                                    format!("{label} {} {unit}", value_at(values, offset)),
+                                    fid.clone(),
+                                    color,
+                                );
+                            }
+                        } else {
+                            let msg = if feature_loading {
+                                "Measuring loudness (momentary / short-term / true peak)..."
+                            } else {
+                                "Loudness not measured"
+                            };
+                            painter.text(
+                                egui::pos2(wave_left + 6.0, rect.top() + 6.0),
+                                egui::Align2::LEFT_TOP,
+                                msg,
+                                fid,
+                                Color32::GRAY,
+                            );
+                        }
+                    }
                     ViewMode::Waveform => {}
                 }
             }
@@ -4559,6 +4807,35 @@ impl crate::app::WavesPreviewer {
                     }
                 }
             }
+            // Loudness lane: a plain click on a momentary / short-term peak
+            // selects that block's measurement window instead of seeking.
+            if view_mode == ViewMode::Loudness
+                && !suppress_seek
+                && resp.clicked_by(egui::PointerButton::Primary)
+                && !ui.input(|i| i.modifiers.shift)
+            {
+                if let (Some(data), Some(pos)) = (loudness_data, resp.interact_pointer_pos()) {
+                    let lane_rect = egui::Rect::from_min_size(
+                        egui::pos2(wave_left, rect.top()),
+                        egui::vec2(wave_w, h),
+                    );
+                    if let Some((sel_start, sel_end)) = Self::loudness_lane_peak_window(
+                        data,
+                        lane_rect,
+                        geom.visible_start(),
+                        geom.visible_end(),
+                        pos,
+                    ) {
+                        let sel_end = sel_end.min(display_samples_len);
+                        Self::editor_set_selection_from_anchor(
+                            tab,
+                            sel_start.min(sel_end),
+                            sel_end,
+                        );
+                        suppress_seek = true;
+                    }
+                }
+            }
             // ---- Tool canvas gestures (Waveform view) ----
             // Gain curve editing owns the pointer like the WORLD pencil;
             // PitchShift's pitch line and Speed/TimeStretch's selection-edge
@@ -5829,6 +6106,7 @@ impl crate::app::WavesPreviewer {
             // Draw per-channel lanes with dB grid and playhead
             waveform_render_started = Some(std::time::Instant::now());
             if show_waveform {
+            let lane_layout = tab.channel_layout();
             for lane_idx in 0..lane_count {
                 let channel_index = if use_mixdown {
                     None
@@ -5865,6 +6143,19 @@ impl crate::app::WavesPreviewer {
                     let fid = TextStyle::Monospace.resolve(ui.style());
                     painter.text(egui::pos2(rect.left() + 2.0, y0), egui::Align2::LEFT_CENTER, format!("{db:.0} dB"), fid, Color32::GRAY);
                 }
+                // Speaker label (L/R/C/LFE/...) in the gutter at the lane top.
+                if let Some(ch) = channel_index.filter(|_| tab.ch_samples.len() > 1) {
+                    let fid = TextStyle::Monospace.resolve(ui.style());
+                    painter.text(egui::pos2(rect.left() + 2.0, lane_top + 2.0), egui::Align2::LEFT_TOP, lane_layout.label(ch), fid, Color32::LIGHT_GRAY);
+                }
+                // BW64 ADM object / pack names at the top-left of the lane.
+                if let Some(label) = channel_index
+                    .and_then(|ch| tab.adm_lanes().and_then(|lanes| lanes.get(ch)))
+                    .and_then(|lane| lane.label())
+                {
+                    let fid = TextStyle::Small.resolve(ui.style());
+                    painter.text(egui::pos2(lane_rect.left() + 4.0, lane_top + 2.0), egui::Align2::LEFT_TOP, label, fid, Color32::from_rgb(170, 200, 235));
+                }
 
                 if visible_len > 0 {
                     let (wave_lod, lane_query_ms, lane_draw_ms) = if tab.loading
@@ -10135,9 +10426,16 @@ impl crate::app::WavesPreviewer {
                                             .clicked()
                                         {
                                             if preview_ok {
-                                                if let Ok(lufs) = crate::wave::lufs_integrated_from_multi(
+                                                let layout = LoudnessLayout::resolve(
+                                                    tab.ch_samples.len(),
+                                                    Some(tab.channel_layout()),
+                                                    self.loudness_layout_override,
+                                                )
+                                                .layout;
+                                                if let Ok(lufs) = crate::wave::lufs_integrated_for_layout(
                                                     &tab.ch_samples,
                                                     self.audio.shared.out_sample_rate,
+                                                    layout,
                                                 ) {
                                                     if lufs.is_finite() {
                                                         let gain_db = target_lufs - lufs;
@@ -11864,11 +12162,11 @@ impl crate::app::WavesPreviewer {
                                             )
                                             .weak(),
                                         );
-                                        let in_count = tab.ch_samples.len().max(1);
+                                        let input_layout = tab.channel_layout();
                                         let apply = Self::ui_channel_routing_patchbay(
                                             ui,
                                             &mut tab.channel_routing_draft,
-                                            in_count,
+                                            input_layout,
                                         );
                                         if apply && !apply_busy && !tab.loading {
                                             pending_channel_routing_apply = true;
@@ -12646,6 +12944,81 @@ impl crate::app::WavesPreviewer {
                                 );
                             }
                         }
+                        ViewMode::Loudness => {
+                            Self::inspector_section(ui, "Loudness (EBU R128)");
+                            ui.checkbox(&mut tab.show_waveform_overlay, "Waveform overlay");
+                            ui.horizontal_wrapped(|ui| {
+                                if ui.button("Re-measure").clicked() {
+                                    pending_loudness_refresh = true;
+                                }
+                                if analysis_loading && ui.button("Cancel").clicked() {
+                                    cancel_feature_analysis = true;
+                                }
+                            });
+                            if let Some(data) = loudness_data {
+                                let max_of = |values: &[f32]| {
+                                    values
+                                        .iter()
+                                        .copied()
+                                        .filter(|v| *v > Self::LOUDNESS_LANE_DB_RANGE.0)
+                                        .reduce(f32::max)
+                                        .map(|v| format!("{v:.1}"))
+                                        .unwrap_or_else(|| "--".to_string())
+                                };
+                                egui::Grid::new("loudness_lane_max")
+                                    .num_columns(2)
+                                    .show(ui, |ui| {
+                                        for (label, value, unit, color) in [
+                                            (
+                                                "Momentary max",
+                                                max_of(&data.momentary_lufs),
+                                                "LUFS",
+                                                Color32::from_rgb(110, 190, 255),
+                                            ),
+                                            (
+                                                "Short-term max",
+                                                max_of(&data.short_term_lufs),
+                                                "LUFS",
+                                                Color32::from_rgb(255, 190, 80),
+                                            ),
+                                            (
+                                                "True peak max",
+                                                max_of(&data.true_peak_db),
+                                                "dBTP",
+                                                Color32::from_rgb(235, 95, 95),
+                                            ),
+                                        ] {
+                                            ui.label(RichText::new(label).color(color));
+                                            ui.label(
+                                                RichText::new(format!("{value} {unit}"))
+                                                    .monospace(),
+                                            );
+                                            ui.end_row();
+                                        }
+                                    });
+                                ui.label(
+                                    RichText::new(format!(
+                                        "Target {:.1} LUFS \u{00B1}{:.1} LU (Normalize Loudness settings)",
+                                        self.loudnorm_dialog_target,
+                                        self.loudnorm_dialog_tolerance
+                                    ))
+                                    .weak(),
+                                );
+                                ui.label(
+                                    RichText::new(
+                                        "Click a momentary or short-term peak to select its measurement window.",
+                                    )
+                                    .weak(),
+                                );
+                            } else if analysis_loading {
+                                ui.label(RichText::new("Measuring all channels...").weak());
+                            } else {
+                                ui.label(
+                                    RichText::new("Loudness measurement starts automatically for this view")
+                                        .weak(),
+                                );
+                            }
+                        }
                     }
                 });
                 },
@@ -12985,6 +13358,13 @@ impl crate::app::WavesPreviewer {
             };
             self.cancel_feature_analysis_for_key(&key);
         }
+        if pending_loudness_refresh {
+            let key = EditorAnalysisKey {
+                path: tab_path.clone(),
+                kind: EditorAnalysisKind::Loudness,
+            };
+            self.cancel_feature_analysis_for_key(&key);
+        }
         if pending_world_resynth {
             self.spawn_world_resynth_for_tab(tab_idx);
         }
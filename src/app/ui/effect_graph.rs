@@ -6,7 +6,8 @@ use std::sync::Arc;
 use crate::app::helpers::db_to_color;
 use crate::app::input_focus::UiScrollTarget;
 use crate::app::types::{
-    EffectGraphBitDepth, EffectGraphCombineMode, EffectGraphDebugPreview, EffectGraphNodeCategory,
+    EffectGraphBitDepth, EffectGraphCombineMode, EffectGraphDebugPreview, EffectGraphDither,
+    EffectGraphNodeCategory,
     EffectGraphNodeData, EffectGraphNodeKind, EffectGraphNodeRunPhase, EffectGraphPlaybackTarget,
     EffectGraphPortDirection, EffectGraphPortKey, EffectGraphResampleQuality, EffectGraphSeverity,
     EffectGraphSpectrumMode,
@@ -1662,7 +1663,11 @@ impl crate::app::WavesPreviewer {
                     pre_roll_ms,
                     post_roll_ms,
                 } => trim = Some((*threshold_below_peak_db, *pre_roll_ms, *post_roll_ms)),
-                EffectGraphNodeData::BitDepth { depth } => bit_depth = Some(*depth),
+                EffectGraphNodeData::BitDepth {
+                    depth,
+                    dither,
+                    auto_blank,
+                } => bit_depth = Some((*depth, *dither, *auto_blank)),
                 EffectGraphNodeData::Resampler {
                     target_sample_rate,
                     quality,
@@ -3010,7 +3015,10 @@ impl crate::app::WavesPreviewer {
                                 .weak(),
                         );
                     }
-                    if let Some(depth) = bit_depth {
+                    if let Some((depth, dither, auto_blank)) = bit_depth {
+                        let mut new_depth = depth;
+                        let mut new_dither = dither;
+                        let mut new_auto_blank = auto_blank;
                         ui.horizontal(|ui| {
                             ui.label("Depth")
                                 .on_hover_text("Quantizes the signal to this bit depth (preview of the resolution loss)");
@@ -3019,16 +3027,43 @@ impl crate::app::WavesPreviewer {
                                 (EffectGraphBitDepth::Pcm24, "24-bit"),
                                 (EffectGraphBitDepth::Float32, "32-bit float"),
                             ] {
-                                if ui.selectable_label(depth == option, label).clicked() && depth != option {
-                                    self.effect_graph_push_undo_snapshot();
-                                    if let Some(node_mut) = self.effect_graph.draft.nodes.get_mut(idx) {
-                                        node_mut.data = EffectGraphNodeData::BitDepth { depth: option };
-                                    }
-                                    self.effect_graph.draft_dirty = true;
-                                    self.revalidate_effect_graph_draft();
+                                if ui.selectable_label(new_depth == option, label).clicked() {
+                                    new_depth = option;
                                 }
                             }
                         });
+                        if new_depth != EffectGraphBitDepth::Float32 {
+                            ui.horizontal(|ui| {
+                                ui.label("Dither")
+                                    .on_hover_text("Dither/noise shaping applied before rounding");
+                                egui::ComboBox::from_id_salt(format!("effect_graph_dither_{idx}"))
+                                    .selected_text(new_dither.to_wave_dither_mode().label())
+                                    .show_ui(ui, |ui| {
+                                        for option in EffectGraphDither::ALL {
+                                            ui.selectable_value(
+                                                &mut new_dither,
+                                                option,
+                                                option.to_wave_dither_mode().label(),
+                                            );
+                                        }
+                                    });
+                            });
+                            if new_dither != EffectGraphDither::Off {
+                                ui.checkbox(&mut new_auto_blank, "Auto-blank on digital silence");
+                            }
+                        }
+                        if (new_depth, new_dither, new_auto_blank) != (depth, dither, auto_blank) {
+                            self.effect_graph_push_undo_snapshot();
+                            if let Some(node_mut) = self.effect_graph.draft.nodes.get_mut(idx) {
+                                node_mut.data = EffectGraphNodeData::BitDepth {
+                                    depth: new_depth,
+                                    dither: new_dither,
+                                    auto_blank: new_auto_blank,
+                                };
+                            }
+                            self.effect_graph.draft_dirty = true;
+                            self.revalidate_effect_graph_draft();
+                        }
                     }
                     if let Some((mut target_sample_rate, quality)) = resampler {
                         let mut changed = false;
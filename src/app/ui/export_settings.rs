@@ -2,8 +2,21 @@ use crate::app::types::{
     ConflictPolicy, EditorHorizontalZoomAnchorMode, EditorPauseResumeMode, ItemBgMode, SaveMode,
     SpectrogramScale, SrcQuality, ThemeMode, ViewMode, WindowFunction,
 };
+use crate::channel_layout::ChannelLayout;
 use egui::RichText;
 
+/// Surround layouts offered as a loudness override, as (channels, mask).
+const LOUDNESS_LAYOUT_CHOICES: [(u16, u32); 8] = [
+    (4, 0x33),
+    (4, 0x603),
+    (5, 0x37),
+    (5, 0x607),
+    (6, 0x3F),
+    (6, 0x60F),
+    (8, 0x63F),
+    (8, 0xFF),
+];
+
 impl crate::app::WavesPreviewer {
     pub(in crate::app) fn ui_export_settings_window(&mut self, ctx: &egui::Context) {
         if self.show_export_settings {
@@ -162,29 +175,86 @@ impl crate::app::WavesPreviewer {
                                         .weak()
                                         .small(),
                                 );
+                                ui.label(
+                                    RichText::new(
+                                        "(M4A keeps 7.1 only with front Lc/Rc channels; \
+                                         other 7.1 layouts are folded to 5.1)",
+                                    )
+                                    .weak()
+                                    .small(),
+                                );
+                            });
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("Opus Bitrate:");
+                                egui::ComboBox::from_id_salt("export_opus_kbps")
+                                    .selected_text(format!(
+                                        "{} kbps",
+                                        self.export_cfg.codec.opus_bitrate_kbps
+                                    ))
+                                    .show_ui(ui, |ui| {
+                                        for &kbps in crate::opus_codec::OPUS_BITRATES_KBPS {
+                                            if ui
+                                                .selectable_value(
+                                                    &mut self.export_cfg.codec.opus_bitrate_kbps,
+                                                    kbps,
+                                                    format!("{kbps} kbps"),
+                                                )
+                                                .changed()
+                                            {
+                                                codec_changed = true;
+                                            }
+                                        }
+                                    });
+                                ui.label("Complexity:");
+                                let mut complexity = self.export_cfg.codec.opus_complexity;
+                                if ui
+                                    .add(egui::Slider::new(&mut complexity, 0..=10))
+                                    .changed()
+                                {
+                                    self.export_cfg.codec.opus_complexity = complexity;
+                                    codec_changed = true;
+                                }
+                                ui.label(
+                                    RichText::new("(per stereo pair; Opus encodes at 48 kHz)")
+                                        .weak()
+                                        .small(),
+                                );
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Surround downmix:");
+                                let mode = &mut self.export_cfg.codec.downmix;
+                                egui::ComboBox::new("export_downmix", "")
+                                    .selected_text(mode.label())
+                                    .show_ui(ui, |ui| {
+                                        for value in [
+                                            crate::wave::DownmixMode::Itu,
+                                            crate::wave::DownmixMode::FirstTwo,
+                                        ] {
+                                            if ui
+                                                .selectable_value(mode, value, value.label())
+                                                .changed()
+                                            {
+                                                codec_changed = true;
+                                            }
+                                        }
+                                    });
+                                ui.label(
+                                    RichText::new(
+                                        "(MP3 is stereo-only; AAC keeps up to 5.1/7.1, Vorbis/Opus up to 8 ch)",
+                                    )
+                                    .weak()
+                                    .small(),
+                                );
                             });
                             ui.horizontal(|ui| {
                                 ui.label("Dither (16-bit export):");
                                 let mode = &mut self.export_cfg.codec.dither_mode;
                                 egui::ComboBox::new("export_dither_mode", "")
-                                    .selected_text(match mode {
-                                        crate::wave::DitherMode::Off => "Off",
-                                        crate::wave::DitherMode::Tpdf => "TPDF",
-                                        crate::wave::DitherMode::TpdfNoiseShaped => {
-                                            "TPDF + noise shaping"
-                                        }
-                                    })
+                                    .selected_text(mode.label())
                                     .show_ui(ui, |ui| {
-                                        for (value, label) in [
-                                            (crate::wave::DitherMode::Off, "Off"),
-                                            (crate::wave::DitherMode::Tpdf, "TPDF"),
-                                            (
-                                                crate::wave::DitherMode::TpdfNoiseShaped,
-                                                "TPDF + noise shaping",
-                                            ),
-                                        ] {
+                                        for value in crate::wave::DitherMode::ALL {
                                             if ui
-                                                .selectable_value(mode, value, label)
+                                                .selectable_value(mode, value, value.label())
                                                 .changed()
                                             {
                                                 codec_changed = true;
@@ -194,7 +264,7 @@ impl crate::app::WavesPreviewer {
                             });
                             ui.label(
                                 RichText::new(
-                                    "TPDF decorrelates 16-bit quantization error; noise shaping                                      additionally pushes the noise out of the most audible band                                      (2nd-order highpass).",
+                                    "TPDF decorrelates 16-bit quantization error. The highpass modes push the noise up in frequency (1st order: gentle, 2nd order: steeper); the psychoacoustic curve (44.1/48 kHz) also pulls it down around 2-5 kHz, where hearing is most sensitive.",
                                 )
                                 .weak()
                                 .small(),
@@ -211,6 +281,18 @@ impl crate::app::WavesPreviewer {
                             {
                                 codec_changed = true;
                             }
+                            if ui
+                                .checkbox(
+                                    &mut self.export_cfg.codec.dither_auto_blank,
+                                    "Auto-blank dither on digital silence",
+                                )
+                                .on_hover_text(
+                                    "Write runs of exact digital silence as true zeros instead of dither noise (dither resumes as soon as signal returns).",
+                                )
+                                .changed()
+                            {
+                                codec_changed = true;
+                            }
                             if codec_changed {
                                 self.save_prefs();
                             }
@@ -416,6 +498,39 @@ impl crate::app::WavesPreviewer {
                                 }
                             });
                             ui.separator();
+                            ui.label("Loudness (LUFS):");
+                            ui.horizontal(|ui| {
+                                ui.label("Surround layout:");
+                                let current = self.loudness_layout_override;
+                                let mut picked = current;
+                                egui::ComboBox::from_id_salt("loudness_layout_override")
+                                    .selected_text(
+                                        current
+                                            .map(|layout| layout.describe())
+                                            .unwrap_or_else(|| "From file".to_string()),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut picked, None, "From file");
+                                        for (channels, mask) in LOUDNESS_LAYOUT_CHOICES {
+                                            let layout = ChannelLayout::new(channels, mask);
+                                            ui.selectable_value(
+                                                &mut picked,
+                                                Some(layout),
+                                                layout.describe(),
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(
+                                        "BS.1770 channel weights (LFE excluded, surrounds +1.5 dB) \
+                                         follow the file's channel mask. An override replaces it \
+                                         for files with the same channel count.",
+                                    );
+                                if picked != current {
+                                    self.set_loudness_layout_override(picked);
+                                }
+                            });
+                            ui.separator();
                             ui.label("Editor:");
                             let mut eps = self.zero_cross_epsilon;
                             if ui
@@ -514,6 +629,7 @@ impl crate::app::WavesPreviewer {
                                             (ViewMode::Tempogram, "Tempogram"),
                                             (ViewMode::Chromagram, "Chromagram"),
                                             (ViewMode::World, "World (F0/Env)"),
+                                            (ViewMode::Loudness, "Loudness"),
                                         ] {
                                             if ui
                                                 .selectable_label(tab.leaf_view_mode() == vm, label)
@@ -212,6 +212,12 @@ impl crate::app::WavesPreviewer {
             if let Some(tp) = row.effective_true_peak_db {
                 vals.push(format!("{tp:+.1} dBTP"));
             }
+            if let Some(lra) = row.lra_lu {
+                vals.push(format!("LRA {lra:.1} LU"));
+            }
+            if let Some(st) = row.effective_lufs_s_max {
+                vals.push(format!("S max {st:+.1}"));
+            }
             if let (Some(lead), Some(trail)) = (row.leading_silence_ms, row.trailing_silence_ms) {
                 vals.push(format!("sil {lead:.0}/{trail:.0} ms"));
             }
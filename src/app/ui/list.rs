@@ -282,6 +282,7 @@ impl crate::app::WavesPreviewer {
         // the closure that borrows self mutably.
         let uses_hours = self.list_length_uses_hours();
         let blank_threshold_dbfs = self.blank_threshold_dbfs;
+        let loudness_layout_override = self.loudness_layout_override;
         // Compile the search highlight regex once per frame instead of per row.
         let highlight_re = self.cached_highlight_regex();
         let metrics = self.list_view_metrics(ui);
@@ -401,9 +402,16 @@ impl crate::app::WavesPreviewer {
                                     .as_ref()
                                     .map(|m| m.thumb.is_empty() && m.decode_error.is_none())
                                     .unwrap_or(true);
+                            // A loudness measured under a layout override the
+                            // user has since changed is stale, not missing.
                             let needs_lufs_meta = cols.lufs
                                 && !self.lufs_override.contains_key(&path_owned)
-                                && item.meta.as_ref().and_then(|m| m.lufs_i).is_none();
+                                && item.meta.as_ref().map_or(true, |m| {
+                                    m.lufs_i.is_none()
+                                        || m.loudness_layout.is_some_and(|l| {
+                                            l.is_stale(loudness_layout_override)
+                                        })
+                                });
                             let decode_ok = item
                                 .meta
                                 .as_ref()
@@ -420,6 +428,10 @@ impl crate::app::WavesPreviewer {
                                         .as_ref()
                                         .and_then(|m| m.lufs_m_max)
                                         .is_none())
+                                // LRA stays unset under 3 s, so key off the
+                                // full-decode marker instead.
+                                || (cols.lra
+                                    && item.meta.as_ref().map_or(true, |m| m.peak_db_estimate))
                                 || ((cols.silence_lead || cols.silence_tail)
                                     && item
                                         .meta
@@ -1059,7 +1071,9 @@ impl crate::app::WavesPreviewer {
                                         ui.painter().rect_filled(ui.max_rect(), 0.0, bg);
                                     }
                                     ui.visuals_mut().override_text_color = row_fg;
-                                    let base = self.meta_for_path(&path_owned).and_then(|m| m.lufs_i);
+                                    let meta = self.meta_for_path(&path_owned);
+                                    let base = meta.and_then(|m| m.lufs_i);
+                                    let layout = meta.and_then(|m| m.loudness_layout);
                                     let gain_db = self.pending_gain_db_for_path(&path_owned);
                                     let eff = if let Some(v) = self.lufs_override.get(&path_owned) {
                                         Some(*v)
@@ -1084,6 +1098,16 @@ impl crate::app::WavesPreviewer {
                                         fid,
                                         egui::Color32::WHITE,
                                     );
+                                    let resp2 = match layout {
+                                        Some(l) if eff.is_some() => {
+                                            resp2.on_hover_text(format!(
+                                                "Weighted as {}{}",
+                                                l.layout.describe(),
+                                                if l.overridden { " (override)" } else { "" }
+                                            ))
+                                        }
+                                        _ => resp2,
+                                    };
                                     let resp2 = self.attach_row_context_menu(resp2, row_idx, ctx);
                                     if resp2.clicked_by(egui::PointerButton::Primary) {
                                         clicked_to_load = true;
@@ -1274,6 +1298,32 @@ impl crate::app::WavesPreviewer {
                                     }
                                 });
                             }
+                            C::Lra => {
+                                row.col(|ui| {
+                                    if let Some(bg) = row_bg {
+                                        ui.painter().rect_filled(ui.max_rect(), 0.0, bg);
+                                    }
+                                    ui.visuals_mut().override_text_color = row_fg;
+                                    let meta = self.meta_for_path(&path_owned);
+                                    let text = match meta.and_then(|m| m.lra_lu) {
+                                        Some(lu) => format!("{lu:.1}"),
+                                        None if meta.is_some_and(|m| !m.peak_db_estimate) => {
+                                            "-".into()
+                                        }
+                                        None => "...".into(),
+                                    };
+                                    let resp = ui
+                                        .add(
+                                            egui::Label::new(RichText::new(text).monospace())
+                                                .sense(Sense::click()),
+                                        )
+                                        .on_hover_cursor(egui::CursorIcon::PointingHand);
+                                    let resp = self.attach_row_context_menu(resp, row_idx, ctx);
+                                    if resp.clicked_by(egui::PointerButton::Primary) {
+                                        clicked_to_load = true;
+                                    }
+                                });
+                            }
                             C::Bpm => {
                                 row.col(|ui| {
                                     if let Some(bg) = row_bg {
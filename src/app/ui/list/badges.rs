@@ -49,12 +49,24 @@ impl WavesPreviewer {
                         Color32::from_rgb(48, 96, 168),
                         Color32::from_rgb(120, 182, 255),
                     ),
+                    "w64" => (
+                        "W64".to_string(),
+                        "Sony Wave64 file".to_string(),
+                        Color32::from_rgb(40, 84, 146),
+                        Color32::from_rgb(112, 168, 236),
+                    ),
                     "aiff" | "aif" => (
                         "AIFF".to_string(),
                         "AIFF file".to_string(),
                         Color32::from_rgb(88, 70, 150),
                         Color32::from_rgb(176, 156, 244),
                     ),
+                    "caf" => (
+                        "CAF".to_string(),
+                        "Core Audio Format file".to_string(),
+                        Color32::from_rgb(112, 70, 132),
+                        Color32::from_rgb(212, 162, 236),
+                    ),
                     "flac" => (
                         "FLAC".to_string(),
                         "FLAC file".to_string(),
@@ -79,6 +91,12 @@ impl WavesPreviewer {
                         Color32::from_rgb(88, 106, 128),
                         Color32::from_rgb(172, 198, 228),
                     ),
+                    "opus" => (
+                        "OPUS".to_string(),
+                        "Ogg Opus file".to_string(),
+                        Color32::from_rgb(104, 88, 132),
+                        Color32::from_rgb(196, 178, 236),
+                    ),
                     _ => {
                         let upper = if ext.is_empty() {
                             "FILE".to_string()
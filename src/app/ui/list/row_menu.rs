@@ -206,6 +206,13 @@ impl WavesPreviewer {
                 self.spawn_convert_format_selected(selected.clone(), "wav");
                 ui.close();
             }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("To W64"))
+                .clicked()
+            {
+                self.spawn_convert_format_selected(selected.clone(), "w64");
+                ui.close();
+            }
             if ui
                 .add_enabled(has_selection, egui::Button::new("To AIFF"))
                 .clicked()
@@ -213,6 +220,13 @@ impl WavesPreviewer {
                 self.spawn_convert_format_selected(selected.clone(), "aiff");
                 ui.close();
             }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("To CAF"))
+                .clicked()
+            {
+                self.spawn_convert_format_selected(selected.clone(), "caf");
+                ui.close();
+            }
             if ui
                 .add_enabled(has_selection, egui::Button::new("To FLAC"))
                 .clicked()
@@ -241,6 +255,17 @@ impl WavesPreviewer {
                 self.spawn_convert_format_selected(selected.clone(), "ogg");
                 ui.close();
             }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("To OPUS"))
+                .clicked()
+            {
+                self.spawn_convert_format_selected(selected.clone(), "opus");
+                ui.close();
+            }
+        });
+        let artwork_targets = self.artwork_targets(&selected);
+        ui.menu_button("Artwork", |ui| {
+            self.artwork_menu_contents(ui, &artwork_targets);
         });
         if ui
             .add_enabled(has_selection, egui::Button::new("Remove from List"))
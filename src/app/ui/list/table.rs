@@ -304,6 +304,7 @@ impl WavesPreviewer {
             "bits" => 50.0,
             "bit_rate" => 70.0,
             "peak" | "lufs" | "dbtp" | "lufs_s" | "lufs_m" => 90.0,
+            "lra" => 60.0,
             "silence_lead" | "silence_tail" => 80.0,
             "edge_zero" | "over_peak" | "blank_pad" => 60.0,
             "bpm" => 70.0,
@@ -352,6 +353,7 @@ impl WavesPreviewer {
                 C::Dbtp => Some(("dBTP", SortKey::TruePeak, false)),
                 C::LufsS => Some(("LUFS-S", SortKey::LufsShort, false)),
                 C::LufsM => Some(("LUFS-M", SortKey::LufsMomentary, false)),
+                C::Lra => Some(("LRA", SortKey::LoudnessRange, false)),
                 C::SilenceLead => Some(("Sil.Head", SortKey::SilenceLead, false)),
                 C::SilenceTail => Some(("Sil.Tail", SortKey::SilenceTail, false)),
                 // Descending first so one click brings the NG rows to the top.
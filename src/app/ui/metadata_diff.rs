@@ -0,0 +1,204 @@
+use egui::{Color32, RichText};
+
+use crate::metadata::diff::{DiffKind, MetadataDiff, NodeDiffSide};
+
+fn diff_kind_color(kind: DiffKind) -> Option<Color32> {
+    match kind {
+        DiffKind::Unchanged => None,
+        DiffKind::Added => Some(Color32::from_rgb(110, 200, 120)),
+        DiffKind::Removed => Some(Color32::from_rgb(235, 110, 110)),
+        DiffKind::Changed => Some(Color32::from_rgb(235, 190, 90)),
+        DiffKind::Moved => Some(Color32::from_rgb(120, 170, 235)),
+    }
+}
+
+fn diff_kind_marker(kind: DiffKind) -> &'static str {
+    match kind {
+        DiffKind::Unchanged => " ",
+        DiffKind::Added => "+",
+        DiffKind::Removed => "-",
+        DiffKind::Changed => "~",
+        DiffKind::Moved => "↕",
+    }
+}
+
+fn diff_cell(ui: &mut egui::Ui, text: Option<String>, kind: DiffKind) {
+    let Some(text) = text else {
+        ui.label(RichText::new("—").weak());
+        return;
+    };
+    let mut rich = RichText::new(text).monospace();
+    if let Some(color) = diff_kind_color(kind) {
+        rich = rich.color(color);
+    }
+    ui.add(
+        egui::Label::new(rich)
+            .truncate()
+            .show_tooltip_when_elided(true),
+    );
+}
+
+fn node_side_text(side: &NodeDiffSide) -> String {
+    let mut text = format!("{} B @0x{:X}", side.size, side.offset);
+    if side.declared_size != side.size {
+        text.push_str(&format!(" (declared {})", side.declared_size));
+    }
+    if let Some(hash) = &side.sha256 {
+        text.push_str(&format!("  {}", &hash[..hash.len().min(12)]));
+    }
+    if let Some(summary) = &side.summary {
+        let summary = summary.chars().take(80).collect::<String>();
+        text.push_str(&format!("  {summary}"));
+    }
+    text
+}
+
+fn diff_counts_text(diff: &MetadataDiff) -> String {
+    let fields = diff.field_counts;
+    let nodes = diff.node_counts;
+    format!(
+        "fields +{} -{} ~{} · nodes +{} -{} ~{} ↕{}",
+        fields.added,
+        fields.removed,
+        fields.changed,
+        nodes.added,
+        nodes.removed,
+        nodes.changed,
+        nodes.moved
+    )
+}
+
+impl crate::app::WavesPreviewer {
+    /// Side-by-side view of the tab's last metadata diff: normalized fields,
+    /// then the node tree with sizes, payload hashes and summaries.
+    pub(in crate::app) fn ui_metadata_diff_window(&mut self, ctx: &egui::Context, tab_idx: usize) {
+        let tab = &mut self.tabs[tab_idx];
+        let Some(diff) = tab.metadata_diff.as_ref() else {
+            return;
+        };
+        let mut open = true;
+        let mut copy_json = false;
+        let only_changes = &mut tab.metadata_diff_only_changes;
+        egui::Window::new("Metadata Diff")
+            .id(egui::Id::new(("metadata_diff_window", tab.tab_id)))
+            .open(&mut open)
+            .default_size([880.0, 520.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                egui::Grid::new(("metadata_diff_sides", tab.tab_id))
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (label, side) in [("Left", &diff.left), ("Right", &diff.right)] {
+                            ui.label(RichText::new(label).strong());
+                            ui.label(
+                                RichText::new(format!(
+                                    "{}  ({} · {} bytes{})",
+                                    side.label,
+                                    side.container.key(),
+                                    side.file_len,
+                                    if side.hashed {
+                                        ""
+                                    } else {
+                                        " · no payload hashes"
+                                    }
+                                ))
+                                .monospace(),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal_wrapped(|ui| {
+                    if diff.identical {
+                        ui.colored_label(Color32::from_rgb(110, 200, 120), "Identical");
+                    } else {
+                        ui.label(diff_counts_text(diff));
+                    }
+                    ui.separator();
+                    ui.checkbox(only_changes, "Only differences");
+                    if ui.button("Copy JSON").clicked() {
+                        copy_json = true;
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new(format!("Fields ({})", diff.fields.len()))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                egui::Grid::new(("metadata_diff_fields", tab.tab_id))
+                                    .num_columns(3)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for field in &diff.fields {
+                                            if *only_changes && field.kind == DiffKind::Unchanged {
+                                                continue;
+                                            }
+                                            diff_cell(
+                                                ui,
+                                                Some(format!(
+                                                    "{} {}",
+                                                    diff_kind_marker(field.kind),
+                                                    field.key
+                                                )),
+                                                field.kind,
+                                            );
+                                            diff_cell(ui, field.left.clone(), field.kind);
+                                            diff_cell(ui, field.right.clone(), field.kind);
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        egui::CollapsingHeader::new(format!("Nodes ({})", diff.nodes.len()))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                egui::Grid::new(("metadata_diff_nodes", tab.tab_id))
+                                    .num_columns(3)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for node in &diff.nodes {
+                                            if *only_changes && node.kind == DiffKind::Unchanged {
+                                                continue;
+                                            }
+                                            let name =
+                                                node.path.rsplit('/').next().unwrap_or(&node.path);
+                                            let occurrence = if node.occurrence > 0 {
+                                                format!(" #{}", node.occurrence + 1)
+                                            } else {
+                                                String::new()
+                                            };
+                                            diff_cell(
+                                                ui,
+                                                Some(format!(
+                                                    "{} {}{name}{occurrence}",
+                                                    diff_kind_marker(node.kind),
+                                                    "  ".repeat(node.depth)
+                                                )),
+                                                node.kind,
+                                            );
+                                            diff_cell(
+                                                ui,
+                                                node.left.as_ref().map(node_side_text),
+                                                node.kind,
+                                            );
+                                            diff_cell(
+                                                ui,
+                                                node.right.as_ref().map(node_side_text),
+                                                node.kind,
+                                            );
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    });
+            });
+        if copy_json {
+            if let Ok(json) = serde_json::to_string_pretty(diff) {
+                ctx.copy_text(json);
+            }
+        }
+        if !open {
+            tab.metadata_diff = None;
+        }
+    }
+}
@@ -7,9 +7,10 @@ use crate::app::types::{
     EditorPrimaryView, MetadataActionResult, MetadataDetailTab, MetadataHexPage, MetadataSubView,
     VirtualSourceRef,
 };
+use crate::metadata::chunk_edit::{ChunkEdit, ChunkEditOutput};
 use crate::metadata::{
-    ContentKind, HashAlgorithm, MetadataDocument, MetadataNode, NodeId, PayloadRef, SearchKind,
-    SourceRange,
+    ContainerKind, ContentKind, DiagnosticLevel, HashAlgorithm, MetadataDocument, MetadataNode,
+    NodeId, PayloadRef, SearchKind, SourceRange,
 };
 
 const HEX_PAGE_BYTES: usize = 64 * 1024;
@@ -48,6 +49,43 @@ enum PendingMetadataAction {
         output: PathBuf,
         overwrite: bool,
     },
+    Repair {
+        output: PathBuf,
+        options: crate::metadata::repair::RepairOptions,
+    },
+    ChunkEdit {
+        edits: Vec<ChunkEdit>,
+        output: ChunkEditOutput,
+    },
+    Diff {
+        left: MetadataDiffTarget,
+        right: MetadataDiffTarget,
+    },
+}
+
+/// One side of an inspector diff: a file that is inspected (and hashed) in
+/// the background, or a document the tab already holds.
+enum MetadataDiffTarget {
+    File(PathBuf),
+    Snapshot(String, Arc<MetadataDocument>),
+}
+
+impl MetadataDiffTarget {
+    fn into_side(
+        self,
+        cancel: &std::sync::atomic::AtomicBool,
+    ) -> anyhow::Result<crate::metadata::diff::DiffSide> {
+        match self {
+            Self::File(path) => crate::metadata::diff::DiffSide::inspect(
+                &path,
+                crate::metadata::diff::DiffOptions::default(),
+                Some(cancel),
+            ),
+            Self::Snapshot(label, document) => Ok(crate::metadata::diff::DiffSide::from_document(
+                label, document,
+            )),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -108,6 +146,12 @@ impl crate::app::WavesPreviewer {
                 MetadataSubView::Hex,
                 "Hex",
             );
+            ui.selectable_value(
+                &mut self.tabs[tab_idx].metadata_sub_view,
+                MetadataSubView::Ucs,
+                "UCS",
+            )
+            .on_hover_text("Edit UCS / ASWG fields and write them back to the file");
             ui.separator();
             if ui.button("Refresh").clicked() {
                 refresh = true;
@@ -159,6 +203,14 @@ impl crate::app::WavesPreviewer {
                     "Source metadata — it may not match the current edited buffer.",
                 );
             }
+            let artwork_targets = if source_is_virtual_origin {
+                Vec::new()
+            } else {
+                self.artwork_targets(std::slice::from_ref(&source_path))
+            };
+            ui.menu_button("Artwork", |ui| {
+                self.artwork_menu_contents(ui, &artwork_targets);
+            });
         });
         if let Some(error) = self.tabs[tab_idx].metadata_error.clone() {
             ui.colored_label(Color32::LIGHT_RED, error);
@@ -174,6 +226,10 @@ impl crate::app::WavesPreviewer {
         };
         let mut registry_changed = false;
         let mut detected_additions = Vec::new();
+        let mut repair_action = None;
+        let mut chunk_edits = None;
+        let mut chunk_export = None;
+        let mut diff_action = None;
         ui.horizontal_wrapped(|ui| {
             ui.menu_button("Detected Fields", |ui| {
                 ui.label("Normalized fields in this file");
@@ -210,7 +266,156 @@ impl crate::app::WavesPreviewer {
                     }
                 }
             });
+            let repairable = matches!(
+                document.container,
+                ContainerKind::RiffWave
+                    | ContainerKind::Rf64
+                    | ContainerKind::Bw64
+                    | ContainerKind::Aiff
+                    | ContainerKind::Aifc
+            );
+            if repairable {
+                let busy = self.tabs[tab_idx].metadata_action_rx.is_some();
+                ui.menu_button("Repair", |ui| {
+                    let problems = document
+                        .diagnostics
+                        .iter()
+                        .filter(|diagnostic| diagnostic.level != DiagnosticLevel::Info)
+                        .count();
+                    ui.label(format!("{problems} warning(s) / error(s) diagnosed"));
+                    ui.checkbox(
+                        &mut self.tabs[tab_idx].metadata_repair_keep_unknown,
+                        "Keep unknown chunks",
+                    );
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Repair to Copy..."))
+                        .on_hover_text(
+                            "Rebuild sizes, pad bytes and whole frames into a new file (RF64 when over 4 GiB)",
+                        )
+                        .clicked()
+                    {
+                        let stem = source_path
+                            .file_stem()
+                            .and_then(|stem| stem.to_str())
+                            .unwrap_or("repaired");
+                        let ext = source_path
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .unwrap_or("wav");
+                        let mut dialog =
+                            rfd::FileDialog::new().set_file_name(format!("{stem}.repaired.{ext}"));
+                        if let Some(dir) = source_path.parent() {
+                            dialog = dialog.set_directory(dir);
+                        }
+                        if let Some(output) = dialog.save_file() {
+                            repair_action = Some(PendingMetadataAction::Repair {
+                                output,
+                                options: crate::metadata::repair::RepairOptions {
+                                    keep_unknown_chunks: self.tabs[tab_idx]
+                                        .metadata_repair_keep_unknown,
+                                },
+                            });
+                        }
+                        ui.close();
+                    }
+                });
+                ui.menu_button("Chunks", |ui| {
+                    self.metadata_chunk_menu_contents(
+                        ui,
+                        tab_idx,
+                        &document,
+                        busy,
+                        &mut chunk_edits,
+                        &mut chunk_export,
+                    );
+                });
+            }
+            let busy = self.tabs[tab_idx].metadata_action_rx.is_some();
+            ui.menu_button("Diff", |ui| {
+                diff_action = self.metadata_diff_menu_contents(
+                    ui,
+                    tab_idx,
+                    &source_path,
+                    source_is_virtual_origin,
+                    &document,
+                    busy,
+                );
+            });
         });
+        if let Some(action) = repair_action {
+            self.start_metadata_action(tab_idx, source_path.clone(), action);
+        }
+        if let Some(action) = diff_action {
+            self.start_metadata_action(tab_idx, source_path.clone(), action);
+        }
+        if let Some((id, payload)) = chunk_export {
+            let name = id.trim().replace(['/', '\\'], "_");
+            let mut dialog = rfd::FileDialog::new().set_file_name(format!("{name}.bin"));
+            if let Some(dir) = source_path.parent() {
+                dialog = dialog.set_directory(dir);
+            }
+            if let Some(output) = dialog.save_file() {
+                self.start_metadata_action(
+                    tab_idx,
+                    source_path.clone(),
+                    PendingMetadataAction::Extract {
+                        payload,
+                        output,
+                        overwrite: true,
+                    },
+                );
+            }
+        }
+        if let Some(edits) = chunk_edits {
+            let output = if self.tabs[tab_idx].metadata_chunk_in_place {
+                Some(ChunkEditOutput::InPlace)
+            } else {
+                let stem = source_path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or("edited");
+                let ext = source_path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("wav");
+                let mut dialog =
+                    rfd::FileDialog::new().set_file_name(format!("{stem}.edited.{ext}"));
+                if let Some(dir) = source_path.parent() {
+                    dialog = dialog.set_directory(dir);
+                }
+                dialog.save_file().map(ChunkEditOutput::Copy)
+            };
+            if let Some(output) = output {
+                self.start_metadata_action(
+                    tab_idx,
+                    source_path.clone(),
+                    PendingMetadataAction::ChunkEdit { edits, output },
+                );
+            }
+        }
+        if let Some((output, report)) = self.tabs[tab_idx].metadata_repair_report.as_ref() {
+            egui::CollapsingHeader::new(format!(
+                "Repair report: {} change(s) -> {}",
+                report.changes.len(),
+                output.display()
+            ))
+            .id_salt(("metadata_repair_report", self.tabs[tab_idx].tab_id))
+            .show(ui, |ui| {
+                if report.changes.is_empty() {
+                    ui.label("No structural problems; the copy is byte-identical in layout.");
+                }
+                for change in &report.changes {
+                    let offset = change
+                        .offset
+                        .map(|offset| format!(" @0x{offset:X}"))
+                        .unwrap_or_default();
+                    ui.label(
+                        RichText::new(format!("{}{offset}: {}", change.code, change.message))
+                            .monospace(),
+                    );
+                }
+            });
+        }
         for (key, label) in detected_additions {
             self.add_metadata_list_column(key, label);
         }
@@ -228,6 +433,7 @@ impl crate::app::WavesPreviewer {
         if self.playback_is_playing_now() {
             ctx.request_repaint();
         }
+        self.ui_metadata_diff_window(ctx, tab_idx);
 
         match self.tabs[tab_idx].metadata_sub_view {
             MetadataSubView::Structure => self.ui_metadata_structure(
@@ -248,6 +454,13 @@ impl crate::app::WavesPreviewer {
                 exact_live,
                 source_time,
             ),
+            MetadataSubView::Ucs => self.ui_metadata_ucs(
+                ui,
+                tab_idx,
+                &source_path,
+                &document,
+                source_is_virtual_origin,
+            ),
         }
     }
 
@@ -271,7 +484,7 @@ impl crate::app::WavesPreviewer {
         None
     }
 
-    fn reset_metadata_tab(&mut self, tab_idx: usize) {
+    pub(in crate::app) fn reset_metadata_tab(&mut self, tab_idx: usize) {
         let tab = &mut self.tabs[tab_idx];
         tab.metadata_document = None;
         tab.metadata_loading = false;
@@ -295,9 +508,12 @@ impl crate::app::WavesPreviewer {
         }
         tab.metadata_action_progress = None;
         tab.metadata_action_total = 0;
+        tab.metadata_repair_report = None;
         tab.metadata_artwork_requested = None;
         tab.metadata_artwork_rx = None;
         tab.metadata_artwork_texture = None;
+        tab.metadata_ucs_draft = None;
+        tab.metadata_diff = None;
     }
 
     fn start_metadata_scan(&mut self, tab_idx: usize, path: PathBuf) {
@@ -370,6 +586,7 @@ impl crate::app::WavesPreviewer {
             .as_ref()
             .and_then(|rx| rx.try_recv().ok());
         if let Some(result) = action_result {
+            let mut rescan = None;
             let tab = &mut self.tabs[tab_idx];
             tab.metadata_action_rx = None;
             tab.metadata_action_cancel = None;
@@ -386,12 +603,54 @@ impl crate::app::WavesPreviewer {
                 Ok(MetadataActionResult::Extracted(path)) => {
                     tab.metadata_action_status = Some(format!("Extracted to {}", path.display()));
                 }
+                Ok(MetadataActionResult::Repaired(output, report)) => {
+                    tab.metadata_action_status = Some(format!(
+                        "Repaired to {}: {} change(s)",
+                        output.display(),
+                        report.changes.len()
+                    ));
+                    tab.metadata_repair_report = Some((output, report));
+                }
+                Ok(MetadataActionResult::ChunksEdited(report)) => {
+                    tab.metadata_action_status = Some(match &report.backup {
+                        Some(backup) => format!(
+                            "Chunks written in place ({} chunk(s)); backup: {}",
+                            report.chunks.len(),
+                            backup.display()
+                        ),
+                        None => format!(
+                            "Chunks written to {} ({} chunk(s))",
+                            report.output.display(),
+                            report.chunks.len()
+                        ),
+                    });
+                    if report.backup.is_some() {
+                        rescan = Some(report.output);
+                    }
+                }
                 Ok(MetadataActionResult::CopyHex(value)) => {
                     ctx.copy_text(value);
                     tab.metadata_action_status = Some("Copied hex bytes".to_string());
                 }
+                Ok(MetadataActionResult::Diffed(diff)) => {
+                    tab.metadata_action_status = Some(if diff.identical {
+                        "Diff: identical".to_string()
+                    } else {
+                        format!(
+                            "Diff: {} field / {} node difference(s)",
+                            diff.field_counts.total(),
+                            diff.node_counts.total()
+                        )
+                    });
+                    tab.metadata_diff = Some(diff);
+                }
                 Err(error) => tab.metadata_action_status = Some(format!("Error: {error}")),
             }
+            if let Some(path) = rescan {
+                let status = self.tabs[tab_idx].metadata_action_status.take();
+                self.invalidate_metadata_for_path(&path);
+                self.tabs[tab_idx].metadata_action_status = status;
+            }
             ctx.request_repaint();
         }
 
@@ -972,6 +1231,10 @@ impl crate::app::WavesPreviewer {
             | PendingMetadataAction::Hash { payload }
             | PendingMetadataAction::Copy { payload }
             | PendingMetadataAction::Extract { payload, .. } => payload.length,
+            PendingMetadataAction::Repair { .. } | PendingMetadataAction::ChunkEdit { .. } => {
+                std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0)
+            }
+            PendingMetadataAction::Diff { .. } => 0,
         };
         let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
         let progress = Arc::new(std::sync::atomic::AtomicU64::new(0));
@@ -1036,12 +1299,284 @@ impl crate::app::WavesPreviewer {
                     Some(&progress),
                 )
                 .map(|_| MetadataActionResult::Extracted(output)),
+                PendingMetadataAction::Repair { output, options } => {
+                    crate::metadata::repair::repair_to_path(
+                        &path,
+                        &output,
+                        &options,
+                        true,
+                        Some(&cancel),
+                        Some(&progress),
+                    )
+                    .map(|report| MetadataActionResult::Repaired(output, report))
+                }
+                PendingMetadataAction::ChunkEdit { edits, output } => {
+                    crate::metadata::chunk_edit::apply_chunk_edits(
+                        &path,
+                        &edits,
+                        &output,
+                        true,
+                        Some(&cancel),
+                        Some(&progress),
+                    )
+                    .map(MetadataActionResult::ChunksEdited)
+                }
+                PendingMetadataAction::Diff { left, right } => left
+                    .into_side(&cancel)
+                    .and_then(|left| Ok((left, right.into_side(&cancel)?)))
+                    .map(|(left, right)| {
+                        MetadataActionResult::Diffed(crate::metadata::diff::diff_documents(
+                            &left, &right,
+                        ))
+                    }),
             }
             .map_err(|error| error.to_string());
             let _ = tx.send(result);
         });
     }
 
+    /// "Diff" menu: compare this file with its `.bak`, with the document it
+    /// had before the last in-place write, or with another file.
+    fn metadata_diff_menu_contents(
+        &mut self,
+        ui: &mut egui::Ui,
+        tab_idx: usize,
+        source_path: &Path,
+        source_is_virtual_origin: bool,
+        document: &Arc<MetadataDocument>,
+        busy: bool,
+    ) -> Option<PendingMetadataAction> {
+        let mut action = None;
+        let backup = crate::metadata::chunk_edit::backup_path(source_path);
+        let has_backup = !source_is_virtual_origin && backup.is_file();
+        if ui
+            .add_enabled(!busy && has_backup, egui::Button::new("Compare with .bak"))
+            .on_hover_text(backup.display().to_string())
+            .clicked()
+        {
+            action = Some(PendingMetadataAction::Diff {
+                left: MetadataDiffTarget::File(backup.clone()),
+                right: MetadataDiffTarget::File(source_path.to_path_buf()),
+            });
+            ui.close();
+        }
+        let presave = self.tabs[tab_idx].metadata_presave.clone();
+        if ui
+            .add_enabled(
+                !busy && presave.is_some(),
+                egui::Button::new("Compare with Pre-save State"),
+            )
+            .on_hover_text("Document before the last in-place write in this session")
+            .clicked()
+        {
+            if let Some(presave) = presave {
+                let name = source_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                action = Some(PendingMetadataAction::Diff {
+                    left: MetadataDiffTarget::Snapshot(format!("{name} (pre-save)"), presave),
+                    right: MetadataDiffTarget::Snapshot(name, Arc::clone(document)),
+                });
+            }
+            ui.close();
+        }
+        if ui
+            .add_enabled(!busy, egui::Button::new("Compare with File..."))
+            .clicked()
+        {
+            let mut dialog = rfd::FileDialog::new();
+            if let Some(dir) = source_path.parent() {
+                dialog = dialog.set_directory(dir);
+            }
+            if let Some(other) = dialog.pick_file() {
+                action = Some(PendingMetadataAction::Diff {
+                    left: MetadataDiffTarget::File(source_path.to_path_buf()),
+                    right: MetadataDiffTarget::File(other),
+                });
+            }
+            ui.close();
+        }
+        if self.tabs[tab_idx].metadata_diff.is_some() {
+            ui.separator();
+            if ui.button("Close Diff").clicked() {
+                self.tabs[tab_idx].metadata_diff = None;
+                ui.close();
+            }
+        }
+        action
+    }
+
+    /// "Chunks" menu: reorder / delete / export the top-level chunk selected
+    /// in the tree, or insert a new one before it. Edits are only collected
+    /// here; the caller asks for the output and runs them in the background.
+    fn metadata_chunk_menu_contents(
+        &mut self,
+        ui: &mut egui::Ui,
+        tab_idx: usize,
+        document: &MetadataDocument,
+        busy: bool,
+        edits: &mut Option<Vec<ChunkEdit>>,
+        export: &mut Option<(String, PayloadRef)>,
+    ) {
+        let entries = match crate::metadata::chunk_edit::chunk_entries(document) {
+            Ok(entries) => entries,
+            Err(err) => {
+                ui.colored_label(Color32::LIGHT_RED, format!("{err:#}"));
+                return;
+            }
+        };
+        let selected = self.tabs[tab_idx]
+            .metadata_selected_node
+            .and_then(|node| crate::metadata::chunk_edit::chunk_index_for_node(document, node));
+        match selected {
+            Some(index) => ui.label(format!(
+                "Selected: {} (#{index}, {} bytes)",
+                entries[index].id, entries[index].length
+            )),
+            None => ui.label(RichText::new("Select a top-level chunk in the tree").weak()),
+        };
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !busy && selected.is_some_and(|index| index > 0),
+                    egui::Button::new("Move Up"),
+                )
+                .clicked()
+            {
+                if let Some(index) = selected {
+                    *edits = Some(vec![ChunkEdit::Move {
+                        from: index,
+                        to: index - 1,
+                    }]);
+                    ui.close();
+                }
+            }
+            if ui
+                .add_enabled(
+                    !busy && selected.is_some_and(|index| index + 1 < entries.len()),
+                    egui::Button::new("Move Down"),
+                )
+                .clicked()
+            {
+                if let Some(index) = selected {
+                    *edits = Some(vec![ChunkEdit::Move {
+                        from: index,
+                        to: index + 1,
+                    }]);
+                    ui.close();
+                }
+            }
+        });
+        let audio = entries
+            .iter()
+            .position(|entry| entry.id == "data" || entry.id == "SSND");
+        if let (Some(index), Some(audio)) = (selected, audio) {
+            if index > audio
+                && ui
+                    .add_enabled(
+                        !busy,
+                        egui::Button::new(format!("Move Before {}", entries[audio].id)),
+                    )
+                    .on_hover_text("Readers that stop at the audio chunk will still see it")
+                    .clicked()
+            {
+                *edits = Some(vec![ChunkEdit::Move {
+                    from: index,
+                    to: audio,
+                }]);
+                ui.close();
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!busy && selected.is_some(), egui::Button::new("Delete"))
+                .clicked()
+            {
+                if let Some(index) = selected {
+                    *edits = Some(vec![ChunkEdit::Remove { index }]);
+                    ui.close();
+                }
+            }
+            if ui
+                .add_enabled(
+                    !busy && selected.is_some(),
+                    egui::Button::new("Export Payload..."),
+                )
+                .clicked()
+            {
+                if let Some(index) = selected {
+                    if let Ok(payload) = crate::metadata::chunk_edit::chunk_payload(document, index)
+                    {
+                        *export = Some((entries[index].id.clone(), payload));
+                    }
+                    ui.close();
+                }
+            }
+        });
+        ui.separator();
+        ui.label(if selected.is_some() {
+            "Insert before the selection"
+        } else {
+            "Insert at the end"
+        });
+        let tab = &mut self.tabs[tab_idx];
+        ui.horizontal(|ui| {
+            ui.label("ID");
+            ui.add(
+                egui::TextEdit::singleline(&mut tab.metadata_chunk_insert_id)
+                    .char_limit(4)
+                    .desired_width(48.0)
+                    .font(egui::TextStyle::Monospace),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut tab.metadata_chunk_insert_hex)
+                    .hint_text("hex bytes")
+                    .desired_width(200.0)
+                    .font(egui::TextStyle::Monospace),
+            );
+        });
+        let index = selected.unwrap_or(entries.len());
+        ui.horizontal(|ui| {
+            let insert_hex = ui.add_enabled(!busy, egui::Button::new("Insert Hex"));
+            let insert_file = ui.add_enabled(!busy, egui::Button::new("Insert from File..."));
+            if !insert_hex.clicked() && !insert_file.clicked() {
+                return;
+            }
+            let payload = if insert_hex.clicked() {
+                crate::metadata::chunk_edit::parse_hex_payload(&tab.metadata_chunk_insert_hex)
+                    .map(Some)
+            } else {
+                rfd::FileDialog::new()
+                    .pick_file()
+                    .map(|path| {
+                        std::fs::read(&path)
+                            .map_err(|err| anyhow::anyhow!("read {}: {err}", path.display()))
+                    })
+                    .transpose()
+            };
+            let edit = crate::metadata::chunk_edit::parse_chunk_id(
+                tab.metadata_chunk_insert_id.trim_end(),
+            )
+            .and_then(|id| {
+                payload
+                    .map(|payload| payload.map(|payload| ChunkEdit::Insert { index, id, payload }))
+            });
+            match edit {
+                Ok(Some(edit)) => *edits = Some(vec![edit]),
+                Ok(None) => {}
+                Err(err) => tab.metadata_action_status = Some(format!("Error: {err:#}")),
+            }
+            ui.close();
+        });
+        ui.separator();
+        ui.checkbox(
+            &mut tab.metadata_chunk_in_place,
+            "Overwrite source (keep .bak)",
+        )
+        .on_hover_text("Off: every edit is saved to a copy you choose");
+    }
+
     fn request_metadata_artwork(&mut self, tab_idx: usize, path: PathBuf, node: &MetadataNode) {
         let tab = &mut self.tabs[tab_idx];
         if tab
@@ -2703,6 +3238,7 @@ fn metadata_node_hidden_in_structure(
         crate::metadata::ContainerKind::RiffWave
             | crate::metadata::ContainerKind::Rf64
             | crate::metadata::ContainerKind::Bw64
+            | crate::metadata::ContainerKind::W64
     ) && node.name.trim_end() == "fmt"
 }
 
@@ -2861,6 +3397,7 @@ mod tests {
             diagnostics: Vec::new(),
             completion: crate::metadata::Completion::Complete,
             audio_mapping: Some(mapping),
+            adm_objects: Vec::new(),
         };
         let timeline = MetadataAudioTimeline::from_document(&document);
 
@@ -1,3 +1,5 @@
+pub(super) mod analyzer;
+pub(super) mod artwork_dialog;
 pub(super) mod channel_routing;
 pub(super) mod debug;
 pub(super) mod dsp_widgets;
@@ -11,14 +13,18 @@ pub(super) mod inspection;
 pub(super) mod keymap_settings;
 pub(super) mod list;
 pub(super) mod list_columns;
+pub(super) mod metadata_diff;
 pub(super) mod metadata_inspector;
 pub(super) mod plugin_manager;
 pub(super) mod recording;
 pub(super) mod regions;
 pub(super) mod shortcuts;
+pub(super) mod tag_editor;
+pub(super) mod template_editor;
 pub(super) mod tools;
 pub(super) mod topbar;
 pub(super) mod transcript;
 pub(super) mod transcription_settings;
+pub(super) mod ucs_editor;
 pub(super) mod undo_history;
 pub(super) mod zoo;
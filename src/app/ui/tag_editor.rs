@@ -0,0 +1,202 @@
+use egui::{Color32, RichText};
+
+use crate::tags::TagKey;
+
+const CELL_WIDTH: f32 = 140.0;
+
+impl crate::app::WavesPreviewer {
+    /// Tag table over the list selection: one row per file, one column per
+    /// field or custom key. Edited cells are highlighted until written.
+    pub(crate) fn ui_tag_editor_dialog(&mut self, ctx: &egui::Context) {
+        if !self.tag_editor.show_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut write_clicked = false;
+        let mut fill_clicked = false;
+        let mut revert_clicked = false;
+        let mut add_column = false;
+        let columns = self.tag_editor_columns();
+        let taggable = self
+            .tag_editor
+            .rows
+            .iter()
+            .filter(|row| row.format.is_some())
+            .count();
+        let dirty = self
+            .tag_editor
+            .rows
+            .iter()
+            .filter(|row| row.format.is_some() && row.draft != row.stored)
+            .count();
+        let total = self.tag_editor.rows.len();
+        let scroll_target = self.begin_floating_scroll_surface("tag_editor_window");
+        let scroll_guard = self.pointer_scroll_input_guard(scroll_target, ctx);
+        let shown = egui::Window::new("Edit Tags")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(820.0)
+            .default_height(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{taggable} of {total} selected file(s) carry editable tags (MP3 ID3v2, M4A atoms, FLAC/Ogg/Opus Vorbis comments). Other frames and comments are kept."
+                ));
+                ui.horizontal(|ui| {
+                    ui.label("Custom key:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.tag_editor.new_column)
+                            .hint_text("e.g. MOOD")
+                            .desired_width(160.0),
+                    );
+                    let key = self.tag_editor.new_column.trim();
+                    let exists = self
+                        .tag_editor
+                        .custom_columns
+                        .iter()
+                        .any(|c| c.eq_ignore_ascii_case(key))
+                        || crate::tags::TagField::from_key(key).is_some();
+                    if ui
+                        .add_enabled(!key.is_empty() && !exists, egui::Button::new("Add column"))
+                        .on_hover_text("TXXX description (MP3), iTunes freeform name (M4A) or Vorbis comment key")
+                        .clicked()
+                    {
+                        add_column = true;
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::both()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("tag_editor_grid")
+                            .num_columns(columns.len() + 1)
+                            .striped(true)
+                            .spacing([6.0, 3.0])
+                            .show(ui, |ui| {
+                                ui.label(RichText::new("File").strong());
+                                for key in &columns {
+                                    ui.label(RichText::new(key.label()).strong());
+                                }
+                                ui.end_row();
+                                ui.label(RichText::new("Set all").weak());
+                                for key in &columns {
+                                    let mut value = self.tag_editor.fill.value(key).to_string();
+                                    if ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut value)
+                                                .hint_text("(keep)")
+                                                .desired_width(CELL_WIDTH),
+                                        )
+                                        .changed()
+                                    {
+                                        self.tag_editor.fill.set(key, &value);
+                                    }
+                                }
+                                ui.end_row();
+                                for row in self.tag_editor.rows.iter_mut() {
+                                    let name = row
+                                        .path
+                                        .file_name()
+                                        .and_then(|n| n.to_str())
+                                        .unwrap_or_default();
+                                    let mut label = RichText::new(name).monospace();
+                                    if row.error.is_some() {
+                                        label = label.color(Color32::LIGHT_RED);
+                                    }
+                                    let mut hover = row.path.display().to_string();
+                                    if let Some(format) = row.format {
+                                        hover.push_str(&format!("\n{}", format.label()));
+                                    }
+                                    if let Some(error) = &row.error {
+                                        hover.push_str(&format!("\n{error}"));
+                                    }
+                                    ui.label(label).on_hover_text(hover);
+                                    for key in &columns {
+                                        tag_cell(ui, row, key);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Fill")
+                        .on_hover_text("Copy the non-empty \"Set all\" cells into every row")
+                        .clicked()
+                    {
+                        fill_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(dirty > 0, egui::Button::new(format!("Write ({dirty})")))
+                        .on_hover_text("Write edited rows in place (undo: list undo)")
+                        .clicked()
+                    {
+                        write_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(dirty > 0, egui::Button::new("Revert"))
+                        .clicked()
+                    {
+                        revert_clicked = true;
+                    }
+                    if ui.button("Close").clicked() {
+                        self.tag_editor.show_dialog = false;
+                    }
+                });
+            });
+        drop(scroll_guard);
+        if let Some(shown) = shown.as_ref() {
+            self.register_scroll_surface(scroll_target, &shown.response);
+        }
+        if add_column {
+            let key = self.tag_editor.new_column.trim().to_string();
+            self.tag_editor.custom_columns.push(key);
+            self.tag_editor.new_column.clear();
+        }
+        if fill_clicked {
+            self.fill_tag_editor_rows();
+        }
+        if revert_clicked {
+            for row in self.tag_editor.rows.iter_mut() {
+                row.draft = row.stored.clone();
+            }
+        }
+        if write_clicked {
+            self.apply_tag_editor();
+        }
+        if !open {
+            self.tag_editor.show_dialog = false;
+        }
+    }
+}
+
+fn tag_cell(ui: &mut egui::Ui, row: &mut crate::app::types::TagEditorRow, key: &TagKey) {
+    let Some(format) = row.format else {
+        ui.label(RichText::new("—").weak());
+        return;
+    };
+    let key_error = match key {
+        TagKey::Custom(name) => crate::tags::custom_key_error(format, name),
+        TagKey::Field(_) => None,
+    };
+    if let Some(error) = key_error {
+        ui.label(RichText::new("n/a").weak()).on_hover_text(error);
+        return;
+    }
+    let stored = row.stored.value(key).to_string();
+    let mut value = row.draft.value(key).to_string();
+    let mut edit = egui::TextEdit::singleline(&mut value).desired_width(CELL_WIDTH);
+    if value != stored {
+        edit = edit.text_color(Color32::LIGHT_GREEN);
+    }
+    let response = ui.add(edit);
+    if value != stored && !stored.is_empty() {
+        response.clone().on_hover_text(format!("was: {stored}"));
+    }
+    if response.changed() {
+        row.draft.set(key, &value);
+    }
+}
@@ -0,0 +1,311 @@
+use egui::{Color32, RichText};
+
+use crate::metadata_template::{
+    MetadataTemplate, TemplateField, TemplateTarget, TEMPLATE_TARGETS, TEMPLATE_TOKENS,
+};
+
+const FILTER_HELP: &str =
+    "Filters follow `|`: upper, lower, trim, max=N, default=TEXT. `{{` and `}}` are literal braces.";
+
+impl crate::app::WavesPreviewer {
+    /// Template library editor plus a preview of every selected file's
+    /// rendered values; Apply writes them in place (list undo).
+    pub(crate) fn ui_metadata_template_dialog(&mut self, ctx: &egui::Context) {
+        if !self.metadata_templates.show_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut close_clicked = false;
+        let mut preview_clicked = false;
+        let mut apply_clicked = false;
+        let mut save_clicked = false;
+        let mut new_clicked = false;
+        let mut duplicate_clicked = false;
+        let mut delete_clicked = false;
+        let mut remove_field: Option<usize> = None;
+        let mut add_field = false;
+        let total = self.metadata_templates.paths.len();
+        let scroll_target = self.begin_floating_scroll_surface("metadata_template_window");
+        let scroll_guard = self.pointer_scroll_input_guard(scroll_target, ctx);
+        let state = &mut self.metadata_templates;
+        let shown = egui::Window::new("Apply Metadata Template")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(760.0)
+            .default_height(480.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{total} selected file(s). bext/iXML targets apply to WAV, tag targets to MP3/M4A/FLAC/Ogg/Opus."
+                ));
+                ui.horizontal(|ui| {
+                    ui.label("Template:");
+                    let selected_name = state
+                        .library
+                        .get(state.selected)
+                        .map(|template| template.name.clone())
+                        .unwrap_or_default();
+                    egui::ComboBox::from_id_salt("metadata_template_pick")
+                        .selected_text(selected_name)
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            for (idx, template) in state.library.iter().enumerate() {
+                                if ui
+                                    .selectable_label(idx == state.selected, &template.name)
+                                    .clicked()
+                                {
+                                    state.selected = idx;
+                                    state.preview.clear();
+                                    state.error = None;
+                                }
+                            }
+                        });
+                    if ui.button("New").clicked() {
+                        new_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(!state.library.is_empty(), egui::Button::new("Duplicate"))
+                        .clicked()
+                    {
+                        duplicate_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(!state.library.is_empty(), egui::Button::new("Delete"))
+                        .clicked()
+                    {
+                        delete_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(state.library_dirty, egui::Button::new("Save Library"))
+                        .on_hover_text("metadata-templates.json in the settings folder")
+                        .clicked()
+                    {
+                        save_clicked = true;
+                    }
+                });
+                if let Some(template) = state.library.get_mut(state.selected) {
+                    let mut edited = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Name:");
+                        edited |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut template.name)
+                                    .desired_width(200.0),
+                            )
+                            .changed();
+                    });
+                    egui::Grid::new("metadata_template_fields")
+                        .num_columns(3)
+                        .spacing([6.0, 3.0])
+                        .show(ui, |ui| {
+                            ui.label(RichText::new("Target").strong());
+                            ui.label(RichText::new("Expression").strong());
+                            ui.label("");
+                            ui.end_row();
+                            for (idx, field) in template.fields.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    let mut target = egui::TextEdit::singleline(&mut field.target)
+                                        .desired_width(170.0);
+                                    if TemplateTarget::parse(&field.target).is_err() {
+                                        target = target.text_color(Color32::LIGHT_RED);
+                                    }
+                                    edited |= ui.add(target).changed();
+                                    ui.menu_button("▾", |ui| {
+                                        for known in TEMPLATE_TARGETS {
+                                            if ui.button(known).clicked() {
+                                                field.target = known.to_string();
+                                                edited = true;
+                                                ui.close();
+                                            }
+                                        }
+                                    });
+                                });
+                                edited |= ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut field.expression)
+                                            .hint_text("{ucs.fxname} - {external.Scene}")
+                                            .desired_width(360.0),
+                                    )
+                                    .changed();
+                                if ui.small_button("✕").on_hover_text("Remove field").clicked() {
+                                    remove_field = Some(idx);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    if ui.button("Add Field").clicked() {
+                        add_field = true;
+                    }
+                    if edited {
+                        state.library_dirty = true;
+                        state.preview.clear();
+                    }
+                }
+                egui::CollapsingHeader::new("Tokens")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("metadata_template_tokens")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (token, help) in TEMPLATE_TOKENS {
+                                    ui.label(RichText::new(token).monospace());
+                                    ui.label(help);
+                                    ui.end_row();
+                                }
+                            });
+                        ui.label(RichText::new(FILTER_HELP).weak());
+                    });
+                if let Some(error) = &state.error {
+                    ui.colored_label(Color32::LIGHT_RED, error);
+                }
+                ui.separator();
+                egui::ScrollArea::both()
+                    .max_height(240.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("metadata_template_preview")
+                            .num_columns(4)
+                            .striped(true)
+                            .spacing([8.0, 3.0])
+                            .show(ui, |ui| {
+                                ui.label(RichText::new("File").strong());
+                                ui.label(RichText::new("Target").strong());
+                                ui.label(RichText::new("Value").strong());
+                                ui.label(RichText::new("Notes").strong());
+                                ui.end_row();
+                                for row in &state.preview {
+                                    let name = row
+                                        .path
+                                        .file_name()
+                                        .and_then(|n| n.to_str())
+                                        .unwrap_or_default();
+                                    for (idx, field) in row.fields.iter().enumerate() {
+                                        if idx == 0 {
+                                            let mut label = RichText::new(name).monospace();
+                                            if row.error.is_some() {
+                                                label = label.weak();
+                                            }
+                                            ui.label(label)
+                                                .on_hover_text(row.path.display().to_string());
+                                        } else {
+                                            ui.label("");
+                                        }
+                                        ui.label(RichText::new(&field.target).monospace());
+                                        if field.applies {
+                                            ui.add(
+                                                egui::Label::new(
+                                                    RichText::new(&field.value).monospace(),
+                                                )
+                                                .truncate()
+                                                .show_tooltip_when_elided(true),
+                                            );
+                                        } else {
+                                            ui.label(RichText::new("n/a").weak());
+                                        }
+                                        let mut notes = Vec::new();
+                                        if !field.missing.is_empty() {
+                                            notes.push(format!(
+                                                "no value: {}",
+                                                field.missing.join(", ")
+                                            ));
+                                        }
+                                        notes.extend(field.warnings.iter().cloned());
+                                        if notes.is_empty() {
+                                            ui.label("");
+                                        } else {
+                                            ui.colored_label(
+                                                Color32::from_rgb(235, 190, 90),
+                                                notes.join("; "),
+                                            );
+                                        }
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(total > 0, egui::Button::new("Preview"))
+                        .clicked()
+                    {
+                        preview_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(total > 0, egui::Button::new(format!("Apply ({total})")))
+                        .on_hover_text("Write the rendered values in place (undo: list undo)")
+                        .clicked()
+                    {
+                        apply_clicked = true;
+                    }
+                    if ui.button("Close").clicked() {
+                        close_clicked = true;
+                    }
+                });
+            });
+        drop(scroll_guard);
+        if let Some(shown) = shown.as_ref() {
+            self.register_scroll_surface(scroll_target, &shown.response);
+        }
+        let state = &mut self.metadata_templates;
+        if let Some(idx) = remove_field {
+            if let Some(template) = state.library.get_mut(state.selected) {
+                template.fields.remove(idx);
+                state.library_dirty = true;
+                state.preview.clear();
+            }
+        }
+        if add_field {
+            if let Some(template) = state.library.get_mut(state.selected) {
+                template.fields.push(TemplateField {
+                    target: TEMPLATE_TARGETS[0].to_string(),
+                    expression: String::new(),
+                });
+                state.library_dirty = true;
+            }
+        }
+        if new_clicked {
+            state.library.push(MetadataTemplate {
+                name: format!("Template {}", state.library.len() + 1),
+                fields: vec![TemplateField {
+                    target: TEMPLATE_TARGETS[0].to_string(),
+                    expression: "{name}".to_string(),
+                }],
+            });
+            state.selected = state.library.len() - 1;
+            state.library_dirty = true;
+            state.preview.clear();
+        }
+        if duplicate_clicked {
+            if let Some(template) = state.library.get(state.selected) {
+                let mut copy = template.clone();
+                copy.name = format!("{} copy", copy.name);
+                state.library.push(copy);
+                state.selected = state.library.len() - 1;
+                state.library_dirty = true;
+            }
+        }
+        if delete_clicked && state.selected < state.library.len() {
+            state.library.remove(state.selected);
+            state.selected = state.selected.min(state.library.len().saturating_sub(1));
+            state.library_dirty = true;
+            state.preview.clear();
+        }
+        if save_clicked {
+            self.save_metadata_template_library();
+        }
+        if preview_clicked {
+            self.build_metadata_template_preview();
+        }
+        if apply_clicked {
+            self.apply_metadata_template();
+        }
+        if !open || close_clicked {
+            self.metadata_templates.show_dialog = false;
+            if self.metadata_templates.library_dirty {
+                self.save_metadata_template_library();
+            }
+        }
+    }
+}
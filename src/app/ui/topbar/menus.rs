@@ -291,6 +291,16 @@ impl WavesPreviewer {
                 self.show_engine_export_dialog = true;
                 ui.close();
             }
+            if ui
+                .button("Export Library Catalog...")
+                .on_hover_text(
+                    "Write an SQLite/CSV catalog (technical info, metadata, UCS, markers, loudness, peaks, artwork) for library tools",
+                )
+                .clicked()
+            {
+                self.show_catalog_export_dialog = true;
+                ui.close();
+            }
             if ui
                 .button("Edit BWF Metadata...")
                 .on_hover_text(
@@ -301,6 +311,46 @@ impl WavesPreviewer {
                 self.open_bwf_dialog();
                 ui.close();
             }
+            if ui
+                .button("Edit Cart Chunk (AES46)...")
+                .on_hover_text(
+                    "Write the broadcast cart chunk (title, cut ID, dates, post-timers) into the selected WAV files",
+                )
+                .clicked()
+            {
+                self.open_cart_dialog();
+                ui.close();
+            }
+            if ui
+                .button("Edit UCS Metadata...")
+                .on_hover_text(
+                    "Write UCS CatID/FXName/CreatorID/SourceID into iXML (ASWG/USER) and bext of the selected WAV files, optionally renaming them",
+                )
+                .clicked()
+            {
+                self.open_ucs_batch_dialog();
+                ui.close();
+            }
+            if ui
+                .button("Edit Tags...")
+                .on_hover_text(
+                    "Edit title/artist/album/genre/comment/ISRC and custom keys of the selected MP3/M4A/FLAC/Ogg/Opus files in a table",
+                )
+                .clicked()
+            {
+                self.open_tag_editor();
+                ui.close();
+            }
+            if ui
+                .button("Apply Metadata Template...")
+                .on_hover_text(
+                    "Fill bext/iXML fields and tags of the selected files from saved token templates such as {ucs.fxname} - {external.Scene}",
+                )
+                .clicked()
+            {
+                self.open_metadata_template_dialog();
+                ui.close();
+            }
             ui.separator();
             let multi = self.selected_paths().len() >= 2;
             if ui
@@ -341,6 +391,48 @@ impl WavesPreviewer {
                 self.start_mix_audition();
                 ui.close();
             }
+            ui.separator();
+            if ui
+                .add_enabled(
+                    !self.files.is_empty(),
+                    egui::Button::new("Play From Here to End of List"),
+                )
+                .on_hover_text(
+                    "Play from the selected row to the last row back to back; each next \
+                     row is pre-rendered so the handoff is gapless. Stop playback to end",
+                )
+                .clicked()
+            {
+                self.start_playlist_from_here();
+                ui.close();
+            }
+            if ui
+                .add_enabled(multi, egui::Button::new("Play Selection as Playlist"))
+                .on_hover_text("Play the selected files back to back (gapless) in list order")
+                .clicked()
+            {
+                self.start_playlist_from_selection();
+                ui.close();
+            }
+            ui.menu_button("Playlist Options", |ui| {
+                let mut options = self.playlist_options;
+                ui.checkbox(&mut options.repeat_all, "Repeat All");
+                ui.checkbox(&mut options.shuffle, "Shuffle");
+                ui.horizontal(|ui| {
+                    ui.label("Crossfade");
+                    ui.add(
+                        egui::DragValue::new(&mut options.crossfade_ms)
+                            .range(0..=Self::PLAYLIST_MAX_CROSSFADE_MS)
+                            .speed(10.0)
+                            .suffix(" ms"),
+                    )
+                    .on_hover_text("Equal-power crossfade between rows; 0 = gapless splice");
+                });
+                if options != self.playlist_options {
+                    self.playlist_options = options;
+                    self.save_prefs();
+                }
+            });
         });
     }
 
@@ -380,6 +472,15 @@ impl WavesPreviewer {
                 self.show_transcript_window = true;
                 ui.close();
             }
+            let mut analyzer_open = self.analyzer_panel.open;
+            if ui
+                .checkbox(&mut analyzer_open, "Analyzer Panel")
+                .on_hover_text("Realtime RTA, goniometer and correlation of what's playing")
+                .changed()
+            {
+                self.set_analyzer_panel_open(analyzer_open);
+                ui.close();
+            }
             if ui.button("Screenshot (F9)").clicked() {
                 let path = self.default_screenshot_path();
                 self.request_screenshot(ctx, path, false);
@@ -45,8 +45,10 @@ enum TopbarActivityCancel {
     Transcript,
     Music,
     VariationAudition,
+    Playlist,
     MixAudition,
     DuplicateScan,
+    CatalogExport,
     EditorAnalysis,
     VirtualTrim,
 }
@@ -377,6 +379,14 @@ impl WavesPreviewer {
                 cancel: Some(TopbarActivityCancel::DuplicateScan),
             });
         }
+        if let Some(state) = &self.catalog_export_state {
+            items.push(TopbarActivityItem {
+                label: format!("Catalog: {}/{}", state.done, state.total.max(1)),
+                progress: Some((state.done as f32 / state.total.max(1) as f32).clamp(0.0, 1.0)),
+                show_percentage: true,
+                cancel: Some(TopbarActivityCancel::CatalogExport),
+            });
+        }
         if let Some(state) = &self.variation_audition {
             let mode = match state.mode {
                 crate::app::types::VariationAuditionMode::RoundRobin => "RR",
@@ -393,6 +403,33 @@ impl WavesPreviewer {
                 cancel: Some(TopbarActivityCancel::VariationAudition),
             });
         }
+        if let Some(state) = &self.playlist_playback {
+            let len = state.paths.len().max(1);
+            let options = self.playlist_options;
+            let mut flags = Vec::new();
+            if options.shuffle {
+                flags.push("shuffle");
+            }
+            if options.repeat_all {
+                flags.push("repeat");
+            }
+            let suffix = if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            };
+            let label = if state.played == 0 {
+                format!("Playlist: preparing {len} rows...")
+            } else {
+                format!("Playlist {}/{len}{suffix}", (state.played - 1) % len + 1)
+            };
+            items.push(TopbarActivityItem {
+                label,
+                progress: None,
+                show_percentage: false,
+                cancel: Some(TopbarActivityCancel::Playlist),
+            });
+        }
         if let Some(state) = &self.transcript_ai_state {
             let total = state.total.max(1);
             let done = state.done.min(total);
@@ -532,7 +569,12 @@ impl WavesPreviewer {
                 self.cancel_variation_audition();
                 self.audio.stop();
             }
+            TopbarActivityCancel::Playlist => {
+                self.cancel_playlist();
+                self.audio.stop();
+            }
             TopbarActivityCancel::DuplicateScan => self.cancel_duplicate_scan(),
+            TopbarActivityCancel::CatalogExport => self.cancel_catalog_export(),
             TopbarActivityCancel::EditorAnalysis => self.cancel_all_editor_analyses(),
         }
     }
@@ -650,7 +692,11 @@ impl WavesPreviewer {
         )
         .on_hover_text(
             "Realtime loudness of what's playing: M = momentary LUFS (400 ms), \
-                 S = short-term LUFS (3 s), TP = true peak (dBTP, 4x oversampled)",
+                 S = short-term LUFS (3 s), TP = true peak (dBTP, 4x oversampled).\n\
+                 Measured on the device output: channels are weighted by the \
+                 file's speaker layout (or the Surround layout override) when they \
+                 reach the device one-to-one; folded-down output (e.g. 5.1 on a \
+                 stereo device) is weighted by the device layout.",
         );
         if m.is_some() || s.is_some() || tp.is_some() {
             ui.ctx()
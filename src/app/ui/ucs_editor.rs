@@ -0,0 +1,313 @@
+use std::path::Path;
+
+use egui::{Color32, RichText};
+
+use crate::metadata::ucs::{UcsField, UcsFields, UcsIssue, UcsIssueLevel};
+use crate::metadata::MetadataDocument;
+
+const CAT_ID_SUGGESTIONS: usize = 8;
+
+fn issue_color(level: UcsIssueLevel) -> Color32 {
+    match level {
+        UcsIssueLevel::Error => Color32::LIGHT_RED,
+        UcsIssueLevel::Warning => Color32::YELLOW,
+    }
+}
+
+fn ucs_issue_list(ui: &mut egui::Ui, issues: &[UcsIssue]) {
+    for issue in issues {
+        ui.colored_label(
+            issue_color(issue.level),
+            format!("{}: {}", issue.field.label(), issue.message),
+        );
+    }
+}
+
+/// Field grid shared by the inspector view and the batch dialog. CatID
+/// input offers table suggestions while it does not resolve; picking one
+/// fills Category/SubCategory.
+fn ucs_fields_form(ui: &mut egui::Ui, id_salt: &str, fields: &mut UcsFields, hint: &str) {
+    let issues = fields.resolved().validate();
+    egui::Grid::new(("ucs_fields_grid", id_salt))
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for field in UcsField::ALL {
+                let flagged = issues
+                    .iter()
+                    .filter(|issue| issue.field == field)
+                    .map(|issue| issue.level)
+                    .max_by_key(|level| *level == UcsIssueLevel::Error);
+                let label = RichText::new(field.label());
+                ui.label(match flagged {
+                    Some(level) => label.color(issue_color(level)),
+                    None => label,
+                });
+                ui.add(
+                    egui::TextEdit::singleline(fields.get_mut(field))
+                        .hint_text(hint)
+                        .desired_width(280.0),
+                );
+                ui.end_row();
+            }
+        });
+    let cat_id = fields.cat_id.trim().to_string();
+    match crate::metadata::ucs::lookup_cat_id(&cat_id) {
+        Some(category) => {
+            ui.label(
+                RichText::new(format!(
+                    "{} / {} — {}",
+                    category.category, category.subcategory, category.explanation
+                ))
+                .weak(),
+            );
+        }
+        None if !cat_id.is_empty() => {
+            let suggestions = crate::metadata::ucs::search_categories(&cat_id, CAT_ID_SUGGESTIONS);
+            if !suggestions.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new("CatID suggestions:").weak());
+                    for category in suggestions {
+                        if ui
+                            .small_button(category.cat_id)
+                            .on_hover_text(format!(
+                                "{} / {}\n{}",
+                                category.category, category.subcategory, category.explanation
+                            ))
+                            .clicked()
+                        {
+                            fields.cat_id = category.cat_id.to_string();
+                            fields.category = category.category.to_string();
+                            fields.subcategory = category.subcategory.to_string();
+                        }
+                    }
+                });
+            }
+        }
+        None => {}
+    }
+}
+
+fn ucs_targets_row(ui: &mut egui::Ui, state: &mut crate::app::types::UcsEditorState) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Write to:");
+        ui.checkbox(&mut state.targets.aswg, "iXML ASWG");
+        ui.checkbox(&mut state.targets.user, "iXML USER");
+        ui.checkbox(&mut state.targets.bext_description, "bext description");
+        ui.checkbox(&mut state.rename_files, "Rename file to UCS filename");
+    });
+}
+
+fn any_target(state: &crate::app::types::UcsEditorState) -> bool {
+    state.targets.aswg || state.targets.user || state.targets.bext_description || state.rename_files
+}
+
+impl crate::app::WavesPreviewer {
+    /// Metadata Inspector UCS view: edit the tab's UCS fields (full replace;
+    /// blank fields are removed) and write them back to the source WAV.
+    pub(in crate::app) fn ui_metadata_ucs(
+        &mut self,
+        ui: &mut egui::Ui,
+        tab_idx: usize,
+        source_path: &Path,
+        document: &MetadataDocument,
+        is_virtual_origin: bool,
+    ) {
+        let stored = UcsFields::from_document(document);
+        let mut draft = self.tabs[tab_idx]
+            .metadata_ucs_draft
+            .clone()
+            .unwrap_or_else(|| stored.clone());
+        let is_wav = source_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("wav"))
+            .unwrap_or(false);
+        let writable = is_wav && !is_virtual_origin;
+        let mut write_clicked = false;
+        let mut revert_clicked = false;
+        egui::ScrollArea::vertical()
+            .id_salt(("metadata_ucs_scroll", self.tabs[tab_idx].tab_id))
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                if !is_wav {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        "UCS write-back is available for WAV files only.",
+                    );
+                } else if is_virtual_origin {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        "Virtual items are read-only here; write UCS fields on the source file.",
+                    );
+                }
+                ucs_fields_form(ui, "inspector", &mut draft, "");
+                ui.separator();
+                ucs_targets_row(ui, &mut self.ucs_editor);
+                ui.separator();
+                let resolved = draft.resolved();
+                let issues = resolved.validate();
+                ui.label(RichText::new("Preview").strong());
+                egui::Grid::new(("ucs_preview_grid", self.tabs[tab_idx].tab_id))
+                    .num_columns(2)
+                    .spacing([12.0, 2.0])
+                    .show(ui, |ui| {
+                        for field in UcsField::ALL {
+                            let before = stored.get(field);
+                            let after = resolved.get(field);
+                            ui.label(field.label());
+                            if before == after {
+                                ui.label(RichText::new(after).monospace().weak());
+                            } else {
+                                ui.label(
+                                    RichText::new(format!("{before} → {after}"))
+                                        .monospace()
+                                        .color(Color32::LIGHT_GREEN),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                        if self.ucs_editor.rename_files {
+                            ui.label("Filename");
+                            match crate::app::ucs_ops::ucs_file_name(source_path, &resolved) {
+                                Ok(name) => ui.label(RichText::new(name).monospace()),
+                                Err(error) => ui.colored_label(Color32::LIGHT_RED, error),
+                            };
+                            ui.end_row();
+                        }
+                    });
+                ucs_issue_list(ui, &issues);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let can_write = writable
+                        && any_target(&self.ucs_editor)
+                        && !issues.iter().any(|i| i.level == UcsIssueLevel::Error);
+                    if ui
+                        .add_enabled(can_write, egui::Button::new("Write"))
+                        .on_hover_text(
+                            "Write the fields into the checked targets (undo: list undo)",
+                        )
+                        .clicked()
+                    {
+                        write_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(draft != stored, egui::Button::new("Revert"))
+                        .clicked()
+                    {
+                        revert_clicked = true;
+                    }
+                });
+            });
+        if revert_clicked {
+            self.tabs[tab_idx].metadata_ucs_draft = None;
+            return;
+        }
+        if write_clicked {
+            self.tabs[tab_idx].metadata_ucs_draft = None;
+            self.write_ucs_fields(vec![(source_path.to_path_buf(), draft)], 0);
+            return;
+        }
+        self.tabs[tab_idx].metadata_ucs_draft = (draft != stored).then_some(draft);
+    }
+
+    /// Batch UCS dialog over the list selection. Blank fields keep each
+    /// file's current value; rows with errors are skipped on write.
+    pub(crate) fn ui_ucs_batch_dialog(&mut self, ctx: &egui::Context) {
+        if !self.ucs_editor.show_batch_dialog {
+            return;
+        }
+        let mut open = true;
+        let mut apply_clicked = false;
+        let mut preview_clicked = false;
+        let total = self.ucs_editor.batch_paths.len();
+        let scroll_target = self.begin_floating_scroll_surface("ucs_batch_window");
+        let scroll_guard = self.pointer_scroll_input_guard(scroll_target, ctx);
+        let shown = egui::Window::new("Edit UCS Metadata")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(620.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Applies to {total} selected file(s); non-WAV rows are skipped."
+                ));
+                ui.separator();
+                ucs_fields_form(ui, "batch", &mut self.ucs_editor.batch_fields, "(keep)");
+                ui.separator();
+                ucs_targets_row(ui, &mut self.ucs_editor);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Preview").clicked() {
+                        preview_clicked = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            total > 0 && any_target(&self.ucs_editor),
+                            egui::Button::new("Write"),
+                        )
+                        .clicked()
+                    {
+                        apply_clicked = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.ucs_editor.show_batch_dialog = false;
+                    }
+                });
+                if self.ucs_editor.batch_preview.is_empty() {
+                    return;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(260.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("ucs_batch_preview_grid")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label(RichText::new("File").strong());
+                                ui.label(RichText::new("CatID").strong());
+                                ui.label(RichText::new("FXName").strong());
+                                ui.label(RichText::new("Result").strong());
+                                ui.end_row();
+                                for row in &self.ucs_editor.batch_preview {
+                                    let name = row
+                                        .path
+                                        .file_name()
+                                        .and_then(|n| n.to_str())
+                                        .unwrap_or_default();
+                                    ui.label(RichText::new(name).monospace());
+                                    ui.label(row.fields.cat_id.as_str());
+                                    ui.label(row.fields.fx_name.as_str());
+                                    match (&row.error, &row.new_name) {
+                                        (Some(error), _) => {
+                                            ui.colored_label(Color32::LIGHT_RED, error);
+                                        }
+                                        (None, Ok(new_name)) => {
+                                            ui.label(RichText::new(new_name).monospace());
+                                        }
+                                        (None, Err(_)) => {
+                                            ui.label(RichText::new("ok").weak());
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        drop(scroll_guard);
+        if let Some(shown) = shown.as_ref() {
+            self.register_scroll_surface(scroll_target, &shown.response);
+        }
+        if preview_clicked {
+            self.build_ucs_batch_preview();
+        }
+        if apply_clicked {
+            self.apply_ucs_batch();
+        } else if !open {
+            self.ucs_editor.show_batch_dialog = false;
+        }
+    }
+}
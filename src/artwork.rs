@@ -0,0 +1,310 @@
+//! Embedded cover art writes: set a front cover (optionally resized and
+//! re-encoded) or strip all artwork from MP3 / WAV (ID3 `APIC`), M4A
+//! (`covr`) and FLAC (`PICTURE`).
+
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use id3::frame::{Picture, PictureType};
+use id3::TagLike;
+use image::{DynamicImage, ImageFormat};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArtworkEncoding {
+    /// Embed JPEG/PNG sources as-is; other formats become PNG.
+    #[default]
+    Keep,
+    Jpeg,
+    Png,
+}
+
+impl ArtworkEncoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            ArtworkEncoding::Keep => "Keep",
+            ArtworkEncoding::Jpeg => "JPEG",
+            ArtworkEncoding::Png => "PNG",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "keep" => Some(ArtworkEncoding::Keep),
+            "jpeg" | "jpg" => Some(ArtworkEncoding::Jpeg),
+            "png" => Some(ArtworkEncoding::Png),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArtworkOptions {
+    /// Downscale so neither edge exceeds this many pixels (never upscales).
+    pub max_edge: Option<u32>,
+    pub encoding: ArtworkEncoding,
+    pub jpeg_quality: u8,
+}
+
+impl Default for ArtworkOptions {
+    fn default() -> Self {
+        Self {
+            max_edge: None,
+            encoding: ArtworkEncoding::Keep,
+            jpeg_quality: 90,
+        }
+    }
+}
+
+/// Image bytes ready to embed, with the fields FLAC `PICTURE` records.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedArtwork {
+    pub mime: &'static str,
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// Bits per pixel.
+    pub depth: u32,
+}
+
+impl PreparedArtwork {
+    fn is_jpeg(&self) -> bool {
+        self.mime == "image/jpeg"
+    }
+}
+
+pub fn artwork_write_supported(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref(),
+        Some("mp3" | "wav" | "m4a" | "flac")
+    )
+}
+
+/// Decode `bytes` (any format the `image` crate reads), apply the resize and
+/// encoding options and return what gets embedded.
+pub fn prepare_artwork(bytes: &[u8], options: &ArtworkOptions) -> Result<PreparedArtwork> {
+    let format = image::guess_format(bytes).context("unrecognized image format")?;
+    let decoded = image::load_from_memory_with_format(bytes, format).context("decode image")?;
+    let resized = options
+        .max_edge
+        .filter(|edge| *edge > 0 && (decoded.width() > *edge || decoded.height() > *edge))
+        .map(|edge| decoded.resize(edge, edge, image::imageops::FilterType::Lanczos3));
+    let passthrough = options.encoding == ArtworkEncoding::Keep
+        && resized.is_none()
+        && matches!(format, ImageFormat::Jpeg | ImageFormat::Png);
+    if passthrough {
+        return Ok(PreparedArtwork {
+            mime: if format == ImageFormat::Jpeg {
+                "image/jpeg"
+            } else {
+                "image/png"
+            },
+            data: bytes.to_vec(),
+            width: decoded.width(),
+            height: decoded.height(),
+            depth: decoded.color().bits_per_pixel() as u32,
+        });
+    }
+    let image = resized.unwrap_or(decoded);
+    let jpeg = match options.encoding {
+        ArtworkEncoding::Jpeg => true,
+        ArtworkEncoding::Png => false,
+        ArtworkEncoding::Keep => format == ImageFormat::Jpeg,
+    };
+    let mut data = Vec::new();
+    if jpeg {
+        // JPEG has no alpha channel.
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+            &mut data,
+            options.jpeg_quality.clamp(1, 100),
+        );
+        rgb.write_with_encoder(encoder)
+            .context("encode artwork jpeg")?;
+        Ok(PreparedArtwork {
+            mime: "image/jpeg",
+            data,
+            width: rgb.width(),
+            height: rgb.height(),
+            depth: 24,
+        })
+    } else {
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .context("encode artwork png")?;
+        Ok(PreparedArtwork {
+            mime: "image/png",
+            data,
+            width: image.width(),
+            height: image.height(),
+            depth: image.color().bits_per_pixel() as u32,
+        })
+    }
+}
+
+/// Replace all embedded pictures with `artwork` as the front cover, or strip
+/// every picture for `None`. Other tags are kept.
+pub fn write_artwork(path: &Path, artwork: Option<&PreparedArtwork>) -> Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    crate::app::watch::note_self_write(path);
+    match ext.as_str() {
+        "mp3" => write_mp3_artwork(path, artwork),
+        "wav" => write_wav_artwork(path, artwork),
+        "m4a" => write_m4a_artwork(path, artwork),
+        "flac" => crate::flac_meta::write_flac_artwork(path, artwork),
+        _ => anyhow::bail!(
+            "artwork editing supports MP3, WAV, M4A and FLAC: {}",
+            path.display()
+        ),
+    }
+}
+
+fn set_id3_artwork(tag: &mut id3::Tag, artwork: Option<&PreparedArtwork>) {
+    tag.remove_all_pictures();
+    if let Some(artwork) = artwork {
+        tag.add_frame(Picture {
+            mime_type: artwork.mime.to_string(),
+            picture_type: PictureType::CoverFront,
+            description: String::new(),
+            data: artwork.data.clone(),
+        });
+    }
+}
+
+fn write_mp3_artwork(path: &Path, artwork: Option<&PreparedArtwork>) -> Result<()> {
+    let mut tag = match id3::Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(err) if matches!(err.kind, id3::ErrorKind::NoTag) => {
+            if artwork.is_none() {
+                return Ok(());
+            }
+            id3::Tag::new()
+        }
+        Err(err) => return Err(err).with_context(|| format!("read id3: {}", path.display())),
+    };
+    set_id3_artwork(&mut tag, artwork);
+    tag.write_to_path(path, id3::Version::Id3v24)
+        .with_context(|| format!("write mp3 artwork: {}", path.display()))?;
+    Ok(())
+}
+
+fn write_wav_artwork(path: &Path, artwork: Option<&PreparedArtwork>) -> Result<()> {
+    let existing = crate::wave::read_wav_id3_tag(path)?;
+    if existing.is_none() && artwork.is_none() {
+        return Ok(());
+    }
+    let mut tag = existing.unwrap_or_else(id3::Tag::new);
+    set_id3_artwork(&mut tag, artwork);
+    // A tag left empty by stripping goes away with its chunk.
+    let keep = tag.frames().next().is_some();
+    crate::wave::write_wav_id3_tag(path, keep.then_some(&tag))
+}
+
+fn write_m4a_artwork(path: &Path, artwork: Option<&PreparedArtwork>) -> Result<()> {
+    let mut tag = mp4ameta::Tag::read_from_path(path)
+        .with_context(|| format!("read m4a tags: {}", path.display()))?;
+    tag.remove_artworks();
+    if let Some(artwork) = artwork {
+        tag.set_artwork(if artwork.is_jpeg() {
+            mp4ameta::Img::jpeg(artwork.data.clone())
+        } else {
+            mp4ameta::Img::png(artwork.data.clone())
+        });
+    }
+    tag.write_to_path(path)
+        .with_context(|| format!("write m4a artwork: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_artwork_{tag}_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(
+            width,
+            height,
+            Rgba([200, 80, 40, 255]),
+        ));
+        let mut out = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut out), ImageFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn prepare_keeps_png_and_resizes_to_jpeg() {
+        let png = png_bytes(64, 32);
+        let kept = prepare_artwork(&png, &ArtworkOptions::default()).unwrap();
+        assert_eq!(kept.mime, "image/png");
+        assert_eq!(kept.data, png);
+        let resized = prepare_artwork(
+            &png,
+            &ArtworkOptions {
+                max_edge: Some(16),
+                encoding: ArtworkEncoding::Jpeg,
+                jpeg_quality: 80,
+            },
+        )
+        .unwrap();
+        assert_eq!(resized.mime, "image/jpeg");
+        assert_eq!((resized.width, resized.height), (16, 8));
+        assert_eq!(
+            image::guess_format(&resized.data).unwrap(),
+            ImageFormat::Jpeg
+        );
+        assert!(prepare_artwork(b"not an image", &ArtworkOptions::default()).is_err());
+    }
+
+    #[test]
+    fn set_and_strip_round_trip_per_format() {
+        let dir = temp_dir("roundtrip");
+        let chans = vec![vec![0.1f32; 4_410]];
+        let artwork = prepare_artwork(&png_bytes(8, 8), &ArtworkOptions::default()).unwrap();
+        for ext in ["mp3", "wav", "m4a", "flac"] {
+            let path = dir.join(format!("tone.{ext}"));
+            crate::wave::export_channels_audio(&chans, 44_100, &path).unwrap();
+            crate::loop_markers::write_loop_markers(&path, Some((10, 400))).unwrap();
+            write_artwork(&path, Some(&artwork)).unwrap();
+            assert_eq!(
+                crate::audio_io::read_embedded_artwork(&path).as_deref(),
+                Some(artwork.data.as_slice()),
+                "{ext}"
+            );
+            write_artwork(&path, None).unwrap();
+            assert!(
+                crate::audio_io::read_embedded_artwork(&path).is_none(),
+                "{ext}"
+            );
+            assert_eq!(
+                crate::loop_markers::read_loop_markers(&path),
+                Some((10, 400)),
+                "{ext}: loop markers lost"
+            );
+            crate::audio_io::decode_audio_multi(&path).unwrap();
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -54,17 +54,20 @@ pub const METER_TAP_CAPACITY: usize = 1 << 15;
 /// Sentinel for "no valid reading" in the milli-LUFS / milli-dB atomics.
 pub const METER_VALUE_INVALID: i32 = i32::MIN;
 
-/// Lock-free SPSC tap: the audio callback publishes post-gain L/R frames,
-/// a low-priority meter thread drains them. Overwrite semantics — a slow
-/// reader just loses the oldest frames.
+/// Lock-free SPSC tap: the audio callback publishes post-gain output frames
+/// (up to [`METER_CH_SLOTS`] channels, at least L/R), a low-priority meter
+/// thread drains them. Overwrite semantics — a slow reader just loses the
+/// oldest frames.
 pub struct MeterTap {
-    buf_l: Box<[std::sync::atomic::AtomicU32]>,
-    buf_r: Box<[std::sync::atomic::AtomicU32]>,
+    bufs: Box<[Box<[std::sync::atomic::AtomicU32]>]>,
     write_idx: std::sync::atomic::AtomicUsize,
 }
 
 impl MeterTap {
-    fn new() -> Self {
+    /// Tap for an output with `out_channels` channels. Mono outputs still get
+    /// an L/R pair (R repeats L); outputs past [`METER_CH_SLOTS`] keep the
+    /// first slots only.
+    fn new(out_channels: usize) -> Self {
         let mk = || {
             (0..METER_TAP_CAPACITY)
                 .map(|_| std::sync::atomic::AtomicU32::new(0))
@@ -72,26 +75,37 @@ impl MeterTap {
                 .into_boxed_slice()
         };
         Self {
-            buf_l: mk(),
-            buf_r: mk(),
+            bufs: (0..out_channels.clamp(2, METER_CH_SLOTS))
+                .map(|_| mk())
+                .collect(),
             write_idx: std::sync::atomic::AtomicUsize::new(0),
         }
     }
 
+    pub fn channels(&self) -> usize {
+        self.bufs.len()
+    }
+
+    /// Publish one output frame. Tap channels beyond the frame repeat its
+    /// first sample (mono outputs).
     #[inline]
-    pub fn push_frame(&self, l: f32, r: f32) {
+    pub fn push_frame(&self, frame: &[f32]) {
         use std::sync::atomic::Ordering;
         let idx = self.write_idx.load(Ordering::Relaxed);
         let slot = idx & (METER_TAP_CAPACITY - 1);
-        self.buf_l[slot].store(l.to_bits(), Ordering::Relaxed);
-        self.buf_r[slot].store(r.to_bits(), Ordering::Relaxed);
+        let first = frame.first().copied().unwrap_or(0.0);
+        for (ch, buf) in self.bufs.iter().enumerate() {
+            let v = frame.get(ch).copied().unwrap_or(first);
+            buf[slot].store(v.to_bits(), Ordering::Relaxed);
+        }
         self.write_idx.store(idx.wrapping_add(1), Ordering::Release);
     }
 
-    /// Append frames written since `cursor` to the buffers; returns the new
-    /// cursor. When the reader fell more than the capacity behind, only the
-    /// newest `METER_TAP_CAPACITY` frames are returned.
-    pub fn read_since(&self, cursor: usize, out_l: &mut Vec<f32>, out_r: &mut Vec<f32>) -> usize {
+    /// Append frames written since `cursor` to `out` (one buffer per tap
+    /// channel; extra buffers are left alone); returns the new cursor. When
+    /// the reader fell more than the capacity behind, only the newest
+    /// `METER_TAP_CAPACITY` frames are returned.
+    pub fn read_since(&self, cursor: usize, out: &mut [Vec<f32>]) -> usize {
         use std::sync::atomic::Ordering;
         let end = self.write_idx.load(Ordering::Acquire);
         let available = end.wrapping_sub(cursor);
@@ -100,10 +114,11 @@ impl MeterTap {
         }
         let take = available.min(METER_TAP_CAPACITY);
         let start = end.wrapping_sub(take);
-        for i in 0..take {
-            let slot = start.wrapping_add(i) & (METER_TAP_CAPACITY - 1);
-            out_l.push(f32::from_bits(self.buf_l[slot].load(Ordering::Relaxed)));
-            out_r.push(f32::from_bits(self.buf_r[slot].load(Ordering::Relaxed)));
+        for (buf, out) in self.bufs.iter().zip(out.iter_mut()) {
+            for i in 0..take {
+                let slot = start.wrapping_add(i) & (METER_TAP_CAPACITY - 1);
+                out.push(f32::from_bits(buf[slot].load(Ordering::Relaxed)));
+            }
         }
         end
     }
@@ -111,7 +126,7 @@ impl MeterTap {
 
 pub struct SharedAudio {
     pub samples: ArcSwapOption<AudioBuffer>, // multi-channel samples in [-1, 1]
-    streamed_wav: ArcSwapOption<MappedWavSource>,
+    streamed: ArcSwapOption<StreamedSource>,
     pub vol: AtomicF32, // 0.0..1.0 linear gain
     pub playing: std::sync::atomic::AtomicBool,
     pub play_pos: std::sync::atomic::AtomicUsize,
@@ -140,12 +155,23 @@ pub struct SharedAudio {
     pub ramp_target: AtomicF32,
     pub ramp_step: AtomicF32,
     pub ramp_events: std::sync::atomic::AtomicUsize,
+    // Gapless playlist: a buffer queued to follow the current one. When the
+    // buffer source runs out (loop off) the callback swaps it in and keeps
+    // rendering in the same block; `queued_handoffs` counts the swaps.
+    pub queued: ArcSwapOption<AudioBuffer>,
+    pub queued_handoffs: std::sync::atomic::AtomicUsize,
     // Realtime loudness metering: callback-fed tap ring + thread-published
     // readings (LUFS x100 / dBTP x100; METER_VALUE_INVALID = no reading).
     pub meter_tap: MeterTap,
     pub lufs_m_milli: std::sync::atomic::AtomicI32,
     pub lufs_s_milli: std::sync::atomic::AtomicI32,
     pub true_peak_db_milli: std::sync::atomic::AtomicI32,
+    // Speaker layout of the audible source (`ChannelLayout::pack`, 0 = not
+    // known) for the realtime meter's BS.1770 channel weights.
+    pub meter_layout_bits: std::sync::atomic::AtomicU64,
+    // Analyzer panel (RTA / goniometer / correlation), computed on the same
+    // meter thread while the panel is open.
+    pub analyzer: crate::analyzer::AnalyzerShared,
 }
 
 pub struct AudioEngine {
@@ -267,6 +293,79 @@ impl MappedWavSource {
     }
 }
 
+/// A file played in place instead of from a decoded buffer: memory-mapped
+/// PCM WAV, or FLAC decoded chunk-by-chunk off the audio thread.
+#[derive(Debug)]
+enum StreamedSource {
+    Wav(MappedWavSource),
+    Flac(crate::flac_stream::FlacStreamSource),
+}
+
+impl StreamedSource {
+    fn len(&self) -> usize {
+        match self {
+            Self::Wav(src) => src.len(),
+            Self::Flac(src) => src.len(),
+        }
+    }
+
+    fn channel_count(&self) -> usize {
+        match self {
+            Self::Wav(src) => src.channel_count(),
+            Self::Flac(src) => src.channel_count(),
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        match self {
+            Self::Wav(src) => src.sample_rate(),
+            Self::Flac(src) => src.sample_rate(),
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            Self::Wav(src) => src.path(),
+            Self::Flac(src) => src.path(),
+        }
+    }
+
+    /// Reader for one callback block.
+    fn reader(&self) -> StreamedReader<'_> {
+        match self {
+            Self::Wav(src) => StreamedReader::Wav(src),
+            Self::Flac(src) => StreamedReader::Flac(src.block_reader()),
+        }
+    }
+}
+
+/// Per-block access to a [`StreamedSource`]; the FLAC reader caches its
+/// decoded chunks for the block.
+enum StreamedReader<'a> {
+    Wav(&'a MappedWavSource),
+    Flac(crate::flac_stream::FlacBlockReader<'a>),
+}
+
+impl StreamedReader<'_> {
+    /// Whether `frame_idx` can be played now. A FLAC chunk may still be
+    /// decoding after a seek; the callback holds the playhead until it is.
+    #[inline]
+    fn is_frame_ready(&self, frame_idx: usize) -> bool {
+        match self {
+            Self::Wav(_) => true,
+            Self::Flac(reader) => reader.is_frame_ready(frame_idx),
+        }
+    }
+
+    #[inline]
+    fn sample_at_interp(&self, ch_idx: usize, pos_f: f64) -> f32 {
+        match self {
+            Self::Wav(src) => src.sample_at_interp(ch_idx, pos_f),
+            Self::Flac(reader) => reader.sample_at_interp(ch_idx, pos_f),
+        }
+    }
+}
+
 #[allow(dead_code)]
 fn read_mapped_wav_header(file: &mut File, path: &Path) -> Result<Option<MappedWavHeader>> {
     let _ = file;
@@ -434,7 +533,7 @@ impl AudioEngine {
     fn new_shared(out_channels: usize, out_sample_rate: u32) -> Arc<SharedAudio> {
         let shared = Arc::new(SharedAudio {
             samples: ArcSwapOption::from(None),
-            streamed_wav: ArcSwapOption::from(None),
+            streamed: ArcSwapOption::from(None),
             vol: AtomicF32::new(1.0),
             playing: std::sync::atomic::AtomicBool::new(false),
             play_pos: std::sync::atomic::AtomicUsize::new(0),
@@ -457,10 +556,14 @@ impl AudioEngine {
             ramp_target: AtomicF32::new(1.0),
             ramp_step: AtomicF32::new(1.0),
             ramp_events: std::sync::atomic::AtomicUsize::new(0),
-            meter_tap: MeterTap::new(),
+            queued: ArcSwapOption::from(None),
+            queued_handoffs: std::sync::atomic::AtomicUsize::new(0),
+            meter_tap: MeterTap::new(out_channels),
             lufs_m_milli: std::sync::atomic::AtomicI32::new(METER_VALUE_INVALID),
             lufs_s_milli: std::sync::atomic::AtomicI32::new(METER_VALUE_INVALID),
             true_peak_db_milli: std::sync::atomic::AtomicI32::new(METER_VALUE_INVALID),
+            meter_layout_bits: std::sync::atomic::AtomicU64::new(0),
+            analyzer: crate::analyzer::AnalyzerShared::default(),
         });
         Self::spawn_meter_thread(&shared);
         shared
@@ -473,31 +576,42 @@ impl AudioEngine {
     /// Low-priority metering thread: drains the callback tap ring, runs the
     /// BS.1770 momentary/short-term meters and the 4x true-peak scan, and
     /// publishes the readings as atomics. Exits when the SharedAudio drops.
+    ///
+    /// Stereo and mono outputs meter as L/R; wider outputs are weighted as the
+    /// default speaker layout for their channel count.
+    ///
+    /// While the Analyzer panel is open the same chunks also drive the RTA /
+    /// goniometer (published through `SharedAudio::analyzer`), polled faster
+    /// so the display keeps up.
     fn spawn_meter_thread(shared: &Arc<SharedAudio>) {
         use std::sync::atomic::Ordering;
         let weak = Arc::downgrade(shared);
+        let tap_channels = shared.meter_tap.channels();
         let _ = std::thread::Builder::new()
             .name("neowaves-meter".into())
             .spawn(move || {
                 let mut cursor = 0usize;
                 let mut loudness: Option<crate::meter::LoudnessMeter> = None;
-                let mut tp_l = crate::meter::TruePeakChannel::new();
-                let mut tp_r = crate::meter::TruePeakChannel::new();
+                let mut loudness_layout_bits = 0u64;
+                let mut analyzer: Option<crate::analyzer::Analyzer> = None;
+                let mut tp: Vec<crate::meter::TruePeakChannel> = (0..tap_channels)
+                    .map(|_| crate::meter::TruePeakChannel::new())
+                    .collect();
                 let mut tp_recent: std::collections::VecDeque<(std::time::Instant, f32)> =
                     std::collections::VecDeque::new();
                 let mut last_data = std::time::Instant::now();
-                let mut buf_l: Vec<f32> = Vec::new();
-                let mut buf_r: Vec<f32> = Vec::new();
+                let mut bufs: Vec<Vec<f32>> = vec![Vec::new(); tap_channels];
                 let mut poll_ms = 50u64;
                 loop {
                     std::thread::sleep(std::time::Duration::from_millis(poll_ms));
                     let Some(shared) = weak.upgrade() else {
                         break;
                     };
-                    buf_l.clear();
-                    buf_r.clear();
-                    cursor = shared.meter_tap.read_since(cursor, &mut buf_l, &mut buf_r);
-                    if buf_l.is_empty() {
+                    for buf in &mut bufs {
+                        buf.clear();
+                    }
+                    cursor = shared.meter_tap.read_since(cursor, &mut bufs);
+                    if bufs[0].is_empty() {
                         // Silence/stopped: hold the last reading briefly, then
                         // invalidate and reset so the next playback starts clean.
                         if last_data.elapsed() > std::time::Duration::from_millis(500) {
@@ -513,9 +627,14 @@ impl AudioEngine {
                             if let Some(m) = loudness.as_mut() {
                                 m.reset();
                             }
-                            tp_l.reset();
-                            tp_r.reset();
+                            for ch in &mut tp {
+                                ch.reset();
+                            }
                             tp_recent.clear();
+                            if let Some(a) = analyzer.as_mut() {
+                                a.reset();
+                                shared.analyzer.clear();
+                            }
                             // Idle backoff: nothing to meter until playback
                             // resumes, so poll lazily (still well under the
                             // 400 ms momentary window once data returns).
@@ -523,13 +642,37 @@ impl AudioEngine {
                         }
                         continue;
                     }
-                    poll_ms = 50;
+                    poll_ms = if shared.analyzer.is_enabled() { 25 } else { 50 };
                     last_data = std::time::Instant::now();
                     let sr = shared.out_sample_rate.max(1);
-                    let meter =
-                        loudness.get_or_insert_with(|| crate::meter::LoudnessMeter::new(sr));
-                    meter.push(&buf_l, &buf_r);
-                    let chunk_max = tp_l.scan(&buf_l).max(tp_r.scan(&buf_r));
+                    let layout_bits = shared.meter_layout_bits.load(Ordering::Relaxed);
+                    if layout_bits != loudness_layout_bits {
+                        // New source layout: re-weight from a clean window.
+                        loudness_layout_bits = layout_bits;
+                        loudness = None;
+                    }
+                    let meter = loudness.get_or_insert_with(|| {
+                        Self::realtime_loudness_meter(sr, tap_channels, layout_bits)
+                    });
+                    let chans: Vec<&[f32]> = bufs.iter().map(Vec::as_slice).collect();
+                    meter.push_channels(&chans);
+                    if shared.analyzer.is_enabled() {
+                        let a = analyzer.get_or_insert_with(|| crate::analyzer::Analyzer::new(sr));
+                        if shared.analyzer.take_peak_reset() {
+                            a.reset_peaks();
+                        }
+                        let (averaging, peak_hold) = shared.analyzer.options();
+                        shared
+                            .analyzer
+                            .publish(a.process(&chans, averaging, peak_hold));
+                    } else if analyzer.take().is_some() {
+                        shared.analyzer.clear();
+                    }
+                    let chunk_max = tp
+                        .iter_mut()
+                        .zip(bufs.iter())
+                        .map(|(ch, buf)| ch.scan(buf))
+                        .fold(0.0f32, f32::max);
                     let now = std::time::Instant::now();
                     tp_recent.push_back((now, chunk_max));
                     while tp_recent
@@ -558,6 +701,28 @@ impl AudioEngine {
             });
     }
 
+    /// Realtime meter weighting. The tap carries the device output, so the
+    /// source's speaker layout applies only when its channels reach the
+    /// device one-to-one; a folded-down output (e.g. 5.1 on stereo) is
+    /// metered with the device's default layout.
+    fn realtime_loudness_meter(
+        sr: u32,
+        tap_channels: usize,
+        layout_bits: u64,
+    ) -> crate::meter::LoudnessMeter {
+        use crate::channel_layout::ChannelLayout;
+        match ChannelLayout::unpack(layout_bits) {
+            Some(layout) if usize::from(layout.channels()) == tap_channels => {
+                crate::meter::LoudnessMeter::with_layout(sr, layout)
+            }
+            _ if tap_channels > 2 => crate::meter::LoudnessMeter::with_layout(
+                sr,
+                ChannelLayout::default_for(tap_channels as u16),
+            ),
+            _ => crate::meter::LoudnessMeter::new(sr),
+        }
+    }
+
     fn choose_output_config(
         device: &cpal::Device,
         preferred_sample_rate: Option<u32>,
@@ -719,7 +884,7 @@ impl AudioEngine {
             cfg,
             move |data: &mut [T], _| {
                 let maybe_samples = shared.samples.load();
-                let maybe_stream = shared.streamed_wav.load();
+                let maybe_stream = shared.streamed.load();
                 let playing = shared.playing.load(std::sync::atomic::Ordering::Relaxed);
                 if !playing {
                     for frame in data.chunks_mut(channels) {
@@ -767,8 +932,10 @@ impl AudioEngine {
                 }
 
                 if let Some(samples_arc) = maybe_samples.as_ref() {
-                    let samples = samples_arc.as_ref();
-                    let len = samples.len();
+                    // Owned handle so a playlist handoff can switch buffers
+                    // mid-block (refcount bump only, no allocation).
+                    let mut current = Arc::clone(samples_arc);
+                    let mut len = current.len();
                     if len == 0 {
                         shared
                             .playing
@@ -784,7 +951,7 @@ impl AudioEngine {
                         Self::zero_channel_meters(&shared);
                         return;
                     }
-                    let src_channels = samples.channel_count();
+                    let mut src_channels = current.channel_count();
                     let valid_loop = looping && loop_end > loop_start && loop_end <= len;
                     let xfade = if valid_loop {
                         loop_xfade_samples.min((loop_end - loop_start) / 2)
@@ -810,6 +977,16 @@ impl AudioEngine {
                                     pos_f, loop_start, loop_end, xfade_skip,
                                 );
                                 pos = pos_f.floor() as usize;
+                            } else if let Some(next) = Self::take_queued_handoff(&shared) {
+                                // Gapless handoff: carry the overshoot into
+                                // the queued buffer.
+                                let ended_len = len;
+                                current = next;
+                                len = current.len();
+                                src_channels = current.channel_count();
+                                pos_f = (pos_f - ended_len as f64)
+                                    .clamp(0.0, len.saturating_sub(1) as f64);
+                                pos = pos_f.floor() as usize;
                             } else {
                                 shared
                                     .playing
@@ -824,7 +1001,8 @@ impl AudioEngine {
                             pos_f =
                                 Self::wrap_loop_position(pos_f, loop_start, loop_end, xfade_skip);
                         }
-                        let mut tap_frame = [0.0f32; 2];
+                        let samples = current.as_ref();
+                        let mut tap_frame = [0.0f32; METER_CH_SLOTS];
                         for (out_ch, out_sample) in frame.iter_mut().enumerate() {
                             let sample = if valid_loop && xfade > 0 {
                                 Self::sample_loop_with_xfade(
@@ -856,7 +1034,7 @@ impl AudioEngine {
                             };
                             let out = (sample * vol).clamp(-1.0, 1.0);
                             *out_sample = T::from_sample(out);
-                            if out_ch < 2 {
+                            if out_ch < METER_CH_SLOTS {
                                 tap_frame[out_ch] = out;
                             }
                             meter_sum_sq += f64::from(out * out);
@@ -866,14 +1044,9 @@ impl AudioEngine {
                             ch_peak[slot] = ch_peak[slot].max(out.abs());
                             ch_counts[slot] += 1;
                         }
-                        shared.meter_tap.push_frame(
-                            tap_frame[0],
-                            if channels >= 2 {
-                                tap_frame[1]
-                            } else {
-                                tap_frame[0]
-                            },
-                        );
+                        shared
+                            .meter_tap
+                            .push_frame(&tap_frame[..channels.min(METER_CH_SLOTS)]);
                         pos_f += rate;
                         if valid_loop && pos_f >= loop_end as f64 {
                             pos_f =
@@ -917,6 +1090,7 @@ impl AudioEngine {
                         return;
                     }
                     let src_channels = stream.channel_count();
+                    let reader = stream.reader();
                     let valid_loop = looping && loop_end > loop_start && loop_end <= len;
                     let xfade = if valid_loop {
                         loop_xfade_samples.min((loop_end - loop_start) / 2)
@@ -956,7 +1130,15 @@ impl AudioEngine {
                             pos_f =
                                 Self::wrap_loop_position(pos_f, loop_start, loop_end, xfade_skip);
                         }
-                        let mut tap_frame = [0.0f32; 2];
+                        if !reader.is_frame_ready(pos_f.floor() as usize) {
+                            // Seeked ahead of the decode worker: hold the
+                            // playhead on silence until its chunk is ready.
+                            for ch in frame.iter_mut() {
+                                *ch = T::from_sample(0.0);
+                            }
+                            continue;
+                        }
+                        let mut tap_frame = [0.0f32; METER_CH_SLOTS];
                         for (out_ch, out_sample) in frame.iter_mut().enumerate() {
                             let sample = if valid_loop && xfade > 0 {
                                 Self::sample_loop_with_xfade(
@@ -972,7 +1154,7 @@ impl AudioEngine {
                                             out_ch,
                                             sample_pos,
                                             audible_mask,
-                                            |c, p| stream.sample_at_interp(c, p),
+                                            |c, p| reader.sample_at_interp(c, p),
                                         )
                                     },
                                 )
@@ -983,12 +1165,12 @@ impl AudioEngine {
                                     out_ch,
                                     pos_f,
                                     audible_mask,
-                                    |c, p| stream.sample_at_interp(c, p),
+                                    |c, p| reader.sample_at_interp(c, p),
                                 )
                             };
                             let out = (sample * vol).clamp(-1.0, 1.0);
                             *out_sample = T::from_sample(out);
-                            if out_ch < 2 {
+                            if out_ch < METER_CH_SLOTS {
                                 tap_frame[out_ch] = out;
                             }
                             meter_sum_sq += f64::from(out * out);
@@ -998,14 +1180,9 @@ impl AudioEngine {
                             ch_peak[slot] = ch_peak[slot].max(out.abs());
                             ch_counts[slot] += 1;
                         }
-                        shared.meter_tap.push_frame(
-                            tap_frame[0],
-                            if channels >= 2 {
-                                tap_frame[1]
-                            } else {
-                                tap_frame[0]
-                            },
-                        );
+                        shared
+                            .meter_tap
+                            .push_frame(&tap_frame[..channels.min(METER_CH_SLOTS)]);
                         pos_f += rate;
                         if valid_loop && pos_f >= loop_end as f64 {
                             pos_f =
@@ -1050,7 +1227,8 @@ impl AudioEngine {
 
     pub fn set_samples(&self, samples: Arc<AudioBuffer>) {
         let len = samples.len();
-        self.shared.streamed_wav.store(None);
+        self.shared.streamed.store(None);
+        self.shared.queued.store(None);
         self.shared.samples.store(Some(samples));
         self.shared
             .play_pos
@@ -1114,7 +1292,8 @@ impl AudioEngine {
         let new_len = samples.len();
         let (new_pos, new_pos_f) =
             Self::remap_pos_for_new_source(old_pos_f, from_sr, to_sr, new_len);
-        self.shared.streamed_wav.store(None);
+        self.shared.streamed.store(None);
+        self.shared.queued.store(None);
         self.shared.samples.store(Some(samples));
         self.shared
             .play_pos
@@ -1155,11 +1334,53 @@ impl AudioEngine {
         );
     }
 
+    /// Play `path` in place: FLAC through the chunked stream decoder,
+    /// anything else as a memory-mapped PCM WAV.
+    pub fn set_streaming_path(&self, path: &Path) -> Result<()> {
+        let is_flac = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("flac"));
+        if is_flac {
+            self.set_streaming_flac_path(path)
+        } else {
+            self.set_streaming_wav_path(path)
+        }
+    }
+
     pub fn set_streaming_wav_path(&self, path: &Path) -> Result<()> {
-        let source = Arc::new(MappedWavSource::open(path)?);
+        let source = MappedWavSource::open(path)?;
+        self.set_streamed_source(StreamedSource::Wav(source));
+        Ok(())
+    }
+
+    /// Stream a FLAC file. The decode worker follows the playhead (and the
+    /// loop start while looping) through a weak handle and exits once the
+    /// source is replaced or the engine drops.
+    pub fn set_streaming_flac_path(&self, path: &Path) -> Result<()> {
+        use std::sync::atomic::Ordering;
+        let weak = Arc::downgrade(&self.shared);
+        let probe: crate::flac_stream::FlacStreamProbe = Box::new(move || {
+            let shared = weak.upgrade()?;
+            Some(crate::flac_stream::FlacStreamCursor {
+                play_frame: shared.play_pos.load(Ordering::Relaxed),
+                loop_start: shared
+                    .loop_enabled
+                    .load(Ordering::Relaxed)
+                    .then(|| shared.loop_start.load(Ordering::Relaxed)),
+            })
+        });
+        let source = crate::flac_stream::FlacStreamSource::open(path, probe)?;
+        self.set_streamed_source(StreamedSource::Flac(source));
+        Ok(())
+    }
+
+    fn set_streamed_source(&self, source: StreamedSource) {
+        let source = Arc::new(source);
         let len = source.len();
         self.shared.samples.store(None);
-        self.shared.streamed_wav.store(Some(source));
+        self.shared.queued.store(None);
+        self.shared.streamed.store(Some(source));
         self.shared
             .play_pos
             .store(0, std::sync::atomic::Ordering::Relaxed);
@@ -1172,11 +1393,10 @@ impl AudioEngine {
         self.shared
             .loop_end
             .store(len, std::sync::atomic::Ordering::Relaxed);
-        Ok(())
     }
 
     pub fn clear_streaming_source(&self) {
-        self.shared.streamed_wav.store(None);
+        self.shared.streamed.store(None);
     }
 
     pub fn has_audio_source(&self) -> bool {
@@ -1188,40 +1408,58 @@ impl AudioEngine {
             .unwrap_or(false)
             || self
                 .shared
-                .streamed_wav
+                .streamed
                 .load()
                 .as_ref()
                 .map(|src| src.len() > 0)
                 .unwrap_or(false)
     }
 
-    pub fn current_source_len(&self) -> usize {
+    pub fn current_source_channels(&self) -> usize {
         self.shared
             .samples
             .load()
             .as_ref()
-            .map(|buf| buf.len())
+            .map(|buf| buf.channel_count())
             .or_else(|| {
                 self.shared
-                    .streamed_wav
+                    .streamed
                     .load()
                     .as_ref()
-                    .map(|src| src.len())
+                    .map(|src| src.channel_count())
             })
             .unwrap_or(0)
     }
 
-    pub fn streaming_wav_sample_rate(&self) -> Option<u32> {
+    /// Publish the audible source's speaker layout to the realtime meter.
+    pub fn set_meter_layout(&self, layout: Option<crate::channel_layout::ChannelLayout>) {
+        self.shared.meter_layout_bits.store(
+            crate::channel_layout::ChannelLayout::pack(layout),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+    }
+
+    pub fn current_source_len(&self) -> usize {
         self.shared
-            .streamed_wav
+            .samples
+            .load()
+            .as_ref()
+            .map(|buf| buf.len())
+            .or_else(|| self.shared.streamed.load().as_ref().map(|src| src.len()))
+            .unwrap_or(0)
+    }
+
+    pub fn streaming_sample_rate(&self) -> Option<u32> {
+        self.shared
+            .streamed
             .load()
             .as_ref()
             .map(|src| src.sample_rate())
     }
 
-    pub fn is_streaming_wav_path(&self, path: &Path) -> bool {
+    pub fn is_streaming_path(&self, path: &Path) -> bool {
         self.shared
-            .streamed_wav
+            .streamed
             .load()
             .as_ref()
             .map(|src| src.path() == path)
@@ -1234,7 +1472,8 @@ impl AudioEngine {
             .shared
             .play_pos
             .load(std::sync::atomic::Ordering::Relaxed);
-        self.shared.streamed_wav.store(None);
+        self.shared.streamed.store(None);
+        self.shared.queued.store(None);
         self.shared.samples.store(Some(samples));
         if pos >= new_len {
             self.shared
@@ -1271,6 +1510,40 @@ impl AudioEngine {
         }
     }
 
+    /// Queue `samples` to start sample-accurately when the current buffer
+    /// source ends (gapless playlist). Any new source clears the queue.
+    pub fn queue_next_buffer(&self, samples: Arc<AudioBuffer>) {
+        self.shared.queued.store(Some(samples));
+    }
+
+    pub fn clear_queued_buffer(&self) {
+        self.shared.queued.store(None);
+    }
+
+    pub fn has_queued_buffer(&self) -> bool {
+        self.shared.queued.load().is_some()
+    }
+
+    pub fn queued_handoff_count(&self) -> usize {
+        self.shared
+            .queued_handoffs
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Callback side of the playlist handoff: promote the queued buffer to
+    /// the current source. Empty buffers are dropped (playback then ends).
+    pub(crate) fn take_queued_handoff(shared: &SharedAudio) -> Option<Arc<AudioBuffer>> {
+        let next = shared.queued.swap(None)?;
+        if next.is_empty() {
+            return None;
+        }
+        shared.samples.store(Some(Arc::clone(&next)));
+        shared
+            .queued_handoffs
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Some(next)
+    }
+
     /// Set per-source-channel mute/solo bitmasks consulted by the playback
     /// callback's channel mapping. Bit N = source channel N; solo wins.
     pub fn set_channel_masks(&self, mute: u64, solo: u64) {
@@ -1546,7 +1819,7 @@ impl AudioEngine {
             .clamp(0.25, 4.0) as f64;
         let window_frames = window_frames.max(1).min(len.max(1));
         let maybe_samples = self.shared.samples.load();
-        let maybe_stream = self.shared.streamed_wav.load();
+        let maybe_stream = self.shared.streamed.load();
         let mut sum_sq = 0.0f64;
         let mut count = 0usize;
         if let Some(samples_arc) = maybe_samples.as_ref() {
@@ -1564,10 +1837,11 @@ impl AudioEngine {
             }
         } else if let Some(stream) = maybe_stream.as_ref() {
             let src_channels = stream.channel_count().max(1);
+            let reader = stream.reader();
             for _ in 0..window_frames {
                 let mut mixed = 0.0f32;
                 for src_ch in 0..src_channels {
-                    mixed += stream.sample_at_interp(src_ch, pos_f);
+                    mixed += reader.sample_at_interp(src_ch, pos_f);
                 }
                 let out = ((mixed / src_channels as f32) * vol).clamp(-1.0, 1.0);
                 sum_sq += f64::from(out * out);
@@ -1913,6 +2187,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn realtime_meter_weights_by_source_layout_only_when_channels_match() {
+        use crate::channel_layout::ChannelLayout;
+        let sr = 48_000u32;
+        let tone: Vec<f32> = (0..sr as usize / 2)
+            .map(|i| (i as f32 / sr as f32 * 1000.0 * std::f32::consts::TAU).sin() * 0.5)
+            .collect();
+        let silent = vec![0.0f32; tone.len()];
+        // Signal only on channel 4 (index 3): LFE in the default 5.1 mask,
+        // a back surround in FL FR FC BL BR SL (0x237).
+        let mut chans: Vec<&[f32]> = vec![silent.as_slice(); 6];
+        chans[3] = tone.as_slice();
+        let measure = |tap: usize, bits: u64| {
+            let mut meter = AudioEngine::realtime_loudness_meter(sr, tap, bits);
+            meter.push_channels(&chans[..tap]);
+            meter.momentary_lufs()
+        };
+        let back = ChannelLayout::pack(Some(ChannelLayout::new(6, 0x237)));
+        assert!(measure(6, back).is_some_and(|v| v > -20.0));
+        let lfe = measure(6, 0);
+        assert!(
+            lfe.is_none_or(|v| v < -70.0),
+            "default 5.1 drops the LFE: {lfe:?}"
+        );
+        // A 6-channel layout can't apply to a 2-channel (folded) device tap.
+        assert_eq!(measure(2, back), measure(2, 0));
+    }
+
+    #[test]
+    fn queued_buffer_hands_off_once_and_is_cleared_by_new_sources() {
+        let audio = AudioEngine::new_for_test();
+        audio.set_samples_mono(vec![0.0; 4]);
+        audio.queue_next_buffer(Arc::new(AudioBuffer::from_mono(vec![0.5; 3])));
+        assert!(audio.has_queued_buffer());
+        let next = AudioEngine::take_queued_handoff(&audio.shared).expect("queued buffer");
+        assert_eq!(next.len(), 3);
+        assert_eq!(audio.current_source_len(), 3);
+        assert_eq!(audio.queued_handoff_count(), 1);
+        assert!(AudioEngine::take_queued_handoff(&audio.shared).is_none());
+
+        // An empty queued buffer never becomes the source.
+        audio.queue_next_buffer(Arc::new(AudioBuffer::from_mono(Vec::new())));
+        assert!(AudioEngine::take_queued_handoff(&audio.shared).is_none());
+        assert_eq!(audio.current_source_len(), 3);
+
+        audio.queue_next_buffer(Arc::new(AudioBuffer::from_mono(vec![0.25; 2])));
+        audio.set_samples_mono(vec![0.0; 8]);
+        assert!(
+            !audio.has_queued_buffer(),
+            "a new source must drop a stale playlist queue"
+        );
+        assert_eq!(audio.queued_handoff_count(), 1);
+    }
+
     #[test]
     fn streaming_wav_source_reports_length_and_rate_without_heap_buffer() {
         let mut path = std::env::temp_dir();
@@ -2070,34 +2398,48 @@ mod tests {
 
     #[test]
     fn meter_tap_ring_roundtrips_and_clamps_backlog() {
-        let tap = MeterTap::new();
+        let tap = MeterTap::new(2);
         for i in 0..100 {
-            tap.push_frame(i as f32, -(i as f32));
+            tap.push_frame(&[i as f32, -(i as f32)]);
         }
-        let mut l = Vec::new();
-        let mut r = Vec::new();
-        let cursor = tap.read_since(0, &mut l, &mut r);
+        let mut out = vec![Vec::new(), Vec::new()];
+        let cursor = tap.read_since(0, &mut out);
         assert_eq!(cursor, 100);
-        assert_eq!(l.len(), 100);
-        assert_eq!(l[0], 0.0);
-        assert_eq!(l[99], 99.0);
-        assert_eq!(r[99], -99.0);
+        assert_eq!(out[0].len(), 100);
+        assert_eq!(out[0][0], 0.0);
+        assert_eq!(out[0][99], 99.0);
+        assert_eq!(out[1][99], -99.0);
         // No new frames: cursor stays put, nothing appended.
-        l.clear();
-        r.clear();
-        assert_eq!(tap.read_since(cursor, &mut l, &mut r), 100);
-        assert!(l.is_empty());
+        out.iter_mut().for_each(Vec::clear);
+        assert_eq!(tap.read_since(cursor, &mut out), 100);
+        assert!(out[0].is_empty());
         // Reader falls a full ring behind: only the newest CAPACITY frames
         // come back, oldest first.
         for i in 0..(METER_TAP_CAPACITY + 500) {
-            tap.push_frame(i as f32, 0.0);
+            tap.push_frame(&[i as f32, 0.0]);
         }
-        l.clear();
-        r.clear();
-        let end = tap.read_since(cursor, &mut l, &mut r);
+        out.iter_mut().for_each(Vec::clear);
+        let end = tap.read_since(cursor, &mut out);
+        let l = &out[0];
         assert_eq!(end, 100 + METER_TAP_CAPACITY + 500);
         assert_eq!(l.len(), METER_TAP_CAPACITY);
         assert_eq!(l[0], 500.0, "oldest surviving frame after overwrite");
         assert_eq!(*l.last().unwrap(), (METER_TAP_CAPACITY + 500 - 1) as f32);
     }
+
+    #[test]
+    fn meter_tap_carries_surround_channels_and_widens_mono() {
+        let surround = MeterTap::new(6);
+        assert_eq!(surround.channels(), 6);
+        surround.push_frame(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let mut out = vec![Vec::new(); 6];
+        surround.read_since(0, &mut out);
+        assert_eq!(out[5], [6.0]);
+        let mono = MeterTap::new(1);
+        assert_eq!(mono.channels(), 2);
+        mono.push_frame(&[0.5]);
+        let mut out = vec![Vec::new(), Vec::new()];
+        mono.read_since(0, &mut out);
+        assert_eq!(out[1], [0.5], "R repeats the mono sample");
+    }
 }
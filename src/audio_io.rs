@@ -11,18 +11,57 @@ use fdk_aac::dec::{
 use id3::TagLike;
 use mp4::{ChannelConfig, Mp4Reader, TrackType};
 use symphonia::core::audio::{AudioBufferRef, SampleBuffer};
-use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::codecs::{CodecRegistry, DecoderOptions, CODEC_TYPE_OPUS};
 use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use symphonia::core::sample::SampleFormat;
-use symphonia::default::{get_codecs, get_probe};
+use symphonia::default::{get_probe, register_enabled_codecs};
 
-pub const SUPPORTED_EXTS: &[&str] = &["wav", "aiff", "aif", "flac", "mp3", "m4a", "ogg"];
+use crate::channel_layout::ChannelLayout;
+
+pub const SUPPORTED_EXTS: &[&str] = &[
+    "wav", "w64", "aiff", "aif", "caf", "flac", "mp3", "m4a", "ogg", "opus",
+];
 pub const EDITOR_PROXY_OVERVIEW_MAX_TOTAL_SAMPLES: usize = 16_384;
 
+/// symphonia's default codecs plus the libopus-backed Opus decoder
+/// (symphonia demuxes Ogg Opus but ships no Opus decoder).
+fn codec_registry() -> &'static CodecRegistry {
+    static REGISTRY: OnceLock<CodecRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = CodecRegistry::new();
+        register_enabled_codecs(&mut registry);
+        registry.register_all::<crate::opus_codec::OpusDecoder>();
+        registry
+    })
+}
+
+/// Ogg streams are opened in gapless mode so the Ogg reader trims the end
+/// padding implied by the final granule position (Opus pre-skip is dropped
+/// by the decoder itself). That covers `.ogg` files holding Opus as well as
+/// `.opus`, and Vorbis gets its exact granule length. MP3 uses it to strip
+/// the LAME-tag encoder delay and padding; M4A `iTunSMPB` is applied
+/// separately (see `gapless.rs`).
+fn format_options_for_path(path: &Path) -> FormatOptions {
+    FormatOptions {
+        enable_gapless: is_ogg_container_path(path) || is_mp3_path(path),
+        ..Default::default()
+    }
+}
+
+/// symphonia input for `path`. W64 is read through a synthesized RIFF/WAVE
+/// view (see `w64.rs`); everything else is the file itself.
+fn open_media_source(path: &Path) -> Result<Box<dyn MediaSource>> {
+    if let Some(source) = crate::w64::W64WaveSource::open(path)? {
+        return Ok(Box::new(source));
+    }
+    let file = File::open(path).with_context(|| format!("open audio: {}", path.display()))?;
+    Ok(Box::new(file))
+}
+
 fn io_trace_enabled() -> bool {
     static ENABLED: OnceLock<bool> = OnceLock::new();
     *ENABLED.get_or_init(|| {
@@ -104,6 +143,9 @@ pub enum SampleValueKind {
 #[derive(Clone, Copy, Debug)]
 pub struct AudioInfo {
     pub channels: u16,
+    /// Speaker positions: the WAVE_FORMAT_EXTENSIBLE mask when the file
+    /// declares one, otherwise the default layout for the channel count.
+    pub channel_layout: ChannelLayout,
     pub sample_rate: u32,
     pub bits_per_sample: u16,
     pub sample_value_kind: SampleValueKind,
@@ -132,6 +174,25 @@ fn is_m4a_path(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+fn is_mp3_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("mp3"))
+        .unwrap_or(false)
+}
+
+/// Extensions of Ogg files, whichever codec they carry.
+fn is_ogg_container_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|ext| {
+            ["opus", "ogg", "oga"]
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e))
+        })
+        .unwrap_or(false)
+}
+
 fn is_wav_path(path: &Path) -> bool {
     path.extension()
         .and_then(|s| s.to_str())
@@ -200,6 +261,12 @@ fn read_audio_info_m4a_mp4(
     } else {
         None
     };
+    let (total_frames, duration_secs) = match crate::gapless::read_m4a_gapless(path)
+        .and_then(|g| g.with_coded_len(total_frames).valid_frames)
+    {
+        Some(frames) => (Some(frames), frames as f32 / sample_rate as f32),
+        None => (total_frames, duration_secs),
+    };
     let bit_rate_bps = if duration_secs.is_finite() && duration_secs > 0.0 {
         file_size
             .map(|bytes| ((bytes as f64) * 8.0 / duration_secs as f64).round() as u32)
@@ -209,6 +276,7 @@ fn read_audio_info_m4a_mp4(
     };
     Ok(AudioInfo {
         channels,
+        channel_layout: ChannelLayout::default_for(channels),
         sample_rate,
         bits_per_sample: 16,
         sample_value_kind: SampleValueKind::Unknown,
@@ -238,6 +306,10 @@ fn read_audio_info_wav(
         };
         return Ok(AudioInfo {
             channels: info.channels,
+            channel_layout: info
+                .channel_mask
+                .map(|mask| ChannelLayout::new(info.channels, mask))
+                .unwrap_or_else(|| ChannelLayout::default_for(info.channels)),
             sample_rate: info.sample_rate,
             bits_per_sample: info.bits_per_sample,
             sample_value_kind: if info.audio_format == 3 {
@@ -281,6 +353,7 @@ fn read_audio_info_wav(
     };
     Ok(AudioInfo {
         channels: spec.channels,
+        channel_layout: ChannelLayout::default_for(spec.channels),
         sample_rate: spec.sample_rate,
         bits_per_sample: spec.bits_per_sample,
         sample_value_kind,
@@ -483,6 +556,54 @@ fn build_wav_proxy_preview_sparse(
     }))
 }
 
+/// FLAC counterpart of [`build_wav_proxy_preview_sparse`]: only the audio
+/// frames holding a strided sample are decoded, and seek points (SEEKTABLE
+/// or a cached frame index) let the walk jump over the rest.
+fn build_flac_proxy_preview_sparse(
+    path: &Path,
+    max_total_samples: usize,
+) -> Result<Option<AudioProxyPreview>> {
+    let Some(layout) = crate::flac_stream::read_flac_stream_layout(path)? else {
+        return Ok(None);
+    };
+    let total_source_frames = layout.info.total_frames as usize;
+    let source_channels = layout.info.channels.max(1) as usize;
+    let keep_channels = proxy_keep_channel_count(source_channels);
+    let stride = proxy_frame_stride(total_source_frames, keep_channels, max_total_samples.max(1));
+    let proxy_frames = total_source_frames.div_ceil(stride).max(1);
+    let mut out: Vec<Vec<f32>> = (0..keep_channels)
+        .map(|_| Vec::with_capacity(proxy_frames))
+        .collect();
+    let visited = crate::flac_stream::for_each_flac_strided_frame(path, stride, |frame| {
+        if keep_channels == 1 {
+            let mean = frame.iter().sum::<f32>() / frame.len().max(1) as f32;
+            out[0].push(mean.clamp(-1.0, 1.0));
+        } else {
+            for (channel, sample) in out.iter_mut().zip(frame) {
+                channel.push(*sample);
+            }
+        }
+    })
+    .with_context(|| format!("read flac proxy frames: {}", path.display()))?;
+    if visited.is_none() || out.first().is_none_or(|ch| ch.is_empty()) {
+        return Ok(None);
+    }
+    let sample_rate = layout.info.sample_rate.max(1);
+    let actual_proxy_frames = out.first().map(|ch| ch.len()).unwrap_or(0);
+    Ok(Some(AudioProxyPreview {
+        channels: out,
+        sample_rate: proxy_output_sample_rate(
+            sample_rate,
+            total_source_frames,
+            actual_proxy_frames,
+        )
+        .clamp(1, sample_rate),
+        source_sample_rate: sample_rate,
+        total_source_frames,
+        is_full_resolution: stride == 1 && keep_channels == source_channels,
+    }))
+}
+
 fn collect_wav_proxy_mono<F>(
     channels: usize,
     stride: usize,
@@ -543,6 +664,35 @@ where
     Ok(out)
 }
 
+/// Overview proxy for the formats that support sparse reads (WAV, FLAC);
+/// `None` for everything else, which falls back to a full decode.
+pub fn build_proxy_preview(
+    path: &Path,
+    max_total_samples: usize,
+) -> Result<Option<AudioProxyPreview>> {
+    let is_flac = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("flac"));
+    if !is_flac {
+        return build_wav_proxy_preview(path, max_total_samples);
+    }
+    let proxy = build_flac_proxy_preview_sparse(path, max_total_samples)?;
+    if let Some(proxy) = proxy.as_ref() {
+        io_trace(
+            "decode_flac_proxy_preview_sparse",
+            path,
+            "flac",
+            "proxy",
+            proxy.sample_rate,
+            proxy.channels.len() as u16,
+            32,
+            proxy.channels.first().map(|ch| ch.len()),
+        );
+    }
+    Ok(proxy)
+}
+
 pub fn build_wav_proxy_preview(
     path: &Path,
     max_total_samples: usize,
@@ -714,6 +864,19 @@ fn audio_specific_config_bytes(profile: u8, freq_index: u8, chan_conf: u8) -> [u
     [byte_a, byte_b]
 }
 
+/// `iTunSMPB` trimming for the fdk decode paths; `duration_secs` is the
+/// track duration, used when the tag leaves the source length blank.
+fn m4a_gapless_trim(
+    path: &Path,
+    duration_secs: f64,
+    sample_rate: u32,
+) -> Option<crate::gapless::GaplessTrim> {
+    let info = crate::gapless::read_m4a_gapless(path)?;
+    let coded = (duration_secs.is_finite() && duration_secs > 0.0)
+        .then(|| (duration_secs * sample_rate as f64).round() as u64);
+    Some(crate::gapless::GaplessTrim::new(info.with_coded_len(coded)))
+}
+
 fn decode_m4a_fdk(path: &Path, max_secs: Option<f32>) -> Result<(Vec<Vec<f32>>, u32, bool)> {
     let file = File::open(path).with_context(|| format!("open m4a: {}", path.display()))?;
     let size = file.metadata().map(|m| m.len()).unwrap_or(0);
@@ -751,6 +914,7 @@ fn decode_m4a_fdk(path: &Path, max_secs: Option<f32>) -> Result<(Vec<Vec<f32>>,
         .unwrap_or(mp4::SampleFreqIndex::Freq44100 as u8);
     let chan_conf = channel_cfg as u8;
     let asc = audio_specific_config_bytes(profile, freq_index, chan_conf);
+    let mut trim = m4a_gapless_trim(path, track.duration().as_secs_f64(), sample_rate);
     let mut decoder = AacDecoder::new(AacTransport::Raw);
     decoder
         .config_raw(&asc)
@@ -811,7 +975,8 @@ fn decode_m4a_fdk(path: &Path, max_secs: Option<f32>) -> Result<(Vec<Vec<f32>>,
                     if needed == 0 || pcm.len() < needed {
                         continue;
                     }
-                    for i in 0..frames {
+                    let keep = trim.as_mut().map(|t| t.keep(frames)).unwrap_or(0..frames);
+                    for i in keep {
                         for c in 0..ch {
                             let v = pcm[i * ch + c] as f32 / 32768.0;
                             chans[c].push(v);
@@ -965,6 +1130,7 @@ where
         .unwrap_or(mp4::SampleFreqIndex::Freq44100 as u8);
     let chan_conf = channel_cfg as u8;
     let asc = audio_specific_config_bytes(profile, freq_index, chan_conf);
+    let mut trim = m4a_gapless_trim(path, track.duration().as_secs_f64(), sample_rate);
     let mut decoder = AacDecoder::new(AacTransport::Raw);
     decoder
         .config_raw(&asc)
@@ -1023,13 +1189,14 @@ where
                     if pending.is_empty() {
                         pending = vec![Vec::new(); ch];
                     }
-                    for i in 0..frames {
+                    let keep = trim.as_mut().map(|t| t.keep(frames)).unwrap_or(0..frames);
+                    decoded_frames = decoded_frames.saturating_add(keep.len());
+                    for i in keep {
                         for c in 0..ch {
                             let v = pcm[i * ch + c] as f32 / 32768.0;
                             pending[c].push(v);
                         }
                     }
-                    decoded_frames = decoded_frames.saturating_add(frames);
                     if pending.first().map(|c| c.len()).unwrap_or(0) >= emit_frames
                         && !emit_ready_chunk(
                             path,
@@ -1220,6 +1387,23 @@ fn mixdown_to_mono(chans: &[Vec<f32>]) -> Vec<f32> {
     mono
 }
 
+/// Speaker layout a file declares in its header, if any: the
+/// WAVE_FORMAT_EXTENSIBLE mask of WAVE (RIFF/RF64/BW64) and W64, or the CAF
+/// `chan` chunk. Callers fall back to [`ChannelLayout::default_for`].
+pub fn read_channel_layout(path: &Path) -> Option<ChannelLayout> {
+    if let Ok(Some(info)) = crate::wav_stream::read_wave_pcm_info(path) {
+        return info
+            .channel_mask
+            .map(|mask| ChannelLayout::new(info.channels, mask));
+    }
+    if let Ok(Some(info)) = crate::w64::read_w64_info(path) {
+        return info
+            .channel_mask()
+            .map(|mask| ChannelLayout::new(info.channels(), mask));
+    }
+    crate::caf::read_caf_channel_layout(path)
+}
+
 pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
     let metadata = std::fs::metadata(path).ok();
     let created_at = metadata.as_ref().and_then(|m| m.created().ok());
@@ -1245,8 +1429,7 @@ pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
         }
     }
     let probe_once = |hint_ext: Option<&str>| -> Result<_> {
-        let file = File::open(path).with_context(|| format!("open audio: {}", path.display()))?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        let mss = MediaSourceStream::new(open_media_source(path)?, Default::default());
         let mut hint = Hint::new();
         if let Some(ext) = hint_ext {
             hint.with_extension(ext);
@@ -1255,7 +1438,7 @@ pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
             .format(
                 &hint,
                 mss,
-                &FormatOptions::default(),
+                &format_options_for_path(path),
                 &MetadataOptions::default(),
             )
             .map_err(Into::into)
@@ -1307,7 +1490,24 @@ pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
             _ => SampleValueKind::Int,
         })
         .unwrap_or(SampleValueKind::Unknown);
-    let duration_secs = match (cp.time_base, cp.n_frames) {
+    // Ogg Opus granule positions include the pre-skip priming samples.
+    let total_frames = cp.n_frames.map(|n| {
+        if cp.codec == CODEC_TYPE_OPUS {
+            n.saturating_sub(cp.delay.unwrap_or(0) as u64)
+        } else {
+            n
+        }
+    });
+    // MP3 is already trimmed by the gapless reader; M4A keeps `iTunSMPB` in
+    // the tag.
+    let total_frames = if is_m4a_path(path) {
+        crate::gapless::read_m4a_gapless(path)
+            .and_then(|g| g.with_coded_len(total_frames).valid_frames)
+            .or(total_frames)
+    } else {
+        total_frames
+    };
+    let duration_secs = match (cp.time_base, total_frames) {
         (Some(tb), Some(n)) => {
             let secs = (n as f64) * (tb.numer as f64) / (tb.denom as f64);
             Some(secs as f32)
@@ -1334,6 +1534,18 @@ pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
             }
         }
     }
+    // symphonia's AAC decoder stops at stereo, so surround M4A takes its
+    // channel count from the MPEG-4 channel configuration instead.
+    if (channels == 0 || bits_per_sample == 0) && is_m4a_path(path) {
+        if let Ok(info) = read_audio_info_m4a_mp4(path, created_at, modified_at, file_size) {
+            if channels == 0 {
+                channels = info.channels;
+            }
+            if bits_per_sample == 0 {
+                bits_per_sample = info.bits_per_sample;
+            }
+        }
+    }
     let mut bit_rate_bps = None;
     if let (Some(secs), Some(bytes)) = (duration_secs, file_size) {
         if secs.is_finite() && secs > 0.0 {
@@ -1351,16 +1563,20 @@ pub fn read_audio_info(path: &Path) -> Result<AudioInfo> {
         sample_rate,
         channels,
         bits_per_sample,
-        cp.n_frames.map(|v| v as usize),
+        total_frames.map(|v| v as usize),
     );
+    let channel_layout = read_channel_layout(path)
+        .filter(|layout| layout.channels() == channels)
+        .unwrap_or_else(|| ChannelLayout::default_for(channels));
     Ok(AudioInfo {
         channels,
+        channel_layout,
         sample_rate,
         bits_per_sample,
         sample_value_kind,
         bit_rate_bps,
         duration_secs,
-        total_frames: cp.n_frames,
+        total_frames,
         created_at,
         modified_at,
     })
@@ -1427,7 +1643,11 @@ pub fn read_embedded_artwork(path: &Path) -> Option<Vec<u8>> {
     let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
     match ext.to_ascii_lowercase().as_str() {
         "m4a" => read_artwork_m4a(path),
-        "mp3" | "wav" => read_artwork_id3(path),
+        "mp3" => read_artwork_id3(path),
+        "wav" => crate::wave::read_wav_id3_tag(path)
+            .ok()
+            .flatten()
+            .and_then(|tag| tag.pictures().next().map(|picture| picture.data.clone())),
         "flac" => crate::flac_meta::read_flac_artwork(path),
         _ => None,
     }
@@ -1549,8 +1769,7 @@ fn open_decoder(
 )> {
     let ext_hint = path.extension().and_then(|s| s.to_str());
     let probe_once = |hint_ext: Option<&str>| -> Result<_> {
-        let file = File::open(path).with_context(|| format!("open audio: {}", path.display()))?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        let mss = MediaSourceStream::new(open_media_source(path)?, Default::default());
         let mut hint = Hint::new();
         if let Some(ext) = hint_ext {
             hint.with_extension(ext);
@@ -1559,7 +1778,7 @@ fn open_decoder(
             .format(
                 &hint,
                 mss,
-                &FormatOptions::default(),
+                &format_options_for_path(path),
                 &MetadataOptions::default(),
             )
             .map_err(Into::into)
@@ -1581,7 +1800,13 @@ fn open_decoder(
     };
     let format = probed.format;
     let track = format.default_track().context("no default track")?.clone();
-    let decoder = get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    let mut decoder = codec_registry().make(&track.codec_params, &DecoderOptions::default())?;
+    if is_m4a_path(path) {
+        if let Some(info) = crate::gapless::read_m4a_gapless(path) {
+            let info = info.with_coded_len(track.codec_params.n_frames);
+            decoder = Box::new(crate::gapless::GaplessTrimDecoder::new(decoder, info));
+        }
+    }
     let sample_rate_hint = track.codec_params.sample_rate.unwrap_or(0);
     Ok((format, decoder, track.id, sample_rate_hint))
 }
@@ -0,0 +1,898 @@
+//! Core Audio Format (`.caf`) chunk reader/writer.
+//!
+//! symphonia demuxes CAF for decoding; this module covers the rest: writing
+//! linear PCM CAF, the `chan` speaker layout, and the `mark` / `regn` /
+//! `strg` chunks that carry markers, regions and the sustain loop. Rewrites
+//! keep every other chunk in place and stream-copy the audio.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::channel_layout::ChannelLayout;
+use crate::wave::{DitherMode, Quantizer, WavBitDepth};
+
+const CAF_FILE_HEADER_LEN: u64 = 8;
+const CAF_CHUNK_HEADER_LEN: u64 = 12;
+
+/// `mChannelLayoutTag` values. Tags other than the two `Use*` forms carry
+/// the channel count in their low 16 bits.
+const LAYOUT_TAG_USE_DESCRIPTIONS: u32 = 0;
+const LAYOUT_TAG_USE_BITMAP: u32 = 1 << 16;
+const LAYOUT_TAG_MONO: u32 = (100 << 16) | 1;
+const LAYOUT_TAG_STEREO: u32 = (101 << 16) | 2;
+const LAYOUT_TAG_STEREO_HEADPHONES: u32 = (102 << 16) | 2;
+const LAYOUT_TAG_QUADRAPHONIC: u32 = (108 << 16) | 4;
+const LAYOUT_TAG_MPEG_3_0_A: u32 = (113 << 16) | 3;
+const LAYOUT_TAG_MPEG_5_0_A: u32 = (117 << 16) | 5;
+const LAYOUT_TAG_MPEG_5_1_A: u32 = (121 << 16) | 6;
+const LAYOUT_TAG_MPEG_7_1_A: u32 = (126 << 16) | 8;
+/// `kAudioChannelLabel_Discrete_0`; unpositioned channels are written as
+/// `Discrete_N`.
+const CHANNEL_LABEL_DISCRETE: u32 = 1 << 16;
+
+/// `mType` of a `mark` entry. Plain markers are written as `Generic`.
+const MARKER_GENERIC: u32 = 0;
+const MARKER_INDEX: u32 = u32::from_be_bytes(*b"indx");
+const MARKER_REGION_START: u32 = u32::from_be_bytes(*b"rbeg");
+const MARKER_REGION_END: u32 = u32::from_be_bytes(*b"rend");
+const MARKER_SUSTAIN_LOOP_START: u32 = u32::from_be_bytes(*b"slbg");
+const MARKER_SUSTAIN_LOOP_END: u32 = u32::from_be_bytes(*b"slen");
+/// `CAFMarker`: type, f64 frame position, id, 8-byte SMPTE time, channel.
+const CAF_MARKER_LEN: usize = 28;
+
+pub fn is_caf_header(bytes: &[u8]) -> bool {
+    bytes.len() >= 8 && &bytes[0..4] == b"caff"
+}
+
+/// One top-level chunk; offsets are absolute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CafChunkInfo {
+    pub id: [u8; 4],
+    pub header_offset: u64,
+    /// Size field as written; `-1` marks a `data` chunk running to EOF.
+    pub declared_size: i64,
+    pub payload_offset: u64,
+    /// Payload bytes actually present in the file.
+    pub payload_len: u64,
+}
+
+/// Walk the top-level chunks, stopping at the first truncated header or
+/// invalid size.
+pub fn read_caf_chunks(path: &Path) -> Result<Vec<CafChunkInfo>> {
+    let mut file = File::open(path).with_context(|| format!("open caf: {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    let mut head = [0u8; 8];
+    file.read_exact(&mut head)
+        .with_context(|| format!("read caf header: {}", path.display()))?;
+    if !is_caf_header(&head) {
+        anyhow::bail!("not a CAF file: {}", path.display());
+    }
+    let mut chunks = Vec::new();
+    let mut pos = CAF_FILE_HEADER_LEN;
+    while pos + CAF_CHUNK_HEADER_LEN <= file_len {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 12];
+        file.read_exact(&mut header)?;
+        let id: [u8; 4] = header[0..4].try_into().unwrap();
+        let declared_size = i64::from_be_bytes(header[4..12].try_into().unwrap());
+        let payload_offset = pos + CAF_CHUNK_HEADER_LEN;
+        let available = file_len - payload_offset;
+        let payload_len = match u64::try_from(declared_size) {
+            Ok(size) => size.min(available),
+            Err(_) => available,
+        };
+        chunks.push(CafChunkInfo {
+            id,
+            header_offset: pos,
+            declared_size,
+            payload_offset,
+            payload_len,
+        });
+        let Ok(size) = u64::try_from(declared_size) else {
+            break;
+        };
+        let Some(next) = payload_offset.checked_add(size) else {
+            break;
+        };
+        pos = next;
+    }
+    Ok(chunks)
+}
+
+fn read_payload(file: &mut File, chunk: &CafChunkInfo) -> Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(chunk.payload_offset))?;
+    let mut payload = vec![0u8; chunk.payload_len as usize];
+    file.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// Payload of the first `id` chunk.
+pub fn read_caf_chunk(path: &Path, id: &[u8; 4]) -> Result<Option<Vec<u8>>> {
+    let chunks = read_caf_chunks(path)?;
+    let Some(chunk) = chunks.iter().find(|c| &c.id == id) else {
+        return Ok(None);
+    };
+    let mut file = File::open(path).with_context(|| format!("open caf: {}", path.display()))?;
+    Ok(Some(read_payload(&mut file, chunk)?))
+}
+
+// ---- Rewrites ----
+
+enum CafBody {
+    Bytes(Vec<u8>),
+    /// Payload left in the source file (audio and anything not edited).
+    Source {
+        offset: u64,
+        len: u64,
+    },
+}
+
+struct CafPart {
+    id: [u8; 4],
+    body: CafBody,
+}
+
+impl CafPart {
+    fn bytes(id: [u8; 4], payload: Vec<u8>) -> Self {
+        Self {
+            id,
+            body: CafBody::Bytes(payload),
+        }
+    }
+}
+
+fn load_parts(path: &Path) -> Result<Vec<CafPart>> {
+    Ok(read_caf_chunks(path)?
+        .into_iter()
+        .map(|chunk| CafPart {
+            id: chunk.id,
+            body: CafBody::Source {
+                offset: chunk.payload_offset,
+                len: chunk.payload_len,
+            },
+        })
+        .collect())
+}
+
+/// Replace the `id` chunk with `payload` (`None` drops it). A new chunk goes
+/// before `data`.
+fn replace_part(parts: &mut Vec<CafPart>, id: [u8; 4], payload: Option<Vec<u8>>) {
+    let existing = parts.iter().position(|p| p.id == id);
+    parts.retain(|p| p.id != id);
+    let Some(payload) = payload else {
+        return;
+    };
+    let at = existing
+        .or_else(|| parts.iter().position(|p| &p.id == b"data"))
+        .unwrap_or(parts.len())
+        .min(parts.len());
+    parts.insert(at, CafPart::bytes(id, payload));
+}
+
+/// Write `parts` to `dst`, copying `Source` payloads out of `src`. Every
+/// chunk gets an explicit size, including a `data` chunk read as `-1`.
+fn write_parts(src: &Path, dst: &Path, parts: &[CafPart]) -> Result<()> {
+    let mut input =
+        BufReader::new(File::open(src).with_context(|| format!("open caf: {}", src.display()))?);
+    let out_file = File::create(dst).with_context(|| format!("create caf: {}", dst.display()))?;
+    let mut out = BufWriter::new(out_file);
+    out.write_all(b"caff")?;
+    out.write_all(&1u16.to_be_bytes())?;
+    out.write_all(&0u16.to_be_bytes())?;
+    for part in parts {
+        out.write_all(&part.id)?;
+        match &part.body {
+            CafBody::Bytes(bytes) => {
+                out.write_all(&(bytes.len() as i64).to_be_bytes())?;
+                out.write_all(bytes)?;
+            }
+            CafBody::Source { offset, len } => {
+                out.write_all(&(*len as i64).to_be_bytes())?;
+                input.seek(SeekFrom::Start(*offset))?;
+                let copied = std::io::copy(&mut (&mut input).take(*len), &mut out)
+                    .with_context(|| format!("copy caf chunk: {}", src.display()))?;
+                if copied != *len {
+                    anyhow::bail!("caf chunk truncated: {}", src.display());
+                }
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Rewrite `path` in place through a sibling temp file.
+fn rewrite_caf(path: &Path, parts: &[CafPart]) -> Result<()> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let tmp = parent.join(format!(
+        ".wvp_tmp_cafmeta_{}_{}.caf",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    if let Err(err) = write_parts(path, &tmp, parts) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err);
+    }
+    match std::fs::rename(&tmp, path) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            // Windows: rename fails while the target exists; replace via copy.
+            let res = std::fs::copy(&tmp, path)
+                .map(|_| ())
+                .with_context(|| format!("replace caf: {}", path.display()));
+            let _ = std::fs::remove_file(&tmp);
+            res
+        }
+    }
+}
+
+/// Chunks describing the audio itself; everything else is metadata that
+/// survives a re-encode (see [`copy_caf_metadata_from_source`]).
+fn is_audio_core_chunk(id: &[u8; 4]) -> bool {
+    matches!(
+        id,
+        b"desc" | b"data" | b"pakt" | b"kuki" | b"chan" | b"free"
+    )
+}
+
+/// Carry `src`'s metadata chunks (`info`, `mark`, `regn`, `strg`, `uuid`,
+/// ...) into the freshly written `dst`, keeping `dst`'s audio chunks.
+pub fn copy_caf_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
+    let source = read_caf_chunks(src)?;
+    let mut src_file = File::open(src).with_context(|| format!("open caf: {}", src.display()))?;
+    let mut parts = load_parts(dst)?;
+    parts.retain(|p| is_audio_core_chunk(&p.id));
+    let at = parts
+        .iter()
+        .position(|p| &p.id == b"data")
+        .unwrap_or(parts.len());
+    let carried = source
+        .iter()
+        .filter(|c| !is_audio_core_chunk(&c.id))
+        .map(|c| Ok(CafPart::bytes(c.id, read_payload(&mut src_file, c)?)))
+        .collect::<Result<Vec<_>>>()?;
+    if carried.is_empty() {
+        return Ok(());
+    }
+    parts.splice(at..at, carried);
+    rewrite_caf(dst, &parts)
+}
+
+// ---- Writing PCM ----
+
+/// Write `chans` as little-endian linear PCM CAF with a `chan` chunk for
+/// `layout` (ignored unless it matches the channel count).
+pub fn write_caf(
+    chans: &[Vec<f32>],
+    sample_rate: u32,
+    dst: &Path,
+    depth: WavBitDepth,
+    layout: ChannelLayout,
+) -> Result<()> {
+    let channels = chans.len().max(1);
+    let frames = chans.first().map(|c| c.len()).unwrap_or(0);
+    let bytes_per_sample = match depth {
+        WavBitDepth::Pcm16 => 2usize,
+        WavBitDepth::Pcm24 => 3,
+        WavBitDepth::Float32 => 4,
+    };
+    let is_float = matches!(depth, WavBitDepth::Float32);
+    let layout = if layout.channels() as usize == channels {
+        layout
+    } else {
+        ChannelLayout::default_for(channels.min(u16::MAX as usize) as u16)
+    };
+
+    let out_file = File::create(dst).with_context(|| format!("create caf: {}", dst.display()))?;
+    let mut out = BufWriter::new(out_file);
+    out.write_all(b"caff")?;
+    out.write_all(&1u16.to_be_bytes())?;
+    out.write_all(&0u16.to_be_bytes())?;
+
+    // desc: kAudioFormatLinearPCM, flags bit 0 = float, bit 1 = little-endian.
+    let flags: u32 = if is_float { 0b11 } else { 0b10 };
+    out.write_all(b"desc")?;
+    out.write_all(&32i64.to_be_bytes())?;
+    out.write_all(&f64::from(sample_rate.max(1)).to_be_bytes())?;
+    out.write_all(b"lpcm")?;
+    out.write_all(&flags.to_be_bytes())?;
+    out.write_all(&((bytes_per_sample * channels) as u32).to_be_bytes())?;
+    out.write_all(&1u32.to_be_bytes())?;
+    out.write_all(&(channels as u32).to_be_bytes())?;
+    out.write_all(&u32::from(depth.bits_per_sample()).to_be_bytes())?;
+
+    let chan = encode_caf_channel_layout(&layout);
+    out.write_all(b"chan")?;
+    out.write_all(&(chan.len() as i64).to_be_bytes())?;
+    out.write_all(&chan)?;
+
+    let data_len = 4 + (frames * channels * bytes_per_sample) as i64;
+    out.write_all(b"data")?;
+    out.write_all(&data_len.to_be_bytes())?;
+    out.write_all(&0u32.to_be_bytes())?; // mEditCount
+    let mut quant = match depth {
+        WavBitDepth::Pcm16 => Quantizer::for_export(
+            32768.0,
+            i16::MIN as f64,
+            i16::MAX as f64,
+            channels,
+            16,
+            sample_rate,
+        ),
+        WavBitDepth::Pcm24 => Quantizer::for_export(
+            8_388_607.0,
+            -8_388_607.0,
+            8_388_607.0,
+            channels,
+            24,
+            sample_rate,
+        ),
+        WavBitDepth::Float32 => Quantizer::new(1.0, -1.0, 1.0, 1, DitherMode::Off),
+    };
+    for i in 0..frames {
+        for ci in 0..channels {
+            let v = chans
+                .get(ci)
+                .and_then(|c| c.get(i))
+                .copied()
+                .unwrap_or(0.0)
+                .clamp(-1.0, 1.0);
+            match depth {
+                WavBitDepth::Pcm16 => {
+                    out.write_all(&(quant.quantize(ci, v) as i16).to_le_bytes())?;
+                }
+                WavBitDepth::Pcm24 => {
+                    out.write_all(&quant.quantize(ci, v).to_le_bytes()[0..3])?;
+                }
+                WavBitDepth::Float32 => out.write_all(&v.to_le_bytes())?,
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+// ---- Channel layout ----
+
+/// Speaker layout from a `chan` payload for a `channels`-channel stream.
+/// Bitmap and label bits match the WAVE channel mask for the 18 standard
+/// positions; unknown layout tags give `None`.
+pub fn parse_caf_channel_layout(payload: &[u8], channels: u16) -> Option<ChannelLayout> {
+    let be_u32 = |at: usize| {
+        payload
+            .get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let tag = be_u32(0)?;
+    let mask = match tag {
+        LAYOUT_TAG_USE_BITMAP => be_u32(4)?,
+        LAYOUT_TAG_USE_DESCRIPTIONS => {
+            let count = be_u32(8)? as usize;
+            let speakers: Vec<Option<u32>> = (0..count.min(channels as usize))
+                .map(|i| match be_u32(12 + i * 20)? {
+                    label @ 1..=18 => Some(1u32 << (label - 1)),
+                    _ => None,
+                })
+                .collect();
+            if speakers.len() != channels as usize {
+                return None;
+            }
+            return Some(ChannelLayout::from_speakers(&speakers));
+        }
+        LAYOUT_TAG_MONO => 0x4,
+        LAYOUT_TAG_STEREO | LAYOUT_TAG_STEREO_HEADPHONES => 0x3,
+        LAYOUT_TAG_QUADRAPHONIC => 0x33,
+        LAYOUT_TAG_MPEG_3_0_A => 0x7,
+        LAYOUT_TAG_MPEG_5_0_A => 0x37,
+        LAYOUT_TAG_MPEG_5_1_A => 0x3F,
+        LAYOUT_TAG_MPEG_7_1_A => 0xFF,
+        _ => return None,
+    };
+    Some(ChannelLayout::new(channels, mask))
+}
+
+/// `chan` payload for `layout`: a named tag for mono and stereo, the
+/// channel bitmap when every channel has a speaker, otherwise per-channel
+/// descriptions with `Discrete_N` for unpositioned channels.
+pub fn encode_caf_channel_layout(layout: &ChannelLayout) -> Vec<u8> {
+    let channels = layout.channels() as usize;
+    let mut out = Vec::with_capacity(12 + channels * 20);
+    let tag = match (layout.channels(), layout.mask()) {
+        (1, 0x4) => Some(LAYOUT_TAG_MONO),
+        (2, 0x3) => Some(LAYOUT_TAG_STEREO),
+        _ => None,
+    };
+    if let Some(tag) = tag {
+        out.extend_from_slice(&tag.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        return out;
+    }
+    if layout.mask().count_ones() as usize == channels {
+        out.extend_from_slice(&LAYOUT_TAG_USE_BITMAP.to_be_bytes());
+        out.extend_from_slice(&layout.mask().to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        return out;
+    }
+    out.extend_from_slice(&LAYOUT_TAG_USE_DESCRIPTIONS.to_be_bytes());
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&(channels as u32).to_be_bytes());
+    for ch in 0..channels {
+        let label = match layout.speaker_bit(ch) {
+            Some(bit) => bit.trailing_zeros() + 1,
+            None => CHANNEL_LABEL_DISCRETE | ch as u32,
+        };
+        out.extend_from_slice(&label.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes()); // mChannelFlags
+        out.extend_from_slice(&[0u8; 12]); // mCoordinates
+    }
+    out
+}
+
+/// Channel count from a `desc` payload.
+pub fn desc_channels(desc: &[u8]) -> Option<u16> {
+    let bytes = desc.get(24..28)?;
+    u16::try_from(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).ok()
+}
+
+pub fn read_caf_channel_layout(path: &Path) -> Option<ChannelLayout> {
+    let channels = desc_channels(&read_caf_chunk(path, b"desc").ok()??)?;
+    let chan = read_caf_chunk(path, b"chan").ok()??;
+    parse_caf_channel_layout(&chan, channels)
+}
+
+// ---- info / strg ----
+
+/// Key/value pairs of an `info` chunk (count, then NUL-terminated UTF-8
+/// key and value strings).
+pub fn parse_caf_info(payload: &[u8]) -> Vec<(String, String)> {
+    let Some(count) = payload.get(0..4) else {
+        return Vec::new();
+    };
+    let count = u32::from_be_bytes([count[0], count[1], count[2], count[3]]) as usize;
+    let mut strings = payload[4..]
+        .split(|b| *b == 0)
+        .map(|s| String::from_utf8_lossy(s).into_owned());
+    let mut out = Vec::new();
+    for _ in 0..count {
+        let (Some(key), Some(value)) = (strings.next(), strings.next()) else {
+            break;
+        };
+        out.push((key, value));
+    }
+    out
+}
+
+/// `strg` entries as (string id, text).
+pub fn parse_caf_strings(payload: &[u8]) -> Vec<(u32, String)> {
+    let Some(count) = payload.get(0..4) else {
+        return Vec::new();
+    };
+    let count = u32::from_be_bytes([count[0], count[1], count[2], count[3]]) as usize;
+    let table_end = 4usize.saturating_add(count.saturating_mul(12));
+    let Some(data) = payload.get(table_end..) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for i in 0..count {
+        let at = 4 + i * 12;
+        let id = u32::from_be_bytes(payload[at..at + 4].try_into().unwrap());
+        let offset = i64::from_be_bytes(payload[at + 4..at + 12].try_into().unwrap());
+        let Some(text) = usize::try_from(offset).ok().and_then(|o| data.get(o..)) else {
+            continue;
+        };
+        let end = text.iter().position(|b| *b == 0).unwrap_or(text.len());
+        out.push((id, String::from_utf8_lossy(&text[..end]).into_owned()));
+    }
+    out
+}
+
+fn encode_caf_strings(strings: &[(u32, String)]) -> Vec<u8> {
+    let mut table = Vec::with_capacity(4 + strings.len() * 12);
+    let mut data = Vec::new();
+    table.extend_from_slice(&(strings.len() as u32).to_be_bytes());
+    for (id, text) in strings {
+        table.extend_from_slice(&id.to_be_bytes());
+        table.extend_from_slice(&(data.len() as i64).to_be_bytes());
+        data.extend_from_slice(text.replace('\0', " ").as_bytes());
+        data.push(0);
+    }
+    table.extend_from_slice(&data);
+    table
+}
+
+// ---- Markers and regions ----
+
+/// One `CAFMarker`. The SMPTE time is carried through untouched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CafMarker {
+    pub kind: u32,
+    pub frame: f64,
+    pub id: u32,
+    pub smpte: [u8; 8],
+    pub channel: u32,
+}
+
+impl CafMarker {
+    fn new(kind: u32, frame: u64, id: u32) -> Self {
+        Self {
+            kind,
+            frame: frame as f64,
+            id,
+            smpte: [0; 8],
+            channel: 0,
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.frame.max(0.0).round() as u64
+    }
+
+    /// `mType` as text: the fourcc, or `generic`.
+    pub fn kind_name(&self) -> String {
+        if self.kind == MARKER_GENERIC {
+            "generic".to_string()
+        } else {
+            String::from_utf8_lossy(&self.kind.to_be_bytes()).into_owned()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CafRegion {
+    pub id: u32,
+    pub flags: u32,
+    pub markers: Vec<CafMarker>,
+}
+
+fn parse_marker_at(payload: &[u8], at: usize) -> Option<CafMarker> {
+    let bytes = payload.get(at..at + CAF_MARKER_LEN)?;
+    Some(CafMarker {
+        kind: u32::from_be_bytes(bytes[0..4].try_into().unwrap()),
+        frame: f64::from_be_bytes(bytes[4..12].try_into().unwrap()),
+        id: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+        smpte: bytes[16..24].try_into().unwrap(),
+        channel: u32::from_be_bytes(bytes[24..28].try_into().unwrap()),
+    })
+}
+
+fn encode_marker(out: &mut Vec<u8>, marker: &CafMarker) {
+    out.extend_from_slice(&marker.kind.to_be_bytes());
+    out.extend_from_slice(&marker.frame.to_be_bytes());
+    out.extend_from_slice(&marker.id.to_be_bytes());
+    out.extend_from_slice(&marker.smpte);
+    out.extend_from_slice(&marker.channel.to_be_bytes());
+}
+
+/// Entries of a `mark` chunk (SMPTE time type, count, markers).
+pub fn parse_caf_markers(payload: &[u8]) -> Vec<CafMarker> {
+    let Some(count) = payload.get(4..8) else {
+        return Vec::new();
+    };
+    let count = u32::from_be_bytes([count[0], count[1], count[2], count[3]]) as usize;
+    (0..count)
+        .map_while(|i| parse_marker_at(payload, 8 + i * CAF_MARKER_LEN))
+        .collect()
+}
+
+fn encode_caf_markers(markers: &[CafMarker]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + markers.len() * CAF_MARKER_LEN);
+    out.extend_from_slice(&0u32.to_be_bytes()); // kCAF_SMPTE_TimeTypeNone
+    out.extend_from_slice(&(markers.len() as u32).to_be_bytes());
+    for marker in markers {
+        encode_marker(&mut out, marker);
+    }
+    out
+}
+
+/// Entries of a `regn` chunk (SMPTE time type, count, regions).
+pub fn parse_caf_regions(payload: &[u8]) -> Vec<CafRegion> {
+    let Some(count) = payload.get(4..8) else {
+        return Vec::new();
+    };
+    let count = u32::from_be_bytes([count[0], count[1], count[2], count[3]]) as usize;
+    let mut out = Vec::new();
+    let mut pos = 8usize;
+    for _ in 0..count {
+        let Some(head) = payload.get(pos..pos + 12) else {
+            break;
+        };
+        let id = u32::from_be_bytes(head[0..4].try_into().unwrap());
+        let flags = u32::from_be_bytes(head[4..8].try_into().unwrap());
+        let marker_count = u32::from_be_bytes(head[8..12].try_into().unwrap()) as usize;
+        pos += 12;
+        let markers: Vec<CafMarker> = (0..marker_count)
+            .map_while(|i| parse_marker_at(payload, pos + i * CAF_MARKER_LEN))
+            .collect();
+        let complete = markers.len() == marker_count;
+        pos += markers.len() * CAF_MARKER_LEN;
+        out.push(CafRegion { id, flags, markers });
+        if !complete {
+            break;
+        }
+    }
+    out
+}
+
+fn encode_caf_regions(regions: &[CafRegion]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&0u32.to_be_bytes()); // kCAF_SMPTE_TimeTypeNone
+    out.extend_from_slice(&(regions.len() as u32).to_be_bytes());
+    for region in regions {
+        out.extend_from_slice(&region.id.to_be_bytes());
+        out.extend_from_slice(&region.flags.to_be_bytes());
+        out.extend_from_slice(&(region.markers.len() as u32).to_be_bytes());
+        for marker in &region.markers {
+            encode_marker(&mut out, marker);
+        }
+    }
+    out
+}
+
+impl CafRegion {
+    /// `[start, end)` from the `rbeg` / `rend` markers, falling back to the
+    /// outermost marker positions.
+    pub fn span(&self) -> Option<(u64, u64)> {
+        let of_kind = |kind: u32| {
+            self.markers
+                .iter()
+                .find(|m| m.kind == kind)
+                .map(CafMarker::position)
+        };
+        let positions = self.markers.iter().map(CafMarker::position);
+        let start = of_kind(MARKER_REGION_START).or_else(|| positions.clone().min())?;
+        let end = of_kind(MARKER_REGION_END).or_else(|| positions.max())?;
+        (end > start).then_some((start, end))
+    }
+}
+
+/// Cue markers and regions kept in a CAF file, positions in sample frames.
+/// Markers are the `Generic` and `Index` entries of `mark`; other marker
+/// types (loops, program/track marks) are not part of this list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CafCues {
+    pub markers: Vec<(u64, String)>,
+    pub regions: Vec<(u64, u64, String)>,
+}
+
+fn is_cue_marker(marker: &CafMarker) -> bool {
+    matches!(marker.kind, MARKER_GENERIC | MARKER_INDEX)
+}
+
+fn is_loop_marker(marker: &CafMarker) -> bool {
+    matches!(
+        marker.kind,
+        MARKER_SUSTAIN_LOOP_START | MARKER_SUSTAIN_LOOP_END
+    )
+}
+
+struct CafMarkerChunks {
+    markers: Vec<CafMarker>,
+    regions: Vec<CafRegion>,
+    strings: Vec<(u32, String)>,
+}
+
+fn read_marker_chunks(path: &Path) -> Result<CafMarkerChunks> {
+    let chunks = read_caf_chunks(path)?;
+    let mut file = File::open(path).with_context(|| format!("open caf: {}", path.display()))?;
+    let mut payload_of = |id: &[u8; 4]| -> Result<Option<Vec<u8>>> {
+        match chunks.iter().find(|c| &c.id == id) {
+            Some(chunk) => Ok(Some(read_payload(&mut file, chunk)?)),
+            None => Ok(None),
+        }
+    };
+    Ok(CafMarkerChunks {
+        markers: payload_of(b"mark")?
+            .map(|p| parse_caf_markers(&p))
+            .unwrap_or_default(),
+        regions: payload_of(b"regn")?
+            .map(|p| parse_caf_regions(&p))
+            .unwrap_or_default(),
+        strings: payload_of(b"strg")?
+            .map(|p| parse_caf_strings(&p))
+            .unwrap_or_default(),
+    })
+}
+
+/// Store `state` back into `path`: empty lists drop their chunk, and `strg`
+/// keeps only the names still referenced.
+fn write_marker_chunks(path: &Path, mut state: CafMarkerChunks) -> Result<()> {
+    let referenced: Vec<u32> = state
+        .markers
+        .iter()
+        .map(|m| m.id)
+        .chain(state.regions.iter().map(|r| r.id))
+        .chain(
+            state
+                .regions
+                .iter()
+                .flat_map(|r| r.markers.iter().map(|m| m.id)),
+        )
+        .collect();
+    state.strings.retain(|(id, _)| referenced.contains(id));
+    let mut parts = load_parts(path)?;
+    replace_part(
+        &mut parts,
+        *b"mark",
+        (!state.markers.is_empty()).then(|| encode_caf_markers(&state.markers)),
+    );
+    replace_part(
+        &mut parts,
+        *b"regn",
+        (!state.regions.is_empty()).then(|| encode_caf_regions(&state.regions)),
+    );
+    replace_part(
+        &mut parts,
+        *b"strg",
+        (!state.strings.is_empty()).then(|| encode_caf_strings(&state.strings)),
+    );
+    rewrite_caf(path, &parts)
+}
+
+/// Read cue markers and regions, taking labels from `strg` by marker and
+/// region id.
+pub fn read_caf_cues(path: &Path) -> Result<CafCues> {
+    let state = read_marker_chunks(path)?;
+    let name_of = |id: u32| {
+        state
+            .strings
+            .iter()
+            .find(|(sid, _)| *sid == id && id != 0)
+            .map(|(_, text)| text.clone())
+    };
+    let mut cues = CafCues::default();
+    for (i, marker) in state
+        .markers
+        .iter()
+        .filter(|m| is_cue_marker(m))
+        .enumerate()
+    {
+        let label = name_of(marker.id).unwrap_or_else(|| format!("M{:02}", i + 1));
+        cues.markers.push((marker.position(), label));
+    }
+    for (i, region) in state.regions.iter().enumerate() {
+        let Some((start, end)) = region.span() else {
+            continue;
+        };
+        let label = name_of(region.id).unwrap_or_else(|| format!("Region {}", i + 1));
+        cues.regions.push((start, end, label));
+    }
+    cues.markers.sort_by_key(|(pos, _)| *pos);
+    cues.regions.sort_by_key(|(start, end, _)| (*start, *end));
+    Ok(cues)
+}
+
+/// Replace the cue markers and regions, keeping loop and other typed
+/// markers. Each region is written as an `rbeg` / `rend` pair.
+pub fn write_caf_cues(path: &Path, cues: &CafCues) -> Result<()> {
+    let mut state = read_marker_chunks(path)?;
+    state.markers.retain(|m| !is_cue_marker(m));
+    state.regions.clear();
+    let mut next_id = state.markers.iter().map(|m| m.id).max().unwrap_or(0);
+    let mut alloc_id = || -> Result<u32> {
+        next_id = next_id.checked_add(1).context("too many CAF markers")?;
+        Ok(next_id)
+    };
+    for (position, label) in &cues.markers {
+        let id = alloc_id()?;
+        state
+            .markers
+            .push(CafMarker::new(MARKER_GENERIC, *position, id));
+        if !label.is_empty() {
+            state.strings.push((id, label.clone()));
+        }
+    }
+    for (start, end, label) in cues.regions.iter().filter(|(s, e, _)| e > s) {
+        let id = alloc_id()?;
+        let markers = vec![
+            CafMarker::new(MARKER_REGION_START, *start, alloc_id()?),
+            CafMarker::new(MARKER_REGION_END, *end, alloc_id()?),
+        ];
+        state.regions.push(CafRegion {
+            id,
+            flags: 0,
+            markers,
+        });
+        if !label.is_empty() {
+            state.strings.push((id, label.clone()));
+        }
+    }
+    state.markers.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+    write_marker_chunks(path, state)
+}
+
+/// The sustain loop from `slbg` / `slen` markers.
+pub fn read_caf_loop_markers(path: &Path) -> Option<(u64, u64)> {
+    let state = read_marker_chunks(path).ok()?;
+    let position = |kind: u32| {
+        state
+            .markers
+            .iter()
+            .find(|m| m.kind == kind)
+            .map(CafMarker::position)
+    };
+    let start = position(MARKER_SUSTAIN_LOOP_START)?;
+    let end = position(MARKER_SUSTAIN_LOOP_END)?;
+    (end > start).then_some((start, end))
+}
+
+/// Write (or clear) the sustain loop as `slbg` / `slen` markers. Cue
+/// markers and regions are kept.
+pub fn write_caf_loop_markers(path: &Path, loop_opt: Option<(u64, u64)>) -> Result<()> {
+    let mut state = read_marker_chunks(path)?;
+    state.markers.retain(|m| !is_loop_marker(m));
+    if let Some((start, end)) = loop_opt.filter(|(s, e)| e > s) {
+        let next_id = state
+            .markers
+            .iter()
+            .map(|m| m.id)
+            .chain(state.regions.iter().map(|r| r.id))
+            .chain(
+                state
+                    .regions
+                    .iter()
+                    .flat_map(|r| r.markers.iter().map(|m| m.id)),
+            )
+            .max()
+            .unwrap_or(0);
+        state.markers.push(CafMarker::new(
+            MARKER_SUSTAIN_LOOP_START,
+            start,
+            next_id + 1,
+        ));
+        state
+            .markers
+            .push(CafMarker::new(MARKER_SUSTAIN_LOOP_END, end, next_id + 2));
+        state.markers.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+    }
+    write_marker_chunks(path, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_layout_round_trips_through_chan_payloads() {
+        for layout in [
+            ChannelLayout::default_for(1),
+            ChannelLayout::default_for(2),
+            ChannelLayout::default_for(6),
+            ChannelLayout::new(6, 0x60F),
+            ChannelLayout::new(4, 0x3),
+        ] {
+            let payload = encode_caf_channel_layout(&layout);
+            assert_eq!(
+                parse_caf_channel_layout(&payload, layout.channels()),
+                Some(layout)
+            );
+        }
+        let mut tagged = LAYOUT_TAG_MPEG_5_1_A.to_be_bytes().to_vec();
+        tagged.extend_from_slice(&[0; 8]);
+        assert_eq!(
+            parse_caf_channel_layout(&tagged, 6),
+            Some(ChannelLayout::default_for(6))
+        );
+    }
+
+    #[test]
+    fn info_and_string_chunks_parse() {
+        let mut info = 2u32.to_be_bytes().to_vec();
+        info.extend_from_slice(b"title\0Rain\0artist\0Field\0");
+        assert_eq!(
+            parse_caf_info(&info),
+            [
+                ("title".to_string(), "Rain".to_string()),
+                ("artist".to_string(), "Field".to_string())
+            ]
+        );
+        let strings = vec![(3, "Intro".to_string()), (7, "Drop".to_string())];
+        assert_eq!(parse_caf_strings(&encode_caf_strings(&strings)), strings);
+    }
+}
@@ -0,0 +1,282 @@
+//! Speaker layouts for multichannel audio.
+//!
+//! A layout is a WAVE_FORMAT_EXTENSIBLE `dwChannelMask` plus a channel
+//! count. As in the WAVE spec, channel `i` sits on the `i`-th lowest set bit
+//! of the mask; channels past the last set bit have no speaker position and
+//! are labelled by index.
+
+pub const SPEAKER_FRONT_LEFT: u32 = 0x1;
+pub const SPEAKER_FRONT_RIGHT: u32 = 0x2;
+pub const SPEAKER_FRONT_CENTER: u32 = 0x4;
+pub const SPEAKER_LOW_FREQUENCY: u32 = 0x8;
+pub const SPEAKER_BACK_LEFT: u32 = 0x10;
+pub const SPEAKER_BACK_RIGHT: u32 = 0x20;
+pub const SPEAKER_FRONT_LEFT_OF_CENTER: u32 = 0x40;
+pub const SPEAKER_FRONT_RIGHT_OF_CENTER: u32 = 0x80;
+pub const SPEAKER_BACK_CENTER: u32 = 0x100;
+pub const SPEAKER_SIDE_LEFT: u32 = 0x200;
+pub const SPEAKER_SIDE_RIGHT: u32 = 0x400;
+
+/// Bits defined by the WAVE spec (front left through top back right).
+const SPEAKER_ALL: u32 = 0x3FFFF;
+
+/// Short labels per speaker bit, lowest bit first.
+const SPEAKER_LABELS: [&str; 18] = [
+    "L", "R", "C", "LFE", "Lrs", "Rrs", "Lc", "Rc", "Cs", "Ls", "Rs", "Tc", "Tfl", "Tfc", "Tfr",
+    "Trl", "Trc", "Trr",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChannelLayout {
+    channels: u16,
+    mask: u32,
+}
+
+impl ChannelLayout {
+    /// Layout for `channels` channels declared with `mask`. Undefined bits and
+    /// bits beyond the channel count are dropped, so the mask never names
+    /// more speakers than there are channels.
+    pub fn new(channels: u16, mask: u32) -> Self {
+        let mut kept = 0u32;
+        let mut remaining = channels;
+        let mut bits = mask & SPEAKER_ALL;
+        while bits != 0 && remaining > 0 {
+            let bit = bits & bits.wrapping_neg();
+            kept |= bit;
+            bits &= !bit;
+            remaining -= 1;
+        }
+        Self {
+            channels,
+            mask: kept,
+        }
+    }
+
+    /// The layout assumed for files that do not declare one: the
+    /// `KSAUDIO_SPEAKER_*` defaults Windows and most DAWs use.
+    pub fn default_for(channels: u16) -> Self {
+        let mask = match channels {
+            1 => SPEAKER_FRONT_CENTER,
+            2 => 0x3,
+            3 => 0x7,
+            4 => 0x33,
+            5 => 0x37,
+            6 => 0x3F,
+            7 => 0x70F,
+            8 => 0x63F,
+            _ => 0,
+        };
+        Self { channels, mask }
+    }
+
+    /// Layout built from one optional speaker bit per channel. Falls back to
+    /// [`ChannelLayout::default_for`] unless every channel has a distinct bit
+    /// in ascending order, which is the only order a mask can express.
+    pub fn from_speakers(speakers: &[Option<u32>]) -> Self {
+        let channels = speakers.len().min(u16::MAX as usize) as u16;
+        let mut mask = 0u32;
+        let mut last = 0u32;
+        for speaker in speakers {
+            match speaker {
+                Some(bit) if *bit > last && bit.is_power_of_two() && bit & SPEAKER_ALL != 0 => {
+                    mask |= bit;
+                    last = *bit;
+                }
+                _ => return Self::default_for(channels),
+            }
+        }
+        Self { channels, mask }
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// `channels << 32 | mask`, 0 for `None`, so an optional layout can be
+    /// handed to another thread through an `AtomicU64`.
+    pub fn pack(layout: Option<Self>) -> u64 {
+        layout
+            .filter(|layout| layout.channels > 0)
+            .map_or(0, |layout| {
+                (u64::from(layout.channels) << 32) | u64::from(layout.mask)
+            })
+    }
+
+    /// Inverse of [`ChannelLayout::pack`].
+    pub fn unpack(bits: u64) -> Option<Self> {
+        (bits != 0).then(|| Self::new((bits >> 32) as u16, bits as u32))
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default_for(self.channels)
+    }
+
+    /// Speaker bit of `channel`, if the mask assigns one.
+    pub fn speaker_bit(&self, channel: usize) -> Option<u32> {
+        if channel >= self.channels as usize {
+            return None;
+        }
+        let mut bits = self.mask;
+        for _ in 0..channel {
+            bits &= bits.wrapping_sub(1);
+        }
+        (bits != 0).then_some(bits & bits.wrapping_neg())
+    }
+
+    /// Short lane label such as `L`, `C`, `LFE` or `Ls`. Back surrounds read
+    /// `Ls`/`Rs` when the layout has no side pair (5.1 back, quad), mono
+    /// reads `M`, and unpositioned channels read `Ch N`.
+    pub fn label(&self, channel: usize) -> String {
+        if self.channels == 1 && self.mask == SPEAKER_FRONT_CENTER && channel == 0 {
+            return "M".to_string();
+        }
+        let Some(bit) = self.speaker_bit(channel) else {
+            return format!("Ch {}", channel + 1);
+        };
+        let has_sides = self.mask & (SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT) != 0;
+        match bit {
+            SPEAKER_BACK_LEFT if !has_sides => "Ls".to_string(),
+            SPEAKER_BACK_RIGHT if !has_sides => "Rs".to_string(),
+            _ => SPEAKER_LABELS[bit.trailing_zeros() as usize].to_string(),
+        }
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        (0..self.channels as usize)
+            .map(|ch| self.label(ch))
+            .collect()
+    }
+
+    /// BS.1770-4 power weight `G` of `channel`: 0 for LFE, 1.41 for the
+    /// surrounds at 60-120 degrees (the side pair, or the back pair when the
+    /// layout has no sides), 1.0 for everything else including unpositioned
+    /// channels.
+    pub fn bs1770_weight(&self, channel: usize) -> f32 {
+        let has_sides = self.mask & (SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT) != 0;
+        match self.speaker_bit(channel) {
+            Some(SPEAKER_LOW_FREQUENCY) => 0.0,
+            Some(SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT) => 1.41,
+            Some(SPEAKER_BACK_LEFT | SPEAKER_BACK_RIGHT) if !has_sides => 1.41,
+            _ => 1.0,
+        }
+    }
+
+    pub fn bs1770_weights(&self) -> Vec<f32> {
+        (0..self.channels as usize)
+            .map(|ch| self.bs1770_weight(ch))
+            .collect()
+    }
+
+    /// Common name of the layout, if it is a well-known one.
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match (self.channels, self.mask) {
+            (1, 0x4) => "Mono",
+            (2, 0x3) => "Stereo",
+            (3, 0x7) => "3.0",
+            (3, 0xB) => "2.1",
+            (4, 0x33) => "Quad",
+            (4, 0x603) => "Quad (side)",
+            (4, 0x107) => "4.0",
+            (5, 0x37) => "5.0",
+            (5, 0x607) => "5.0 (side)",
+            (6, 0x3F) => "5.1",
+            (6, 0x60F) => "5.1 (side)",
+            (7, 0x70F) => "6.1",
+            (8, 0x63F) => "7.1",
+            (8, 0xFF) => "7.1 (wide)",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Name plus channel labels, e.g. `5.1 (L R C LFE Ls Rs)`.
+    pub fn describe(&self) -> String {
+        let name = self
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}ch", self.channels));
+        format!("{name} ({})", self.labels().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_follow_mask_bits_in_ascending_order() {
+        let side = ChannelLayout::new(6, 0x60F);
+        assert_eq!(side.labels(), ["L", "R", "C", "LFE", "Ls", "Rs"]);
+        let back = ChannelLayout::default_for(6);
+        assert_eq!(back.labels(), ["L", "R", "C", "LFE", "Ls", "Rs"]);
+        assert_eq!(back.name(), Some("5.1"));
+        assert_eq!(side.name(), Some("5.1 (side)"));
+        let seven = ChannelLayout::default_for(8);
+        assert_eq!(
+            seven.labels(),
+            ["L", "R", "C", "LFE", "Lrs", "Rrs", "Ls", "Rs"]
+        );
+        assert_eq!(ChannelLayout::default_for(1).labels(), ["M"]);
+        assert_eq!(ChannelLayout::default_for(2).labels(), ["L", "R"]);
+    }
+
+    #[test]
+    fn mask_is_trimmed_to_channel_count_and_extra_channels_are_indexed() {
+        let trimmed = ChannelLayout::new(2, 0x3F);
+        assert_eq!(trimmed.mask(), 0x3);
+        let short = ChannelLayout::new(4, 0x3);
+        assert_eq!(short.labels(), ["L", "R", "Ch 3", "Ch 4"]);
+        assert_eq!(ChannelLayout::new(3, 0).labels(), ["Ch 1", "Ch 2", "Ch 3"]);
+        assert_eq!(ChannelLayout::default_for(12).mask(), 0);
+    }
+
+    #[test]
+    fn bs1770_weights_follow_speaker_positions() {
+        assert_eq!(
+            ChannelLayout::default_for(6).bs1770_weights(),
+            [1.0, 1.0, 1.0, 0.0, 1.41, 1.41]
+        );
+        assert_eq!(
+            ChannelLayout::new(6, 0x60F).bs1770_weights(),
+            [1.0, 1.0, 1.0, 0.0, 1.41, 1.41]
+        );
+        // 7.1: the rear pair sits behind the 60-120 degree zone.
+        assert_eq!(
+            ChannelLayout::default_for(8).bs1770_weights(),
+            [1.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.41, 1.41]
+        );
+        assert_eq!(ChannelLayout::new(3, 0).bs1770_weights(), [1.0; 3]);
+    }
+
+    #[test]
+    fn pack_roundtrips_and_zero_means_none() {
+        let side = ChannelLayout::new(6, 0x60F);
+        assert_eq!(
+            ChannelLayout::unpack(ChannelLayout::pack(Some(side))),
+            Some(side)
+        );
+        assert_eq!(ChannelLayout::pack(None), 0);
+        assert_eq!(ChannelLayout::pack(Some(ChannelLayout::new(0, 0))), 0);
+        assert_eq!(ChannelLayout::unpack(0), None);
+    }
+
+    #[test]
+    fn from_speakers_requires_distinct_ascending_bits() {
+        let kept = ChannelLayout::from_speakers(&[
+            Some(SPEAKER_FRONT_LEFT),
+            Some(SPEAKER_FRONT_RIGHT),
+            Some(SPEAKER_SIDE_LEFT),
+            Some(SPEAKER_SIDE_RIGHT),
+        ]);
+        assert_eq!(kept.mask(), 0x603);
+        let swapped =
+            ChannelLayout::from_speakers(&[Some(SPEAKER_FRONT_RIGHT), Some(SPEAKER_FRONT_LEFT)]);
+        assert_eq!(swapped, ChannelLayout::default_for(2));
+        let mixed = ChannelLayout::from_speakers(&[None]);
+        assert_eq!(mixed, ChannelLayout::default_for(1));
+    }
+}
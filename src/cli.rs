@@ -85,11 +85,14 @@ const EXPORT_FILE_AFTER_HELP: &str = r#"Examples:
   neowaves --cli export file --input .\demo.wav --output .\demo_copy.wav
   neowaves --cli export file --input .\demo.wav --output .\demo_gain.wav --gain-db -3.0
   neowaves --cli export file --session .\work.nwsess --overwrite
-  neowaves --cli export file --session .\work.nwsess --output .\music_loop.mp3 --format mp3"#;
+  neowaves --cli export file --session .\work.nwsess --output .\music_loop.mp3 --format mp3
+  neowaves --cli export file --input .\ambience_51.wav --output .\ambience_51.m4a
+  neowaves --cli export file --input .\ambience_51.wav --output .\ambience.mp3 --downmix first2"#;
 
 const EXPORT_VERIFY_LOOP_TAGS_AFTER_HELP: &str = r#"Examples:
   neowaves --cli export verify-loop-tags --input .\music_loop.mp3
-  neowaves --cli export verify-loop-tags --input .\battle_loop.wav"#;
+  neowaves --cli export verify-loop-tags --input .\battle_loop.wav
+  neowaves --cli export verify-loop-tags --input .\music_loop.m4a --reference .\music_loop.wav"#;
 
 const BATCH_LOUDNESS_PLAN_AFTER_HELP: &str = r#"Examples:
   neowaves --cli batch loudness plan --session .\work.nwsess --query _BGM --target-lufs -24
@@ -102,11 +105,13 @@ const BATCH_LOUDNESS_APPLY_AFTER_HELP: &str = r#"Examples:
 const BATCH_INSPECT_AFTER_HELP: &str = r#"Examples:
   neowaves --cli batch inspect --session .\work.nwsess --query _SE
   neowaves --cli batch inspect --session .\work.nwsess --target-lufs -16 --lufs-tolerance 2 --report .\qa.csv
-  neowaves --cli batch inspect --session .\work.nwsess --no-silence --require-loop --report .\loops.json"#;
+  neowaves --cli batch inspect --session .\work.nwsess --no-silence --require-loop --report .\loops.json
+  neowaves --cli batch inspect --session .\work.nwsess --max-lra 15 --max-short-term -18 --loudness-report .\loudness.csv"#;
 
 const BATCH_EXPORT_AFTER_HELP: &str = r#"Examples:
   neowaves --cli batch export --session .\work.nwsess --query _BGM --overwrite
-  neowaves --cli batch export --session .\work.nwsess --query-id <id> --output-dir .\out --report .\export.md"#;
+  neowaves --cli batch export --session .\work.nwsess --query-id <id> --output-dir .\out --report .\export.md
+  neowaves --cli batch export --session .\work.nwsess --query _AMB --output-dir .\out --downmix itu"#;
 
 const EFFECT_GRAPH_AFTER_HELP: &str = r#"Examples:
   neowaves --cli effect-graph list
@@ -437,6 +442,13 @@ pub enum ItemCommand {
 pub struct ItemInspectArgs {
     #[arg(long, value_name = "AUDIO")]
     pub input: PathBuf,
+    /// Decode and add a `loudness` report (I / S max / M max / LRA / TP and
+    /// the short-term histogram).
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub loudness: bool,
+    /// Also write the loudness report (.json / .csv / .txt); implies --loudness.
+    #[arg(long = "loudness-report", value_name = "PATH")]
+    pub loudness_report: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -451,6 +463,14 @@ pub enum ItemMetadataCommand {
     Summary(ItemMetadataSummaryArgs),
     #[command(subcommand)]
     Payload(ItemMetadataPayloadCommand),
+    Set(ItemMetadataSetArgs),
+    Repair(ItemMetadataRepairArgs),
+    #[command(subcommand)]
+    Chunk(ItemMetadataChunkCommand),
+    Diff(ItemMetadataDiffArgs),
+    #[command(subcommand)]
+    Template(ItemMetadataTemplateCommand),
+    Cart(ItemMetadataCartArgs),
 }
 
 #[derive(Debug, Args)]
@@ -469,6 +489,237 @@ pub struct ItemMetadataSummaryArgs {
     pub include_raw: bool,
 }
 
+/// Edit text tags (ID3v2 / MP4 / Vorbis comments) in place. Keys are
+/// `title`, `artist`, `album`, `genre`, `comment`, `isrc` or a custom key
+/// (`custom:NAME` forces a custom key that collides with a field name).
+#[derive(Debug, Args)]
+pub struct ItemMetadataSetArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+    #[arg(long = "remove", value_name = "KEY")]
+    pub remove: Vec<String>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+/// Rebuild a broken RIFF / RF64 / BW64 WAVE or AIFF / AIFC container into
+/// OUTPUT; the source file is never modified.
+#[derive(Debug, Args)]
+pub struct ItemMetadataRepairArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+    #[arg(long, value_name = "FILE", required_unless_present = "dry_run")]
+    pub output: Option<PathBuf>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub overwrite: bool,
+    /// Drop chunks the inspector does not recognize.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub drop_unknown: bool,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+/// Compare the metadata of two files: normalized fields, the node tree and
+/// chunk payload hashes. Without RIGHT, LEFT is compared against its `.bak`
+/// (the `.bak` is the left side).
+#[derive(Debug, Args)]
+pub struct ItemMetadataDiffArgs {
+    #[arg(value_name = "LEFT")]
+    pub left: PathBuf,
+    #[arg(value_name = "RIGHT")]
+    pub right: Option<PathBuf>,
+    /// Only list added / removed / changed / moved rows.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub only_changes: bool,
+    /// Also hash audio payloads such as `data` and `SSND`.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub hash_audio: bool,
+    /// Exit with an error when the files differ (for CI checks).
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub fail_on_diff: bool,
+}
+
+/// Metadata templates: `target = expression` fields rendered per file from
+/// tokens such as `{name}`, `{mtime:%Y-%m-%d}`, `{ucs.fxname}` or
+/// `{external.Scene}` and written into bext, iXML or text tags.
+#[derive(Debug, Subcommand)]
+pub enum ItemMetadataTemplateCommand {
+    List(ItemMetadataTemplateListArgs),
+    Apply(ItemMetadataTemplateApplyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataTemplateListArgs {
+    /// Template library JSON (default: the app's settings folder).
+    #[arg(long, value_name = "JSON")]
+    pub library: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataTemplateApplyArgs {
+    #[arg(long, value_name = "AUDIO", conflicts_with = "session")]
+    pub input: Vec<PathBuf>,
+    /// Apply to the session list; external data and transcripts of the
+    /// session feed `{external.*}` and `{transcript}`.
+    #[arg(long, value_name = "SESSION", required_unless_present = "input")]
+    pub session: Option<PathBuf>,
+    #[command(flatten)]
+    pub filter: CliQueryFilterArgs,
+    /// Library template to start from.
+    #[arg(long, value_name = "NAME")]
+    pub template: Option<String>,
+    #[arg(long, value_name = "JSON")]
+    pub library: Option<PathBuf>,
+    /// Add a field or replace the template's field for TARGET.
+    #[arg(long = "set", value_name = "TARGET=EXPR")]
+    pub set: Vec<String>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+/// Read or write the BWF `cart` chunk (AES46) of a WAV. Without edits the
+/// current chunk is printed. Keys are `title`, `artist`, `cut_id`,
+/// `client_id`, `category`, `classification`, `out_cue`, `start_date`,
+/// `start_time`, `end_date`, `end_time`, `producer_app_id`,
+/// `producer_app_version`, `user_def`, `url` and `tag_text`.
+#[derive(Debug, Args)]
+pub struct ItemMetadataCartArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+    #[arg(long = "set", value_name = "KEY=VALUE", conflicts_with = "remove")]
+    pub set: Vec<String>,
+    /// Replace the post-timers (up to 8), e.g. `SEGs=96000`.
+    #[arg(
+        long = "timer",
+        value_name = "USAGE=SAMPLE",
+        conflicts_with_all = ["remove", "timers_from_markers"]
+    )]
+    pub timers: Vec<String>,
+    /// Post-timers from markers labelled with a usage ID (`SEGs`, `cart:XXXX`).
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "remove")]
+    pub timers_from_markers: bool,
+    /// Replace the usage-ID markers with the written post-timers.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "remove")]
+    pub markers_from_timers: bool,
+    /// Drop the cart chunk.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub remove: bool,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+/// Raw top-level chunk edits on RIFF / RF64 / BW64 WAVE and AIFF / AIFC
+/// files. `ds64` is not listed and is regenerated on write.
+#[derive(Debug, Subcommand)]
+pub enum ItemMetadataChunkCommand {
+    List(ItemMetadataChunkListArgs),
+    Remove(ItemMetadataChunkRemoveArgs),
+    Insert(ItemMetadataChunkInsertArgs),
+    Move(ItemMetadataChunkMoveArgs),
+    Export(ItemMetadataChunkExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataChunkListArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+}
+
+/// Selects one chunk by list index or by id (the Nth occurrence).
+#[derive(Debug, Args, Clone)]
+pub struct ItemMetadataChunkSelectorArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+    #[arg(long, conflicts_with = "id", required_unless_present = "id")]
+    pub index: Option<usize>,
+    #[arg(long, value_name = "FOURCC")]
+    pub id: Option<String>,
+    #[arg(long, default_value_t = 0, requires = "id")]
+    pub occurrence: usize,
+}
+
+/// Write the result to `--output` or over the input with `--in-place`
+/// (the previous file is kept as `<name>.bak`).
+#[derive(Debug, Args, Clone)]
+pub struct ItemMetadataChunkWriteArgs {
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "in_place",
+        required_unless_present = "in_place"
+    )]
+    pub output: Option<PathBuf>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub in_place: bool,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataChunkRemoveArgs {
+    #[command(flatten)]
+    pub selector: ItemMetadataChunkSelectorArgs,
+    #[command(flatten)]
+    pub write: ItemMetadataChunkWriteArgs,
+}
+
+/// Insert a chunk from `--file` or `--hex`; appended unless `--at` or
+/// `--before` is given.
+#[derive(Debug, Args)]
+pub struct ItemMetadataChunkInsertArgs {
+    #[arg(long, value_name = "AUDIO")]
+    pub input: PathBuf,
+    #[arg(long, value_name = "FOURCC")]
+    pub id: String,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "hex",
+        required_unless_present = "hex"
+    )]
+    pub file: Option<PathBuf>,
+    #[arg(long, value_name = "HEX")]
+    pub hex: Option<String>,
+    #[arg(long, value_name = "INDEX", conflicts_with = "before")]
+    pub at: Option<usize>,
+    /// Insert before the first chunk with this id.
+    #[arg(long, value_name = "FOURCC")]
+    pub before: Option<String>,
+    #[command(flatten)]
+    pub write: ItemMetadataChunkWriteArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataChunkMoveArgs {
+    #[command(flatten)]
+    pub selector: ItemMetadataChunkSelectorArgs,
+    /// Final list index of the moved chunk.
+    #[arg(
+        long,
+        value_name = "INDEX",
+        conflicts_with = "before",
+        required_unless_present = "before"
+    )]
+    pub to: Option<usize>,
+    /// Move in front of the first chunk with this id.
+    #[arg(long, value_name = "FOURCC")]
+    pub before: Option<String>,
+    #[command(flatten)]
+    pub write: ItemMetadataChunkWriteArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ItemMetadataChunkExportArgs {
+    #[command(flatten)]
+    pub selector: ItemMetadataChunkSelectorArgs,
+    #[arg(long, value_name = "FILE")]
+    pub output: PathBuf,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ItemMetadataPayloadCommand {
     Read(ItemMetadataPayloadReadArgs),
@@ -529,12 +780,32 @@ pub struct ItemMetadataPayloadExtractArgs {
     pub overwrite: bool,
 }
 
+/// Without `--set` / `--strip` the embedded artwork is extracted to PNG.
+/// `--set` replaces every embedded picture with IMAGE as the front cover
+/// (MP3 / WAV / M4A / FLAC).
 #[derive(Debug, Args)]
 pub struct ItemArtworkArgs {
     #[arg(long, value_name = "AUDIO")]
     pub input: PathBuf,
-    #[arg(long, value_name = "PNG")]
+    #[arg(long, value_name = "PNG", conflicts_with_all = ["set", "strip"])]
     pub output: Option<PathBuf>,
+    #[arg(long, value_name = "IMAGE", conflicts_with = "strip")]
+    pub set: Option<PathBuf>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub strip: bool,
+    /// Downscale so neither edge exceeds PX.
+    #[arg(long, value_name = "PX", requires = "set")]
+    pub max_size: Option<u32>,
+    /// keep | jpeg | png
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        default_value = "keep",
+        requires = "set"
+    )]
+    pub encode: String,
+    #[arg(long, value_name = "1-100", default_value_t = 90, requires = "set")]
+    pub quality: u8,
 }
 
 #[derive(Debug, Subcommand)]
@@ -1687,6 +1958,15 @@ pub struct ExportFileArgs {
     pub loop_end_sample: Option<usize>,
     #[arg(long = "marker")]
     pub markers: Vec<String>,
+    /// Fold-down for layouts the target codec can't carry (default: itu).
+    #[arg(long, value_enum)]
+    pub downmix: Option<CliDownmix>,
+    /// Dither / noise shaping for integer PCM (default: the saved export preference).
+    #[arg(long, value_enum)]
+    pub dither: Option<CliDither>,
+    /// Write runs of digital silence as exact zeros instead of dither noise.
+    #[arg(long = "dither-auto-blank")]
+    pub dither_auto_blank: bool,
 }
 
 #[derive(Debug, Args)]
@@ -1694,6 +1974,8 @@ pub struct ExportFileArgs {
 pub struct ExportVerifyLoopTagsArgs {
     #[arg(long, value_name = "AUDIO")]
     pub input: PathBuf,
+    #[arg(long, value_name = "AUDIO")]
+    pub reference: Option<PathBuf>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -1704,6 +1986,8 @@ pub enum BatchCommand {
     Inspect(BatchInspectArgs),
     #[command(name = "engine-export")]
     EngineExport(BatchEngineExportArgs),
+    #[command(name = "catalog-export")]
+    CatalogExport(BatchCatalogExportArgs),
 }
 
 #[derive(Debug, Args)]
@@ -1719,6 +2003,25 @@ pub struct BatchEngineExportArgs {
     pub output: PathBuf,
 }
 
+#[derive(Debug, Args)]
+pub struct BatchCatalogExportArgs {
+    #[arg(long, value_name = "SESSION")]
+    pub session: PathBuf,
+    #[command(flatten)]
+    pub filter: CliQueryFilterArgs,
+    #[arg(long, value_name = "PATH")]
+    pub output: PathBuf,
+    /// Catalog format: sqlite | csv. Defaults from the output extension.
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<String>,
+    /// Leave loudness and peaks empty instead of decoding every file.
+    #[arg(long, default_value_t = false)]
+    pub no_measure: bool,
+    /// Replace an existing output file.
+    #[arg(long, default_value_t = false)]
+    pub overwrite: bool,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum BatchLoudnessCommand {
     Plan(BatchLoudnessPlanArgs),
@@ -1770,6 +2073,16 @@ pub struct BatchInspectArgs {
     pub max_leading_silence_ms: f32,
     #[arg(long = "max-trailing-silence-ms", default_value_t = 1000.0)]
     pub max_trailing_silence_ms: f32,
+    /// Flag a loudness range above LU (enables the LRA check).
+    #[arg(long = "max-lra", value_name = "LU")]
+    pub max_lra: Option<f32>,
+    /// Flag a max short-term loudness above LUFS (enables the check).
+    #[arg(
+        long = "max-short-term",
+        value_name = "LUFS",
+        allow_hyphen_values = true
+    )]
+    pub max_short_term: Option<f32>,
     #[arg(long = "no-loudness", action = ArgAction::SetTrue)]
     pub no_loudness: bool,
     #[arg(long = "no-true-peak", action = ArgAction::SetTrue)]
@@ -1785,6 +2098,9 @@ pub struct BatchInspectArgs {
     pub naming_pattern: Option<String>,
     #[arg(long, value_name = "PATH")]
     pub report: Option<PathBuf>,
+    /// Per-file loudness report with the short-term histogram (.json / .csv / .txt).
+    #[arg(long = "loudness-report", value_name = "PATH")]
+    pub loudness_report: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -1800,6 +2116,15 @@ pub struct BatchExportArgs {
     pub output_dir: Option<PathBuf>,
     #[arg(long, value_name = "PATH")]
     pub report: Option<PathBuf>,
+    /// Fold-down for layouts the target codec can't carry (default: itu).
+    #[arg(long, value_enum)]
+    pub downmix: Option<CliDownmix>,
+    /// Dither / noise shaping for integer PCM (default: the saved export preference).
+    #[arg(long, value_enum)]
+    pub dither: Option<CliDither>,
+    /// Write runs of digital silence as exact zeros instead of dither noise.
+    #[arg(long = "dither-auto-blank")]
+    pub dither_auto_blank: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -2002,6 +2327,24 @@ pub enum CliCursorSnap {
     ZeroCross,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliDownmix {
+    Itu,
+    #[value(name = "first2")]
+    FirstTwo,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliDither {
+    Off,
+    Tpdf,
+    #[value(name = "tpdf-hp")]
+    TpdfHighpass,
+    #[value(name = "tpdf-ns")]
+    TpdfNoiseShaped,
+    Shaped,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliEffectGraphNodeKind {
     Input,
@@ -2060,6 +2403,7 @@ pub enum CliViewMode {
     Tempogram,
     Chromagram,
     World,
+    Loudness,
 }
 
 impl From<CliViewMode> for app::ViewMode {
@@ -2072,6 +2416,7 @@ impl From<CliViewMode> for app::ViewMode {
             CliViewMode::Tempogram => app::ViewMode::Tempogram,
             CliViewMode::Chromagram => app::ViewMode::Chromagram,
             CliViewMode::World => app::ViewMode::World,
+            CliViewMode::Loudness => app::ViewMode::Loudness,
         }
     }
 }
@@ -2398,6 +2743,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_export_downmix_choice() {
+        let cli = CliRoot::try_parse_from([
+            "neowaves",
+            "batch",
+            "export",
+            "--session",
+            "work.nwsess",
+            "--output-dir",
+            "out",
+            "--downmix",
+            "first2",
+        ])
+        .expect("parse batch export downmix");
+        match cli.command {
+            CliCommand::Batch(BatchCommand::Export(args)) => {
+                assert!(matches!(args.downmix, Some(CliDownmix::FirstTwo)));
+            }
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[test]
+    fn parses_export_dither_choice() {
+        let cli = CliRoot::try_parse_from([
+            "neowaves",
+            "batch",
+            "export",
+            "--session",
+            "work.nwsess",
+            "--output-dir",
+            "out",
+            "--dither",
+            "shaped",
+            "--dither-auto-blank",
+        ])
+        .expect("parse batch export dither");
+        match cli.command {
+            CliCommand::Batch(BatchCommand::Export(args)) => {
+                assert!(matches!(args.dither, Some(CliDither::Shaped)));
+                assert!(args.dither_auto_blank);
+            }
+            _ => panic!("unexpected command"),
+        }
+    }
+
     #[test]
     fn parses_batch_loudness_plan() {
         let cli = CliRoot::try_parse_from([
@@ -140,7 +140,7 @@ fn encode_flac_file(path: &Path, meta: &FlacMeta) -> Result<()> {
     }
 }
 
-fn parse_vorbis_comments(payload: &[u8]) -> Vec<(String, String)> {
+pub(crate) fn parse_vorbis_comments(payload: &[u8]) -> Vec<(String, String)> {
     let mut out = Vec::new();
     let read_u32 = |data: &[u8], pos: usize| -> Option<u32> {
         data.get(pos..pos + 4)
@@ -173,7 +173,7 @@ fn parse_vorbis_comments(payload: &[u8]) -> Vec<(String, String)> {
     out
 }
 
-fn build_vorbis_comment_payload(vendor: &str, comments: &[(String, String)]) -> Vec<u8> {
+pub(crate) fn build_vorbis_comment_payload(vendor: &str, comments: &[(String, String)]) -> Vec<u8> {
     let mut out = Vec::new();
     out.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
     out.extend_from_slice(vendor.as_bytes());
@@ -186,7 +186,7 @@ fn build_vorbis_comment_payload(vendor: &str, comments: &[(String, String)]) ->
     out
 }
 
-fn vendor_string(payload: &[u8]) -> String {
+pub(crate) fn vendor_string(payload: &[u8]) -> String {
     let len = payload
         .get(0..4)
         .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
@@ -298,6 +298,53 @@ pub fn read_flac_artwork(path: &Path) -> Option<Vec<u8>> {
     payload.get(pos..pos + data_len).map(|b| b.to_vec())
 }
 
+/// Replace every PICTURE block with one front cover (`Some`) or remove them
+/// all (`None`). Other blocks keep their order.
+pub fn write_flac_artwork(
+    path: &Path,
+    artwork: Option<&crate::artwork::PreparedArtwork>,
+) -> Result<()> {
+    let mut file = parse_flac(path)?;
+    file.blocks.retain(|b| b.block_type != BLOCK_PICTURE);
+    if let Some(artwork) = artwork {
+        // Right after the comment block, or after STREAMINFO without one.
+        let insert_at = file
+            .blocks
+            .iter()
+            .position(|b| b.block_type == BLOCK_VORBIS_COMMENT)
+            .or_else(|| {
+                file.blocks
+                    .iter()
+                    .position(|b| b.block_type == BLOCK_STREAMINFO)
+            })
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        file.blocks.insert(
+            insert_at,
+            FlacBlock {
+                block_type: BLOCK_PICTURE,
+                payload: build_picture_payload(artwork),
+            },
+        );
+    }
+    encode_flac_file(path, &file)
+}
+
+fn build_picture_payload(artwork: &crate::artwork::PreparedArtwork) -> Vec<u8> {
+    let mut out = Vec::with_capacity(artwork.data.len() + 64);
+    out.extend_from_slice(&3u32.to_be_bytes()); // front cover
+    out.extend_from_slice(&(artwork.mime.len() as u32).to_be_bytes());
+    out.extend_from_slice(artwork.mime.as_bytes());
+    out.extend_from_slice(&0u32.to_be_bytes()); // empty description
+    out.extend_from_slice(&artwork.width.to_be_bytes());
+    out.extend_from_slice(&artwork.height.to_be_bytes());
+    out.extend_from_slice(&artwork.depth.to_be_bytes());
+    out.extend_from_slice(&0u32.to_be_bytes()); // not indexed
+    out.extend_from_slice(&(artwork.data.len() as u32).to_be_bytes());
+    out.extend_from_slice(&artwork.data);
+    out
+}
+
 /// Carry Vorbis comments and PICTURE blocks from `src` into `dst` (both FLAC).
 /// STREAMINFO / SEEKTABLE / CUESHEET are not copied: they describe the source
 /// audio stream and would be invalid for freshly encoded audio.
@@ -0,0 +1,1159 @@
+//! Exact-stream FLAC playback source.
+//!
+//! FLAC has no fixed bytes-per-frame, so random access goes through seek
+//! points: the file's own SEEKTABLE when present, otherwise a frame index
+//! built once in the background and cached per file. A worker thread decodes
+//! fixed-size chunks around the playhead into a small ring; the audio
+//! callback only copies samples out of already-decoded chunks and renders
+//! silence for a chunk that is not ready yet. No decoding ever happens on
+//! the callback thread.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwapOption;
+use symphonia::core::audio::{Channels, SampleBuffer};
+use symphonia::core::codecs::{CodecParameters, Decoder, DecoderOptions, CODEC_TYPE_FLAC};
+use symphonia::core::formats::Packet;
+
+const BLOCK_STREAMINFO: u8 = 0;
+const BLOCK_SEEKTABLE: u8 = 3;
+const STREAMINFO_LEN: usize = 34;
+const SEEKPOINT_LEN: usize = 18;
+const SEEKPOINT_PLACEHOLDER: u64 = u64::MAX;
+
+/// Longest legal frame header: sync/flags (4) + coded number (7) + block
+/// size (2) + sample rate (2) + CRC-8 (1).
+const MAX_FRAME_HEADER_LEN: usize = 16;
+const READ_WINDOW: usize = 256 * 1024;
+/// Give up looking for the next frame boundary after this many bytes; real
+/// frames are orders of magnitude smaller.
+const MAX_FRAME_SCAN: usize = 16 * 1024 * 1024;
+
+/// Frames per decoded chunk handed to the audio callback.
+pub const CHUNK_FRAMES: usize = 16_384;
+const CHUNK_SLOTS: usize = 32;
+/// Chunks kept decoded ahead of the playhead (~2.7 s at 48 kHz).
+const CHUNKS_AHEAD: usize = 8;
+const WORKER_IDLE: Duration = Duration::from_millis(4);
+/// Chunks the audio thread can hand back between two worker passes.
+const RETIRE_SLOTS: usize = 16;
+/// Spacing of the built frame index, in seconds.
+const INDEX_SPACING_SEC: f64 = 0.25;
+const INDEX_CACHE_CAP: usize = 64;
+
+/// The STREAMINFO fields streaming needs, plus the raw block for the decoder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlacStreamInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub total_frames: u64,
+    pub min_block_size: u16,
+    pub max_block_size: u16,
+    pub max_frame_size: u32,
+    raw: [u8; STREAMINFO_LEN],
+}
+
+impl FlacStreamInfo {
+    fn parse(payload: &[u8]) -> Option<Self> {
+        let raw: [u8; STREAMINFO_LEN] = payload.get(..STREAMINFO_LEN)?.try_into().ok()?;
+        let packed = u64::from_be_bytes(raw[10..18].try_into().ok()?);
+        Some(Self {
+            sample_rate: (packed >> 44) as u32,
+            channels: ((packed >> 41) & 0x07) as u16 + 1,
+            bits_per_sample: ((packed >> 36) & 0x1F) as u16 + 1,
+            total_frames: packed & 0x0F_FFFF_FFFF,
+            min_block_size: u16::from_be_bytes([raw[0], raw[1]]),
+            max_block_size: u16::from_be_bytes([raw[2], raw[3]]),
+            max_frame_size: u32::from_be_bytes([0, raw[7], raw[8], raw[9]]),
+            raw,
+        })
+    }
+}
+
+/// First sample of a frame and the absolute byte offset of its header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlacSeekPoint {
+    pub sample: u64,
+    pub offset: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FlacStreamLayout {
+    pub info: FlacStreamInfo,
+    /// Byte offset of the first audio frame.
+    pub audio_offset: u64,
+    pub file_len: u64,
+    /// SEEKTABLE points with absolute offsets, sorted by sample. Empty when
+    /// the file carries no (usable) SEEKTABLE.
+    pub seek_table: Vec<FlacSeekPoint>,
+}
+
+impl FlacStreamLayout {
+    fn start_point(&self) -> FlacSeekPoint {
+        FlacSeekPoint {
+            sample: 0,
+            offset: self.audio_offset,
+        }
+    }
+}
+
+/// Read the metadata section of `path`, skipping every block except
+/// STREAMINFO and SEEKTABLE. `None` when the file is not FLAC or cannot be
+/// streamed (unknown length or sample rate).
+pub fn read_flac_stream_layout(path: &Path) -> Result<Option<FlacStreamLayout>> {
+    let file = File::open(path).with_context(|| format!("open flac: {}", path.display()))?;
+    let file_len = file
+        .metadata()
+        .with_context(|| format!("stat flac: {}", path.display()))?
+        .len();
+    let mut reader = BufReader::new(file);
+    let mut magic = [0u8; 4];
+    if reader.read_exact(&mut magic).is_err() || &magic != b"fLaC" {
+        return Ok(None);
+    }
+    let mut info = None;
+    let mut seek_table = Vec::new();
+    let mut audio_offset = 4u64;
+    loop {
+        let mut header = [0u8; 4];
+        reader
+            .read_exact(&mut header)
+            .with_context(|| format!("truncated FLAC metadata: {}", path.display()))?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let size = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        audio_offset += 4 + size as u64;
+        match block_type {
+            BLOCK_STREAMINFO | BLOCK_SEEKTABLE => {
+                let mut payload = vec![0u8; size];
+                reader.read_exact(&mut payload).with_context(|| {
+                    format!("truncated FLAC metadata block: {}", path.display())
+                })?;
+                if block_type == BLOCK_STREAMINFO {
+                    info = FlacStreamInfo::parse(&payload);
+                } else {
+                    seek_table = parse_seek_table(&payload);
+                }
+            }
+            _ => {
+                reader
+                    .seek_relative(size as i64)
+                    .with_context(|| format!("skip FLAC metadata block: {}", path.display()))?;
+            }
+        }
+        if is_last {
+            break;
+        }
+    }
+    let Some(info) = info else {
+        return Ok(None);
+    };
+    if info.sample_rate == 0 || info.total_frames == 0 || audio_offset >= file_len {
+        return Ok(None);
+    }
+    for point in &mut seek_table {
+        point.offset = point.offset.saturating_add(audio_offset);
+    }
+    seek_table.retain(|p| p.sample < info.total_frames && p.offset < file_len);
+    Ok(Some(FlacStreamLayout {
+        info,
+        audio_offset,
+        file_len,
+        seek_table,
+    }))
+}
+
+/// SEEKTABLE offsets are relative to the first frame; placeholder points are
+/// dropped.
+fn parse_seek_table(payload: &[u8]) -> Vec<FlacSeekPoint> {
+    let mut points: Vec<FlacSeekPoint> = payload
+        .chunks_exact(SEEKPOINT_LEN)
+        .filter_map(|entry| {
+            let sample = u64::from_be_bytes(entry[0..8].try_into().ok()?);
+            let offset = u64::from_be_bytes(entry[8..16].try_into().ok()?);
+            (sample != SEEKPOINT_PLACEHOLDER).then_some(FlacSeekPoint { sample, offset })
+        })
+        .collect();
+    points.sort_by_key(|p| p.sample);
+    points.dedup_by_key(|p| p.sample);
+    points
+}
+
+/// The last point at or before `target`.
+fn best_seek_point(points: &[FlacSeekPoint], target: u64) -> Option<FlacSeekPoint> {
+    let idx = points.partition_point(|p| p.sample <= target);
+    idx.checked_sub(1).map(|i| points[i])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FrameHeader {
+    first_sample: u64,
+    block_size: u32,
+}
+
+impl FrameHeader {
+    fn end_sample(&self) -> u64 {
+        self.first_sample + u64::from(self.block_size)
+    }
+}
+
+/// Parse and validate a frame header at the start of `bytes`. Besides the
+/// sync code and CRC-8, the channel count, sample size and rate must agree
+/// with STREAMINFO, which keeps false syncs inside frame payloads out.
+fn parse_frame_header(bytes: &[u8], info: &FlacStreamInfo) -> Option<FrameHeader> {
+    if bytes.len() < 5 || bytes[0] != 0xFF || bytes[1] & 0xFE != 0xF8 {
+        return None;
+    }
+    let variable_blocking = bytes[1] & 0x01 != 0;
+    let block_code = bytes[2] >> 4;
+    let rate_code = bytes[2] & 0x0F;
+    let channel_code = bytes[3] >> 4;
+    let size_code = (bytes[3] >> 1) & 0x07;
+    if block_code == 0 || rate_code == 0x0F || bytes[3] & 0x01 != 0 {
+        return None;
+    }
+    let channels = match channel_code {
+        0..=7 => u16::from(channel_code) + 1,
+        8..=10 => 2,
+        _ => return None,
+    };
+    let bits = match size_code {
+        0 => info.bits_per_sample,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        7 => 32,
+        _ => return None,
+    };
+    if channels != info.channels || bits != info.bits_per_sample {
+        return None;
+    }
+    let (number, used) = read_coded_number(&bytes[4..])?;
+    let mut pos = 4 + used;
+    let read_u8 = |pos: &mut usize| -> Option<u32> {
+        let v = u32::from(*bytes.get(*pos)?);
+        *pos += 1;
+        Some(v)
+    };
+    let read_u16 = |pos: &mut usize| -> Option<u32> {
+        let v = u32::from(u16::from_be_bytes([
+            *bytes.get(*pos)?,
+            *bytes.get(*pos + 1)?,
+        ]));
+        *pos += 2;
+        Some(v)
+    };
+    let block_size = match block_code {
+        1 => 192,
+        2..=5 => 576 << (block_code - 2),
+        6 => read_u8(&mut pos)? + 1,
+        7 => read_u16(&mut pos)? + 1,
+        _ => 256 << (block_code - 8),
+    };
+    let sample_rate = match rate_code {
+        0 => info.sample_rate,
+        1 => 88_200,
+        2 => 176_400,
+        3 => 192_000,
+        4 => 8_000,
+        5 => 16_000,
+        6 => 22_050,
+        7 => 24_000,
+        8 => 32_000,
+        9 => 44_100,
+        10 => 48_000,
+        11 => 96_000,
+        12 => read_u8(&mut pos)? * 1_000,
+        13 => read_u16(&mut pos)?,
+        _ => read_u16(&mut pos)? * 10,
+    };
+    if sample_rate != info.sample_rate {
+        return None;
+    }
+    if crc8(&bytes[..pos]) != *bytes.get(pos)? {
+        return None;
+    }
+    let first_sample = if variable_blocking {
+        number
+    } else {
+        number.checked_mul(u64::from(info.max_block_size.max(1)))?
+    };
+    Some(FrameHeader {
+        first_sample,
+        block_size,
+    })
+}
+
+/// The UTF-8-style coded frame/sample number (up to 36 bits in 7 bytes).
+fn read_coded_number(bytes: &[u8]) -> Option<(u64, usize)> {
+    let first = *bytes.first()?;
+    let extra = match first.leading_ones() {
+        0 => 0,
+        n @ 2..=7 => n as usize - 1,
+        _ => return None,
+    };
+    let mut value = if extra == 0 {
+        u64::from(first)
+    } else {
+        u64::from(first & (0x7F >> (extra + 1)))
+    };
+    for i in 1..=extra {
+        let b = *bytes.get(i)?;
+        if b & 0xC0 != 0x80 {
+            return None;
+        }
+        value = (value << 6) | u64::from(b & 0x3F);
+    }
+    Some((value, extra + 1))
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &b| {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Buffered reader that finds frame boundaries without decoding: a frame
+/// ends where the next valid header with the expected first sample starts.
+struct FlacFrameReader {
+    file: File,
+    file_len: u64,
+    buf: Vec<u8>,
+    buf_offset: u64,
+}
+
+impl FlacFrameReader {
+    fn open(path: &Path, file_len: u64) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("open flac stream: {}", path.display()))?;
+        Ok(Self {
+            file,
+            file_len,
+            buf: Vec::new(),
+            buf_offset: 0,
+        })
+    }
+
+    /// Bytes `[offset, offset + len)`, clamped to the end of the file.
+    fn window(&mut self, offset: u64, len: usize) -> Result<&[u8]> {
+        let offset = offset.min(self.file_len);
+        let end = offset.saturating_add(len as u64).min(self.file_len);
+        let buf_end = self.buf_offset + self.buf.len() as u64;
+        if offset < self.buf_offset || end > buf_end {
+            let read_len = (end - offset)
+                .max(READ_WINDOW as u64)
+                .min(self.file_len - offset) as usize;
+            self.buf.resize(read_len, 0);
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file
+                .read_exact(&mut self.buf)
+                .context("read FLAC frames")?;
+            self.buf_offset = offset;
+        }
+        let start = (offset - self.buf_offset) as usize;
+        Ok(&self.buf[start..start + (end - offset) as usize])
+    }
+
+    /// Header and end offset of the frame starting at `offset`; `None` when
+    /// no valid header starts there.
+    fn frame_at(
+        &mut self,
+        offset: u64,
+        info: &FlacStreamInfo,
+    ) -> Result<Option<(FrameHeader, u64)>> {
+        let file_len = self.file_len;
+        let Some(header) = parse_frame_header(self.window(offset, MAX_FRAME_HEADER_LEN)?, info)
+        else {
+            return Ok(None);
+        };
+        let next_sample = header.end_sample();
+        if next_sample >= info.total_frames {
+            return Ok(Some((header, file_len)));
+        }
+        let mut span = READ_WINDOW.max(info.max_frame_size as usize * 2);
+        loop {
+            let bytes = self.window(offset, span)?;
+            let at_eof = offset + bytes.len() as u64 >= file_len;
+            let limit = if at_eof {
+                bytes.len()
+            } else {
+                bytes.len().saturating_sub(MAX_FRAME_HEADER_LEN)
+            };
+            let mut pos = 2;
+            while pos < limit {
+                let Some(skip) = bytes[pos..limit].iter().position(|&b| b == 0xFF) else {
+                    break;
+                };
+                pos += skip;
+                if parse_frame_header(&bytes[pos..], info)
+                    .is_some_and(|next| next.first_sample == next_sample)
+                {
+                    return Ok(Some((header, offset + pos as u64)));
+                }
+                pos += 1;
+            }
+            if at_eof {
+                return Ok(Some((header, file_len)));
+            }
+            if span >= MAX_FRAME_SCAN {
+                anyhow::bail!("no FLAC frame boundary within {MAX_FRAME_SCAN} bytes of {offset}");
+            }
+            span *= 2;
+        }
+    }
+}
+
+/// Symphonia's FLAC decoder fed one frame per packet.
+struct FlacFrameDecoder {
+    decoder: Box<dyn Decoder>,
+    sample_buf: Option<SampleBuffer<f32>>,
+}
+
+impl FlacFrameDecoder {
+    fn new(info: &FlacStreamInfo) -> Result<Self> {
+        let channel_bits = (1u32 << info.channels.clamp(1, 8)) - 1;
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(CODEC_TYPE_FLAC)
+            .with_sample_rate(info.sample_rate)
+            .with_bits_per_sample(u32::from(info.bits_per_sample))
+            .with_channels(Channels::from_bits_truncate(channel_bits))
+            .with_n_frames(info.total_frames)
+            .with_max_frames_per_packet(u64::from(info.max_block_size))
+            .with_extra_data(Box::from(&info.raw[..]));
+        let decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .context("create FLAC decoder")?;
+        Ok(Self {
+            decoder,
+            sample_buf: None,
+        })
+    }
+
+    /// Decode one frame to interleaved samples.
+    fn decode(&mut self, bytes: &[u8], header: FrameHeader) -> Result<&[f32]> {
+        let packet =
+            Packet::new_from_slice(0, header.first_sample, u64::from(header.block_size), bytes);
+        let decoded = self.decoder.decode(&packet).context("decode FLAC frame")?;
+        let spec = *decoded.spec();
+        let needed = decoded.capacity() * spec.channels.count();
+        if self
+            .sample_buf
+            .as_ref()
+            .is_none_or(|buf| buf.capacity() < needed)
+        {
+            self.sample_buf = Some(SampleBuffer::<f32>::new(decoded.capacity() as u64, spec));
+        }
+        let buf = self.sample_buf.as_mut().expect("sample buffer allocated");
+        buf.copy_interleaved_ref(decoded);
+        Ok(buf.samples())
+    }
+}
+
+/// Copy the part of a decoded frame starting at `first` that falls inside
+/// `[start, end)` into `out` (interleaved, indexed from `start`).
+fn copy_overlap(
+    first: u64,
+    samples: &[f32],
+    start: u64,
+    end: u64,
+    channels: usize,
+    out: &mut [f32],
+) {
+    let frames = (samples.len() / channels.max(1)) as u64;
+    let from = first.max(start);
+    let to = (first + frames).min(end);
+    if from >= to {
+        return;
+    }
+    let src = (from - first) as usize * channels;
+    let dst = (from - start) as usize * channels;
+    let len = (to - from) as usize * channels;
+    out[dst..dst + len].copy_from_slice(&samples[src..src + len]);
+}
+
+/// Sequential range decoder: continues from the previous range when it can,
+/// otherwise repositions through the nearest seek point. Frames before the
+/// requested range are walked but not decoded.
+struct FlacRangeDecoder {
+    layout: FlacStreamLayout,
+    reader: FlacFrameReader,
+    decoder: FlacFrameDecoder,
+    next: FlacSeekPoint,
+    /// The last decoded frame; frames straddle chunk boundaries.
+    carry_first: Option<u64>,
+    carry: Vec<f32>,
+}
+
+impl FlacRangeDecoder {
+    fn new(path: &Path, layout: FlacStreamLayout) -> Result<Self> {
+        let reader = FlacFrameReader::open(path, layout.file_len)?;
+        let decoder = FlacFrameDecoder::new(&layout.info)?;
+        let next = layout.start_point();
+        Ok(Self {
+            layout,
+            reader,
+            decoder,
+            next,
+            carry_first: None,
+            carry: Vec::new(),
+        })
+    }
+
+    fn channels(&self) -> usize {
+        self.layout.info.channels.max(1) as usize
+    }
+
+    fn reset(&mut self) {
+        self.next = self.layout.start_point();
+        self.carry_first = None;
+    }
+
+    fn decode_range(
+        &mut self,
+        start: u64,
+        end: u64,
+        points: &[FlacSeekPoint],
+        out: &mut Vec<f32>,
+    ) -> Result<()> {
+        let channels = self.channels();
+        let info = self.layout.info;
+        out.clear();
+        out.resize((end - start) as usize * channels, 0.0);
+        if let Some(first) = self.carry_first {
+            copy_overlap(first, &self.carry, start, end, channels, out);
+        }
+        let best = best_seek_point(points, start).unwrap_or(self.layout.start_point());
+        if self.next.sample > start || best.sample > self.next.sample {
+            self.next = best;
+        }
+        while self.next.sample < end && self.next.offset < self.layout.file_len {
+            let at = self.next;
+            let (header, frame_end) = match self.reader.frame_at(at.offset, &info)? {
+                Some((header, end)) if header.first_sample == at.sample => (header, end),
+                _ if at != self.layout.start_point() => {
+                    // A stale or foreign seek point: walk from the first frame.
+                    self.next = self.layout.start_point();
+                    continue;
+                }
+                _ => anyhow::bail!("lost FLAC frame sync at byte {}", at.offset),
+            };
+            if header.end_sample() > start {
+                let bytes = self
+                    .reader
+                    .window(at.offset, (frame_end - at.offset) as usize)?;
+                let samples = self.decoder.decode(bytes, header)?;
+                copy_overlap(header.first_sample, samples, start, end, channels, out);
+                self.carry_first = Some(header.first_sample);
+                self.carry.clear();
+                self.carry.extend_from_slice(samples);
+            }
+            self.next = FlacSeekPoint {
+                sample: header.end_sample(),
+                offset: frame_end,
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Walk every frame header once and keep a seek point about every
+/// `INDEX_SPACING_SEC`. Used for files without a SEEKTABLE.
+pub fn build_flac_frame_index(
+    path: &Path,
+    layout: &FlacStreamLayout,
+) -> Result<Vec<FlacSeekPoint>> {
+    let info = layout.info;
+    let mut reader = FlacFrameReader::open(path, layout.file_len)?;
+    let spacing = ((f64::from(info.sample_rate) * INDEX_SPACING_SEC) as u64).max(1);
+    let mut points = Vec::new();
+    let mut at = layout.start_point();
+    let mut next_mark = 0u64;
+    while at.sample < info.total_frames && at.offset < layout.file_len {
+        let Some((header, end)) = reader.frame_at(at.offset, &info)? else {
+            anyhow::bail!(
+                "lost FLAC frame sync at byte {}: {}",
+                at.offset,
+                path.display()
+            );
+        };
+        if header.first_sample >= next_mark {
+            points.push(FlacSeekPoint {
+                sample: header.first_sample,
+                offset: at.offset,
+            });
+            next_mark = header.first_sample + spacing;
+        }
+        at = FlacSeekPoint {
+            sample: header.end_sample(),
+            offset: end,
+        };
+    }
+    Ok(points)
+}
+
+type FrameIndexKey = (PathBuf, u64, Option<SystemTime>);
+
+fn frame_index_cache() -> &'static Mutex<HashMap<FrameIndexKey, Arc<Vec<FlacSeekPoint>>>> {
+    static CACHE: OnceLock<Mutex<HashMap<FrameIndexKey, Arc<Vec<FlacSeekPoint>>>>> =
+        OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn frame_index_key(path: &Path, layout: &FlacStreamLayout) -> FrameIndexKey {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    (path.to_path_buf(), layout.file_len, modified)
+}
+
+fn cached_frame_index(path: &Path, layout: &FlacStreamLayout) -> Option<Arc<Vec<FlacSeekPoint>>> {
+    frame_index_cache()
+        .lock()
+        .ok()?
+        .get(&frame_index_key(path, layout))
+        .cloned()
+}
+
+/// The frame index for `path`, built on first use and cached for the
+/// session (keyed by path, size and mtime, so a rewritten file re-indexes).
+pub fn flac_frame_index(path: &Path, layout: &FlacStreamLayout) -> Result<Arc<Vec<FlacSeekPoint>>> {
+    if let Some(points) = cached_frame_index(path, layout) {
+        return Ok(points);
+    }
+    let points = Arc::new(build_flac_frame_index(path, layout)?);
+    if let Ok(mut cache) = frame_index_cache().lock() {
+        if cache.len() >= INDEX_CACHE_CAP {
+            cache.clear();
+        }
+        cache.insert(frame_index_key(path, layout), Arc::clone(&points));
+    }
+    Ok(points)
+}
+
+/// Seek points usable right now: the SEEKTABLE, else an already-cached
+/// frame index, else none (walk from the first frame).
+fn known_seek_points(path: &Path, layout: &FlacStreamLayout) -> Arc<Vec<FlacSeekPoint>> {
+    if !layout.seek_table.is_empty() {
+        return Arc::new(layout.seek_table.clone());
+    }
+    cached_frame_index(path, layout).unwrap_or_default()
+}
+
+/// Visit every `stride`-th source frame (interleaved samples) of a FLAC
+/// file, decoding only the audio frames that contain one and jumping over
+/// the rest through known seek points. `None` when the file cannot be
+/// streamed.
+pub fn for_each_flac_strided_frame<F>(
+    path: &Path,
+    stride: usize,
+    mut visit: F,
+) -> Result<Option<FlacStreamInfo>>
+where
+    F: FnMut(&[f32]),
+{
+    let Some(layout) = read_flac_stream_layout(path)? else {
+        return Ok(None);
+    };
+    let info = layout.info;
+    let channels = info.channels.max(1) as usize;
+    let stride = stride.max(1) as u64;
+    let points = known_seek_points(path, &layout);
+    let mut reader = FlacFrameReader::open(path, layout.file_len)?;
+    let mut decoder = FlacFrameDecoder::new(&info)?;
+    let mut at = layout.start_point();
+    let mut target = 0u64;
+    while target < info.total_frames && at.offset < layout.file_len {
+        if let Some(point) = best_seek_point(&points, target).filter(|p| p.sample > at.sample) {
+            at = point;
+        }
+        let Some((header, end)) = reader.frame_at(at.offset, &info)? else {
+            anyhow::bail!(
+                "lost FLAC frame sync at byte {}: {}",
+                at.offset,
+                path.display()
+            );
+        };
+        if target < header.end_sample() {
+            let bytes = reader.window(at.offset, (end - at.offset) as usize)?;
+            let samples = decoder.decode(bytes, header)?;
+            let frames = (samples.len() / channels) as u64;
+            while target < header.end_sample() && target < info.total_frames {
+                let i = target.saturating_sub(header.first_sample);
+                if i < frames {
+                    let i = i as usize * channels;
+                    visit(&samples[i..i + channels]);
+                }
+                target += stride;
+            }
+        }
+        at = FlacSeekPoint {
+            sample: header.end_sample(),
+            offset: end,
+        };
+    }
+    Ok(Some(info))
+}
+
+/// Where the playhead is, as seen by the decode worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlacStreamCursor {
+    pub play_frame: usize,
+    /// Loop start while looping is enabled, so the wrap target stays decoded.
+    pub loop_start: Option<usize>,
+}
+
+/// Polled by the decode worker; returning `None` stops it.
+pub type FlacStreamProbe = Box<dyn Fn() -> Option<FlacStreamCursor> + Send>;
+
+struct FlacChunk {
+    index: usize,
+    samples: Vec<f32>,
+}
+
+struct FlacChunkRing {
+    slots: Vec<ArcSwapOption<FlacChunk>>,
+    channels: usize,
+    stop: AtomicBool,
+    /// Chunks a block reader let go of, as `Arc::into_raw` pointers. The
+    /// reader may hold the last reference to an evicted chunk, so it parks
+    /// the chunk here and the worker frees it off the audio thread.
+    retired: [AtomicPtr<FlacChunk>; RETIRE_SLOTS],
+}
+
+impl FlacChunkRing {
+    fn new(channels: usize) -> Self {
+        Self {
+            slots: (0..CHUNK_SLOTS)
+                .map(|_| ArcSwapOption::from(None))
+                .collect(),
+            channels: channels.max(1),
+            stop: AtomicBool::new(false),
+            retired: std::array::from_fn(|_| AtomicPtr::new(std::ptr::null_mut())),
+        }
+    }
+
+    /// Hand `chunk` to the worker for dropping. Lock- and allocation-free.
+    fn retire(&self, chunk: Arc<FlacChunk>) {
+        let raw = Arc::into_raw(chunk).cast_mut();
+        for slot in &self.retired {
+            if slot
+                .compare_exchange(
+                    std::ptr::null_mut(),
+                    raw,
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                return;
+            }
+        }
+        // Every slot is taken, so the worker is stalled or gone; dropping here
+        // beats leaking the chunk.
+        // SAFETY: `raw` came from `Arc::into_raw` above and was not published.
+        drop(unsafe { Arc::from_raw(raw) });
+    }
+
+    /// Drop every retired chunk. Called from the worker thread.
+    fn drain_retired(&self) {
+        for slot in &self.retired {
+            let raw = slot.swap(std::ptr::null_mut(), Ordering::AcqRel);
+            if !raw.is_null() {
+                // SAFETY: only `retire` stores non-null pointers, each from
+                // `Arc::into_raw`, and the swap hands it to exactly one taker.
+                drop(unsafe { Arc::from_raw(raw) });
+            }
+        }
+    }
+
+    fn has(&self, chunk: usize) -> bool {
+        self.slots[chunk % CHUNK_SLOTS]
+            .load()
+            .as_ref()
+            .is_some_and(|c| c.index == chunk)
+    }
+}
+
+impl Drop for FlacChunkRing {
+    fn drop(&mut self) {
+        self.drain_retired();
+    }
+}
+
+struct FlacStreamWorker {
+    path: PathBuf,
+    decoder: FlacRangeDecoder,
+    ring: Arc<FlacChunkRing>,
+    seek_table: Arc<Vec<FlacSeekPoint>>,
+    /// Built frame index, published by the index thread when there is no
+    /// SEEKTABLE.
+    index: Arc<ArcSwapOption<Vec<FlacSeekPoint>>>,
+    chunk_count: usize,
+}
+
+impl FlacStreamWorker {
+    fn fill(&mut self, chunk: usize) {
+        let total = self.decoder.layout.info.total_frames;
+        let start = (chunk * CHUNK_FRAMES) as u64;
+        let end = (start + CHUNK_FRAMES as u64).min(total);
+        let points = if self.seek_table.is_empty() {
+            self.index.load_full().unwrap_or_default()
+        } else {
+            Arc::clone(&self.seek_table)
+        };
+        let mut samples = Vec::new();
+        if let Err(err) = self.decoder.decode_range(start, end, &points, &mut samples) {
+            // Store silence rather than retrying the same broken range forever.
+            eprintln!(
+                "flac stream decode failed ({}, chunk {chunk}): {err:#}",
+                self.path.display()
+            );
+            samples.clear();
+            samples.resize((end - start) as usize * self.ring.channels, 0.0);
+            self.decoder.reset();
+        }
+        self.ring.slots[chunk % CHUNK_SLOTS].store(Some(Arc::new(FlacChunk {
+            index: chunk,
+            samples,
+        })));
+    }
+
+    /// Next chunk to decode: the playhead window first, then the loop head
+    /// unless its slot would evict part of that window.
+    fn next_missing(&self, cursor: FlacStreamCursor) -> Option<usize> {
+        let play = cursor.play_frame / CHUNK_FRAMES;
+        let ahead = play..play + CHUNKS_AHEAD;
+        let evicts_ahead = |c: usize| {
+            !ahead.contains(&c)
+                && (c % CHUNK_SLOTS + CHUNK_SLOTS - play % CHUNK_SLOTS) % CHUNK_SLOTS < CHUNKS_AHEAD
+        };
+        let loop_head = cursor
+            .loop_start
+            .map(|s| s / CHUNK_FRAMES)
+            .into_iter()
+            .flat_map(|c| [c, c + 1])
+            .filter(|c| !evicts_ahead(*c));
+        ahead
+            .clone()
+            .chain(loop_head)
+            .filter(|c| *c < self.chunk_count)
+            .find(|c| !self.ring.has(*c))
+    }
+
+    fn run(mut self, probe: FlacStreamProbe) {
+        while !self.ring.stop.load(Ordering::Relaxed) {
+            self.ring.drain_retired();
+            let Some(cursor) = probe() else {
+                break;
+            };
+            match self.next_missing(cursor) {
+                Some(chunk) => self.fill(chunk),
+                None => std::thread::sleep(WORKER_IDLE),
+            }
+        }
+    }
+}
+
+/// A FLAC file played in place: decoded chunk-by-chunk off the audio thread.
+pub struct FlacStreamSource {
+    path: PathBuf,
+    info: FlacStreamInfo,
+    ring: Arc<FlacChunkRing>,
+}
+
+impl FlacStreamSource {
+    /// Open `path` and start its decode worker. The first chunk is decoded
+    /// before returning so playback from the top starts without a gap.
+    pub fn open(path: &Path, probe: FlacStreamProbe) -> Result<Self> {
+        let layout = read_flac_stream_layout(path)?
+            .with_context(|| format!("unsupported flac stream source: {}", path.display()))?;
+        let info = layout.info;
+        let ring = Arc::new(FlacChunkRing::new(info.channels as usize));
+        let index = Arc::new(ArcSwapOption::from(None));
+        if layout.seek_table.is_empty() {
+            if let Some(points) = cached_frame_index(path, &layout) {
+                index.store(Some(points));
+            } else {
+                let index = Arc::clone(&index);
+                let index_path = path.to_path_buf();
+                let index_layout = layout.clone();
+                let _ = std::thread::Builder::new()
+                    .name("neowaves-flac-index".into())
+                    .spawn(move || match flac_frame_index(&index_path, &index_layout) {
+                        Ok(points) => index.store(Some(points)),
+                        Err(err) => eprintln!("flac frame index failed: {err:#}"),
+                    });
+            }
+        }
+        let chunk_count = (info.total_frames as usize).div_ceil(CHUNK_FRAMES);
+        let mut worker = FlacStreamWorker {
+            path: path.to_path_buf(),
+            seek_table: Arc::new(layout.seek_table.clone()),
+            decoder: FlacRangeDecoder::new(path, layout)?,
+            ring: Arc::clone(&ring),
+            index,
+            chunk_count,
+        };
+        worker.fill(0);
+        std::thread::Builder::new()
+            .name("neowaves-flac-stream".into())
+            .spawn(move || worker.run(probe))
+            .with_context(|| format!("spawn flac stream worker: {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            info,
+            ring,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.info.total_frames as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.info.channels.max(1) as usize
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.info.sample_rate.max(1)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the chunk holding `frame_idx` is decoded and playable.
+    pub fn is_frame_ready(&self, frame_idx: usize) -> bool {
+        self.ring.has(frame_idx / CHUNK_FRAMES)
+    }
+
+    /// Reader for one audio callback block.
+    pub fn block_reader(&self) -> FlacBlockReader<'_> {
+        FlacBlockReader {
+            source: self,
+            chunks: RefCell::new([None, None]),
+        }
+    }
+
+    /// Lock-free read of an already-decoded sample; 0.0 when its chunk is
+    /// not ready yet.
+    pub fn sample_at_frame(&self, frame_idx: usize, ch_idx: usize) -> f32 {
+        self.block_reader().sample_at_frame(frame_idx, ch_idx)
+    }
+}
+
+/// One callback block's view of a [`FlacStreamSource`]. It keeps the chunks
+/// it has read from, so a ring slot is loaded again only when the playhead
+/// crosses into another chunk rather than for every sample.
+pub struct FlacBlockReader<'a> {
+    source: &'a FlacStreamSource,
+    /// Indexed by chunk parity: interpolation across a chunk boundary keeps
+    /// both neighbours cached.
+    chunks: RefCell<[Option<Arc<FlacChunk>>; 2]>,
+}
+
+impl FlacBlockReader<'_> {
+    /// Run `f` on the decoded chunk `chunk`, or return `None` when the ring
+    /// does not hold it yet.
+    fn with_chunk<R>(&self, chunk: usize, f: impl FnOnce(&FlacChunk) -> R) -> Option<R> {
+        let mut cached = self.chunks.borrow_mut();
+        let entry = &mut cached[chunk % 2];
+        if entry.as_ref().is_none_or(|c| c.index != chunk) {
+            let ring = &self.source.ring;
+            let loaded = match ring.slots[chunk % CHUNK_SLOTS].load_full() {
+                Some(c) if c.index == chunk => Some(c),
+                stale => {
+                    stale.into_iter().for_each(|c| ring.retire(c));
+                    None
+                }
+            };
+            if let Some(evicted) = std::mem::replace(entry, loaded) {
+                ring.retire(evicted);
+            }
+        }
+        entry.as_deref().map(f)
+    }
+
+    /// Whether the chunk holding `frame_idx` is decoded and playable.
+    pub fn is_frame_ready(&self, frame_idx: usize) -> bool {
+        self.with_chunk(frame_idx / CHUNK_FRAMES, |_| ()).is_some()
+    }
+
+    pub fn sample_at_interp(&self, ch_idx: usize, pos_f: f64) -> f32 {
+        let len = self.source.len();
+        if len == 0 {
+            return 0.0;
+        }
+        let max_index = len.saturating_sub(1);
+        let pf = if pos_f.is_finite() {
+            pos_f.clamp(0.0, max_index as f64)
+        } else {
+            0.0
+        };
+        let i0 = pf.floor() as usize;
+        let i1 = (i0 + 1).min(max_index);
+        let t = (pf - i0 as f64).clamp(0.0, 1.0) as f32;
+        let s0 = self.sample_at_frame(i0, ch_idx);
+        let s1 = self.sample_at_frame(i1, ch_idx);
+        s0 * (1.0 - t) + s1 * t
+    }
+
+    /// Read of an already-decoded sample; 0.0 when its chunk is not ready.
+    pub fn sample_at_frame(&self, frame_idx: usize, ch_idx: usize) -> f32 {
+        let chunk = frame_idx / CHUNK_FRAMES;
+        let channels = self.source.ring.channels;
+        let i = (frame_idx - chunk * CHUNK_FRAMES) * channels + ch_idx.min(channels - 1);
+        self.with_chunk(chunk, |decoded| decoded.samples.get(i).copied())
+            .flatten()
+            .unwrap_or(0.0)
+    }
+}
+
+impl Drop for FlacBlockReader<'_> {
+    fn drop(&mut self) {
+        for chunk in self.chunks.get_mut().iter_mut().filter_map(Option::take) {
+            self.source.ring.retire(chunk);
+        }
+    }
+}
+
+impl Drop for FlacStreamSource {
+    fn drop(&mut self) {
+        // Only flag the worker: this may run on the audio thread when the
+        // callback held the last reference, so it must not join.
+        self.ring.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for FlacStreamSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlacStreamSource")
+            .field("path", &self.path)
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_info() -> FlacStreamInfo {
+        let mut raw = [0u8; STREAMINFO_LEN];
+        raw[0..2].copy_from_slice(&4096u16.to_be_bytes());
+        raw[2..4].copy_from_slice(&4096u16.to_be_bytes());
+        let packed: u64 = (48_000u64 << 44) | (1u64 << 41) | (15u64 << 36) | 100_000;
+        raw[10..18].copy_from_slice(&packed.to_be_bytes());
+        FlacStreamInfo::parse(&raw).expect("streaminfo")
+    }
+
+    fn frame_header(frame_number: u8, block_code: u8) -> Vec<u8> {
+        // Fixed blocking, 48 kHz (code 10), stereo independent, 16-bit (code 4).
+        let mut bytes = vec![0xFF, 0xF8, (block_code << 4) | 10, 0x18, frame_number];
+        bytes.push(crc8(&bytes));
+        bytes
+    }
+
+    #[test]
+    fn streaminfo_fields_unpack() {
+        let info = test_info();
+        assert_eq!(info.sample_rate, 48_000);
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.total_frames, 100_000);
+        assert_eq!(info.max_block_size, 4096);
+    }
+
+    #[test]
+    fn frame_headers_validate_crc_and_stream_parameters() {
+        let info = test_info();
+        let header = parse_frame_header(&frame_header(3, 12), &info).expect("valid header");
+        assert_eq!(header.first_sample, 3 * 4096);
+        assert_eq!(header.block_size, 4096);
+
+        let mut corrupt = frame_header(3, 12);
+        corrupt[4] = 4;
+        assert_eq!(parse_frame_header(&corrupt, &info), None, "CRC-8 mismatch");
+
+        let mut mono = frame_header(3, 12);
+        mono[3] = 0x08;
+        let crc_pos = mono.len() - 1;
+        mono[crc_pos] = crc8(&mono[..crc_pos]);
+        assert_eq!(parse_frame_header(&mono, &info), None, "channel mismatch");
+    }
+
+    #[test]
+    fn coded_numbers_decode_multi_byte_forms() {
+        assert_eq!(read_coded_number(&[0x7F]), Some((0x7F, 1)));
+        assert_eq!(read_coded_number(&[0xC2, 0x80]), Some((0x80, 2)));
+        assert_eq!(read_coded_number(&[0xE0, 0xA0, 0x80]), Some((0x800, 3)));
+        assert_eq!(read_coded_number(&[0x80]), None);
+        assert_eq!(read_coded_number(&[0xC2, 0x00]), None);
+    }
+
+    #[test]
+    fn seek_table_drops_placeholders_and_picks_preceding_point() {
+        let mut payload = Vec::new();
+        for (sample, offset) in [(8192u64, 900u64), (0, 0), (SEEKPOINT_PLACEHOLDER, 0)] {
+            payload.extend_from_slice(&sample.to_be_bytes());
+            payload.extend_from_slice(&offset.to_be_bytes());
+            payload.extend_from_slice(&4096u16.to_be_bytes());
+        }
+        let points = parse_seek_table(&payload);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].sample, 0);
+        assert_eq!(best_seek_point(&points, 8191).map(|p| p.offset), Some(0));
+        assert_eq!(best_seek_point(&points, 9000).map(|p| p.offset), Some(900));
+    }
+
+    #[test]
+    fn retired_chunks_are_freed_by_the_drain() {
+        let ring = FlacChunkRing::new(1);
+        let chunk = Arc::new(FlacChunk {
+            index: 3,
+            samples: vec![0.0; 4],
+        });
+        let weak = Arc::downgrade(&chunk);
+        ring.retire(chunk);
+        assert!(weak.upgrade().is_some(), "retire must not drop in place");
+        ring.drain_retired();
+        assert!(weak.upgrade().is_none());
+
+        // A full retire list falls back to dropping rather than leaking.
+        let parked: Vec<_> = (0..=RETIRE_SLOTS)
+            .map(|index| {
+                Arc::new(FlacChunk {
+                    index,
+                    samples: Vec::new(),
+                })
+            })
+            .collect();
+        let weaks: Vec<_> = parked.iter().map(Arc::downgrade).collect();
+        parked.into_iter().for_each(|c| ring.retire(c));
+        assert!(weaks[RETIRE_SLOTS].upgrade().is_none());
+        drop(ring);
+        assert!(weaks.iter().all(|w| w.upgrade().is_none()));
+    }
+}
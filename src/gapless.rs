@@ -0,0 +1,651 @@
+//! Encoder delay ("priming") and end padding for MP3 and AAC.
+//!
+//! Lossy encoders prepend priming samples and pad the last frame, so decoded
+//! sample 0 is not the first source sample unless the container says how
+//! much to drop. Two conventions are handled here:
+//!
+//! - MP3: the LAME extension of the Xing/Info frame. symphonia's MP3 reader
+//!   trims it when opened with `enable_gapless`; on export,
+//!   [`apply_mp3_gapless_info`] replaces LAME's blank tag frame with a filled
+//!   Info frame so the delay/padding survive.
+//! - AAC in MP4: the iTunes `iTunSMPB` freeform atom. Neither the mp4 crate
+//!   nor symphonia's isomp4 reader look at it, so the M4A decode paths run
+//!   the decoded frames through [`GaplessTrim`] themselves.
+//!
+//! [`GaplessInfo`] always describes the *decoded* stream: `priming` frames
+//! are dropped from the start and `padding` frames from the end.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use mp4ameta::{Data, FreeformIdent, Tag as Mp4Tag};
+use symphonia::core::audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal};
+use symphonia::core::codecs::{
+    CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult,
+};
+use symphonia::core::errors::{unsupported_error, Result as SymphoniaResult};
+use symphonia::core::formats::Packet;
+
+const ITUNES_MEAN: &str = "com.apple.iTunes";
+const ITUNSMPB_KEY: &str = "iTunSMPB";
+
+/// Encoder delay of libmp3lame (`ENCDELAY`), in output samples.
+pub const LAME_ENCODER_DELAY: u32 = 576;
+/// Delay added by the MP3 synthesis filterbank on decode. The LAME tag
+/// stores encoder-side values; readers add this on top.
+pub const MP3_DECODER_DELAY: u32 = 529;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GaplessInfo {
+    /// Frames to drop from the start of the decoded stream.
+    pub priming: u32,
+    /// Frames to drop from the end of the decoded stream.
+    pub padding: u32,
+    /// Source length in frames, when the container records it.
+    pub valid_frames: Option<u64>,
+}
+
+impl GaplessInfo {
+    /// Fills in `valid_frames` from the coded stream length when the
+    /// container only recorded priming and padding.
+    pub fn with_coded_len(mut self, coded_frames: Option<u64>) -> Self {
+        if self.valid_frames.is_none() {
+            self.valid_frames = coded_frames
+                .map(|n| n.saturating_sub(u64::from(self.priming) + u64::from(self.padding)));
+        }
+        self
+    }
+}
+
+/// Trims priming and padding off a linearly decoded stream, one decoded
+/// block at a time.
+#[derive(Clone, Debug)]
+pub struct GaplessTrim {
+    info: GaplessInfo,
+    position: u64,
+}
+
+impl GaplessTrim {
+    pub fn new(info: GaplessInfo) -> Self {
+        Self { info, position: 0 }
+    }
+
+    /// Given a freshly decoded block of `frames` frames, returns the range
+    /// of the block that belongs to the source signal.
+    pub fn keep(&mut self, frames: usize) -> std::ops::Range<usize> {
+        let range = self.keep_at(self.position, frames);
+        self.position += frames as u64;
+        range
+    }
+
+    /// Like [`GaplessTrim::keep`] for a block that starts `start` frames
+    /// into the decoded stream, so the trim does not depend on having
+    /// decoded everything before it (e.g. after a seek).
+    pub fn keep_at(&self, start: u64, frames: usize) -> std::ops::Range<usize> {
+        let end = start + frames as u64;
+        let first = u64::from(self.info.priming);
+        let last = self
+            .info
+            .valid_frames
+            .map(|n| first.saturating_add(n))
+            .unwrap_or(u64::MAX);
+        let from = first.clamp(start, end);
+        let to = last.clamp(from, end);
+        (from - start) as usize..(to - start) as usize
+    }
+}
+
+/// Parses an iTunes `iTunSMPB` value
+/// (`" 00000000 PPPPPPPP EEEEEEEE LLLLLLLLLLLLLLLL ..."`, hex).
+pub fn parse_itunsmpb(text: &str) -> Option<GaplessInfo> {
+    let fields = text
+        .split_whitespace()
+        .take(4)
+        .map(|field| u64::from_str_radix(field, 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    if fields.len() < 4 {
+        return None;
+    }
+    let priming = u32::try_from(fields[1]).ok()?;
+    let padding = u32::try_from(fields[2]).ok()?;
+    let valid_frames = (fields[3] > 0).then_some(fields[3]);
+    if priming == 0 && padding == 0 && valid_frames.is_none() {
+        return None;
+    }
+    Some(GaplessInfo {
+        priming,
+        padding,
+        valid_frames,
+    })
+}
+
+pub fn format_itunsmpb(info: &GaplessInfo) -> String {
+    let mut out = format!(
+        " 00000000 {:08X} {:08X} {:016X}",
+        info.priming,
+        info.padding,
+        info.valid_frames.unwrap_or(0)
+    );
+    for _ in 0..8 {
+        out.push_str(" 00000000");
+    }
+    out
+}
+
+pub fn read_m4a_gapless(path: &Path) -> Option<GaplessInfo> {
+    let tag = Mp4Tag::read_from_path(path).ok()?;
+    let key = FreeformIdent::new_static(ITUNES_MEAN, ITUNSMPB_KEY);
+    let value = tag.strings_of(&key).next()?;
+    parse_itunsmpb(value)
+}
+
+/// Replaces (or with `None`, removes) the `iTunSMPB` atom in a tag.
+pub fn set_m4a_gapless_tag(tag: &mut Mp4Tag, info: Option<&GaplessInfo>) {
+    let key = FreeformIdent::new_static(ITUNES_MEAN, ITUNSMPB_KEY);
+    tag.remove_strings_of(&key);
+    if let Some(info) = info {
+        tag.set_data(key, Data::Utf8(format_itunsmpb(info)));
+    }
+}
+
+pub fn write_m4a_gapless(path: &Path, info: Option<&GaplessInfo>) -> Result<()> {
+    let mut tag = Mp4Tag::read_from_path(path)
+        .with_context(|| format!("read m4a tags: {}", path.display()))?;
+    set_m4a_gapless_tag(&mut tag, info);
+    tag.write_to_path(path)
+        .with_context(|| format!("write m4a gapless info: {}", path.display()))?;
+    Ok(())
+}
+
+/// Wraps a symphonia decoder and applies a [`GaplessTrim`] to its output.
+/// Used for M4A streams carrying `iTunSMPB`, since symphonia's AAC decoder
+/// does not honor it. Each packet is trimmed by its own timestamp, so the
+/// trim stays right after a seek.
+pub struct GaplessTrimDecoder {
+    inner: Box<dyn Decoder>,
+    trim: GaplessTrim,
+    buf: AudioBuffer<f32>,
+}
+
+impl GaplessTrimDecoder {
+    pub fn new(inner: Box<dyn Decoder>, info: GaplessInfo) -> Self {
+        Self {
+            inner,
+            trim: GaplessTrim::new(info),
+            buf: AudioBuffer::unused(),
+        }
+    }
+
+    /// Decoded-frame position of a packet timestamp. MP4 audio tracks
+    /// usually tick at the sample rate; other time bases are converted.
+    fn frame_of(&self, ts: u64) -> u64 {
+        let params = self.inner.codec_params();
+        match (params.time_base, params.sample_rate) {
+            (Some(tb), Some(sr)) if tb.denom > 0 && (tb.numer, tb.denom) != (1, sr) => {
+                let frames =
+                    u128::from(ts) * u128::from(tb.numer) * u128::from(sr) / u128::from(tb.denom);
+                frames.min(u128::from(u64::MAX)) as u64
+            }
+            _ => ts,
+        }
+    }
+}
+
+impl Decoder for GaplessTrimDecoder {
+    fn try_new(_params: &CodecParameters, _options: &DecoderOptions) -> SymphoniaResult<Self> {
+        unsupported_error("gapless: wrapper is constructed around an existing decoder")
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        self.inner.codec_params()
+    }
+
+    fn decode(&mut self, packet: &Packet) -> SymphoniaResult<AudioBufferRef<'_>> {
+        let start = self.frame_of(packet.ts);
+        self.buf.clear();
+        let decoded = self.inner.decode(packet)?;
+        if self.buf.capacity() < decoded.capacity() || self.buf.spec() != decoded.spec() {
+            self.buf = decoded.make_equivalent::<f32>();
+        }
+        decoded.convert(&mut self.buf);
+        let frames = self.buf.frames();
+        let keep = self.trim.keep_at(start, frames);
+        self.buf.trim(keep.start, frames - keep.end);
+        Ok(self.buf.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        self.inner.finalize()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buf.as_audio_buffer_ref()
+    }
+}
+
+/// CRC-16/ARC (reflected 0x8005, init 0), as used by the LAME tag.
+fn crc16_lame(bytes: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &b in bytes {
+        crc ^= u16::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+const MPEG1_L3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MPEG2_L3_KBPS: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+const MPEG1_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+/// Xing/Info tag with frames, bytes, TOC and quality fields.
+const INFO_TAG_LEN: usize = 120;
+const LAME_EXT_LEN: usize = 36;
+
+#[derive(Clone, Copy, Debug)]
+struct Mp3Header {
+    raw: [u8; 4],
+    mpeg1: bool,
+    sample_rate: u32,
+    mono: bool,
+    bitrate_kbps: u32,
+    frame_len: usize,
+}
+
+impl Mp3Header {
+    fn parse(raw: [u8; 4]) -> Option<Self> {
+        if raw[0] != 0xFF || raw[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        let version = (raw[1] >> 3) & 0x03;
+        let layer = (raw[1] >> 1) & 0x03;
+        if version == 1 || layer != 1 {
+            return None;
+        }
+        let bitrate_index = (raw[2] >> 4) as usize;
+        let rate_index = ((raw[2] >> 2) & 0x03) as usize;
+        if bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+            return None;
+        }
+        let mpeg1 = version == 3;
+        let sample_rate = match version {
+            3 => MPEG1_RATES[rate_index],
+            2 => MPEG1_RATES[rate_index] / 2,
+            _ => MPEG1_RATES[rate_index] / 4,
+        };
+        let bitrate_kbps = if mpeg1 {
+            MPEG1_L3_KBPS[bitrate_index]
+        } else {
+            MPEG2_L3_KBPS[bitrate_index]
+        };
+        let padding = usize::from((raw[2] >> 1) & 0x01);
+        let frame_len =
+            (Self::slot_bytes(mpeg1) * bitrate_kbps * 1000 / sample_rate) as usize + padding;
+        Some(Self {
+            raw,
+            mpeg1,
+            sample_rate,
+            mono: raw[3] >> 6 == 3,
+            bitrate_kbps,
+            frame_len,
+        })
+    }
+
+    fn slot_bytes(mpeg1: bool) -> u32 {
+        if mpeg1 {
+            144
+        } else {
+            72
+        }
+    }
+
+    fn samples_per_frame(&self) -> u32 {
+        if self.mpeg1 {
+            1152
+        } else {
+            576
+        }
+    }
+
+    fn side_info_len(&self) -> usize {
+        match (self.mpeg1, self.mono) {
+            (true, true) => 17,
+            (true, false) => 32,
+            (false, true) => 9,
+            (false, false) => 17,
+        }
+    }
+
+    fn has_crc(&self) -> bool {
+        self.raw[1] & 0x01 == 0
+    }
+
+    /// Offset of the Xing/Info id inside the frame.
+    fn tag_offset(&self) -> usize {
+        4 + if self.has_crc() { 2 } else { 0 } + self.side_info_len()
+    }
+}
+
+fn info_tag_id_at(frame: &[u8], header: &Mp3Header) -> bool {
+    let at = header.tag_offset();
+    matches!(frame.get(at..at + 4), Some(b"Xing") | Some(b"Info"))
+}
+
+/// LAME writes an all-zero frame up front and expects the caller to fill
+/// it in after encoding; a decoder sees it as one frame of silence.
+fn is_tag_placeholder(frame: &[u8], header: &Mp3Header) -> bool {
+    info_tag_id_at(frame, header) || frame[4..].iter().all(|&b| b == 0)
+}
+
+fn build_info_frame(
+    template: &Mp3Header,
+    audio_frames: u32,
+    audio: &[u8],
+    delay: u32,
+    padding: u32,
+) -> Option<Vec<u8>> {
+    let kbps_table = if template.mpeg1 {
+        &MPEG1_L3_KBPS
+    } else {
+        &MPEG2_L3_KBPS
+    };
+    // Side info is zero (no CRC) so decoders skip the frame as a tag.
+    let side_info = template.side_info_len();
+    let needed = 4 + side_info + INFO_TAG_LEN + LAME_EXT_LEN;
+    let slot = Mp3Header::slot_bytes(template.mpeg1);
+    let frame_len_at =
+        |index: usize| (slot * kbps_table[index] * 1000 / template.sample_rate) as usize;
+    let template_index = (template.raw[2] >> 4) as usize;
+    let bitrate_index = if frame_len_at(template_index) >= needed {
+        template_index
+    } else {
+        (1..kbps_table.len()).find(|&index| frame_len_at(index) >= needed)?
+    };
+    let frame_len = frame_len_at(bitrate_index);
+    let mut frame = vec![0u8; frame_len];
+    frame[0] = 0xFF;
+    frame[1] = template.raw[1] | 0x01;
+    frame[2] = ((bitrate_index as u8) << 4) | (template.raw[2] & 0x0C);
+    frame[3] = template.raw[3];
+
+    let stream_bytes = u32::try_from(frame_len + audio.len()).ok()?;
+    let mut tag = Vec::with_capacity(INFO_TAG_LEN + LAME_EXT_LEN);
+    tag.extend_from_slice(b"Info");
+    tag.extend_from_slice(&0x0Fu32.to_be_bytes());
+    tag.extend_from_slice(&audio_frames.to_be_bytes());
+    tag.extend_from_slice(&stream_bytes.to_be_bytes());
+    // Constant bitrate: the TOC is a straight line.
+    tag.extend((0..100u32).map(|i| (i * 256 / 100) as u8));
+    tag.extend_from_slice(&0u32.to_be_bytes());
+    tag.extend_from_slice(b"LAME3.100");
+    tag.push(0x01); // tag revision 0, CBR
+    tag.push(0); // lowpass (unknown)
+    tag.extend_from_slice(&[0u8; 8]); // peak + radio/audiophile ReplayGain
+    tag.push(0); // encoding flags / ATH type
+    tag.push(template.bitrate_kbps.min(255) as u8);
+    let trim = (delay.min(0x0FFF) << 12) | padding.min(0x0FFF);
+    tag.extend_from_slice(&trim.to_be_bytes()[1..]);
+    tag.push(0); // misc
+    tag.push(0); // MP3Gain
+    tag.extend_from_slice(&0u16.to_be_bytes()); // preset / surround
+    tag.extend_from_slice(&stream_bytes.to_be_bytes());
+    tag.extend_from_slice(&crc16_lame(audio).to_be_bytes());
+    let tag_start = 4 + side_info;
+    frame[tag_start..tag_start + tag.len()].copy_from_slice(&tag);
+    let crc_at = tag_start + tag.len();
+    let crc = crc16_lame(&frame[..crc_at]);
+    frame[crc_at..crc_at + 2].copy_from_slice(&crc.to_be_bytes());
+    Some(frame)
+}
+
+/// Puts a LAME Info frame carrying `delay`/padding in front of an mp3lame
+/// stream (replacing LAME's blank placeholder frame when present).
+/// `source_frames` is the number of frames fed to the encoder at
+/// `source_rate`. Streams that cannot be parsed are returned unchanged.
+pub fn apply_mp3_gapless_info(
+    stream: Vec<u8>,
+    source_frames: u64,
+    source_rate: u32,
+    delay: u32,
+) -> Vec<u8> {
+    let mut frames = Vec::new();
+    let mut pos = 0usize;
+    while pos + 4 <= stream.len() {
+        let raw = [
+            stream[pos],
+            stream[pos + 1],
+            stream[pos + 2],
+            stream[pos + 3],
+        ];
+        let Some(header) = Mp3Header::parse(raw) else {
+            return stream;
+        };
+        if pos + header.frame_len > stream.len() {
+            return stream;
+        }
+        frames.push((pos, header));
+        pos += header.frame_len;
+    }
+    let Some(&(_, first)) = frames.first() else {
+        return stream;
+    };
+    let skip_first = is_tag_placeholder(&stream[..first.frame_len], &first);
+    let audio_start = if skip_first { first.frame_len } else { 0 };
+    let audio_frames = frames.len() - usize::from(skip_first);
+    let Some(&(_, template)) = frames.get(usize::from(skip_first)) else {
+        return stream;
+    };
+    // LAME may resample internally; its delay is in output samples.
+    let coded_frames = if template.sample_rate == source_rate {
+        source_frames
+    } else {
+        (source_frames as f64 * template.sample_rate as f64 / source_rate.max(1) as f64).round()
+            as u64
+    };
+    let total = audio_frames as u64 * u64::from(template.samples_per_frame());
+    let Some(padding) = total.checked_sub(u64::from(delay) + coded_frames) else {
+        return stream;
+    };
+    if delay > 0x0FFF || padding > 0x0FFF {
+        return stream;
+    }
+    let audio = &stream[audio_start..pos];
+    let Some(info_frame) =
+        build_info_frame(&template, audio_frames as u32, audio, delay, padding as u32)
+    else {
+        return stream;
+    };
+    let mut out = Vec::with_capacity(info_frame.len() + audio.len() + stream.len() - pos);
+    out.extend_from_slice(&info_frame);
+    out.extend_from_slice(audio);
+    out.extend_from_slice(&stream[pos..]);
+    out
+}
+
+fn id3v2_len(head: &[u8; 10]) -> Option<u64> {
+    if &head[..3] != b"ID3" {
+        return None;
+    }
+    let size = head[6..10]
+        .iter()
+        .fold(0u64, |acc, &b| (acc << 7) | u64::from(b & 0x7F));
+    let footer = if head[5] & 0x10 != 0 { 10 } else { 0 };
+    Some(10 + size + footer)
+}
+
+/// Reads the LAME delay/padding of an MP3, expressed for the decoded stream
+/// (the decoder delay is folded into `priming`). `Ok(None)` means the file
+/// has no LAME tag, so decoding keeps the priming samples.
+pub fn read_mp3_gapless(path: &Path) -> Result<Option<GaplessInfo>> {
+    let mut file = File::open(path).with_context(|| format!("open mp3: {}", path.display()))?;
+    let mut head = [0u8; 10];
+    let mut offset = 0u64;
+    if file.read_exact(&mut head).is_ok() {
+        offset = id3v2_len(&head).unwrap_or(0);
+    }
+    file.seek(SeekFrom::Start(offset))
+        .with_context(|| format!("seek mp3: {}", path.display()))?;
+    let mut frame = Vec::with_capacity(2048);
+    file.take(2048)
+        .read_to_end(&mut frame)
+        .with_context(|| format!("read mp3: {}", path.display()))?;
+    if frame.len() < 4 {
+        return Ok(None);
+    }
+    let Some(header) = Mp3Header::parse([frame[0], frame[1], frame[2], frame[3]]) else {
+        return Ok(None);
+    };
+    frame.truncate(header.frame_len);
+    if !info_tag_id_at(&frame, &header) {
+        return Ok(None);
+    }
+    Ok(parse_lame_tag(&frame, &header))
+}
+
+fn parse_lame_tag(frame: &[u8], header: &Mp3Header) -> Option<GaplessInfo> {
+    let mut at = header.tag_offset() + 4;
+    let flags = u32::from_be_bytes(frame.get(at..at + 4)?.try_into().ok()?);
+    at += 4;
+    let mut frame_count = None;
+    if flags & 0x1 != 0 {
+        frame_count = Some(u32::from_be_bytes(frame.get(at..at + 4)?.try_into().ok()?));
+        at += 4;
+    }
+    if flags & 0x2 != 0 {
+        at += 4;
+    }
+    if flags & 0x4 != 0 {
+        at += 100;
+    }
+    if flags & 0x8 != 0 {
+        at += 4;
+    }
+    let ext = frame.get(at..at + 24)?;
+    if !matches!(&ext[..4], b"LAME" | b"Lavf" | b"Lavc") {
+        return None;
+    }
+    let trim = u32::from_be_bytes([0, ext[21], ext[22], ext[23]]);
+    let delay = trim >> 12;
+    let padding = trim & 0x0FFF;
+    let valid_frames = frame_count.and_then(|n| {
+        (u64::from(n) * u64::from(header.samples_per_frame()))
+            .checked_sub(u64::from(delay) + u64::from(padding))
+    });
+    Some(GaplessInfo {
+        priming: delay + MP3_DECODER_DELAY,
+        padding: padding.saturating_sub(MP3_DECODER_DELAY),
+        valid_frames,
+    })
+}
+
+/// Gapless info for any supported lossy path (`None` for other formats or
+/// files without it).
+pub fn read_gapless_info(path: &Path) -> Option<GaplessInfo> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "mp3" => read_mp3_gapless(path).ok().flatten(),
+        "m4a" => read_m4a_gapless(path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_matches_arc_check_value() {
+        assert_eq!(crc16_lame(b"123456789"), 0xBB3D);
+    }
+
+    #[test]
+    fn itunsmpb_round_trips() {
+        let info = GaplessInfo {
+            priming: 2048,
+            padding: 960,
+            valid_frames: Some(441_000),
+        };
+        let text = format_itunsmpb(&info);
+        assert!(text.starts_with(" 00000000 00000800 000003C0 000000000006BAD0"));
+        assert_eq!(text.split_whitespace().count(), 12);
+        assert_eq!(parse_itunsmpb(&text), Some(info));
+        assert_eq!(parse_itunsmpb(" 00000000 00000000 00000000 0"), None);
+    }
+
+    #[test]
+    fn trim_spans_block_boundaries() {
+        let mut trim = GaplessTrim::new(GaplessInfo {
+            priming: 1500,
+            padding: 0,
+            valid_frames: Some(1000),
+        });
+        assert_eq!(trim.keep(1024), 1024..1024);
+        assert_eq!(trim.keep(1024), 476..1024);
+        assert_eq!(trim.keep(1024), 0..452);
+        assert_eq!(trim.keep(1024), 0..0);
+    }
+
+    #[test]
+    fn trim_by_position_matches_linear_decode_after_seek() {
+        let info = GaplessInfo {
+            priming: 2112,
+            padding: 600,
+            valid_frames: Some(5000),
+        };
+        let mut linear = GaplessTrim::new(info);
+        let ranges: Vec<_> = (0..8).map(|_| linear.keep(1024)).collect();
+        // A seek lands on block 1 (still priming) or block 6 (end padding):
+        // trimming by position gives the linear result without blocks 0..n.
+        let trim = GaplessTrim::new(info);
+        for block in [1usize, 2, 6, 7] {
+            assert_eq!(trim.keep_at(block as u64 * 1024, 1024), ranges[block]);
+        }
+        assert_eq!(trim.keep_at(1024, 1024), 1024..1024);
+        assert_eq!(trim.keep_at(2048, 1024), 64..1024);
+        assert_eq!(trim.keep_at(7168, 1024), 0..0);
+    }
+
+    #[test]
+    fn info_frame_replaces_lame_placeholder() {
+        // MPEG-1 Layer III, 128 kbps, 44.1 kHz, joint stereo, no CRC.
+        let raw = [0xFF, 0xFB, 0x90, 0x44];
+        let header = Mp3Header::parse(raw).expect("header");
+        assert_eq!(header.frame_len, 417);
+        let mut stream = vec![0u8; header.frame_len];
+        stream[..4].copy_from_slice(&raw);
+        for i in 0..10u8 {
+            let mut frame = vec![i.wrapping_add(1); header.frame_len];
+            frame[..4].copy_from_slice(&raw);
+            stream.extend_from_slice(&frame);
+        }
+        let source_frames = 10 * 1152 - 576 - 700;
+        let out = apply_mp3_gapless_info(stream.clone(), source_frames, 44_100, 576);
+        assert_eq!(out.len(), stream.len());
+        assert_eq!(&out[header.frame_len..], &stream[header.frame_len..]);
+        let info = parse_lame_tag(&out[..header.frame_len], &header).expect("lame tag");
+        assert_eq!(info.priming, 576 + MP3_DECODER_DELAY);
+        assert_eq!(info.padding, 700 - MP3_DECODER_DELAY);
+        assert_eq!(info.valid_frames, Some(source_frames));
+        let crc_at = header.tag_offset() + INFO_TAG_LEN + LAME_EXT_LEN - 2;
+        assert_eq!(
+            crc16_lame(&out[..crc_at]).to_be_bytes(),
+            [out[crc_at], out[crc_at + 1]]
+        );
+    }
+}
@@ -1,17 +1,30 @@
+pub mod aac_enc;
+pub mod adm;
+pub mod analyzer;
 pub mod app;
+pub mod artwork;
 pub mod audio;
 pub mod audio_asset;
 pub mod audio_capture;
 pub mod audio_io;
+pub mod caf;
+pub mod channel_layout;
 pub mod cli;
 pub mod crash_report;
 pub mod flac_meta;
+pub mod flac_stream;
+pub mod gapless;
 pub mod ipc;
 pub mod loop_markers;
 pub mod markers;
 pub mod metadata;
+pub mod metadata_template;
 pub mod meter;
+pub mod ogg_meta;
+pub mod opus_codec;
 pub mod plugin;
+pub mod tags;
+pub mod w64;
 pub mod wav_stream;
 pub mod wave;
 
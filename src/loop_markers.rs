@@ -22,12 +22,19 @@ pub fn read_loop_markers(path: &Path) -> Option<(u64, u64)> {
         "aiff" | "aif" => {
             crate::wave::read_aiff_loop_markers(path).map(|(s, e)| (s as u64, e as u64))
         }
+        "caf" => crate::caf::read_caf_loop_markers(path),
         "flac" => crate::flac_meta::read_flac_loop_markers(path)
             .ok()
             .flatten(),
         "mp3" => read_mp3_loop_markers(path).ok().flatten(),
         "m4a" => read_m4a_loop_markers(path).ok().flatten(),
-        // Formats without in-file loop support (ogg): JSON sidecar.
+        // Native comment first; a sidecar left by older builds still counts
+        // until the next write migrates it into the file.
+        "ogg" | "opus" => crate::ogg_meta::read_ogg_loop_markers(path)
+            .ok()
+            .flatten()
+            .or_else(|| read_sidecar_loop_markers(path)),
+        // Formats without in-file loop support: JSON sidecar.
         _ => read_sidecar_loop_markers(path),
     }
 }
@@ -55,11 +62,28 @@ pub fn write_loop_markers(path: &Path, loop_opt: Option<(u64, u64)>) -> Result<(
             let loop_opt = loop_opt.and_then(|(s, e)| u64_to_u32_pair(s, e));
             crate::wave::write_aiff_loop_markers(path, loop_opt)
         }
+        Some("caf") => crate::caf::write_caf_loop_markers(path, loop_opt),
         Some("flac") => crate::flac_meta::write_flac_loop_markers(path, loop_opt),
         Some("mp3") => write_mp3_loop_markers(path, loop_opt),
         Some("m4a") => write_m4a_loop_markers(path, loop_opt),
-        // Formats without in-file loop support (ogg): JSON sidecar so a save
-        // with a loop region no longer counts as a failure.
+        Some("ogg") | Some("opus") => {
+            if let Err(err) = crate::ogg_meta::write_ogg_loop_markers(path, loop_opt) {
+                // Streams the comment rewriter cannot handle (multiplexed or
+                // non-Vorbis/Opus Ogg) keep using the sidecar.
+                eprintln!(
+                    "ogg loop tags unavailable, using sidecar for {}: {err:#}",
+                    path.display()
+                );
+                return write_sidecar_loop_markers(path, loop_opt);
+            }
+            let stale_sidecar = loop_sidecar_path(path);
+            if stale_sidecar.is_file() {
+                let _ = std::fs::remove_file(stale_sidecar);
+            }
+            Ok(())
+        }
+        // Formats without in-file loop support: JSON sidecar so a save with a
+        // loop region no longer counts as a failure.
         _ => write_sidecar_loop_markers(path, loop_opt),
     }
 }
@@ -1,9 +1,13 @@
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 const MARKER_FILE_VERSION: u32 = 1;
+/// Vorbis comments carrying the marker / region lists (same JSON shape as
+/// the sidecars) for FLAC and Ogg.
+const MARKERS_COMMENT_KEY: &str = "NEOWAVES_MARKERS";
+const REGIONS_COMMENT_KEY: &str = "NEOWAVES_REGIONS";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MarkerEntry {
@@ -34,7 +38,7 @@ fn sidecar_path(path: &Path) -> PathBuf {
     path.with_extension("markers.json")
 }
 
-// ---- Regions: labeled [start, end) ranges, sidecar-persisted ----
+// ---- Regions: labeled [start, end) ranges ----
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RegionEntry {
@@ -63,36 +67,28 @@ fn region_sidecar_path(path: &Path) -> PathBuf {
     path.with_extension("regions.json")
 }
 
-/// Read the region sidecar (if any), mapped into `out_sr` sample space.
+/// Read regions (native for AIFF / CAF / FLAC / Ogg, otherwise the sidecar),
+/// mapped into `out_sr` sample space.
 pub fn read_regions(path: &Path, out_sr: u32, file_sr: u32) -> Result<Vec<RegionEntry>> {
+    // A sidecar from older builds is only consulted when the file has no
+    // native list yet; the next write migrates it.
+    if let Some(data) = read_native_regions(path, file_sr) {
+        let src_sr = stored_sample_rate(data.sample_rate, file_sr);
+        return Ok(map_regions_to_output(data.regions, out_sr, src_sr));
+    }
     let sidecar = region_sidecar_path(path);
     if !sidecar.is_file() {
         return Ok(Vec::new());
     }
     let bytes = std::fs::read(&sidecar)?;
     let data: RegionFile = serde_json::from_slice(&bytes)?;
-    let src_sr = if data.sample_rate > 0 {
-        data.sample_rate
-    } else {
-        file_sr.max(1)
-    };
-    let ratio = out_sr.max(1) as f64 / src_sr.max(1) as f64;
-    let mut regions: Vec<RegionEntry> = data
-        .regions
-        .into_iter()
-        .filter(|r| r.end > r.start)
-        .map(|r| RegionEntry {
-            start: ((r.start as f64) * ratio).round().max(0.0) as usize,
-            end: ((r.end as f64) * ratio).round().max(0.0) as usize,
-            label: r.label,
-        })
-        .collect();
-    regions.sort_by_key(|r| (r.start, r.end));
-    Ok(regions)
+    let src_sr = stored_sample_rate(data.sample_rate, file_sr);
+    Ok(map_regions_to_output(data.regions, out_sr, src_sr))
 }
 
-/// Write the region sidecar in `file_sr` sample space (regions given in
-/// `out_sr` space). An empty list removes the sidecar.
+/// Write regions in `file_sr` sample space (regions given in `out_sr`
+/// space): natively where the format allows, otherwise to the sidecar. An
+/// empty list clears the native list and removes the sidecar.
 pub fn write_regions(
     path: &Path,
     out_sr: u32,
@@ -100,12 +96,6 @@ pub fn write_regions(
     regions: &[RegionEntry],
 ) -> Result<()> {
     let sidecar = region_sidecar_path(path);
-    if regions.is_empty() {
-        if sidecar.is_file() {
-            let _ = std::fs::remove_file(&sidecar);
-        }
-        return Ok(());
-    }
     let src_sr = file_sr.max(1);
     let ratio = src_sr as f64 / out_sr.max(1) as f64;
     let stored: Vec<RegionRecord> = regions
@@ -122,10 +112,246 @@ pub fn write_regions(
         sample_rate: src_sr,
         regions: stored,
     };
+    if let Some(store) = native_cue_store(path) {
+        match write_native_regions(path, store, &payload) {
+            Ok(()) => {
+                if sidecar.is_file() {
+                    let _ = std::fs::remove_file(&sidecar);
+                }
+                return Ok(());
+            }
+            Err(err) => eprintln!(
+                "native regions unavailable, using sidecar for {}: {err:#}",
+                path.display()
+            ),
+        }
+    }
+    if regions.is_empty() {
+        if sidecar.is_file() {
+            let _ = std::fs::remove_file(&sidecar);
+        }
+        return Ok(());
+    }
     std::fs::write(sidecar, serde_json::to_vec_pretty(&payload)?)?;
     Ok(())
 }
 
+fn map_regions_to_output(records: Vec<RegionRecord>, out_sr: u32, src_sr: u32) -> Vec<RegionEntry> {
+    let ratio = out_sr.max(1) as f64 / src_sr.max(1) as f64;
+    let mut regions: Vec<RegionEntry> = records
+        .into_iter()
+        .filter(|r| r.end > r.start)
+        .map(|r| RegionEntry {
+            start: ((r.start as f64) * ratio).round().max(0.0) as usize,
+            end: ((r.end as f64) * ratio).round().max(0.0) as usize,
+            label: r.label,
+        })
+        .collect();
+    regions.sort_by_key(|r| (r.start, r.end));
+    regions
+}
+
+fn stored_sample_rate(stored: u32, file_sr: u32) -> u32 {
+    if stored > 0 {
+        stored
+    } else {
+        file_sr.max(1)
+    }
+}
+
+/// Formats that keep markers and regions in the file itself. WAV markers go
+/// through the `cue ` / `adtl` path below and are handled separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NativeCueStore {
+    /// AIFF `MARK` entries with `COMT` labels (`wave::read_aiff_cues`).
+    Aiff,
+    /// CAF `mark` / `regn` entries named through `strg` (`caf::read_caf_cues`).
+    Caf,
+    /// `NEOWAVES_MARKERS` / `NEOWAVES_REGIONS` Vorbis comments (FLAC, Ogg).
+    VorbisComment,
+}
+
+fn native_cue_store(path: &Path) -> Option<NativeCueStore> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "aif" | "aiff" => Some(NativeCueStore::Aiff),
+        "caf" => Some(NativeCueStore::Caf),
+        "flac" | "ogg" | "opus" => Some(NativeCueStore::VorbisComment),
+        _ => None,
+    }
+}
+
+fn is_flac_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.eq_ignore_ascii_case("flac"))
+        .unwrap_or(false)
+}
+
+fn read_cue_comment(path: &Path, key: &str) -> Option<String> {
+    let comments = if is_flac_path(path) {
+        crate::flac_meta::read_flac_vorbis_comments(path)
+    } else {
+        crate::ogg_meta::read_ogg_vorbis_comments(path)
+    }
+    .ok()?;
+    comments
+        .into_iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+fn write_cue_comment(path: &Path, key: &str, value: Option<String>) -> Result<()> {
+    if is_flac_path(path) {
+        crate::flac_meta::update_flac_vorbis_comments(path, &[(key, value)])
+    } else {
+        crate::ogg_meta::update_ogg_vorbis_comments(path, &[(key, value)])
+    }
+}
+
+fn aiff_position(sample: u64) -> Result<u32> {
+    u32::try_from(sample).context("position exceeds the 32-bit AIFF MARK range")
+}
+
+fn read_native_markers(path: &Path, file_sr: u32) -> Option<MarkerFile> {
+    match native_cue_store(path)? {
+        NativeCueStore::Aiff => {
+            let cues = crate::wave::read_aiff_cues(path).ok()?;
+            (!cues.markers.is_empty()).then(|| MarkerFile {
+                version: MARKER_FILE_VERSION,
+                sample_rate: file_sr.max(1),
+                markers: cues
+                    .markers
+                    .into_iter()
+                    .map(|(sample, label)| MarkerRecord {
+                        sample: sample as u64,
+                        label,
+                    })
+                    .collect(),
+            })
+        }
+        NativeCueStore::Caf => {
+            let cues = crate::caf::read_caf_cues(path).ok()?;
+            (!cues.markers.is_empty()).then(|| MarkerFile {
+                version: MARKER_FILE_VERSION,
+                sample_rate: file_sr.max(1),
+                markers: cues
+                    .markers
+                    .into_iter()
+                    .map(|(sample, label)| MarkerRecord { sample, label })
+                    .collect(),
+            })
+        }
+        NativeCueStore::VorbisComment => {
+            serde_json::from_str(&read_cue_comment(path, MARKERS_COMMENT_KEY)?).ok()
+        }
+    }
+}
+
+fn write_native_markers(path: &Path, store: NativeCueStore, payload: &MarkerFile) -> Result<()> {
+    match store {
+        NativeCueStore::Aiff => {
+            let mut cues = crate::wave::read_aiff_cues(path)?;
+            cues.markers = payload
+                .markers
+                .iter()
+                .map(|m| Ok((aiff_position(m.sample)?, m.label.clone())))
+                .collect::<Result<_>>()?;
+            crate::wave::write_aiff_cues(path, &cues)
+        }
+        NativeCueStore::Caf => {
+            let mut cues = crate::caf::read_caf_cues(path)?;
+            cues.markers = payload
+                .markers
+                .iter()
+                .map(|m| (m.sample, m.label.clone()))
+                .collect();
+            crate::caf::write_caf_cues(path, &cues)
+        }
+        NativeCueStore::VorbisComment => {
+            let value = if payload.markers.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(payload)?)
+            };
+            write_cue_comment(path, MARKERS_COMMENT_KEY, value)
+        }
+    }
+}
+
+fn read_native_regions(path: &Path, file_sr: u32) -> Option<RegionFile> {
+    match native_cue_store(path)? {
+        NativeCueStore::Aiff => {
+            let cues = crate::wave::read_aiff_cues(path).ok()?;
+            (!cues.regions.is_empty()).then(|| RegionFile {
+                version: REGION_FILE_VERSION,
+                sample_rate: file_sr.max(1),
+                regions: cues
+                    .regions
+                    .into_iter()
+                    .map(|(start, end, label)| RegionRecord {
+                        start: start as u64,
+                        end: end as u64,
+                        label,
+                    })
+                    .collect(),
+            })
+        }
+        NativeCueStore::Caf => {
+            let cues = crate::caf::read_caf_cues(path).ok()?;
+            (!cues.regions.is_empty()).then(|| RegionFile {
+                version: REGION_FILE_VERSION,
+                sample_rate: file_sr.max(1),
+                regions: cues
+                    .regions
+                    .into_iter()
+                    .map(|(start, end, label)| RegionRecord { start, end, label })
+                    .collect(),
+            })
+        }
+        NativeCueStore::VorbisComment => {
+            serde_json::from_str(&read_cue_comment(path, REGIONS_COMMENT_KEY)?).ok()
+        }
+    }
+}
+
+fn write_native_regions(path: &Path, store: NativeCueStore, payload: &RegionFile) -> Result<()> {
+    match store {
+        NativeCueStore::Aiff => {
+            let mut cues = crate::wave::read_aiff_cues(path)?;
+            cues.regions = payload
+                .regions
+                .iter()
+                .map(|r| {
+                    Ok((
+                        aiff_position(r.start)?,
+                        aiff_position(r.end)?,
+                        r.label.clone(),
+                    ))
+                })
+                .collect::<Result<_>>()?;
+            crate::wave::write_aiff_cues(path, &cues)
+        }
+        NativeCueStore::Caf => {
+            let mut cues = crate::caf::read_caf_cues(path)?;
+            cues.regions = payload
+                .regions
+                .iter()
+                .map(|r| (r.start, r.end, r.label.clone()))
+                .collect();
+            crate::caf::write_caf_cues(path, &cues)
+        }
+        NativeCueStore::VorbisComment => {
+            let value = if payload.regions.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(payload)?)
+            };
+            write_cue_comment(path, REGIONS_COMMENT_KEY, value)
+        }
+    }
+}
+
 pub fn read_markers(path: &Path, out_sr: u32, file_sr: u32) -> Result<Vec<MarkerEntry>> {
     let is_wav = path
         .extension()
@@ -138,6 +364,12 @@ pub fn read_markers(path: &Path, out_sr: u32, file_sr: u32) -> Result<Vec<Marker
             return Ok(map_markers_to_output(markers, out_sr, file_sr));
         }
     }
+    // As with regions, a leftover sidecar only counts while the file has no
+    // native list; the next write migrates it.
+    if let Some(data) = read_native_markers(path, file_sr) {
+        let src_sr = stored_sample_rate(data.sample_rate, file_sr);
+        return Ok(map_markers_to_output(data.markers, out_sr, src_sr));
+    }
     if !sidecar.is_file() {
         return Ok(Vec::new());
     }
@@ -161,11 +393,7 @@ pub fn read_markers(path: &Path, out_sr: u32, file_sr: u32) -> Result<Vec<Marker
             }
         }
     };
-    let src_sr = if data.sample_rate > 0 {
-        data.sample_rate
-    } else {
-        file_sr.max(1)
-    };
+    let src_sr = stored_sample_rate(data.sample_rate, file_sr);
     Ok(map_markers_to_output(data.markers, out_sr, src_sr))
 }
 
@@ -197,6 +425,21 @@ pub fn write_markers(
         sample_rate: src_sr,
         markers: stored,
     };
+    if let Some(store) = native_cue_store(path) {
+        match write_native_markers(path, store, &payload) {
+            Ok(()) => {
+                if sidecar.is_file() {
+                    let _ = std::fs::remove_file(&sidecar);
+                }
+                return Ok(());
+            }
+            // e.g. multiplexed Ogg or positions past the AIFF range.
+            Err(err) => eprintln!(
+                "native markers unavailable, using sidecar for {}: {err:#}",
+                path.display()
+            ),
+        }
+    }
     let text = serde_json::to_vec_pretty(&payload)?;
     std::fs::write(sidecar, text)?;
     Ok(())
@@ -455,6 +698,128 @@ mod region_tests {
         let _ = std::fs::remove_dir_all(path.parent().unwrap());
     }
 
+    fn sine_chans(sr: u32) -> Vec<Vec<f32>> {
+        let tone: Vec<f32> = (0..sr as usize / 2)
+            .map(|i| ((i as f32 / sr as f32) * 440.0 * std::f32::consts::TAU).sin() * 0.25)
+            .collect();
+        vec![tone.clone(), tone]
+    }
+
+    #[test]
+    fn aiff_markers_and_regions_are_native_and_keep_the_loop() {
+        let path = temp_wav_path("aiff_native").with_file_name("audio.aiff");
+        crate::wave::export_channels_audio(&sine_chans(48_000), 48_000, &path)
+            .expect("export aiff");
+        crate::loop_markers::write_loop_markers(&path, Some((1_000, 9_000))).expect("loop");
+        let markers = vec![
+            MarkerEntry {
+                sample: 480,
+                label: "hit".into(),
+            },
+            MarkerEntry {
+                sample: 12_000,
+                label: "ドン — a label well past the pstring limit ".repeat(8),
+            },
+        ];
+        let regions = vec![RegionEntry {
+            start: 2_000,
+            end: 6_000,
+            label: "verse".into(),
+        }];
+        write_markers(&path, 48_000, 48_000, &markers).expect("write markers");
+        write_regions(&path, 48_000, 48_000, &regions).expect("write regions");
+        assert!(!sidecar_path(&path).exists());
+        assert!(!region_sidecar_path(&path).exists());
+        assert_eq!(read_markers(&path, 48_000, 48_000).unwrap(), markers);
+        assert_eq!(read_regions(&path, 48_000, 48_000).unwrap(), regions);
+        assert_eq!(
+            crate::loop_markers::read_loop_markers(&path),
+            Some((1_000, 9_000))
+        );
+        // Rewriting the loop leaves markers and regions in place.
+        crate::loop_markers::write_loop_markers(&path, Some((1_500, 9_500))).expect("loop 2");
+        assert_eq!(read_markers(&path, 48_000, 48_000).unwrap(), markers);
+        assert_eq!(read_regions(&path, 48_000, 48_000).unwrap(), regions);
+        write_markers(&path, 48_000, 48_000, &[]).expect("clear markers");
+        assert!(read_markers(&path, 48_000, 48_000).unwrap().is_empty());
+        assert_eq!(read_regions(&path, 48_000, 48_000).unwrap(), regions);
+        let (decoded, _) = crate::audio_io::decode_audio_multi(&path).expect("decode aiff");
+        assert_eq!(decoded[0].len(), 24_000);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn caf_markers_and_regions_are_native_and_keep_the_loop() {
+        let path = temp_wav_path("caf_native").with_file_name("audio.caf");
+        crate::wave::export_channels_audio(&sine_chans(48_000), 48_000, &path).expect("export caf");
+        crate::loop_markers::write_loop_markers(&path, Some((1_000, 9_000))).expect("loop");
+        let markers = vec![
+            MarkerEntry {
+                sample: 480,
+                label: "hit".into(),
+            },
+            MarkerEntry {
+                sample: 12_000,
+                label: "ドン".into(),
+            },
+        ];
+        let regions = vec![RegionEntry {
+            start: 2_000,
+            end: 6_000,
+            label: "verse".into(),
+        }];
+        write_markers(&path, 48_000, 48_000, &markers).expect("write markers");
+        write_regions(&path, 48_000, 48_000, &regions).expect("write regions");
+        assert!(!sidecar_path(&path).exists());
+        assert!(!region_sidecar_path(&path).exists());
+        assert_eq!(read_markers(&path, 48_000, 48_000).unwrap(), markers);
+        assert_eq!(read_regions(&path, 48_000, 48_000).unwrap(), regions);
+        assert_eq!(
+            crate::loop_markers::read_loop_markers(&path),
+            Some((1_000, 9_000))
+        );
+        write_markers(&path, 48_000, 48_000, &[]).expect("clear markers");
+        assert!(read_markers(&path, 48_000, 48_000).unwrap().is_empty());
+        assert_eq!(read_regions(&path, 48_000, 48_000).unwrap(), regions);
+        let (decoded, _) = crate::audio_io::decode_audio_multi(&path).expect("decode caf");
+        assert_eq!(decoded[0].len(), 24_000);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn flac_markers_and_regions_migrate_sidecars_into_comments() {
+        let path = temp_wav_path("flac_native").with_file_name("audio.flac");
+        crate::wave::export_channels_audio(&sine_chans(44_100), 44_100, &path)
+            .expect("export flac");
+        // Sidecars left behind by an older build...
+        std::fs::write(
+            sidecar_path(&path),
+            br#"{"version":1,"sample_rate":44100,"markers":[{"sample":441,"label":"a"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            region_sidecar_path(&path),
+            br#"{"version":1,"sample_rate":44100,"regions":[{"start":10,"end":20,"label":"r"}]}"#,
+        )
+        .unwrap();
+        let markers = read_markers(&path, 88_200, 44_100).unwrap();
+        assert_eq!(markers[0].sample, 882);
+        let regions = read_regions(&path, 88_200, 44_100).unwrap();
+        // ...move into the file on the next write.
+        write_markers(&path, 88_200, 44_100, &markers).unwrap();
+        write_regions(&path, 88_200, 44_100, &regions).unwrap();
+        assert!(!sidecar_path(&path).exists());
+        assert!(!region_sidecar_path(&path).exists());
+        let comments = crate::flac_meta::read_flac_vorbis_comments(&path).unwrap();
+        assert!(comments.iter().any(|(k, _)| k == MARKERS_COMMENT_KEY));
+        assert!(comments.iter().any(|(k, _)| k == REGIONS_COMMENT_KEY));
+        assert_eq!(read_markers(&path, 88_200, 44_100).unwrap(), markers);
+        assert_eq!(read_regions(&path, 44_100, 44_100).unwrap()[0].end, 20);
+        write_regions(&path, 88_200, 44_100, &[]).unwrap();
+        assert!(read_regions(&path, 88_200, 44_100).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
     #[test]
     fn rf64_markers_use_64_bit_sidecar_positions() {
         let path = temp_wav_path("rf64_markers");
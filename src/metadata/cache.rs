@@ -2,7 +2,12 @@
 //!
 //! All SQLite access is intended to happen on the metadata worker. The GUI
 //! only communicates with [`MetadataSummaryPool`] through channels.
+//!
+//! Every stored summary is also written to an FTS5 index (one row per
+//! canonical path, newest summary wins) so list search can match metadata
+//! text of files whose summaries are no longer held in memory.
 
+use super::fulltext::{FulltextColumn, FulltextDocument, FulltextQuery};
 use super::{
     summarize_path, MetadataSummary, ScanBudget, SummaryRequest, ASWG_SCHEMA_VERSION,
     PARSER_SCHEMA_VERSION,
@@ -139,8 +144,53 @@ impl MetadataCache {
             );
             CREATE INDEX IF NOT EXISTS metadata_summary_lru
                 ON metadata_summary(last_access);
+            CREATE TABLE IF NOT EXISTS metadata_fulltext_source (
+                id INTEGER PRIMARY KEY,
+                canonical_path TEXT NOT NULL UNIQUE,
+                source_path TEXT NOT NULL
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS metadata_fulltext USING fts5(
+                name, \"desc\", ucs, tags, other,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
             ",
         )?;
+        self.backfill_fulltext()?;
+        Ok(())
+    }
+
+    /// Index summaries stored before the full-text table existed.
+    fn backfill_fulltext(&self) -> Result<()> {
+        let indexed: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM metadata_fulltext_source",
+            [],
+            |row| row.get(0),
+        )?;
+        if indexed > 0 {
+            return Ok(());
+        }
+        let transaction = self.connection.unchecked_transaction()?;
+        {
+            let mut statement = self.connection.prepare(
+                "
+                SELECT canonical_path, summary_json FROM metadata_summary
+                WHERE parser_schema = ?1 AND normalizer_version = ?2
+                ORDER BY last_access ASC
+                ",
+            )?;
+            let rows = statement.query_map(
+                params![PARSER_SCHEMA_VERSION, NORMALIZER_VERSION.as_str()],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )?;
+            for row in rows {
+                let (canonical_path, summary_json) = row?;
+                let Ok(summary) = serde_json::from_str::<MetadataSummary>(&summary_json) else {
+                    continue;
+                };
+                self.index_fulltext(&canonical_path, &summary.source, &summary)?;
+            }
+        }
+        transaction.commit()?;
         Ok(())
     }
 
@@ -166,7 +216,7 @@ impl MetadataCache {
             Some(previous) => merge_summary_coverage(previous, scanned),
             None => scanned,
         };
-        self.store(&key, &summary)?;
+        self.store(&key, path, &summary)?;
         self.prune_if_needed()?;
         Ok(CacheLookup {
             summary,
@@ -226,9 +276,10 @@ impl MetadataCache {
         Ok(Some(summary))
     }
 
-    fn store(&self, key: &CacheKey, summary: &MetadataSummary) -> Result<()> {
+    fn store(&self, key: &CacheKey, source: &Path, summary: &MetadataSummary) -> Result<()> {
         let coverage_json = serde_json::to_string(&summary.coverage)?;
         let summary_json = serde_json::to_string(summary)?;
+        let transaction = self.connection.unchecked_transaction()?;
         self.connection.execute(
             "
             INSERT OR REPLACE INTO metadata_summary (
@@ -249,9 +300,74 @@ impl MetadataCache {
                 summary_json.len() as i64,
             ],
         )?;
+        self.index_fulltext(&key.canonical_path, source, summary)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn index_fulltext(
+        &self,
+        canonical_path: &str,
+        source: &Path,
+        summary: &MetadataSummary,
+    ) -> Result<()> {
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let document = FulltextDocument::from_summary(&name, summary);
+        let id: i64 = self.connection.query_row(
+            "
+            INSERT INTO metadata_fulltext_source (canonical_path, source_path)
+            VALUES (?1, ?2)
+            ON CONFLICT(canonical_path) DO UPDATE SET source_path = excluded.source_path
+            RETURNING id
+            ",
+            params![canonical_path, source.to_string_lossy()],
+            |row| row.get(0),
+        )?;
+        self.connection.execute(
+            "DELETE FROM metadata_fulltext WHERE rowid = ?1",
+            params![id],
+        )?;
+        self.connection.execute(
+            "
+            INSERT INTO metadata_fulltext (rowid, name, \"desc\", ucs, tags, other)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ",
+            params![
+                id,
+                document.column(FulltextColumn::Name),
+                document.column(FulltextColumn::Desc),
+                document.column(FulltextColumn::Ucs),
+                document.column(FulltextColumn::Tags),
+                document.column(FulltextColumn::Other),
+            ],
+        )?;
         Ok(())
     }
 
+    /// Source paths whose indexed metadata matches every term of `query`.
+    pub fn search_fulltext(&self, query: &FulltextQuery) -> Result<Vec<PathBuf>> {
+        let Some(expression) = query.fts_expression() else {
+            return Ok(Vec::new());
+        };
+        let mut statement = self.connection.prepare(
+            "
+            SELECT source.source_path
+            FROM metadata_fulltext
+            JOIN metadata_fulltext_source AS source ON source.id = metadata_fulltext.rowid
+            WHERE metadata_fulltext MATCH ?1
+            ",
+        )?;
+        let rows = statement.query_map(params![expression], |row| row.get::<_, String>(0))?;
+        let mut paths = Vec::new();
+        for row in rows {
+            paths.push(PathBuf::from(row?));
+        }
+        Ok(paths)
+    }
+
     fn prune_if_needed(&self) -> Result<()> {
         let stored: i64 = self.connection.query_row(
             "SELECT COALESCE(SUM(stored_bytes), 0) FROM metadata_summary",
@@ -306,6 +422,25 @@ impl MetadataCache {
                 ",
                 params![victim.0, victim.1, victim.2, victim.3],
             )?;
+            // Drop the index row once no summary of the path is left.
+            self.connection.execute(
+                "
+                DELETE FROM metadata_fulltext WHERE rowid IN (
+                    SELECT id FROM metadata_fulltext_source WHERE canonical_path=?1
+                      AND NOT EXISTS (
+                        SELECT 1 FROM metadata_summary WHERE canonical_path=?1
+                      )
+                )
+                ",
+                params![victim.0],
+            )?;
+            self.connection.execute(
+                "
+                DELETE FROM metadata_fulltext_source WHERE canonical_path=?1
+                  AND NOT EXISTS (SELECT 1 FROM metadata_summary WHERE canonical_path=?1)
+                ",
+                params![victim.0],
+            )?;
         }
         Ok(())
     }
@@ -368,11 +503,12 @@ fn cache_key(path: &Path) -> Result<CacheKey> {
 
 fn coverage_satisfies(coverage: &[String], request: &SummaryRequest) -> bool {
     let covered: HashSet<&str> = coverage.iter().map(String::as_str).collect();
-    let fields = request.fields.is_empty() && covered.contains("__all__")
-        || request
-            .fields
-            .iter()
-            .all(|field| covered.contains(field.as_str()) || covered.contains("__all__"));
+    let fields = covered.contains("__all__")
+        || !request.fields.is_empty()
+            && request
+                .fields
+                .iter()
+                .all(|field| covered.contains(field.as_str()));
     fields && (!request.include_raw || covered.contains("__raw__"))
 }
 
@@ -409,6 +545,7 @@ fn merge_summary_coverage(
     previous.completion = current.completion;
     previous.unknown_nodes = current.unknown_nodes;
     previous.diagnostics = current.diagnostics;
+    previous.adm_objects = current.adm_objects;
     previous
 }
 
@@ -437,11 +574,24 @@ pub struct SummaryUpdate {
     pub cache_hit: bool,
 }
 
+pub type FulltextSearchResult = Result<Vec<PathBuf>, String>;
+
+struct FulltextSearchTask {
+    query: FulltextQuery,
+    reply: mpsc::Sender<FulltextSearchResult>,
+}
+
 struct QueueInner {
     tasks: HashMap<PathBuf, SummaryTask>,
     high: VecDeque<PathBuf>,
     low: VecDeque<PathBuf>,
     running: HashMap<PathBuf, Arc<AtomicBool>>,
+    search: Option<FulltextSearchTask>,
+}
+
+enum WorkerJob {
+    Summary(SummaryTask, Arc<AtomicBool>),
+    Search(FulltextSearchTask),
 }
 
 struct SharedQueue {
@@ -463,6 +613,7 @@ impl MetadataSummaryPool {
                 high: VecDeque::new(),
                 low: VecDeque::new(),
                 running: HashMap::new(),
+                search: None,
             }),
             wake: Condvar::new(),
             stop: AtomicBool::new(false),
@@ -539,6 +690,17 @@ impl MetadataSummaryPool {
         }
     }
 
+    /// Query the persistent full-text index on the worker. Searches run ahead
+    /// of queued summaries and are not held back by playback protection; a
+    /// newer query replaces one that has not started yet.
+    pub fn search(&self, query: FulltextQuery) -> mpsc::Receiver<FulltextSearchResult> {
+        let (reply, result) = mpsc::channel();
+        let mut inner = self.shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.search = Some(FulltextSearchTask { query, reply });
+        self.shared.wake.notify_one();
+        result
+    }
+
     pub fn cancel(&self, path: &Path) {
         let mut inner = self.shared.inner.lock().unwrap_or_else(|e| e.into_inner());
         inner.tasks.remove(path);
@@ -566,12 +728,15 @@ fn summary_worker(
     let mut cache: Option<MetadataCache> = None;
     let mut cache_initialized = false;
     loop {
-        let (task, cancel) = {
+        let job = {
             let mut inner = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
             loop {
                 if shared.stop.load(Ordering::Relaxed) {
                     return;
                 }
+                if let Some(search) = inner.search.take() {
+                    break WorkerJob::Search(search);
+                }
                 if shared.paused.load(Ordering::Relaxed) {
                     inner = shared
                         .wake
@@ -586,7 +751,7 @@ fn summary_worker(
                     };
                     let cancel = Arc::new(AtomicBool::new(false));
                     inner.running.insert(path, Arc::clone(&cancel));
-                    break (task, cancel);
+                    break WorkerJob::Summary(task, cancel);
                 }
                 inner = shared
                     .wake
@@ -600,6 +765,19 @@ fn summary_worker(
                 .and_then(|path| MetadataCache::open(path).ok());
             cache_initialized = true;
         }
+        let (task, cancel) = match job {
+            WorkerJob::Summary(task, cancel) => (task, cancel),
+            WorkerJob::Search(search) => {
+                let result = match cache.as_ref() {
+                    Some(cache) => cache
+                        .search_fulltext(&search.query)
+                        .map_err(|error| format!("{error:#}")),
+                    None => Ok(Vec::new()),
+                };
+                let _ = search.reply.send(result);
+                continue;
+            }
+        };
         let result = if let Some(cache) = cache.as_mut() {
             cache
                 .get_or_scan(
@@ -645,36 +823,55 @@ fn summary_worker(
     }
 }
 
+/// A summary held in memory together with its folded full-text document,
+/// built once on insert so list filtering does not rebuild it per row.
+struct MemoryEntry {
+    summary: Arc<MetadataSummary>,
+    document: FulltextDocument,
+    bytes: usize,
+}
+
 #[derive(Default)]
 pub struct SummaryMemoryCache {
-    entries: HashMap<PathBuf, Arc<MetadataSummary>>,
+    entries: HashMap<PathBuf, MemoryEntry>,
     order: VecDeque<PathBuf>,
     estimated_bytes: usize,
 }
 
 impl SummaryMemoryCache {
     pub fn get(&mut self, path: &Path) -> Option<Arc<MetadataSummary>> {
-        let value = self.entries.get(path).cloned()?;
+        let value = Arc::clone(&self.entries.get(path)?.summary);
         self.touch(path);
         Some(value)
     }
 
     pub fn peek(&self, path: &Path) -> Option<&Arc<MetadataSummary>> {
-        self.entries.get(path)
+        self.entries.get(path).map(|entry| &entry.summary)
+    }
+
+    /// Full-text document of a cached summary, named after the file.
+    pub fn document(&self, path: &Path) -> Option<&FulltextDocument> {
+        self.entries.get(path).map(|entry| &entry.document)
     }
 
     pub fn insert(&mut self, path: PathBuf, summary: MetadataSummary) -> Arc<MetadataSummary> {
-        if let Some(previous) = self.entries.remove(&path) {
-            self.estimated_bytes = self
-                .estimated_bytes
-                .saturating_sub(estimate_summary_bytes(&previous));
-            self.order.retain(|candidate| candidate != &path);
-        }
+        self.remove(&path);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let document = FulltextDocument::from_summary(&name, &summary);
+        let bytes = estimate_summary_bytes(&summary).saturating_add(document.text_bytes());
         let summary = Arc::new(summary);
-        self.estimated_bytes = self
-            .estimated_bytes
-            .saturating_add(estimate_summary_bytes(&summary));
-        self.entries.insert(path.clone(), Arc::clone(&summary));
+        self.estimated_bytes = self.estimated_bytes.saturating_add(bytes);
+        self.entries.insert(
+            path.clone(),
+            MemoryEntry {
+                summary: Arc::clone(&summary),
+                document,
+                bytes,
+            },
+        );
         self.order.push_back(path);
         while self.entries.len() > MEMORY_SUMMARY_LIMIT || self.estimated_bytes > MEMORY_BYTE_LIMIT
         {
@@ -682,14 +879,19 @@ impl SummaryMemoryCache {
                 break;
             };
             if let Some(removed) = self.entries.remove(&victim) {
-                self.estimated_bytes = self
-                    .estimated_bytes
-                    .saturating_sub(estimate_summary_bytes(&removed));
+                self.estimated_bytes = self.estimated_bytes.saturating_sub(removed.bytes);
             }
         }
         summary
     }
 
+    pub fn remove(&mut self, path: &Path) {
+        if let Some(removed) = self.entries.remove(path) {
+            self.estimated_bytes = self.estimated_bytes.saturating_sub(removed.bytes);
+            self.order.retain(|candidate| candidate != path);
+        }
+    }
+
     fn touch(&mut self, path: &Path) {
         self.order.retain(|candidate| candidate != path);
         self.order.push_back(path.to_path_buf());
@@ -731,6 +933,27 @@ mod tests {
         wav
     }
 
+    fn wav_with_info(entries: &[(&[u8; 4], &str)]) -> Vec<u8> {
+        let mut info = b"INFO".to_vec();
+        for (id, text) in entries {
+            let mut value = text.as_bytes().to_vec();
+            value.push(0);
+            info.extend_from_slice(*id);
+            info.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            info.extend_from_slice(&value);
+            if value.len() % 2 == 1 {
+                info.push(0);
+            }
+        }
+        let mut wav = minimal_wav();
+        wav.extend_from_slice(b"LIST");
+        wav.extend_from_slice(&(info.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&info);
+        let riff_len = (wav.len() - 8) as u32;
+        wav[4..8].copy_from_slice(&riff_len.to_le_bytes());
+        wav
+    }
+
     #[test]
     fn summary_pool_pause_defers_disk_scan_until_resumed() {
         let dir = std::env::temp_dir().join(format!(
@@ -875,4 +1098,90 @@ mod tests {
         drop(cache);
         let _ = std::fs::remove_dir_all(dir);
     }
+
+    #[test]
+    fn fulltext_index_follows_stored_summaries() {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves-metadata-fulltext-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rain = dir.join("roof_01.wav");
+        let wind = dir.join("gust_01.wav");
+        let cafe = dir.join("terrace_01.wav");
+        let db = dir.join("cache.sqlite3");
+        std::fs::write(
+            &rain,
+            wav_with_info(&[(b"ICMT", "Heavy rain on tin roof"), (b"INAM", "Storm")]),
+        )
+        .unwrap();
+        std::fs::write(&wind, wav_with_info(&[(b"ICMT", "Wind gust, light rain")])).unwrap();
+        std::fs::write(&cafe, wav_with_info(&[(b"ICMT", "Café terrace, Señora")])).unwrap();
+        let mut cache = MetadataCache::open(&db).unwrap();
+        let mut documents = Vec::new();
+        for path in [&rain, &wind, &cafe] {
+            let lookup = cache
+                .get_or_scan(path, SummaryRequest::default(), ScanBudget::list(), None)
+                .unwrap();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let document = FulltextDocument::from_summary(&name, &lookup.summary);
+            documents.push((path.clone(), document));
+        }
+        let search = |cache: &MetadataCache, query: &str| {
+            let mut hits = cache.search_fulltext(&FulltextQuery::parse(query)).unwrap();
+            hits.sort();
+            hits
+        };
+        assert_eq!(search(&cache, "rain"), vec![wind.clone(), rain.clone()]);
+        assert_eq!(search(&cache, "rain heavy roof"), vec![rain.clone()]);
+        assert_eq!(search(&cache, "desc:gust"), vec![wind.clone()]);
+        assert_eq!(search(&cache, "tags:storm name:roof"), vec![rain.clone()]);
+        assert!(search(&cache, "desc:storm").is_empty());
+        assert_eq!(search(&cache, "cafe senora"), vec![cafe.clone()]);
+        // The in-memory matcher used for summaries outside the index agrees
+        // with FTS5 on token prefixes, phrases and columns.
+        for query in [
+            "rain",
+            "ain",
+            "hea ti",
+            "\"heavy rain\"",
+            "\"heav rain\"",
+            "desc:gust",
+            "desc:storm",
+            "tags:storm name:roof",
+            "gust_0",
+            "wav",
+            "cafe",
+            "Café",
+            "CAFE\u{301}",
+            "\"cafe terrace\"",
+            "senora",
+        ] {
+            let parsed = FulltextQuery::parse(query);
+            let mut in_memory: Vec<PathBuf> = documents
+                .iter()
+                .filter(|(_, document)| parsed.matches(document))
+                .map(|(path, _)| path.clone())
+                .collect();
+            in_memory.sort();
+            assert_eq!(in_memory, search(&cache, query), "query {query}");
+        }
+
+        std::fs::write(&rain, wav_with_info(&[(b"ICMT", "Thunder clap")])).unwrap();
+        cache
+            .get_or_scan(&rain, SummaryRequest::default(), ScanBudget::list(), None)
+            .unwrap();
+        assert_eq!(search(&cache, "rain"), vec![wind.clone()]);
+        drop(cache);
+
+        // Reopening keeps the index; it is only rebuilt when missing.
+        let cache = MetadataCache::open(&db).unwrap();
+        assert_eq!(search(&cache, "thunder"), vec![rain.clone()]);
+        drop(cache);
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
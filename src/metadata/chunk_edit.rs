@@ -0,0 +1,610 @@
+//! Raw top-level chunk edits on RIFF / RF64 / BW64 WAVE and AIFF / AIFC
+//! containers: remove, insert (from bytes), reorder and payload export.
+//!
+//! The chunk list comes from the inspector's [`MetadataDocument`] (children
+//! of the container root). Edits are applied to that list and the container
+//! is rebuilt with the [`repair`](super::repair) layout writer, either into a
+//! copy or over the source with a `<name>.bak` backup. `ds64` is never listed;
+//! it is regenerated for RF64 / BW64 output.
+
+use super::repair::{
+    aiff_layout, read_at, wave_layout, wave_needs_rf64, write_layout, ChunkBody, PlannedChunk,
+};
+use super::{
+    aiff_chunk_known, fourcc, inspect_path, paths_refer_to_same_file, read_u16_le, read_u64_le,
+    riff_chunk_known, Completion, ContainerKind, InspectOptions, MetadataDocument, NodeId,
+    ParseStatus, PayloadRef, ScanBudget,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChunkEntry {
+    pub index: usize,
+    pub id: String,
+    /// Header offset in the source; `None` for inserted chunks.
+    pub offset: Option<u64>,
+    pub length: u64,
+    pub known: bool,
+}
+
+/// One edit of the top-level chunk list. Indices refer to the list as left
+/// by the previous edit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkEdit {
+    Remove {
+        index: usize,
+    },
+    /// `index == len` appends.
+    Insert {
+        index: usize,
+        id: [u8; 4],
+        payload: Vec<u8>,
+    },
+    /// `to` is the position in the resulting list.
+    Move {
+        from: usize,
+        to: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkEditOutput {
+    Copy(PathBuf),
+    /// Replace the source, keeping the previous file as `<name>.bak`.
+    InPlace,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChunkEditReport {
+    pub source_container: ContainerKind,
+    pub output_container: ContainerKind,
+    pub output: PathBuf,
+    pub backup: Option<PathBuf>,
+    pub output_len: u64,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+pub fn chunk_edit_supported(container: ContainerKind) -> bool {
+    matches!(
+        container,
+        ContainerKind::RiffWave
+            | ContainerKind::Rf64
+            | ContainerKind::Bw64
+            | ContainerKind::Aiff
+            | ContainerKind::Aifc
+    )
+}
+
+/// Top-level chunks of `document` in file order, `ds64` excluded.
+pub fn chunk_entries(document: &MetadataDocument) -> Result<Vec<ChunkEntry>> {
+    Ok(editable_nodes(document)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let node = &document.nodes[id as usize];
+            ChunkEntry {
+                index,
+                id: node.name.clone(),
+                offset: Some(node.offset),
+                length: node.readable_size,
+                known: node.known,
+            }
+        })
+        .collect())
+}
+
+/// Position of `node` in [`chunk_entries`], when it is a top-level chunk.
+pub fn chunk_index_for_node(document: &MetadataDocument, node: NodeId) -> Option<usize> {
+    editable_nodes(document)
+        .ok()?
+        .into_iter()
+        .position(|id| id == node)
+}
+
+pub fn chunk_payload(document: &MetadataDocument, index: usize) -> Result<PayloadRef> {
+    let nodes = editable_nodes(document)?;
+    let id = nodes
+        .get(index)
+        .ok_or_else(|| anyhow!("chunk index {index} is out of range (0..{})", nodes.len()))?;
+    Ok(document.nodes[*id as usize].payload)
+}
+
+/// Parse a chunk id; shorter ids are padded with spaces (`ID3` -> `ID3 `).
+pub fn parse_chunk_id(text: &str) -> Result<[u8; 4]> {
+    let bytes = text.as_bytes();
+    if bytes.is_empty()
+        || bytes.len() > 4
+        || !bytes
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        bail!("chunk id must be 1-4 printable ASCII characters: {text:?}");
+    }
+    let mut id = [b' '; 4];
+    id[..bytes.len()].copy_from_slice(bytes);
+    if id[0] == b' ' {
+        bail!("chunk id must not start with a space: {text:?}");
+    }
+    Ok(id)
+}
+
+/// Hex digits with optional `0x` prefix and whitespace / `:` / `-` separators.
+pub fn parse_hex_payload(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace() && *byte != b':' && *byte != b'-')
+        .collect();
+    if digits.len() % 2 != 0 {
+        bail!("hex payload has an odd number of digits");
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).unwrap_or("??");
+            u8::from_str_radix(pair, 16).map_err(|_| anyhow!("invalid hex byte {pair:?}"))
+        })
+        .collect()
+}
+
+pub fn backup_path(source: &Path) -> PathBuf {
+    let name = source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("backup");
+    source.with_file_name(format!("{name}.bak"))
+}
+
+pub fn apply_chunk_edits(
+    source: &Path,
+    edits: &[ChunkEdit],
+    output: &ChunkEditOutput,
+    overwrite: bool,
+    cancel: Option<&AtomicBool>,
+    progress: Option<&AtomicU64>,
+) -> Result<ChunkEditReport> {
+    let document = inspect_path(
+        source,
+        InspectOptions {
+            budget: ScanBudget::editor(),
+            decode_xml: false,
+            decode_values: false,
+            cancel: None,
+        },
+    )?;
+    let nodes = editable_nodes(&document)?;
+    let mut file = File::open(source).with_context(|| format!("open {}", source.display()))?;
+    let header = read_at(&mut file, 0, 12)?;
+    let mut chunks = Vec::new();
+    for id in nodes {
+        let node = &document.nodes[id as usize];
+        let chunk_id: [u8; 4] = read_at(&mut file, node.offset, 4)?.try_into().unwrap();
+        chunks.push(PlannedChunk::source(
+            chunk_id,
+            node.offset,
+            node.payload.file_offset,
+            node.payload.length,
+        ));
+    }
+    let wave = header[8..12] == *b"WAVE";
+    for edit in edits {
+        apply_edit(&mut chunks, edit, wave)?;
+    }
+
+    let source_container = document.container;
+    let (layout, output_container) = if wave {
+        check_wave_order(&chunks)?;
+        let root: [u8; 4] = header[0..4].try_into().unwrap();
+        let (root, output_container) = if &root == b"RIFF" && wave_needs_rf64(&chunks) {
+            (*b"RF64", ContainerKind::Rf64)
+        } else {
+            (root, source_container)
+        };
+        let frames = wave_frames(&mut file, &chunks)?;
+        (wave_layout(root, chunks, frames)?, output_container)
+    } else {
+        let form: [u8; 4] = header[8..12].try_into().unwrap();
+        (aiff_layout(form, chunks)?, source_container)
+    };
+    drop(file);
+
+    let (output, backup) = match output {
+        ChunkEditOutput::Copy(path) => {
+            if path.exists() && !overwrite {
+                bail!("output already exists; explicit overwrite is required");
+            }
+            if paths_refer_to_same_file(source, path) {
+                bail!("copy output must not be the source file; edit in place instead");
+            }
+            write_layout(source, &layout, path, cancel, progress)?;
+            (path.clone(), None)
+        }
+        ChunkEditOutput::InPlace => {
+            let ext = source
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("tmp");
+            let tmp = crate::wave::unique_sibling_tmp(source, "chunk", ext);
+            let result = write_layout(source, &layout, &tmp, cancel, progress)
+                .and_then(|()| crate::wave::replace_file_with_tmp(&tmp, source, true));
+            if let Err(err) = result {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(err);
+            }
+            (source.to_path_buf(), Some(backup_path(source)))
+        }
+    };
+
+    let mut offset = 12u64;
+    let mut entries = Vec::new();
+    for chunk in layout.chunks() {
+        let header_offset = offset;
+        offset += chunk.stored_len();
+        if &chunk.id == b"ds64" {
+            continue;
+        }
+        let id = fourcc(&chunk.id);
+        entries.push(ChunkEntry {
+            index: entries.len(),
+            known: if wave {
+                riff_chunk_known(&chunk.id)
+            } else {
+                aiff_chunk_known(&id)
+            },
+            id,
+            offset: Some(header_offset),
+            length: chunk.body.len(),
+        });
+    }
+    Ok(ChunkEditReport {
+        source_container,
+        output_container,
+        output,
+        backup,
+        output_len: layout.output_len(),
+        chunks: entries,
+    })
+}
+
+fn editable_nodes(document: &MetadataDocument) -> Result<Vec<NodeId>> {
+    if !chunk_edit_supported(document.container) {
+        bail!("chunk editing supports RIFF / RF64 / BW64 WAVE and AIFF / AIFC files");
+    }
+    if document.completion != Completion::Complete {
+        bail!("the container was not fully inspected");
+    }
+    let root = document
+        .roots
+        .first()
+        .and_then(|id| document.node(*id))
+        .ok_or_else(|| anyhow!("the document has no container root"))?;
+    let mut nodes = Vec::with_capacity(root.children.len());
+    for id in &root.children {
+        let node = &document.nodes[*id as usize];
+        if node.status == ParseStatus::Truncated {
+            bail!(
+                "{} at 0x{:08X} is truncated; repair the file first",
+                node.name,
+                node.offset
+            );
+        }
+        if node.name != "ds64" {
+            nodes.push(*id);
+        }
+    }
+    Ok(nodes)
+}
+
+fn required_chunk(id: &[u8; 4], wave: bool) -> bool {
+    if wave {
+        matches!(id, b"fmt " | b"data")
+    } else {
+        matches!(id, b"COMM" | b"SSND")
+    }
+}
+
+fn apply_edit(chunks: &mut Vec<PlannedChunk>, edit: &ChunkEdit, wave: bool) -> Result<()> {
+    let len = chunks.len();
+    let out_of_range = |index: usize| anyhow!("chunk index {index} is out of range (0..{len})");
+    match edit {
+        ChunkEdit::Remove { index } => {
+            let chunk = chunks.get(*index).ok_or_else(|| out_of_range(*index))?;
+            if required_chunk(&chunk.id, wave) {
+                bail!("{} is required and cannot be removed", fourcc(&chunk.id));
+            }
+            chunks.remove(*index);
+        }
+        ChunkEdit::Insert { index, id, payload } => {
+            if *index > len {
+                return Err(out_of_range(*index));
+            }
+            if id == b"ds64" || id == b"RIFF" || id == b"FORM" {
+                bail!("{} cannot be inserted as a chunk", fourcc(id));
+            }
+            // Required chunks cannot be removed, so one is always present.
+            if required_chunk(id, wave) {
+                bail!("{} already exists and cannot be inserted", fourcc(id));
+            }
+            if payload.len() as u64 > u32::MAX as u64 {
+                bail!("inserted payload exceeds 4 GiB");
+            }
+            chunks.insert(
+                *index,
+                PlannedChunk {
+                    id: *id,
+                    header_offset: None,
+                    size_field: 0,
+                    body: ChunkBody::Bytes(payload.clone()),
+                },
+            );
+        }
+        ChunkEdit::Move { from, to } => {
+            if *from >= len {
+                return Err(out_of_range(*from));
+            }
+            if *to >= len {
+                return Err(out_of_range(*to));
+            }
+            let chunk = chunks.remove(*from);
+            chunks.insert(*to, chunk);
+        }
+    }
+    Ok(())
+}
+
+fn check_wave_order(chunks: &[PlannedChunk]) -> Result<()> {
+    let position = |id: &[u8; 4]| chunks.iter().position(|chunk| &chunk.id == id);
+    if let (Some(fmt), Some(data)) = (position(b"fmt "), position(b"data")) {
+        if fmt > data {
+            bail!("fmt must stay before data");
+        }
+    }
+    Ok(())
+}
+
+/// Frame count for a regenerated `ds64`: from the source `ds64` when the
+/// data chunk is untouched, else derived from `fmt` block align.
+fn wave_frames(file: &mut File, chunks: &[PlannedChunk]) -> Result<Option<u64>> {
+    let Some(fmt) = chunks.iter().find(|chunk| &chunk.id == b"fmt ") else {
+        return Ok(None);
+    };
+    let fmt = match &fmt.body {
+        ChunkBody::Source { offset, length } => read_at(file, *offset, (*length).min(16) as usize)?,
+        ChunkBody::Bytes(bytes) => bytes.clone(),
+    };
+    let block_align = read_u16_le(&fmt, 12).unwrap_or(0) as u64;
+    let data_len = chunks
+        .iter()
+        .find(|chunk| &chunk.id == b"data")
+        .map(|chunk| chunk.body.len());
+    Ok(match data_len {
+        Some(len) if block_align > 0 => Some(len / block_align),
+        _ => {
+            // Compressed / unknown frame size: keep what the header says.
+            let ds64 = read_at(file, 12, 8)
+                .ok()
+                .filter(|header| &header[0..4] == b"ds64")
+                .and_then(|_| read_at(file, 20, 24).ok());
+            ds64.and_then(|payload| read_u64_le(&payload, 16))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_chunk_edit_{tag}_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chunk(id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(payload);
+        if payload.len() & 1 != 0 {
+            out.push(0);
+        }
+        out
+    }
+
+    fn write_wav(path: &Path, chunks: &[Vec<u8>]) {
+        let body: Vec<u8> = chunks.concat();
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend(body);
+        fs::write(path, bytes).unwrap();
+    }
+
+    fn fmt_pcm16_mono() -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&48_000u32.to_le_bytes());
+        fmt.extend_from_slice(&96_000u32.to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        fmt
+    }
+
+    fn ids(report: &ChunkEditReport) -> Vec<&str> {
+        report
+            .chunks
+            .iter()
+            .map(|chunk| chunk.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parses_ids_and_hex() {
+        assert_eq!(parse_chunk_id("ID3").unwrap(), *b"ID3 ");
+        assert_eq!(parse_chunk_id("gmet").unwrap(), *b"gmet");
+        assert!(parse_chunk_id("toolong").is_err());
+        assert!(parse_chunk_id(" abc").is_err());
+        assert_eq!(
+            parse_hex_payload("0x01 02:ff-A0").unwrap(),
+            vec![1, 2, 0xff, 0xa0]
+        );
+        assert!(parse_hex_payload("abc").is_err());
+    }
+
+    #[test]
+    fn insert_move_and_remove_into_a_copy() {
+        let dir = temp_dir("copy");
+        let source = dir.join("take.wav");
+        write_wav(
+            &source,
+            &[
+                chunk(b"fmt ", &fmt_pcm16_mono()),
+                chunk(b"data", &[7u8; 8]),
+                chunk(b"LIST", b"INFOINAM\x04\0\0\0abc\0"),
+                chunk(b"junk", b"x"),
+            ],
+        );
+        let original = fs::read(&source).unwrap();
+        let output = dir.join("take.edited.wav");
+        let report = apply_chunk_edits(
+            &source,
+            &[
+                ChunkEdit::Move { from: 2, to: 1 },
+                ChunkEdit::Remove { index: 3 },
+                ChunkEdit::Insert {
+                    index: 3,
+                    id: *b"gmet",
+                    payload: b"odd".to_vec(),
+                },
+            ],
+            &ChunkEditOutput::Copy(output.clone()),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(ids(&report), vec!["fmt ", "LIST", "data", "gmet"]);
+        assert_eq!(fs::read(&source).unwrap(), original);
+        assert_eq!(fs::metadata(&output).unwrap().len(), report.output_len);
+
+        let document = inspect_path(&output, InspectOptions::default()).unwrap();
+        let entries = chunk_entries(&document).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[3].id, "gmet");
+        assert_eq!(entries[3].length, 3);
+        let payload = chunk_payload(&document, 3).unwrap();
+        let bytes = fs::read(&output).unwrap();
+        let start = payload.file_offset as usize;
+        assert_eq!(&bytes[start..start + 3], b"odd");
+        assert!(
+            document
+                .diagnostics
+                .iter()
+                .all(|diagnostic| !diagnostic.code.starts_with("riff.")),
+            "{:?}",
+            document.diagnostics
+        );
+    }
+
+    #[test]
+    fn in_place_keeps_a_backup_and_guards_required_chunks() {
+        let dir = temp_dir("in_place");
+        let source = dir.join("take.wav");
+        write_wav(
+            &source,
+            &[
+                chunk(b"fmt ", &fmt_pcm16_mono()),
+                chunk(b"cart", &[0u8; 6]),
+                chunk(b"data", &[1u8; 4]),
+            ],
+        );
+        let original = fs::read(&source).unwrap();
+
+        let err = apply_chunk_edits(
+            &source,
+            &[ChunkEdit::Remove { index: 2 }],
+            &ChunkEditOutput::InPlace,
+            false,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("required"), "{err}");
+        let err = apply_chunk_edits(
+            &source,
+            &[ChunkEdit::Move { from: 0, to: 2 }],
+            &ChunkEditOutput::InPlace,
+            false,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("before data"), "{err}");
+        let err = apply_chunk_edits(
+            &source,
+            &[ChunkEdit::Insert {
+                index: 3,
+                id: *b"data",
+                payload: vec![2u8; 4],
+            }],
+            &ChunkEditOutput::InPlace,
+            false,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot be inserted"), "{err}");
+        assert_eq!(fs::read(&source).unwrap(), original);
+
+        let report = apply_chunk_edits(
+            &source,
+            &[ChunkEdit::Remove { index: 1 }],
+            &ChunkEditOutput::InPlace,
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(ids(&report), vec!["fmt ", "data"]);
+        assert_eq!(report.backup, Some(dir.join("take.wav.bak")));
+        assert_eq!(fs::read(dir.join("take.wav.bak")).unwrap(), original);
+        assert_eq!(fs::metadata(&source).unwrap().len(), report.output_len);
+    }
+
+    #[test]
+    fn singleton_chunks_cannot_be_inserted() {
+        let insert = |id: &[u8; 4]| ChunkEdit::Insert {
+            index: 0,
+            id: *id,
+            payload: vec![0u8; 4],
+        };
+        let mut chunks = Vec::new();
+        for id in [b"fmt ", b"data"] {
+            assert!(apply_edit(&mut chunks, &insert(id), true).is_err());
+        }
+        for id in [b"COMM", b"SSND"] {
+            assert!(apply_edit(&mut chunks, &insert(id), false).is_err());
+        }
+        assert!(chunks.is_empty());
+        // Only the container's own singletons are guarded.
+        apply_edit(&mut chunks, &insert(b"COMM"), true).unwrap();
+        apply_edit(&mut chunks, &insert(b"data"), false).unwrap();
+        assert_eq!(chunks.len(), 2);
+    }
+}
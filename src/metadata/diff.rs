@@ -0,0 +1,587 @@
+//! Structural diff of two metadata documents.
+//!
+//! Nodes are matched by tree path and occurrence (`/RIFF/WAVE/LIST` #1), so a
+//! chunk that only shifted offset because an earlier chunk grew is unchanged.
+//! Chunk-level payloads are compared by SHA-256; nodes without a hash (XML
+//! elements, or a side captured from a document whose bytes are gone, such as
+//! the inspector's pre-save snapshot) fall back to size and decoded summary.
+//! Sibling order changes are found with a longest common subsequence per
+//! parent and reported as moves.
+
+use super::{
+    hash_payload, inspect_path, Completion, ContainerKind, ContentKind, HashAlgorithm,
+    InspectOptions, MetadataDocument, NodeId, ParseStatus,
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Parents with more children than this skip move detection; the LCS table
+/// is quadratic.
+const MOVE_DETECTION_MAX_CHILDREN: usize = 2_048;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiffOptions {
+    /// Also hash audio payloads (`data`, `SSND`, ...). Off by default so a
+    /// metadata diff of long recordings stays cheap.
+    pub hash_audio: bool,
+}
+
+/// One side of a diff: a document plus the payload hashes that could be
+/// computed for it.
+#[derive(Clone, Debug)]
+pub struct DiffSide {
+    pub label: String,
+    pub document: Arc<MetadataDocument>,
+    pub hashes: HashMap<NodeId, String>,
+}
+
+impl DiffSide {
+    pub fn inspect(path: &Path, options: DiffOptions, cancel: Option<&AtomicBool>) -> Result<Self> {
+        let document = inspect_path(path, InspectOptions::default())?;
+        let mut hashes = HashMap::new();
+        for node in &document.nodes {
+            if !hashable(&document, node.id, options) {
+                continue;
+            }
+            let hash = hash_payload(path, node.payload, HashAlgorithm::Sha256, cancel)?;
+            hashes.insert(node.id, hash);
+        }
+        Ok(Self {
+            label: path.display().to_string(),
+            document: Arc::new(document),
+            hashes,
+        })
+    }
+
+    /// A side without payload hashes; nodes compare by size and summary.
+    pub fn from_document(label: impl Into<String>, document: Arc<MetadataDocument>) -> Self {
+        Self {
+            label: label.into(),
+            document,
+            hashes: HashMap::new(),
+        }
+    }
+}
+
+/// Chunk-level nodes: children of a container whose payload is not itself
+/// decoded into child nodes.
+fn hashable(document: &MetadataDocument, id: NodeId, options: DiffOptions) -> bool {
+    let Some(node) = document.node(id) else {
+        return false;
+    };
+    if !node.children.is_empty() || node.status == ParseStatus::Truncated {
+        return false;
+    }
+    if node.content == ContentKind::Audio && !options.hash_audio {
+        return false;
+    }
+    node.parent
+        .and_then(|parent| document.node(parent))
+        .is_some_and(|parent| parent.content == ContentKind::Container)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Unchanged,
+    Added,
+    Removed,
+    Changed,
+    Moved,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DiffSideInfo {
+    pub label: String,
+    pub container: ContainerKind,
+    pub file_len: u64,
+    pub completion: Completion,
+    pub hashed: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FieldDiff {
+    pub key: String,
+    pub kind: DiffKind,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeDiffSide {
+    pub node: NodeId,
+    pub offset: u64,
+    pub size: u64,
+    pub declared_size: u64,
+    pub status: ParseStatus,
+    pub sha256: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeDiff {
+    pub path: String,
+    pub occurrence: usize,
+    pub depth: usize,
+    pub kind: DiffKind,
+    /// Sibling order changed. A node can be both changed and moved; `kind`
+    /// then reports `changed`.
+    pub moved: bool,
+    pub left: Option<NodeDiffSide>,
+    pub right: Option<NodeDiffSide>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DiffCounts {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub moved: usize,
+}
+
+impl DiffCounts {
+    fn add(&mut self, kind: DiffKind) {
+        match kind {
+            DiffKind::Unchanged => {}
+            DiffKind::Added => self.added += 1,
+            DiffKind::Removed => self.removed += 1,
+            DiffKind::Changed => self.changed += 1,
+            DiffKind::Moved => self.moved += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.added + self.removed + self.changed + self.moved
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MetadataDiff {
+    pub left: DiffSideInfo,
+    pub right: DiffSideInfo,
+    pub identical: bool,
+    pub field_counts: DiffCounts,
+    pub node_counts: DiffCounts,
+    pub fields: Vec<FieldDiff>,
+    pub nodes: Vec<NodeDiff>,
+}
+
+impl MetadataDiff {
+    /// Drop unchanged rows (the counts are kept).
+    pub fn retain_changes(&mut self) {
+        self.fields
+            .retain(|field| field.kind != DiffKind::Unchanged);
+        self.nodes.retain(|node| node.kind != DiffKind::Unchanged);
+    }
+}
+
+type NodeKey = (String, usize);
+
+pub fn diff_documents(left: &DiffSide, right: &DiffSide) -> MetadataDiff {
+    let fields = diff_fields(&left.document, &right.document);
+    let nodes = diff_nodes(left, right);
+    let mut field_counts = DiffCounts::default();
+    for field in &fields {
+        field_counts.add(field.kind);
+    }
+    let mut node_counts = DiffCounts::default();
+    for node in &nodes {
+        node_counts.add(node.kind);
+    }
+    MetadataDiff {
+        left: side_info(left),
+        right: side_info(right),
+        identical: field_counts.total() == 0 && node_counts.total() == 0,
+        field_counts,
+        node_counts,
+        fields,
+        nodes,
+    }
+}
+
+fn side_info(side: &DiffSide) -> DiffSideInfo {
+    DiffSideInfo {
+        label: side.label.clone(),
+        container: side.document.container,
+        file_len: side.document.file_len,
+        completion: side.document.completion,
+        hashed: !side.hashes.is_empty(),
+    }
+}
+
+fn resolved_fields(document: &MetadataDocument) -> BTreeMap<&str, String> {
+    document
+        .normalized
+        .iter()
+        .map(|field| {
+            let value = field
+                .values
+                .get(field.resolved_index)
+                .map(|value| value.value.display())
+                .unwrap_or_default();
+            (field.key.as_str(), value)
+        })
+        .collect()
+}
+
+fn diff_fields(left: &MetadataDocument, right: &MetadataDocument) -> Vec<FieldDiff> {
+    let left = resolved_fields(left);
+    let right = resolved_fields(right);
+    let keys = left
+        .keys()
+        .chain(right.keys())
+        .copied()
+        .collect::<std::collections::BTreeSet<_>>();
+    keys.into_iter()
+        .map(|key| {
+            let (left, right) = (left.get(key).cloned(), right.get(key).cloned());
+            let kind = match (&left, &right) {
+                (Some(_), None) => DiffKind::Removed,
+                (None, Some(_)) => DiffKind::Added,
+                (Some(a), Some(b)) if a != b => DiffKind::Changed,
+                _ => DiffKind::Unchanged,
+            };
+            FieldDiff {
+                key: key.to_string(),
+                kind,
+                left,
+                right,
+            }
+        })
+        .collect()
+}
+
+/// Key every node by path and its occurrence among nodes with that path, in
+/// document (depth-first) order.
+fn node_keys(document: &MetadataDocument) -> Vec<(NodeId, NodeKey)> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut out = Vec::with_capacity(document.nodes.len());
+    let mut stack = document.roots.iter().rev().copied().collect::<Vec<_>>();
+    while let Some(id) = stack.pop() {
+        let Some(node) = document.node(id) else {
+            continue;
+        };
+        let occurrence = seen.entry(node.path.as_str()).or_insert(0);
+        out.push((id, (node.path.clone(), *occurrence)));
+        *occurrence += 1;
+        stack.extend(node.children.iter().rev().copied());
+    }
+    out
+}
+
+fn node_side(side: &DiffSide, id: NodeId) -> Option<NodeDiffSide> {
+    let node = side.document.node(id)?;
+    Some(NodeDiffSide {
+        node: id,
+        offset: node.offset,
+        size: node.readable_size,
+        declared_size: node.declared_size,
+        status: node.status,
+        sha256: side.hashes.get(&id).cloned(),
+        summary: node.summary.clone(),
+    })
+}
+
+fn node_depth(document: &MetadataDocument, id: NodeId) -> usize {
+    let mut depth = 0;
+    let mut current = document.node(id).and_then(|node| node.parent);
+    while let Some(parent) = current {
+        depth += 1;
+        current = document.node(parent).and_then(|node| node.parent);
+    }
+    depth
+}
+
+fn content_changed(left: &NodeDiffSide, right: &NodeDiffSide) -> bool {
+    if left.size != right.size || left.declared_size != right.declared_size {
+        return true;
+    }
+    if left.status != right.status {
+        return true;
+    }
+    match (&left.sha256, &right.sha256) {
+        (Some(a), Some(b)) => a != b,
+        _ => left.summary != right.summary,
+    }
+}
+
+fn diff_nodes(left: &DiffSide, right: &DiffSide) -> Vec<NodeDiff> {
+    let left_keys = node_keys(&left.document);
+    let right_keys = node_keys(&right.document);
+    let left_ids: HashMap<&NodeKey, NodeId> =
+        left_keys.iter().map(|(id, key)| (key, *id)).collect();
+    let right_ids: HashMap<&NodeKey, NodeId> =
+        right_keys.iter().map(|(id, key)| (key, *id)).collect();
+    let moved = moved_keys(left, right, &left_keys, &right_keys, &right_ids);
+
+    let mut out = Vec::new();
+    for (id, key) in &left_keys {
+        let left_side = node_side(left, *id);
+        let depth = node_depth(&left.document, *id);
+        match right_ids.get(key) {
+            Some(right_id) => {
+                let right_side = node_side(right, *right_id);
+                let changed = match (&left_side, &right_side) {
+                    (Some(a), Some(b)) => content_changed(a, b),
+                    _ => false,
+                };
+                let is_moved = moved.contains(key);
+                let kind = if changed {
+                    DiffKind::Changed
+                } else if is_moved {
+                    DiffKind::Moved
+                } else {
+                    DiffKind::Unchanged
+                };
+                out.push(NodeDiff {
+                    path: key.0.clone(),
+                    occurrence: key.1,
+                    depth,
+                    kind,
+                    moved: is_moved,
+                    left: left_side,
+                    right: right_side,
+                });
+            }
+            None => out.push(NodeDiff {
+                path: key.0.clone(),
+                occurrence: key.1,
+                depth,
+                kind: DiffKind::Removed,
+                moved: false,
+                left: left_side,
+                right: None,
+            }),
+        }
+    }
+    // Added nodes go right after the row of their preceding right-side
+    // sibling so the list reads like the right-hand tree.
+    for (index, (id, key)) in right_keys.iter().enumerate() {
+        if left_ids.contains_key(key) {
+            continue;
+        }
+        let anchor = right_keys[..index]
+            .iter()
+            .rev()
+            .find_map(|(_, previous)| {
+                out.iter()
+                    .position(|row| row.path == previous.0 && row.occurrence == previous.1)
+            })
+            .map(|position| position + 1)
+            .unwrap_or(0);
+        out.insert(
+            anchor,
+            NodeDiff {
+                path: key.0.clone(),
+                occurrence: key.1,
+                depth: node_depth(&right.document, *id),
+                kind: DiffKind::Added,
+                moved: false,
+                left: None,
+                right: node_side(right, *id),
+            },
+        );
+    }
+    out
+}
+
+/// Keys of nodes present on both sides whose order among their common
+/// siblings differs, i.e. those outside the longest common subsequence.
+fn moved_keys(
+    left: &DiffSide,
+    right: &DiffSide,
+    left_keys: &[(NodeId, NodeKey)],
+    right_keys: &[(NodeId, NodeKey)],
+    right_ids: &HashMap<&NodeKey, NodeId>,
+) -> HashSet<NodeKey> {
+    let left_key_of: HashMap<NodeId, &NodeKey> =
+        left_keys.iter().map(|(id, key)| (*id, key)).collect();
+    let right_key_of: HashMap<NodeId, &NodeKey> =
+        right_keys.iter().map(|(id, key)| (*id, key)).collect();
+    let mut moved = HashSet::new();
+    for (left_id, key) in left_keys {
+        let Some(right_id) = right_ids.get(key) else {
+            continue;
+        };
+        let (Some(left_parent), Some(right_parent)) =
+            (left.document.node(*left_id), right.document.node(*right_id))
+        else {
+            continue;
+        };
+        if left_parent.children.len() < 2
+            || left_parent.children.len() > MOVE_DETECTION_MAX_CHILDREN
+            || right_parent.children.len() > MOVE_DETECTION_MAX_CHILDREN
+        {
+            continue;
+        }
+        let left_children = left_parent
+            .children
+            .iter()
+            .filter_map(|id| left_key_of.get(id).copied())
+            .collect::<Vec<_>>();
+        let right_children = right_parent
+            .children
+            .iter()
+            .filter_map(|id| right_key_of.get(id).copied())
+            .collect::<HashSet<_>>();
+        let right_order = right_parent
+            .children
+            .iter()
+            .filter_map(|id| right_key_of.get(id).copied())
+            .filter(|key| left_children.contains(key))
+            .collect::<Vec<_>>();
+        let left_order = left_children
+            .into_iter()
+            .filter(|key| right_children.contains(key))
+            .collect::<Vec<_>>();
+        let common = longest_common_subsequence(&left_order, &right_order);
+        for key in left_order {
+            if !common.contains(key) {
+                moved.insert(key.clone());
+            }
+        }
+    }
+    moved
+}
+
+fn longest_common_subsequence<'a>(
+    left: &[&'a NodeKey],
+    right: &[&'a NodeKey],
+) -> HashSet<&'a NodeKey> {
+    let (n, m) = (left.len(), right.len());
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if left[i] == right[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let mut common = HashSet::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if left[i] == right[j] {
+            common.insert(left[i]);
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(payload);
+        if payload.len() % 2 == 1 {
+            out.push(0);
+        }
+        out
+    }
+
+    fn wav(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body = chunks.concat();
+        let mut out = b"RIFF".to_vec();
+        out.extend_from_slice(&((body.len() + 4) as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(&body);
+        out
+    }
+
+    fn fmt() -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&1u16.to_le_bytes());
+        payload.extend_from_slice(&1u16.to_le_bytes());
+        payload.extend_from_slice(&48_000u32.to_le_bytes());
+        payload.extend_from_slice(&96_000u32.to_le_bytes());
+        payload.extend_from_slice(&2u16.to_le_bytes());
+        payload.extend_from_slice(&16u16.to_le_bytes());
+        chunk(b"fmt ", &payload)
+    }
+
+    fn info(title: &str) -> Vec<u8> {
+        let mut payload = b"INFO".to_vec();
+        let mut value = title.as_bytes().to_vec();
+        value.push(0);
+        payload.extend_from_slice(&chunk(b"INAM", &value));
+        chunk(b"LIST", &payload)
+    }
+
+    fn side(dir: &Path, name: &str, bytes: &[u8]) -> DiffSide {
+        let path = dir.join(name);
+        std::fs::write(&path, bytes).unwrap();
+        DiffSide::inspect(&path, DiffOptions::default(), None).unwrap()
+    }
+
+    #[test]
+    fn diff_reports_added_removed_changed_and_moved_nodes() {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves-metadata-diff-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let data = chunk(b"data", &[0; 8]);
+        let left = side(
+            &dir,
+            "left.wav",
+            &wav(&[fmt(), chunk(b"junk", &[1; 4]), info("Door"), data.clone()]),
+        );
+        let right = side(
+            &dir,
+            "right.wav",
+            &wav(&[fmt(), info("Gate"), data.clone(), chunk(b"gmet", b"ab")]),
+        );
+        let diff = diff_documents(&left, &right);
+        assert!(!diff.identical);
+        let title = diff
+            .fields
+            .iter()
+            .find(|field| field.key == "title")
+            .unwrap();
+        assert_eq!(title.kind, DiffKind::Changed);
+        assert_eq!(title.right.as_deref(), Some("Gate"));
+        let kind_of = |suffix: &str| {
+            diff.nodes
+                .iter()
+                .find(|node| node.path.ends_with(suffix))
+                .map(|node| node.kind)
+        };
+        assert_eq!(kind_of("/junk"), Some(DiffKind::Removed));
+        assert_eq!(kind_of("/gmet"), Some(DiffKind::Added));
+        assert_eq!(kind_of("/INAM"), Some(DiffKind::Changed));
+        assert_eq!(kind_of("/fmt "), Some(DiffKind::Unchanged));
+        // Shifted by the removed chunk but still after `fmt`: not a move.
+        assert_eq!(kind_of("/data"), Some(DiffKind::Unchanged));
+
+        let reordered = side(
+            &dir,
+            "reordered.wav",
+            &wav(&[fmt(), data, chunk(b"junk", &[1; 4]), info("Door")]),
+        );
+        let diff = diff_documents(&left, &reordered);
+        assert_eq!(diff.node_counts.moved, 1);
+        assert_eq!(diff.field_counts.total(), 0);
+
+        let same = diff_documents(&left, &left);
+        assert!(same.identical);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,381 @@
+//! Full-text queries over metadata summaries.
+//!
+//! A query is a list of whitespace separated terms that must all match.
+//! Double quotes group a phrase, and a `desc:`, `ucs:`, `tags:` or `name:`
+//! prefix restricts a term to one column.  The same parsed query drives the
+//! SQLite FTS5 index kept by [`super::cache`] and the in-memory fallback for
+//! summaries that only exist in the list's memory cache.
+
+use super::{MetadataSummary, MetadataValue};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FulltextColumn {
+    Name,
+    Desc,
+    Ucs,
+    Tags,
+    Other,
+}
+
+impl FulltextColumn {
+    /// FTS5 column name, also accepted as the query qualifier.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Desc => "desc",
+            Self::Ucs => "ucs",
+            Self::Tags => "tags",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn from_qualifier(qualifier: &str) -> Option<Self> {
+        match qualifier.to_ascii_lowercase().as_str() {
+            "name" | "file" => Some(Self::Name),
+            "desc" | "description" | "comment" | "note" => Some(Self::Desc),
+            "ucs" => Some(Self::Ucs),
+            "tags" | "tag" => Some(Self::Tags),
+            _ => None,
+        }
+    }
+
+    /// Column that indexes a normalized summary field.
+    pub fn for_field(key: &str) -> Self {
+        if key.starts_with("ucs.") {
+            return Self::Ucs;
+        }
+        match key {
+            "bwf.description" | "comment" | "description" | "ixml.note" => Self::Desc,
+            "title" | "artist" | "album" | "genre" | "bwf.originator" | "ixml.project"
+            | "ixml.scene" | "ixml.take" | "ixml.track_name" => Self::Tags,
+            _ if [".description", ".comment", ".note", ".notes"]
+                .iter()
+                .any(|suffix| key.ends_with(suffix)) =>
+            {
+                Self::Desc
+            }
+            _ => Self::Other,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FulltextTerm {
+    pub column: Option<FulltextColumn>,
+    pub text: String,
+    /// `text` folded like the indexed columns, i.e. its tokens joined by
+    /// single spaces.
+    pub folded: String,
+    pub phrase: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FulltextQuery {
+    pub terms: Vec<FulltextTerm>,
+}
+
+impl FulltextQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut terms = Vec::new();
+        let mut chars = query.chars().peekable();
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+            let mut text = String::new();
+            let mut column = None;
+            let mut phrase = false;
+            let mut in_quotes = false;
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() && !in_quotes {
+                    break;
+                }
+                chars.next();
+                match c {
+                    '"' => {
+                        in_quotes = !in_quotes;
+                        phrase = true;
+                    }
+                    ':' if column.is_none() && !phrase => {
+                        match FulltextColumn::from_qualifier(&text) {
+                            Some(qualified) => {
+                                column = Some(qualified);
+                                text.clear();
+                            }
+                            None => text.push(c),
+                        }
+                    }
+                    _ => text.push(c),
+                }
+            }
+            let folded = fold(&text);
+            if !folded.is_empty() {
+                terms.push(FulltextTerm {
+                    column,
+                    text,
+                    folded,
+                    phrase,
+                });
+            }
+        }
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// FTS5 `MATCH` expression. Unquoted terms match token prefixes.
+    pub fn fts_expression(&self) -> Option<String> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        let parts = self
+            .terms
+            .iter()
+            .map(|term| {
+                let mut part = String::new();
+                if let Some(column) = term.column {
+                    part.push_str(column.key());
+                    part.push_str(" : ");
+                }
+                // Folding leaves only letters, digits and spaces, so the
+                // phrase needs no escaping.
+                part.push('"');
+                part.push_str(&term.folded);
+                part.push('"');
+                if !term.phrase {
+                    part.push('*');
+                }
+                part
+            })
+            .collect::<Vec<_>>();
+        Some(parts.join(" AND "))
+    }
+
+    /// In-memory equivalent of [`FulltextQuery::fts_expression`]: each term
+    /// is a token sequence, and an unquoted term's last token only has to be
+    /// a prefix.
+    pub fn matches(&self, document: &FulltextDocument) -> bool {
+        !self.terms.is_empty()
+            && self.terms.iter().all(|term| {
+                let hit = |text: &str| tokens_match(text, &term.folded, !term.phrase);
+                match term.column {
+                    Some(column) => hit(&document.columns[column.index()]),
+                    None => document.columns.iter().any(|column| hit(column)),
+                }
+            })
+    }
+}
+
+/// Searchable text of one file, split into the index columns.
+#[derive(Clone, Debug, Default)]
+pub struct FulltextDocument {
+    columns: [String; 5],
+}
+
+impl FulltextDocument {
+    pub fn from_summary(name: &str, summary: &MetadataSummary) -> Self {
+        let mut columns: [Vec<String>; 5] = Default::default();
+        columns[FulltextColumn::Name.index()].push(name.to_string());
+        for field in &summary.fields {
+            let column = &mut columns[FulltextColumn::for_field(&field.key).index()];
+            for value in &field.values {
+                if let MetadataValue::Text(text) = &value.value {
+                    if !text.trim().is_empty() && !column.contains(text) {
+                        column.push(text.clone());
+                    }
+                }
+            }
+        }
+        Self {
+            columns: columns.map(|values| fold(&values.join(" "))),
+        }
+    }
+
+    /// Heap size of the folded text, for memory budgeting.
+    pub fn text_bytes(&self) -> usize {
+        self.columns.iter().map(String::capacity).sum()
+    }
+
+    /// Column text as stored in the FTS index, lowercase and separator-folded.
+    pub fn column(&self, column: FulltextColumn) -> &str {
+        &self.columns[column.index()]
+    }
+}
+
+/// Whether the folded `needle` occurs in the folded `text` starting on a
+/// token boundary and, unless `prefix`, ending on one. Both sides separate
+/// tokens by single spaces, so this is a token-sequence match.
+fn tokens_match(text: &str, needle: &str, prefix: bool) -> bool {
+    std::iter::once(0)
+        .chain(text.match_indices(' ').map(|(at, _)| at + 1))
+        .any(|at| {
+            let rest = &text[at..];
+            rest.starts_with(needle)
+                && (prefix || rest.len() == needle.len() || rest[needle.len()..].starts_with(' '))
+        })
+}
+
+/// ASCII base letter of each precomposed letter from U+00C0, or `.` when its
+/// NFD form is not an ASCII letter followed only by combining marks.
+const LATIN_BASE: &[u8; 400] = b"\
+    AAAAAA.CEEEEIIII.NOOOOO..UUUUY..aaaaaa.ceeeeiiii.nooooo..uuuuy.y\
+    AaAaAaCcCcCcCcDd..EeEeEeEeEeGgGgGgGgHh..IiIiIiIiI...JjKk.LlLlLl.\
+    ...NnNnNn...OoOoOo..RrRrRrSsSsSsSsTtTt..UuUuUuUuUuUuWwYyYZzZzZz.\
+    ................................Oo.............Uu...............\
+    .............AaIiOoUuUuUuUuUu.AaAa....GgKkOoOo..j...Gg..NnAa....\
+    AaAaEeEeIiIiOoOoRrRrUuUuSsTt..Hh......AaEeOoOoOoOoYy............\
+    ................";
+/// The same table for Latin Extended Additional, from U+1E00.
+const LATIN_ADDITIONAL_BASE: &[u8; 256] = b"\
+    AaBbBbBbCcDdDdDdDdDdEeEeEeEeEeFfGgHhHhHhHhHhIiIiKkKkKkLlLlLlLlMm\
+    MmMmNnNnNnNnOoOoOoOoPpPpRrRrRrRrSsSsSsSsSsTtTtTtTtUuUuUuUuUuVvVv\
+    WwWwWwWwWwXxXxYyZzZzZzhtwy......AaAaAaAaAaAaAaAaAaAaAaAaEeEeEeEe\
+    EeEeEeEeIiIiOoOoOoOoOoOoOoOoOoOoOoOoUuUuUuUuUuUuUuYyYyYyYy......";
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036f}')
+}
+
+/// `remove_diacritics 2`: the base letter of a precomposed Latin letter.
+fn strip_diacritic(c: char) -> char {
+    let code = c as usize;
+    let base = match code {
+        0x00c0..=0x024f => LATIN_BASE[code - 0x00c0],
+        0x1e00..=0x1eff => LATIN_ADDITIONAL_BASE[code - 0x1e00],
+        _ => b'.',
+    };
+    if base == b'.' {
+        c
+    } else {
+        base as char
+    }
+}
+
+/// Lowercase, drop diacritics and collapse everything that is not a letter
+/// or digit into a single space, mirroring `unicode61 remove_diacritics 2`
+/// closely enough that the in-memory fallback and the index agree on
+/// `Rain_Heavy` vs `rain heavy` and `Café` vs `cafe`.
+fn fold(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pending_space = false;
+    for c in text.chars() {
+        if is_combining_mark(c) {
+            // Part of the surrounding token, like a decomposed accent.
+            continue;
+        }
+        if c.is_alphanumeric() {
+            if pending_space && !out.is_empty() {
+                out.push(' ');
+            }
+            pending_space = false;
+            out.extend(
+                c.to_lowercase()
+                    .filter(|c| !is_combining_mark(*c))
+                    .map(strip_diacritic),
+            );
+        } else {
+            pending_space = true;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{
+        Completion, ContainerKind, SourceRange, SourcedValue, SummaryField, PARSER_SCHEMA_VERSION,
+    };
+    use std::path::PathBuf;
+
+    fn summary(fields: &[(&str, &str)]) -> MetadataSummary {
+        MetadataSummary {
+            schema_version: PARSER_SCHEMA_VERSION,
+            source: PathBuf::from("take.wav"),
+            container: ContainerKind::RiffWave,
+            completion: Completion::Complete,
+            fields: fields
+                .iter()
+                .map(|(key, value)| SummaryField {
+                    key: key.to_string(),
+                    values: vec![SourcedValue {
+                        value: MetadataValue::Text(value.to_string()),
+                        source_path: "/RIFF/WAVE/bext".to_string(),
+                        source_node: 0,
+                        source_range: SourceRange {
+                            offset: 0,
+                            length: 0,
+                        },
+                        encoding: None,
+                        guessed_encoding: false,
+                    }],
+                    resolved: Some(MetadataValue::Text(value.to_string())),
+                    conflict: false,
+                })
+                .collect(),
+            raw_fields: Default::default(),
+            unknown_nodes: 0,
+            diagnostics: Vec::new(),
+            coverage: vec!["__all__".to_string()],
+            adm_objects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_qualifiers_phrases_and_unknown_prefixes() {
+        let query = FulltextQuery::parse(r#"rain desc:"heavy roof" UCS:RAIN sr:48000 name:"#);
+        assert_eq!(query.terms.len(), 4);
+        assert_eq!(query.terms[0].column, None);
+        assert_eq!(query.terms[1].column, Some(FulltextColumn::Desc));
+        assert_eq!(query.terms[1].text, "heavy roof");
+        assert!(query.terms[1].phrase);
+        assert_eq!(query.terms[2].column, Some(FulltextColumn::Ucs));
+        assert_eq!(query.terms[3].column, None);
+        assert_eq!(query.terms[3].text, "sr:48000");
+        assert_eq!(
+            query.fts_expression().unwrap(),
+            r#""rain"* AND desc : "heavy roof" AND ucs : "rain"* AND "sr 48000"*"#
+        );
+    }
+
+    #[test]
+    fn in_memory_match_respects_columns() {
+        let summary = summary(&[
+            ("bwf.description", "Heavy rain on tin roof"),
+            ("ucs.fx_name", "Rain_Heavy"),
+            ("title", "Storm Walla"),
+        ]);
+        let document = FulltextDocument::from_summary("RAIN_Roof_01.wav", &summary);
+        assert_eq!(document.column(FulltextColumn::Ucs), "rain heavy");
+        assert!(FulltextQuery::parse("rain heavy roof").matches(&document));
+        assert!(FulltextQuery::parse("desc:tin ucs:\"rain heavy\"").matches(&document));
+        assert!(FulltextQuery::parse("tags:walla name:roof").matches(&document));
+        assert!(!FulltextQuery::parse("ucs:roof").matches(&document));
+        assert!(!FulltextQuery::parse("rain thunder").matches(&document));
+        // Token prefixes, not substrings, like FTS5 `"term"*`.
+        assert!(FulltextQuery::parse("hea ti").matches(&document));
+        assert!(!FulltextQuery::parse("ain").matches(&document));
+        assert!(!FulltextQuery::parse("\"heav rain\"").matches(&document));
+        assert!(FulltextQuery::parse("\"heavy rain\"").matches(&document));
+    }
+
+    #[test]
+    fn folding_strips_diacritics_like_unicode61() {
+        assert_eq!(fold("Café Crème"), "cafe creme");
+        assert_eq!(fold("cafe\u{301} ÅNGSTRÖM"), "cafe angstrom");
+        assert_eq!(fold("Ẩm_thực"), "am thuc");
+        let document = FulltextDocument::from_summary(
+            "Cafe_Terrace.wav",
+            &summary(&[("bwf.description", "Café terrace, Señora laughing")]),
+        );
+        assert!(FulltextQuery::parse("desc:cafe senora").matches(&document));
+        assert!(FulltextQuery::parse("\"CAFÉ TERRACE\"").matches(&document));
+    }
+}
@@ -2,9 +2,17 @@
 //!
 //! This module deliberately does not share the legacy RIFF writers.  Physical
 //! structure is represented by file ranges, and payload bytes are opened only
-//! by an explicit read/search/hash/extract operation.
+//! by an explicit read/search/hash/extract operation.  [`repair`] rebuilds
+//! broken RIFF/AIFF containers into a new file and never writes the source;
+//! [`chunk_edit`] reuses its layout writer for user-driven chunk edits.
+//! [`fulltext`] parses list search queries for the summary cache's FTS index,
+//! and [`diff`] compares two documents node by node.
 
 pub mod cache;
+pub mod chunk_edit;
+pub mod diff;
+pub mod fulltext;
+pub mod repair;
 pub mod ucs;
 
 use anyhow::{anyhow, bail, Context, Result};
@@ -23,7 +31,7 @@ use std::sync::{
 };
 use std::time::{Duration, Instant};
 
-pub const PARSER_SCHEMA_VERSION: u32 = 1;
+pub const PARSER_SCHEMA_VERSION: u32 = 3;
 pub const UCS_DATA_VERSION: &str = "8.2.1";
 pub const ASWG_SCHEMA_VERSION: &str = "1.1";
 pub const MAX_XML_BYTES: u64 = 16 * 1024 * 1024;
@@ -43,8 +51,11 @@ pub enum ContainerKind {
     Mp4,
     Flac,
     Ogg,
+    Opus,
     Aiff,
     Aifc,
+    W64,
+    Caf,
     Unknown,
 }
 
@@ -58,8 +69,11 @@ impl ContainerKind {
             Self::Mp4 => "mp4",
             Self::Flac => "flac",
             Self::Ogg => "ogg",
+            Self::Opus => "opus",
             Self::Aiff => "aiff",
             Self::Aifc => "aifc",
+            Self::W64 => "w64",
+            Self::Caf => "caf",
             Self::Unknown => "binary",
         }
     }
@@ -254,6 +268,9 @@ pub struct MetadataDocument {
     pub diagnostics: Vec<MetadataDiagnostic>,
     pub completion: Completion,
     pub audio_mapping: Option<AudioByteMapping>,
+    /// ADM audioObjects of a BW64 `axml` chunk.
+    #[serde(default)]
+    pub adm_objects: Vec<crate::adm::AdmObjectSummary>,
 }
 
 impl MetadataDocument {
@@ -306,6 +323,8 @@ pub struct MetadataSummary {
     pub unknown_nodes: usize,
     pub diagnostics: Vec<MetadataDiagnostic>,
     pub coverage: Vec<String>,
+    #[serde(default)]
+    pub adm_objects: Vec<crate::adm::AdmObjectSummary>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -431,6 +450,79 @@ impl<'a, R: Read + Seek> ScanIo<'a, R> {
         let available = self.file_len.saturating_sub(offset).min(declared);
         self.read_at(offset, available.min(cap as u64) as usize)
     }
+
+    /// Offset of the chunk after a payload of `declared` bytes, including the
+    /// pad byte after odd sizes. Some writers drop the pad; that reading is
+    /// taken (and reported as `true`) only when it alone lands on a plausible
+    /// chunk id.
+    fn next_chunk_offset(&mut self, payload_offset: u64, declared: u64) -> Result<(u64, bool)> {
+        let end = payload_offset
+            .checked_add(declared)
+            .ok_or_else(|| anyhow!("chunk offset overflow"))?;
+        if declared & 1 == 0 {
+            return Ok((end, false));
+        }
+        let padded = end + 1;
+        if end.saturating_add(8) > self.file_len {
+            return Ok((padded, false));
+        }
+        let unpadded_ok = plausible_chunk_id(&self.read_at(end, 4)?);
+        let padded_ok = padded.saturating_add(8) <= self.file_len
+            && plausible_chunk_id(&self.read_at(padded, 4)?);
+        Ok(if unpadded_ok && !padded_ok {
+            (end, true)
+        } else {
+            (padded, false)
+        })
+    }
+}
+
+/// Printable ASCII, as every registered RIFF / IFF chunk id is.
+fn plausible_chunk_id(id: &[u8]) -> bool {
+    id.len() == 4 && id.iter().all(|byte| (0x20..=0x7e).contains(byte))
+}
+
+fn riff_chunk_known(id: &[u8; 4]) -> bool {
+    matches!(
+        id,
+        b"fmt "
+            | b"data"
+            | b"ds64"
+            | b"bext"
+            | b"cart"
+            | b"LIST"
+            | b"cue "
+            | b"smpl"
+            | b"acid"
+            | b"iXML"
+            | b"axml"
+            | b"chna"
+            | b"ID3 "
+            | b"id3 "
+            | b"JUNK"
+            | b"PAD "
+            | b"FLLR"
+            | b"PEAK"
+            | b"levl"
+            | b"XMP "
+    )
+}
+
+fn aiff_chunk_known(id: &str) -> bool {
+    matches!(
+        id,
+        "COMM"
+            | "SSND"
+            | "FVER"
+            | "MARK"
+            | "INST"
+            | "COMT"
+            | "NAME"
+            | "AUTH"
+            | "ANNO"
+            | "(c) "
+            | "ID3 "
+    )
 }
 
 struct DocumentBuilder {
@@ -453,6 +545,7 @@ impl DocumentBuilder {
                 diagnostics: Vec::new(),
                 completion: Completion::Complete,
                 audio_mapping: None,
+                adm_objects: Vec::new(),
             },
             normalized_index: HashMap::new(),
             max_nodes,
@@ -698,8 +791,10 @@ pub fn inspect_reader<R: Read + Seek>(
         ContainerKind::Mp3 => scan_id3_mp3(&mut io, &mut builder),
         ContainerKind::Mp4 => scan_mp4(&mut io, &mut builder),
         ContainerKind::Flac => scan_flac(&mut io, &mut builder),
-        ContainerKind::Ogg => scan_ogg(&mut io, &mut builder),
+        ContainerKind::Ogg | ContainerKind::Opus => scan_ogg(&mut io, &mut builder),
         ContainerKind::Aiff | ContainerKind::Aifc => scan_aiff(&mut io, &mut builder),
+        ContainerKind::W64 => scan_w64(&mut io, &mut builder),
+        ContainerKind::Caf => scan_caf(&mut io, &mut builder),
         ContainerKind::Unknown => scan_unknown(&mut io, &mut builder),
     };
     if let Err(err) = scan_result {
@@ -749,11 +844,31 @@ fn sniff_container<R: Read + Seek>(sniff: &[u8], io: &mut ScanIo<'_, R>) -> Resu
             ContainerKind::Unknown
         });
     }
+    if sniff.starts_with(b"riff") {
+        let header = io.read_prefix(0, io.file_len, 40)?;
+        if crate::w64::is_w64_header(&header) {
+            return Ok(ContainerKind::W64);
+        }
+    }
+    if crate::caf::is_caf_header(sniff) {
+        return Ok(ContainerKind::Caf);
+    }
     if sniff.starts_with(b"fLaC") {
         return Ok(ContainerKind::Flac);
     }
     if sniff.starts_with(b"OggS") {
-        return Ok(ContainerKind::Ogg);
+        // The first page carries the codec identification packet.
+        let segments = io
+            .read_prefix(26, io.file_len, 1)?
+            .first()
+            .copied()
+            .unwrap_or(0) as u64;
+        let ident = io.read_prefix(27 + segments, io.file_len, 8)?;
+        return Ok(if ident.starts_with(b"OpusHead") {
+            ContainerKind::Opus
+        } else {
+            ContainerKind::Ogg
+        });
     }
     if sniff.starts_with(b"ID3") {
         return Ok(ContainerKind::Mp3);
@@ -879,10 +994,14 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
     )?;
 
     let mut pos = 12u64;
+    let mut ds64_riff_size = None;
     let mut ds64_data_size = None;
+    let mut fmt_seen = false;
     let mut ds64_table: HashMap<[u8; 4], Vec<u64>> = HashMap::new();
     let mut fmt = WaveFormat::default();
     let mut pending_data: Option<(NodeId, u64, u64)> = None;
+    let mut chna = None;
+    let mut adm = None;
     while pos.saturating_add(8) <= io.file_len {
         io.check()?;
         let chunk_header = io.read_at(pos, 8)?;
@@ -917,28 +1036,7 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
             b"ID3 " | b"id3 " => ContentKind::Container,
             _ => ContentKind::Binary,
         };
-        let known = matches!(
-            &id,
-            b"fmt "
-                | b"data"
-                | b"ds64"
-                | b"bext"
-                | b"LIST"
-                | b"cue "
-                | b"smpl"
-                | b"acid"
-                | b"iXML"
-                | b"axml"
-                | b"chna"
-                | b"ID3 "
-                | b"id3 "
-                | b"JUNK"
-                | b"PAD "
-                | b"FLLR"
-                | b"PEAK"
-                | b"levl"
-                | b"XMP "
-        );
+        let known = riff_chunk_known(&id);
         let node = builder.add_node(
             Some(root),
             id_text.clone(),
@@ -975,6 +1073,7 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
                 let bytes = io.read_prefix(payload_offset, readable, 1024 * 1024)?;
                 if bytes.len() >= 28 {
                     let riff_size = read_u64_le(&bytes, 0).unwrap_or(0);
+                    ds64_riff_size = Some(riff_size);
                     ds64_data_size = read_u64_le(&bytes, 8);
                     let sample_count = read_u64_le(&bytes, 16).unwrap_or(0);
                     builder.document.nodes[node as usize].summary = Some(format!(
@@ -997,7 +1096,17 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
                     }
                 }
             }
+            b"fmt " if fmt_seen => {
+                builder.diagnostic(
+                    DiagnosticLevel::Error,
+                    "wave.duplicate_fmt",
+                    "a second fmt chunk follows the first one; readers disagree on which applies",
+                    Some(pos),
+                    Some(node),
+                );
+            }
             b"fmt " => {
+                fmt_seen = true;
                 let bytes = io.read_prefix(payload_offset, readable, 64)?;
                 fmt = parse_wave_format(&bytes);
                 builder.document.nodes[node as usize].summary = Some(format!(
@@ -1011,10 +1120,18 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
             }
             b"LIST" => scan_riff_list(io, builder, node, payload_offset, readable)?,
             b"bext" => decode_bext(io, builder, node, payload_offset, readable)?,
+            b"cart" => decode_cart(io, builder, node, payload_offset, readable)?,
             b"cue " => decode_cue(io, builder, node, payload_offset, readable)?,
             b"smpl" => decode_smpl(io, builder, node, payload_offset, readable)?,
             b"acid" => decode_acid(io, builder, node, payload_offset, readable)?,
-            b"iXML" | b"axml" | b"XMP " if io.options.decode_xml => {
+            b"chna" => chna = decode_chna(io, builder, node, payload_offset, readable)?,
+            b"axml" => {
+                if io.options.decode_xml {
+                    decode_xml_payload(io, builder, node, payload_offset, readable)?;
+                }
+                adm = decode_adm(io, builder, node, payload_offset, readable)?;
+            }
+            b"iXML" | b"XMP " if io.options.decode_xml => {
                 decode_xml_payload(io, builder, node, payload_offset, readable)?;
             }
             b"ID3 " | b"id3 " => {
@@ -1025,18 +1142,332 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
         if truncated {
             break;
         }
-        pos = payload_offset
-            .checked_add(declared)
-            .and_then(|v| v.checked_add(declared & 1))
-            .ok_or_else(|| anyhow!("RIFF chunk offset overflow"))?;
+        let (next, missing_pad) = io.next_chunk_offset(payload_offset, declared)?;
+        if missing_pad {
+            builder.diagnostic(
+                DiagnosticLevel::Warning,
+                "riff.missing_pad",
+                format!("{id_text} has an odd size but no pad byte"),
+                Some(next),
+                Some(node),
+            );
+        }
+        pos = next;
+    }
+
+    let declared_total = if declared_root == u32::MAX as u64 {
+        ds64_riff_size
+    } else {
+        Some(declared_root)
+    };
+    if let Some(declared_total) = declared_total {
+        let actual = io.file_len.saturating_sub(8);
+        if declared_total != actual {
+            builder.diagnostic(
+                DiagnosticLevel::Warning,
+                "riff.size_mismatch",
+                format!("{root_name} declares {declared_total} bytes but the file holds {actual}"),
+                Some(4),
+                Some(root),
+            );
+        }
+    }
+    if let Some(adm) = adm {
+        builder.document.adm_objects = adm.object_summaries(chna.as_ref());
+    }
+    map_wave_audio(builder, fmt, pending_data);
+    Ok(())
+}
+
+fn decode_chna<R: Read + Seek>(
+    io: &mut ScanIo<'_, R>,
+    builder: &mut DocumentBuilder,
+    node: NodeId,
+    offset: u64,
+    length: u64,
+) -> Result<Option<crate::adm::Chna>> {
+    let bytes = io.read_prefix(offset, length, 1024 * 1024)?;
+    let Some(chna) = crate::adm::parse_chna(&bytes) else {
+        return Ok(None);
+    };
+    builder.document.nodes[node as usize].summary = Some(format!(
+        "{} tracks, {} UIDs",
+        chna.num_tracks, chna.num_uids
+    ));
+    for (idx, entry) in bytes[4..]
+        .chunks_exact(crate::adm::CHNA_ENTRY_LEN)
+        .enumerate()
+    {
+        let Some(track) = crate::adm::parse_chna_entry(entry) else {
+            continue;
+        };
+        builder.add_scalar(
+            node,
+            format!("Track {}", track.track_index),
+            format!(
+                "{} / {} / {}",
+                track.uid, track.track_format_ref, track.pack_format_ref
+            ),
+            SourceRange {
+                offset: offset + 4 + (idx * crate::adm::CHNA_ENTRY_LEN) as u64,
+                length: 40,
+            },
+        )?;
+    }
+    Ok(Some(chna))
+}
+
+/// Adds the audioProgramme → audioContent → audioObject tree of an `axml`
+/// payload under `parent`. Objects no content refers to are listed at the
+/// top of the tree.
+fn decode_adm<R: Read + Seek>(
+    io: &mut ScanIo<'_, R>,
+    builder: &mut DocumentBuilder,
+    parent: NodeId,
+    offset: u64,
+    length: u64,
+) -> Result<Option<crate::adm::Adm>> {
+    use crate::adm::{Adm, AdmElement, AdmKind};
+    if length > MAX_XML_BYTES {
+        return Ok(None);
+    }
+    let bytes = io.read_at(offset, length as usize)?;
+    let adm = match crate::adm::parse_axml(&bytes) {
+        Ok(adm) => adm,
+        Err(err) => {
+            builder.diagnostic(
+                DiagnosticLevel::Warning,
+                "adm.invalid_axml",
+                format!("axml is not a readable ADM document: {err}"),
+                Some(offset),
+                Some(parent),
+            );
+            return Ok(None);
+        }
+    };
+    if adm.elements.is_empty() {
+        return Ok(Some(adm));
+    }
+    let range = SourceRange { offset, length };
+    let root = builder.add_node(
+        Some(parent),
+        "ADM",
+        offset,
+        0,
+        length,
+        length,
+        PayloadRef {
+            file_offset: offset,
+            length,
+        },
+        ContentKind::Container,
+        true,
+        ParseStatus::Parsed,
+        Some(format!(
+            "{} programmes, {} objects",
+            adm.of_kind(AdmKind::AudioProgramme).count(),
+            adm.of_kind(AdmKind::AudioObject).count()
+        )),
+    )?;
+    fn label(element: &AdmElement) -> String {
+        if element.name.is_empty() {
+            element.id.clone()
+        } else {
+            format!("{} ({})", element.name, element.id)
+        }
+    }
+    fn add_object(
+        builder: &mut DocumentBuilder,
+        adm: &Adm,
+        parent: NodeId,
+        object: &AdmElement,
+        range: SourceRange,
+    ) -> Result<()> {
+        let packs: Vec<String> = object
+            .refs_to("audioPackFormatIDRef")
+            .map(|id| {
+                adm.find(AdmKind::AudioPackFormat, id)
+                    .map(label)
+                    .unwrap_or_else(|| id.to_string())
+            })
+            .collect();
+        let uids: Vec<&str> = object.refs_to("audioTrackUIDRef").collect();
+        let node = builder.add_scalar(parent, "audioObject", label(object), range)?;
+        if !packs.is_empty() {
+            builder.add_scalar(node, "Packs", packs.join(", "), range)?;
+        }
+        if !uids.is_empty() {
+            builder.add_scalar(node, "Track UIDs", uids.join(", "), range)?;
+        }
+        Ok(())
+    }
+    let mut listed = std::collections::HashSet::new();
+    for programme in adm.of_kind(AdmKind::AudioProgramme) {
+        let programme_node = builder.add_scalar(root, "audioProgramme", label(programme), range)?;
+        for content_id in programme.refs_to("audioContentIDRef") {
+            let Some(content) = adm.find(AdmKind::AudioContent, content_id) else {
+                continue;
+            };
+            let content_node =
+                builder.add_scalar(programme_node, "audioContent", label(content), range)?;
+            for object_id in content.refs_to("audioObjectIDRef") {
+                if let Some(object) = adm.find(AdmKind::AudioObject, object_id) {
+                    add_object(builder, &adm, content_node, object, range)?;
+                    listed.insert(object.id.clone());
+                }
+            }
+        }
+    }
+    for object in adm.of_kind(AdmKind::AudioObject) {
+        if !listed.contains(&object.id) {
+            add_object(builder, &adm, root, object, range)?;
+        }
+    }
+    Ok(Some(adm))
+}
+
+fn scan_w64<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuilder) -> Result<()> {
+    let header_len = crate::w64::W64_HEADER_LEN;
+    let chunk_header_len = crate::w64::W64_CHUNK_HEADER_LEN;
+    if io.file_len < header_len {
+        bail!("truncated W64 header");
+    }
+    let header = io.read_at(0, header_len as usize)?;
+    let declared_root = read_u64_le(&header, 16).unwrap_or(0);
+    let root = builder.add_node(
+        None,
+        "riff/wave",
+        0,
+        header_len,
+        declared_root.saturating_sub(header_len),
+        io.file_len.saturating_sub(header_len),
+        PayloadRef {
+            file_offset: header_len,
+            length: io.file_len.saturating_sub(header_len),
+        },
+        ContentKind::Container,
+        true,
+        ParseStatus::Parsed,
+        Some(format!("{} bytes", io.file_len)),
+    )?;
+
+    let mut pos = header_len;
+    let mut fmt = WaveFormat::default();
+    let mut pending_data: Option<(NodeId, u64, u64)> = None;
+    while pos.saturating_add(chunk_header_len) <= io.file_len {
+        io.check()?;
+        let chunk_header = io.read_at(pos, chunk_header_len as usize)?;
+        let guid: [u8; 16] = chunk_header[0..16].try_into().unwrap();
+        let size = read_u64_le(&chunk_header, 16).unwrap_or(0);
+        let payload_offset = pos + chunk_header_len;
+        let declared = size.saturating_sub(chunk_header_len);
+        let readable = declared.min(io.file_len.saturating_sub(payload_offset));
+        let truncated = readable < declared;
+        let name = crate::w64::guid_name(&guid);
+        // Fourcc-style GUIDs map back onto the RIFF chunk they stand in for.
+        let id = [b"fmt ", b"data", b"fact", b"bext", b"cart", b"junk", b"levl"]
+            .into_iter()
+            .find(|fourcc| guid == crate::w64::chunk_guid(fourcc))
+            .copied();
+        let content = match id.as_ref() {
+            Some(b"data") => ContentKind::Audio,
+            Some(b"junk") => ContentKind::Padding,
+            _ if guid == crate::w64::W64_LIST_GUID => ContentKind::Container,
+            _ => ContentKind::Binary,
+        };
+        let known = id.is_some() || name == "summarylist" || name == "marker" || name == "list";
+        let node = builder.add_node(
+            Some(root),
+            name.clone(),
+            pos,
+            chunk_header_len,
+            declared,
+            readable,
+            PayloadRef {
+                file_offset: payload_offset,
+                length: readable,
+            },
+            content,
+            known,
+            if truncated {
+                ParseStatus::Truncated
+            } else {
+                ParseStatus::Parsed
+            },
+            Some(format!("{} bytes @ 0x{payload_offset:08X}", declared)),
+        )?;
+        builder.add_scalar(
+            node,
+            "GUID",
+            crate::w64::format_guid(&guid),
+            SourceRange {
+                offset: pos,
+                length: 16,
+            },
+        )?;
+        if truncated || size < chunk_header_len {
+            builder.diagnostic(
+                DiagnosticLevel::Error,
+                "w64.truncated_chunk",
+                format!("{name} declares {declared} bytes but only {readable} are readable"),
+                Some(pos),
+                Some(node),
+            );
+            break;
+        }
+        match id.as_ref() {
+            Some(b"fmt ") => {
+                let bytes = io.read_prefix(payload_offset, readable, 64)?;
+                fmt = parse_wave_format(&bytes);
+                builder.document.nodes[node as usize].summary = Some(format!(
+                    "{} Hz, {} ch, {} bit, block {}",
+                    fmt.sample_rate, fmt.channels, fmt.container_bits, fmt.block_align
+                ));
+                add_wave_fmt_children(builder, node, payload_offset, fmt)?;
+            }
+            Some(b"data") => {
+                pending_data.get_or_insert((node, payload_offset, readable));
+            }
+            Some(b"bext") => decode_bext(io, builder, node, payload_offset, readable)?,
+            Some(b"cart") => decode_cart(io, builder, node, payload_offset, readable)?,
+            _ if guid == crate::w64::W64_LIST_GUID => {
+                scan_riff_list(io, builder, node, payload_offset, readable)?;
+            }
+            _ => {}
+        }
+        pos = pos
+            .checked_add(size)
+            .and_then(|v| v.checked_add((8 - size % 8) % 8))
+            .ok_or_else(|| anyhow!("W64 chunk offset overflow"))?;
     }
+    map_wave_audio(builder, fmt, pending_data);
+    Ok(())
+}
 
+/// Exact byte mapping for the first `data` chunk when `fmt` is PCM or float.
+fn map_wave_audio(
+    builder: &mut DocumentBuilder,
+    fmt: WaveFormat,
+    pending_data: Option<(NodeId, u64, u64)>,
+) {
     if let Some((node_id, data_offset, data_length)) = pending_data {
         if fmt.pcm_or_float
             && fmt.block_align > 0
             && fmt.channels > 0
             && fmt.block_align % fmt.channels == 0
         {
+            if data_length % fmt.block_align as u64 != 0 {
+                builder.diagnostic(
+                    DiagnosticLevel::Warning,
+                    "wave.partial_frame",
+                    format!(
+                        "data holds {data_length} bytes, not a whole number of {}-byte frames",
+                        fmt.block_align
+                    ),
+                    Some(data_offset),
+                    Some(node_id),
+                );
+            }
             let format_name = match fmt.format_tag {
                 1 => "PCM",
                 3 => "IEEE Float",
@@ -1066,7 +1497,6 @@ fn scan_riff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
             );
         }
     }
-    Ok(())
 }
 
 fn parse_wave_format(bytes: &[u8]) -> WaveFormat {
@@ -1303,6 +1733,110 @@ fn decode_bext<R: Read + Seek>(
     Ok(())
 }
 
+/// AES46 `cart`: text fields normalize to `cart.<key>`, post-timers to
+/// `cart.post_timers` (`USAGE=sample; ...`).
+fn decode_cart<R: Read + Seek>(
+    io: &mut ScanIo<'_, R>,
+    builder: &mut DocumentBuilder,
+    node: NodeId,
+    offset: u64,
+    length: u64,
+) -> Result<()> {
+    use crate::wave::{
+        CartField, CART_LEVEL_REFERENCE_OFFSET, CART_POST_TIMERS_OFFSET, CART_POST_TIMER_SLOTS,
+    };
+    let bytes = io.read_prefix(offset, length, 64 * 1024)?;
+    if let Some(raw) = bytes.get(0..4) {
+        builder.add_scalar(
+            node,
+            "Version",
+            decode_legacy_text(raw).0,
+            SourceRange { offset, length: 4 },
+        )?;
+    }
+    for field in CartField::ALL {
+        let (start, len) = field.layout();
+        let len = len.unwrap_or(bytes.len().saturating_sub(start));
+        let Some(raw) = bytes.get(start..start + len) else {
+            continue;
+        };
+        let (text, enc, guessed) = decode_legacy_text(raw);
+        if field == CartField::TagText && text.is_empty() {
+            continue;
+        }
+        let range = SourceRange {
+            offset: offset + start as u64,
+            length: len as u64,
+        };
+        let child = builder.add_scalar(node, field.label(), text.clone(), range)?;
+        if !text.is_empty() {
+            builder.normalize(
+                format!("cart.{}", field.key()),
+                MetadataValue::Text(text),
+                child,
+                range,
+                Some(enc),
+                guessed,
+            );
+        }
+    }
+    if let Some(level) = read_u32_le(&bytes, CART_LEVEL_REFERENCE_OFFSET) {
+        builder.add_scalar(
+            node,
+            "LevelReference",
+            (level as i32).to_string(),
+            SourceRange {
+                offset: offset + CART_LEVEL_REFERENCE_OFFSET as u64,
+                length: 4,
+            },
+        )?;
+    }
+    let mut timers = Vec::new();
+    for slot in 0..CART_POST_TIMER_SLOTS {
+        let pos = CART_POST_TIMERS_OFFSET + slot * 8;
+        let Some(raw) = bytes.get(pos..pos + 8) else {
+            break;
+        };
+        let sample = read_u32_le(raw, 4).unwrap_or(0);
+        if raw[..4].iter().all(|&b| b == 0) || sample == u32::MAX {
+            continue;
+        }
+        let usage = String::from_utf8_lossy(&raw[..4]).trim_end().to_string();
+        let range = SourceRange {
+            offset: offset + pos as u64,
+            length: 8,
+        };
+        let child = builder.add_scalar(
+            node,
+            format!("PostTimer {}", slot + 1),
+            format!("{usage} @ {sample}"),
+            range,
+        )?;
+        timers.push((format!("{usage}={sample}"), child, range));
+    }
+    builder.document.nodes[node as usize].summary = Some(format!(
+        "{} ({} post-timers)",
+        decode_legacy_text(bytes.get(4..68).unwrap_or_default()).0,
+        timers.len()
+    ));
+    if let Some((_, child, range)) = timers.first() {
+        let text = timers
+            .iter()
+            .map(|(text, _, _)| text.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        builder.normalize(
+            "cart.post_timers".to_string(),
+            MetadataValue::Text(text),
+            *child,
+            *range,
+            None,
+            false,
+        );
+    }
+    Ok(())
+}
+
 fn decode_cue<R: Read + Seek>(
     io: &mut ScanIo<'_, R>,
     builder: &mut DocumentBuilder,
@@ -1807,6 +2341,7 @@ fn normalize_xml_key(name: &str) -> Option<String> {
         "scene" => Some("ixml.scene".to_string()),
         "take" => Some("ixml.take".to_string()),
         "trackname" => Some("ixml.track_name".to_string()),
+        "note" => Some("ixml.note".to_string()),
         _ => None,
     }
 }
@@ -1833,7 +2368,9 @@ fn scan_aiff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
         ParseStatus::Parsed,
         Some(format!("{} bytes", io.file_len)),
     )?;
+    let declared_root = read_u32_be(&header, 4).unwrap_or(0) as u64;
     let mut pos = 12u64;
+    let mut comm_seen = false;
     while pos.saturating_add(8) <= io.file_len {
         let header = io.read_at(pos, 8)?;
         let id = fourcc(&header[0..4]);
@@ -1846,10 +2383,7 @@ fn scan_aiff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
             "MARK" | "INST" => ContentKind::Container,
             _ => ContentKind::Binary,
         };
-        let known = matches!(
-            id.as_str(),
-            "COMM" | "SSND" | "MARK" | "INST" | "NAME" | "AUTH" | "ANNO" | "(c) "
-        );
+        let known = aiff_chunk_known(&id);
         let node = builder.add_node(
             Some(root),
             id.clone(),
@@ -1870,8 +2404,27 @@ fn scan_aiff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
             },
             Some(format!("{} bytes @ 0x{payload_offset:08X}", declared)),
         )?;
+        if readable < declared {
+            builder.diagnostic(
+                DiagnosticLevel::Error,
+                "aiff.truncated_chunk",
+                format!("{id} declares {declared} bytes but only {readable} are readable"),
+                Some(pos),
+                Some(node),
+            );
+        }
         match id.as_str() {
+            "COMM" if comm_seen => {
+                builder.diagnostic(
+                    DiagnosticLevel::Error,
+                    "aiff.duplicate_comm",
+                    "a second COMM chunk follows the first one",
+                    Some(pos),
+                    Some(node),
+                );
+            }
             "COMM" => {
+                comm_seen = true;
                 let bytes = io.read_prefix(payload_offset, readable, 64)?;
                 if bytes.len() >= 18 {
                     let channels = read_u16_be(&bytes, 0).unwrap_or(0);
@@ -1914,14 +2467,371 @@ fn scan_aiff<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuild
         if readable < declared {
             break;
         }
+        let (next, missing_pad) = io.next_chunk_offset(payload_offset, declared)?;
+        if missing_pad {
+            builder.diagnostic(
+                DiagnosticLevel::Warning,
+                "aiff.missing_pad",
+                format!("{id} has an odd size but no pad byte"),
+                Some(next),
+                Some(node),
+            );
+        }
+        pos = next;
+    }
+    let actual = io.file_len.saturating_sub(8);
+    if declared_root != actual {
+        builder.diagnostic(
+            DiagnosticLevel::Warning,
+            "aiff.size_mismatch",
+            format!("FORM declares {declared_root} bytes but the file holds {actual}"),
+            Some(4),
+            Some(root),
+        );
+    }
+    Ok(())
+}
+
+fn scan_caf<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuilder) -> Result<()> {
+    if io.file_len < 8 {
+        bail!("truncated CAF header");
+    }
+    let header = io.read_at(0, 8)?;
+    let version = read_u16_be(&header, 4).unwrap_or(0);
+    let root = builder.add_node(
+        None,
+        "caff",
+        0,
+        8,
+        io.file_len.saturating_sub(8),
+        io.file_len.saturating_sub(8),
+        PayloadRef {
+            file_offset: 8,
+            length: io.file_len.saturating_sub(8),
+        },
+        ContentKind::Container,
+        true,
+        ParseStatus::Parsed,
+        Some(format!("version {version}, {} bytes", io.file_len)),
+    )?;
+    let mut pos = 8u64;
+    let mut desc: Option<CafDesc> = None;
+    let mut strings: HashMap<u32, String> = HashMap::new();
+    // `mark` / `regn` names live in `strg`, which may follow them.
+    let mut cue_chunks: Vec<([u8; 4], NodeId, u64, u64)> = Vec::new();
+    let mut pending_data: Option<(NodeId, u64, u64)> = None;
+    while pos.saturating_add(12) <= io.file_len {
+        io.check()?;
+        let chunk_header = io.read_at(pos, 12)?;
+        let id: [u8; 4] = chunk_header[0..4].try_into().unwrap();
+        let id_text = fourcc(&id);
+        let size = i64::from_be_bytes(chunk_header[4..12].try_into().unwrap());
+        let payload_offset = pos + 12;
+        let available = io.file_len.saturating_sub(payload_offset);
+        // A `data` size of -1 means the audio runs to the end of the file.
+        let to_eof = size == -1 && &id == b"data";
+        let declared = if to_eof {
+            available
+        } else {
+            u64::try_from(size).unwrap_or(u64::MAX)
+        };
+        let readable = declared.min(available);
+        let truncated = readable < declared;
+        let content = match &id {
+            b"data" => ContentKind::Audio,
+            b"free" => ContentKind::Padding,
+            b"info" | b"strg" => ContentKind::Text,
+            b"mark" | b"regn" => ContentKind::Container,
+            _ => ContentKind::Binary,
+        };
+        let known = matches!(
+            &id,
+            b"desc"
+                | b"chan"
+                | b"data"
+                | b"pakt"
+                | b"kuki"
+                | b"info"
+                | b"strg"
+                | b"mark"
+                | b"regn"
+                | b"free"
+                | b"uuid"
+                | b"ovvw"
+                | b"peak"
+                | b"inst"
+                | b"midi"
+                | b"umid"
+                | b"lrsc"
+        );
+        let node = builder.add_node(
+            Some(root),
+            id_text.clone(),
+            pos,
+            12,
+            declared,
+            readable,
+            PayloadRef {
+                file_offset: payload_offset,
+                length: readable,
+            },
+            content,
+            known,
+            if truncated {
+                ParseStatus::Truncated
+            } else {
+                ParseStatus::Parsed
+            },
+            Some(if to_eof {
+                format!("to EOF @ 0x{payload_offset:08X}")
+            } else {
+                format!("{declared} bytes @ 0x{payload_offset:08X}")
+            }),
+        )?;
+        if truncated {
+            builder.diagnostic(
+                DiagnosticLevel::Error,
+                "caf.truncated_chunk",
+                if size < 0 {
+                    format!("{id_text} declares an invalid size {size}")
+                } else {
+                    format!("{id_text} declares {declared} bytes but only {readable} are readable")
+                },
+                Some(pos),
+                Some(node),
+            );
+            break;
+        }
+        match &id {
+            b"desc" => {
+                let bytes = io.read_prefix(payload_offset, readable, 32)?;
+                if let Some(parsed) = CafDesc::parse(&bytes) {
+                    builder.document.nodes[node as usize].summary = Some(format!(
+                        "{:.0} Hz, {}, {} ch, {} bit",
+                        parsed.sample_rate,
+                        fourcc(&parsed.format_id),
+                        parsed.channels,
+                        parsed.bits
+                    ));
+                    desc = Some(parsed);
+                }
+            }
+            b"chan" => {
+                let bytes = io.read_prefix(payload_offset, readable, 64 * 1024)?;
+                let channels = desc.map(|d| d.channels as u16).unwrap_or(0);
+                builder.document.nodes[node as usize].summary = Some(
+                    match crate::caf::parse_caf_channel_layout(&bytes, channels) {
+                        Some(layout) => layout.describe(),
+                        None => format!("layout tag 0x{:08X}", read_u32_be(&bytes, 0).unwrap_or(0)),
+                    },
+                );
+            }
+            b"info" => decode_caf_info(io, builder, node, payload_offset, readable)?,
+            b"strg" => {
+                let bytes = io.read_prefix(payload_offset, readable, 8 * 1024 * 1024)?;
+                let parsed = crate::caf::parse_caf_strings(&bytes);
+                builder.document.nodes[node as usize].summary =
+                    Some(format!("{} strings", parsed.len()));
+                strings.extend(parsed);
+            }
+            b"mark" | b"regn" => cue_chunks.push((id, node, payload_offset, readable)),
+            b"data" => {
+                // The audio follows the 4-byte edit count.
+                pending_data.get_or_insert((node, payload_offset + 4, readable.saturating_sub(4)));
+            }
+            _ => {}
+        }
+        if to_eof {
+            break;
+        }
         pos = payload_offset
             .checked_add(declared)
-            .and_then(|v| v.checked_add(declared & 1))
-            .ok_or_else(|| anyhow!("AIFF chunk overflow"))?;
+            .ok_or_else(|| anyhow!("CAF chunk offset overflow"))?;
+    }
+    for (id, node, offset, length) in cue_chunks {
+        let bytes = io.read_prefix(offset, length, 8 * 1024 * 1024)?;
+        let name = |id: u32| strings.get(&id).cloned().unwrap_or_default();
+        if &id == b"mark" {
+            let markers = crate::caf::parse_caf_markers(&bytes);
+            builder.document.nodes[node as usize].summary =
+                Some(format!("{} markers", markers.len()));
+            for (idx, marker) in markers.iter().enumerate() {
+                builder.add_scalar(
+                    node,
+                    format!("Marker {}", marker.id),
+                    format!(
+                        "{} {}: {}",
+                        marker.kind_name(),
+                        marker.position(),
+                        name(marker.id)
+                    ),
+                    SourceRange {
+                        offset: offset + 8 + (idx * 28) as u64,
+                        length: 28,
+                    },
+                )?;
+            }
+        } else {
+            let regions = crate::caf::parse_caf_regions(&bytes);
+            builder.document.nodes[node as usize].summary =
+                Some(format!("{} regions", regions.len()));
+            let mut at = offset + 8;
+            for region in &regions {
+                let entry_len = 12 + (region.markers.len() * 28) as u64;
+                let span = region
+                    .span()
+                    .map(|(start, end)| format!("{start}..{end}"))
+                    .unwrap_or_else(|| "empty".to_string());
+                builder.add_scalar(
+                    node,
+                    format!("Region {}", region.id),
+                    format!("{span}: {}", name(region.id)),
+                    SourceRange {
+                        offset: at,
+                        length: entry_len,
+                    },
+                )?;
+                at += entry_len;
+            }
+        }
+    }
+    if let (Some(desc), Some((node_id, data_offset, data_length))) = (desc, pending_data) {
+        map_caf_audio(builder, desc, node_id, data_offset, data_length);
     }
     Ok(())
 }
 
+/// `CAFAudioDescription` fields the inspector uses.
+#[derive(Clone, Copy, Debug)]
+struct CafDesc {
+    sample_rate: f64,
+    format_id: [u8; 4],
+    format_flags: u32,
+    bytes_per_packet: u32,
+    frames_per_packet: u32,
+    channels: u32,
+    bits: u32,
+}
+
+impl CafDesc {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            sample_rate: f64::from_bits(read_u64_be(bytes, 0)?),
+            format_id: bytes.get(8..12)?.try_into().ok()?,
+            format_flags: read_u32_be(bytes, 12)?,
+            bytes_per_packet: read_u32_be(bytes, 16)?,
+            frames_per_packet: read_u32_be(bytes, 20)?,
+            channels: read_u32_be(bytes, 24)?,
+            bits: read_u32_be(bytes, 28)?,
+        })
+    }
+}
+
+/// Exact byte mapping for little-endian linear PCM of 16 bits or more,
+/// the layout the inspector's sample decoder shares with WAVE.
+fn map_caf_audio(
+    builder: &mut DocumentBuilder,
+    desc: CafDesc,
+    node_id: NodeId,
+    data_offset: u64,
+    data_length: u64,
+) {
+    let is_float = desc.format_flags & 1 != 0;
+    let little_endian = desc.format_flags & 2 != 0;
+    let channels = u16::try_from(desc.channels).unwrap_or(0);
+    let Ok(block_align) = u16::try_from(desc.bytes_per_packet) else {
+        return;
+    };
+    if &desc.format_id != b"lpcm"
+        || !little_endian
+        || desc.frames_per_packet != 1
+        || channels == 0
+        || block_align == 0
+        || block_align % channels != 0
+    {
+        return;
+    }
+    let container_bits = block_align / channels * 8;
+    if container_bits < 16 || desc.bits != container_bits as u32 {
+        return;
+    }
+    builder.document.audio_mapping = Some(AudioByteMapping {
+        node_id,
+        data_offset,
+        data_length,
+        sample_rate: desc.sample_rate.round() as u32,
+        channels,
+        block_align,
+        container_bits,
+        valid_bits: container_bits,
+        format_tag: if is_float { 3 } else { 1 },
+        format_subtype: None,
+        format_name: if is_float {
+            "Linear PCM (float, LE)".to_string()
+        } else {
+            "Linear PCM (LE)".to_string()
+        },
+    });
+}
+
+fn decode_caf_info<R: Read + Seek>(
+    io: &mut ScanIo<'_, R>,
+    builder: &mut DocumentBuilder,
+    parent: NodeId,
+    offset: u64,
+    length: u64,
+) -> Result<()> {
+    let bytes = io.read_prefix(offset, length, 1024 * 1024)?;
+    let count = read_u32_be(&bytes, 0).unwrap_or(0) as usize;
+    builder.document.nodes[parent as usize].summary = Some(format!("{count} entries"));
+    let mut pos = 4usize;
+    for _ in 0..count {
+        let Some(key_len) = bytes
+            .get(pos..)
+            .and_then(|b| b.iter().position(|c| *c == 0))
+        else {
+            break;
+        };
+        let value_start = pos + key_len + 1;
+        let Some(value_len) = bytes
+            .get(value_start..)
+            .and_then(|b| b.iter().position(|c| *c == 0))
+        else {
+            break;
+        };
+        let key = String::from_utf8_lossy(&bytes[pos..pos + key_len]).into_owned();
+        let value =
+            String::from_utf8_lossy(&bytes[value_start..value_start + value_len]).into_owned();
+        let range = SourceRange {
+            offset: offset + value_start as u64,
+            length: value_len as u64,
+        };
+        let scalar = builder.add_scalar(parent, key.clone(), value.clone(), range)?;
+        if let Some(normalized) = normalize_caf_info_key(&key) {
+            builder.normalize(
+                normalized,
+                MetadataValue::Text(value),
+                scalar,
+                range,
+                Some("UTF-8".to_string()),
+                false,
+            );
+        }
+        pos = value_start + value_len + 1;
+    }
+    Ok(())
+}
+
+/// `info` keys are the lower-case names from the CAF spec (`title`,
+/// `artist`, `comments`, `year`, ...).
+fn normalize_caf_info_key(key: &str) -> Option<String> {
+    match key.trim().to_ascii_lowercase().as_str() {
+        "comments" => Some("comment".to_string()),
+        "year" | "recorded date" => Some("date".to_string()),
+        other => normalize_comment_key(other),
+    }
+}
+
 fn decode_extended_80(data: &[u8]) -> Option<f64> {
     if data.len() < 10 {
         return None;
@@ -3080,7 +3990,10 @@ fn scan_ogg<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuilde
                 state.packet_physical_end = segment_start.saturating_add(lace as u64);
                 if lace < 255 {
                     state.packets += 1;
-                    if state.packet_buf.starts_with(b"\x03vorbis")
+                    if state.packet_buf.starts_with(b"OpusHead") {
+                        let packet_start = state.packet_start.unwrap_or(segment_start);
+                        decode_opus_head(builder, state.node, packet_start, &state.packet_buf)?;
+                    } else if state.packet_buf.starts_with(b"\x03vorbis")
                         || state.packet_buf.starts_with(b"OpusTags")
                     {
                         let packet_start = state.packet_start.unwrap_or(segment_start);
@@ -3184,6 +4097,104 @@ fn scan_ogg<R: Read + Seek>(io: &mut ScanIo<'_, R>, builder: &mut DocumentBuilde
     Ok(())
 }
 
+fn decode_opus_head(
+    builder: &mut DocumentBuilder,
+    parent: NodeId,
+    offset: u64,
+    packet: &[u8],
+) -> Result<()> {
+    let length = packet.len() as u64;
+    let Some(head) = crate::opus_codec::OpusHead::parse(packet) else {
+        builder.diagnostic(
+            DiagnosticLevel::Warning,
+            "opus.head_invalid",
+            "OpusHead identification header is malformed",
+            Some(offset),
+            Some(parent),
+        );
+        return Ok(());
+    };
+    let node = builder.add_node(
+        Some(parent),
+        "OpusHead",
+        offset,
+        0,
+        length,
+        length,
+        PayloadRef {
+            file_offset: offset,
+            length,
+        },
+        ContentKind::Container,
+        true,
+        ParseStatus::Parsed,
+        Some(format!(
+            "{} ch, pre-skip {}, input {} Hz, gain {:+.2} dB",
+            head.channels,
+            head.pre_skip,
+            head.input_sample_rate,
+            head.output_gain_db()
+        )),
+    )?;
+    let field = |pos: u64, len: u64| SourceRange {
+        offset: offset + pos,
+        length: len,
+    };
+    builder.add_scalar(node, "Version", head.version.to_string(), field(8, 1))?;
+    builder.add_scalar(node, "Channels", head.channels.to_string(), field(9, 1))?;
+    builder.add_scalar(
+        node,
+        "PreSkip",
+        format!(
+            "{} samples ({:.2} ms)",
+            head.pre_skip,
+            head.pre_skip as f64 / 48.0
+        ),
+        field(10, 2),
+    )?;
+    builder.add_scalar(
+        node,
+        "InputSampleRate",
+        head.input_sample_rate.to_string(),
+        field(12, 4),
+    )?;
+    builder.add_scalar(
+        node,
+        "OutputGain",
+        format!(
+            "{:+.2} dB (Q7.8 {})",
+            head.output_gain_db(),
+            head.output_gain_q8
+        ),
+        field(16, 2),
+    )?;
+    builder.add_scalar(
+        node,
+        "ChannelMappingFamily",
+        head.mapping_family.to_string(),
+        field(18, 1),
+    )?;
+    if head.mapping_family != 0 {
+        builder.add_scalar(
+            node,
+            "StreamCount",
+            format!("{} ({} coupled)", head.stream_count, head.coupled_count),
+            field(19, 2),
+        )?;
+        builder.add_scalar(
+            node,
+            "ChannelMapping",
+            head.mapping
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            field(21, head.mapping.len() as u64),
+        )?;
+    }
+    Ok(())
+}
+
 fn decode_vorbis_packet_bytes(
     builder: &mut DocumentBuilder,
     parent: NodeId,
@@ -3306,10 +4317,13 @@ pub fn summarize_path(
         path,
         InspectOptions {
             budget,
-            decode_xml: request
-                .fields
-                .iter()
-                .any(|key| key.starts_with("ucs.") || key.starts_with("ixml.")),
+            // An all-fields request feeds the full-text index, which needs
+            // iXML notes and UCS names.
+            decode_xml: request.fields.is_empty()
+                || request
+                    .fields
+                    .iter()
+                    .any(|key| key.starts_with("ucs.") || key.starts_with("ixml.")),
             decode_values: true,
             cancel,
         },
@@ -3373,6 +4387,7 @@ pub fn summary_from_document(
             }
             coverage
         },
+        adm_objects: document.adm_objects.clone(),
     }
 }
 
@@ -4036,12 +5051,33 @@ mod tests {
         ogg[18..22].copy_from_slice(&0u32.to_le_bytes());
         ogg[26] = 0;
 
+        let mut opus = vec![0; 27];
+        opus[0..4].copy_from_slice(b"OggS");
+        opus[5] = 2;
+        opus[14..18].copy_from_slice(&1u32.to_le_bytes());
+        opus[26] = 1;
+        let head = crate::opus_codec::OpusHead {
+            version: 1,
+            channels: 2,
+            pre_skip: 312,
+            input_sample_rate: 44_100,
+            output_gain_q8: 0,
+            mapping_family: 0,
+            stream_count: 1,
+            coupled_count: 1,
+            mapping: vec![0, 1],
+        }
+        .to_bytes();
+        opus.push(head.len() as u8);
+        opus.extend_from_slice(&head);
+
         for (bytes, expected) in [
             (aiff, ContainerKind::Aiff),
             (flac, ContainerKind::Flac),
             (id3, ContainerKind::Mp3),
             (mp4, ContainerKind::Mp4),
             (ogg, ContainerKind::Ogg),
+            (opus, ContainerKind::Opus),
         ] {
             let document = inspect_memory(bytes);
             assert_eq!(document.container, expected);
@@ -4215,6 +5251,101 @@ mod tests {
         assert!(document.nodes.iter().any(|node| node.name == "ANNO"));
     }
 
+    #[test]
+    fn w64_guid_chunks_are_walked_with_eight_byte_alignment() {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&48_000u32.to_le_bytes());
+        fmt.extend_from_slice(&96_000u32.to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        let mut body = Vec::new();
+        for (guid, payload) in [
+            (crate::w64::chunk_guid(b"fmt "), fmt),
+            (crate::w64::chunk_guid(b"data"), vec![0; 10]),
+            ([0x11; 16], vec![1, 2, 3]),
+        ] {
+            body.extend_from_slice(&guid);
+            body.extend_from_slice(&((payload.len() + 24) as u64).to_le_bytes());
+            body.extend_from_slice(&payload);
+            body.resize(body.len().next_multiple_of(8), 0);
+        }
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&crate::w64::W64_RIFF_GUID);
+        bytes.extend_from_slice(&((body.len() + 40) as u64).to_le_bytes());
+        bytes.extend_from_slice(&crate::w64::W64_WAVE_GUID);
+        bytes.extend_from_slice(&body);
+        let document = inspect_memory(bytes);
+        assert_eq!(document.container, ContainerKind::W64);
+        let names: Vec<_> = document
+            .nodes
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert!(names.contains(&"fmt "));
+        assert!(names.contains(&"data"));
+        assert!(names.contains(&"{11111111-1111-1111-1111-111111111111}"));
+        assert_eq!(document.audio_mapping.unwrap().frame_count(), 5);
+    }
+
+    #[test]
+    fn caf_chunks_decode_layout_info_and_named_markers() {
+        fn chunk(out: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+            out.extend_from_slice(id);
+            out.extend_from_slice(&(payload.len() as i64).to_be_bytes());
+            out.extend_from_slice(payload);
+        }
+        let mut bytes = b"caff\x00\x01\x00\x00".to_vec();
+        let mut desc = Vec::new();
+        desc.extend_from_slice(&48_000f64.to_be_bytes());
+        desc.extend_from_slice(b"lpcm");
+        for value in [2u32, 4, 1, 2, 16] {
+            desc.extend_from_slice(&value.to_be_bytes());
+        }
+        chunk(&mut bytes, b"desc", &desc);
+        let mut chan = ((101u32 << 16) | 2).to_be_bytes().to_vec();
+        chan.extend_from_slice(&[0; 8]);
+        chunk(&mut bytes, b"chan", &chan);
+        let mut info = 1u32.to_be_bytes().to_vec();
+        info.extend_from_slice(b"title\0Rain\0");
+        chunk(&mut bytes, b"info", &info);
+        let mut mark = vec![0; 4];
+        mark.extend_from_slice(&1u32.to_be_bytes());
+        mark.extend_from_slice(&0u32.to_be_bytes());
+        mark.extend_from_slice(&12.0f64.to_be_bytes());
+        mark.extend_from_slice(&7u32.to_be_bytes());
+        mark.extend_from_slice(&[0; 12]);
+        chunk(&mut bytes, b"mark", &mark);
+        let mut strg = 1u32.to_be_bytes().to_vec();
+        strg.extend_from_slice(&7u32.to_be_bytes());
+        strg.extend_from_slice(&0i64.to_be_bytes());
+        strg.extend_from_slice(b"Hit\0");
+        chunk(&mut bytes, b"strg", &strg);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(-1i64).to_be_bytes());
+        bytes.extend_from_slice(&[0; 4 + 16]);
+        let document = inspect_memory(bytes);
+        assert_eq!(document.container, ContainerKind::Caf);
+        let summary = |name: &str| {
+            document
+                .nodes
+                .iter()
+                .find(|node| node.name == name)
+                .and_then(|node| node.summary.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(summary("chan"), "Stereo (L R)");
+        assert_eq!(summary("Marker 7"), "generic 12: Hit");
+        let title = document
+            .normalized
+            .iter()
+            .find(|field| field.key == "title")
+            .unwrap();
+        assert_eq!(title.values[0].value, MetadataValue::Text("Rain".into()));
+        assert_eq!(document.audio_mapping.unwrap().frame_count(), 4);
+    }
+
     #[test]
     fn raw_fourcc_bytes_remain_lossless_in_column_keys() {
         assert_eq!(
@@ -4337,4 +5468,63 @@ mod tests {
         assert_eq!(std::fs::read(&source).unwrap(), b"source bytes");
         let _ = std::fs::remove_dir_all(dir);
     }
+
+    #[test]
+    fn bw64_chna_and_axml_become_structured_adm_nodes() {
+        fn chunk(out: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+            out.extend_from_slice(id);
+            out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            out.extend_from_slice(payload);
+            if payload.len() & 1 == 1 {
+                out.push(0);
+            }
+        }
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&48_000u32.to_le_bytes());
+        fmt.extend_from_slice(&96_000u32.to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        let mut chna = 1u16.to_le_bytes().to_vec();
+        chna.extend_from_slice(&1u16.to_le_bytes());
+        chna.extend_from_slice(&1u16.to_le_bytes());
+        chna.extend_from_slice(b"ATU_00000001AT_00031001_01AP_00031001\0");
+        let axml = br#"<ebuCoreMain><audioFormatExtended>
+<audioProgramme audioProgrammeID="APR_1001" audioProgrammeName="Mix"><audioContentIDRef>ACO_1001</audioContentIDRef></audioProgramme>
+<audioContent audioContentID="ACO_1001" audioContentName="Speech"><audioObjectIDRef>AO_1001</audioObjectIDRef></audioContent>
+<audioObject audioObjectID="AO_1001" audioObjectName="Narrator"><audioPackFormatIDRef>AP_00031001</audioPackFormatIDRef><audioTrackUIDRef>ATU_00000001</audioTrackUIDRef></audioObject>
+<audioPackFormat audioPackFormatID="AP_00031001" audioPackFormatName="Narrator pack" typeDefinition="Objects"/>
+</audioFormatExtended></ebuCoreMain>"#;
+        let mut body = b"WAVE".to_vec();
+        chunk(&mut body, b"fmt ", &fmt);
+        chunk(&mut body, b"chna", &chna);
+        chunk(&mut body, b"data", &[0; 8]);
+        chunk(&mut body, b"axml", axml);
+        let mut bytes = b"BW64".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&body);
+
+        let document = inspect_memory(bytes);
+        assert_eq!(document.container, ContainerKind::Bw64);
+        let summary = |name: &str| {
+            document
+                .nodes
+                .iter()
+                .find(|node| node.name == name)
+                .and_then(|node| node.summary.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(summary("chna"), "1 tracks, 1 UIDs");
+        assert_eq!(
+            summary("Track 1"),
+            "ATU_00000001 / AT_00031001_01 / AP_00031001"
+        );
+        assert_eq!(summary("audioProgramme"), "Mix (APR_1001)");
+        assert_eq!(summary("audioObject"), "Narrator (AO_1001)");
+        assert_eq!(summary("Packs"), "Narrator pack (AP_00031001)");
+        let object = &document.adm_objects[0];
+        assert_eq!(object.name, "Narrator");
+        assert_eq!(object.channels, [1]);
+    }
 }
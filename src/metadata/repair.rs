@@ -0,0 +1,1107 @@
+//! Copy-out repair of RIFF / RF64 / BW64 WAVE and AIFF / AIFC containers.
+//!
+//! The source is only read. Chunks are recovered with a lenient walk (sizes
+//! that overrun EOF, missing pad bytes, unset recorder sizes), the container
+//! is rebuilt into a new file and every change is listed in the report.
+
+use super::{
+    aiff_chunk_known, fourcc, paths_refer_to_same_file, plausible_chunk_id, read_u16_be,
+    read_u16_le, read_u32_be, read_u32_le, read_u64_le, riff_chunk_known, ContainerKind,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RepairOptions {
+    /// Copy chunks the inspector does not recognize instead of dropping them.
+    pub keep_unknown_chunks: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            keep_unknown_chunks: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RepairChange {
+    pub code: String,
+    pub message: String,
+    /// Source offset the change refers to.
+    pub offset: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RepairedChunk {
+    pub id: String,
+    /// Header offset in the source; `None` for generated chunks (`ds64`).
+    pub source_offset: Option<u64>,
+    pub length: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RepairReport {
+    pub source_container: ContainerKind,
+    pub output_container: ContainerKind,
+    pub source_len: u64,
+    pub output_len: u64,
+    /// Whole audio frames in the rebuilt file, when the format has fixed-size
+    /// frames.
+    pub frames: Option<u64>,
+    pub chunks: Vec<RepairedChunk>,
+    pub changes: Vec<RepairChange>,
+}
+
+pub(super) enum ChunkBody {
+    Source { offset: u64, length: u64 },
+    Bytes(Vec<u8>),
+}
+
+impl ChunkBody {
+    pub(super) fn len(&self) -> u64 {
+        match self {
+            ChunkBody::Source { length, .. } => *length,
+            ChunkBody::Bytes(bytes) => bytes.len() as u64,
+        }
+    }
+}
+
+pub(super) struct PlannedChunk {
+    pub(super) id: [u8; 4],
+    pub(super) header_offset: Option<u64>,
+    pub(super) size_field: u32,
+    pub(super) body: ChunkBody,
+}
+
+impl PlannedChunk {
+    pub(super) fn source(id: [u8; 4], header_offset: u64, offset: u64, length: u64) -> Self {
+        Self {
+            id,
+            header_offset: Some(header_offset),
+            size_field: 0,
+            body: ChunkBody::Source { offset, length },
+        }
+    }
+
+    pub(super) fn stored_len(&self) -> u64 {
+        let len = self.body.len();
+        8 + len + (len & 1)
+    }
+}
+
+/// Top-level chunk sequence of a container with its size fields resolved.
+pub(super) struct ContainerLayout {
+    root: [u8; 4],
+    root_size: u32,
+    form: [u8; 4],
+    big_endian: bool,
+    chunks: Vec<PlannedChunk>,
+}
+
+impl ContainerLayout {
+    pub(super) fn output_len(&self) -> u64 {
+        12 + self
+            .chunks
+            .iter()
+            .map(PlannedChunk::stored_len)
+            .sum::<u64>()
+    }
+
+    pub(super) fn chunks(&self) -> &[PlannedChunk] {
+        &self.chunks
+    }
+}
+
+/// A rebuilt container layout; [`write_repair`] streams it to disk.
+pub struct RepairPlan {
+    pub report: RepairReport,
+    layout: ContainerLayout,
+}
+
+pub fn plan_repair(path: &Path, options: &RepairOptions) -> Result<RepairPlan> {
+    let mut file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    if file_len < 12 {
+        bail!("file is too short to hold a container header");
+    }
+    let header = read_at(&mut file, 0, 12)?;
+    match (&header[0..4], &header[8..12]) {
+        (b"RIFF" | b"RF64" | b"BW64", b"WAVE") => plan_wave(&mut file, file_len, &header, options),
+        (b"FORM", b"AIFF" | b"AIFC") => plan_aiff(&mut file, file_len, &header, options),
+        _ => bail!("repair supports RIFF / RF64 / BW64 WAVE and AIFF / AIFC files"),
+    }
+}
+
+/// Plan and write in one step. The output must not be the source file.
+pub fn repair_to_path(
+    source: &Path,
+    output: &Path,
+    options: &RepairOptions,
+    overwrite: bool,
+    cancel: Option<&AtomicBool>,
+    progress: Option<&AtomicU64>,
+) -> Result<RepairReport> {
+    let plan = plan_repair(source, options)?;
+    write_repair(source, &plan, output, overwrite, cancel, progress)?;
+    Ok(plan.report)
+}
+
+pub fn write_repair(
+    source: &Path,
+    plan: &RepairPlan,
+    output: &Path,
+    overwrite: bool,
+    cancel: Option<&AtomicBool>,
+    progress: Option<&AtomicU64>,
+) -> Result<()> {
+    if output.exists() && !overwrite {
+        bail!("output already exists; explicit overwrite is required");
+    }
+    if paths_refer_to_same_file(source, output) {
+        bail!("repair output must not be the source file");
+    }
+    write_layout(source, &plan.layout, output, cancel, progress)
+}
+
+/// Stream `layout` (chunk bodies read from `source`) into `output` through a
+/// `.part` file that replaces `output` once complete.
+pub(super) fn write_layout(
+    source: &Path,
+    layout: &ContainerLayout,
+    output: &Path,
+    cancel: Option<&AtomicBool>,
+    progress: Option<&AtomicU64>,
+) -> Result<()> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let part = output.with_extension(format!(
+        "{}.part",
+        output
+            .extension()
+            .and_then(|value| value.to_str())
+            .unwrap_or("repair")
+    ));
+    if part.exists() {
+        fs::remove_file(&part)?;
+    }
+    let result = (|| -> Result<()> {
+        let mut input = File::open(source)?;
+        let mut out = BufWriter::new(File::create(&part)?);
+        let size_bytes = |value: u32| {
+            if layout.big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            }
+        };
+        out.write_all(&layout.root)?;
+        out.write_all(&size_bytes(layout.root_size))?;
+        out.write_all(&layout.form)?;
+        let mut written = 12u64;
+        let mut buffer = vec![0u8; 1024 * 1024];
+        for chunk in &layout.chunks {
+            out.write_all(&chunk.id)?;
+            out.write_all(&size_bytes(chunk.size_field))?;
+            written += 8;
+            match &chunk.body {
+                ChunkBody::Bytes(bytes) => {
+                    out.write_all(bytes)?;
+                    written += bytes.len() as u64;
+                }
+                ChunkBody::Source { offset, length } => {
+                    input.seek(SeekFrom::Start(*offset))?;
+                    let mut remaining = *length;
+                    while remaining > 0 {
+                        if cancel.is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                            bail!("write cancelled");
+                        }
+                        let count = remaining.min(buffer.len() as u64) as usize;
+                        input.read_exact(&mut buffer[..count])?;
+                        out.write_all(&buffer[..count])?;
+                        remaining -= count as u64;
+                        written += count as u64;
+                        if let Some(progress) = progress {
+                            progress.store(written, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+            if chunk.body.len() & 1 != 0 {
+                out.write_all(&[0])?;
+                written += 1;
+            }
+        }
+        out.flush()?;
+        let expected = layout.output_len();
+        if written != expected {
+            bail!("wrote {written} bytes, expected {expected}");
+        }
+        Ok(())
+    })();
+    if let Err(err) = result {
+        let _ = fs::remove_file(&part);
+        return Err(err);
+    }
+    if output.exists() {
+        fs::remove_file(output)?;
+    }
+    fs::rename(&part, output)?;
+    Ok(())
+}
+
+pub(super) fn read_at(file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut out = vec![0u8; len];
+    file.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn push_change(
+    changes: &mut Vec<RepairChange>,
+    code: &str,
+    offset: Option<u64>,
+    message: impl Into<String>,
+) {
+    changes.push(RepairChange {
+        code: code.to_string(),
+        message: message.into(),
+        offset,
+    });
+}
+
+/// Same pad-byte rule as the scanner, plus a missing final pad at EOF.
+fn next_chunk_offset(
+    file: &mut File,
+    file_len: u64,
+    payload_offset: u64,
+    declared: u64,
+    id_text: &str,
+    prefix: &str,
+    changes: &mut Vec<RepairChange>,
+) -> Result<u64> {
+    let end = payload_offset + declared;
+    if declared & 1 == 0 {
+        return Ok(end);
+    }
+    let missing_pad = if end >= file_len {
+        true
+    } else {
+        let padded = end + 1;
+        let unpadded_ok = end + 8 <= file_len && plausible_chunk_id(&read_at(file, end, 4)?);
+        let padded_ok = padded + 8 <= file_len && plausible_chunk_id(&read_at(file, padded, 4)?);
+        unpadded_ok && !padded_ok
+    };
+    if missing_pad {
+        push_change(
+            changes,
+            &format!("{prefix}.missing_pad"),
+            Some(end),
+            format!("added the missing pad byte after {id_text}"),
+        );
+        Ok(end)
+    } else {
+        Ok(end + 1)
+    }
+}
+
+fn plan_wave(
+    file: &mut File,
+    file_len: u64,
+    header: &[u8],
+    options: &RepairOptions,
+) -> Result<RepairPlan> {
+    let mut changes = Vec::new();
+    let root: [u8; 4] = header[0..4].try_into().unwrap();
+    let source_container = match &root {
+        b"RF64" => ContainerKind::Rf64,
+        b"BW64" => ContainerKind::Bw64,
+        _ => ContainerKind::RiffWave,
+    };
+    let wide_source = source_container != ContainerKind::RiffWave;
+    let declared_root = read_u32_le(header, 4).unwrap_or(0) as u64;
+    let mut ds64: Option<(u64, u64)> = None;
+    let mut chunks: Vec<PlannedChunk> = Vec::new();
+    let mut fmt: Option<Vec<u8>> = None;
+    let mut data_index: Option<usize> = None;
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        let chunk_header = read_at(file, pos, 8)?;
+        let id: [u8; 4] = chunk_header[0..4].try_into().unwrap();
+        if !plausible_chunk_id(&id) {
+            push_change(
+                &mut changes,
+                "riff.trailing_garbage",
+                Some(pos),
+                format!("dropped {} bytes that do not start a chunk", file_len - pos),
+            );
+            pos = file_len;
+            break;
+        }
+        let id_text = fourcc(&id);
+        let size32 = read_u32_le(&chunk_header, 4).unwrap_or(0);
+        let payload_offset = pos + 8;
+        let available = file_len - payload_offset;
+        let mut declared = size32 as u64;
+        if &id == b"ds64" && available >= 16 {
+            let bytes = read_at(file, payload_offset, 16)?;
+            ds64 = Some((
+                read_u64_le(&bytes, 0).unwrap_or(0),
+                read_u64_le(&bytes, 8).unwrap_or(0),
+            ));
+        }
+        if &id == b"data" && size32 == u32::MAX {
+            if let Some((_, data_size)) = ds64 {
+                declared = data_size;
+            }
+        }
+        // A zero size is a legitimately empty chunk when another chunk follows.
+        let zero_unset = declared == 0
+            && !(available >= 8 && plausible_chunk_id(&read_at(file, payload_offset, 4)?));
+        let unset = zero_unset || (size32 == u32::MAX && ds64.is_none());
+        if &id == b"data" && data_index.is_none() && unset && available > 0 {
+            push_change(
+                &mut changes,
+                "wave.data_size_recovered",
+                Some(pos),
+                format!(
+                    "data size was unset (0x{size32:08X}); recovered {available} bytes up to the end of the file"
+                ),
+            );
+            declared = available;
+        }
+        let mut length = declared;
+        if declared > available {
+            if &id == b"data" {
+                push_change(
+                    &mut changes,
+                    "wave.data_overruns_eof",
+                    Some(pos),
+                    format!("data declares {declared} bytes but only {available} remain; clipped"),
+                );
+                length = available;
+            } else {
+                push_change(
+                    &mut changes,
+                    "riff.truncated_chunk",
+                    Some(pos),
+                    format!(
+                        "{id_text} declares {declared} bytes but only {available} remain; dropped"
+                    ),
+                );
+                pos = file_len;
+                break;
+            }
+        }
+        let keep = match &id {
+            // Regenerated for 64-bit output.
+            b"ds64" => false,
+            b"fmt " if fmt.is_some() => {
+                push_change(
+                    &mut changes,
+                    "wave.duplicate_fmt",
+                    Some(pos),
+                    "dropped a second fmt chunk; the first one is kept",
+                );
+                false
+            }
+            b"fmt " => {
+                fmt = Some(read_at(file, payload_offset, length.min(64) as usize)?);
+                true
+            }
+            b"data" if data_index.is_some() => {
+                push_change(
+                    &mut changes,
+                    "wave.duplicate_data",
+                    Some(pos),
+                    "dropped a second data chunk",
+                );
+                false
+            }
+            _ if riff_chunk_known(&id) || options.keep_unknown_chunks => true,
+            _ => {
+                push_change(
+                    &mut changes,
+                    "riff.unknown_chunk_dropped",
+                    Some(pos),
+                    format!("dropped unknown chunk {id_text}"),
+                );
+                false
+            }
+        };
+        if keep {
+            if &id == b"data" {
+                data_index = Some(chunks.len());
+            }
+            chunks.push(PlannedChunk::source(id, pos, payload_offset, length));
+        }
+        if length < declared {
+            pos = file_len;
+            break;
+        }
+        pos = next_chunk_offset(
+            file,
+            file_len,
+            payload_offset,
+            declared,
+            &id_text,
+            "riff",
+            &mut changes,
+        )?;
+    }
+    if pos < file_len {
+        push_change(
+            &mut changes,
+            "riff.trailing_bytes",
+            Some(pos),
+            format!("dropped {} trailing bytes", file_len - pos),
+        );
+    }
+
+    let fmt = fmt.ok_or_else(|| anyhow!("no fmt chunk to rebuild from"))?;
+    let mut data_index = data_index.ok_or_else(|| anyhow!("no data chunk to rebuild from"))?;
+    let fmt_index = chunks
+        .iter()
+        .position(|chunk| &chunk.id == b"fmt ")
+        .expect("fmt was kept");
+    if fmt_index > data_index {
+        let chunk = chunks.remove(fmt_index);
+        chunks.insert(data_index, chunk);
+        data_index += 1;
+        push_change(
+            &mut changes,
+            "wave.fmt_moved",
+            None,
+            "moved fmt in front of data",
+        );
+    }
+    let block_align = read_u16_le(&fmt, 12).unwrap_or(0) as u64;
+    let frames = (block_align > 0).then(|| {
+        let chunk = &mut chunks[data_index];
+        let length = chunk.body.len();
+        let whole = length - length % block_align;
+        if whole != length {
+            if let ChunkBody::Source { length, .. } = &mut chunk.body {
+                *length = whole;
+            }
+            push_change(
+                &mut changes,
+                "wave.partial_frame",
+                chunk.header_offset,
+                format!(
+                    "trimmed {} bytes of a partial {block_align}-byte frame",
+                    length - whole
+                ),
+            );
+        }
+        whole / block_align
+    });
+
+    let (out_root, output_container) = if wide_source {
+        (root, source_container)
+    } else if wave_needs_rf64(&chunks) {
+        push_change(
+            &mut changes,
+            "rf64.promoted",
+            None,
+            "promoted to RF64: the rebuilt file exceeds the 4 GiB RIFF limit",
+        );
+        (*b"RF64", ContainerKind::Rf64)
+    } else {
+        (root, source_container)
+    };
+    let riff_len = wave_riff_len(&chunks, output_container != ContainerKind::RiffWave);
+    let declared_total = if wide_source {
+        ds64.map(|(riff_size, _)| riff_size)
+    } else {
+        Some(declared_root)
+    };
+    if declared_total != Some(riff_len) {
+        push_change(
+            &mut changes,
+            "riff.size_fixed",
+            Some(4),
+            match declared_total {
+                Some(declared) => format!("container size {declared} -> {riff_len}"),
+                None => format!("container size rebuilt as {riff_len} (no ds64)"),
+            },
+        );
+    }
+    let layout = wave_layout(out_root, chunks, frames)?;
+    Ok(build_plan(
+        source_container,
+        output_container,
+        file_len,
+        frames,
+        changes,
+        layout,
+    ))
+}
+
+const DS64_LEN: u64 = 28;
+
+/// RIFF size field value for a WAVE body of `chunks` (without `ds64`).
+pub(super) fn wave_riff_len(chunks: &[PlannedChunk], wide: bool) -> u64 {
+    4 + chunks.iter().map(PlannedChunk::stored_len).sum::<u64>()
+        + if wide { 8 + DS64_LEN } else { 0 }
+}
+
+/// True when a plain RIFF container cannot address `chunks`.
+pub(super) fn wave_needs_rf64(chunks: &[PlannedChunk]) -> bool {
+    wave_riff_len(chunks, false) > u32::MAX as u64
+        || chunks
+            .iter()
+            .any(|chunk| &chunk.id == b"data" && chunk.body.len() > u32::MAX as u64)
+}
+
+/// Resolve WAVE size fields. For RF64 / BW64 roots the `data` size moves to
+/// a regenerated `ds64` chunk placed first.
+pub(super) fn wave_layout(
+    root: [u8; 4],
+    mut chunks: Vec<PlannedChunk>,
+    frames: Option<u64>,
+) -> Result<ContainerLayout> {
+    let wide = &root != b"RIFF";
+    let riff_len = wave_riff_len(&chunks, wide);
+    if !wide && riff_len > u32::MAX as u64 {
+        bail!("the WAVE body exceeds the 4 GiB RIFF limit");
+    }
+    let mut data_len = None;
+    for chunk in chunks.iter_mut() {
+        let len = chunk.body.len();
+        chunk.size_field = if wide && &chunk.id == b"data" && data_len.is_none() {
+            data_len = Some(len);
+            u32::MAX
+        } else {
+            u32::try_from(len)
+                .map_err(|_| anyhow!("{} exceeds 4 GiB and cannot be stored", fourcc(&chunk.id)))?
+        };
+    }
+    if wide {
+        let mut payload = Vec::with_capacity(DS64_LEN as usize);
+        payload.extend_from_slice(&riff_len.to_le_bytes());
+        payload.extend_from_slice(&data_len.unwrap_or(0).to_le_bytes());
+        payload.extend_from_slice(&frames.unwrap_or(0).to_le_bytes());
+        payload.extend_from_slice(&0u32.to_le_bytes());
+        chunks.insert(
+            0,
+            PlannedChunk {
+                id: *b"ds64",
+                header_offset: None,
+                size_field: DS64_LEN as u32,
+                body: ChunkBody::Bytes(payload),
+            },
+        );
+    }
+    Ok(ContainerLayout {
+        root,
+        root_size: if wide { u32::MAX } else { riff_len as u32 },
+        form: *b"WAVE",
+        big_endian: false,
+        chunks,
+    })
+}
+
+/// Bytes per frame of an uncompressed AIFF / AIFC stream.
+fn aiff_frame_bytes(comm: &[u8], aifc: bool) -> Option<u64> {
+    let channels = read_u16_be(comm, 0)? as u64;
+    let bits = read_u16_be(comm, 6)? as u64;
+    let compression: &[u8] = if aifc { comm.get(18..22)? } else { b"NONE" };
+    let sample_bytes = match compression {
+        b"NONE" | b"twos" | b"sowt" | b"raw " | b"in24" | b"in32" => bits.div_ceil(8),
+        b"fl32" | b"FL32" => 4,
+        b"fl64" | b"FL64" => 8,
+        _ => return None,
+    };
+    (channels > 0 && sample_bytes > 0).then_some(channels * sample_bytes)
+}
+
+fn plan_aiff(
+    file: &mut File,
+    file_len: u64,
+    header: &[u8],
+    options: &RepairOptions,
+) -> Result<RepairPlan> {
+    let mut changes = Vec::new();
+    let form: [u8; 4] = header[8..12].try_into().unwrap();
+    let aifc = &form == b"AIFC";
+    let source_container = if aifc {
+        ContainerKind::Aifc
+    } else {
+        ContainerKind::Aiff
+    };
+    let declared_root = read_u32_be(header, 4).unwrap_or(0) as u64;
+    let mut chunks: Vec<PlannedChunk> = Vec::new();
+    let mut comm: Option<(usize, Vec<u8>)> = None;
+    let mut ssnd_index: Option<usize> = None;
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        let chunk_header = read_at(file, pos, 8)?;
+        let id: [u8; 4] = chunk_header[0..4].try_into().unwrap();
+        if !plausible_chunk_id(&id) {
+            push_change(
+                &mut changes,
+                "aiff.trailing_garbage",
+                Some(pos),
+                format!("dropped {} bytes that do not start a chunk", file_len - pos),
+            );
+            pos = file_len;
+            break;
+        }
+        let id_text = fourcc(&id);
+        let payload_offset = pos + 8;
+        let available = file_len - payload_offset;
+        let declared = read_u32_be(&chunk_header, 4).unwrap_or(0) as u64;
+        let mut length = declared;
+        if declared > available {
+            if &id == b"SSND" {
+                push_change(
+                    &mut changes,
+                    "aiff.ssnd_overruns_eof",
+                    Some(pos),
+                    format!("SSND declares {declared} bytes but only {available} remain; clipped"),
+                );
+                length = available;
+            } else {
+                push_change(
+                    &mut changes,
+                    "aiff.truncated_chunk",
+                    Some(pos),
+                    format!(
+                        "{id_text} declares {declared} bytes but only {available} remain; dropped"
+                    ),
+                );
+                pos = file_len;
+                break;
+            }
+        }
+        let keep = match &id {
+            b"COMM" if comm.is_some() => {
+                push_change(
+                    &mut changes,
+                    "aiff.duplicate_comm",
+                    Some(pos),
+                    "dropped a second COMM chunk; the first one is kept",
+                );
+                false
+            }
+            b"COMM" => {
+                comm = Some((
+                    chunks.len(),
+                    read_at(file, payload_offset, length.min(4096) as usize)?,
+                ));
+                true
+            }
+            b"SSND" if ssnd_index.is_some() => {
+                push_change(
+                    &mut changes,
+                    "aiff.duplicate_ssnd",
+                    Some(pos),
+                    "dropped a second SSND chunk",
+                );
+                false
+            }
+            _ if aiff_chunk_known(&id_text) || options.keep_unknown_chunks => true,
+            _ => {
+                push_change(
+                    &mut changes,
+                    "aiff.unknown_chunk_dropped",
+                    Some(pos),
+                    format!("dropped unknown chunk {id_text}"),
+                );
+                false
+            }
+        };
+        if keep {
+            if &id == b"SSND" {
+                ssnd_index = Some(chunks.len());
+            }
+            chunks.push(PlannedChunk::source(id, pos, payload_offset, length));
+        }
+        if length < declared {
+            pos = file_len;
+            break;
+        }
+        pos = next_chunk_offset(
+            file,
+            file_len,
+            payload_offset,
+            declared,
+            &id_text,
+            "aiff",
+            &mut changes,
+        )?;
+    }
+    if pos < file_len {
+        push_change(
+            &mut changes,
+            "aiff.trailing_bytes",
+            Some(pos),
+            format!("dropped {} trailing bytes", file_len - pos),
+        );
+    }
+
+    let (comm_index, comm) = comm.ok_or_else(|| anyhow!("no COMM chunk to rebuild from"))?;
+    let ssnd_index = ssnd_index.ok_or_else(|| anyhow!("no SSND chunk to rebuild from"))?;
+    if comm.len() < 18 {
+        bail!("COMM chunk is too short");
+    }
+    let ssnd_len = chunks[ssnd_index].body.len();
+    if ssnd_len < 8 {
+        bail!("SSND chunk is too short");
+    }
+    let ChunkBody::Source {
+        offset: ssnd_payload,
+        ..
+    } = chunks[ssnd_index].body
+    else {
+        unreachable!("SSND comes from the source");
+    };
+    let data_offset = read_u32_be(&read_at(file, ssnd_payload, 4)?, 0).unwrap_or(0) as u64;
+    let frame_bytes = aiff_frame_bytes(&comm, aifc);
+    let frames = frame_bytes.map(|frame_bytes| {
+        let audio = ssnd_len.saturating_sub(8 + data_offset);
+        let whole = audio - audio % frame_bytes;
+        if whole != audio {
+            if let ChunkBody::Source { length, .. } = &mut chunks[ssnd_index].body {
+                *length = 8 + data_offset + whole;
+            }
+            push_change(
+                &mut changes,
+                "aiff.partial_frame",
+                chunks[ssnd_index].header_offset,
+                format!(
+                    "trimmed {} bytes of a partial {frame_bytes}-byte frame",
+                    audio - whole
+                ),
+            );
+        }
+        whole / frame_bytes
+    });
+    if let Some(frames) = frames {
+        let declared_frames = read_u32_be(&comm, 2).unwrap_or(0) as u64;
+        if declared_frames != frames {
+            let frames32 =
+                u32::try_from(frames).map_err(|_| anyhow!("AIFF cannot hold {frames} frames"))?;
+            let mut patched = comm.clone();
+            patched[2..6].copy_from_slice(&frames32.to_be_bytes());
+            chunks[comm_index].body = ChunkBody::Bytes(patched);
+            push_change(
+                &mut changes,
+                "aiff.frame_count_fixed",
+                chunks[comm_index].header_offset,
+                format!("COMM frame count {declared_frames} -> {frames}"),
+            );
+        }
+    }
+
+    let form_len = aiff_form_len(&chunks);
+    if declared_root != form_len {
+        push_change(
+            &mut changes,
+            "aiff.size_fixed",
+            Some(4),
+            format!("FORM size {declared_root} -> {form_len}"),
+        );
+    }
+    let layout = aiff_layout(form, chunks)?;
+    Ok(build_plan(
+        source_container,
+        source_container,
+        file_len,
+        frames,
+        changes,
+        layout,
+    ))
+}
+
+fn aiff_form_len(chunks: &[PlannedChunk]) -> u64 {
+    4 + chunks.iter().map(PlannedChunk::stored_len).sum::<u64>()
+}
+
+/// Resolve AIFF / AIFC size fields; `form` is the form type.
+pub(super) fn aiff_layout(form: [u8; 4], mut chunks: Vec<PlannedChunk>) -> Result<ContainerLayout> {
+    let form_len = aiff_form_len(&chunks);
+    if form_len > u32::MAX as u64 {
+        bail!("the rebuilt AIFF would exceed 4 GiB; convert it to RF64 instead");
+    }
+    for chunk in chunks.iter_mut() {
+        chunk.size_field = chunk.body.len() as u32;
+    }
+    Ok(ContainerLayout {
+        root: *b"FORM",
+        root_size: form_len as u32,
+        form,
+        big_endian: true,
+        chunks,
+    })
+}
+
+fn build_plan(
+    source_container: ContainerKind,
+    output_container: ContainerKind,
+    source_len: u64,
+    frames: Option<u64>,
+    changes: Vec<RepairChange>,
+    layout: ContainerLayout,
+) -> RepairPlan {
+    RepairPlan {
+        report: RepairReport {
+            source_container,
+            output_container,
+            source_len,
+            output_len: layout.output_len(),
+            frames,
+            chunks: layout
+                .chunks
+                .iter()
+                .map(|chunk| RepairedChunk {
+                    id: fourcc(&chunk.id),
+                    source_offset: chunk.header_offset,
+                    length: chunk.body.len(),
+                })
+                .collect(),
+            changes,
+        },
+        layout,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{inspect_path, DiagnosticLevel, InspectOptions};
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_repair_{tag}_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn pcm16_stereo_fmt() -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&48_000u32.to_le_bytes());
+        fmt.extend_from_slice(&192_000u32.to_le_bytes());
+        fmt.extend_from_slice(&4u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        fmt
+    }
+
+    fn chunk(id: &[u8; 4], size: u32, payload: &[u8], pad: bool) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(payload);
+        if pad && payload.len() & 1 != 0 {
+            out.push(0);
+        }
+        out
+    }
+
+    fn codes(report: &RepairReport) -> Vec<&str> {
+        report
+            .changes
+            .iter()
+            .map(|change| change.code.as_str())
+            .collect()
+    }
+
+    fn assert_clean(path: &Path) {
+        let document = inspect_path(path, InspectOptions::default()).unwrap();
+        let problems: Vec<_> = document
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.level != DiagnosticLevel::Info)
+            .collect();
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn truncated_recorder_wav_gets_sizes_and_whole_frames() {
+        let dir = temp_dir("recorder");
+        let source = dir.join("take.wav");
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend(chunk(b"fmt ", 16, &pcm16_stereo_fmt(), true));
+        // Recorder died before patching the data size; 10 bytes = 2.5 frames.
+        bytes.extend(chunk(b"data", 0, &[1u8; 10], false));
+        fs::write(&source, &bytes).unwrap();
+
+        let output = dir.join("take.repaired.wav");
+        let report = repair_to_path(
+            &source,
+            &output,
+            &RepairOptions::default(),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        let codes = codes(&report);
+        assert!(codes.contains(&"wave.data_size_recovered"), "{codes:?}");
+        assert!(codes.contains(&"wave.partial_frame"), "{codes:?}");
+        assert!(codes.contains(&"riff.size_fixed"), "{codes:?}");
+        assert_eq!(report.frames, Some(2));
+        assert_eq!(fs::metadata(&output).unwrap().len(), report.output_len);
+        assert_clean(&output);
+        assert_eq!(
+            fs::read(&source).unwrap(),
+            bytes,
+            "source must stay untouched"
+        );
+        let decoded = crate::audio_io::decode_audio_multi(&output).unwrap();
+        assert_eq!(decoded.0[0].len(), 2);
+        assert!(repair_to_path(
+            &source,
+            &output,
+            &RepairOptions::default(),
+            false,
+            None,
+            None
+        )
+        .is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn missing_pad_duplicate_fmt_and_unknown_chunks() {
+        let dir = temp_dir("layout");
+        let source = dir.join("odd.wav");
+        let mut body = b"WAVE".to_vec();
+        body.extend(chunk(b"fmt ", 16, &pcm16_stereo_fmt(), true));
+        body.extend(chunk(b"zzzz", 3, b"abc", false));
+        body.extend(chunk(b"fmt ", 16, &pcm16_stereo_fmt(), true));
+        body.extend(chunk(b"data", 8, &[2u8; 8], true));
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        fs::write(&source, &bytes).unwrap();
+
+        let dropped = plan_repair(
+            &source,
+            &RepairOptions {
+                keep_unknown_chunks: false,
+            },
+        )
+        .unwrap()
+        .report;
+        let codes_dropped = codes(&dropped);
+        assert!(
+            codes_dropped.contains(&"riff.missing_pad"),
+            "{codes_dropped:?}"
+        );
+        assert!(codes_dropped.contains(&"wave.duplicate_fmt"));
+        assert!(codes_dropped.contains(&"riff.unknown_chunk_dropped"));
+        assert_eq!(
+            dropped
+                .chunks
+                .iter()
+                .map(|chunk| chunk.id.as_str())
+                .collect::<Vec<_>>(),
+            ["fmt ", "data"]
+        );
+
+        let output = dir.join("odd.fixed.wav");
+        let kept = repair_to_path(
+            &source,
+            &output,
+            &RepairOptions::default(),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(kept.chunks.iter().any(|chunk| chunk.id == "zzzz"));
+        assert_clean(&output);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn truncated_aiff_fixes_comm_frame_count() {
+        let dir = temp_dir("aiff");
+        let source = dir.join("cut.aif");
+        let mut comm = Vec::new();
+        comm.extend_from_slice(&1u16.to_be_bytes());
+        comm.extend_from_slice(&100u32.to_be_bytes());
+        comm.extend_from_slice(&16u16.to_be_bytes());
+        // 48000 Hz as an 80-bit extended float.
+        comm.extend_from_slice(&[0x40, 0x0E, 0xBB, 0x80, 0, 0, 0, 0, 0, 0]);
+        let mut ssnd = vec![0u8; 8];
+        ssnd.extend([0x10u8, 0x00].repeat(100));
+        let mut body = b"AIFF".to_vec();
+        for (id, payload) in [(b"COMM", comm), (b"SSND", ssnd)] {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+            body.extend(payload);
+        }
+        let mut bytes = b"FORM".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        bytes.extend(body);
+        // Cut into the middle of a 2-byte frame: 73.5 frames remain.
+        bytes.truncate(bytes.len() - 53);
+        fs::write(&source, &bytes).unwrap();
+
+        let output = dir.join("cut.fixed.aif");
+        let report = repair_to_path(
+            &source,
+            &output,
+            &RepairOptions::default(),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        let codes = codes(&report);
+        assert!(codes.contains(&"aiff.ssnd_overruns_eof"), "{codes:?}");
+        assert!(codes.contains(&"aiff.partial_frame"), "{codes:?}");
+        assert!(codes.contains(&"aiff.frame_count_fixed"), "{codes:?}");
+        assert_clean(&output);
+        assert_eq!(report.frames, Some(73));
+        let frames = 73;
+        let decoded = crate::audio_io::decode_audio_multi(&output).unwrap();
+        assert_eq!(decoded.0[0].len(), frames);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn oversized_recovered_data_is_promoted_to_rf64() {
+        let dir = temp_dir("rf64");
+        let source = dir.join("long.wav");
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend(chunk(b"fmt ", 16, &pcm16_stereo_fmt(), true));
+        bytes.extend(chunk(b"data", u32::MAX, &[], false));
+        fs::write(&source, &bytes).unwrap();
+        // Sparse tail: only the headers are read by planning.
+        let file = fs::OpenOptions::new().write(true).open(&source).unwrap();
+        file.set_len(bytes.len() as u64 + (5u64 << 30)).unwrap();
+        drop(file);
+
+        let report = plan_repair(&source, &RepairOptions::default())
+            .unwrap()
+            .report;
+        let codes = codes(&report);
+        assert!(codes.contains(&"wave.data_size_recovered"), "{codes:?}");
+        assert!(codes.contains(&"rf64.promoted"), "{codes:?}");
+        assert_eq!(report.output_container, ContainerKind::Rf64);
+        assert_eq!(report.chunks[0].id, "ds64");
+        assert_eq!(report.frames, Some((5u64 << 30) / 4));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
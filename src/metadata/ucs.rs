@@ -4,6 +4,9 @@
 //! embedded in the binary. Normalization and validation therefore never
 //! depend on network state. Unknown CatIDs are preserved verbatim: read-only
 //! inspection must not invent a replacement.
+//!
+//! [`UcsFields`] is the write-back side: the values the UCS editor owns,
+//! validated against the same table before anything touches a file.
 
 use std::collections::HashMap;
 use std::sync::OnceLock;
@@ -109,6 +112,364 @@ pub fn resolve_cat_id(value: &str) -> UcsResolution {
     }
 }
 
+/// Categories whose CatID, Category, SubCategory or synonyms contain
+/// `query` (case-insensitive), exact CatID matches first, then in
+/// Category/SubCategory order.
+pub fn search_categories(query: &str, limit: usize) -> Vec<UcsCategory> {
+    let needle = query.trim().to_ascii_lowercase();
+    if needle.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let mut matches: Vec<(u8, UcsCategory)> = category_map()
+        .values()
+        .filter_map(|item| {
+            let rank = if item.cat_id.eq_ignore_ascii_case(&needle) {
+                0
+            } else if item.cat_id.to_ascii_lowercase().starts_with(&needle) {
+                1
+            } else if item.category.to_ascii_lowercase().contains(&needle)
+                || item.subcategory.to_ascii_lowercase().contains(&needle)
+            {
+                2
+            } else if item.synonyms.to_ascii_lowercase().contains(&needle) {
+                3
+            } else {
+                return None;
+            };
+            Some((rank, *item))
+        })
+        .collect();
+    matches.sort_by(|(rank_a, a), (rank_b, b)| {
+        rank_a
+            .cmp(rank_b)
+            .then_with(|| a.category.cmp(b.category))
+            .then_with(|| a.subcategory.cmp(b.subcategory))
+    });
+    matches.truncate(limit);
+    matches.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Characters UCS filenames cannot carry on any common filesystem.
+const FILENAME_FORBIDDEN: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// FXName length the UCS guidelines recommend staying under.
+pub const FX_NAME_RECOMMENDED_CHARS: usize = 25;
+
+/// The bext `Description` field is 256 bytes.
+pub const BEXT_DESCRIPTION_BYTES: usize = 256;
+
+/// UCS fields edited and written back by the UCS editor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UcsFields {
+    pub cat_id: String,
+    pub category: String,
+    pub subcategory: String,
+    pub fx_name: String,
+    pub creator_id: String,
+    pub source_id: String,
+    /// Free-text description for the bext chunk; the FXName is written
+    /// there instead when this is empty.
+    pub description: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UcsField {
+    CatId,
+    Category,
+    SubCategory,
+    FxName,
+    CreatorId,
+    SourceId,
+    Description,
+}
+
+impl UcsField {
+    pub const ALL: [UcsField; 7] = [
+        UcsField::CatId,
+        UcsField::Category,
+        UcsField::SubCategory,
+        UcsField::FxName,
+        UcsField::CreatorId,
+        UcsField::SourceId,
+        UcsField::Description,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UcsField::CatId => "CatID",
+            UcsField::Category => "Category",
+            UcsField::SubCategory => "SubCategory",
+            UcsField::FxName => "FXName",
+            UcsField::CreatorId => "CreatorID",
+            UcsField::SourceId => "SourceID",
+            UcsField::Description => "Description",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UcsIssueLevel {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UcsIssue {
+    pub field: UcsField,
+    pub level: UcsIssueLevel,
+    pub message: String,
+}
+
+impl UcsFields {
+    pub fn get(&self, field: UcsField) -> &str {
+        match field {
+            UcsField::CatId => &self.cat_id,
+            UcsField::Category => &self.category,
+            UcsField::SubCategory => &self.subcategory,
+            UcsField::FxName => &self.fx_name,
+            UcsField::CreatorId => &self.creator_id,
+            UcsField::SourceId => &self.source_id,
+            UcsField::Description => &self.description,
+        }
+    }
+
+    pub fn get_mut(&mut self, field: UcsField) -> &mut String {
+        match field {
+            UcsField::CatId => &mut self.cat_id,
+            UcsField::Category => &mut self.category,
+            UcsField::SubCategory => &mut self.subcategory,
+            UcsField::FxName => &mut self.fx_name,
+            UcsField::CreatorId => &mut self.creator_id,
+            UcsField::SourceId => &mut self.source_id,
+            UcsField::Description => &mut self.description,
+        }
+    }
+
+    /// Current values as normalized by the inspector: the resolved value of
+    /// each `ucs.*` key, and the bext description.
+    pub fn from_document(document: &super::MetadataDocument) -> UcsFields {
+        let mut out = UcsFields::default();
+        for (key, field) in [
+            ("ucs.cat_id", UcsField::CatId),
+            ("ucs.category", UcsField::Category),
+            ("ucs.subcategory", UcsField::SubCategory),
+            ("ucs.fx_name", UcsField::FxName),
+            ("ucs.creator_id", UcsField::CreatorId),
+            ("ucs.source_id", UcsField::SourceId),
+            ("bwf.description", UcsField::Description),
+        ] {
+            let value = document
+                .normalized
+                .iter()
+                .find(|normalized| normalized.key == key)
+                .and_then(|normalized| normalized.values.get(normalized.resolved_index))
+                .map(|value| value.value.display());
+            if let Some(value) = value {
+                *out.get_mut(field) = value.trim().to_string();
+            }
+        }
+        out
+    }
+
+    pub fn is_empty(&self) -> bool {
+        UcsField::ALL
+            .iter()
+            .all(|&field| self.get(field).trim().is_empty())
+    }
+
+    /// Batch apply: every non-empty field of `patch` replaces ours, blank
+    /// patch fields keep the per-file value. A new CatID also drops the
+    /// old Category/SubCategory so [`UcsFields::resolved`] refills them.
+    pub fn overlay(&self, patch: &UcsFields) -> UcsFields {
+        let mut out = self.clone();
+        for field in UcsField::ALL {
+            let value = patch.get(field).trim();
+            if !value.is_empty() {
+                *out.get_mut(field) = value.to_string();
+            }
+        }
+        if !patch.cat_id.trim().is_empty() && patch.cat_id.trim() != self.cat_id.trim() {
+            if patch.category.trim().is_empty() {
+                out.category.clear();
+            }
+            if patch.subcategory.trim().is_empty() {
+                out.subcategory.clear();
+            }
+        }
+        out
+    }
+
+    /// Trimmed copy with the exact UCS 8.2.1 CatID spelling and, for known
+    /// CatIDs, Category/SubCategory taken from the table when left empty.
+    pub fn resolved(&self) -> UcsFields {
+        let mut out = UcsFields::default();
+        for field in UcsField::ALL {
+            *out.get_mut(field) = self.get(field).trim().to_string();
+        }
+        let resolution = resolve_cat_id(&out.cat_id);
+        out.cat_id = resolution.canonical;
+        if let Some(category) = resolution.category {
+            if out.category.is_empty() {
+                out.category = category.category.to_string();
+            }
+            if out.subcategory.is_empty() {
+                out.subcategory = category.subcategory.to_string();
+            }
+        }
+        out
+    }
+
+    /// Check the fields against the embedded table and the UCS/ASWG naming
+    /// rules. Errors block writing; warnings are advisory.
+    pub fn validate(&self) -> Vec<UcsIssue> {
+        let mut issues = Vec::new();
+        let mut push = |field: UcsField, level: UcsIssueLevel, message: String| {
+            issues.push(UcsIssue {
+                field,
+                level,
+                message,
+            });
+        };
+        let cat_id = self.cat_id.trim();
+        let resolution = resolve_cat_id(cat_id);
+        if cat_id.is_empty() {
+            push(
+                UcsField::CatId,
+                UcsIssueLevel::Error,
+                "CatID is required".to_string(),
+            );
+        } else if !resolution.known {
+            push(
+                UcsField::CatId,
+                UcsIssueLevel::Error,
+                format!("CatID {cat_id} is not in UCS {VERSION}"),
+            );
+        } else if resolution.was_alias {
+            push(
+                UcsField::CatId,
+                UcsIssueLevel::Warning,
+                format!("CatID will be written as {}", resolution.canonical),
+            );
+        }
+        if let Some(category) = resolution.category {
+            for (field, value, expected) in [
+                (UcsField::Category, self.category.trim(), category.category),
+                (
+                    UcsField::SubCategory,
+                    self.subcategory.trim(),
+                    category.subcategory,
+                ),
+            ] {
+                if !value.is_empty() && !value.eq_ignore_ascii_case(expected) {
+                    push(
+                        field,
+                        UcsIssueLevel::Error,
+                        format!(
+                            "{} {value} does not match CatID {} ({expected})",
+                            field.label(),
+                            category.cat_id
+                        ),
+                    );
+                }
+            }
+        }
+        let fx_name = self.fx_name.trim();
+        if fx_name.is_empty() {
+            push(
+                UcsField::FxName,
+                UcsIssueLevel::Error,
+                "FXName is required".to_string(),
+            );
+        } else if fx_name.chars().count() > FX_NAME_RECOMMENDED_CHARS {
+            push(
+                UcsField::FxName,
+                UcsIssueLevel::Warning,
+                format!("FXName is longer than the {FX_NAME_RECOMMENDED_CHARS} characters UCS recommends"),
+            );
+        }
+        for field in [UcsField::CreatorId, UcsField::SourceId] {
+            if self.get(field).trim().is_empty() {
+                push(
+                    field,
+                    UcsIssueLevel::Warning,
+                    format!("{} is empty; UCS filenames need it", field.label()),
+                );
+            }
+        }
+        for field in [UcsField::FxName, UcsField::CreatorId, UcsField::SourceId] {
+            let value = self.get(field).trim();
+            if value.contains('_') {
+                push(
+                    field,
+                    UcsIssueLevel::Error,
+                    format!(
+                        "{} must not contain '_' (the UCS filename separator)",
+                        field.label()
+                    ),
+                );
+            }
+            if value.contains(FILENAME_FORBIDDEN) || value.chars().any(char::is_control) {
+                push(
+                    field,
+                    UcsIssueLevel::Error,
+                    format!(
+                        "{} contains characters that are not allowed in filenames",
+                        field.label()
+                    ),
+                );
+            }
+        }
+        for field in [UcsField::FxName, UcsField::CreatorId] {
+            if self.get(field).contains('-') {
+                push(
+                    field,
+                    UcsIssueLevel::Warning,
+                    format!(
+                        "{} contains '-', which the ASWG 1.1 rule disallows",
+                        field.label()
+                    ),
+                );
+            }
+        }
+        if self.description.trim().len() > BEXT_DESCRIPTION_BYTES {
+            push(
+                UcsField::Description,
+                UcsIssueLevel::Warning,
+                format!("Description is cut to {BEXT_DESCRIPTION_BYTES} bytes in bext"),
+            );
+        }
+        issues
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.validate()
+            .iter()
+            .any(|issue| issue.level == UcsIssueLevel::Error)
+    }
+
+    /// UCS filename stem `CatID_FXName_CreatorID_SourceID`. Fails when the
+    /// fields do not validate or CreatorID/SourceID are missing.
+    pub fn filename_stem(&self) -> Result<String, String> {
+        if let Some(issue) = self
+            .validate()
+            .into_iter()
+            .find(|issue| issue.level == UcsIssueLevel::Error)
+        {
+            return Err(issue.message);
+        }
+        let resolved = self.resolved();
+        for field in [UcsField::CreatorId, UcsField::SourceId] {
+            if resolved.get(field).is_empty() {
+                return Err(format!("{} is required for a UCS filename", field.label()));
+            }
+        }
+        Ok(format!(
+            "{}_{}_{}_{}",
+            resolved.cat_id, resolved.fx_name, resolved.creator_id, resolved.source_id
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,6 +508,74 @@ mod tests {
         assert!(resolved.known);
     }
 
+    fn forest() -> UcsFields {
+        UcsFields {
+            cat_id: "ambforst".to_string(),
+            fx_name: "Night Crickets".to_string(),
+            creator_id: "ZUKKY".to_string(),
+            source_id: "REC001".to_string(),
+            ..UcsFields::default()
+        }
+    }
+
+    #[test]
+    fn resolved_fields_fill_category_from_the_table() {
+        let resolved = forest().resolved();
+        assert_eq!(resolved.cat_id, "AMBForst");
+        assert_eq!(resolved.category, "AMBIENCE");
+        assert_eq!(resolved.subcategory, "FOREST");
+        assert_eq!(
+            forest().filename_stem().as_deref(),
+            Ok("AMBForst_Night Crickets_ZUKKY_REC001")
+        );
+    }
+
+    #[test]
+    fn validation_rejects_unknown_ids_mismatches_and_separators() {
+        let mut fields = forest();
+        fields.cat_id = "CUSTOMCat".to_string();
+        assert!(fields.has_errors());
+
+        let mut fields = forest();
+        fields.category = "WATER".to_string();
+        let issues = fields.validate();
+        assert!(issues
+            .iter()
+            .any(|issue| issue.field == UcsField::Category && issue.level == UcsIssueLevel::Error));
+
+        let mut fields = forest();
+        fields.fx_name = "Night_Crickets".to_string();
+        assert!(fields.filename_stem().is_err());
+
+        let mut fields = forest();
+        fields.source_id.clear();
+        assert!(!fields.has_errors());
+        assert!(fields.filename_stem().is_err());
+    }
+
+    #[test]
+    fn overlay_keeps_blank_fields_and_refreshes_category_for_new_cat_id() {
+        let base = forest().resolved();
+        let patch = UcsFields {
+            cat_id: "AIRBlow".to_string(),
+            ..UcsFields::default()
+        };
+        let merged = base.overlay(&patch).resolved();
+        assert_eq!(merged.cat_id, "AIRBlow");
+        assert_eq!(merged.category, "AIR");
+        assert_eq!(merged.subcategory, "BLOW");
+        assert_eq!(merged.fx_name, "Night Crickets");
+        assert_eq!(merged.creator_id, "ZUKKY");
+    }
+
+    #[test]
+    fn category_search_ranks_cat_id_prefix_before_synonyms() {
+        let hits = search_categories("ambfor", 5);
+        assert_eq!(hits.first().map(|item| item.cat_id), Some("AMBForst"));
+        assert!(search_categories("", 5).is_empty());
+        assert!(search_categories("crickets", 50).len() <= 50);
+    }
+
     #[test]
     fn unknown_cat_id_is_preserved() {
         let resolved = resolve_cat_id("CUSTOMCat");
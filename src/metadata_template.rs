@@ -0,0 +1,865 @@
+//! Metadata templates: per-file token expressions such as
+//! `{ucs.fxname} - {external.Scene}` rendered over a batch of files and
+//! written through the existing bext / iXML (WAV) and ID3 / MP4 / Vorbis
+//! comment (tags) writers.
+//!
+//! An expression is literal text with `{token}` placeholders. A token may
+//! carry a format after `:` (`{mtime:%Y-%m-%d}`, `{index:03}`) and filters
+//! after `|` (`{external.Scene|upper|default=none}`); `{{` and `}}` are
+//! literal braces.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::ucs::{UcsField, UcsFields};
+use crate::tags::TagKey;
+use crate::wave::{BextFields, IxmlFields};
+
+/// A named set of `target = expression` fields, as stored in the library.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataTemplate {
+    pub name: String,
+    pub fields: Vec<TemplateField>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateField {
+    pub target: String,
+    pub expression: String,
+}
+
+/// Targets offered by the editor; `tag.KEY` also takes any custom key.
+pub const TEMPLATE_TARGETS: [&str; 16] = [
+    "bext.description",
+    "bext.originator",
+    "bext.originator_reference",
+    "bext.origination_date",
+    "bext.origination_time",
+    "ixml.project",
+    "ixml.scene",
+    "ixml.take",
+    "ixml.tape",
+    "ixml.note",
+    "tag.title",
+    "tag.artist",
+    "tag.album",
+    "tag.genre",
+    "tag.comment",
+    "tag.isrc",
+];
+
+/// Tokens and what they read, for the editor's help and the CLI reference.
+pub const TEMPLATE_TOKENS: [(&str, &str); 18] = [
+    ("{name}", "file name without extension"),
+    ("{file}", "file name with extension"),
+    ("{ext}", "extension"),
+    ("{folder}", "parent folder name"),
+    ("{path}", "full path"),
+    ("{user}", "OS user name"),
+    ("{index:03}", "1-based position in the batch (zero padded)"),
+    ("{count}", "number of files in the batch"),
+    ("{now:%Y-%m-%d}", "current date/time (strftime format)"),
+    ("{mtime:%Y-%m-%d}", "file modified date/time"),
+    ("{created:%H:%M:%S}", "file created date/time"),
+    ("{sr}", "sample rate in Hz"),
+    ("{channels}", "channel count"),
+    ("{bits}", "bits per sample"),
+    ("{duration:2}", "length in seconds (decimals)"),
+    ("{transcript}", "transcript text"),
+    (
+        "{ucs.fxname}",
+        "UCS field: catid, category, subcategory, fxname, creatorid, sourceid, description",
+    ),
+    (
+        "{external.Column}",
+        "external data column of the file's row",
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BextTarget {
+    Description,
+    Originator,
+    OriginatorReference,
+    OriginationDate,
+    OriginationTime,
+}
+
+impl BextTarget {
+    /// Field width in the `bext` payload; longer values are truncated.
+    fn limit(self) -> usize {
+        match self {
+            BextTarget::Description => 256,
+            BextTarget::Originator | BextTarget::OriginatorReference => 32,
+            BextTarget::OriginationDate => 10,
+            BextTarget::OriginationTime => 8,
+        }
+    }
+
+    fn slot(self, fields: &mut BextFields) -> &mut String {
+        match self {
+            BextTarget::Description => &mut fields.description,
+            BextTarget::Originator => &mut fields.originator,
+            BextTarget::OriginatorReference => &mut fields.originator_reference,
+            BextTarget::OriginationDate => &mut fields.origination_date,
+            BextTarget::OriginationTime => &mut fields.origination_time,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IxmlTarget {
+    Project,
+    Scene,
+    Take,
+    Tape,
+    Note,
+}
+
+impl IxmlTarget {
+    fn slot(self, fields: &mut IxmlFields) -> &mut String {
+        match self {
+            IxmlTarget::Project => &mut fields.project,
+            IxmlTarget::Scene => &mut fields.scene,
+            IxmlTarget::Take => &mut fields.take,
+            IxmlTarget::Tape => &mut fields.tape,
+            IxmlTarget::Note => &mut fields.note,
+        }
+    }
+}
+
+const BEXT_TARGETS: [(&str, BextTarget); 5] = [
+    ("description", BextTarget::Description),
+    ("originator", BextTarget::Originator),
+    ("originator_reference", BextTarget::OriginatorReference),
+    ("origination_date", BextTarget::OriginationDate),
+    ("origination_time", BextTarget::OriginationTime),
+];
+
+const IXML_TARGETS: [(&str, IxmlTarget); 5] = [
+    ("project", IxmlTarget::Project),
+    ("scene", IxmlTarget::Scene),
+    ("take", IxmlTarget::Take),
+    ("tape", IxmlTarget::Tape),
+    ("note", IxmlTarget::Note),
+];
+
+/// Where a rendered value is written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateTarget {
+    Bext(BextTarget),
+    Ixml(IxmlTarget),
+    Tag(TagKey),
+}
+
+impl TemplateTarget {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let (namespace, name) = raw.split_once('.').unwrap_or((raw, ""));
+        let lookup = |name: &str| name.to_ascii_lowercase();
+        let target = match namespace.to_ascii_lowercase().as_str() {
+            "bext" => BEXT_TARGETS
+                .iter()
+                .find(|(key, _)| *key == lookup(name))
+                .map(|(_, target)| TemplateTarget::Bext(*target)),
+            "ixml" => IXML_TARGETS
+                .iter()
+                .find(|(key, _)| *key == lookup(name))
+                .map(|(_, target)| TemplateTarget::Ixml(*target)),
+            "tag" if !name.trim().is_empty() => Some(TemplateTarget::Tag(TagKey::parse(name))),
+            _ => None,
+        };
+        target.with_context(|| format!("unknown target `{raw}` (use bext.*, ixml.* or tag.KEY)"))
+    }
+
+    pub fn key(&self) -> String {
+        match self {
+            TemplateTarget::Bext(target) => format!(
+                "bext.{}",
+                BEXT_TARGETS
+                    .iter()
+                    .find(|(_, t)| t == target)
+                    .map(|(key, _)| *key)
+                    .unwrap_or_default()
+            ),
+            TemplateTarget::Ixml(target) => format!(
+                "ixml.{}",
+                IXML_TARGETS
+                    .iter()
+                    .find(|(_, t)| t == target)
+                    .map(|(key, _)| *key)
+                    .unwrap_or_default()
+            ),
+            TemplateTarget::Tag(TagKey::Field(field)) => format!("tag.{}", field.key()),
+            TemplateTarget::Tag(TagKey::Custom(key)) => format!("tag.{key}"),
+        }
+    }
+
+    /// bext / iXML targets apply to WAV, tag targets to the tag formats.
+    pub fn applies_to(&self, path: &Path) -> bool {
+        match self {
+            TemplateTarget::Bext(_) | TemplateTarget::Ixml(_) => is_wav_path(path),
+            TemplateTarget::Tag(_) => crate::tags::tag_format(path).is_some(),
+        }
+    }
+}
+
+fn is_wav_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false)
+}
+
+/// Everything a token can read for one file.
+#[derive(Clone, Debug)]
+pub struct TemplateContext {
+    pub path: PathBuf,
+    /// 1-based position in the batch, and the batch size.
+    pub index: usize,
+    pub count: usize,
+    pub now: DateTime<Local>,
+    pub modified: Option<DateTime<Local>>,
+    pub created: Option<DateTime<Local>>,
+    pub user: String,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    pub bits: Option<u16>,
+    pub duration_secs: Option<f32>,
+    pub ucs: UcsFields,
+    /// Column name -> value of the file's external data row.
+    pub external: HashMap<String, String>,
+    pub transcript: Option<String>,
+}
+
+impl TemplateContext {
+    /// Context with only the path-derived values; no file is read.
+    pub fn new(path: &Path, index: usize, count: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            index,
+            count,
+            now: Local::now(),
+            modified: None,
+            created: None,
+            user: std::env::var("USERNAME")
+                .or_else(|_| std::env::var("USER"))
+                .unwrap_or_default(),
+            sample_rate: None,
+            channels: None,
+            bits: None,
+            duration_secs: None,
+            ucs: UcsFields::default(),
+            external: HashMap::new(),
+            transcript: None,
+        }
+    }
+
+    /// File times, audio info and (for WAV) UCS fields of `path`. External
+    /// columns and transcript text are app state and filled in by the caller.
+    pub fn for_path(path: &Path, index: usize, count: usize) -> Self {
+        let mut context = Self::new(path, index, count);
+        if let Ok(meta) = std::fs::metadata(path) {
+            context.modified = meta.modified().ok().map(DateTime::<Local>::from);
+            context.created = meta.created().ok().map(DateTime::<Local>::from);
+        }
+        if let Ok(info) = crate::audio_io::read_audio_info(path) {
+            context.sample_rate = Some(info.sample_rate);
+            context.channels = Some(info.channels);
+            context.bits = (info.bits_per_sample > 0).then_some(info.bits_per_sample);
+            context.duration_secs = info.duration_secs;
+        }
+        if is_wav_path(path) {
+            context.ucs = crate::wave::read_wav_ucs(path).unwrap_or_default();
+        }
+        context
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenSource {
+    Name,
+    File,
+    Ext,
+    Folder,
+    Path,
+    User,
+    Index,
+    Count,
+    Now,
+    Modified,
+    Created,
+    SampleRate,
+    Channels,
+    Bits,
+    Duration,
+    Transcript,
+    Ucs(UcsField),
+    External(String),
+}
+
+impl TokenSource {
+    fn parse(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        if let Some(field) = lower.strip_prefix("ucs.") {
+            let field = field.replace(['_', '-'], "");
+            return UcsField::ALL
+                .into_iter()
+                .find(|candidate| candidate.label().to_ascii_lowercase() == field)
+                .map(TokenSource::Ucs);
+        }
+        if lower.starts_with("external.") {
+            let column = name["external.".len()..].trim();
+            return (!column.is_empty()).then(|| TokenSource::External(column.to_string()));
+        }
+        Some(match lower.as_str() {
+            "name" | "stem" => TokenSource::Name,
+            "file" | "filename" => TokenSource::File,
+            "ext" => TokenSource::Ext,
+            "folder" | "dir" => TokenSource::Folder,
+            "path" => TokenSource::Path,
+            "user" => TokenSource::User,
+            "index" => TokenSource::Index,
+            "count" => TokenSource::Count,
+            "now" => TokenSource::Now,
+            "mtime" | "modified" => TokenSource::Modified,
+            "created" | "ctime" => TokenSource::Created,
+            "sr" | "sample_rate" => TokenSource::SampleRate,
+            "channels" => TokenSource::Channels,
+            "bits" => TokenSource::Bits,
+            "duration" => TokenSource::Duration,
+            "transcript" => TokenSource::Transcript,
+            _ => return None,
+        })
+    }
+
+    fn is_date(&self) -> bool {
+        matches!(
+            self,
+            TokenSource::Now | TokenSource::Modified | TokenSource::Created
+        )
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(
+            self,
+            TokenSource::Index | TokenSource::Count | TokenSource::Duration
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Filter {
+    Upper,
+    Lower,
+    Trim,
+    Max(usize),
+    Default(String),
+}
+
+impl Filter {
+    fn parse(raw: &str) -> Result<Self> {
+        let (name, arg) = match raw.split_once('=') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (raw.trim(), None),
+        };
+        Ok(match (name.to_ascii_lowercase().as_str(), arg) {
+            ("upper", None) => Filter::Upper,
+            ("lower", None) => Filter::Lower,
+            ("trim", None) => Filter::Trim,
+            ("max", Some(arg)) => Filter::Max(
+                arg.trim()
+                    .parse()
+                    .with_context(|| format!("`max` needs a character count: `{raw}`"))?,
+            ),
+            ("default", Some(arg)) => Filter::Default(arg.to_string()),
+            _ => bail!("unknown filter `{raw}` (upper, lower, trim, max=N, default=TEXT)"),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    text: String,
+    source: TokenSource,
+    format: Option<String>,
+    filters: Vec<Filter>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Literal(String),
+    Token(Token),
+}
+
+/// A parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    parts: Vec<Part>,
+}
+
+/// Value of an expression for one file, with the tokens that had no value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rendered {
+    pub value: String,
+    pub missing: Vec<String>,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inner.push(c),
+                            None => bail!("unclosed `{{` in `{text}`"),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Token(Self::parse_token(&inner)?));
+                }
+                '}' => bail!("unmatched `}}` in `{text}` (write `}}}}` for a brace)"),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    fn parse_token(inner: &str) -> Result<Token> {
+        let mut pieces = inner.split('|');
+        let head = pieces.next().unwrap_or_default();
+        let filters = pieces.map(Filter::parse).collect::<Result<Vec<_>>>()?;
+        let (name, format) = match head.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format.to_string())),
+            None => (head.trim(), None),
+        };
+        let source =
+            TokenSource::parse(name).with_context(|| format!("unknown token `{{{name}}}`"))?;
+        if let Some(format) = &format {
+            if source.is_date() {
+                let invalid = chrono::format::StrftimeItems::new(format)
+                    .any(|item| matches!(item, chrono::format::Item::Error));
+                if invalid {
+                    bail!("invalid date format `{format}` in `{{{inner}}}`");
+                }
+            } else if source.is_number() {
+                if format.trim().parse::<usize>().is_err() {
+                    bail!("`{{{name}}}` takes a digit count, not `{format}`");
+                }
+            } else {
+                bail!("`{{{name}}}` takes no format");
+            }
+        }
+        Ok(Token {
+            text: name.to_string(),
+            source,
+            format,
+            filters,
+        })
+    }
+
+    pub fn render(&self, context: &TemplateContext) -> Rendered {
+        let mut out = Rendered::default();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.value.push_str(text),
+                Part::Token(token) => {
+                    let mut value = token_value(token, context).unwrap_or_default();
+                    if value.trim().is_empty() {
+                        match token.filters.iter().find_map(|filter| match filter {
+                            Filter::Default(text) => Some(text),
+                            _ => None,
+                        }) {
+                            Some(fallback) => value = fallback.clone(),
+                            None => out.missing.push(token.text.clone()),
+                        }
+                    }
+                    for filter in &token.filters {
+                        value = match filter {
+                            Filter::Upper => value.to_uppercase(),
+                            Filter::Lower => value.to_lowercase(),
+                            Filter::Trim => value.trim().to_string(),
+                            Filter::Max(limit) => value.chars().take(*limit).collect(),
+                            Filter::Default(_) => value,
+                        };
+                    }
+                    out.value.push_str(&value);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_date(date: Option<DateTime<Local>>, format: Option<&str>) -> Option<String> {
+    date.map(|date| date.format(format.unwrap_or("%Y-%m-%d")).to_string())
+}
+
+fn token_value(token: &Token, context: &TemplateContext) -> Option<String> {
+    let path = &context.path;
+    let os = |value: Option<&std::ffi::OsStr>| value.map(|v| v.to_string_lossy().into_owned());
+    let width = token
+        .format
+        .as_deref()
+        .and_then(|format| format.trim().parse::<usize>().ok());
+    match &token.source {
+        TokenSource::Name => os(path.file_stem()),
+        TokenSource::File => os(path.file_name()),
+        TokenSource::Ext => os(path.extension()),
+        TokenSource::Folder => os(path.parent().and_then(Path::file_name)),
+        TokenSource::Path => Some(path.display().to_string()),
+        TokenSource::User => Some(context.user.clone()),
+        TokenSource::Index => Some(format!(
+            "{:0width$}",
+            context.index,
+            width = width.unwrap_or(0)
+        )),
+        TokenSource::Count => Some(format!(
+            "{:0width$}",
+            context.count,
+            width = width.unwrap_or(0)
+        )),
+        TokenSource::Now => format_date(Some(context.now), token.format.as_deref()),
+        TokenSource::Modified => format_date(context.modified, token.format.as_deref()),
+        TokenSource::Created => format_date(context.created, token.format.as_deref()),
+        TokenSource::SampleRate => context.sample_rate.map(|v| v.to_string()),
+        TokenSource::Channels => context.channels.map(|v| v.to_string()),
+        TokenSource::Bits => context.bits.map(|v| v.to_string()),
+        TokenSource::Duration => context
+            .duration_secs
+            .map(|secs| format!("{secs:.prec$}", prec = width.unwrap_or(3))),
+        TokenSource::Transcript => context
+            .transcript
+            .as_deref()
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" ")),
+        TokenSource::Ucs(field) => Some(context.ucs.get(*field).to_string()),
+        TokenSource::External(column) => context
+            .external
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(column))
+            .map(|(_, value)| value.clone()),
+    }
+}
+
+/// A template whose targets and expressions parsed.
+#[derive(Clone, Debug)]
+pub struct CompiledTemplate {
+    pub fields: Vec<(TemplateTarget, Expression)>,
+}
+
+/// One target of one file after rendering.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RenderedField {
+    pub target: String,
+    pub value: String,
+    /// Tokens without a value; they render empty.
+    pub missing: Vec<String>,
+    /// Length or shape problems the writer will paper over (truncation).
+    pub warnings: Vec<String>,
+    /// False when the target does not exist in this file's format.
+    pub applies: bool,
+}
+
+impl MetadataTemplate {
+    pub fn compile(&self) -> Result<CompiledTemplate> {
+        let mut fields: Vec<(TemplateTarget, Expression)> = Vec::new();
+        for field in &self.fields {
+            let target = TemplateTarget::parse(&field.target)?;
+            if fields.iter().any(|(existing, _)| *existing == target) {
+                bail!("`{}` is set more than once", target.key());
+            }
+            let expression = Expression::parse(&field.expression).with_context(|| target.key())?;
+            fields.push((target, expression));
+        }
+        if fields.is_empty() {
+            bail!("template `{}` has no fields", self.name);
+        }
+        Ok(CompiledTemplate { fields })
+    }
+}
+
+impl CompiledTemplate {
+    pub fn render(&self, context: &TemplateContext) -> Vec<RenderedField> {
+        self.fields
+            .iter()
+            .map(|(target, expression)| {
+                let rendered = expression.render(context);
+                let applies = target.applies_to(&context.path);
+                let mut warnings = Vec::new();
+                if let (TemplateTarget::Bext(bext), true) = (target, applies) {
+                    if rendered.value.len() > bext.limit() {
+                        warnings.push(format!(
+                            "{} bytes; bext keeps the first {}",
+                            rendered.value.len(),
+                            bext.limit()
+                        ));
+                    }
+                    if !rendered.value.is_ascii() {
+                        warnings.push(
+                            "bext fields are ASCII; other characters may not display".to_string(),
+                        );
+                    }
+                }
+                RenderedField {
+                    target: target.key(),
+                    value: rendered.value,
+                    missing: rendered.missing,
+                    warnings,
+                    applies,
+                }
+            })
+            .collect()
+    }
+
+    /// Write the rendered values of the applicable targets into `path`.
+    /// Only the targeted bext / iXML fields and tag keys change; everything
+    /// else in the file is kept by the underlying writers.
+    pub fn write(&self, path: &Path, rendered: &[RenderedField]) -> Result<()> {
+        let values = self
+            .fields
+            .iter()
+            .zip(rendered)
+            .filter(|(_, field)| field.applies)
+            .map(|((target, _), field)| (target, field.value.as_str()))
+            .collect::<Vec<_>>();
+        let mut bext = None;
+        let mut ixml = None;
+        let mut tags = None;
+        for (target, value) in &values {
+            match target {
+                TemplateTarget::Bext(field) => {
+                    if bext.is_none() {
+                        bext = Some(crate::wave::read_wav_bext(path)?.unwrap_or_default());
+                    }
+                    if let Some(fields) = bext.as_mut() {
+                        *field.slot(fields) = value.to_string();
+                    }
+                }
+                TemplateTarget::Ixml(field) => {
+                    if ixml.is_none() {
+                        ixml = Some(crate::wave::read_wav_ixml(path)?.unwrap_or_default());
+                    }
+                    if let Some(fields) = ixml.as_mut() {
+                        *field.slot(fields) = value.to_string();
+                    }
+                }
+                TemplateTarget::Tag(key) => {
+                    if tags.is_none() {
+                        let current = crate::tags::read_tags(path)?;
+                        tags = Some((current.clone(), current));
+                    }
+                    if let Some((_, after)) = tags.as_mut() {
+                        after.set(key, value);
+                    }
+                }
+            }
+        }
+        if let Some(fields) = bext {
+            crate::wave::write_wav_bext(path, &fields)?;
+        }
+        if let Some(fields) = ixml {
+            crate::wave::write_wav_info_ixml(path, &crate::wave::InfoFields::default(), &fields)?;
+        }
+        if let Some((before, after)) = tags {
+            crate::tags::write_tags(path, &crate::tags::diff_tags(&before, &after))?;
+        }
+        Ok(())
+    }
+}
+
+/// Seed library used until the user saves their own.
+pub fn default_templates() -> Vec<MetadataTemplate> {
+    vec![MetadataTemplate {
+        name: "BWF from UCS".to_string(),
+        fields: [
+            ("bext.description", "{ucs.fxname}"),
+            ("bext.originator", "{user}"),
+            ("bext.origination_date", "{mtime:%Y-%m-%d}"),
+            ("bext.origination_time", "{mtime:%H:%M:%S}"),
+        ]
+        .into_iter()
+        .map(|(target, expression)| TemplateField {
+            target: target.to_string(),
+            expression: expression.to_string(),
+        })
+        .collect(),
+    }]
+}
+
+/// Templates stored at `path`, or the seed library when the file is absent.
+pub fn load_library(path: &Path) -> Result<Vec<MetadataTemplate>> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("parse template library: {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(default_templates()),
+        Err(err) => Err(err).with_context(|| format!("read template library: {}", path.display())),
+    }
+}
+
+pub fn save_library(path: &Path, templates: &[MetadataTemplate]) -> Result<()> {
+    let bytes = serde_json::to_vec_pretty(templates)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("json.part");
+    std::fs::write(&temporary, bytes)
+        .with_context(|| format!("write template library: {}", temporary.display()))?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    std::fs::rename(&temporary, path)
+        .with_context(|| format!("write template library: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        let mut context = TemplateContext::new(Path::new("/sfx/Rain/RAIN_Roof_01.wav"), 7, 12);
+        context.user = "mika".to_string();
+        context.modified = Some(
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 4)
+                .and_then(|date| date.and_hms_opt(5, 6, 7))
+                .and_then(|time| time.and_local_timezone(Local).single())
+                .unwrap(),
+        );
+        context.duration_secs = Some(1.5);
+        context.ucs.fx_name = "Heavy Roof".to_string();
+        context
+            .external
+            .insert("Scene".to_string(), "Storm".to_string());
+        context
+    }
+
+    fn render(expression: &str) -> Rendered {
+        Expression::parse(expression).unwrap().render(&context())
+    }
+
+    #[test]
+    fn renders_tokens_formats_and_filters() {
+        assert_eq!(
+            render("{ucs.fxname} - {external.scene|upper}").value,
+            "Heavy Roof - STORM"
+        );
+        assert_eq!(
+            render("{mtime:%Y-%m-%d} {mtime:%H:%M:%S}").value,
+            "2026-03-04 05:06:07"
+        );
+        assert_eq!(
+            render("{name}_{index:03}of{count}").value,
+            "RAIN_Roof_01_007of12"
+        );
+        assert_eq!(
+            render("{folder}.{ext} {duration:1}s {user}").value,
+            "Rain.wav 1.5s mika"
+        );
+        assert_eq!(render("{{{name|lower|max=4}}}").value, "{rain}");
+        let missing = render("{ucs.catid}/{external.Take}/{transcript|default=n/a}");
+        assert_eq!(missing.value, "//n/a");
+        assert_eq!(missing.missing, vec!["ucs.catid", "external.Take"]);
+    }
+
+    #[test]
+    fn rejects_unknown_tokens_targets_and_formats() {
+        for bad in [
+            "{nope}",
+            "{name:%Y}",
+            "{index:x}",
+            "{mtime:%Q}",
+            "{name|shout}",
+            "{name",
+            "name}",
+        ] {
+            assert!(Expression::parse(bad).is_err(), "{bad}");
+        }
+        assert!(TemplateTarget::parse("bext.umid").is_err());
+        assert_eq!(
+            TemplateTarget::parse("tag.MOOD").unwrap(),
+            TemplateTarget::Tag(TagKey::Custom("MOOD".to_string()))
+        );
+        let template = MetadataTemplate {
+            name: "dup".to_string(),
+            fields: vec![
+                TemplateField {
+                    target: "ixml.scene".to_string(),
+                    expression: "a".to_string(),
+                },
+                TemplateField {
+                    target: "IXML.Scene".to_string(),
+                    expression: "b".to_string(),
+                },
+            ],
+        };
+        assert!(template.compile().is_err());
+    }
+
+    #[test]
+    fn writes_bext_and_ixml_fields_of_a_wav() {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves-template-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Door.wav");
+        crate::wave::export_channels_audio(&[vec![0.0; 480]], 48_000, &path).unwrap();
+        crate::wave::write_wav_bext(
+            &path,
+            &BextFields {
+                originator_reference: "keep me".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let template = MetadataTemplate {
+            name: "test".to_string(),
+            fields: [
+                ("bext.description", "{name} take {index}"),
+                ("ixml.scene", "{external.Scene}"),
+                ("tag.title", "{name}"),
+            ]
+            .into_iter()
+            .map(|(target, expression)| TemplateField {
+                target: target.to_string(),
+                expression: expression.to_string(),
+            })
+            .collect(),
+        }
+        .compile()
+        .unwrap();
+        let mut context = TemplateContext::for_path(&path, 2, 2);
+        context
+            .external
+            .insert("Scene".to_string(), "12A".to_string());
+        let rendered = template.render(&context);
+        assert!(!rendered[2].applies, "tags do not apply to WAV");
+        template.write(&path, &rendered).unwrap();
+        let bext = crate::wave::read_wav_bext(&path).unwrap().unwrap();
+        assert_eq!(bext.description, "Door take 2");
+        assert_eq!(bext.originator_reference, "keep me");
+        let ixml = crate::wave::read_wav_ixml(&path).unwrap().unwrap();
+        assert_eq!(ixml.scene, "12A");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
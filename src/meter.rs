@@ -1,8 +1,9 @@
 //! Realtime loudness metering DSP (BS.1770): K-weighting at arbitrary sample
 //! rates, 100 ms block-power windows for momentary (400 ms) / short-term (3 s)
-//! LUFS, and a 4x-oversampled true-peak detector. Pure state machines — the
-//! audio callback only feeds a lock-free tap ring (see `audio.rs`); a
-//! low-priority thread drives these and publishes atomics for the UI.
+//! LUFS with per-channel BS.1770 weights, and a 4x-oversampled true-peak
+//! detector. Pure state machines — the audio callback only feeds a lock-free
+//! tap ring (see `audio.rs`); a low-priority thread drives these and
+//! publishes atomics for the UI.
 
 /// One biquad stage as (b0, b1, b2, a1, a2), a0 normalized to 1.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -109,20 +110,35 @@ const BLOCK_MS: usize = 100;
 const MOMENTARY_BLOCKS: usize = 4; // 400 ms
 const SHORT_TERM_BLOCKS: usize = 30; // 3 s
 
-/// Streaming momentary / short-term loudness over a stereo feed.
+/// Streaming momentary / short-term loudness over a multichannel feed, each
+/// channel's K-weighted power scaled by its BS.1770 weight.
 pub struct LoudnessMeter {
-    filters: [KWeightFilter; 2],
+    filters: Vec<KWeightFilter>,
+    weights: Vec<f64>,
     samples_per_block: usize,
     cur_sum: f64,
     cur_count: usize,
-    /// Mean K-weighted power (z_L + z_R) per completed 100 ms block.
+    /// Mean weighted K-weighted power (sum of G_i * z_i) per completed
+    /// 100 ms block.
     blocks: std::collections::VecDeque<f64>,
 }
 
 impl LoudnessMeter {
+    /// Stereo meter (L and R, weight 1.0 each).
     pub fn new(sr: u32) -> Self {
+        Self::with_weights(sr, &[1.0, 1.0])
+    }
+
+    /// Meter over the channels of `layout`, weighted per BS.1770 (LFE
+    /// excluded, surrounds +1.5 dB).
+    pub fn with_layout(sr: u32, layout: crate::channel_layout::ChannelLayout) -> Self {
+        Self::with_weights(sr, &layout.bs1770_weights())
+    }
+
+    fn with_weights(sr: u32, weights: &[f32]) -> Self {
         Self {
-            filters: [KWeightFilter::new(sr), KWeightFilter::new(sr)],
+            filters: weights.iter().map(|_| KWeightFilter::new(sr)).collect(),
+            weights: weights.iter().map(|&w| f64::from(w)).collect(),
             samples_per_block: (sr.max(1) as usize * BLOCK_MS / 1000).max(1),
             cur_sum: 0.0,
             cur_count: 0,
@@ -142,11 +158,26 @@ impl LoudnessMeter {
     /// Feed one chunk of stereo frames (equal lengths; mono callers pass the
     /// same slice twice).
     pub fn push(&mut self, l: &[f32], r: &[f32]) {
-        let n = l.len().min(r.len());
+        self.push_channels(&[l, r]);
+    }
+
+    /// Feed one chunk with one slice per meter channel (equal lengths).
+    /// Missing channels count as silence; extra slices are ignored.
+    pub fn push_channels(&mut self, chans: &[&[f32]]) {
+        let used = chans.len().min(self.filters.len());
+        let n = chans[..used].iter().map(|c| c.len()).min().unwrap_or(0);
         for i in 0..n {
-            let yl = self.filters[0].process(l[i]);
-            let yr = self.filters[1].process(r[i]);
-            self.cur_sum += yl * yl + yr * yr;
+            let mut power = 0.0f64;
+            for ((filter, &w), chan) in self
+                .filters
+                .iter_mut()
+                .zip(self.weights.iter())
+                .zip(chans.iter())
+            {
+                let y = filter.process(chan[i]);
+                power += w * y * y;
+            }
+            self.cur_sum += power;
             self.cur_count += 1;
             if self.cur_count >= self.samples_per_block {
                 self.blocks.push_back(self.cur_sum / self.cur_count as f64);
@@ -346,6 +377,37 @@ mod tests {
         assert!(meter.momentary_lufs().is_none());
     }
 
+    #[test]
+    fn layout_meter_weights_surrounds_and_drops_lfe() {
+        let sr = 48_000;
+        let amp = 10.0f32.powf(-23.0 / 20.0);
+        let tone = sine(997.0, amp, sr, 4.0);
+        let silence = vec![0.0f32; tone.len()];
+        let layout = crate::channel_layout::ChannelLayout::default_for(6);
+        let measure = |lit: &[usize]| {
+            let chans: Vec<&[f32]> = (0..6)
+                .map(|ch| {
+                    if lit.contains(&ch) {
+                        tone.as_slice()
+                    } else {
+                        silence.as_slice()
+                    }
+                })
+                .collect();
+            let mut meter = LoudnessMeter::with_layout(sr, layout);
+            meter.push_channels(&chans);
+            meter.short_term_lufs().expect("short-term after 4 s")
+        };
+        let front = measure(&[0, 1]);
+        assert!((front + 23.0).abs() <= 0.3, "L/R tone: {front}");
+        let surround = measure(&[4, 5]);
+        assert!(
+            (surround - front - 10.0 * 1.41f32.log10()).abs() <= 0.1,
+            "surround {surround} vs front {front}"
+        );
+        assert!(measure(&[3]) < -60.0, "LFE must not register");
+    }
+
     #[test]
     fn true_peak_sees_intersample_overshoot() {
         // Quarter-band sine sampled at its zero-adjacent points: sample peak
@@ -0,0 +1,556 @@
+//! Minimal Ogg comment-header reader/writer for Vorbis and Opus streams.
+//!
+//! Vorbis comments live in the second header packet of the first logical
+//! stream, so editing them means re-paginating the header packets. Audio
+//! pages are copied through with their payload untouched; only the page
+//! sequence number (and therefore the CRC) is rewritten when the number of
+//! header pages changes. Loop markers use the same `LOOPSTART` / `LOOPEND`
+//! convention as the FLAC path.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::flac_meta::{build_vorbis_comment_payload, parse_vorbis_comments, vendor_string};
+
+const LOOPSTART_KEY: &str = "LOOPSTART";
+const LOOPEND_KEY: &str = "LOOPEND";
+
+const FLAG_CONTINUED: u8 = 0x01;
+const FLAG_BOS: u8 = 0x02;
+const PAGE_HEADER_LEN: usize = 27;
+const MAX_SEGMENTS: usize = 255;
+/// Granule position of a page on which no packet completes.
+const GRANULE_NONE: u64 = u64::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OggCodec {
+    Vorbis,
+    Opus,
+}
+
+impl OggCodec {
+    fn from_ident(packet: &[u8]) -> Option<Self> {
+        if packet.starts_with(b"\x01vorbis") {
+            Some(Self::Vorbis)
+        } else if packet.starts_with(b"OpusHead") {
+            Some(Self::Opus)
+        } else {
+            None
+        }
+    }
+
+    /// Identification + comment (+ setup for Vorbis).
+    fn header_packets(self) -> usize {
+        match self {
+            Self::Vorbis => 3,
+            Self::Opus => 2,
+        }
+    }
+
+    fn comment_magic(self) -> &'static [u8] {
+        match self {
+            Self::Vorbis => b"\x03vorbis",
+            Self::Opus => b"OpusTags",
+        }
+    }
+}
+
+struct OggPage {
+    header_type: u8,
+    granule: u64,
+    serial: u32,
+    sequence: u32,
+    lacing: Vec<u8>,
+    body: Vec<u8>,
+}
+
+/// Header packets of the first logical stream plus where its audio starts.
+struct OggHeaders {
+    codec: OggCodec,
+    serial: u32,
+    packets: Vec<Vec<u8>>,
+    header_pages: u32,
+    audio_offset: u64,
+}
+
+impl OggHeaders {
+    fn comment_payload(&self) -> &[u8] {
+        &self.packets[1][self.codec.comment_magic().len()..]
+    }
+}
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut r = (i as u32) << 24;
+        let mut j = 0;
+        while j < 8 {
+            r = if r & 0x8000_0000 != 0 {
+                (r << 1) ^ 0x04C1_1DB7
+            } else {
+                r << 1
+            };
+            j += 1;
+        }
+        table[i] = r;
+        i += 1;
+    }
+    table
+}
+
+static CRC_TABLE: [u32; 256] = crc_table();
+
+/// Ogg page checksum: CRC-32, polynomial 0x04C11DB7, no reflection, zero
+/// initial value and no final xor.
+fn ogg_crc(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |crc, &b| {
+        (crc << 8) ^ CRC_TABLE[((crc >> 24) as u8 ^ b) as usize]
+    })
+}
+
+/// Fill `buf` as far as possible; returns how many bytes were read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Read the next page, or `None` at a clean end of file. Anything that is not
+/// a well-formed page is an error so a rewrite never drops trailing bytes.
+fn read_page<R: Read>(reader: &mut R) -> Result<Option<OggPage>> {
+    let mut header = [0u8; PAGE_HEADER_LEN];
+    match read_full(reader, &mut header)? {
+        0 => return Ok(None),
+        PAGE_HEADER_LEN => {}
+        _ => anyhow::bail!("truncated Ogg page header"),
+    }
+    if &header[0..4] != b"OggS" || header[4] != 0 {
+        anyhow::bail!("invalid Ogg page (missing OggS capture pattern)");
+    }
+    let mut lacing = vec![0u8; header[26] as usize];
+    reader
+        .read_exact(&mut lacing)
+        .context("truncated Ogg segment table")?;
+    let body_len: usize = lacing.iter().map(|&v| v as usize).sum();
+    let mut body = vec![0u8; body_len];
+    reader
+        .read_exact(&mut body)
+        .context("truncated Ogg page body")?;
+    let page = OggPage {
+        header_type: header[5],
+        granule: u64::from_le_bytes(header[6..14].try_into().unwrap()),
+        serial: u32::from_le_bytes(header[14..18].try_into().unwrap()),
+        sequence: u32::from_le_bytes(header[18..22].try_into().unwrap()),
+        lacing,
+        body,
+    };
+    let stored_crc = u32::from_le_bytes(header[22..26].try_into().unwrap());
+    if encode_page_crc(&page) != stored_crc {
+        anyhow::bail!("Ogg page {} has a bad checksum", page.sequence);
+    }
+    Ok(Some(page))
+}
+
+fn page_bytes(page: &OggPage, crc: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(PAGE_HEADER_LEN + page.lacing.len() + page.body.len());
+    out.extend_from_slice(b"OggS");
+    out.push(0);
+    out.push(page.header_type);
+    out.extend_from_slice(&page.granule.to_le_bytes());
+    out.extend_from_slice(&page.serial.to_le_bytes());
+    out.extend_from_slice(&page.sequence.to_le_bytes());
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.push(page.lacing.len() as u8);
+    out.extend_from_slice(&page.lacing);
+    out.extend_from_slice(&page.body);
+    out
+}
+
+fn encode_page_crc(page: &OggPage) -> u32 {
+    ogg_crc(&page_bytes(page, 0))
+}
+
+fn encode_page(page: &OggPage) -> Vec<u8> {
+    let mut out = page_bytes(page, 0);
+    let crc = ogg_crc(&out);
+    out[22..26].copy_from_slice(&crc.to_le_bytes());
+    out
+}
+
+fn parse_ogg_headers(path: &Path) -> Result<OggHeaders> {
+    let file = File::open(path).with_context(|| format!("open ogg: {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut offset = 0u64;
+    let mut serial = None;
+    let mut codec = None;
+    let mut packets: Vec<Vec<u8>> = Vec::new();
+    let mut partial: Vec<u8> = Vec::new();
+    let mut header_pages = 0u32;
+    loop {
+        let page = read_page(&mut reader)
+            .with_context(|| format!("read ogg headers: {}", path.display()))?
+            .with_context(|| format!("ogg headers end prematurely: {}", path.display()))?;
+        offset += (PAGE_HEADER_LEN + page.lacing.len() + page.body.len()) as u64;
+        header_pages += 1;
+        match serial {
+            None if page.header_type & FLAG_BOS == 0 => {
+                anyhow::bail!("first Ogg page is not a stream start: {}", path.display())
+            }
+            None => serial = Some(page.serial),
+            Some(s) if s != page.serial => anyhow::bail!(
+                "multiplexed Ogg streams are not supported for tag editing: {}",
+                path.display()
+            ),
+            Some(_) => {}
+        }
+        let mut pos = 0usize;
+        for &lace in &page.lacing {
+            partial.extend_from_slice(&page.body[pos..pos + lace as usize]);
+            pos += lace as usize;
+            if lace < 255 {
+                packets.push(std::mem::take(&mut partial));
+            }
+        }
+        if codec.is_none() {
+            if let Some(first) = packets.first() {
+                codec = Some(OggCodec::from_ident(first).with_context(|| {
+                    format!(
+                        "unsupported Ogg codec (only Vorbis and Opus): {}",
+                        path.display()
+                    )
+                })?);
+            }
+        }
+        let Some(codec) = codec else {
+            continue;
+        };
+        let needed = codec.header_packets();
+        if packets.len() > needed || (packets.len() == needed && !partial.is_empty()) {
+            anyhow::bail!(
+                "audio data shares a page with the Ogg header packets: {}",
+                path.display()
+            );
+        }
+        if packets.len() == needed {
+            if !packets[1].starts_with(codec.comment_magic()) {
+                anyhow::bail!("Ogg comment header is missing: {}", path.display());
+            }
+            return Ok(OggHeaders {
+                codec,
+                serial: serial.unwrap_or_default(),
+                packets,
+                header_pages,
+                audio_offset: offset,
+            });
+        }
+    }
+}
+
+/// Lay `packets` out on consecutive pages starting at `first_sequence`,
+/// splitting packets across pages with the continuation flag as needed.
+fn paginate(packets: &[&[u8]], serial: u32, first_sequence: u32, bos: bool) -> Vec<OggPage> {
+    let mut pages = Vec::new();
+    let mut lacing = Vec::new();
+    let mut body = Vec::new();
+    let mut continued = false;
+    let mut packet_ended = false;
+    let flush = |lacing: &mut Vec<u8>,
+                 body: &mut Vec<u8>,
+                 continued: bool,
+                 packet_ended: bool,
+                 pages: &mut Vec<OggPage>| {
+        let mut header_type = if continued { FLAG_CONTINUED } else { 0 };
+        if bos && pages.is_empty() {
+            header_type |= FLAG_BOS;
+        }
+        pages.push(OggPage {
+            header_type,
+            // Header pages carry granule 0 (Vorbis I / RFC 7845).
+            granule: if packet_ended { 0 } else { GRANULE_NONE },
+            serial,
+            sequence: first_sequence + pages.len() as u32,
+            lacing: std::mem::take(lacing),
+            body: std::mem::take(body),
+        });
+    };
+    for packet in packets {
+        let mut rest: &[u8] = packet;
+        loop {
+            let take = rest.len().min(255);
+            lacing.push(take as u8);
+            body.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+            let done = take < 255;
+            packet_ended |= done;
+            if lacing.len() == MAX_SEGMENTS {
+                flush(&mut lacing, &mut body, continued, packet_ended, &mut pages);
+                continued = !done;
+                packet_ended = false;
+            }
+            if done {
+                break;
+            }
+        }
+    }
+    if !lacing.is_empty() {
+        flush(&mut lacing, &mut body, continued, packet_ended, &mut pages);
+    }
+    pages
+}
+
+fn build_comment_packet(codec: OggCodec, vendor: &str, comments: &[(String, String)]) -> Vec<u8> {
+    let mut out = codec.comment_magic().to_vec();
+    out.extend_from_slice(&build_vorbis_comment_payload(vendor, comments));
+    if codec == OggCodec::Vorbis {
+        // Vorbis comment headers end with a set framing bit; Opus has none.
+        out.push(1);
+    }
+    out
+}
+
+/// Rewrite `path` with `comment_packet` in place of the existing comment
+/// header. The identification page stays alone on its page, comment (+ setup)
+/// packets are re-paginated, and audio pages keep their payload and granule.
+fn rewrite_ogg_comment_packet(
+    path: &Path,
+    headers: &OggHeaders,
+    comment_packet: &[u8],
+) -> Result<()> {
+    let mut pages = paginate(&[headers.packets[0].as_slice()], headers.serial, 0, true);
+    let mut rest: Vec<&[u8]> = vec![comment_packet];
+    rest.extend(headers.packets[2..].iter().map(|p| p.as_slice()));
+    pages.extend(paginate(&rest, headers.serial, pages.len() as u32, false));
+    let sequence_delta = pages.len() as i64 - headers.header_pages as i64;
+
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let tmp = parent.join(format!(
+        ".wvp_tmp_oggmeta_{}_{}.ogg",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    // Write inside a closure so both handles close before the rename and a
+    // failed write never leaves the temp file behind.
+    let written = (|| -> Result<()> {
+        let tmp_file =
+            File::create(&tmp).with_context(|| format!("create ogg tmp: {}", tmp.display()))?;
+        let mut out = BufWriter::new(tmp_file);
+        for page in &pages {
+            out.write_all(&encode_page(page))?;
+        }
+        let src =
+            File::open(path).with_context(|| format!("open ogg audio: {}", path.display()))?;
+        let mut src = BufReader::new(src);
+        src.seek(SeekFrom::Start(headers.audio_offset))?;
+        if sequence_delta == 0 {
+            // Same page count: audio pages are byte-identical, stream-copy them.
+            std::io::copy(&mut src, &mut out)
+                .with_context(|| format!("copy ogg audio pages: {}", path.display()))?;
+        } else {
+            while let Some(mut page) = read_page(&mut src)
+                .with_context(|| format!("read ogg audio page: {}", path.display()))?
+            {
+                if page.serial == headers.serial {
+                    page.sequence = (page.sequence as i64 + sequence_delta) as u32;
+                }
+                out.write_all(&encode_page(&page))?;
+            }
+        }
+        out.flush()?;
+        Ok(())
+    })();
+    if let Err(err) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err);
+    }
+    match std::fs::rename(&tmp, path) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            // Windows: rename fails while the target exists; replace via copy.
+            let res = std::fs::copy(&tmp, path)
+                .map(|_| ())
+                .with_context(|| format!("replace ogg: {}", path.display()));
+            let _ = std::fs::remove_file(&tmp);
+            res
+        }
+    }
+}
+
+/// Read all Vorbis comments (KEY=value pairs) from an Ogg Vorbis/Opus file.
+pub fn read_ogg_vorbis_comments(path: &Path) -> Result<Vec<(String, String)>> {
+    let headers = parse_ogg_headers(path)?;
+    Ok(parse_vorbis_comments(headers.comment_payload()))
+}
+
+/// Upsert (`Some`) or remove (`None`) Vorbis comment keys in an Ogg
+/// Vorbis/Opus file, preserving the vendor string and all other comments.
+/// Keys compare case-insensitively per the Vorbis comment spec.
+pub fn update_ogg_vorbis_comments(path: &Path, changes: &[(&str, Option<String>)]) -> Result<()> {
+    let headers = parse_ogg_headers(path)?;
+    let payload = headers.comment_payload();
+    let vendor = vendor_string(payload);
+    let mut comments = parse_vorbis_comments(payload);
+    for (key, value) in changes {
+        comments.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+        if let Some(value) = value {
+            comments.push((key.to_string(), value.clone()));
+        }
+    }
+    let packet = build_comment_packet(headers.codec, &vendor, &comments);
+    if packet == headers.packets[1] {
+        return Ok(());
+    }
+    rewrite_ogg_comment_packet(path, &headers, &packet)
+}
+
+pub fn read_ogg_loop_markers(path: &Path) -> Result<Option<(u64, u64)>> {
+    let comments = read_ogg_vorbis_comments(path)?;
+    let find = |key: &str| {
+        comments
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, v)| v.trim().parse::<u64>().ok())
+    };
+    Ok(match (find(LOOPSTART_KEY), find(LOOPEND_KEY)) {
+        (Some(s), Some(e)) if e > s => Some((s, e)),
+        _ => None,
+    })
+}
+
+pub fn write_ogg_loop_markers(path: &Path, loop_opt: Option<(u64, u64)>) -> Result<()> {
+    let (start, end) = match loop_opt.filter(|(s, e)| e > s) {
+        Some((s, e)) => (Some(s.to_string()), Some(e.to_string())),
+        None => (None, None),
+    };
+    update_ogg_vorbis_comments(path, &[(LOOPSTART_KEY, start), (LOOPEND_KEY, end)])
+}
+
+/// Carry the source's comments over to a freshly encoded `dst` of the same
+/// codec. The destination keeps its own vendor string (it names the encoder
+/// that actually produced the stream).
+pub fn copy_ogg_comments_from_source(src: &Path, dst: &Path) -> Result<()> {
+    let Ok(src_headers) = parse_ogg_headers(src) else {
+        return Ok(());
+    };
+    let dst_headers = parse_ogg_headers(dst)?;
+    if src_headers.codec != dst_headers.codec {
+        return Ok(());
+    }
+    let comments = parse_vorbis_comments(src_headers.comment_payload());
+    if comments.is_empty() {
+        return Ok(());
+    }
+    let vendor = vendor_string(dst_headers.comment_payload());
+    let packet = build_comment_packet(dst_headers.codec, &vendor, &comments);
+    rewrite_ogg_comment_packet(dst, &dst_headers, &packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_ogg_meta_{tag}_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn audio_pages(path: &Path) -> Vec<(u64, Vec<u8>)> {
+        let headers = parse_ogg_headers(path).unwrap();
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        reader.seek(SeekFrom::Start(headers.audio_offset)).unwrap();
+        let mut pages = Vec::new();
+        while let Some(page) = read_page(&mut reader).unwrap() {
+            pages.push((page.granule, page.body));
+        }
+        pages
+    }
+
+    #[test]
+    fn crc_matches_reference_vector() {
+        // "123456789" with the Ogg CRC parameters (CRC-32/CKSUM without the
+        // final inversion).
+        assert_eq!(ogg_crc(b"123456789"), 0x89A1_897F);
+    }
+
+    #[test]
+    fn paginate_splits_long_packets_with_continuation() {
+        let big = vec![7u8; 255 * 300];
+        let pages = paginate(&[big.as_slice(), b"tail".as_slice()], 9, 1, false);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].header_type, 0);
+        assert_eq!(pages[0].granule, GRANULE_NONE);
+        assert_eq!(pages[1].header_type, FLAG_CONTINUED);
+        assert_eq!(pages[1].sequence, 2);
+        assert_eq!(pages[1].granule, 0);
+        let total: usize = pages.iter().map(|p| p.body.len()).sum();
+        assert_eq!(total, big.len() + 4);
+        // A packet that is an exact multiple of 255 needs a terminating 0 lace.
+        let exact = vec![1u8; 510];
+        let pages = paginate(&[exact.as_slice()], 9, 0, true);
+        assert_eq!(pages[0].lacing, vec![255, 255, 0]);
+        assert_eq!(pages[0].header_type, FLAG_BOS);
+    }
+
+    #[test]
+    fn ogg_loop_tags_rewrite_headers_and_keep_audio_pages() {
+        let dir = temp_dir("loop");
+        let sr = 44_100u32;
+        let chans: Vec<Vec<f32>> = (0..2)
+            .map(|ch| {
+                (0..sr as usize / 2)
+                    .map(|i| {
+                        ((i as f32 / sr as f32)
+                            * (330.0 + 110.0 * ch as f32)
+                            * std::f32::consts::TAU)
+                            .sin()
+                            * 0.2
+                    })
+                    .collect()
+            })
+            .collect();
+        for ext in ["ogg", "opus"] {
+            let path = dir.join(format!("tone.{ext}"));
+            crate::wave::export_channels_audio(&chans, sr, &path).unwrap();
+            let before = audio_pages(&path);
+            write_ogg_loop_markers(&path, Some((1_000, 20_000))).unwrap();
+            assert_eq!(read_ogg_loop_markers(&path).unwrap(), Some((1_000, 20_000)));
+            // A comment large enough to spill onto extra header pages forces
+            // audio page renumbering; payloads and granules must not change.
+            update_ogg_vorbis_comments(&path, &[("COMMENT", Some("x".repeat(200_000)))]).unwrap();
+            assert_eq!(audio_pages(&path), before, "{ext}: audio pages changed");
+            let comments = read_ogg_vorbis_comments(&path).unwrap();
+            assert!(comments
+                .iter()
+                .any(|(k, v)| k == "COMMENT" && v.len() == 200_000));
+            write_ogg_loop_markers(&path, None).unwrap();
+            update_ogg_vorbis_comments(&path, &[("COMMENT", None)]).unwrap();
+            assert_eq!(read_ogg_loop_markers(&path).unwrap(), None);
+            assert_eq!(audio_pages(&path), before, "{ext}: audio pages changed");
+            let (decoded, _) = crate::audio_io::decode_audio_multi(&path).unwrap();
+            assert_eq!(decoded.len(), 2);
+            assert!(!decoded[0].is_empty());
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
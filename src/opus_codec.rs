@@ -0,0 +1,513 @@
+//! Ogg Opus decode/encode on top of libopus' multistream API.
+//!
+//! Demuxing is left to symphonia's Ogg reader, which already recognizes
+//! `OpusHead` streams and passes the identification header through as codec
+//! extra data. [`OpusDecoder`] plugs into the codec registry used by
+//! `audio_io`, so list preview, editor and CLI decode paths all pick Opus up
+//! without a format-specific branch. Encoding ([`encode_ogg_opus`]) writes
+//! the pages itself via the `ogg` crate.
+//!
+//! Opus always runs at 48 kHz. Pre-skip is dropped by the decoder, end
+//! padding is trimmed from the final granule position (the Ogg reader is
+//! opened in gapless mode for Opus), and the header output gain is applied
+//! to every decoded sample.
+
+use std::io::Write;
+use std::path::Path;
+use std::ptr::NonNull;
+
+use anyhow::{Context, Result};
+use audiopus_sys as ffi;
+use symphonia::core::audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal, SignalSpec};
+use symphonia::core::codecs::{
+    CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult, CODEC_TYPE_OPUS,
+};
+use symphonia::core::errors::{decode_error, unsupported_error, Result as SymphoniaResult};
+use symphonia::core::formats::Packet;
+
+/// Opus decodes and encodes at 48 kHz regardless of the original input rate.
+pub const OPUS_SAMPLE_RATE: u32 = 48_000;
+/// Longest legal Opus packet: 120 ms at 48 kHz.
+const MAX_FRAME_SAMPLES: usize = 5_760;
+/// Encoder frame size: 20 ms at 48 kHz.
+const ENCODE_FRAME_SAMPLES: usize = 960;
+/// Upper bound for one encoded multistream packet.
+const MAX_PACKET_BYTES: usize = 4_000 * 8;
+/// Audio packets per Ogg page (~1 s at 20 ms frames) to keep seeking granular.
+const PACKETS_PER_PAGE: usize = 50;
+pub const OPUS_MAX_CHANNELS: usize = 8;
+pub const OPUS_BITRATES_KBPS: &[u32] = &[32, 48, 64, 96, 128, 160, 192, 256];
+
+/// Parsed `OpusHead` identification header (RFC 7845 section 5.1).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpusHead {
+    pub version: u8,
+    pub channels: u8,
+    pub pre_skip: u16,
+    pub input_sample_rate: u32,
+    /// Output gain in Q7.8 dB.
+    pub output_gain_q8: i16,
+    pub mapping_family: u8,
+    pub stream_count: u8,
+    pub coupled_count: u8,
+    pub mapping: Vec<u8>,
+}
+
+impl OpusHead {
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() < 19 || &buf[0..8] != b"OpusHead" {
+            return None;
+        }
+        let version = buf[8];
+        if version >> 4 != 0 {
+            return None;
+        }
+        let channels = buf[9];
+        if channels == 0 {
+            return None;
+        }
+        let pre_skip = u16::from_le_bytes([buf[10], buf[11]]);
+        let input_sample_rate = u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]);
+        let output_gain_q8 = i16::from_le_bytes([buf[16], buf[17]]);
+        let mapping_family = buf[18];
+        let (stream_count, coupled_count, mapping) = if mapping_family == 0 {
+            if channels > 2 {
+                return None;
+            }
+            (1, channels - 1, (0..channels).collect())
+        } else {
+            let table = buf.get(19..21 + channels as usize)?;
+            let (streams, coupled) = (table[0], table[1]);
+            if streams == 0 || coupled > streams {
+                return None;
+            }
+            (streams, coupled, table[2..].to_vec())
+        };
+        Some(Self {
+            version,
+            channels,
+            pre_skip,
+            input_sample_rate,
+            output_gain_q8,
+            mapping_family,
+            stream_count,
+            coupled_count,
+            mapping,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(21 + self.mapping.len());
+        out.extend_from_slice(b"OpusHead");
+        out.push(1);
+        out.push(self.channels);
+        out.extend_from_slice(&self.pre_skip.to_le_bytes());
+        out.extend_from_slice(&self.input_sample_rate.to_le_bytes());
+        out.extend_from_slice(&self.output_gain_q8.to_le_bytes());
+        out.push(self.mapping_family);
+        if self.mapping_family != 0 {
+            out.push(self.stream_count);
+            out.push(self.coupled_count);
+            out.extend_from_slice(&self.mapping);
+        }
+        out
+    }
+
+    pub fn output_gain_db(&self) -> f32 {
+        self.output_gain_q8 as f32 / 256.0
+    }
+}
+
+/// Index into WAV-ordered channels for each Vorbis-ordered output slot
+/// (Vorbis I spec 4.3.9, used by Opus mapping family 1). `None` for layouts
+/// where both orders coincide or that have no defined order.
+pub fn vorbis_order_from_wav(channels: usize) -> Option<&'static [usize]> {
+    match channels {
+        3 => Some(&[0, 2, 1]),
+        5 => Some(&[0, 2, 1, 3, 4]),
+        6 => Some(&[0, 2, 1, 4, 5, 3]),
+        7 => Some(&[0, 2, 1, 5, 6, 4, 3]),
+        8 => Some(&[0, 2, 1, 6, 7, 4, 5, 3]),
+        _ => None,
+    }
+}
+
+struct MsDecoder(NonNull<ffi::OpusMSDecoder>);
+
+// SAFETY: the libopus state is only touched through `&mut OpusDecoder`.
+unsafe impl Send for MsDecoder {}
+unsafe impl Sync for MsDecoder {}
+
+impl Drop for MsDecoder {
+    fn drop(&mut self) {
+        unsafe { ffi::opus_multistream_decoder_destroy(self.0.as_ptr()) }
+    }
+}
+
+/// symphonia decoder for `CODEC_TYPE_OPUS`, backed by libopus.
+pub struct OpusDecoder {
+    params: CodecParameters,
+    state: MsDecoder,
+    head: OpusHead,
+    gain: f32,
+    buf: AudioBuffer<f32>,
+    scratch: Vec<f32>,
+    skip_remaining: usize,
+}
+
+impl OpusDecoder {
+    fn reset_skip(&mut self) {
+        self.skip_remaining = self.head.pre_skip as usize;
+    }
+}
+
+impl Decoder for OpusDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> SymphoniaResult<Self> {
+        let Some(head) = params.extra_data.as_deref().and_then(OpusHead::parse) else {
+            return unsupported_error("opus: missing or invalid OpusHead");
+        };
+        let Some(channels) = params.channels else {
+            return unsupported_error("opus: missing channel layout");
+        };
+        if channels.count() != head.channels as usize {
+            return unsupported_error("opus: channel layout does not match OpusHead");
+        }
+        let mut err = 0;
+        let ptr = unsafe {
+            ffi::opus_multistream_decoder_create(
+                OPUS_SAMPLE_RATE as i32,
+                head.channels as i32,
+                head.stream_count as i32,
+                head.coupled_count as i32,
+                head.mapping.as_ptr(),
+                &mut err,
+            )
+        };
+        let Some(state) = NonNull::new(ptr).filter(|_| err == ffi::OPUS_OK) else {
+            return unsupported_error("opus: decoder init failed");
+        };
+        let gain = 10.0f32.powf(head.output_gain_db() / 20.0);
+        let spec = SignalSpec::new(OPUS_SAMPLE_RATE, channels);
+        Ok(Self {
+            params: params.clone(),
+            state: MsDecoder(state),
+            skip_remaining: head.pre_skip as usize,
+            scratch: vec![0.0; MAX_FRAME_SAMPLES * head.channels as usize],
+            head,
+            gain,
+            buf: AudioBuffer::new(MAX_FRAME_SAMPLES as u64, spec),
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[CodecDescriptor {
+            codec: CODEC_TYPE_OPUS,
+            short_name: "opus",
+            long_name: "Opus Interactive Audio Codec (libopus)",
+            inst_func: |params, opts| Ok(Box::new(OpusDecoder::try_new(params, opts)?)),
+        }]
+    }
+
+    fn reset(&mut self) {
+        unsafe {
+            ffi::opus_multistream_decoder_ctl(self.state.0.as_ptr(), ffi::OPUS_RESET_STATE);
+        }
+        // After a seek the stream restarts mid-way; pre-skip applies only to
+        // the very first decoded samples, which a seek to 0 replays.
+        self.skip_remaining = 0;
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> SymphoniaResult<AudioBufferRef<'_>> {
+        self.buf.clear();
+        if packet.ts == 0 {
+            self.reset_skip();
+        }
+        let channels = self.head.channels as usize;
+        let decoded = unsafe {
+            ffi::opus_multistream_decode_float(
+                self.state.0.as_ptr(),
+                packet.data.as_ptr(),
+                packet.data.len() as i32,
+                self.scratch.as_mut_ptr(),
+                MAX_FRAME_SAMPLES as i32,
+                0,
+            )
+        };
+        if decoded < 0 {
+            return decode_error("opus: invalid packet");
+        }
+        let decoded = decoded as usize;
+        let skip = self.skip_remaining.min(decoded);
+        self.skip_remaining -= skip;
+        let keep = decoded - skip;
+        self.buf.render_reserved(Some(keep));
+        let remap = (self.head.mapping_family == 1)
+            .then(|| vorbis_order_from_wav(channels))
+            .flatten();
+        for out_ch in 0..channels {
+            // libopus emits family-1 streams in Vorbis order; expose WAV order.
+            let src_ch = match remap {
+                Some(order) => order.iter().position(|&w| w == out_ch).unwrap_or(out_ch),
+                None => out_ch,
+            };
+            let dst = self.buf.chan_mut(out_ch);
+            for (i, v) in dst.iter_mut().enumerate() {
+                *v = self.scratch[(skip + i) * channels + src_ch] * self.gain;
+            }
+        }
+        self.buf
+            .trim(packet.trim_start() as usize, packet.trim_end() as usize);
+        Ok(self.buf.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buf.as_audio_buffer_ref()
+    }
+}
+
+/// Encoder settings read from `wave::CodecExportOptions` at encode time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpusEncodeSettings {
+    /// Target bitrate for a stereo stream; mono uses half and multichannel
+    /// layouts scale with the number of coded channel pairs.
+    pub bitrate_kbps: u32,
+    /// libopus complexity 0..=10.
+    pub complexity: u8,
+}
+
+struct MsEncoder(NonNull<ffi::OpusMSEncoder>);
+
+impl Drop for MsEncoder {
+    fn drop(&mut self) {
+        unsafe { ffi::opus_multistream_encoder_destroy(self.0.as_ptr()) }
+    }
+}
+
+fn opus_error_text(code: i32) -> String {
+    let ptr = unsafe { ffi::opus_strerror(code) };
+    if ptr.is_null() {
+        return format!("opus error {code}");
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn build_opus_tags(comments: &[(String, String)]) -> Vec<u8> {
+    let vendor = concat!("NeoWaves ", env!("CARGO_PKG_VERSION"));
+    let mut out = Vec::new();
+    out.extend_from_slice(b"OpusTags");
+    out.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    out.extend_from_slice(vendor.as_bytes());
+    out.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+    for (key, value) in comments {
+        let entry = format!("{key}={value}");
+        out.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+        out.extend_from_slice(entry.as_bytes());
+    }
+    out
+}
+
+/// Encode 48 kHz channels (WAV channel order, 1..=8 channels) to an Ogg Opus
+/// file. `input_sample_rate` is recorded in `OpusHead` for information only.
+pub fn encode_ogg_opus(
+    dst: &Path,
+    chans_48k: &[Vec<f32>],
+    input_sample_rate: u32,
+    settings: OpusEncodeSettings,
+) -> Result<()> {
+    let channels = chans_48k.len();
+    if channels == 0 || channels > OPUS_MAX_CHANNELS {
+        anyhow::bail!("opus export supports 1..={OPUS_MAX_CHANNELS} channels (got {channels})");
+    }
+    let mapping_family = if channels <= 2 { 0 } else { 1 };
+    let mut streams = 0i32;
+    let mut coupled = 0i32;
+    let mut mapping = [0u8; OPUS_MAX_CHANNELS];
+    let mut err = 0i32;
+    let ptr = unsafe {
+        ffi::opus_multistream_surround_encoder_create(
+            OPUS_SAMPLE_RATE as i32,
+            channels as i32,
+            mapping_family,
+            &mut streams,
+            &mut coupled,
+            mapping.as_mut_ptr(),
+            ffi::OPUS_APPLICATION_AUDIO,
+            &mut err,
+        )
+    };
+    let encoder = NonNull::new(ptr)
+        .filter(|_| err == ffi::OPUS_OK)
+        .map(MsEncoder)
+        .ok_or_else(|| anyhow::anyhow!("opus encoder init: {}", opus_error_text(err)))?;
+    let st = encoder.0.as_ptr();
+    let pairs_x2 = (coupled * 2 + (streams - coupled)).max(1) as u32;
+    let bitrate_bps = (settings.bitrate_kbps.clamp(6, 512) * 1000 * pairs_x2 / 2) as i32;
+    let mut lookahead = 0i32;
+    unsafe {
+        ffi::opus_multistream_encoder_ctl(st, ffi::OPUS_SET_BITRATE_REQUEST, bitrate_bps);
+        ffi::opus_multistream_encoder_ctl(
+            st,
+            ffi::OPUS_SET_COMPLEXITY_REQUEST,
+            settings.complexity.min(10) as i32,
+        );
+        ffi::opus_multistream_encoder_ctl(
+            st,
+            ffi::OPUS_GET_LOOKAHEAD_REQUEST,
+            &mut lookahead as *mut i32,
+        );
+    }
+    let pre_skip = lookahead.max(0) as u16;
+    let head = OpusHead {
+        version: 1,
+        channels: channels as u8,
+        pre_skip,
+        input_sample_rate,
+        output_gain_q8: 0,
+        mapping_family: mapping_family as u8,
+        stream_count: streams as u8,
+        coupled_count: coupled as u8,
+        mapping: mapping[..channels].to_vec(),
+    };
+
+    let file =
+        std::fs::File::create(dst).with_context(|| format!("create opus: {}", dst.display()))?;
+    let mut writer = ogg::PacketWriter::new(std::io::BufWriter::new(file));
+    let serial = rand::random::<u32>();
+    writer
+        .write_packet(
+            head.to_bytes().into_boxed_slice(),
+            serial,
+            ogg::PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .context("write OpusHead")?;
+    writer
+        .write_packet(
+            build_opus_tags(&[]).into_boxed_slice(),
+            serial,
+            ogg::PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .context("write OpusTags")?;
+
+    // Feed `pre_skip` extra samples of silence at the tail so the encoder's
+    // lookahead flushes the real end of the signal.
+    let frames = chans_48k.iter().map(|c| c.len()).min().unwrap_or(0);
+    let total_in = frames + pre_skip as usize;
+    let packet_count = total_in.div_ceil(ENCODE_FRAME_SAMPLES).max(1);
+    let final_granule = pre_skip as u64 + frames as u64;
+    let order = (mapping_family == 1)
+        .then(|| vorbis_order_from_wav(channels))
+        .flatten();
+    let mut pcm = vec![0.0f32; ENCODE_FRAME_SAMPLES * channels];
+    let mut packet = vec![0u8; MAX_PACKET_BYTES];
+    for index in 0..packet_count {
+        let start = index * ENCODE_FRAME_SAMPLES;
+        for i in 0..ENCODE_FRAME_SAMPLES {
+            for slot in 0..channels {
+                let src_ch = order.map(|o| o[slot]).unwrap_or(slot);
+                pcm[i * channels + slot] = chans_48k[src_ch].get(start + i).copied().unwrap_or(0.0);
+            }
+        }
+        let len = unsafe {
+            ffi::opus_multistream_encode_float(
+                st,
+                pcm.as_ptr(),
+                ENCODE_FRAME_SAMPLES as i32,
+                packet.as_mut_ptr(),
+                packet.len() as i32,
+            )
+        };
+        if len < 0 {
+            anyhow::bail!("opus encode: {}", opus_error_text(len));
+        }
+        let is_last = index + 1 == packet_count;
+        let granule = if is_last {
+            final_granule
+        } else {
+            ((index + 1) * ENCODE_FRAME_SAMPLES) as u64
+        };
+        let end_info = if is_last {
+            ogg::PacketWriteEndInfo::EndStream
+        } else if (index + 1) % PACKETS_PER_PAGE == 0 {
+            ogg::PacketWriteEndInfo::EndPage
+        } else {
+            ogg::PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(
+                packet[..len as usize].to_vec().into_boxed_slice(),
+                serial,
+                end_info,
+                granule,
+            )
+            .context("write opus packet")?;
+    }
+    writer
+        .inner_mut()
+        .flush()
+        .with_context(|| format!("flush opus: {}", dst.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opus_head_roundtrips_family_one() {
+        let head = OpusHead {
+            version: 1,
+            channels: 6,
+            pre_skip: 312,
+            input_sample_rate: 44_100,
+            output_gain_q8: -256,
+            mapping_family: 1,
+            stream_count: 4,
+            coupled_count: 2,
+            mapping: vec![0, 4, 1, 2, 3, 5],
+        };
+        let parsed = OpusHead::parse(&head.to_bytes()).expect("parse");
+        assert_eq!(parsed, head);
+        assert!((parsed.output_gain_db() + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn opus_head_family_zero_synthesizes_mapping() {
+        let head = OpusHead {
+            version: 1,
+            channels: 2,
+            pre_skip: 312,
+            input_sample_rate: 48_000,
+            output_gain_q8: 0,
+            mapping_family: 0,
+            stream_count: 1,
+            coupled_count: 1,
+            mapping: vec![0, 1],
+        };
+        let bytes = head.to_bytes();
+        assert_eq!(bytes.len(), 19);
+        assert_eq!(OpusHead::parse(&bytes), Some(head));
+    }
+
+    #[test]
+    fn vorbis_order_is_a_permutation() {
+        for n in 1..=8 {
+            if let Some(order) = vorbis_order_from_wav(n) {
+                let mut sorted = order.to_vec();
+                sorted.sort_unstable();
+                assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+            }
+        }
+    }
+}
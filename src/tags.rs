@@ -0,0 +1,663 @@
+//! Cross-format text tags: ID3v2 (MP3), MP4 `ilst` atoms (M4A) and Vorbis
+//! comments (FLAC / Ogg Vorbis / Opus) behind one field model.
+//!
+//! Writes read the existing tag, change only the requested keys and write it
+//! back, so frames/atoms/comments the model does not know about survive.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use id3::frame::{Comment, Content, ExtendedText, Frame};
+use id3::{TagLike, Version};
+use mp4ameta::{Data, DataIdent, Fourcc, Tag as Mp4Tag};
+
+const ITUNES_MEAN: &str = "com.apple.iTunes";
+/// Freeform atoms owned by the exporter (gapless info); not user tags.
+const MP4_HIDDEN_FREEFORM: [&str; 1] = ["iTunSMPB"];
+/// Vorbis comments that carry binary payloads (artwork) rather than text.
+const VORBIS_HIDDEN_KEYS: [&str; 2] = ["METADATA_BLOCK_PICTURE", "COVERART"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TagField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    Comment,
+    Isrc,
+}
+
+impl TagField {
+    pub const ALL: [TagField; 6] = [
+        TagField::Title,
+        TagField::Artist,
+        TagField::Album,
+        TagField::Genre,
+        TagField::Comment,
+        TagField::Isrc,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TagField::Title => "Title",
+            TagField::Artist => "Artist",
+            TagField::Album => "Album",
+            TagField::Genre => "Genre",
+            TagField::Comment => "Comment",
+            TagField::Isrc => "ISRC",
+        }
+    }
+
+    /// Key used by the CLI (`--set title=...`) and JSON output.
+    pub fn key(self) -> &'static str {
+        match self {
+            TagField::Title => "title",
+            TagField::Artist => "artist",
+            TagField::Album => "album",
+            TagField::Genre => "genre",
+            TagField::Comment => "comment",
+            TagField::Isrc => "isrc",
+        }
+    }
+
+    pub fn from_key(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        Self::ALL
+            .into_iter()
+            .find(|field| field.key().eq_ignore_ascii_case(raw))
+    }
+
+    fn id3_frame(self) -> &'static str {
+        match self {
+            TagField::Title => "TIT2",
+            TagField::Artist => "TPE1",
+            TagField::Album => "TALB",
+            TagField::Genre => "TCON",
+            TagField::Comment => "COMM",
+            TagField::Isrc => "TSRC",
+        }
+    }
+
+    fn vorbis_key(self) -> &'static str {
+        match self {
+            TagField::Title => "TITLE",
+            TagField::Artist => "ARTIST",
+            TagField::Album => "ALBUM",
+            TagField::Genre => "GENRE",
+            TagField::Comment => "COMMENT",
+            TagField::Isrc => "ISRC",
+        }
+    }
+}
+
+/// A standard field or a custom key: TXXX description (ID3), iTunes
+/// freeform name (MP4) or any other Vorbis comment key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TagKey {
+    Field(TagField),
+    Custom(String),
+}
+
+impl TagKey {
+    /// Standard field names map to [`TagKey::Field`]; anything else is a
+    /// custom key (a `custom:` prefix forces that, e.g. `custom:title`).
+    pub fn parse(raw: &str) -> Self {
+        if let Some(custom) = raw.trim().strip_prefix("custom:") {
+            return TagKey::Custom(custom.trim().to_string());
+        }
+        match TagField::from_key(raw) {
+            Some(field) => TagKey::Field(field),
+            None => TagKey::Custom(raw.trim().to_string()),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            TagKey::Field(field) => field.label().to_string(),
+            TagKey::Custom(key) => key.clone(),
+        }
+    }
+}
+
+/// Upsert (`Some`) or remove (`None` or empty) one key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagChange {
+    pub key: TagKey,
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TagFormat {
+    Id3v2,
+    Mp4,
+    FlacVorbis,
+    OggVorbis,
+}
+
+impl TagFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            TagFormat::Id3v2 => "ID3v2",
+            TagFormat::Mp4 => "MP4",
+            TagFormat::FlacVorbis | TagFormat::OggVorbis => "Vorbis comment",
+        }
+    }
+}
+
+pub fn tag_format(path: &Path) -> Option<TagFormat> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "mp3" => Some(TagFormat::Id3v2),
+        "m4a" => Some(TagFormat::Mp4),
+        "flac" => Some(TagFormat::FlacVorbis),
+        "ogg" | "opus" => Some(TagFormat::OggVorbis),
+        _ => None,
+    }
+}
+
+/// Text tags of one file. Empty strings mean "not present"; multi-valued
+/// keys show their first value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioTags {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub genre: String,
+    pub comment: String,
+    pub isrc: String,
+    /// Custom keys in file order.
+    pub custom: Vec<(String, String)>,
+}
+
+impl AudioTags {
+    pub fn get(&self, field: TagField) -> &str {
+        match field {
+            TagField::Title => &self.title,
+            TagField::Artist => &self.artist,
+            TagField::Album => &self.album,
+            TagField::Genre => &self.genre,
+            TagField::Comment => &self.comment,
+            TagField::Isrc => &self.isrc,
+        }
+    }
+
+    pub fn get_mut(&mut self, field: TagField) -> &mut String {
+        match field {
+            TagField::Title => &mut self.title,
+            TagField::Artist => &mut self.artist,
+            TagField::Album => &mut self.album,
+            TagField::Genre => &mut self.genre,
+            TagField::Comment => &mut self.comment,
+            TagField::Isrc => &mut self.isrc,
+        }
+    }
+
+    pub fn value(&self, key: &TagKey) -> &str {
+        match key {
+            TagKey::Field(field) => self.get(*field),
+            TagKey::Custom(name) => self
+                .custom
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+                .unwrap_or(""),
+        }
+    }
+
+    pub fn set(&mut self, key: &TagKey, value: &str) {
+        match key {
+            TagKey::Field(field) => *self.get_mut(*field) = value.to_string(),
+            TagKey::Custom(name) => {
+                match self
+                    .custom
+                    .iter_mut()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                {
+                    Some((_, v)) => *v = value.to_string(),
+                    None if !value.is_empty() => {
+                        self.custom.push((name.clone(), value.to_string()))
+                    }
+                    None => {}
+                }
+                self.custom.retain(|(_, v)| !v.is_empty());
+            }
+        }
+    }
+
+    pub fn apply(&mut self, changes: &[TagChange]) {
+        for change in changes {
+            self.set(&change.key, change.value.as_deref().unwrap_or(""));
+        }
+    }
+}
+
+/// Changes that turn `before` into `after`.
+pub fn diff_tags(before: &AudioTags, after: &AudioTags) -> Vec<TagChange> {
+    let mut changes = Vec::new();
+    for field in TagField::ALL {
+        if before.get(field) != after.get(field) {
+            let value = after.get(field);
+            changes.push(TagChange {
+                key: TagKey::Field(field),
+                value: (!value.is_empty()).then(|| value.to_string()),
+            });
+        }
+    }
+    for (key, value) in &after.custom {
+        if before.value(&TagKey::Custom(key.clone())) != value {
+            changes.push(TagChange {
+                key: TagKey::Custom(key.clone()),
+                value: (!value.is_empty()).then(|| value.clone()),
+            });
+        }
+    }
+    for (key, _) in &before.custom {
+        if after.value(&TagKey::Custom(key.clone())).is_empty() {
+            changes.push(TagChange {
+                key: TagKey::Custom(key.clone()),
+                value: None,
+            });
+        }
+    }
+    changes
+}
+
+/// Why `key` cannot be used as a custom key in `format`, if anything.
+pub fn custom_key_error(format: TagFormat, key: &str) -> Option<String> {
+    if key.trim().is_empty() {
+        return Some("custom key is empty".to_string());
+    }
+    if key != key.trim() {
+        return Some(format!("custom key `{key}` has surrounding spaces"));
+    }
+    match format {
+        TagFormat::FlacVorbis | TagFormat::OggVorbis => {
+            // Vorbis comment field names: printable ASCII 0x20..=0x7D except '='.
+            if key
+                .bytes()
+                .any(|b| !(0x20..=0x7D).contains(&b) || b == b'=')
+            {
+                return Some(format!(
+                    "Vorbis comment key `{key}` must be ASCII without `=`"
+                ));
+            }
+            if VORBIS_HIDDEN_KEYS
+                .iter()
+                .any(|hidden| hidden.eq_ignore_ascii_case(key))
+            {
+                return Some(format!("`{key}` is an artwork comment, not text"));
+            }
+        }
+        TagFormat::Mp4 if MP4_HIDDEN_FREEFORM.contains(&key) => {
+            return Some(format!("`{key}` is written by the encoder"));
+        }
+        _ => {}
+    }
+    None
+}
+
+pub fn read_tags(path: &Path) -> Result<AudioTags> {
+    match tag_format(path) {
+        Some(TagFormat::Id3v2) => read_id3_tags(path),
+        Some(TagFormat::Mp4) => read_mp4_tags(path),
+        Some(TagFormat::FlacVorbis) => Ok(vorbis_to_tags(
+            &crate::flac_meta::read_flac_vorbis_comments(path)?,
+        )),
+        Some(TagFormat::OggVorbis) => Ok(vorbis_to_tags(
+            &crate::ogg_meta::read_ogg_vorbis_comments(path)?,
+        )),
+        None => anyhow::bail!(
+            "tag editing supports MP3, M4A, FLAC, Ogg and Opus: {}",
+            path.display()
+        ),
+    }
+}
+
+/// Apply `changes` in place, keeping every frame/atom/comment not named in
+/// them. An empty change list leaves the file untouched.
+pub fn write_tags(path: &Path, changes: &[TagChange]) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let format = tag_format(path).with_context(|| {
+        format!(
+            "tag editing supports MP3, M4A, FLAC, Ogg and Opus: {}",
+            path.display()
+        )
+    })?;
+    for change in changes {
+        if let TagKey::Custom(key) = &change.key {
+            if let Some(error) = custom_key_error(format, key) {
+                anyhow::bail!("{error}: {}", path.display());
+            }
+        }
+    }
+    crate::app::watch::note_self_write(path);
+    match format {
+        TagFormat::Id3v2 => write_id3_tags(path, changes),
+        TagFormat::Mp4 => write_mp4_tags(path, changes),
+        TagFormat::FlacVorbis => {
+            let changes = vorbis_changes(changes);
+            crate::flac_meta::update_flac_vorbis_comments(path, &borrow_changes(&changes))
+        }
+        TagFormat::OggVorbis => {
+            let changes = vorbis_changes(changes);
+            crate::ogg_meta::update_ogg_vorbis_comments(path, &borrow_changes(&changes))
+        }
+    }
+}
+
+/// Carry the standard fields and custom keys from `src` to `dst` when the
+/// two use different tag formats (e.g. an MP3 converted to M4A on save).
+/// Same-format pairs are copied verbatim by the container carry-over.
+pub fn copy_tags_across_formats(src: &Path, dst: &Path) -> Result<()> {
+    let (Some(src_format), Some(dst_format)) = (tag_format(src), tag_format(dst)) else {
+        return Ok(());
+    };
+    if src_format == dst_format {
+        return Ok(());
+    }
+    let Ok(tags) = read_tags(src) else {
+        return Ok(());
+    };
+    let mut changes = diff_tags(&AudioTags::default(), &tags);
+    changes.retain(|change| match &change.key {
+        TagKey::Custom(key) => custom_key_error(dst_format, key).is_none(),
+        TagKey::Field(_) => true,
+    });
+    write_tags(dst, &changes)
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.is_empty())
+}
+
+// ---- ID3v2 ----
+
+fn read_id3_tags(path: &Path) -> Result<AudioTags> {
+    let tag = match id3::Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(err) if matches!(err.kind, id3::ErrorKind::NoTag) => return Ok(AudioTags::default()),
+        Err(err) => return Err(err).with_context(|| format!("read id3: {}", path.display())),
+    };
+    let mut tags = AudioTags::default();
+    for field in TagField::ALL {
+        let value = match field {
+            TagField::Comment => tag
+                .comments()
+                .find(|c| c.description.is_empty())
+                .or_else(|| tag.comments().next())
+                .map(|c| c.text.clone()),
+            _ => tag
+                .get(field.id3_frame())
+                .and_then(|frame| frame.content().text())
+                .map(|text| text.to_string()),
+        };
+        *tags.get_mut(field) = value.unwrap_or_default();
+    }
+    tags.custom = tag
+        .extended_texts()
+        .map(|t| (t.description.clone(), t.value.clone()))
+        .collect();
+    Ok(tags)
+}
+
+fn write_id3_tags(path: &Path, changes: &[TagChange]) -> Result<()> {
+    let mut tag = match id3::Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(err) if matches!(err.kind, id3::ErrorKind::NoTag) => id3::Tag::new(),
+        Err(err) => return Err(err).with_context(|| format!("read id3: {}", path.display())),
+    };
+    for change in changes {
+        match &change.key {
+            TagKey::Field(TagField::Comment) => {
+                tag.remove_comment(Some(""), None);
+                if let Some(text) = non_empty(&change.value) {
+                    tag.add_frame(Frame::with_content(
+                        "COMM",
+                        Content::Comment(Comment {
+                            lang: "eng".to_string(),
+                            description: String::new(),
+                            text: text.to_string(),
+                        }),
+                    ));
+                }
+            }
+            TagKey::Field(field) => {
+                tag.remove(field.id3_frame());
+                if let Some(text) = non_empty(&change.value) {
+                    tag.set_text(field.id3_frame(), text);
+                }
+            }
+            TagKey::Custom(key) => {
+                tag.remove_extended_text(Some(key), None);
+                if let Some(value) = non_empty(&change.value) {
+                    tag.add_frame(Frame::with_content(
+                        "TXXX",
+                        Content::ExtendedText(ExtendedText {
+                            description: key.clone(),
+                            value: value.to_string(),
+                        }),
+                    ));
+                }
+            }
+        }
+    }
+    tag.write_to_path(path, Version::Id3v24)
+        .with_context(|| format!("write mp3 tags: {}", path.display()))?;
+    Ok(())
+}
+
+// ---- MP4 ----
+
+fn mp4_ident(field: TagField) -> DataIdent {
+    match field {
+        TagField::Title => DataIdent::from(Fourcc(*b"\xa9nam")),
+        TagField::Artist => DataIdent::from(Fourcc(*b"\xa9ART")),
+        TagField::Album => DataIdent::from(Fourcc(*b"\xa9alb")),
+        TagField::Genre => DataIdent::from(Fourcc(*b"\xa9gen")),
+        TagField::Comment => DataIdent::from(Fourcc(*b"\xa9cmt")),
+        // No native atom; iTunes-style freeform like most taggers.
+        TagField::Isrc => DataIdent::freeform(ITUNES_MEAN, "ISRC"),
+    }
+}
+
+fn read_mp4_tags(path: &Path) -> Result<AudioTags> {
+    let tag = Mp4Tag::read_from_path(path)
+        .with_context(|| format!("read m4a tags: {}", path.display()))?;
+    let mut tags = AudioTags::default();
+    for field in TagField::ALL {
+        *tags.get_mut(field) = tag
+            .strings_of(&mp4_ident(field))
+            .next()
+            .unwrap_or_default()
+            .to_string();
+    }
+    for (ident, data) in tag.data() {
+        let DataIdent::Freeform { mean, name } = ident else {
+            continue;
+        };
+        if mean != ITUNES_MEAN
+            || name.eq_ignore_ascii_case("ISRC")
+            || MP4_HIDDEN_FREEFORM.contains(&name.as_str())
+        {
+            continue;
+        }
+        if let Some(value) = data.string() {
+            if !tags.custom.iter().any(|(k, _)| k == name) {
+                tags.custom.push((name.clone(), value.to_string()));
+            }
+        }
+    }
+    Ok(tags)
+}
+
+fn write_mp4_tags(path: &Path, changes: &[TagChange]) -> Result<()> {
+    let mut tag = Mp4Tag::read_from_path(path)
+        .with_context(|| format!("read m4a tags: {}", path.display()))?;
+    for change in changes {
+        let ident = match &change.key {
+            TagKey::Field(field) => mp4_ident(*field),
+            TagKey::Custom(key) => DataIdent::freeform(ITUNES_MEAN, key.as_str()),
+        };
+        tag.remove_data_of(&ident);
+        if let Some(value) = non_empty(&change.value) {
+            tag.set_data(ident, Data::Utf8(value.to_string()));
+        }
+    }
+    tag.write_to_path(path)
+        .with_context(|| format!("write m4a tags: {}", path.display()))?;
+    Ok(())
+}
+
+// ---- Vorbis comments ----
+
+fn vorbis_to_tags(comments: &[(String, String)]) -> AudioTags {
+    let mut tags = AudioTags::default();
+    for (key, value) in comments {
+        let standard = TagField::ALL
+            .into_iter()
+            .find(|field| field.vorbis_key().eq_ignore_ascii_case(key));
+        match standard {
+            Some(field) => {
+                let slot = tags.get_mut(field);
+                if slot.is_empty() {
+                    *slot = value.clone();
+                }
+            }
+            None if VORBIS_HIDDEN_KEYS
+                .iter()
+                .any(|hidden| hidden.eq_ignore_ascii_case(key)) => {}
+            None => {
+                if !tags.custom.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                    tags.custom.push((key.clone(), value.clone()));
+                }
+            }
+        }
+    }
+    tags
+}
+
+fn vorbis_changes(changes: &[TagChange]) -> Vec<(String, Option<String>)> {
+    changes
+        .iter()
+        .map(|change| {
+            let key = match &change.key {
+                TagKey::Field(field) => field.vorbis_key().to_string(),
+                TagKey::Custom(key) => key.to_ascii_uppercase(),
+            };
+            (key, non_empty(&change.value).map(str::to_string))
+        })
+        .collect()
+}
+
+fn borrow_changes(changes: &[(String, Option<String>)]) -> Vec<(&str, Option<String>)> {
+    changes
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_tags_{tag}_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn tone() -> Vec<Vec<f32>> {
+        vec![(0..22_050)
+            .map(|i| (i as f32 / 44_100.0 * 440.0 * std::f32::consts::TAU).sin() * 0.2)
+            .collect()]
+    }
+
+    fn set(key: &str, value: &str) -> TagChange {
+        TagChange {
+            key: TagKey::parse(key),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn tag_key_parse_maps_standard_names_and_custom_prefix() {
+        assert_eq!(TagKey::parse("Title"), TagKey::Field(TagField::Title));
+        assert_eq!(TagKey::parse("isrc"), TagKey::Field(TagField::Isrc));
+        assert_eq!(
+            TagKey::parse("custom:title"),
+            TagKey::Custom("title".to_string())
+        );
+        assert_eq!(TagKey::parse("MOOD"), TagKey::Custom("MOOD".to_string()));
+    }
+
+    #[test]
+    fn diff_round_trips_field_and_custom_edits() {
+        let before = AudioTags {
+            title: "a".into(),
+            custom: vec![("OLD".into(), "1".into())],
+            ..Default::default()
+        };
+        let mut after = before.clone();
+        after.set(&TagKey::Field(TagField::Artist), "b");
+        after.set(&TagKey::Custom("OLD".into()), "");
+        after.set(&TagKey::Custom("NEW".into()), "2");
+        let changes = diff_tags(&before, &after);
+        let mut applied = before.clone();
+        applied.apply(&changes);
+        assert_eq!(applied, after);
+        assert!(diff_tags(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn edits_round_trip_and_preserve_unknown_keys_per_format() {
+        let dir = temp_dir("roundtrip");
+        for ext in ["mp3", "m4a", "flac", "ogg", "opus"] {
+            let path = dir.join(format!("tone.{ext}"));
+            crate::wave::export_channels_audio(&tone(), 44_100, &path).unwrap();
+            crate::loop_markers::write_loop_markers(&path, Some((100, 2_000))).unwrap();
+            write_tags(
+                &path,
+                &[
+                    set("title", "Door Slam"),
+                    set("artist", "Foley Team"),
+                    set("comment", "take 3"),
+                    set("isrc", "JPX012600001"),
+                    set("MOOD", "tense"),
+                ],
+            )
+            .unwrap();
+            let tags = read_tags(&path).unwrap();
+            assert_eq!(tags.title, "Door Slam", "{ext}");
+            assert_eq!(tags.artist, "Foley Team", "{ext}");
+            assert_eq!(tags.comment, "take 3", "{ext}");
+            assert_eq!(tags.isrc, "JPX012600001", "{ext}");
+            assert_eq!(tags.value(&TagKey::Custom("MOOD".into())), "tense", "{ext}");
+            write_tags(
+                &path,
+                &[TagChange {
+                    key: TagKey::Field(TagField::Title),
+                    value: None,
+                }],
+            )
+            .unwrap();
+            let tags = read_tags(&path).unwrap();
+            assert!(tags.title.is_empty(), "{ext}");
+            assert_eq!(tags.artist, "Foley Team", "{ext}");
+            assert_eq!(
+                crate::loop_markers::read_loop_markers(&path),
+                Some((100, 2_000)),
+                "{ext}: loop tags lost"
+            );
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,481 @@
+//! Sony Wave64 (`.w64`) container support.
+//!
+//! W64 is RIFF/WAVE with 16-byte GUID chunk ids and 64-bit chunk sizes that
+//! include the 24-byte chunk header; chunks are 8-byte aligned. The `fmt `
+//! payload is an ordinary WAVEFORMATEX(TENSIBLE), so decoding hands symphonia
+//! a synthesized RIFF/WAVE view of the file ([`W64WaveSource`]) and export
+//! rewraps a finished WAVE file ([`write_w64_from_wave`]).
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// `riff` chunk GUID (66666972-912E-11CF-A5D6-28DB04C10000) as stored.
+pub const W64_RIFF_GUID: [u8; 16] = [
+    b'r', b'i', b'f', b'f', 0x2E, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+/// `list` chunk GUID; shares the `riff` tail.
+pub const W64_LIST_GUID: [u8; 16] = [
+    b'l', b'i', b's', b't', 0x2F, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+/// Form type GUID (65766177-ACF3-11D3-8CD1-00C04F8EDB8A).
+pub const W64_WAVE_GUID: [u8; 16] = [
+    b'w', b'a', b'v', b'e', 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+/// Tail shared by the GUIDs of chunks named after their RIFF fourcc
+/// (`fmt `, `data`, `fact`, `bext`, `junk`, ...).
+const FOURCC_GUID_TAIL: [u8; 12] = [
+    0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+/// Sony Sound Forge `summarylist` and `marker` chunk GUIDs.
+const SUMMARYLIST_GUID: [u8; 16] = [
+    0xBC, 0x94, 0x5F, 0x92, 0x5A, 0x52, 0xD2, 0x11, 0x86, 0xDC, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+const MARKER_GUID: [u8; 16] = [
+    0x56, 0x62, 0xF7, 0xAB, 0x2D, 0x39, 0xD2, 0x11, 0x86, 0xC7, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+
+/// Size of the `riff` header: GUID, 64-bit size and the `wave` GUID.
+pub const W64_HEADER_LEN: u64 = 40;
+/// Size of a chunk header: GUID plus 64-bit size.
+pub const W64_CHUNK_HEADER_LEN: u64 = 24;
+
+/// GUID of the W64 chunk that stands in for RIFF chunk `fourcc`.
+pub fn chunk_guid(fourcc: &[u8; 4]) -> [u8; 16] {
+    let mut guid = [0u8; 16];
+    guid[..4].copy_from_slice(fourcc);
+    guid[4..].copy_from_slice(&FOURCC_GUID_TAIL);
+    guid
+}
+
+/// Display name for a chunk GUID: the RIFF fourcc for the fourcc-style
+/// GUIDs, a known Sony name, or the GUID in registry form.
+pub fn guid_name(guid: &[u8; 16]) -> String {
+    if guid[4..] == FOURCC_GUID_TAIL || *guid == W64_RIFF_GUID || *guid == W64_LIST_GUID {
+        return String::from_utf8_lossy(&guid[..4]).into_owned();
+    }
+    if *guid == SUMMARYLIST_GUID {
+        return "summarylist".to_string();
+    }
+    if *guid == MARKER_GUID {
+        return "marker".to_string();
+    }
+    format_guid(guid)
+}
+
+/// `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}` with the first three fields read
+/// little-endian, as Windows prints GUIDs.
+pub fn format_guid(guid: &[u8; 16]) -> String {
+    let d1 = u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]);
+    let d2 = u16::from_le_bytes([guid[4], guid[5]]);
+    let d3 = u16::from_le_bytes([guid[6], guid[7]]);
+    let tail: String = guid[10..].iter().map(|b| format!("{b:02X}")).collect();
+    format!(
+        "{{{d1:08X}-{d2:04X}-{d3:04X}-{:02X}{:02X}-{tail}}}",
+        guid[8], guid[9]
+    )
+}
+
+pub fn is_w64_header(bytes: &[u8]) -> bool {
+    bytes.len() >= 40 && bytes[..16] == W64_RIFF_GUID && bytes[24..40] == W64_WAVE_GUID
+}
+
+fn is_w64_file(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut head = [0u8; 40];
+    file.read_exact(&mut head).is_ok() && is_w64_header(&head)
+}
+
+/// One chunk of a W64 file; offsets are absolute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct W64Chunk {
+    pub guid: [u8; 16],
+    pub header_offset: u64,
+    /// Size field as written (including the 24-byte header).
+    pub declared_size: u64,
+    pub payload_offset: u64,
+    /// Payload bytes actually present in the file.
+    pub payload_len: u64,
+}
+
+impl W64Chunk {
+    pub fn is(&self, fourcc: &[u8; 4]) -> bool {
+        self.guid == chunk_guid(fourcc)
+    }
+}
+
+/// Walk the top-level chunks. Stops at the first truncated or malformed
+/// header; the chunk it belongs to is reported with the bytes available.
+pub fn read_w64_chunks(path: &Path) -> Result<Vec<W64Chunk>> {
+    let mut file = File::open(path).with_context(|| format!("open w64: {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    let mut head = [0u8; 40];
+    file.read_exact(&mut head)
+        .with_context(|| format!("read w64 header: {}", path.display()))?;
+    if !is_w64_header(&head) {
+        anyhow::bail!("not a Wave64 file: {}", path.display());
+    }
+    let riff_size = u64::from_le_bytes(head[16..24].try_into().unwrap());
+    let end = riff_size.clamp(W64_HEADER_LEN, file_len.max(W64_HEADER_LEN));
+    let mut chunks = Vec::new();
+    let mut pos = W64_HEADER_LEN;
+    while pos + W64_CHUNK_HEADER_LEN <= end {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 24];
+        file.read_exact(&mut header)?;
+        let mut guid = [0u8; 16];
+        guid.copy_from_slice(&header[..16]);
+        let declared_size = u64::from_le_bytes(header[16..24].try_into().unwrap());
+        let payload_offset = pos + W64_CHUNK_HEADER_LEN;
+        let declared_payload = declared_size.saturating_sub(W64_CHUNK_HEADER_LEN);
+        chunks.push(W64Chunk {
+            guid,
+            header_offset: pos,
+            declared_size,
+            payload_offset,
+            payload_len: declared_payload.min(file_len.saturating_sub(payload_offset)),
+        });
+        if declared_size < W64_CHUNK_HEADER_LEN {
+            break;
+        }
+        let Some(next) = pos
+            .checked_add(declared_size)
+            .and_then(|next| next.checked_add((8 - declared_size % 8) % 8))
+        else {
+            break;
+        };
+        pos = next;
+    }
+    Ok(chunks)
+}
+
+/// The parts of a W64 file decoding needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct W64Info {
+    /// Raw `fmt ` payload (WAVEFORMATEX or WAVEFORMATEXTENSIBLE).
+    pub fmt: Vec<u8>,
+    pub data_offset: u64,
+    pub data_len: u64,
+}
+
+impl W64Info {
+    pub fn channels(&self) -> u16 {
+        self.fmt
+            .get(2..4)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .unwrap_or(0)
+    }
+
+    /// `dwChannelMask` when the format is WAVE_FORMAT_EXTENSIBLE.
+    pub fn channel_mask(&self) -> Option<u32> {
+        let tag = u16::from_le_bytes(self.fmt.get(0..2)?.try_into().ok()?);
+        if tag != 0xFFFE {
+            return None;
+        }
+        Some(u32::from_le_bytes(self.fmt.get(20..24)?.try_into().ok()?))
+    }
+}
+
+pub fn read_w64_info(path: &Path) -> Result<Option<W64Info>> {
+    if !is_w64_file(path) {
+        return Ok(None);
+    }
+    let chunks = read_w64_chunks(path)?;
+    let Some(fmt_chunk) = chunks.iter().find(|c| c.is(b"fmt ")) else {
+        return Ok(None);
+    };
+    let Some(data_chunk) = chunks.iter().find(|c| c.is(b"data")) else {
+        return Ok(None);
+    };
+    if fmt_chunk.payload_len < 16 {
+        return Ok(None);
+    }
+    let mut file = File::open(path).with_context(|| format!("open w64: {}", path.display()))?;
+    file.seek(SeekFrom::Start(fmt_chunk.payload_offset))?;
+    let mut fmt = vec![0u8; fmt_chunk.payload_len.min(256) as usize];
+    file.read_exact(&mut fmt)
+        .with_context(|| format!("read w64 fmt: {}", path.display()))?;
+    Ok(Some(W64Info {
+        fmt,
+        data_offset: data_chunk.payload_offset,
+        data_len: data_chunk.payload_len,
+    }))
+}
+
+/// A W64 file read as RIFF/WAVE: a synthesized `RIFF`/`fmt `/`data` header
+/// followed by the W64 `data` payload. RIFF sizes are 32-bit, so audio past
+/// the first 4 GiB of a larger file is not reachable through this view.
+pub struct W64WaveSource {
+    file: File,
+    header: Vec<u8>,
+    data_offset: u64,
+    data_len: u64,
+    pos: u64,
+}
+
+impl W64WaveSource {
+    /// `None` when `path` is not a W64 file with `fmt ` and `data` chunks.
+    pub fn open(path: &Path) -> Result<Option<Self>> {
+        let Some(info) = read_w64_info(path)? else {
+            return Ok(None);
+        };
+        let fmt_padded = info.fmt.len() + (info.fmt.len() & 1);
+        let header_len = 12 + 8 + fmt_padded + 8;
+        let data_len = info.data_len.min(u32::MAX as u64 - header_len as u64 + 8);
+        let mut header = Vec::with_capacity(header_len);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&((header_len as u64 - 8 + data_len) as u32).to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&(info.fmt.len() as u32).to_le_bytes());
+        header.extend_from_slice(&info.fmt);
+        if info.fmt.len() & 1 == 1 {
+            header.push(0);
+        }
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&(data_len as u32).to_le_bytes());
+        let file = File::open(path).with_context(|| format!("open w64: {}", path.display()))?;
+        Ok(Some(Self {
+            file,
+            header,
+            data_offset: info.data_offset,
+            data_len,
+            pos: 0,
+        }))
+    }
+
+    fn total_len(&self) -> u64 {
+        self.header.len() as u64 + self.data_len
+    }
+}
+
+impl Read for W64WaveSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let header_len = self.header.len() as u64;
+        if self.pos < header_len {
+            let start = self.pos as usize;
+            let n = buf.len().min(self.header.len() - start);
+            buf[..n].copy_from_slice(&self.header[start..start + n]);
+            self.pos += n as u64;
+            return Ok(n);
+        }
+        let remaining = self.total_len().saturating_sub(self.pos);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let want = (buf.len() as u64).min(remaining) as usize;
+        self.file
+            .seek(SeekFrom::Start(self.data_offset + (self.pos - header_len)))?;
+        let n = self.file.read(&mut buf[..want])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for W64WaveSource {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.total_len().checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        let Some(target) = target else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek before start of w64 stream",
+            ));
+        };
+        self.pos = target;
+        Ok(target)
+    }
+}
+
+impl symphonia::core::io::MediaSource for W64WaveSource {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.total_len())
+    }
+}
+
+fn write_chunk_header(out: &mut impl Write, guid: &[u8; 16], payload_len: u64) -> Result<()> {
+    out.write_all(guid)?;
+    out.write_all(&(payload_len + W64_CHUNK_HEADER_LEN).to_le_bytes())?;
+    Ok(())
+}
+
+fn write_chunk_pad(out: &mut impl Write, payload_len: u64) -> Result<()> {
+    let pad = ((8 - (payload_len + W64_CHUNK_HEADER_LEN) % 8) % 8) as usize;
+    out.write_all(&[0u8; 8][..pad])?;
+    Ok(())
+}
+
+/// W64 GUID for a RIFF chunk id. `LIST` and `JUNK` have lowercase W64
+/// names; other ids keep their case.
+fn riff_chunk_guid(id: &[u8; 4]) -> [u8; 16] {
+    match id {
+        b"LIST" => W64_LIST_GUID,
+        b"JUNK" => chunk_guid(b"junk"),
+        _ => chunk_guid(id),
+    }
+}
+
+/// Rewrap the RIFF/WAVE file `wave` as W64 at `dst`: every chunk is carried
+/// over in order under its fourcc GUID (`LIST` becomes the W64 `list` GUID),
+/// with the audio stream-copied.
+pub fn write_w64_from_wave(wave: &Path, dst: &Path) -> Result<()> {
+    let mut src =
+        BufReader::new(File::open(wave).with_context(|| format!("open wav: {}", wave.display()))?);
+    let file_len = src.get_ref().metadata()?.len();
+    let mut root = [0u8; 12];
+    src.read_exact(&mut root)
+        .with_context(|| format!("read wav header: {}", wave.display()))?;
+    if &root[0..4] != b"RIFF" || &root[8..12] != b"WAVE" {
+        anyhow::bail!("not a RIFF/WAVE file: {}", wave.display());
+    }
+    let mut chunks: Vec<([u8; 4], u64, u64)> = Vec::new();
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        src.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        src.read_exact(&mut header)?;
+        let id: [u8; 4] = header[0..4].try_into().unwrap();
+        let declared = u32::from_le_bytes(header[4..8].try_into().unwrap()) as u64;
+        let len = declared.min(file_len - pos - 8);
+        chunks.push((id, pos + 8, len));
+        pos += 8 + declared + (declared & 1);
+    }
+    if !chunks.iter().any(|(id, _, _)| id == b"data") {
+        anyhow::bail!("wav has no data chunk: {}", wave.display());
+    }
+    let out_file = File::create(dst).with_context(|| format!("create w64: {}", dst.display()))?;
+    let mut out = BufWriter::new(out_file);
+    let mut total = W64_HEADER_LEN;
+    for (_, _, len) in &chunks {
+        let chunk = W64_CHUNK_HEADER_LEN + len;
+        total += chunk + (8 - chunk % 8) % 8;
+    }
+    out.write_all(&W64_RIFF_GUID)?;
+    out.write_all(&total.to_le_bytes())?;
+    out.write_all(&W64_WAVE_GUID)?;
+    for (id, offset, len) in &chunks {
+        write_chunk_header(&mut out, &riff_chunk_guid(id), *len)?;
+        src.seek(SeekFrom::Start(*offset))?;
+        let copied = std::io::copy(&mut (&mut src).take(*len), &mut out)
+            .with_context(|| format!("copy wav chunk into w64: {}", dst.display()))?;
+        if copied != *len {
+            anyhow::bail!("wav chunk truncated: {}", wave.display());
+        }
+        write_chunk_pad(&mut out, *len)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn is_audio_core_chunk(chunk: &W64Chunk) -> bool {
+    chunk.is(b"fmt ") || chunk.is(b"data") || chunk.is(b"fact")
+}
+
+/// Carry `src`'s metadata chunks (`bext`, `list`, markers, ...) into the
+/// freshly written `dst`, keeping `dst`'s `fmt ` / `fact` / `data`.
+pub fn copy_w64_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
+    let carried: Vec<W64Chunk> = read_w64_chunks(src)?
+        .into_iter()
+        .filter(|c| !is_audio_core_chunk(c))
+        .collect();
+    if carried.is_empty() {
+        return Ok(());
+    }
+    let fresh: Vec<W64Chunk> = read_w64_chunks(dst)?
+        .into_iter()
+        .filter(is_audio_core_chunk)
+        .collect();
+    // Metadata goes ahead of the audio, in the source's order.
+    let order: Vec<(&Path, &W64Chunk)> = carried
+        .iter()
+        .map(|c| (src, c))
+        .chain(fresh.iter().map(|c| (dst, c)))
+        .collect();
+    let parent = dst.parent().unwrap_or_else(|| Path::new("."));
+    let tmp = parent.join(format!(
+        ".wvp_tmp_w64meta_{}_{}.w64",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    let written = (|| -> Result<()> {
+        let mut src_file = BufReader::new(File::open(src)?);
+        let mut dst_file = BufReader::new(File::open(dst)?);
+        let mut out = BufWriter::new(
+            File::create(&tmp).with_context(|| format!("create w64 tmp: {}", tmp.display()))?,
+        );
+        let mut total = W64_HEADER_LEN;
+        for (_, chunk) in &order {
+            let size = W64_CHUNK_HEADER_LEN + chunk.payload_len;
+            total += size + (8 - size % 8) % 8;
+        }
+        out.write_all(&W64_RIFF_GUID)?;
+        out.write_all(&total.to_le_bytes())?;
+        out.write_all(&W64_WAVE_GUID)?;
+        for (from, chunk) in &order {
+            let input = if *from == src {
+                &mut src_file
+            } else {
+                &mut dst_file
+            };
+            write_chunk_header(&mut out, &chunk.guid, chunk.payload_len)?;
+            input.seek(SeekFrom::Start(chunk.payload_offset))?;
+            std::io::copy(&mut input.take(chunk.payload_len), &mut out)?;
+            write_chunk_pad(&mut out, chunk.payload_len)?;
+        }
+        out.flush()?;
+        Ok(())
+    })();
+    if let Err(err) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err.context(format!(
+            "copy w64 metadata {} -> {}",
+            src.display(),
+            dst.display()
+        )));
+    }
+    match std::fs::rename(&tmp, dst) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            // Windows: rename fails while the target exists; replace via copy.
+            let res = std::fs::copy(&tmp, dst)
+                .map(|_| ())
+                .with_context(|| format!("replace w64: {}", dst.display()));
+            let _ = std::fs::remove_file(&tmp);
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guid_names_cover_fourcc_and_sony_chunks() {
+        assert_eq!(guid_name(&chunk_guid(b"fmt ")), "fmt ");
+        assert_eq!(guid_name(&W64_LIST_GUID), "list");
+        assert_eq!(guid_name(&MARKER_GUID), "marker");
+        assert_eq!(
+            format_guid(&W64_RIFF_GUID),
+            "{66666972-912E-11CF-A5D6-28DB04C10000}"
+        );
+        assert_eq!(
+            format_guid(&W64_WAVE_GUID),
+            "{65766177-ACF3-11D3-8CD1-00C04F8EDB8A}"
+        );
+    }
+}
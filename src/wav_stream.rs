@@ -34,6 +34,8 @@ pub struct WavePcmInfo {
     pub sample_rate: u32,
     pub bits_per_sample: u16,
     pub block_align: u16,
+    /// `dwChannelMask` of a WAVE_FORMAT_EXTENSIBLE header.
+    pub channel_mask: Option<u32>,
     pub data_offset: u64,
     pub data_len: u64,
     pub frame_count: u64,
@@ -79,6 +81,7 @@ pub fn read_wave_pcm_info(path: &Path) -> Result<Option<WavePcmInfo>> {
     let mut sample_rate = 0u32;
     let mut bits_per_sample = 0u16;
     let mut block_align = 0u16;
+    let mut channel_mask = None;
     let mut data = None;
     loop {
         let mut header = [0u8; 8];
@@ -109,6 +112,7 @@ pub fn read_wave_pcm_info(path: &Path) -> Result<Option<WavePcmInfo>> {
                     block_align = u16::from_le_bytes(bytes[12..14].try_into().unwrap());
                     bits_per_sample = u16::from_le_bytes(bytes[14..16].try_into().unwrap());
                     if audio_format == 0xFFFE && bytes.len() >= 26 {
+                        channel_mask = Some(u32::from_le_bytes(bytes[20..24].try_into().unwrap()));
                         audio_format = u16::from_le_bytes(bytes[24..26].try_into().unwrap());
                     }
                 }
@@ -151,12 +155,72 @@ pub fn read_wave_pcm_info(path: &Path) -> Result<Option<WavePcmInfo>> {
         sample_rate,
         bits_per_sample,
         block_align,
+        channel_mask,
         data_offset,
         data_len,
         frame_count: data_len / block_align as u64,
     }))
 }
 
+/// Top-level chunks of a RIFF or RF64/BW64 file in file order, without the
+/// audio: `data` is returned with an empty payload so its position is kept,
+/// and `ds64` is dropped since its sizes only describe the source file.
+/// Chunks longer than `max_chunk_len` are skipped. `None` when `path` is not
+/// a WAVE file.
+pub fn read_wave_metadata_chunks(
+    path: &Path,
+    max_chunk_len: u64,
+) -> Result<Option<Vec<([u8; 4], Vec<u8>)>>> {
+    let mut file =
+        File::open(path).with_context(|| format!("open WAVE header: {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    let mut root = [0u8; 12];
+    if file.read_exact(&mut root).is_err()
+        || !matches!(&root[0..4], b"RIFF" | b"RF64" | b"BW64")
+        || &root[8..12] != b"WAVE"
+    {
+        return Ok(None);
+    }
+    let mut ds64_data_size = None;
+    let mut chunks = Vec::new();
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let id: [u8; 4] = header[0..4].try_into().unwrap();
+        let declared = u32::from_le_bytes(header[4..8].try_into().unwrap()) as u64;
+        let payload = pos + 8;
+        let available = declared.min(file_len - payload);
+        match &id {
+            b"ds64" => {
+                let mut bytes = vec![0u8; available.min(28) as usize];
+                file.read_exact(&mut bytes)?;
+                if bytes.len() >= 16 {
+                    ds64_data_size = Some(u64::from_le_bytes(bytes[8..16].try_into().unwrap()));
+                }
+            }
+            b"data" => {
+                chunks.push((id, Vec::new()));
+                if declared == u32::MAX as u64 {
+                    let logical = ds64_data_size.unwrap_or(declared);
+                    pos = payload.saturating_add(logical).saturating_add(logical & 1);
+                    continue;
+                }
+            }
+            _ if available <= max_chunk_len => {
+                let mut bytes = vec![0u8; available as usize];
+                file.read_exact(&mut bytes)
+                    .with_context(|| format!("read WAVE chunk: {}", path.display()))?;
+                chunks.push((id, bytes));
+            }
+            _ => {}
+        }
+        pos = payload + declared + (declared & 1);
+    }
+    Ok(Some(chunks))
+}
+
 pub struct StreamingWaveWriter {
     path: PathBuf,
     writer: BufWriter<File>,
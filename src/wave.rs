@@ -18,6 +18,7 @@ use vorbis_rs::VorbisEncoderBuilder;
 
 use crate::audio::AudioEngine;
 use crate::audio_io;
+use crate::channel_layout::ChannelLayout;
 use rubato::{
     Async, Fft, FixedAsync, FixedSync, Resampler, SincInterpolationParameters,
     SincInterpolationType, WindowFunction as RubatoWindowFunction,
@@ -137,14 +138,32 @@ pub enum DitherMode {
     /// (NTF = (1 - z^-1)^2): pushes quantization noise out of the low band
     /// where hearing is most sensitive, at the cost of more HF noise.
     TpdfNoiseShaped,
+    /// TPDF dither plus 1st-order highpass shaping (NTF = 1 - z^-1): a
+    /// gentle tilt with only +6 dB of noise at Nyquist.
+    TpdfHighpass,
+    /// TPDF dither plus a psychoacoustic (F-weighted) curve: noise is pulled
+    /// down around 2-5 kHz, where hearing is most sensitive, and parked
+    /// above ~15 kHz. Tuned for 44.1 and 48 kHz; other rates fall back to
+    /// the 2nd-order highpass.
+    TpdfShaped,
 }
 
 impl DitherMode {
+    pub const ALL: [DitherMode; 5] = [
+        DitherMode::Off,
+        DitherMode::Tpdf,
+        DitherMode::TpdfHighpass,
+        DitherMode::TpdfNoiseShaped,
+        DitherMode::TpdfShaped,
+    ];
+
     pub fn prefs_name(self) -> &'static str {
         match self {
             DitherMode::Off => "off",
             DitherMode::Tpdf => "tpdf",
             DitherMode::TpdfNoiseShaped => "tpdf_ns",
+            DitherMode::TpdfHighpass => "tpdf_hp",
+            DitherMode::TpdfShaped => "tpdf_shaped",
         }
     }
 
@@ -153,15 +172,62 @@ impl DitherMode {
             "off" => Some(DitherMode::Off),
             "tpdf" => Some(DitherMode::Tpdf),
             "tpdf_ns" => Some(DitherMode::TpdfNoiseShaped),
+            "tpdf_hp" => Some(DitherMode::TpdfHighpass),
+            "tpdf_shaped" => Some(DitherMode::TpdfShaped),
             _ => None,
         }
     }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DitherMode::Off => "Off",
+            DitherMode::Tpdf => "TPDF",
+            DitherMode::TpdfNoiseShaped => "TPDF + 2nd-order highpass",
+            DitherMode::TpdfHighpass => "TPDF + 1st-order highpass",
+            DitherMode::TpdfShaped => "TPDF + psychoacoustic curve",
+        }
+    }
+
+    /// Error-feedback coefficients `c` for `u = x - sum(c[k] * e[n-1-k])`,
+    /// i.e. NTF = 1 - sum(c[k] z^-(k+1)). Empty for unshaped modes.
+    fn noise_shaping_coefficients(self, sample_rate: u32) -> &'static [f64] {
+        match self {
+            DitherMode::Off | DitherMode::Tpdf => &[],
+            DitherMode::TpdfHighpass => &[1.0],
+            DitherMode::TpdfNoiseShaped => &[2.0, -1.0],
+            DitherMode::TpdfShaped => match sample_rate {
+                44_000..=44_200 => &SHAPED_NTF_44K,
+                47_900..=48_100 => &SHAPED_NTF_48K,
+                _ => &[2.0, -1.0],
+            },
+        }
+    }
 }
 
+/// Wannamaker's 9-tap F-weighted noise-shaping filter for 44.1 kHz
+/// (about -12 dB at DC, deepest around 3-4 kHz, +27 dB at Nyquist).
+const SHAPED_NTF_44K: [f64; 9] = [
+    2.412, -3.370, 3.937, -4.174, 3.353, -2.205, 1.281, -0.569, 0.0847,
+];
+
+/// The same F-weighted curve re-fitted at 48 kHz: the 44.1 kHz response
+/// mapped onto the 48 kHz frequency axis (held flat above 22.05 kHz) and
+/// turned into a monic minimum-phase 12-tap filter via the cepstrum.
+const SHAPED_NTF_48K: [f64; 12] = [
+    2.6254, -3.8247, 4.289, -3.8932, 2.3631, -0.8251, -0.1095, 0.5148, -0.4934, 0.2216, -0.094,
+    0.0468,
+];
+
+const MAX_NTF_TAPS: usize = 12;
+
+/// Consecutive digital-silence samples (per channel) after which auto-blank
+/// stops dithering and resets the shaping filter.
+pub const DITHER_AUTO_BLANK_HOLD: u32 = 64;
+
 /// Integer quantizer shared by every PCM export path (WAV/AIFF/FLAC/format
 /// converter). Callers keep their historical scale/clamp conventions by
 /// passing them in; the quantizer adds dither and (optionally) per-channel
-/// 2nd-order error-feedback noise shaping on top. Deterministic for a given
+/// error-feedback noise shaping on top. Deterministic for a given
 /// construction, so FLAC's MD5 and encode passes can replay the sequence.
 pub struct Quantizer {
     scale: f64,
@@ -169,24 +235,63 @@ pub struct Quantizer {
     max: f64,
     mode: DitherMode,
     rng: TpdfDither,
-    /// Per-channel (e[n-1], e[n-2]) error history for noise shaping. The
-    /// interleaved writers index this by channel so each channel's error
-    /// filter sees its own past, not its neighbor's.
-    err: Vec<(f64, f64)>,
+    ntf: &'static [f64],
+    /// Per-channel error history (e[n-1], e[n-2], ...) for noise shaping.
+    /// The interleaved writers index this by channel so each channel's
+    /// error filter sees its own past, not its neighbor's.
+    err: Vec<[f64; MAX_NTF_TAPS]>,
+    auto_blank: bool,
+    /// Per-channel run length of exact-zero input samples.
+    silent_run: Vec<u32>,
 }
 
 impl Quantizer {
     pub fn new(scale: f64, min: f64, max: f64, channels: usize, mode: DitherMode) -> Self {
+        let channels = channels.max(1);
         Self {
             scale,
             min,
             max,
             mode,
             rng: TpdfDither::new(TpdfDither::DEFAULT_SEED),
-            err: vec![(0.0, 0.0); channels.max(1)],
+            ntf: mode.noise_shaping_coefficients(0),
+            err: vec![[0.0; MAX_NTF_TAPS]; channels],
+            auto_blank: false,
+            silent_run: vec![0; channels],
         }
     }
 
+    /// Select the shaping curve for `sample_rate` (only `TpdfShaped`
+    /// depends on it).
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.ntf = self.mode.noise_shaping_coefficients(sample_rate);
+        self
+    }
+
+    /// Auto-blank: after [`DITHER_AUTO_BLANK_HOLD`] samples of exact digital
+    /// silence a channel is written as true zeros (no dither noise) until
+    /// signal returns.
+    pub fn with_auto_blank(mut self, auto_blank: bool) -> Self {
+        self.auto_blank = auto_blank;
+        self
+    }
+
+    /// Quantizer for an integer export at `bits`, configured from the
+    /// current codec export options (mode, auto-blank) and the stream's
+    /// sample rate.
+    pub fn for_export(
+        scale: f64,
+        min: f64,
+        max: f64,
+        channels: usize,
+        bits: u32,
+        sample_rate: u32,
+    ) -> Self {
+        Self::new(scale, min, max, channels, Self::export_mode_for_bits(bits))
+            .with_sample_rate(sample_rate)
+            .with_auto_blank(codec_export_options().dither_auto_blank)
+    }
+
     /// Effective dither mode for an integer export at `bits`, from the
     /// current codec export options (16-bit uses `dither_mode`; 24-bit only
     /// dithers when `dither_24bit` is also set; anything wider is Off).
@@ -206,25 +311,46 @@ impl Quantizer {
     #[inline]
     pub fn quantize(&mut self, ch: usize, v: f32) -> i32 {
         let x = f64::from(v) * self.scale;
-        match self.mode {
-            DitherMode::Off => x.round().clamp(self.min, self.max) as i32,
-            DitherMode::Tpdf => (x + f64::from(self.rng.next()))
-                .round()
-                .clamp(self.min, self.max) as i32,
-            DitherMode::TpdfNoiseShaped => {
-                let slot = ch.min(self.err.len() - 1);
-                let (e1, e2) = self.err[slot];
-                // Error feedback for NTF (1 - z^-1)^2: with q = u + e and
-                // u = x - 2 e[n-1] + e[n-2], the output error becomes
-                // e - 2 e[n-1] + e[n-2] (2nd-order highpass).
-                let u = x - 2.0 * e1 + e2;
-                let q = (u + f64::from(self.rng.next()))
-                    .round()
-                    .clamp(self.min, self.max);
-                self.err[slot] = (q - u, e1);
-                q as i32
+        if self.mode == DitherMode::Off {
+            return x.round().clamp(self.min, self.max) as i32;
+        }
+        let slot = ch.min(self.err.len() - 1);
+        if self.auto_blank {
+            if v == 0.0 {
+                self.silent_run[slot] = self.silent_run[slot].saturating_add(1);
+                if self.silent_run[slot] >= DITHER_AUTO_BLANK_HOLD {
+                    self.err[slot] = [0.0; MAX_NTF_TAPS];
+                    return 0;
+                }
+            } else {
+                self.silent_run[slot] = 0;
             }
         }
+        if self.ntf.is_empty() {
+            return (x + f64::from(self.rng.next()))
+                .round()
+                .clamp(self.min, self.max) as i32;
+        }
+        // Error feedback: with q = u + e and u = x - sum(c[k] e[n-1-k]),
+        // the output error becomes e - sum(c[k] e[n-1-k]), i.e. the NTF
+        // 1 - sum(c[k] z^-(k+1)) shapes the (dither + rounding) noise.
+        let history = &mut self.err[slot];
+        let feedback: f64 = self
+            .ntf
+            .iter()
+            .zip(history.iter())
+            .map(|(c, e)| c * e)
+            .sum();
+        let u = x - feedback;
+        let q = (u + f64::from(self.rng.next()))
+            .round()
+            .clamp(self.min, self.max);
+        history.copy_within(0..MAX_NTF_TAPS - 1, 1);
+        // Clipping makes q - u arbitrarily large; bounding the stored error
+        // keeps the high-gain curves from ringing after a clipped sample.
+        // Unclipped errors are always within +/-1.5 LSB.
+        history[0] = (q - u).clamp(-2.0, 2.0);
+        q as i32
     }
 }
 
@@ -258,6 +384,38 @@ pub fn quantize_channels_in_place(channels: &mut [Vec<f32>], depth: WavBitDepth)
     }
 }
 
+/// [`quantize_channels_in_place`] with dither: rounds to the integer grid of
+/// `depth` through a [`Quantizer`] (so the result is exactly what an export
+/// at that depth would write) and scales back to float. Float32 and
+/// `DitherMode::Off` take the plain rounding path.
+pub fn quantize_channels_dithered_in_place(
+    channels: &mut [Vec<f32>],
+    depth: WavBitDepth,
+    mode: DitherMode,
+    auto_blank: bool,
+    sample_rate: u32,
+) {
+    let (scale, min, max) = match depth {
+        WavBitDepth::Pcm16 => (32768.0, i16::MIN as f64, i16::MAX as f64),
+        WavBitDepth::Pcm24 => (8_388_607.0, -8_388_607.0, 8_388_607.0),
+        WavBitDepth::Float32 => return quantize_channels_in_place(channels, depth),
+    };
+    if mode == DitherMode::Off {
+        return quantize_channels_in_place(channels, depth);
+    }
+    let mut quant = Quantizer::new(scale, min, max, channels.len(), mode)
+        .with_sample_rate(sample_rate)
+        .with_auto_blank(auto_blank);
+    let frames = channels.iter().map(Vec::len).max().unwrap_or(0);
+    for i in 0..frames {
+        for (ci, ch) in channels.iter_mut().enumerate() {
+            if let Some(v) = ch.get_mut(i) {
+                *v = quant.quantize(ci, v.clamp(-1.0, 1.0)) as f32 / scale as f32;
+            }
+        }
+    }
+}
+
 fn write_wav_range_with_depth(
     chans: &[Vec<f32>],
     sample_rate: u32,
@@ -295,19 +453,21 @@ fn write_wav_range_with_depth(
     };
     let mut writer = hound::WavWriter::create(dst, spec)?;
     let mut quant = match depth {
-        WavBitDepth::Pcm16 => Quantizer::new(
+        WavBitDepth::Pcm16 => Quantizer::for_export(
             32768.0,
             i16::MIN as f64,
             i16::MAX as f64,
             ch as usize,
-            Quantizer::export_mode_for_bits(16),
+            16,
+            sample_rate,
         ),
-        WavBitDepth::Pcm24 => Quantizer::new(
+        WavBitDepth::Pcm24 => Quantizer::for_export(
             8_388_607.0,
             -8_388_607.0,
             8_388_607.0,
             ch as usize,
-            Quantizer::export_mode_for_bits(24),
+            24,
+            sample_rate,
         ),
         WavBitDepth::Float32 => Quantizer::new(1.0, -1.0, 1.0, 1, DitherMode::Off),
     };
@@ -390,19 +550,21 @@ fn write_aiff_with_depth(
 
     let mut sound: Vec<u8> = Vec::with_capacity(sound_len);
     let mut quant = match depth {
-        WavBitDepth::Pcm16 => Quantizer::new(
+        WavBitDepth::Pcm16 => Quantizer::for_export(
             32768.0,
             i16::MIN as f64,
             i16::MAX as f64,
             channels,
-            Quantizer::export_mode_for_bits(16),
+            16,
+            sample_rate,
         ),
-        WavBitDepth::Pcm24 => Quantizer::new(
+        WavBitDepth::Pcm24 => Quantizer::for_export(
             8_388_607.0,
             -8_388_607.0,
             8_388_607.0,
             channels,
-            Quantizer::export_mode_for_bits(24),
+            24,
+            sample_rate,
         ),
         WavBitDepth::Float32 => Quantizer::new(1.0, -1.0, 1.0, 1, DitherMode::Off),
     };
@@ -536,6 +698,215 @@ fn encode_aiff_chunks(path: &Path, is_aifc: bool, chunks: &[AiffChunk]) -> Resul
     replace_file_with_tmp(&tmp, path, false)
 }
 
+/// One `MARK` entry: a marker id and a sample-frame position with a pstring
+/// name.
+struct AiffMark {
+    id: i16,
+    position: u32,
+    name: Vec<u8>,
+}
+
+/// One `COMT` entry; `marker_id` 0 means the comment belongs to no marker.
+struct AiffComment {
+    timestamp: u32,
+    marker_id: i16,
+    text: Vec<u8>,
+}
+
+/// `COMT` text on a region's end marker naming the id of its start marker.
+/// Plain cue markers and region starts carry their label as the comment.
+const AIFF_REGION_END_PREFIX: &str = "neowaves:region-end=";
+
+/// Cue markers and regions kept in AIFF `MARK` chunks, positions in sample
+/// frames. Loop points referenced by `INST` are not part of this list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AiffCues {
+    pub markers: Vec<(u32, String)>,
+    pub regions: Vec<(u32, u32, String)>,
+}
+
+fn parse_aiff_marks(payload: &[u8]) -> Vec<AiffMark> {
+    let mut out = Vec::new();
+    let Some(count) = payload.get(0..2).map(|b| u16::from_be_bytes([b[0], b[1]])) else {
+        return out;
+    };
+    let mut pos = 2usize;
+    for _ in 0..count {
+        let Some(head) = payload.get(pos..pos + 7) else {
+            break;
+        };
+        let id = i16::from_be_bytes([head[0], head[1]]);
+        let position = u32::from_be_bytes([head[2], head[3], head[4], head[5]]);
+        let name_len = head[6] as usize;
+        let name = payload
+            .get(pos + 7..pos + 7 + name_len)
+            .map(|b| b.to_vec())
+            .unwrap_or_default();
+        out.push(AiffMark { id, position, name });
+        let entry = 6 + 1 + name_len;
+        pos += entry + (entry & 1);
+    }
+    out
+}
+
+fn encode_aiff_marks(marks: &[AiffMark]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(marks.len() as u16).to_be_bytes());
+    for mark in marks {
+        let name = &mark.name[..mark.name.len().min(255)];
+        out.extend_from_slice(&mark.id.to_be_bytes());
+        out.extend_from_slice(&mark.position.to_be_bytes());
+        out.push(name.len() as u8);
+        out.extend_from_slice(name);
+        if (1 + name.len()) & 1 == 1 {
+            out.push(0);
+        }
+    }
+    out
+}
+
+fn parse_aiff_comments(payload: &[u8]) -> Vec<AiffComment> {
+    let mut out = Vec::new();
+    let Some(count) = payload.get(0..2).map(|b| u16::from_be_bytes([b[0], b[1]])) else {
+        return out;
+    };
+    let mut pos = 2usize;
+    for _ in 0..count {
+        let Some(head) = payload.get(pos..pos + 8) else {
+            break;
+        };
+        let timestamp = u32::from_be_bytes([head[0], head[1], head[2], head[3]]);
+        let marker_id = i16::from_be_bytes([head[4], head[5]]);
+        let len = u16::from_be_bytes([head[6], head[7]]) as usize;
+        let Some(text) = payload.get(pos + 8..pos + 8 + len) else {
+            break;
+        };
+        out.push(AiffComment {
+            timestamp,
+            marker_id,
+            text: text.to_vec(),
+        });
+        pos += 8 + len + (len & 1);
+    }
+    out
+}
+
+fn encode_aiff_comments(comments: &[AiffComment]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(comments.len() as u16).to_be_bytes());
+    for comment in comments {
+        let text = &comment.text[..comment.text.len().min(u16::MAX as usize)];
+        out.extend_from_slice(&comment.timestamp.to_be_bytes());
+        out.extend_from_slice(&comment.marker_id.to_be_bytes());
+        out.extend_from_slice(&(text.len() as u16).to_be_bytes());
+        out.extend_from_slice(text);
+        if text.len() & 1 == 1 {
+            out.push(0);
+        }
+    }
+    out
+}
+
+/// Marker ids used by the active `INST` sustain/release loops.
+fn aiff_loop_mark_ids(chunks: &[AiffChunk]) -> Vec<i16> {
+    let Some(inst) = chunks.iter().find(|c| &c.id == b"INST") else {
+        return Vec::new();
+    };
+    let p = &inst.payload;
+    let mut ids = Vec::new();
+    for at in [8usize, 14] {
+        let Some(lp) = p.get(at..at + 6) else {
+            continue;
+        };
+        if i16::from_be_bytes([lp[0], lp[1]]) != 0 {
+            ids.push(i16::from_be_bytes([lp[2], lp[3]]));
+            ids.push(i16::from_be_bytes([lp[4], lp[5]]));
+        }
+    }
+    ids
+}
+
+fn aiff_marks_of(chunks: &[AiffChunk]) -> Vec<AiffMark> {
+    chunks
+        .iter()
+        .find(|c| &c.id == b"MARK")
+        .map(|c| parse_aiff_marks(&c.payload))
+        .unwrap_or_default()
+}
+
+/// Hands out `MARK` ids, which are positive 16-bit values, skipping the ids
+/// already taken.
+struct AiffMarkIds {
+    used: std::collections::HashSet<i16>,
+    next: i16,
+}
+
+impl AiffMarkIds {
+    fn new(used: impl IntoIterator<Item = i16>) -> Self {
+        Self {
+            used: used.into_iter().collect(),
+            next: 1,
+        }
+    }
+
+    fn alloc(&mut self) -> Result<i16> {
+        while self.next > 0 {
+            let id = self.next;
+            self.next = self.next.checked_add(1).unwrap_or(0);
+            if self.used.insert(id) {
+                return Ok(id);
+            }
+        }
+        anyhow::bail!("no free AIFF marker id left (MARK ids are positive 16-bit values)")
+    }
+}
+
+/// Drop `COMT` entries attached to a marker id that is not in `marks`;
+/// comments on no marker (id 0) stay.
+fn retain_attached_aiff_comments(comments: &mut Vec<AiffComment>, marks: &[AiffMark]) {
+    comments.retain(|c| c.marker_id == 0 || marks.iter().any(|m| m.id == c.marker_id));
+}
+
+/// Seconds since 1904-01-01, the AIFF `COMT` timestamp epoch.
+fn aiff_timestamp_now() -> u32 {
+    const MAC_EPOCH_OFFSET: u64 = 2_082_844_800;
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| (d.as_secs() + MAC_EPOCH_OFFSET) as u32)
+        .unwrap_or(0)
+}
+
+/// Replace the `MARK` / `COMT` / `INST` chunks (any `None` is dropped). They
+/// go before SSND: several readers treat the sound data as running to the end
+/// of the FORM, so trailing chunks would be misread as audio.
+fn replace_aiff_marker_chunks(
+    chunks: &mut Vec<AiffChunk>,
+    mark: Option<Vec<u8>>,
+    comt: Option<Vec<u8>>,
+    inst: Option<Vec<u8>>,
+) {
+    chunks.retain(|c| &c.id != b"MARK" && &c.id != b"COMT" && &c.id != b"INST");
+    let insert_at = chunks
+        .iter()
+        .position(|c| &c.id == b"SSND")
+        .unwrap_or(chunks.len());
+    let new_chunks = [(*b"MARK", mark), (*b"COMT", comt), (*b"INST", inst)];
+    for (offset, (id, payload)) in new_chunks
+        .into_iter()
+        .filter_map(|(id, payload)| payload.map(|p| (id, p)))
+        .enumerate()
+    {
+        chunks.insert(insert_at + offset, AiffChunk { id, payload });
+    }
+}
+
+fn find_aiff_chunk(chunks: &[AiffChunk], id: &[u8; 4]) -> Option<Vec<u8>> {
+    chunks
+        .iter()
+        .find(|c| &c.id == id)
+        .map(|c| c.payload.clone())
+}
+
 /// Read the sustain loop from AIFF `INST` + `MARK` chunks (the AIFF
 /// counterpart of the WAV `smpl` loop).
 pub fn read_aiff_loop_markers(path: &Path) -> Option<(u32, u32)> {
@@ -550,64 +921,41 @@ pub fn read_aiff_loop_markers(path: &Path) -> Option<(u32, u32)> {
     }
     let begin_id = i16::from_be_bytes([inst.payload[10], inst.payload[11]]);
     let end_id = i16::from_be_bytes([inst.payload[12], inst.payload[13]]);
-    let mark = chunks.iter().find(|c| &c.id == b"MARK")?;
-    let mut positions = std::collections::HashMap::new();
-    let payload = &mark.payload;
-    if payload.len() < 2 {
-        return None;
-    }
-    let count = u16::from_be_bytes([payload[0], payload[1]]) as usize;
-    let mut pos = 2usize;
-    for _ in 0..count {
-        if pos + 6 > payload.len() {
-            break;
-        }
-        let id = i16::from_be_bytes([payload[pos], payload[pos + 1]]);
-        let sample = u32::from_be_bytes([
-            payload[pos + 2],
-            payload[pos + 3],
-            payload[pos + 4],
-            payload[pos + 5],
-        ]);
-        positions.insert(id, sample);
-        let name_len = *payload.get(pos + 6)? as usize;
-        let entry = 6 + 1 + name_len;
-        pos += entry + (entry & 1);
-    }
-    let start = *positions.get(&begin_id)?;
-    let end = *positions.get(&end_id)?;
+    let marks = aiff_marks_of(&chunks);
+    let position = |id: i16| marks.iter().find(|m| m.id == id).map(|m| m.position);
+    let start = position(begin_id)?;
+    let end = position(end_id)?;
     (end > start).then_some((start, end))
 }
 
-/// Write (or clear) the sustain loop as AIFF `MARK` + `INST` chunks.
+/// Write (or clear) the sustain loop as AIFF `MARK` + `INST` chunks. Cue
+/// markers and regions already in `MARK` are kept; comments on the removed
+/// loop markers are dropped.
 pub fn write_aiff_loop_markers(path: &Path, loop_opt: Option<(u32, u32)>) -> Result<()> {
     let (is_aifc, mut chunks) = parse_aiff_chunks(path)?;
-    chunks.retain(|c| &c.id != b"MARK" && &c.id != b"INST");
-    // Insert before SSND: several readers treat the sound data as running to
-    // the end of the FORM, so trailing chunks would be misread as audio.
-    let insert_at = chunks
+    let old_loop_ids = aiff_loop_mark_ids(&chunks);
+    let mut marks = aiff_marks_of(&chunks);
+    marks.retain(|m| !old_loop_ids.contains(&m.id));
+    let mut comments = chunks
         .iter()
-        .position(|c| &c.id == b"SSND")
-        .unwrap_or(chunks.len());
+        .find(|c| &c.id == b"COMT")
+        .map(|c| parse_aiff_comments(&c.payload))
+        .unwrap_or_default();
+    retain_attached_aiff_comments(&mut comments, &marks);
+    let mut inst_payload = None;
     if let Some((start, end)) = loop_opt.filter(|(s, e)| e > s) {
-        let mut mark = Vec::new();
-        mark.extend_from_slice(&2u16.to_be_bytes());
-        for (id, sample, name) in [(1i16, start, b"beg loop".as_slice()), (2, end, b"end loop")] {
-            mark.extend_from_slice(&id.to_be_bytes());
-            mark.extend_from_slice(&sample.to_be_bytes());
-            mark.push(name.len() as u8);
-            mark.extend_from_slice(name);
-            if (1 + name.len()) & 1 == 1 {
-                mark.push(0);
-            }
-        }
-        chunks.insert(
-            insert_at,
-            AiffChunk {
-                id: *b"MARK",
-                payload: mark,
-            },
-        );
+        let mut ids = AiffMarkIds::new(marks.iter().map(|m| m.id));
+        let (begin_id, end_id) = (ids.alloc()?, ids.alloc()?);
+        marks.push(AiffMark {
+            id: begin_id,
+            position: start,
+            name: b"beg loop".to_vec(),
+        });
+        marks.push(AiffMark {
+            id: end_id,
+            position: end,
+            name: b"end loop".to_vec(),
+        });
         let mut inst = Vec::with_capacity(20);
         inst.push(60); // baseNote (C4)
         inst.push(0); // detune
@@ -617,19 +965,127 @@ pub fn write_aiff_loop_markers(path: &Path, loop_opt: Option<(u32, u32)>) -> Res
         inst.push(127); // highVelocity
         inst.extend_from_slice(&0i16.to_be_bytes()); // gain
         inst.extend_from_slice(&1i16.to_be_bytes()); // sustain: forward loop
-        inst.extend_from_slice(&1i16.to_be_bytes()); // sustain begin marker id
-        inst.extend_from_slice(&2i16.to_be_bytes()); // sustain end marker id
+        inst.extend_from_slice(&begin_id.to_be_bytes()); // sustain begin marker id
+        inst.extend_from_slice(&end_id.to_be_bytes()); // sustain end marker id
         inst.extend_from_slice(&0i16.to_be_bytes()); // release: no loop
         inst.extend_from_slice(&0i16.to_be_bytes());
         inst.extend_from_slice(&0i16.to_be_bytes());
-        chunks.insert(
-            insert_at + 1,
-            AiffChunk {
-                id: *b"INST",
-                payload: inst,
-            },
+        inst_payload = Some(inst);
+    }
+    let mark = (!marks.is_empty()).then(|| encode_aiff_marks(&marks));
+    let comt = (!comments.is_empty()).then(|| encode_aiff_comments(&comments));
+    replace_aiff_marker_chunks(&mut chunks, mark, comt, inst_payload);
+    encode_aiff_chunks(path, is_aifc, &chunks)
+}
+
+/// Read cue markers and regions from AIFF `MARK` entries, taking labels from
+/// the matching `COMT` comment (full UTF-8) before the pstring marker name.
+pub fn read_aiff_cues(path: &Path) -> Result<AiffCues> {
+    let (_, chunks) = parse_aiff_chunks(path)?;
+    let loop_ids = aiff_loop_mark_ids(&chunks);
+    let marks: Vec<AiffMark> = aiff_marks_of(&chunks)
+        .into_iter()
+        .filter(|m| !loop_ids.contains(&m.id))
+        .collect();
+    let comments = chunks
+        .iter()
+        .find(|c| &c.id == b"COMT")
+        .map(|c| parse_aiff_comments(&c.payload))
+        .unwrap_or_default();
+    let comment_text = |id: i16| {
+        comments
+            .iter()
+            .find(|c| c.marker_id == id && c.marker_id != 0)
+            .map(|c| String::from_utf8_lossy(&c.text).into_owned())
+    };
+    let label_of = |mark: &AiffMark| {
+        comment_text(mark.id).unwrap_or_else(|| String::from_utf8_lossy(&mark.name).into_owned())
+    };
+    let mut cues = AiffCues::default();
+    let mut region_ids: Vec<i16> = Vec::new();
+    for end_mark in &marks {
+        let Some(start_id) = comment_text(end_mark.id).and_then(|t| {
+            t.strip_prefix(AIFF_REGION_END_PREFIX)?
+                .trim()
+                .parse::<i16>()
+                .ok()
+        }) else {
+            continue;
+        };
+        let Some(start_mark) = marks.iter().find(|m| m.id == start_id) else {
+            continue;
+        };
+        if end_mark.position > start_mark.position {
+            cues.regions
+                .push((start_mark.position, end_mark.position, label_of(start_mark)));
+            region_ids.extend([start_mark.id, end_mark.id]);
+        }
+    }
+    for mark in marks.iter().filter(|m| !region_ids.contains(&m.id)) {
+        cues.markers.push((mark.position, label_of(mark)));
+    }
+    cues.markers.sort_by_key(|(pos, _)| *pos);
+    cues.regions.sort_by_key(|(start, end, _)| (*start, *end));
+    Ok(cues)
+}
+
+/// Replace the cue markers and regions in AIFF `MARK` / `COMT`, keeping the
+/// `INST` loop points and comments that are not attached to a marker.
+/// Regions become a start/end marker pair.
+pub fn write_aiff_cues(path: &Path, cues: &AiffCues) -> Result<()> {
+    let (is_aifc, mut chunks) = parse_aiff_chunks(path)?;
+    let loop_ids = aiff_loop_mark_ids(&chunks);
+    let mut marks: Vec<AiffMark> = aiff_marks_of(&chunks)
+        .into_iter()
+        .filter(|m| loop_ids.contains(&m.id))
+        .collect();
+    let mut comments: Vec<AiffComment> = chunks
+        .iter()
+        .find(|c| &c.id == b"COMT")
+        .map(|c| parse_aiff_comments(&c.payload))
+        .unwrap_or_default();
+    retain_attached_aiff_comments(&mut comments, &marks);
+    let timestamp = aiff_timestamp_now();
+    let mut ids = AiffMarkIds::new(marks.iter().map(|m| m.id));
+    let mut push = |marks: &mut Vec<AiffMark>, id: i16, position: u32, name: &str, text: String| {
+        // pstring names top out at 255 bytes; the full label lives in COMT.
+        let mut cut = name.len().min(255);
+        while !name.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        marks.push(AiffMark {
+            id,
+            position,
+            name: name[..cut].as_bytes().to_vec(),
+        });
+        if !text.is_empty() {
+            comments.push(AiffComment {
+                timestamp,
+                marker_id: id,
+                text: text.into_bytes(),
+            });
+        }
+    };
+    for (position, label) in &cues.markers {
+        let id = ids.alloc()?;
+        push(&mut marks, id, *position, label, label.clone());
+    }
+    for (start, end, label) in cues.regions.iter().filter(|(s, e, _)| e > s) {
+        let start_id = ids.alloc()?;
+        let end_id = ids.alloc()?;
+        push(&mut marks, start_id, *start, label, label.clone());
+        push(
+            &mut marks,
+            end_id,
+            *end,
+            &format!("{label} end"),
+            format!("{AIFF_REGION_END_PREFIX}{start_id}"),
         );
     }
+    let inst = find_aiff_chunk(&chunks, b"INST");
+    let mark = (!marks.is_empty()).then(|| encode_aiff_marks(&marks));
+    let comt = (!comments.is_empty()).then(|| encode_aiff_comments(&comments));
+    replace_aiff_marker_chunks(&mut chunks, mark, comt, inst);
     encode_aiff_chunks(path, is_aifc, &chunks)
 }
 
@@ -1292,78 +1748,451 @@ pub fn read_wav_bext(path: &Path) -> Result<Option<BextFields>> {
     }))
 }
 
-/// RIFF LIST/INFO tags exposed for batch metadata writing.
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct InfoFields {
-    /// INAM — title/name.
-    pub name: String,
-    /// IART — artist/author.
-    pub artist: String,
-    /// ICMT — comment.
-    pub comment: String,
-}
+/// AES46 `cart` text fields, in chunk order. `TagText` is the variable
+/// tail after the fixed 2048-byte part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CartField {
+    Title,
+    Artist,
+    CutId,
+    ClientId,
+    Category,
+    Classification,
+    OutCue,
+    StartDate,
+    StartTime,
+    EndDate,
+    EndTime,
+    ProducerAppId,
+    ProducerAppVersion,
+    UserDef,
+    Url,
+    TagText,
+}
+
+impl CartField {
+    pub const ALL: [CartField; 16] = [
+        CartField::Title,
+        CartField::Artist,
+        CartField::CutId,
+        CartField::ClientId,
+        CartField::Category,
+        CartField::Classification,
+        CartField::OutCue,
+        CartField::StartDate,
+        CartField::StartTime,
+        CartField::EndDate,
+        CartField::EndTime,
+        CartField::ProducerAppId,
+        CartField::ProducerAppVersion,
+        CartField::UserDef,
+        CartField::Url,
+        CartField::TagText,
+    ];
+
+    /// Key used for normalized metadata (`cart.<key>`) and the CLI.
+    pub fn key(self) -> &'static str {
+        match self {
+            CartField::Title => "title",
+            CartField::Artist => "artist",
+            CartField::CutId => "cut_id",
+            CartField::ClientId => "client_id",
+            CartField::Category => "category",
+            CartField::Classification => "classification",
+            CartField::OutCue => "out_cue",
+            CartField::StartDate => "start_date",
+            CartField::StartTime => "start_time",
+            CartField::EndDate => "end_date",
+            CartField::EndTime => "end_time",
+            CartField::ProducerAppId => "producer_app_id",
+            CartField::ProducerAppVersion => "producer_app_version",
+            CartField::UserDef => "user_def",
+            CartField::Url => "url",
+            CartField::TagText => "tag_text",
+        }
+    }
 
-/// Core iXML production fields (BWF iXML spec).
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct IxmlFields {
-    pub project: String,
-    pub scene: String,
-    pub take: String,
-    pub tape: String,
-    pub note: String,
-}
+    pub fn from_key(key: &str) -> Option<Self> {
+        let key = key.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|field| field.key() == key)
+    }
 
-fn xml_escape(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for c in s.chars() {
-        match c {
-            '&' => out.push_str("&amp;"),
-            '<' => out.push_str("&lt;"),
-            '>' => out.push_str("&gt;"),
-            '"' => out.push_str("&quot;"),
-            '\'' => out.push_str("&apos;"),
-            _ => out.push(c),
+    /// Field name as the AES46 structure spells it.
+    pub fn label(self) -> &'static str {
+        match self {
+            CartField::Title => "Title",
+            CartField::Artist => "Artist",
+            CartField::CutId => "CutID",
+            CartField::ClientId => "ClientID",
+            CartField::Category => "Category",
+            CartField::Classification => "Classification",
+            CartField::OutCue => "OutCue",
+            CartField::StartDate => "StartDate",
+            CartField::StartTime => "StartTime",
+            CartField::EndDate => "EndDate",
+            CartField::EndTime => "EndTime",
+            CartField::ProducerAppId => "ProducerAppID",
+            CartField::ProducerAppVersion => "ProducerAppVersion",
+            CartField::UserDef => "UserDef",
+            CartField::Url => "URL",
+            CartField::TagText => "TagText",
+        }
+    }
+
+    /// Byte offset and length in the payload; `TagText` runs to the end.
+    pub fn layout(self) -> (usize, Option<usize>) {
+        match self {
+            CartField::Title => (4, Some(64)),
+            CartField::Artist => (68, Some(64)),
+            CartField::CutId => (132, Some(64)),
+            CartField::ClientId => (196, Some(64)),
+            CartField::Category => (260, Some(64)),
+            CartField::Classification => (324, Some(64)),
+            CartField::OutCue => (388, Some(64)),
+            CartField::StartDate => (452, Some(10)),
+            CartField::StartTime => (462, Some(8)),
+            CartField::EndDate => (470, Some(10)),
+            CartField::EndTime => (480, Some(8)),
+            CartField::ProducerAppId => (488, Some(64)),
+            CartField::ProducerAppVersion => (552, Some(64)),
+            CartField::UserDef => (616, Some(64)),
+            CartField::Url => (1024, Some(1024)),
+            CartField::TagText => (CART_FIXED_BYTES, None),
         }
     }
-    out
 }
 
-fn xml_unescape(s: &str) -> String {
-    s.replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&apos;", "'")
-        .replace("&amp;", "&")
+/// Size of the fixed part of a `cart` payload (through the URL).
+pub const CART_FIXED_BYTES: usize = 2048;
+/// Offset of the `dwLevelReference` field.
+pub const CART_LEVEL_REFERENCE_OFFSET: usize = 680;
+/// Offset of the eight `CART_TIMER` slots (usage FOURCC + u32 sample).
+pub const CART_POST_TIMERS_OFFSET: usize = 684;
+pub const CART_POST_TIMER_SLOTS: usize = 8;
+
+/// One AES46 post-timer: a four-character usage ID (`SEGs`, `INTe`, ...)
+/// and a sample offset from the start of the audio.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CartTimer {
+    pub usage: String,
+    pub sample: u32,
 }
 
-/// Build a RIFF `LIST` payload of form-type `INFO` with INAM/IART/ICMT
-/// sub-chunks (NUL-terminated, word-aligned). Empty fields are omitted;
-/// all-empty yields `None` (nothing to write).
-pub fn build_info_list_chunk(fields: &InfoFields) -> Option<Vec<u8>> {
-    let mut out = b"INFO".to_vec();
-    for (id, value) in [
-        (*b"INAM", fields.name.trim()),
-        (*b"IART", fields.artist.trim()),
-        (*b"ICMT", fields.comment.trim()),
-    ] {
-        if value.is_empty() {
-            continue;
-        }
-        let mut bytes = value.as_bytes().to_vec();
-        bytes.push(0);
-        out.extend_from_slice(&id);
-        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
-        out.extend_from_slice(&bytes);
-        if bytes.len() & 1 == 1 {
-            out.push(0);
+/// BWF `cart` chunk (AES46) for radio automation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CartFields {
+    /// Four ASCII digits, `0101` for AES46-2002.
+    pub version: String,
+    pub title: String,
+    pub artist: String,
+    pub cut_id: String,
+    pub client_id: String,
+    pub category: String,
+    pub classification: String,
+    pub out_cue: String,
+    /// `yyyy-mm-dd`.
+    pub start_date: String,
+    /// `hh:mm:ss`.
+    pub start_time: String,
+    pub end_date: String,
+    pub end_time: String,
+    pub producer_app_id: String,
+    pub producer_app_version: String,
+    pub user_def: String,
+    /// Sample value of 0 dB reference level (0 = not set).
+    pub level_reference: i32,
+    /// Up to eight; extra timers are dropped on write.
+    pub post_timers: Vec<CartTimer>,
+    pub url: String,
+    pub tag_text: String,
+}
+
+impl Default for CartFields {
+    fn default() -> Self {
+        Self {
+            version: "0101".to_string(),
+            title: String::new(),
+            artist: String::new(),
+            cut_id: String::new(),
+            client_id: String::new(),
+            category: String::new(),
+            classification: String::new(),
+            out_cue: String::new(),
+            start_date: String::new(),
+            start_time: String::new(),
+            end_date: String::new(),
+            end_time: String::new(),
+            producer_app_id: String::new(),
+            producer_app_version: String::new(),
+            user_def: String::new(),
+            level_reference: 0,
+            post_timers: Vec::new(),
+            url: String::new(),
+            tag_text: String::new(),
         }
     }
-    (out.len() > 4).then_some(out)
 }
 
-/// Build an `iXML` chunk payload with the core production fields.
-/// Hand-assembled XML (escaped) — no dependencies. All-empty => `None`.
-pub fn build_ixml_chunk(fields: &IxmlFields) -> Option<Vec<u8>> {
+impl CartFields {
+    pub fn get(&self, field: CartField) -> &str {
+        match field {
+            CartField::Title => &self.title,
+            CartField::Artist => &self.artist,
+            CartField::CutId => &self.cut_id,
+            CartField::ClientId => &self.client_id,
+            CartField::Category => &self.category,
+            CartField::Classification => &self.classification,
+            CartField::OutCue => &self.out_cue,
+            CartField::StartDate => &self.start_date,
+            CartField::StartTime => &self.start_time,
+            CartField::EndDate => &self.end_date,
+            CartField::EndTime => &self.end_time,
+            CartField::ProducerAppId => &self.producer_app_id,
+            CartField::ProducerAppVersion => &self.producer_app_version,
+            CartField::UserDef => &self.user_def,
+            CartField::Url => &self.url,
+            CartField::TagText => &self.tag_text,
+        }
+    }
+
+    pub fn get_mut(&mut self, field: CartField) -> &mut String {
+        match field {
+            CartField::Title => &mut self.title,
+            CartField::Artist => &mut self.artist,
+            CartField::CutId => &mut self.cut_id,
+            CartField::ClientId => &mut self.client_id,
+            CartField::Category => &mut self.category,
+            CartField::Classification => &mut self.classification,
+            CartField::OutCue => &mut self.out_cue,
+            CartField::StartDate => &mut self.start_date,
+            CartField::StartTime => &mut self.start_time,
+            CartField::EndDate => &mut self.end_date,
+            CartField::EndTime => &mut self.end_time,
+            CartField::ProducerAppId => &mut self.producer_app_id,
+            CartField::ProducerAppVersion => &mut self.producer_app_version,
+            CartField::UserDef => &mut self.user_def,
+            CartField::Url => &mut self.url,
+            CartField::TagText => &mut self.tag_text,
+        }
+    }
+}
+
+/// Post-timer usage IDs defined by AES46 (segue, intro, audio, secondary
+/// segue, hook and marker start/end), the only IDs a marker
+/// label may carry without the `cart:` prefix.
+const CART_TIMER_USAGE_IDS: [&str; 13] = [
+    "SEGs", "SEGe", "INTs", "INTe", "AUDs", "AUDe", "SECs", "SECe", "HOOK", "HOKs", "HOKe", "MRKs",
+    "MRKe",
+];
+
+/// Post-timer usage ID a marker label stands for: `cart:XXXX`, or one of the
+/// AES46 usage IDs (`SEGs`, `INTe`, `AUDs`, ...) on its own. Other short
+/// labels such as `VOX1` are ordinary markers.
+pub fn cart_timer_usage_from_label(label: &str) -> Option<String> {
+    let label = label.trim();
+    if let Some(usage) = label.strip_prefix("cart:") {
+        let usage = usage.trim();
+        return (!usage.is_empty() && usage.len() <= 4 && usage.is_ascii())
+            .then(|| usage.to_string());
+    }
+    CART_TIMER_USAGE_IDS
+        .contains(&label)
+        .then(|| label.to_string())
+}
+
+/// Marker label for a post-timer; the inverse of
+/// [`cart_timer_usage_from_label`].
+pub fn cart_timer_marker_label(usage: &str) -> String {
+    let usage = usage.trim_end();
+    match cart_timer_usage_from_label(usage) {
+        Some(bare) if bare == usage => bare,
+        _ => format!("cart:{usage}"),
+    }
+}
+
+/// Serialize a `cart` payload: the 2048-byte fixed part, then the tag text.
+pub fn encode_cart_payload(fields: &CartFields) -> Vec<u8> {
+    let mut out = Vec::with_capacity(CART_FIXED_BYTES + fields.tag_text.len());
+    let version = if fields.version.trim().is_empty() {
+        "0101"
+    } else {
+        fields.version.trim()
+    };
+    push_fixed_ascii(&mut out, version, 4);
+    for field in CartField::ALL {
+        let (offset, Some(len)) = field.layout() else {
+            continue;
+        };
+        if field == CartField::Url {
+            out.resize(offset, 0); // Reserved
+        }
+        debug_assert_eq!(out.len(), offset);
+        push_fixed_ascii(&mut out, fields.get(field), len);
+        if field == CartField::UserDef {
+            out.extend_from_slice(&fields.level_reference.to_le_bytes());
+            for slot in 0..CART_POST_TIMER_SLOTS {
+                match fields.post_timers.get(slot) {
+                    Some(timer) => {
+                        push_fixed_ascii(&mut out, &timer.usage, 4);
+                        out.extend_from_slice(&timer.sample.to_le_bytes());
+                    }
+                    None => out.extend_from_slice(&[0; 8]),
+                }
+            }
+        }
+    }
+    out.extend_from_slice(fields.tag_text.as_bytes());
+    out
+}
+
+/// Parse a `cart` payload; short payloads leave the missing fields empty.
+pub fn decode_cart_payload(data: &[u8]) -> CartFields {
+    let mut fields = CartFields {
+        version: read_fixed_ascii(data, 0, 4),
+        ..CartFields::default()
+    };
+    for field in CartField::ALL {
+        let (offset, len) = field.layout();
+        let len = len.unwrap_or(data.len().saturating_sub(offset));
+        *fields.get_mut(field) = read_fixed_ascii(data, offset, len);
+    }
+    if let Some(raw) = data.get(CART_LEVEL_REFERENCE_OFFSET..CART_LEVEL_REFERENCE_OFFSET + 4) {
+        fields.level_reference = i32::from_le_bytes(raw.try_into().unwrap());
+    }
+    for slot in 0..CART_POST_TIMER_SLOTS {
+        let pos = CART_POST_TIMERS_OFFSET + slot * 8;
+        let Some(raw) = data.get(pos..pos + 8) else {
+            break;
+        };
+        let sample = u32::from_le_bytes(raw[4..8].try_into().unwrap());
+        if raw[..4].iter().all(|&b| b == 0) || sample == u32::MAX {
+            continue;
+        }
+        fields.post_timers.push(CartTimer {
+            usage: read_fixed_ascii(raw, 0, 4),
+            sample,
+        });
+    }
+    fields
+}
+
+/// Move the post-timers of a raw `cart` payload to a new sample rate in
+/// place, leaving every other byte as it was.
+fn rescale_cart_timers(payload: &mut [u8], from_sr: u32, to_sr: u32) {
+    if from_sr == 0 || to_sr == 0 || from_sr == to_sr {
+        return;
+    }
+    for slot in 0..CART_POST_TIMER_SLOTS {
+        let pos = CART_POST_TIMERS_OFFSET + slot * 8;
+        let Some(raw) = payload.get_mut(pos..pos + 8) else {
+            break;
+        };
+        let sample = u32::from_le_bytes(raw[4..8].try_into().unwrap());
+        if raw[..4].iter().all(|&b| b == 0) || sample == u32::MAX {
+            continue;
+        }
+        let moved = (sample as u64 * to_sr as u64 + from_sr as u64 / 2) / from_sr as u64;
+        raw[4..8].copy_from_slice(&(moved.min(u32::MAX as u64 - 1) as u32).to_le_bytes());
+    }
+}
+
+/// Write (or replace) the `cart` chunk of a WAV file, or remove it for
+/// `None`; every other chunk is preserved.
+pub fn write_wav_cart(path: &Path, fields: Option<&CartFields>) -> Result<()> {
+    let mut chunks = parse_riff_wave_chunks(path)?;
+    match fields {
+        Some(fields) => set_riff_wave_chunk(&mut chunks, *b"cart", encode_cart_payload(fields)),
+        None => {
+            if !chunks.iter().any(|c| &c.id == b"cart") {
+                return Ok(());
+            }
+            chunks.retain(|c| &c.id != b"cart");
+        }
+    }
+    encode_riff_wave_chunks(path, &chunks)
+}
+
+/// Read the `cart` chunk of a WAV file (None when absent).
+pub fn read_wav_cart(path: &Path) -> Result<Option<CartFields>> {
+    let chunks = parse_riff_wave_chunks(path)?;
+    Ok(chunks
+        .iter()
+        .find(|c| &c.id == b"cart")
+        .map(|c| decode_cart_payload(&c.payload)))
+}
+
+/// RIFF LIST/INFO tags exposed for batch metadata writing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InfoFields {
+    /// INAM — title/name.
+    pub name: String,
+    /// IART — artist/author.
+    pub artist: String,
+    /// ICMT — comment.
+    pub comment: String,
+}
+
+/// Core iXML production fields (BWF iXML spec).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IxmlFields {
+    pub project: String,
+    pub scene: String,
+    pub take: String,
+    pub tape: String,
+    pub note: String,
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Build a RIFF `LIST` payload of form-type `INFO` with INAM/IART/ICMT
+/// sub-chunks (NUL-terminated, word-aligned). Empty fields are omitted;
+/// all-empty yields `None` (nothing to write).
+pub fn build_info_list_chunk(fields: &InfoFields) -> Option<Vec<u8>> {
+    let mut out = b"INFO".to_vec();
+    for (id, value) in [
+        (*b"INAM", fields.name.trim()),
+        (*b"IART", fields.artist.trim()),
+        (*b"ICMT", fields.comment.trim()),
+    ] {
+        if value.is_empty() {
+            continue;
+        }
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.push(0);
+        out.extend_from_slice(&id);
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+        if bytes.len() & 1 == 1 {
+            out.push(0);
+        }
+    }
+    (out.len() > 4).then_some(out)
+}
+
+/// Build an `iXML` chunk payload with the core production fields.
+/// Hand-assembled XML (escaped) — no dependencies. All-empty => `None`.
+pub fn build_ixml_chunk(fields: &IxmlFields) -> Option<Vec<u8>> {
     let mut body = String::new();
     for (tag, value) in [
         ("PROJECT", fields.project.trim()),
@@ -1420,7 +2249,9 @@ pub fn write_wav_info_ixml(path: &Path, info: &InfoFields, ixml: &IxmlFields) ->
     }
     if let Some(payload) = ixml_payload {
         if let Some(existing) = chunks.iter_mut().find(|c| &c.id == b"iXML") {
-            existing.payload = payload;
+            // Keep ASWG/USER and anything else other tools put in the file;
+            // only an unparseable payload is replaced wholesale.
+            existing.payload = merge_core_ixml(&existing.payload, ixml).unwrap_or(payload);
         } else {
             let data_pos = chunks
                 .iter()
@@ -1438,6 +2269,27 @@ pub fn write_wav_info_ixml(path: &Path, info: &InfoFields, ixml: &IxmlFields) ->
     encode_riff_wave_chunks(path, &chunks)
 }
 
+/// Upsert the core production fields at the `BWFXML` root of an existing
+/// iXML payload. `None` when the payload has no `BWFXML` root.
+fn merge_core_ixml(existing: &[u8], fields: &IxmlFields) -> Option<Vec<u8>> {
+    let mut xml = String::from_utf8_lossy(existing)
+        .trim_end_matches('\0')
+        .to_string();
+    let (start, end) = xml_element_inner(&mut xml, "BWFXML")?;
+    let mut inner = xml[start..end].to_string();
+    for (tag, value) in [
+        ("PROJECT", fields.project.trim()),
+        ("SCENE", fields.scene.trim()),
+        ("TAKE", fields.take.trim()),
+        ("TAPE", fields.tape.trim()),
+        ("NOTE", fields.note.trim()),
+    ] {
+        inner = upsert_xml_child(&inner, tag, value, "  ");
+    }
+    xml.replace_range(start..end, &inner);
+    Some(xml.into_bytes())
+}
+
 /// Read the INAM/IART/ICMT tags from a WAV's `LIST/INFO` chunk.
 pub fn read_wav_info(path: &Path) -> Result<Option<InfoFields>> {
     let chunks = parse_riff_wave_chunks(path)?;
@@ -1495,6 +2347,387 @@ pub fn read_wav_ixml(path: &Path) -> Result<Option<IxmlFields>> {
     }))
 }
 
+/// Element names of the ASWG 1.1 iXML extension the UCS editor writes.
+const ASWG_UCS_TAGS: [(&str, crate::metadata::ucs::UcsField); 6] = [
+    ("catId", crate::metadata::ucs::UcsField::CatId),
+    ("category", crate::metadata::ucs::UcsField::Category),
+    ("subCategory", crate::metadata::ucs::UcsField::SubCategory),
+    ("fxName", crate::metadata::ucs::UcsField::FxName),
+    ("creatorId", crate::metadata::ucs::UcsField::CreatorId),
+    ("sourceId", crate::metadata::ucs::UcsField::SourceId),
+];
+const ASWG_NAMESPACE: &str = "http://sce/aswg/ixml";
+
+/// Byte range of the first `<tag ...>...</tag>` (or `<tag/>`) element in
+/// `xml`, matched ASCII case-insensitively. Hand-rolled like the rest of
+/// our iXML handling; good enough for the flat blocks tools write.
+fn find_xml_element(xml: &str, tag: &str) -> Option<(usize, usize)> {
+    let lower = xml.to_ascii_lowercase();
+    let tag_lower = tag.to_ascii_lowercase();
+    let open = format!("<{tag_lower}");
+    let close = format!("</{tag_lower}>");
+    let mut from = 0usize;
+    while let Some(rel) = lower[from..].find(&open) {
+        let start = from + rel;
+        let after = start + open.len();
+        match lower.as_bytes().get(after) {
+            Some(b'>' | b'/' | b' ' | b'\t' | b'\r' | b'\n') => {}
+            _ => {
+                from = after;
+                continue;
+            }
+        }
+        let open_end = after + lower[after..].find('>')? + 1;
+        if lower[..open_end].ends_with("/>") {
+            return Some((start, open_end));
+        }
+        let close_start = open_end + lower[open_end..].find(&close)?;
+        return Some((start, close_start + close.len()));
+    }
+    None
+}
+
+/// Inner range (between the open and close tags) of `tag` in `xml`.
+/// A self-closing `<tag/>` is first expanded to `<tag></tag>`.
+fn xml_element_inner(xml: &mut String, tag: &str) -> Option<(usize, usize)> {
+    let (start, end) = find_xml_element(xml, tag)?;
+    if xml[..end].ends_with("/>") {
+        let open = xml[start..end - 2].trim_end().to_string();
+        xml.replace_range(start..end, &format!("{open}></{tag}>"));
+        let inner = start + open.len() + 1;
+        return Some((inner, inner));
+    }
+    let open_end = start + xml[start..end].find('>')? + 1;
+    let close_start = start + xml[start..end].rfind("</")?;
+    Some((open_end, close_start))
+}
+
+/// Replace, insert or (for an empty value) remove the child element `tag`
+/// of a block's inner XML.
+fn upsert_xml_child(inner: &str, tag: &str, value: &str, indent: &str) -> String {
+    let mut out = inner.to_string();
+    if let Some((start, end)) = find_xml_element(inner, tag) {
+        if value.is_empty() {
+            // Drop the element together with its line when it sits alone.
+            let line_start = inner[..start].rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+            let alone =
+                inner[line_start..start].trim().is_empty() && inner[end..].starts_with('\n');
+            if alone && line_start > 0 {
+                out.replace_range(line_start - 1..end, "");
+            } else {
+                out.replace_range(start..end, "");
+            }
+        } else {
+            out.replace_range(start..end, &format!("<{tag}>{}</{tag}>", xml_escape(value)));
+        }
+    } else if !value.is_empty() {
+        let at = out.trim_end().len();
+        let had_trailing_space = at < out.len();
+        out.insert_str(
+            at,
+            &format!("\n{indent}<{tag}>{}</{tag}>", xml_escape(value)),
+        );
+        if !had_trailing_space {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Write `pairs` into the `block` element of an iXML document, creating
+/// the block before `</BWFXML>` when missing. Other content is kept.
+fn upsert_ixml_block(
+    xml: &mut String,
+    block: &str,
+    block_open: &str,
+    pairs: &[(&str, &str)],
+) -> Result<()> {
+    let (inner_start, inner_end) = match xml_element_inner(xml, block) {
+        Some(range) => range,
+        None => {
+            if pairs.iter().all(|(_, value)| value.is_empty()) {
+                return Ok(());
+            }
+            let Some((_, root_inner_end)) = xml_element_inner(xml, "BWFXML") else {
+                anyhow::bail!("iXML payload has no BWFXML root");
+            };
+            let at = xml[..root_inner_end].trim_end().len();
+            xml.insert_str(at, &format!("\n  {block_open}\n  </{block}>"));
+            xml_element_inner(xml, block).context("insert iXML block")?
+        }
+    };
+    let mut inner = xml[inner_start..inner_end].to_string();
+    for (tag, value) in pairs {
+        inner = upsert_xml_child(&inner, tag, value, "    ");
+    }
+    xml.replace_range(inner_start..inner_end, &inner);
+    Ok(())
+}
+
+/// Text of the child `tag` of the first `block` element in `xml`.
+fn ixml_block_child(xml: &str, block: &str, tag: &str) -> Option<String> {
+    let (start, end) = find_xml_element(xml, block)?;
+    let element = &xml[start..end];
+    let (child_start, child_end) = find_xml_element(element, tag)?;
+    let child = &element[child_start..child_end];
+    let open_end = child.find('>')? + 1;
+    let close_start = child.rfind("</").filter(|&pos| pos >= open_end)?;
+    Some(xml_unescape(child[open_end..close_start].trim()))
+}
+
+/// Merge UCS fields into an iXML document (or a fresh one): the ASWG 1.1
+/// block and the Soundminer-style `<USER>` block.
+pub fn merge_ucs_into_ixml(
+    existing: Option<&str>,
+    fields: &crate::metadata::ucs::UcsFields,
+    targets: UcsWriteTargets,
+) -> Result<String> {
+    let mut xml = match existing {
+        Some(xml) if !xml.trim().is_empty() => xml.to_string(),
+        _ => "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<BWFXML>\n  <IXML_VERSION>1.5</IXML_VERSION>\n</BWFXML>\n"
+            .to_string(),
+    };
+    if targets.aswg {
+        let pairs: Vec<(&str, &str)> = ASWG_UCS_TAGS
+            .iter()
+            .map(|(tag, field)| (*tag, fields.get(*field)))
+            .collect();
+        upsert_ixml_block(
+            &mut xml,
+            "ASWG",
+            &format!("<ASWG xmlns=\"{ASWG_NAMESPACE}\">"),
+            &pairs,
+        )?;
+    }
+    if targets.user {
+        let category_full = if fields.category.is_empty() || fields.subcategory.is_empty() {
+            String::new()
+        } else {
+            format!("{}-{}", fields.category, fields.subcategory)
+        };
+        let pairs = [
+            ("CATID", fields.cat_id.as_str()),
+            ("CATEGORY", fields.category.as_str()),
+            ("SUBCATEGORY", fields.subcategory.as_str()),
+            ("CATEGORYFULL", category_full.as_str()),
+            ("FXNAME", fields.fx_name.as_str()),
+            ("DESCRIPTION", fields.description.as_str()),
+            ("DESIGNER", fields.creator_id.as_str()),
+            ("LIBRARY", fields.source_id.as_str()),
+        ];
+        upsert_ixml_block(&mut xml, "USER", "<USER>", &pairs)?;
+    }
+    Ok(xml)
+}
+
+/// Where [`write_wav_ucs`] puts the UCS fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UcsWriteTargets {
+    /// iXML `<ASWG>` block (ASWG 1.1 namespace).
+    pub aswg: bool,
+    /// iXML `<USER>` block read by Soundminer/BaseHead-style libraries.
+    pub user: bool,
+    /// bext Description (the description, or the FXName when it is empty).
+    pub bext_description: bool,
+}
+
+impl Default for UcsWriteTargets {
+    fn default() -> Self {
+        Self {
+            aswg: true,
+            user: true,
+            bext_description: true,
+        }
+    }
+}
+
+/// Write UCS fields into a WAV in place. Only the targeted iXML blocks and
+/// the bext Description are touched: other iXML content, the rest of bext
+/// (dates, time reference, UMID, coding history) and every other chunk
+/// are preserved. Callers validate and resolve the fields first.
+pub fn write_wav_ucs(
+    path: &Path,
+    fields: &crate::metadata::ucs::UcsFields,
+    targets: UcsWriteTargets,
+) -> Result<()> {
+    let mut chunks = parse_riff_wave_chunks(path)?;
+    if targets.aswg || targets.user {
+        let existing = chunks.iter().find(|c| &c.id == b"iXML").map(|c| {
+            String::from_utf8_lossy(&c.payload)
+                .trim_end_matches('\0')
+                .to_string()
+        });
+        let xml = merge_ucs_into_ixml(existing.as_deref(), fields, targets)
+            .with_context(|| format!("merge UCS into iXML: {}", path.display()))?;
+        set_riff_wave_chunk(&mut chunks, *b"iXML", xml.into_bytes());
+    }
+    if targets.bext_description {
+        let description = if fields.description.trim().is_empty() {
+            fields.fx_name.trim()
+        } else {
+            fields.description.trim()
+        };
+        let mut cut = description
+            .len()
+            .min(crate::metadata::ucs::BEXT_DESCRIPTION_BYTES);
+        while !description.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let mut field = description.as_bytes()[..cut].to_vec();
+        field.resize(crate::metadata::ucs::BEXT_DESCRIPTION_BYTES, 0);
+        match chunks.iter_mut().find(|c| &c.id == b"bext") {
+            Some(existing) => {
+                if existing.payload.len() < 602 {
+                    existing.payload.resize(602, 0);
+                }
+                existing.payload[..field.len()].copy_from_slice(&field);
+            }
+            None => {
+                let mut payload = encode_bext_payload(&BextFields::default());
+                payload[..field.len()].copy_from_slice(&field);
+                set_riff_wave_chunk(&mut chunks, *b"bext", payload);
+            }
+        }
+    }
+    encode_riff_wave_chunks(path, &chunks)
+}
+
+/// Read the UCS fields of a WAV: ASWG first, then `<USER>`, with the bext
+/// Description as the description. Missing values stay empty.
+pub fn read_wav_ucs(path: &Path) -> Result<crate::metadata::ucs::UcsFields> {
+    use crate::metadata::ucs::UcsField;
+    let chunks = parse_riff_wave_chunks(path)?;
+    let mut fields = crate::metadata::ucs::UcsFields::default();
+    if let Some(chunk) = chunks.iter().find(|c| &c.id == b"iXML") {
+        let xml = String::from_utf8_lossy(&chunk.payload);
+        for (tag, field) in ASWG_UCS_TAGS {
+            if let Some(value) = ixml_block_child(&xml, "ASWG", tag) {
+                *fields.get_mut(field) = value;
+            }
+        }
+        for (tag, field) in [
+            ("CATID", UcsField::CatId),
+            ("CATEGORY", UcsField::Category),
+            ("SUBCATEGORY", UcsField::SubCategory),
+            ("FXNAME", UcsField::FxName),
+            ("DESIGNER", UcsField::CreatorId),
+            ("LIBRARY", UcsField::SourceId),
+            ("DESCRIPTION", UcsField::Description),
+        ] {
+            if fields.get(field).is_empty() {
+                if let Some(value) = ixml_block_child(&xml, "USER", tag) {
+                    *fields.get_mut(field) = value;
+                }
+            }
+        }
+    }
+    if fields.description.is_empty() {
+        if let Some(chunk) = chunks.iter().find(|c| &c.id == b"bext") {
+            fields.description = read_fixed_ascii(&chunk.payload, 0, 256);
+        }
+    }
+    Ok(fields)
+}
+
+/// Saved payloads of top-level WAV chunks (`None` = the chunk was absent),
+/// used to undo in-place metadata writes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WavChunkSnapshot {
+    pub chunks: Vec<([u8; 4], Option<Vec<u8>>)>,
+}
+
+/// Capture the first chunk of each id in `ids`.
+pub fn snapshot_wav_chunks(path: &Path, ids: &[[u8; 4]]) -> Result<WavChunkSnapshot> {
+    let chunks = parse_riff_wave_chunks(path)?;
+    Ok(WavChunkSnapshot {
+        chunks: ids
+            .iter()
+            .map(|id| {
+                (
+                    *id,
+                    chunks
+                        .iter()
+                        .find(|c| &c.id == id)
+                        .map(|c| c.payload.clone()),
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Put the snapshotted chunks back: replace or insert the ones that were
+/// present, remove the ones that were absent.
+pub fn restore_wav_chunks(path: &Path, snapshot: &WavChunkSnapshot) -> Result<()> {
+    let mut chunks = parse_riff_wave_chunks(path)?;
+    for (id, payload) in &snapshot.chunks {
+        match payload {
+            Some(payload) => set_riff_wave_chunk(&mut chunks, *id, payload.clone()),
+            None => {
+                if let Some(pos) = chunks.iter().position(|c| &c.id == id) {
+                    chunks.remove(pos);
+                }
+            }
+        }
+    }
+    encode_riff_wave_chunks(path, &chunks)
+}
+
+fn is_id3_chunk(chunk: &RiffWaveChunk) -> bool {
+    chunk.id.eq_ignore_ascii_case(b"ID3 ")
+}
+
+/// ID3 tag stored in a WAV `ID3 ` / `id3 ` chunk (None when absent).
+pub fn read_wav_id3_tag(path: &Path) -> Result<Option<id3::Tag>> {
+    let chunks = parse_riff_wave_chunks(path)?;
+    let Some(chunk) = chunks.iter().find(|c| is_id3_chunk(c)) else {
+        return Ok(None);
+    };
+    let tag = id3::Tag::read_from2(std::io::Cursor::new(&chunk.payload))
+        .with_context(|| format!("decode wav id3 chunk: {}", path.display()))?;
+    Ok(Some(tag))
+}
+
+/// Write `tag` into the WAV ID3 chunk (replacing any `ID3 ` / `id3 ` chunk
+/// in place, else before `data`), or drop the chunk for `None`.
+pub fn write_wav_id3_tag(path: &Path, tag: Option<&id3::Tag>) -> Result<()> {
+    let mut chunks = parse_riff_wave_chunks(path)?;
+    let existing = chunks.iter().position(is_id3_chunk);
+    match tag {
+        Some(tag) => {
+            let mut payload = Vec::new();
+            tag.write_to(&mut payload, id3::Version::Id3v24)
+                .with_context(|| format!("encode wav id3 chunk: {}", path.display()))?;
+            match existing {
+                Some(idx) => chunks[idx].payload = payload,
+                None => set_riff_wave_chunk(&mut chunks, *b"ID3 ", payload),
+            }
+            // Only one tag chunk; a second would shadow the edited one.
+            let mut seen = false;
+            chunks.retain(|c| !is_id3_chunk(c) || !std::mem::replace(&mut seen, true));
+        }
+        None => {
+            if existing.is_none() {
+                return Ok(());
+            }
+            chunks.retain(|c| !is_id3_chunk(c));
+        }
+    }
+    encode_riff_wave_chunks(path, &chunks)
+}
+
+/// Replace the first chunk with `id`, or insert it right before `data`.
+fn set_riff_wave_chunk(chunks: &mut Vec<RiffWaveChunk>, id: [u8; 4], payload: Vec<u8>) {
+    if let Some(existing) = chunks.iter_mut().find(|c| c.id == id) {
+        existing.payload = payload;
+        return;
+    }
+    let data_pos = chunks
+        .iter()
+        .position(|c| &c.id == b"data")
+        .unwrap_or(chunks.len());
+    chunks.insert(data_pos, RiffWaveChunk { id, payload });
+}
+
 #[derive(Clone)]
 struct RiffWaveChunk {
     id: [u8; 4],
@@ -1549,6 +2782,99 @@ fn encode_riff_wave_chunks(path: &Path, chunks: &[RiffWaveChunk]) -> Result<()>
     Ok(())
 }
 
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Tail shared by the KSDATAFORMAT_SUBTYPE_PCM / _IEEE_FLOAT GUIDs.
+const WAVE_SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// Locate the `fmt ` chunk of a RIFF/RF64/BW64 file: payload offset and
+/// payload bytes.
+fn find_wave_fmt_chunk(file: &mut std::fs::File) -> Result<Option<(u64, Vec<u8>)>> {
+    use std::io::{Read, Seek, SeekFrom};
+    let file_len = file.metadata()?.len();
+    let mut root = [0u8; 12];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut root)?;
+    if !matches!(&root[0..4], b"RIFF" | b"RF64" | b"BW64") || &root[8..12] != b"WAVE" {
+        return Ok(None);
+    }
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut header)?;
+        let size = u32::from_le_bytes(header[4..8].try_into().unwrap()) as u64;
+        if &header[0..4] == b"fmt " {
+            let mut payload = vec![0u8; size.min(file_len - pos - 8) as usize];
+            file.read_exact(&mut payload)?;
+            return Ok(Some((pos + 8, payload)));
+        }
+        if &header[0..4] == b"data" {
+            break;
+        }
+        pos += 8 + size + (size & 1);
+    }
+    Ok(None)
+}
+
+/// Declare `layout` in a WAVE file's `fmt ` chunk. An extensible header has
+/// its `dwChannelMask` patched in place; a plain PCM/float header is
+/// rewritten as WAVE_FORMAT_EXTENSIBLE unless the layout is the default one
+/// for a mono or stereo file, which the plain header already implies.
+pub fn write_wav_channel_layout(path: &Path, layout: ChannelLayout) -> Result<()> {
+    use std::io::{Seek, SeekFrom, Write};
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("open wav for channel layout: {}", path.display()))?;
+    let Some((fmt_offset, fmt)) = find_wave_fmt_chunk(&mut file)
+        .with_context(|| format!("read wav fmt: {}", path.display()))?
+    else {
+        anyhow::bail!("no fmt chunk: {}", path.display());
+    };
+    if fmt.len() < 16 {
+        anyhow::bail!("short fmt chunk: {}", path.display());
+    }
+    let format_tag = u16::from_le_bytes([fmt[0], fmt[1]]);
+    let channels = u16::from_le_bytes([fmt[2], fmt[3]]);
+    if channels != layout.channels() {
+        anyhow::bail!(
+            "channel layout has {} channels, file has {channels}: {}",
+            layout.channels(),
+            path.display()
+        );
+    }
+    if format_tag == WAVE_FORMAT_EXTENSIBLE && fmt.len() >= 24 {
+        if u32::from_le_bytes(fmt[20..24].try_into().unwrap()) != layout.mask() {
+            crate::app::watch::note_self_write(path);
+            file.seek(SeekFrom::Start(fmt_offset + 20))?;
+            file.write_all(&layout.mask().to_le_bytes())
+                .with_context(|| format!("write channel mask: {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    if channels <= 2 && layout.is_default() {
+        return Ok(());
+    }
+    drop(file);
+    let mut chunks = parse_riff_wave_chunks(path)?;
+    let Some(fmt_chunk) = chunks.iter_mut().find(|chunk| chunk.id == *b"fmt ") else {
+        anyhow::bail!("no fmt chunk: {}", path.display());
+    };
+    let mut payload = Vec::with_capacity(40);
+    payload.extend_from_slice(&WAVE_FORMAT_EXTENSIBLE.to_le_bytes());
+    payload.extend_from_slice(&fmt[2..16]);
+    payload.extend_from_slice(&22u16.to_le_bytes());
+    payload.extend_from_slice(&fmt[14..16]); // wValidBitsPerSample
+    payload.extend_from_slice(&layout.mask().to_le_bytes());
+    payload.extend_from_slice(&format_tag.to_le_bytes());
+    payload.extend_from_slice(&WAVE_SUBFORMAT_GUID_TAIL);
+    fmt_chunk.payload = payload;
+    encode_riff_wave_chunks(path, &chunks)
+}
+
 fn chunk_is_fresh_audio_core(chunk: &RiffWaveChunk) -> bool {
     chunk.id == *b"fmt " || chunk.id == *b"data" || chunk.id == *b"fact"
 }
@@ -1558,18 +2884,38 @@ fn take_matching_chunk(chunks: &mut Vec<RiffWaveChunk>, id: [u8; 4]) -> Option<R
     Some(chunks.remove(idx))
 }
 
+/// Source chunks come from the streaming walker so RF64/BW64 sources (ADM
+/// `axml`/`chna`, `bext`, ...) carry over too; the result is plain RIFF.
 fn merge_wav_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
-    let source_chunks = parse_riff_wave_chunks(src)?;
+    let source_chunks: Vec<RiffWaveChunk> =
+        crate::wav_stream::read_wave_metadata_chunks(src, u32::MAX as u64)?
+            .with_context(|| format!("not a RIFF/WAVE file: {}", src.display()))?
+            .into_iter()
+            .map(|(id, payload)| RiffWaveChunk { id, payload })
+            .collect();
     let mut fresh_chunks = parse_riff_wave_chunks(dst)?;
+    let fmt_rate = |chunks: &[RiffWaveChunk]| {
+        chunks
+            .iter()
+            .find(|c| &c.id == b"fmt ")
+            .and_then(|c| c.payload.get(4..8))
+            .map(|raw| u32::from_le_bytes(raw.try_into().unwrap()))
+            .unwrap_or(0)
+    };
+    let (source_rate, fresh_rate) = (fmt_rate(&source_chunks), fmt_rate(&fresh_chunks));
     let mut merged = Vec::new();
 
-    for chunk in source_chunks {
+    for mut chunk in source_chunks {
         if chunk_is_fresh_audio_core(&chunk) {
             if let Some(replacement) = take_matching_chunk(&mut fresh_chunks, chunk.id) {
                 merged.push(replacement);
             }
             continue;
         }
+        if &chunk.id == b"cart" {
+            // Post-timers count samples; keep them on the same audio.
+            rescale_cart_timers(&mut chunk.payload, source_rate, fresh_rate);
+        }
         merged.push(chunk);
     }
 
@@ -1594,10 +2940,14 @@ fn copy_mp3_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
 }
 
 fn copy_m4a_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
-    let tag = match mp4ameta::Tag::read_from_path(src) {
+    let mut tag = match mp4ameta::Tag::read_from_path(src) {
         Ok(tag) => tag,
         Err(_) => return Ok(()),
     };
+    // The source's iTunSMPB describes the source encode; keep the one the
+    // exporter wrote for the new stream.
+    let own_gapless = crate::gapless::read_m4a_gapless(dst);
+    crate::gapless::set_m4a_gapless_tag(&mut tag, own_gapless.as_ref());
     tag.write_to_path(dst)
         .with_context(|| format!("copy m4a tags {} -> {}", src.display(), dst.display()))?;
     Ok(())
@@ -1610,8 +2960,15 @@ pub fn copy_audio_metadata_from_source(src: &Path, dst: &Path) -> Result<()> {
         (Some("wav"), Some("wav")) => merge_wav_metadata_from_source(src, dst),
         (Some("mp3"), Some("mp3")) => copy_mp3_metadata_from_source(src, dst),
         (Some("m4a"), Some("m4a")) => copy_m4a_metadata_from_source(src, dst),
+        (Some("w64"), Some("w64")) => crate::w64::copy_w64_metadata_from_source(src, dst),
+        (Some("caf"), Some("caf")) => crate::caf::copy_caf_metadata_from_source(src, dst),
         (Some("flac"), Some("flac")) => crate::flac_meta::copy_flac_metadata_from_source(src, dst),
-        _ => Ok(()),
+        (Some("ogg"), Some("ogg")) | (Some("opus"), Some("opus")) => {
+            crate::ogg_meta::copy_ogg_comments_from_source(src, dst)
+        }
+        // Converting between tag formats (e.g. MP3 -> M4A): carry the text
+        // tags through the cross-format model.
+        _ => crate::tags::copy_tags_across_formats(src, dst),
     }
 }
 
@@ -2699,12 +4056,13 @@ pub fn export_gain_wav(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
             // Symmetric scaling: -1.0 -> -2^(bits-1), +1.0 clamps to
             // 2^(bits-1) - 1. f64 keeps 32-bit quantization exact.
             let scale = (1u64 << (spec.bits_per_sample - 1)) as f64;
-            let mut quant = Quantizer::new(
+            let mut quant = Quantizer::for_export(
                 scale,
                 -scale,
                 scale - 1.0,
                 spec.channels as usize,
-                Quantizer::export_mode_for_bits(spec.bits_per_sample as u32),
+                spec.bits_per_sample as u32,
+                spec.sample_rate,
             );
             for i in 0..frames {
                 for ch in 0..(spec.channels as usize) {
@@ -2726,10 +4084,12 @@ pub fn export_gain_audio(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     match fmt.as_str() {
         "wav" => export_gain_wav(src, dst, gain_db),
         "aiff" | "aif" => export_gain_aiff(src, dst, gain_db),
+        "w64" | "caf" => export_gain_pcm_container(src, dst, gain_db),
         "flac" => export_gain_flac(src, dst, gain_db),
         "mp3" => export_gain_mp3(src, dst, gain_db),
         "m4a" => export_gain_m4a(src, dst, gain_db),
         "ogg" => export_gain_ogg(src, dst, gain_db),
+        "opus" => export_gain_opus(src, dst, gain_db),
         _ => anyhow::bail!("unsupported format: {}", fmt),
     }
 }
@@ -2740,6 +4100,26 @@ fn export_gain_aiff(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     write_aiff_with_depth(&chans, in_sr, dst, WavBitDepth::Float32)
 }
 
+/// W64 / CAF: keep the source's integer depth (16 or 24-bit), otherwise
+/// float, and its speaker layout.
+fn export_gain_pcm_container(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
+    let (mut chans, in_sr) = decode_wav_multi(src)?;
+    apply_gain_in_place(&mut chans, gain_db);
+    let info = audio_io::read_audio_info(src).ok();
+    let depth = match info
+        .as_ref()
+        .map(|info| (info.bits_per_sample, info.sample_value_kind))
+    {
+        Some((16, audio_io::SampleValueKind::Int)) => WavBitDepth::Pcm16,
+        Some((24, audio_io::SampleValueKind::Int)) => WavBitDepth::Pcm24,
+        _ => WavBitDepth::Float32,
+    };
+    let layout = info.map(|info| info.channel_layout);
+    export_channels_audio_with_layout(&chans, in_sr, dst, Some(depth), layout)?;
+    try_copy_audio_metadata_from_source(src, dst);
+    Ok(())
+}
+
 fn export_gain_flac(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     let (mut chans, in_sr) = decode_wav_multi(src)?;
     apply_gain_in_place(&mut chans, gain_db);
@@ -2761,7 +4141,7 @@ fn export_gain_mp3(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     use std::fs;
     let (mut chans, in_sr) = decode_wav_multi(src)?;
     apply_gain_in_place(&mut chans, gain_db);
-    let data = encode_mp3(&chans, in_sr)?;
+    let data = encode_mp3(&chans, in_sr, audio_io::read_channel_layout(src))?;
     fs::write(dst, data)?;
     try_copy_audio_metadata_from_source(src, dst);
     Ok(())
@@ -2770,7 +4150,7 @@ fn export_gain_mp3(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
 fn export_gain_m4a(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     let (mut chans, in_sr) = decode_wav_multi(src)?;
     apply_gain_in_place(&mut chans, gain_db);
-    encode_aac_to_mp4(dst, &chans, in_sr)?;
+    encode_aac_to_mp4(dst, &chans, in_sr, audio_io::read_channel_layout(src))?;
     try_copy_audio_metadata_from_source(src, dst);
     Ok(())
 }
@@ -2778,25 +4158,83 @@ fn export_gain_m4a(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
 fn export_gain_ogg(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
     let (mut chans, in_sr) = decode_wav_multi(src)?;
     apply_gain_in_place(&mut chans, gain_db);
-    encode_ogg_vorbis(dst, &chans, in_sr)
+    encode_ogg_vorbis(dst, &chans, in_sr, audio_io::read_channel_layout(src))?;
+    try_copy_audio_metadata_from_source(src, dst);
+    Ok(())
+}
+
+fn export_gain_opus(src: &Path, dst: &Path, gain_db: f32) -> Result<()> {
+    let (mut chans, in_sr) = decode_wav_multi(src)?;
+    apply_gain_in_place(&mut chans, gain_db);
+    encode_ogg_opus(dst, &chans, in_sr, audio_io::read_channel_layout(src))?;
+    try_copy_audio_metadata_from_source(src, dst);
+    Ok(())
+}
+
+/// How exports fold a channel layout the target codec can't carry down to
+/// stereo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DownmixMode {
+    /// ITU-R BS.775 fold-down: centre and surrounds at -3 dB, LFE dropped.
+    /// The result is scaled down only if it would clip.
+    #[default]
+    Itu,
+    /// Keep channels 1 and 2 and discard the rest.
+    FirstTwo,
+}
+
+impl DownmixMode {
+    pub fn prefs_name(self) -> &'static str {
+        match self {
+            DownmixMode::Itu => "itu",
+            DownmixMode::FirstTwo => "first2",
+        }
+    }
+
+    pub fn from_prefs_name(s: &str) -> Option<DownmixMode> {
+        match s.trim() {
+            "itu" => Some(DownmixMode::Itu),
+            "first2" => Some(DownmixMode::FirstTwo),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DownmixMode::Itu => "ITU downmix",
+            DownmixMode::FirstTwo => "First 2 channels",
+        }
+    }
 }
 
 /// App-wide lossy-encoder settings. Exports run on worker threads far from the
 /// UI config, so the active settings are published here before spawning jobs;
 /// encoders read them at encode time. Defaults match the previous hardcoded
-/// values (MP3 192 kbps, AAC 192/96 kbps stereo/mono, Vorbis library default).
+/// values (MP3 192 kbps, AAC 192/96 kbps stereo/mono, Vorbis library default);
+/// Opus defaults to 128 kbps stereo at the highest complexity.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct CodecExportOptions {
     pub mp3_bitrate_kbps: u32,
     pub aac_bitrate_kbps: u32,
     /// Vorbis perceptual quality in [-0.2, 1.0].
     pub ogg_quality: f32,
+    /// Opus bitrate for a stereo stream (mono uses half, surround scales
+    /// with the number of coded channel pairs).
+    pub opus_bitrate_kbps: u32,
+    /// libopus encoder complexity in [0, 10].
+    pub opus_complexity: u8,
     /// Dither applied when quantizing to 16-bit integer PCM (WAV/AIFF/FLAC/
     /// export-gain writer). Float paths are never dithered.
     pub dither_mode: DitherMode,
     /// Also dither 24-bit integer exports (same mode). Off by default: at
     /// 24-bit the quantization floor is already below any analog chain.
     pub dither_24bit: bool,
+    /// Write digital silence as true zeros instead of dither noise
+    /// (see [`Quantizer::with_auto_blank`]).
+    pub dither_auto_blank: bool,
+    /// Fold-down used when a layout exceeds what the target codec carries
+    /// (MP3 is stereo-only; AAC takes up to 5.1 and 7.1; Vorbis/Opus up to 8).
+    pub downmix: DownmixMode,
 }
 
 impl Default for CodecExportOptions {
@@ -2805,8 +4243,12 @@ impl Default for CodecExportOptions {
             mp3_bitrate_kbps: 192,
             aac_bitrate_kbps: 192,
             ogg_quality: 0.5,
+            opus_bitrate_kbps: 128,
+            opus_complexity: 10,
             dither_mode: DitherMode::Tpdf,
             dither_24bit: false,
+            dither_auto_blank: false,
+            downmix: DownmixMode::Itu,
         }
     }
 }
@@ -2860,18 +4302,102 @@ fn ext_lower(path: &Path) -> Option<String> {
         .map(|s| s.to_ascii_lowercase())
 }
 
-fn normalize_channels_for_encode(chans: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    if chans.len() > 2 {
-        eprintln!(
-            "lossy export: keeping the first 2 of {} channels (mp3/m4a/ogg are stereo-only)",
-            chans.len()
-        );
+/// Passes `chans` through when `fits` accepts the channel count, otherwise
+/// folds it to stereo with the configured [`DownmixMode`], positioning each
+/// channel by its speaker in `layout`.
+fn normalize_channels_for_encode(
+    chans: &[Vec<f32>],
+    layout: Option<ChannelLayout>,
+    codec: &str,
+    fits: impl Fn(usize) -> bool,
+) -> Vec<Vec<f32>> {
+    if chans.is_empty() || fits(chans.len()) {
+        return chans.to_vec();
     }
-    match chans.len() {
-        0 => Vec::new(),
-        1 => vec![chans[0].clone()],
-        _ => vec![chans[0].clone(), chans[1].clone()],
+    let mode = codec_export_options().downmix;
+    eprintln!(
+        "{codec} export: {} channels don't fit the codec, applying {}",
+        chans.len(),
+        mode.label()
+    );
+    downmix_to_stereo(chans, mode, layout)
+}
+
+/// Per-channel (left, right) gains for an ITU-R BS.775 fold-down, from each
+/// channel's speaker in `layout`, or in the default WAV layout for the
+/// channel count when none is declared. Unpositioned channels alternate L/R.
+fn itu_downmix_gains(channels: usize, layout: Option<ChannelLayout>) -> Vec<(f32, f32)> {
+    use crate::channel_layout::*;
+    const K: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    const L: (f32, f32) = (1.0, 0.0);
+    const R: (f32, f32) = (0.0, 1.0);
+    const C: (f32, f32) = (K, K);
+    const LFE: (f32, f32) = (0.0, 0.0);
+    const SL: (f32, f32) = (K, 0.0);
+    const SR: (f32, f32) = (0.0, K);
+    const SPLIT: (f32, f32) = (0.5, 0.5);
+    // Top front/back left and right (WAVE_FORMAT_EXTENSIBLE height bits).
+    const TOP_LEFT: u32 = 0x1000 | 0x8000;
+    const TOP_RIGHT: u32 = 0x4000 | 0x20000;
+    if channels == 1 {
+        return vec![(1.0, 1.0)];
+    }
+    let count = channels.min(u16::MAX as usize) as u16;
+    let layout = layout
+        .filter(|layout| layout.channels() == count)
+        .unwrap_or_else(|| ChannelLayout::default_for(count));
+    (0..channels)
+        .map(|ch| match layout.speaker_bit(ch) {
+            Some(SPEAKER_FRONT_LEFT | SPEAKER_FRONT_LEFT_OF_CENTER) => L,
+            Some(SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_RIGHT_OF_CENTER) => R,
+            Some(SPEAKER_FRONT_CENTER) => C,
+            Some(SPEAKER_LOW_FREQUENCY) => LFE,
+            Some(SPEAKER_BACK_LEFT | SPEAKER_SIDE_LEFT) => SL,
+            Some(SPEAKER_BACK_RIGHT | SPEAKER_SIDE_RIGHT) => SR,
+            Some(bit) if bit & TOP_LEFT != 0 => SL,
+            Some(bit) if bit & TOP_RIGHT != 0 => SR,
+            // Back centre and the top centres feed both sides.
+            Some(_) => SPLIT,
+            None if ch % 2 == 0 => L,
+            None => R,
+        })
+        .collect()
+}
+
+/// Folds any channel count down to stereo (mono passes through). `layout`
+/// places the channels for the ITU fold-down; see [`itu_downmix_gains`].
+pub fn downmix_to_stereo(
+    chans: &[Vec<f32>],
+    mode: DownmixMode,
+    layout: Option<ChannelLayout>,
+) -> Vec<Vec<f32>> {
+    if chans.len() <= 2 {
+        return chans.to_vec();
+    }
+    if mode == DownmixMode::FirstTwo {
+        return vec![chans[0].clone(), chans[1].clone()];
     }
+    let frames = chans.iter().map(|c| c.len()).min().unwrap_or(0);
+    let gains = itu_downmix_gains(chans.len(), layout);
+    let mut left = vec![0.0f32; frames];
+    let mut right = vec![0.0f32; frames];
+    for (ch, &(gl, gr)) in chans.iter().zip(gains.iter()) {
+        for (i, &v) in ch[..frames].iter().enumerate() {
+            left[i] += v * gl;
+            right[i] += v * gr;
+        }
+    }
+    let peak = left
+        .iter()
+        .chain(right.iter())
+        .fold(0.0f32, |m, v| m.max(v.abs()));
+    if peak > 1.0 {
+        let g = 1.0 / peak;
+        for v in left.iter_mut().chain(right.iter_mut()) {
+            *v *= g;
+        }
+    }
+    vec![left, right]
 }
 
 fn apply_gain_in_place(chans: &mut [Vec<f32>], gain_db: f32) {
@@ -2890,11 +4416,21 @@ fn resample_channels(chans: &[Vec<f32>], in_sr: u32, out_sr: u32) -> Vec<Vec<f32
     resample_channels_quality(chans, in_sr, out_sr, ResampleQuality::Good)
 }
 
-fn encode_ogg_vorbis(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
-    let chans = normalize_channels_for_encode(chans);
+/// Encode to Ogg Vorbis. Up to 8 channels are kept; Vorbis mapping family 1
+/// orders them differently from WAV, so they are permuted before encoding.
+fn encode_ogg_vorbis(
+    dst: &Path,
+    chans: &[Vec<f32>],
+    in_sr: u32,
+    layout: Option<ChannelLayout>,
+) -> Result<()> {
+    let mut chans = normalize_channels_for_encode(chans, layout, "ogg", |n| n <= 8);
     if chans.is_empty() {
         anyhow::bail!("empty channels");
     }
+    if let Some(order) = crate::opus_codec::vorbis_order_from_wav(chans.len()) {
+        chans = order.iter().map(|&i| chans[i].clone()).collect();
+    }
     let sample_rate = NonZeroU32::new(in_sr.max(1))
         .ok_or_else(|| anyhow::anyhow!("invalid sample rate for ogg encode"))?;
     let channels = NonZeroU8::new(chans.len().min(u8::MAX as usize) as u8)
@@ -2931,6 +4467,35 @@ fn encode_ogg_vorbis(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
     Ok(())
 }
 
+/// Encode to Ogg Opus. Opus only runs at 48 kHz, so other rates are
+/// resampled first; the original rate is kept in `OpusHead` for reference.
+/// Up to 8 channels are encoded (mapping family 1 above stereo); wider
+/// layouts are downmixed.
+fn encode_ogg_opus(
+    dst: &Path,
+    chans: &[Vec<f32>],
+    in_sr: u32,
+    layout: Option<ChannelLayout>,
+) -> Result<()> {
+    if chans.is_empty() {
+        anyhow::bail!("empty channels");
+    }
+    let chans = normalize_channels_for_encode(chans, layout, "opus", |n| {
+        n <= crate::opus_codec::OPUS_MAX_CHANNELS
+    });
+    let chans_48k = resample_channels(&chans, in_sr.max(1), crate::opus_codec::OPUS_SAMPLE_RATE);
+    let opts = codec_export_options();
+    crate::opus_codec::encode_ogg_opus(
+        dst,
+        &chans_48k,
+        in_sr,
+        crate::opus_codec::OpusEncodeSettings {
+            bitrate_kbps: opts.opus_bitrate_kbps,
+            complexity: opts.opus_complexity,
+        },
+    )
+}
+
 /// Encode to FLAC. FLAC stores integers only, so `Float32` (and unspecified)
 /// depths are written as 24-bit PCM; `Pcm16` stays 16-bit. All channel counts
 /// FLAC supports (up to 8) pass through unchanged.
@@ -2967,6 +4532,7 @@ fn encode_flac(
     // stored in STREAMINFO), so each pass creates its own generator from the
     // same seed and walks the samples in the same order.
     let flac_dither_mode = Quantizer::export_mode_for_bits(bits_per_sample as u32);
+    let flac_auto_blank = codec_export_options().dither_auto_blank;
     let make_quantizer = || {
         Quantizer::new(
             f64::from(max_abs),
@@ -2975,6 +4541,8 @@ fn encode_flac(
             channels,
             flac_dither_mode,
         )
+        .with_sample_rate(sample_rate)
+        .with_auto_blank(flac_auto_blank)
     };
     let config = flacenc::config::Encoder::default()
         .into_verified()
@@ -3064,11 +4632,11 @@ fn encode_flac(
     Ok(())
 }
 
-fn encode_mp3(chans: &[Vec<f32>], in_sr: u32) -> Result<Vec<u8>> {
+fn encode_mp3(chans: &[Vec<f32>], in_sr: u32, layout: Option<ChannelLayout>) -> Result<Vec<u8>> {
     if chans.is_empty() {
         anyhow::bail!("empty channels");
     }
-    let mut chans = normalize_channels_for_encode(chans);
+    let mut chans = normalize_channels_for_encode(chans, layout, "mp3", |n| n <= 2);
     let mut sr = in_sr;
     let mut builder = Mp3Builder::new().context("init mp3 encoder")?;
     builder
@@ -3117,15 +4685,149 @@ fn encode_mp3(chans: &[Vec<f32>], in_sr: u32) -> Result<Vec<u8>> {
     encoder
         .flush_to_vec::<FlushNoGap>(&mut out)
         .map_err(|e| anyhow::anyhow!("mp3 flush: {e:?}"))?;
-    Ok(out)
+    let coded_frames = chans.iter().map(|c| c.len()).min().unwrap_or(0) as u64;
+    Ok(crate::gapless::apply_mp3_gapless_info(
+        out,
+        coded_frames,
+        sr,
+        crate::gapless::LAME_ENCODER_DELAY,
+    ))
 }
 
-fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
+struct AacFrameInfo {
+    frame_len: usize,
+    max_out: usize,
+    delay: u32,
+}
+
+/// Raw AAC-LC frame encoder: the `fdk-aac` wrapper for mono/stereo, the raw
+/// library API for surround layouts.
+enum AacFrameEncoder {
+    Stereo(AacEncoder),
+    Multichannel(crate::aac_enc::MultichannelAacEncoder),
+}
+
+impl AacFrameEncoder {
+    fn new(sample_rate: u32, channels: usize, bitrate: u32) -> Result<Self> {
+        if channels > 2 {
+            return Ok(Self::Multichannel(
+                crate::aac_enc::MultichannelAacEncoder::new(sample_rate, channels, bitrate)?,
+            ));
+        }
+        let params = AacEncoderParams {
+            bit_rate: AacBitRate::Cbr(bitrate),
+            sample_rate,
+            transport: AacTransport::Raw,
+            channels: if channels == 1 {
+                AacChannelMode::Mono
+            } else {
+                AacChannelMode::Stereo
+            },
+            audio_object_type: FdkAudioObjectType::Mpeg4LowComplexity,
+        };
+        let encoder =
+            AacEncoder::new(params).map_err(|e| anyhow::anyhow!("aac encoder init: {e}"))?;
+        Ok(Self::Stereo(encoder))
+    }
+
+    fn info(&self) -> Result<AacFrameInfo> {
+        let (frame_len, max_out, delay) = match self {
+            Self::Stereo(encoder) => {
+                let info = encoder
+                    .info()
+                    .map_err(|e| anyhow::anyhow!("aac encoder info: {e}"))?;
+                (info.frameLength, info.maxOutBufBytes, info.nDelay)
+            }
+            Self::Multichannel(encoder) => {
+                let info = encoder.info()?;
+                (info.frameLength, info.maxOutBufBytes, info.nDelay)
+            }
+        };
+        Ok(AacFrameInfo {
+            frame_len: frame_len as usize,
+            max_out: max_out as usize,
+            delay,
+        })
+    }
+
+    /// Returns (input samples consumed, output bytes written); an empty
+    /// input flushes.
+    fn encode(&mut self, input: &[i16], output: &mut [u8]) -> Result<(usize, usize)> {
+        match self {
+            Self::Stereo(encoder) => {
+                let info = encoder
+                    .encode(input, output)
+                    .map_err(|e| anyhow::anyhow!("aac encode: {e}"))?;
+                Ok((info.input_consumed, info.output_size))
+            }
+            Self::Multichannel(encoder) => encoder.encode(input, output),
+        }
+    }
+}
+
+/// Fold a 7.1 source to 5.1 by speaker position: front-of-centre pairs join
+/// the front pair, side and back pairs share the surround pair and a back
+/// centre splits across it. Outputs fed by several speakers are scaled by
+/// 1/sqrt(n) to keep the power of uncorrelated content.
+fn fold_7_1_to_5_1(chans: &[Vec<f32>], layout: ChannelLayout) -> Vec<Vec<f32>> {
+    use crate::channel_layout::*;
+    const K: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    // Positions of the default 7.1 layout, for channels without a speaker bit.
+    const DEFAULT_OUT: [usize; 8] = [0, 1, 2, 3, 4, 5, 4, 5];
+    let routes: Vec<Vec<(usize, f32)>> = (0..chans.len())
+        .map(|ch| match layout.speaker_bit(ch) {
+            Some(SPEAKER_FRONT_LEFT | SPEAKER_FRONT_LEFT_OF_CENTER) => vec![(0, 1.0)],
+            Some(SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_RIGHT_OF_CENTER) => vec![(1, 1.0)],
+            Some(SPEAKER_FRONT_CENTER) => vec![(2, 1.0)],
+            Some(SPEAKER_LOW_FREQUENCY) => vec![(3, 1.0)],
+            Some(SPEAKER_BACK_LEFT | SPEAKER_SIDE_LEFT) => vec![(4, 1.0)],
+            Some(SPEAKER_BACK_RIGHT | SPEAKER_SIDE_RIGHT) => vec![(5, 1.0)],
+            Some(SPEAKER_BACK_CENTER) => vec![(4, K), (5, K)],
+            _ => vec![(DEFAULT_OUT[ch.min(7)], 1.0)],
+        })
+        .collect();
+    let mut feeds = [0usize; 6];
+    for &(out, _) in routes.iter().flatten() {
+        feeds[out] += 1;
+    }
+    let len = chans.iter().map(|c| c.len()).min().unwrap_or(0);
+    let mut out = vec![vec![0.0f32; len]; 6];
+    for (src, route) in chans.iter().zip(&routes) {
+        for &(dst, gain) in route {
+            let gain = gain / (feeds[dst].max(1) as f32).sqrt();
+            for (o, &x) in out[dst].iter_mut().zip(&src[..len]) {
+                *o += x * gain;
+            }
+        }
+    }
+    out
+}
+
+fn encode_aac_to_mp4(
+    dst: &Path,
+    chans: &[Vec<f32>],
+    in_sr: u32,
+    layout: Option<ChannelLayout>,
+) -> Result<()> {
     use std::fs::File;
     if chans.is_empty() {
         anyhow::bail!("empty channels");
     }
-    let mut chans = normalize_channels_for_encode(chans);
+    let mut chans = normalize_channels_for_encode(chans, layout, "m4a", |n| {
+        n <= 2 || crate::aac_enc::supports_channels(n)
+    });
+    if chans.len() == 8 {
+        let layout = layout
+            .filter(|l| l.channels() == 8)
+            .unwrap_or_else(|| ChannelLayout::default_for(8));
+        if layout.mask() != crate::aac_enc::FRONT_WIDE_7_1_MASK {
+            eprintln!(
+                "m4a export: AAC in MP4 can only signal front-wide 7.1, folding {} to 5.1",
+                layout.describe()
+            );
+            chans = fold_7_1_to_5_1(&chans, layout);
+        }
+    }
     let mut sr = in_sr;
     let mut freq_index = aac_freq_index(sr);
     if freq_index.is_none() {
@@ -3139,31 +4841,23 @@ fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
     let bitrate_kbps = codec_export_options().aac_bitrate_kbps.clamp(32, 320);
     // Halve for mono so the default (192 kbps stereo) keeps the previous
     // 96 kbps mono behavior.
-    let bitrate = if channels == 1 {
-        (bitrate_kbps / 2).max(32) * 1000
-    } else {
-        bitrate_kbps * 1000
-    };
-    let params = AacEncoderParams {
-        bit_rate: AacBitRate::Cbr(bitrate),
-        sample_rate: sr,
-        transport: AacTransport::Raw,
-        channels: if channels == 1 {
-            AacChannelMode::Mono
-        } else {
-            AacChannelMode::Stereo
-        },
-        audio_object_type: FdkAudioObjectType::Mpeg4LowComplexity,
+    // Surround layouts scale the stereo rate by the number of coded
+    // full-band channels (the LFE is nearly free).
+    let bitrate = match channels {
+        1 => (bitrate_kbps / 2).max(32) * 1000,
+        2 => bitrate_kbps * 1000,
+        n => {
+            let full_band = if n >= 6 { n - 1 } else { n } as u32;
+            bitrate_kbps * full_band * 1000 / 2
+        }
     };
-    let encoder = AacEncoder::new(params).map_err(|e| anyhow::anyhow!("aac encoder init: {e}"))?;
-    let info = encoder
-        .info()
-        .map_err(|e| anyhow::anyhow!("aac encoder info: {e}"))?;
-    let frame_len = info.frameLength as usize;
+    let mut encoder = AacFrameEncoder::new(sr, channels, bitrate)?;
+    let info = encoder.info()?;
+    let frame_len = info.frame_len;
     if frame_len == 0 {
         anyhow::bail!("aac frame length is zero");
     }
-    let max_out = (info.maxOutBufBytes as usize).max(4096);
+    let max_out = info.max_out.max(4096);
     let interleaved = interleave_i16(&chans);
     let frame_samples = frame_len * channels;
     let file = File::create(dst).with_context(|| format!("create m4a: {}", dst.display()))?;
@@ -3188,10 +4882,14 @@ fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
             bitrate,
             profile: Mp4AudioObjectType::AacLowComplexity,
             freq_index,
-            chan_conf: if channels == 1 {
-                ChannelConfig::Mono
-            } else {
-                ChannelConfig::Stereo
+            chan_conf: match channels {
+                1 => ChannelConfig::Mono,
+                2 => ChannelConfig::Stereo,
+                3 => ChannelConfig::Three,
+                4 => ChannelConfig::Four,
+                5 => ChannelConfig::Five,
+                6 => ChannelConfig::FiveOne,
+                _ => ChannelConfig::SevenOne,
             },
         }),
     };
@@ -3211,11 +4909,9 @@ fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
             input_slice = &padded;
         }
         let mut out_buf = vec![0u8; max_out];
-        let enc_info = encoder
-            .encode(input_slice, &mut out_buf)
-            .map_err(|e| anyhow::anyhow!("aac encode: {e}"))?;
-        if enc_info.output_size > 0 {
-            let bytes = Bytes::copy_from_slice(&out_buf[..enc_info.output_size]);
+        let (consumed, output_size) = encoder.encode(input_slice, &mut out_buf)?;
+        if output_size > 0 {
+            let bytes = Bytes::copy_from_slice(&out_buf[..output_size]);
             let sample = Mp4Sample {
                 start_time: frame_index * frame_len as u64,
                 duration: frame_len as u32,
@@ -3228,20 +4924,18 @@ fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
                 .map_err(|e| anyhow::anyhow!("mp4 write sample: {e:?}"))?;
             frame_index += 1;
         }
-        if enc_info.input_consumed == 0 {
+        if consumed == 0 {
             break;
         }
-        pos += enc_info.input_consumed;
+        pos += consumed;
     }
     loop {
         let mut out_buf = vec![0u8; max_out];
-        let enc_info = encoder
-            .encode(&[], &mut out_buf)
-            .map_err(|e| anyhow::anyhow!("aac flush: {e}"))?;
-        if enc_info.output_size == 0 {
+        let (_, output_size) = encoder.encode(&[], &mut out_buf)?;
+        if output_size == 0 {
             break;
         }
-        let bytes = Bytes::copy_from_slice(&out_buf[..enc_info.output_size]);
+        let bytes = Bytes::copy_from_slice(&out_buf[..output_size]);
         let sample = Mp4Sample {
             start_time: frame_index * frame_len as u64,
             duration: frame_len as u32,
@@ -3257,6 +4951,15 @@ fn encode_aac_to_mp4(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()> {
     writer
         .write_end()
         .map_err(|e| anyhow::anyhow!("mp4 finalize: {e:?}"))?;
+    let source_frames = (interleaved.len() / channels) as u64;
+    let priming = info.delay;
+    let coded_frames = frame_index * frame_len as u64;
+    let gapless = crate::gapless::GaplessInfo {
+        priming,
+        padding: coded_frames.saturating_sub(u64::from(priming) + source_frames) as u32,
+        valid_frames: Some(source_frames),
+    };
+    crate::gapless::write_m4a_gapless(dst, Some(&gapless))?;
     // Some mp4 readers are strict about esds descriptors. Avoid replacing the mp4
     // output by default; allow optional ADTS fallback via env toggle.
     if crate::audio_io::read_audio_info(dst).is_err()
@@ -3278,7 +4981,8 @@ fn encode_aac_to_adts(dst: &Path, chans: &[Vec<f32>], in_sr: u32) -> Result<()>
     if chans.is_empty() {
         anyhow::bail!("empty channels");
     }
-    let mut chans = normalize_channels_for_encode(chans);
+    // Only reached with the already channel-checked M4A input.
+    let mut chans = normalize_channels_for_encode(chans, None, "aac", |n| n <= 2);
     let mut sr = in_sr;
     if aac_freq_index(sr).is_none() {
         let target = 48_000;
@@ -3418,7 +5122,7 @@ pub fn export_selection_wav_with_depth(
     )
 }
 
-// Export full in-memory audio to a supported format (wav/mp3/m4a) based on dst extension.
+// Export full in-memory audio to a supported format based on dst extension.
 pub fn export_channels_audio(chans: &[Vec<f32>], sample_rate: u32, dst: &Path) -> Result<()> {
     crate::app::watch::note_self_write(dst);
     export_channels_audio_with_depth(chans, sample_rate, dst, None)
@@ -3429,6 +5133,21 @@ pub fn export_channels_audio_with_depth(
     sample_rate: u32,
     dst: &Path,
     wav_depth: Option<WavBitDepth>,
+) -> Result<()> {
+    export_channels_audio_with_layout(chans, sample_rate, dst, wav_depth, None)
+}
+
+/// Like [`export_channels_audio_with_depth`], with the speaker layout WAV,
+/// W64 and CAF output should declare. `None` (or a layout for another
+/// channel count) writes the default layout for the channel count. M4A uses
+/// it to tell front-wide 7.1 from layouts it has to fold to 5.1; other
+/// containers use their own fixed channel order and ignore it.
+pub fn export_channels_audio_with_layout(
+    chans: &[Vec<f32>],
+    sample_rate: u32,
+    dst: &Path,
+    wav_depth: Option<WavBitDepth>,
+    layout: Option<ChannelLayout>,
 ) -> Result<()> {
     // A zero-channel buffer (e.g. from a failed decode) must fail here with a
     // real error — hound panics on a zero block align otherwise.
@@ -3444,7 +5163,30 @@ pub fn export_channels_audio_with_depth(
     match ext.as_str() {
         "wav" => {
             let len = chans.first().map(|c| c.len()).unwrap_or(0);
-            export_selection_wav_with_depth(chans, sample_rate, (0, len), dst, wav_depth)
+            export_selection_wav_with_depth(chans, sample_rate, (0, len), dst, wav_depth)?;
+            let channels = chans.len().min(u16::MAX as usize) as u16;
+            let layout = layout
+                .filter(|layout| layout.channels() == channels)
+                .unwrap_or_else(|| ChannelLayout::default_for(channels));
+            write_wav_channel_layout(dst, layout)
+        }
+        "w64" => {
+            let tmp = unique_sibling_tmp(dst, "w64", "wav");
+            let res =
+                export_channels_audio_with_layout(chans, sample_rate, &tmp, wav_depth, layout)
+                    .and_then(|()| crate::w64::write_w64_from_wave(&tmp, dst));
+            let _ = std::fs::remove_file(&tmp);
+            res
+        }
+        "caf" => {
+            let channels = chans.len().min(u16::MAX as usize) as u16;
+            crate::caf::write_caf(
+                chans,
+                sample_rate,
+                dst,
+                wav_depth.unwrap_or(WavBitDepth::Float32),
+                layout.unwrap_or_else(|| ChannelLayout::default_for(channels)),
+            )
         }
         "aiff" | "aif" => write_aiff_with_depth(
             chans,
@@ -3454,12 +5196,13 @@ pub fn export_channels_audio_with_depth(
         ),
         "flac" => encode_flac(dst, chans, sample_rate, wav_depth),
         "mp3" => {
-            let data = encode_mp3(chans, sample_rate)?;
+            let data = encode_mp3(chans, sample_rate, layout)?;
             std::fs::write(dst, data)?;
             Ok(())
         }
-        "m4a" => encode_aac_to_mp4(dst, chans, sample_rate),
-        "ogg" => encode_ogg_vorbis(dst, chans, sample_rate),
+        "m4a" => encode_aac_to_mp4(dst, chans, sample_rate, layout),
+        "ogg" => encode_ogg_vorbis(dst, chans, sample_rate, layout),
+        "opus" => encode_ogg_opus(dst, chans, sample_rate, layout),
         _ => anyhow::bail!("unsupported export format: {}", ext),
     }
 }
@@ -3471,12 +5214,12 @@ pub fn overwrite_audio_from_channels(
     src: &Path,
     backup: bool,
 ) -> Result<()> {
-    overwrite_audio_from_channels_with_depth(chans, sample_rate, src, backup, None)
+    overwrite_audio_from_channels_with_depth(chans, sample_rate, src, backup, None, None)
 }
 
 /// Allocate a unique temp path next to `src` so concurrent operations in the
 /// same directory never collide on a shared temp name.
-fn unique_sibling_tmp(src: &Path, tag: &str, ext: &str) -> std::path::PathBuf {
+pub(crate) fn unique_sibling_tmp(src: &Path, tag: &str, ext: &str) -> std::path::PathBuf {
     use std::sync::atomic::{AtomicU64, Ordering};
     static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
     let parent = src.parent().unwrap_or_else(|| Path::new("."));
@@ -3526,7 +5269,7 @@ fn replace_file_atomic_win(tmp: &Path, src: &Path) -> std::io::Result<()> {
 
 /// Replace `src` with the finished `tmp`, never leaving a window where the
 /// original is deleted and unrecoverable. Optionally keeps `<name>.bak`.
-fn replace_file_with_tmp(tmp: &Path, src: &Path, backup: bool) -> Result<()> {
+pub(crate) fn replace_file_with_tmp(tmp: &Path, src: &Path, backup: bool) -> Result<()> {
     crate::app::watch::note_self_write(src);
     use std::fs;
     if backup {
@@ -3566,16 +5309,20 @@ fn replace_file_with_tmp(tmp: &Path, src: &Path, backup: bool) -> Result<()> {
     }
 }
 
+/// `layout` is the speaker layout to declare; `None` keeps the one the file
+/// already declares when the channel count is unchanged.
 pub fn overwrite_audio_from_channels_with_depth(
     chans: &[Vec<f32>],
     sample_rate: u32,
     src: &Path,
     backup: bool,
     wav_depth: Option<WavBitDepth>,
+    layout: Option<ChannelLayout>,
 ) -> Result<()> {
     let ext = src.extension().and_then(|s| s.to_str()).unwrap_or("tmp");
     let tmp = unique_sibling_tmp(src, "ow", ext);
-    export_channels_audio_with_depth(chans, sample_rate, &tmp, wav_depth)?;
+    let layout = layout.or_else(|| crate::audio_io::read_channel_layout(src));
+    export_channels_audio_with_layout(chans, sample_rate, &tmp, wav_depth, layout)?;
     try_copy_audio_metadata_from_source(src, &tmp);
     replace_file_with_tmp(&tmp, src, backup)
 }
@@ -3673,43 +5420,212 @@ fn block_means_power(power: &[f32], win: usize, hop: usize) -> Vec<f64> {
     out
 }
 
-/// BS.1770-4 channel power weights. Channel identities are not available
-/// from the decoder here, so common film-order layouts are assumed:
-/// 5.1 = L R C LFE Ls Rs and 7.1 = L R C LFE Ls Rs Lrs Rrs (LFE excluded,
-/// surrounds x1.41). Every other channel count uses weight 1.0.
-fn bs1770_channel_weights(channels: usize) -> Vec<f32> {
-    match channels {
-        6 => vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41],
-        8 => vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41, 1.41, 1.41],
-        n => vec![1.0; n],
+/// BS.1770-4 channel power weights for `channels` channels laid out as
+/// `layout` (see [`ChannelLayout::bs1770_weight`]). A layout for a different
+/// channel count falls back to the default layout for `channels`.
+fn bs1770_channel_weights(layout: ChannelLayout, channels: usize) -> Vec<f32> {
+    let layout = if layout.channels() as usize == channels {
+        layout
+    } else {
+        ChannelLayout::default_for(channels.min(u16::MAX as usize) as u16)
+    };
+    layout.bs1770_weights()
+}
+
+/// Resample to 48 kHz, K-weight, and sum the per-sample power across
+/// channels with the BS.1770 G weights of `layout`.
+fn weighted_power_48k(chans_in: &[Vec<f32>], in_sr: u32, layout: ChannelLayout) -> Vec<f32> {
+    let (mut chans, _) = ensure_sr_48k(chans_in, in_sr);
+    k_weighting_apply_48k(&mut chans);
+    let weights = bs1770_channel_weights(layout, chans.len());
+    let n = chans[0].len();
+    let mut p_sum = vec![0.0f32; n];
+    for (ch, &w) in chans.iter().zip(weights.iter()) {
+        if w == 0.0 {
+            continue;
+        }
+        for i in 0..n {
+            let v = ch[i];
+            p_sum[i] += w * v * v;
+        }
     }
+    p_sum
+}
+
+/// Layout assumed when the caller has none: the `KSAUDIO_SPEAKER_*` default
+/// for the channel count.
+fn default_loudness_layout(chans: &[Vec<f32>]) -> ChannelLayout {
+    ChannelLayout::default_for(chans.len().min(u16::MAX as usize) as u16)
 }
 
 fn power_to_lufs(z: f64) -> f32 {
     K_CONST + 10.0 * (z.max(1e-24)).log10() as f32
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct LoudnessMetrics {
-    /// Integrated loudness (gated per BS.1770-4).
-    pub lufs_i: f32,
-    /// Maximum momentary loudness (400 ms window, ungated; EBU Tech 3341).
-    pub lufs_m_max: Option<f32>,
-    /// Maximum short-term loudness (3 s window, ungated; EBU Tech 3341).
-    pub lufs_s_max: Option<f32>,
-    /// True peak per BS.1770-4 Annex 2 (oversampled inter-sample peak).
-    pub true_peak_db: Option<f32>,
+#[derive(Clone, Copy, Debug)]
+pub struct LoudnessMetrics {
+    /// Integrated loudness (gated per BS.1770-4).
+    pub lufs_i: f32,
+    /// Maximum momentary loudness (400 ms window, ungated; EBU Tech 3341).
+    pub lufs_m_max: Option<f32>,
+    /// Maximum short-term loudness (3 s window, ungated; EBU Tech 3341).
+    pub lufs_s_max: Option<f32>,
+    /// Loudness range (EBU Tech 3342); `None` below one 3 s window.
+    pub lra_lu: Option<f32>,
+    /// True peak per BS.1770-4 Annex 2 (oversampled inter-sample peak).
+    pub true_peak_db: Option<f32>,
+}
+
+/// Block hop and window lengths of the momentary / short-term meters
+/// (EBU Tech 3341).
+pub const LOUDNESS_HOP_SEC: f32 = 0.1;
+pub const LOUDNESS_MOMENTARY_WINDOW_SEC: f32 = 0.4;
+pub const LOUDNESS_SHORT_TERM_WINDOW_SEC: f32 = 3.0;
+
+/// Loudness over time: one value per [`LOUDNESS_HOP_SEC`] hop. Momentary and
+/// short-term value `i` cover the window that starts at `i * hop`; true
+/// peak `i` covers the hop itself.
+#[derive(Clone, Debug)]
+pub struct LoudnessTimeline {
+    /// Rate of the analysed buffer; positions below are in its frames.
+    pub sample_rate: u32,
+    pub frames: usize,
+    pub momentary_lufs: Vec<f32>,
+    pub short_term_lufs: Vec<f32>,
+    pub true_peak_db: Vec<f32>,
+}
+
+impl LoudnessTimeline {
+    pub fn hop_frames(&self) -> f64 {
+        LOUDNESS_HOP_SEC as f64 * self.sample_rate.max(1) as f64
+    }
+
+    /// `(start, end)` frames of block `idx` for a window of `window_sec`.
+    pub fn block_range(&self, idx: usize, window_sec: f32) -> (usize, usize) {
+        let start = (idx as f64 * self.hop_frames()).round() as usize;
+        let len = (window_sec as f64 * self.sample_rate.max(1) as f64).round() as usize;
+        (start.min(self.frames), (start + len).min(self.frames))
+    }
+}
+
+pub fn loudness_timeline_from_multi(chans_in: &[Vec<f32>], in_sr: u32) -> Result<LoudnessTimeline> {
+    loudness_timeline_for_layout(chans_in, in_sr, default_loudness_layout(chans_in))
+}
+
+/// [`loudness_timeline_from_multi`] with the channel weights of `layout`.
+pub fn loudness_timeline_for_layout(
+    chans_in: &[Vec<f32>],
+    in_sr: u32,
+    layout: ChannelLayout,
+) -> Result<LoudnessTimeline> {
+    if chans_in.is_empty() {
+        anyhow::bail!("empty channels");
+    }
+    let p_sum = weighted_power_48k(chans_in, in_sr, layout);
+    let hop = (LOUDNESS_HOP_SEC * 48_000.0) as usize;
+    let lufs = |window_sec: f32| -> Vec<f32> {
+        block_means_power(&p_sum, (window_sec * 48_000.0) as usize, hop)
+            .into_iter()
+            .map(power_to_lufs)
+            .collect()
+    };
+    let momentary_lufs = lufs(LOUDNESS_MOMENTARY_WINDOW_SEC);
+    let short_term_lufs = lufs(LOUDNESS_SHORT_TERM_WINDOW_SEC);
+    let tp_hop = ((LOUDNESS_HOP_SEC as f64 * in_sr.max(1) as f64).round() as usize).max(1);
+    let true_peak_db = true_peak_abs_per_hop(chans_in, in_sr, tp_hop)
+        .into_iter()
+        .map(|peak| {
+            if peak > 0.0 {
+                20.0 * peak.log10()
+            } else {
+                f32::NEG_INFINITY
+            }
+        })
+        .collect();
+    Ok(LoudnessTimeline {
+        sample_rate: in_sr.max(1),
+        frames: chans_in.iter().map(Vec::len).max().unwrap_or(0),
+        momentary_lufs,
+        short_term_lufs,
+        true_peak_db,
+    })
+}
+
+/// Width of a [`LoudnessReport::short_term_histogram`] bin.
+pub const LOUDNESS_HISTOGRAM_BIN_LU: f32 = 1.0;
+
+/// Full per-file loudness report: the metrics plus the short-term
+/// distribution the loudness range is taken from.
+#[derive(Clone, Debug)]
+pub struct LoudnessReport {
+    pub metrics: LoudnessMetrics,
+    /// 10th / 95th percentile of the gated short-term loudness; their
+    /// difference is `metrics.lra_lu`.
+    pub lra_low_lufs: Option<f32>,
+    pub lra_high_lufs: Option<f32>,
+    /// Short-term (3 s, 100 ms hop) loudness values above the -70 LUFS
+    /// absolute gate, in 1 LU bins: `(lower edge LUFS, block count)`,
+    /// ascending and without empty bins at either end.
+    pub short_term_histogram: Vec<(f32, u32)>,
+    /// Short-term blocks measured, gated or not.
+    pub short_term_blocks: usize,
+}
+
+/// Loudness range per EBU Tech 3342: short-term values gated at -70 LUFS
+/// and 20 LU below their power mean, then the 10th to 95th percentile
+/// spread. Returns `(lra, low, high)`.
+fn loudness_range(short_term_lufs: &[f32]) -> Option<(f32, f32, f32)> {
+    let abs_gated: Vec<f32> = short_term_lufs
+        .iter()
+        .copied()
+        .filter(|&l| l > -70.0)
+        .collect();
+    if abs_gated.is_empty() {
+        return None;
+    }
+    let mean_power = abs_gated
+        .iter()
+        .map(|&l| 10f64.powf(((l - K_CONST) / 10.0) as f64))
+        .sum::<f64>()
+        / abs_gated.len() as f64;
+    let rel_gate = power_to_lufs(mean_power) - 20.0;
+    let mut gated: Vec<f32> = abs_gated.into_iter().filter(|&l| l > rel_gate).collect();
+    if gated.is_empty() {
+        return None;
+    }
+    gated.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f32| {
+        let idx = ((gated.len() - 1) as f32 * p).round() as usize;
+        gated[idx.min(gated.len() - 1)]
+    };
+    let low = percentile(0.10);
+    let high = percentile(0.95);
+    Some((high - low, low, high))
 }
 
 /// Inter-sample true peak via polyphase windowed-sinc interpolation
 /// (BS.1770-4 Annex 2). 4x below 96 kHz, 2x below 192 kHz, sample peak above.
 pub fn true_peak_db_from_multi(chans: &[Vec<f32>], in_sr: u32) -> Option<f32> {
-    let mut peak_abs = 0.0f32;
+    let peak_abs = true_peak_abs_per_hop(chans, in_sr, usize::MAX)
+        .into_iter()
+        .fold(0.0f32, f32::max);
+    if peak_abs > 0.0 {
+        Some(20.0 * peak_abs.log10())
+    } else {
+        Some(f32::NEG_INFINITY)
+    }
+}
+
+/// Linear true peak of every `hop` input frames (the last hop may be short).
+fn true_peak_abs_per_hop(chans: &[Vec<f32>], in_sr: u32, hop: usize) -> Vec<f32> {
+    let hop = hop.max(1);
+    let frames = chans.iter().map(Vec::len).max().unwrap_or(0);
+    let mut peaks = vec![0.0f32; frames.div_ceil(hop)];
     for ch in chans {
-        for &v in ch {
+        for (n, &v) in ch.iter().enumerate() {
             let a = v.abs();
-            if a > peak_abs {
-                peak_abs = a;
+            if a > peaks[n / hop] {
+                peaks[n / hop] = a;
             }
         }
     }
@@ -3759,18 +5675,14 @@ pub fn true_peak_db_from_multi(chans: &[Vec<f32>], in_sr: u32) -> Option<f32> {
                         acc += ch[idx] * c;
                     }
                     let a = acc.abs();
-                    if a > peak_abs {
-                        peak_abs = a;
+                    if a > peaks[n / hop] {
+                        peaks[n / hop] = a;
                     }
                 }
             }
         }
     }
-    if peak_abs > 0.0 {
-        Some(20.0 * peak_abs.log10())
-    } else {
-        Some(f32::NEG_INFINITY)
-    }
+    peaks
 }
 
 /// All loudness metrics in one pass: resample-to-48k + K-weighting + the
@@ -3780,125 +5692,155 @@ pub fn true_peak_db_from_multi(chans: &[Vec<f32>], in_sr: u32) -> Option<f32> {
 /// Known deviation: the 48 kHz conversion uses linear interpolation, which
 /// is within ~0.1 LU of a sinc resampler for typical program material but is
 /// not bit-exact against a reference meter at non-48k rates.
+///
+/// Channels are weighted as the default layout for their count; use
+/// [`loudness_metrics_for_layout`] when the file declares one.
 pub fn loudness_metrics_from_multi(chans_in: &[Vec<f32>], in_sr: u32) -> Result<LoudnessMetrics> {
-    loudness_metrics_impl(chans_in, in_sr, true)
+    loudness_metrics_for_layout(chans_in, in_sr, default_loudness_layout(chans_in))
 }
 
-fn loudness_metrics_impl(
+/// [`loudness_metrics_from_multi`] with the channel weights of `layout`.
+pub fn loudness_metrics_for_layout(
     chans_in: &[Vec<f32>],
     in_sr: u32,
-    with_true_peak: bool,
+    layout: ChannelLayout,
 ) -> Result<LoudnessMetrics> {
+    Ok(loudness_metrics_impl(chans_in, in_sr, layout)?.0)
+}
+
+/// [`loudness_metrics_from_multi`] plus the LRA percentiles and the
+/// short-term loudness histogram.
+pub fn loudness_report_from_multi(chans_in: &[Vec<f32>], in_sr: u32) -> Result<LoudnessReport> {
+    loudness_report_for_layout(chans_in, in_sr, default_loudness_layout(chans_in))
+}
+
+/// [`loudness_report_from_multi`] with the channel weights of `layout`.
+pub fn loudness_report_for_layout(
+    chans_in: &[Vec<f32>],
+    in_sr: u32,
+    layout: ChannelLayout,
+) -> Result<LoudnessReport> {
+    let (metrics, short_term) = loudness_metrics_impl(chans_in, in_sr, layout)?;
+    let range = loudness_range(&short_term);
+    let mut counts: std::collections::BTreeMap<i32, u32> = std::collections::BTreeMap::new();
+    for &l in short_term.iter().filter(|&&l| l > -70.0) {
+        *counts
+            .entry((l / LOUDNESS_HISTOGRAM_BIN_LU).floor() as i32)
+            .or_default() += 1;
+    }
+    let short_term_histogram = match (counts.keys().next(), counts.keys().next_back()) {
+        (Some(&first), Some(&last)) => (first..=last)
+            .map(|bin| {
+                (
+                    bin as f32 * LOUDNESS_HISTOGRAM_BIN_LU,
+                    counts.get(&bin).copied().unwrap_or(0),
+                )
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(LoudnessReport {
+        metrics,
+        lra_low_lufs: range.map(|(_, low, _)| low),
+        lra_high_lufs: range.map(|(_, _, high)| high),
+        short_term_histogram,
+        short_term_blocks: short_term.len(),
+    })
+}
+
+/// Metrics plus the short-term loudness of every 3 s block (for LRA and the
+/// report histogram).
+fn loudness_metrics_impl(
+    chans_in: &[Vec<f32>],
+    in_sr: u32,
+    layout: ChannelLayout,
+) -> Result<(LoudnessMetrics, Vec<f32>)> {
     if chans_in.is_empty() {
         anyhow::bail!("empty channels");
     }
-    // The oversampled true-peak scan costs ~48 MACs per sample; callers that
-    // only need integrated loudness (gain-change recalc) skip it.
-    let true_peak_db = if with_true_peak {
-        true_peak_db_from_multi(chans_in, in_sr)
-    } else {
-        None
-    };
-    // Resample to 48k and copy
-    let (mut chans, sr) = ensure_sr_48k(chans_in, in_sr);
-    let _ = sr; // sr is 48k now
-                // K-weighting
-    k_weighting_apply_48k(&mut chans);
-    // Weighted power sum across channels (BS.1770 G weights, LFE excluded
-    // for assumed 5.1/7.1 layouts).
-    let weights = bs1770_channel_weights(chans.len());
-    let n = chans[0].len();
-    let mut p_sum = vec![0.0f32; n];
-    for (ch, &w) in chans.iter().zip(weights.iter()) {
-        if w == 0.0 {
-            continue;
-        }
-        for i in 0..n {
-            let v = ch[i];
-            p_sum[i] += w * v * v;
-        }
-    }
+    let true_peak_db = true_peak_db_from_multi(chans_in, in_sr);
+    let p_sum = weighted_power_48k(chans_in, in_sr, layout);
     // Momentary: 400ms window with 100ms hop
-    let win_m = (0.400 * 48_000.0) as usize;
-    let hop = (0.100 * 48_000.0) as usize;
-    let means = block_means_power(&p_sum, win_m, hop);
-    // Short-term: 3s window with 100ms hop (ungated max per EBU Tech 3341)
-    let win_s = (3.0 * 48_000.0) as usize;
-    let lufs_s_max = block_means_power(&p_sum, win_s, hop)
+    let means = momentary_means(&p_sum);
+    // Short-term: 3s window with 100ms hop (ungated max per EBU Tech 3341,
+    // gated distribution for LRA per Tech 3342)
+    let win_s = (LOUDNESS_SHORT_TERM_WINDOW_SEC * 48_000.0) as usize;
+    let hop = (LOUDNESS_HOP_SEC * 48_000.0) as usize;
+    let short_term: Vec<f32> = block_means_power(&p_sum, win_s, hop)
         .into_iter()
-        .fold(None::<f64>, |acc, m| Some(acc.map_or(m, |a| a.max(m))))
-        .map(power_to_lufs);
+        .map(power_to_lufs)
+        .collect();
+    let lufs_s_max = short_term
+        .iter()
+        .copied()
+        .fold(None::<f32>, |acc, l| Some(acc.map_or(l, |a| a.max(l))));
+    let lra_lu = loudness_range(&short_term).map(|(lra, _, _)| lra);
+    let lufs_m_max = means
+        .iter()
+        .map(|&m| power_to_lufs(m))
+        .fold(None::<f32>, |acc, l| Some(acc.map_or(l, |a| a.max(l))));
+    Ok((
+        LoudnessMetrics {
+            lufs_i: gated_lufs(&p_sum, &means),
+            lufs_m_max,
+            lufs_s_max,
+            lra_lu,
+            true_peak_db,
+        },
+        short_term,
+    ))
+}
+
+/// Mean power of every 400 ms block at a 100 ms hop.
+fn momentary_means(p_sum: &[f32]) -> Vec<f64> {
+    let win_m = (LOUDNESS_MOMENTARY_WINDOW_SEC * 48_000.0) as usize;
+    let hop = (LOUDNESS_HOP_SEC * 48_000.0) as usize;
+    block_means_power(p_sum, win_m, hop)
+}
+
+/// Integrated loudness from the momentary block `means` of `p_sum`: blocks
+/// gated at -70 LUFS, then 10 LU below the absolute-gated average.
+fn gated_lufs(p_sum: &[f32], means: &[f64]) -> f32 {
     if means.is_empty() {
         // Fallback for very short audio (< window): use whole-signal mean power.
         // This avoids returning +/-inf for short clips where BS.1770 windowing can't be applied.
-        let mut acc = 0.0f64;
-        for &v in &p_sum {
-            acc += v as f64;
-        }
-        let n = p_sum.len().max(1) as f64;
-        let l = power_to_lufs(acc / n);
-        return Ok(LoudnessMetrics {
-            lufs_i: l,
-            lufs_m_max: None,
-            lufs_s_max,
-            true_peak_db,
-        });
+        let acc: f64 = p_sum.iter().map(|&v| v as f64).sum();
+        return power_to_lufs(acc / p_sum.len().max(1) as f64);
     }
-    let blocks_lufs: Vec<f32> = means.iter().map(|&m| power_to_lufs(m)).collect();
-    let lufs_m_max = blocks_lufs
-        .iter()
-        .copied()
-        .fold(None::<f32>, |acc, l| Some(acc.map_or(l, |a| a.max(l))));
-    let lufs_i = {
-        // Absolute gate -70 LUFS
-        let mut sel: Vec<bool> = blocks_lufs.iter().map(|&l| l > -70.0).collect();
-        if !sel.iter().any(|&b| b) {
-            f32::NEG_INFINITY
-        } else {
-            // Average of means after absolute gate
-            let mut num = 0usize;
-            let mut acc = 0.0f64;
-            for (i, &ok) in sel.iter().enumerate() {
-                if ok {
-                    acc += means[i];
-                    num += 1;
-                }
-            }
-            let z_abs = if num > 0 { acc / num as f64 } else { 0.0 };
-            if z_abs <= 0.0 {
-                f32::NEG_INFINITY
-            } else {
-                // Relative gate: -10 LU below the absolute-gated average.
-                let thr = power_to_lufs(z_abs) - 10.0;
-                for (i, l) in blocks_lufs.iter().enumerate() {
-                    sel[i] = sel[i] && (*l > thr);
-                }
-                let mut acc2 = 0.0f64;
-                let mut n2 = 0usize;
-                for (i, &ok) in sel.iter().enumerate() {
-                    if ok {
-                        acc2 += means[i];
-                        n2 += 1;
-                    }
-                }
-                if n2 == 0 {
-                    f32::NEG_INFINITY
-                } else {
-                    power_to_lufs(acc2 / n2 as f64)
-                }
-            }
-        }
+    let gated_mean = |threshold: f32| {
+        let (acc, num) = means
+            .iter()
+            .filter(|&&m| power_to_lufs(m) > threshold)
+            .fold((0.0f64, 0usize), |(acc, num), &m| (acc + m, num + 1));
+        (num > 0).then(|| acc / num as f64)
     };
-    Ok(LoudnessMetrics {
-        lufs_i,
-        lufs_m_max,
-        lufs_s_max,
-        true_peak_db,
-    })
+    // Absolute gate -70 LUFS
+    let Some(z_abs) = gated_mean(-70.0).filter(|&z| z > 0.0) else {
+        return f32::NEG_INFINITY;
+    };
+    // Relative gate: -10 LU below the absolute-gated average. Every block
+    // above it also clears the absolute gate.
+    let thr = (power_to_lufs(z_abs) - 10.0).max(-70.0);
+    gated_mean(thr).map_or(f32::NEG_INFINITY, power_to_lufs)
 }
 
 pub fn lufs_integrated_from_multi(chans_in: &[Vec<f32>], in_sr: u32) -> Result<f32> {
-    Ok(loudness_metrics_impl(chans_in, in_sr, false)?.lufs_i)
+    lufs_integrated_for_layout(chans_in, in_sr, default_loudness_layout(chans_in))
+}
+
+/// [`lufs_integrated_from_multi`] with the channel weights of `layout`. Only
+/// the gated momentary blocks are computed: no true peak, short-term values
+/// or loudness range.
+pub fn lufs_integrated_for_layout(
+    chans_in: &[Vec<f32>],
+    in_sr: u32,
+    layout: ChannelLayout,
+) -> Result<f32> {
+    if chans_in.is_empty() {
+        anyhow::bail!("empty channels");
+    }
+    let p_sum = weighted_power_48k(chans_in, in_sr, layout);
+    Ok(gated_lufs(&p_sum, &momentary_means(&p_sum)))
 }
 
 #[cfg(test)]
@@ -4073,6 +6015,56 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn aiff_marker_rewrites_drop_orphan_comments_and_use_free_ids() {
+        let dir = make_temp_dir("aiff_mark_ids");
+        let chans = synth_stereo(44_100, 0.1);
+        let dst = dir.join("marks.aiff");
+        super::write_aiff_with_depth(&chans, 44_100, &dst, super::WavBitDepth::Pcm16)
+            .expect("write aiff");
+        super::write_aiff_loop_markers(&dst, Some((100, 2_000))).expect("write loop");
+        // Another editor's comment on the loop start, and a marker at the top id.
+        let (is_aifc, mut chunks) = super::parse_aiff_chunks(&dst).expect("parse");
+        let loop_ids = super::aiff_loop_mark_ids(&chunks);
+        let mut marks = super::aiff_marks_of(&chunks);
+        marks.push(super::AiffMark {
+            id: i16::MAX,
+            position: 50,
+            name: b"top".to_vec(),
+        });
+        let comments = [super::AiffComment {
+            timestamp: 0,
+            marker_id: loop_ids[0],
+            text: b"loop note".to_vec(),
+        }];
+        let inst = super::find_aiff_chunk(&chunks, b"INST");
+        super::replace_aiff_marker_chunks(
+            &mut chunks,
+            Some(super::encode_aiff_marks(&marks)),
+            Some(super::encode_aiff_comments(&comments)),
+            inst,
+        );
+        super::encode_aiff_chunks(&dst, is_aifc, &chunks).expect("encode");
+
+        super::write_aiff_loop_markers(&dst, Some((200, 3_000))).expect("rewrite loop");
+        assert_eq!(super::read_aiff_loop_markers(&dst), Some((200, 3_000)));
+        let (_, chunks) = super::parse_aiff_chunks(&dst).expect("parse");
+        assert!(
+            super::find_aiff_chunk(&chunks, b"COMT").is_none(),
+            "comment on the removed loop marker is dropped"
+        );
+        let ids: Vec<i16> = super::aiff_marks_of(&chunks).iter().map(|m| m.id).collect();
+        assert!(ids.contains(&i16::MAX));
+        assert!(ids.iter().all(|&id| id > 0), "{ids:?}");
+        let cues = super::read_aiff_cues(&dst).expect("cues");
+        assert_eq!(cues.markers, vec![(50, "top".to_string())]);
+
+        let mut gap = super::AiffMarkIds::new((1..=i16::MAX).filter(|&id| id != 7));
+        assert_eq!(gap.alloc().expect("free id"), 7);
+        assert!(gap.alloc().is_err(), "all positive ids taken");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn export_flac_roundtrip_preserves_audio_and_loop_metadata() {
         let dir = make_temp_dir("flac_roundtrip");
@@ -4117,20 +6109,42 @@ mod tests {
     }
 
     #[test]
-    fn ogg_loop_markers_fall_back_to_sidecar() {
-        let dir = make_temp_dir("ogg_loop_sidecar");
+    fn ogg_loop_markers_are_native_and_migrate_sidecar() {
+        let dir = make_temp_dir("ogg_loop_native");
         let src = dir.join("source.ogg");
         export_channels_audio(&synth_stereo(48_000, 0.5), 48_000, &src).expect("export ogg");
 
-        crate::loop_markers::write_loop_markers(&src, Some((100, 900)))
-            .expect("write ogg loop sidecar");
-        assert!(dir.join("source.loop.json").is_file());
+        // A sidecar written by an older build is still picked up...
+        let sidecar = dir.join("source.loop.json");
+        std::fs::write(
+            &sidecar,
+            br#"{"version":1,"loop_start":100,"loop_end":900}"#,
+        )
+        .expect("write legacy sidecar");
         assert_eq!(
             crate::loop_markers::read_loop_markers(&src),
             Some((100, 900))
         );
-        crate::loop_markers::write_loop_markers(&src, None).expect("clear ogg loop sidecar");
-        assert!(!dir.join("source.loop.json").exists());
+        // ...and the next write moves the loop into the comment header.
+        crate::loop_markers::write_loop_markers(&src, Some((100, 900)))
+            .expect("write ogg loop tags");
+        assert!(!sidecar.exists());
+        assert_eq!(
+            crate::ogg_meta::read_ogg_loop_markers(&src).expect("read ogg tags"),
+            Some((100, 900))
+        );
+        let (decoded, _) = crate::audio_io::decode_audio_multi(&src).expect("decode after rewrite");
+        assert_eq!(decoded[0].len(), synth_stereo(48_000, 0.5)[0].len());
+
+        // Gain export to a new Ogg carries the comments over.
+        let dst = dir.join("out.ogg");
+        export_gain_audio(&src, &dst, -3.0).expect("export gain ogg");
+        assert_eq!(
+            crate::loop_markers::read_loop_markers(&dst),
+            Some((100, 900))
+        );
+        crate::loop_markers::write_loop_markers(&src, None).expect("clear ogg loop");
+        assert!(!sidecar.exists());
         assert_eq!(crate::loop_markers::read_loop_markers(&src), None);
 
         let _ = std::fs::remove_dir_all(&dir);
@@ -4309,6 +6323,81 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn cart_write_read_roundtrip_and_resampled_overwrite_moves_timers() {
+        use crate::wave::{
+            cart_timer_marker_label, cart_timer_usage_from_label, read_wav_cart, write_wav_cart,
+            CartFields, CartTimer, CART_FIXED_BYTES,
+        };
+        let dir = make_temp_dir("cart_roundtrip");
+        let src = dir.join("spot.wav");
+        export_channels_audio(&synth_stereo(48_000, 0.5), 48_000, &src).expect("export wav");
+        assert_eq!(read_wav_cart(&src).expect("read"), None);
+
+        let fields = CartFields {
+            title: "Morning Promo".to_string(),
+            artist: "Station Voice".to_string(),
+            cut_id: "PR0042".to_string(),
+            start_date: "2026-10-01".to_string(),
+            start_time: "06:00:00".to_string(),
+            end_date: "2026-10-31".to_string(),
+            end_time: "23:59:59".to_string(),
+            level_reference: 32_768,
+            post_timers: vec![
+                CartTimer {
+                    usage: "INTe".to_string(),
+                    sample: 4_800,
+                },
+                CartTimer {
+                    usage: "SEGs".to_string(),
+                    sample: 19_200,
+                },
+            ],
+            url: "https://example.com/spot".to_string(),
+            tag_text: "<tag>promo</tag>".to_string(),
+            ..CartFields::default()
+        };
+        write_wav_cart(&src, Some(&fields)).expect("write cart");
+        assert_eq!(read_wav_cart(&src).unwrap(), Some(fields.clone()));
+        let cart = parse_riff_wave_chunks(&src)
+            .unwrap()
+            .into_iter()
+            .find(|c| &c.id == b"cart")
+            .expect("cart chunk");
+        assert_eq!(cart.payload.len(), CART_FIXED_BYTES + 16);
+
+        // A resampling overwrite keeps the chunk and moves the timers.
+        let (chans, _) = crate::audio_io::decode_audio_multi(&src).expect("decode");
+        let resampled: Vec<Vec<f32>> = chans
+            .iter()
+            .map(|ch| ch.iter().step_by(2).copied().collect())
+            .collect();
+        overwrite_audio_from_channels_with_depth(&resampled, 24_000, &src, false, None, None)
+            .expect("overwrite");
+        let moved = read_wav_cart(&src).unwrap().expect("cart kept");
+        assert_eq!(moved.title, "Morning Promo");
+        assert_eq!(moved.post_timers[0].sample, 2_400);
+        assert_eq!(moved.post_timers[1].sample, 9_600);
+
+        write_wav_cart(&src, None).expect("remove cart");
+        assert_eq!(read_wav_cart(&src).unwrap(), None);
+
+        assert_eq!(cart_timer_usage_from_label("SEGs").as_deref(), Some("SEGs"));
+        assert_eq!(
+            cart_timer_usage_from_label("cart:x1").as_deref(),
+            Some("x1")
+        );
+        assert_eq!(cart_timer_usage_from_label("M01"), None);
+        assert_eq!(cart_timer_usage_from_label("Hit1"), None);
+        assert_eq!(cart_timer_usage_from_label("VOX1"), None);
+        assert_eq!(cart_timer_usage_from_label("MRKs").as_deref(), Some("MRKs"));
+        assert_eq!(cart_timer_marker_label("END1"), "cart:END1");
+        assert_eq!(cart_timer_marker_label("INTe"), "INTe");
+        assert_eq!(cart_timer_marker_label("x1"), "cart:x1");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn overwrite_gain_wav_preserves_ancillary_chunks() {
         let dir = make_temp_dir("wav_chunk_preserve");
@@ -4529,6 +6618,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lufs_integrated_matches_the_full_metrics() {
+        let sr = 48_000;
+        let mut chans = stereo_sine(997.0, -20.0, sr, 4.0);
+        // Quiet enough to fall under the relative gate.
+        for (ch, quiet) in chans.iter_mut().zip(stereo_sine(997.0, -40.0, sr, 6.0)) {
+            ch.extend(quiet);
+        }
+        let short = stereo_sine(997.0, -20.0, sr, 0.2);
+        for clip in [&chans, &short] {
+            let integrated = super::lufs_integrated_from_multi(clip, sr).expect("lufs");
+            let metrics = super::loudness_metrics_from_multi(clip, sr).expect("metrics");
+            assert_eq!(integrated, metrics.lufs_i);
+        }
+    }
+
+    // EBU Tech 3342 case 1: 20 s at -20 dBFS then 20 s at -30 dBFS
+    // (1 kHz stereo sine) -> LRA 10 LU +/- 1.
+    #[test]
+    fn loudness_range_matches_tech3342_case_1() {
+        let sr = 48_000;
+        let mut chans = stereo_sine(1000.0, -20.0, sr, 20.0);
+        let quiet = stereo_sine(1000.0, -30.0, sr, 20.0);
+        for (ch, tail) in chans.iter_mut().zip(quiet) {
+            ch.extend(tail);
+        }
+        let report = super::loudness_report_from_multi(&chans, sr).expect("report");
+        let lra = report.metrics.lra_lu.expect("lra");
+        assert!((lra - 10.0).abs() <= 1.0, "LRA {lra}");
+        let counted: u32 = report.short_term_histogram.iter().map(|(_, n)| n).sum();
+        assert_eq!(counted as usize, report.short_term_blocks);
+        let (first_edge, _) = report.short_term_histogram[0];
+        assert!(
+            first_edge <= report.lra_low_lufs.expect("low"),
+            "histogram starts at {first_edge}"
+        );
+
+        // A steady tone has no range; shorter than 3 s has none at all.
+        let steady = stereo_sine(1000.0, -23.0, sr, 10.0);
+        let lra = super::loudness_metrics_from_multi(&steady, sr)
+            .expect("metrics")
+            .lra_lu
+            .expect("lra");
+        assert!(lra.abs() < 0.1, "steady LRA {lra}");
+        let short = stereo_sine(1000.0, -23.0, sr, 2.0);
+        assert_eq!(
+            super::loudness_metrics_from_multi(&short, sr)
+                .expect("metrics")
+                .lra_lu,
+            None
+        );
+    }
+
+    #[test]
+    fn loudness_timeline_tracks_a_burst_and_matches_the_metrics() {
+        let sr = 44_100;
+        let burst = stereo_sine(997.0, -20.0, sr, 1.0);
+        let mut chans = vec![Vec::new(), Vec::new()];
+        for (i, ch) in chans.iter_mut().enumerate() {
+            ch.extend(std::iter::repeat(0.0f32).take((sr * 5) as usize));
+            ch.extend(burst[i].iter().copied());
+            ch.extend(std::iter::repeat(0.0f32).take((sr * 4) as usize));
+        }
+        let timeline = super::loudness_timeline_from_multi(&chans, sr).expect("timeline");
+        let metrics = super::loudness_metrics_from_multi(&chans, sr).expect("metrics");
+        let max = |values: &[f32]| values.iter().copied().fold(f32::MIN, f32::max);
+        assert!((max(&timeline.momentary_lufs) - metrics.lufs_m_max.unwrap()).abs() < 1e-3);
+        assert!((max(&timeline.short_term_lufs) - metrics.lufs_s_max.unwrap()).abs() < 1e-3);
+        assert!((max(&timeline.true_peak_db) - metrics.true_peak_db.unwrap()).abs() < 1e-3);
+        // 10 s in 100 ms hops; silence reads as the floor, the burst as loud.
+        assert_eq!(timeline.true_peak_db.len(), 100);
+        assert!(timeline.true_peak_db[10].is_infinite());
+        assert!(timeline.true_peak_db[55] > -21.0);
+        let loudest = timeline
+            .momentary_lufs
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(idx, _)| idx)
+            .expect("blocks");
+        let (start, end) = timeline.block_range(loudest, super::LOUDNESS_MOMENTARY_WINDOW_SEC);
+        assert!(
+            start >= (sr * 5) as usize && end <= (sr * 6) as usize,
+            "{start}..{end}"
+        );
+    }
+
     // Momentary vs short-term: a 0.5s burst inside 10s of silence fills a
     // 400ms window completely but only a fraction of a 3s window.
     #[test]
@@ -4629,6 +6805,49 @@ mod tests {
         );
     }
 
+    // The declared layout decides which channels are surrounds: the same
+    // tone on channels 5/6 of a 6-channel file is a 1.41-weighted surround
+    // pair for 5.1 but an unweighted front pair for a 6.0 L R C Lc Rc Cs mask.
+    #[test]
+    fn lufs_weights_follow_the_declared_channel_layout() {
+        use crate::channel_layout::ChannelLayout;
+        let sr = 48_000;
+        let tone = stereo_sine(997.0, -23.0, sr, 10.0);
+        let silence = vec![0.0f32; tone[0].len()];
+        let mut chans: Vec<Vec<f32>> = vec![silence.clone(); 6];
+        chans[4] = tone[0].clone();
+        chans[5] = tone[1].clone();
+        let surround =
+            super::lufs_integrated_for_layout(&chans, sr, ChannelLayout::default_for(6)).unwrap();
+        let front =
+            super::lufs_integrated_for_layout(&chans, sr, ChannelLayout::new(6, 0x1C7)).unwrap();
+        let expected = 10.0 * 1.41f32.log10();
+        assert!(
+            ((surround - front) - expected).abs() <= 0.1,
+            "layout weighting: surround {surround}, front {front}"
+        );
+        // 7.1: the rear pair is unweighted, the side pair carries 1.41.
+        let mut seven: Vec<Vec<f32>> = vec![silence.clone(); 8];
+        seven[4] = tone[0].clone();
+        seven[5] = tone[1].clone();
+        let rear = super::loudness_metrics_from_multi(&seven, sr)
+            .unwrap()
+            .lufs_i;
+        seven.swap(4, 6);
+        seven.swap(5, 7);
+        let side = super::loudness_metrics_from_multi(&seven, sr)
+            .unwrap()
+            .lufs_i;
+        assert!(
+            ((side - rear) - expected).abs() <= 0.1,
+            "7.1 weighting: rear {rear}, side {side}"
+        );
+        // A layout for another channel count falls back to the default.
+        let mismatched =
+            super::lufs_integrated_for_layout(&chans, sr, ChannelLayout::default_for(2)).unwrap();
+        assert!((mismatched - surround).abs() <= 0.01);
+    }
+
     #[test]
     fn noise_gate_silences_signal_below_threshold() {
         let sr = 48_000;
@@ -4868,6 +7087,117 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn ucs_write_merges_ixml_and_bext_and_restores_from_snapshot() {
+        let dir = std::env::temp_dir().join(format!(
+            "neowaves_ucs_write_test_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        let path = dir.join("ucs.wav");
+        let ch: Vec<f32> = (0..4800).map(|i| (i as f32 * 0.01).sin() * 0.4).collect();
+        export_channels_audio(&[ch].to_vec(), 48_000, &path).expect("export");
+        let ixml = super::IxmlFields {
+            project: "Forest Trip".into(),
+            take: "2".into(),
+            ..Default::default()
+        };
+        super::write_wav_info_ixml(&path, &Default::default(), &ixml).expect("write ixml");
+        let bext = super::BextFields {
+            description: "old".into(),
+            originator: "Recorder".into(),
+            ..Default::default()
+        };
+        super::write_wav_bext(&path, &bext).expect("write bext");
+        let before = super::snapshot_wav_chunks(&path, &[*b"bext", *b"iXML"]).expect("snapshot");
+
+        let fields = crate::metadata::ucs::UcsFields {
+            cat_id: "AMBForst".into(),
+            fx_name: "Night & Crickets".into(),
+            creator_id: "ZUKKY".into(),
+            source_id: "REC001".into(),
+            ..Default::default()
+        }
+        .resolved();
+        super::write_wav_ucs(&path, &fields, super::UcsWriteTargets::default()).expect("write ucs");
+
+        let got = super::read_wav_ucs(&path).expect("read ucs");
+        assert_eq!(got.cat_id, "AMBForst");
+        assert_eq!(got.category, "AMBIENCE");
+        assert_eq!(got.subcategory, "FOREST");
+        assert_eq!(got.fx_name, "Night & Crickets");
+        assert_eq!(got.description, "Night & Crickets");
+        assert_eq!(super::read_wav_ixml(&path).unwrap().unwrap(), ixml);
+        let got_bext = super::read_wav_bext(&path).unwrap().unwrap();
+        assert_eq!(got_bext.description, "Night & Crickets");
+        assert_eq!(got_bext.originator, "Recorder");
+        let document =
+            crate::metadata::inspect_path(&path, crate::metadata::InspectOptions::default())
+                .expect("inspect");
+        assert!(document
+            .normalized
+            .iter()
+            .any(|field| field.key == "ucs.cat_id" && !field.conflict));
+        assert!(!document
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.code.starts_with("aswg.")));
+
+        // A later BWF write keeps the UCS blocks; a second UCS write
+        // replaces values instead of duplicating elements.
+        super::write_wav_info_ixml(&path, &Default::default(), &ixml).expect("rewrite ixml");
+        let renamed = crate::metadata::ucs::UcsFields {
+            fx_name: "Crickets".into(),
+            ..fields.clone()
+        };
+        super::write_wav_ucs(&path, &renamed, super::UcsWriteTargets::default())
+            .expect("rewrite ucs");
+        let raw = std::fs::read(&path).expect("raw");
+        assert_eq!(raw.windows(7).filter(|w| w == b"<catId>").count(), 1);
+        assert_eq!(super::read_wav_ucs(&path).unwrap().fx_name, "Crickets");
+
+        super::restore_wav_chunks(&path, &before).expect("restore");
+        assert_eq!(
+            super::snapshot_wav_chunks(&path, &[*b"bext", *b"iXML"]).unwrap(),
+            before
+        );
+        assert_eq!(super::read_wav_ucs(&path).unwrap().cat_id, "");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ucs_merge_handles_compact_and_self_closing_blocks() {
+        let fields = crate::metadata::ucs::UcsFields {
+            cat_id: "AIRBlow".into(),
+            fx_name: "Hiss".into(),
+            ..Default::default()
+        };
+        let targets = super::UcsWriteTargets {
+            user: false,
+            ..Default::default()
+        };
+        let xml = super::merge_ucs_into_ixml(
+            Some("<BWFXML><ASWG xmlns=\"http://sce/aswg/ixml\"><catId>OLD</catId><library>L</library></ASWG></BWFXML>"),
+            &fields,
+            targets,
+        )
+        .expect("merge compact");
+        assert!(xml.contains("<catId>AIRBlow</catId>"));
+        assert!(xml.contains("<library>L</library>"));
+        assert!(xml.contains("<fxName>Hiss</fxName>"));
+        assert!(!xml.contains("OLD"));
+        let xml = super::merge_ucs_into_ixml(Some("<BWFXML><ASWG/></BWFXML>"), &fields, targets)
+            .expect("merge self-closing");
+        assert!(
+            xml.contains("<ASWG>\n    <catId>AIRBlow</catId>\n    <fxName>Hiss</fxName>\n</ASWG>")
+        );
+        assert!(super::merge_ucs_into_ixml(Some("<NOTBWF/>"), &fields, targets).is_err());
+    }
+
     #[test]
     fn info_ixml_builders_reject_empty_and_escape() {
         assert!(super::build_info_list_chunk(&Default::default()).is_none());
@@ -4881,4 +7211,88 @@ mod tests {
         assert!(xml.contains("a&lt;b&gt;&amp;c&quot;d&apos;e"), "{xml}");
         assert!(!xml.contains("a<b>"), "raw text must be escaped: {xml}");
     }
+
+    #[test]
+    fn fold_7_1_to_5_1_merges_side_and_back_pairs() {
+        use crate::channel_layout::ChannelLayout;
+        // Default 7.1 (L R C LFE Lb Rb Ls Rs), one impulse per channel.
+        let chans: Vec<Vec<f32>> = (0..8)
+            .map(|ch| {
+                let mut v = vec![0.0f32; 8];
+                v[ch] = 1.0;
+                v
+            })
+            .collect();
+        let out = super::fold_7_1_to_5_1(&chans, ChannelLayout::default_for(8));
+        assert_eq!(out.len(), 6);
+        let k = std::f32::consts::FRAC_1_SQRT_2;
+        for (ch, lane) in out.iter().enumerate().take(4) {
+            assert_eq!(lane[ch], 1.0, "front channel {ch} passes through");
+        }
+        for (src, dst) in [(4, 4), (5, 5), (6, 4), (7, 5)] {
+            assert!((out[dst][src] - k).abs() < 1e-6, "ch {src} -> {dst}");
+        }
+        // Front-wide 7.1: Lc/Rc join the front pair.
+        let wide = super::fold_7_1_to_5_1(&chans, ChannelLayout::new(8, 0xFF));
+        assert!((wide[0][6] - k).abs() < 1e-6);
+        assert!((wide[1][7] - k).abs() < 1e-6);
+        assert!((wide[4][4] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn downmix_folds_surround_per_itu_and_first_two() {
+        // 5.1 in WAV order: L R C LFE Ls Rs, one impulse per channel.
+        let chans: Vec<Vec<f32>> = (0..6)
+            .map(|ch| {
+                let mut v = vec![0.0f32; 6];
+                v[ch] = 0.5;
+                v
+            })
+            .collect();
+        let k = std::f32::consts::FRAC_1_SQRT_2 * 0.5;
+        let itu = super::downmix_to_stereo(&chans, super::DownmixMode::Itu, None);
+        assert_eq!(itu.len(), 2);
+        let expect_l = [0.5, 0.0, k, 0.0, k, 0.0];
+        let expect_r = [0.0, 0.5, k, 0.0, 0.0, k];
+        for (i, (l, r)) in expect_l.iter().zip(expect_r.iter()).enumerate() {
+            assert!((itu[0][i] - l).abs() < 1e-6, "L[{i}]={}", itu[0][i]);
+            assert!((itu[1][i] - r).abs() < 1e-6, "R[{i}]={}", itu[1][i]);
+        }
+
+        let first2 = super::downmix_to_stereo(&chans, super::DownmixMode::FirstTwo, None);
+        assert_eq!(first2, vec![chans[0].clone(), chans[1].clone()]);
+
+        // A full-scale bed would clip when summed; the fold-down backs off.
+        let loud = vec![vec![1.0f32; 4]; 6];
+        let folded = super::downmix_to_stereo(&loud, super::DownmixMode::Itu, None);
+        let peak = folded.iter().flatten().fold(0.0f32, |m, v| m.max(v.abs()));
+        assert!((peak - 1.0).abs() < 1e-6, "peak={peak}");
+    }
+
+    #[test]
+    fn downmix_places_channels_by_declared_layout() {
+        use crate::channel_layout::ChannelLayout;
+        // 3.1 (L R C LFE), one impulse per channel. As quad, C and LFE would
+        // land in the surround feeds.
+        let chans: Vec<Vec<f32>> = (0..4)
+            .map(|ch| {
+                let mut v = vec![0.0f32; 4];
+                v[ch] = 0.5;
+                v
+            })
+            .collect();
+        let k = std::f32::consts::FRAC_1_SQRT_2 * 0.5;
+        let layout = Some(ChannelLayout::new(4, 0xF));
+        let folded = super::downmix_to_stereo(&chans, super::DownmixMode::Itu, layout);
+        let expect_l = [0.5, 0.0, k, 0.0];
+        let expect_r = [0.0, 0.5, k, 0.0];
+        for (i, (l, r)) in expect_l.iter().zip(expect_r.iter()).enumerate() {
+            assert!((folded[0][i] - l).abs() < 1e-6, "L[{i}]={}", folded[0][i]);
+            assert!((folded[1][i] - r).abs() < 1e-6, "R[{i}]={}", folded[1][i]);
+        }
+        // Undeclared 4ch stays quad: channel 2 is the left surround.
+        let quad = super::downmix_to_stereo(&chans, super::DownmixMode::Itu, None);
+        assert!((quad[0][2] - k).abs() < 1e-6);
+        assert!(quad[1][2].abs() < 1e-6);
+    }
 }
@@ -73,7 +73,7 @@ fn assert_probe_and_decode(path: &std::path::Path) {
 fn audio_probe_decode_for_wav_mp3_m4a_ogg() {
     let dir = make_temp_dir("audio_probe_decode");
     let chans = synth_stereo(44_100, 0.20);
-    let formats = ["wav", "aiff", "mp3", "m4a", "ogg"];
+    let formats = ["wav", "aiff", "mp3", "m4a", "ogg", "opus"];
     for ext in formats {
         let path = dir.join(format!("tone.{ext}"));
         neowaves::wave::export_channels_audio(&chans, 44_100, &path)
@@ -82,3 +82,484 @@ fn audio_probe_decode_for_wav_mp3_m4a_ogg() {
     }
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn opus_roundtrip_trims_pre_skip_and_keeps_length() {
+    let dir = make_temp_dir("opus_roundtrip");
+    let sr = 48_000;
+    let chans = synth_stereo(sr, 0.50);
+    let frames = chans[0].len();
+    let path = dir.join("tone.opus");
+    neowaves::wave::export_channels_audio(&chans, sr, &path).expect("export opus");
+
+    let info = neowaves::audio_io::read_audio_info(&path).expect("probe opus");
+    assert_eq!(info.sample_rate, 48_000);
+    assert_eq!(info.channels, 2);
+    assert_eq!(info.total_frames, Some(frames as u64));
+
+    let (decoded, decoded_sr) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+    assert_eq!(decoded_sr, 48_000);
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].len(), frames, "pre-skip/end padding not trimmed");
+    // With pre-skip honored the decoded sine lines up with the source; a
+    // misaligned decode (312 samples of priming) would correlate poorly.
+    let dot: f32 = chans[0]
+        .iter()
+        .zip(&decoded[0])
+        .skip(4_800)
+        .take(9_600)
+        .map(|(a, b)| a * b)
+        .sum();
+    let energy: f32 = chans[0].iter().skip(4_800).take(9_600).map(|a| a * a).sum();
+    assert!(
+        dot / energy > 0.9,
+        "decoded opus is misaligned: {}",
+        dot / energy
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn opus_named_ogg_trims_like_opus() {
+    let dir = make_temp_dir("opus_as_ogg");
+    let sr = 48_000;
+    let chans = synth_stereo(sr, 0.50);
+    let frames = chans[0].len();
+    let opus = dir.join("tone.opus");
+    neowaves::wave::export_channels_audio(&chans, sr, &opus).expect("export opus");
+    // Same Ogg Opus stream, only the extension differs.
+    let ogg = dir.join("tone.ogg");
+    std::fs::copy(&opus, &ogg).expect("copy as ogg");
+    let (from_opus, _) = neowaves::audio_io::decode_audio_multi(&opus).expect("decode opus");
+    let (from_ogg, _) = neowaves::audio_io::decode_audio_multi(&ogg).expect("decode ogg");
+    assert_eq!(from_ogg[0].len(), frames, "ogg end padding kept");
+    assert_eq!(from_ogg, from_opus);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn opus_export_keeps_surround_channels() {
+    let dir = make_temp_dir("opus_surround");
+    let sr = 48_000;
+    let frames = sr as usize / 4;
+    let chans: Vec<Vec<f32>> = (0..6)
+        .map(|ch| {
+            (0..frames)
+                .map(|i| {
+                    let t = i as f32 / sr as f32;
+                    (t * (220.0 + 110.0 * ch as f32) * std::f32::consts::TAU).sin() * 0.2
+                })
+                .collect()
+        })
+        .collect();
+    let path = dir.join("bed.opus");
+    neowaves::wave::export_channels_audio(&chans, sr, &path).expect("export 5.1 opus");
+    let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+    assert_eq!(decoded.len(), 6);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn surround_exports_keep_channel_count_and_order() {
+    let dir = make_temp_dir("lossy_surround");
+    let sr = 48_000;
+    let frames = sr as usize / 2;
+    // Slot 3 is the LFE in both 5.1 and 7.1, which the encoders low-pass.
+    let freqs = [440.0f32, 550.0, 660.0, 60.0, 770.0, 880.0, 990.0, 1100.0];
+    for channels in [6usize, 8] {
+        let chans: Vec<Vec<f32>> = freqs[..channels]
+            .iter()
+            .map(|&freq| {
+                (0..frames)
+                    .map(|i| {
+                        let t = i as f32 / sr as f32;
+                        (t * freq * std::f32::consts::TAU).sin() * 0.2
+                    })
+                    .collect()
+            })
+            .collect();
+        for ext in ["m4a", "ogg", "opus"] {
+            let path = dir.join(format!("bed_{channels}.{ext}"));
+            // M4A carries 7.1 only as front-wide (Lc/Rc) 7.1.
+            let layout = (ext == "m4a" && channels == 8)
+                .then(|| neowaves::channel_layout::ChannelLayout::new(8, 0xFF));
+            neowaves::wave::export_channels_audio_with_layout(&chans, sr, &path, None, layout)
+                .unwrap_or_else(|e| panic!("export {channels}ch {ext} failed: {e}"));
+            let info = neowaves::audio_io::read_audio_info(&path).expect("probe");
+            assert_eq!(info.channels as usize, channels, "{ext}: probed channels");
+            let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+            assert_eq!(decoded.len(), channels, "{ext}: decoded channels");
+            // Every decoded channel must match its own source channel best;
+            // a missing WAV <-> codec order remap would swap C/LFE/surrounds.
+            for (ch, out) in decoded.iter().enumerate() {
+                let best = chans
+                    .iter()
+                    .enumerate()
+                    .map(|(src_ch, src)| {
+                        let dot: f32 = src
+                            .iter()
+                            .zip(out)
+                            .skip(4_800)
+                            .take(14_400)
+                            .map(|(a, b)| a * b)
+                            .sum();
+                        (src_ch, dot.abs())
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(src_ch, _)| src_ch);
+                assert_eq!(best, Some(ch), "{ext} {channels}ch: channel {ch} moved");
+            }
+        }
+        if channels == 8 {
+            // Default 7.1 (side + back surrounds) can't be signalled in MP4
+            // and is folded to 5.1.
+            let path = dir.join("bed_8_default.m4a");
+            neowaves::wave::export_channels_audio(&chans, sr, &path).expect("export m4a");
+            let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+            assert_eq!(decoded.len(), 6);
+        }
+        // MP3 can't carry the layout: the default ITU downmix keeps the
+        // centre in both sides instead of dropping it.
+        let path = dir.join(format!("bed_{channels}.mp3"));
+        neowaves::wave::export_channels_audio(&chans, sr, &path).expect("export mp3");
+        let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode mp3");
+        assert_eq!(decoded.len(), 2);
+        for side in &decoded {
+            let dot: f32 = chans[2]
+                .iter()
+                .zip(side)
+                .skip(4_800)
+                .take(14_400)
+                .map(|(a, b)| a * b)
+                .sum();
+            assert!(dot > 1.0, "centre missing from mp3 downmix: {dot}");
+        }
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn mp3_and_m4a_roundtrips_strip_priming_and_padding() {
+    let dir = make_temp_dir("lossy_gapless");
+    let sr = 44_100;
+    let chans = synth_stereo(sr, 0.75);
+    let frames = chans[0].len();
+    for ext in ["mp3", "m4a"] {
+        let path = dir.join(format!("tone.{ext}"));
+        neowaves::wave::export_channels_audio(&chans, sr, &path)
+            .unwrap_or_else(|e| panic!("export {ext} failed: {e}"));
+        let gapless = neowaves::gapless::read_gapless_info(&path)
+            .unwrap_or_else(|| panic!("{ext}: no gapless info written"));
+        assert_eq!(gapless.valid_frames, Some(frames as u64), "{ext}");
+
+        let info = neowaves::audio_io::read_audio_info(&path).expect("probe");
+        assert_eq!(
+            info.total_frames,
+            Some(frames as u64),
+            "{ext}: probed length"
+        );
+        let (decoded, decoded_sr) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+        assert_eq!(decoded_sr, sr);
+        assert_eq!(
+            decoded[0].len(),
+            frames,
+            "{ext}: priming/padding not trimmed"
+        );
+        // Leftover priming of even a few dozen samples would
+        // decorrelate the 440 Hz tone.
+        let dot: f32 = chans[0]
+            .iter()
+            .zip(&decoded[0])
+            .skip(4_410)
+            .take(22_050)
+            .map(|(a, b)| a * b)
+            .sum();
+        let energy: f32 = chans[0]
+            .iter()
+            .skip(4_410)
+            .take(22_050)
+            .map(|a| a * a)
+            .sum();
+        assert!(
+            dot / energy > 0.9,
+            "{ext}: decoded audio is misaligned: {}",
+            dot / energy
+        );
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn wav_channel_mask_survives_overwrite_and_routing_layouts() {
+    use neowaves::channel_layout::ChannelLayout;
+    let dir = make_temp_dir("wav_channel_mask");
+    let sr = 48_000;
+    let chans: Vec<Vec<f32>> = (0..6)
+        .map(|ch| vec![0.05 * (ch as f32 + 1.0); 4_800])
+        .collect();
+    let path = dir.join("side_51.wav");
+    let side_51 = ChannelLayout::new(6, 0x60F);
+    neowaves::wave::export_channels_audio_with_layout(
+        &chans,
+        sr,
+        &path,
+        Some(neowaves::wave::WavBitDepth::Pcm24),
+        Some(side_51),
+    )
+    .expect("export 5.1 wav");
+    let info = neowaves::audio_io::read_audio_info(&path).expect("probe");
+    assert_eq!(info.channel_layout, side_51);
+    assert_eq!(
+        info.channel_layout.labels(),
+        ["L", "R", "C", "LFE", "Ls", "Rs"]
+    );
+
+    // An edit that keeps the channel count keeps the declared mask.
+    let mut edited = chans.clone();
+    for ch in edited.iter_mut() {
+        ch.truncate(2_400);
+    }
+    neowaves::wave::overwrite_audio_from_channels(&edited, sr, &path, false)
+        .expect("overwrite 5.1 wav");
+    assert_eq!(
+        neowaves::audio_io::read_channel_layout(&path),
+        Some(side_51)
+    );
+
+    // An explicit layout replaces it; a plain 16-bit stereo header becomes
+    // extensible only when the layout is not the default one.
+    neowaves::wave::overwrite_audio_from_channels_with_depth(
+        &edited[..5],
+        sr,
+        &path,
+        false,
+        None,
+        Some(ChannelLayout::new(5, 0x607)),
+    )
+    .expect("overwrite 5.0 wav");
+    let info = neowaves::audio_io::read_audio_info(&path).expect("probe 5.0");
+    assert_eq!(info.channels, 5);
+    assert_eq!(info.channel_layout.mask(), 0x607);
+    let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode 5.0");
+    assert_eq!(decoded.len(), 5);
+    assert!((decoded[4][100] - 0.25).abs() < 1e-3);
+
+    let stereo = dir.join("wide.wav");
+    neowaves::wave::export_channels_audio_with_depth(
+        &edited[..2],
+        sr,
+        &stereo,
+        Some(neowaves::wave::WavBitDepth::Pcm16),
+    )
+    .expect("export stereo");
+    assert_eq!(neowaves::audio_io::read_channel_layout(&stereo), None);
+    neowaves::wave::write_wav_channel_layout(&stereo, ChannelLayout::new(2, 0x600))
+        .expect("declare side pair");
+    let info = neowaves::audio_io::read_audio_info(&stereo).expect("probe side pair");
+    assert_eq!(info.channel_layout.labels(), ["Ls", "Rs"]);
+    let (decoded, _) = neowaves::audio_io::decode_audio_multi(&stereo).expect("decode");
+    assert_eq!(decoded[0].len(), 2_400);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn w64_and_caf_roundtrip_audio_and_channel_layout() {
+    use neowaves::channel_layout::ChannelLayout;
+    let dir = make_temp_dir("w64_caf_roundtrip");
+    let sr = 48_000;
+    let chans: Vec<Vec<f32>> = (0..6)
+        .map(|ch| vec![0.05 * (ch as f32 + 1.0); 4_800])
+        .collect();
+    let side_51 = ChannelLayout::new(6, 0x60F);
+    for ext in ["w64", "caf"] {
+        let path = dir.join(format!("surround.{ext}"));
+        neowaves::wave::export_channels_audio_with_layout(
+            &chans,
+            sr,
+            &path,
+            Some(neowaves::wave::WavBitDepth::Pcm24),
+            Some(side_51),
+        )
+        .unwrap_or_else(|e| panic!("export {ext} failed: {e}"));
+        assert_probe_and_decode(&path);
+        let info = neowaves::audio_io::read_audio_info(&path).expect("probe");
+        assert_eq!(info.channels, 6, "{ext}");
+        assert_eq!(info.sample_rate, sr, "{ext}");
+        assert_eq!(info.channel_layout, side_51, "{ext}");
+        let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+        assert_eq!(decoded.len(), 6, "{ext}");
+        assert_eq!(decoded[0].len(), 4_800, "{ext}");
+        assert!((decoded[5][100] - 0.30).abs() < 1e-3, "{ext}");
+
+        let document =
+            neowaves::metadata::inspect_path(&path, Default::default()).expect("inspect");
+        assert_eq!(document.container.key(), ext);
+        assert!(document.audio_mapping.is_some(), "{ext}");
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn bw64_adm_chunks_survive_overwrite_and_name_the_lanes() {
+    let dir = make_temp_dir("bw64_adm");
+    let sr = 48_000;
+    let chans = synth_stereo(sr, 0.1);
+    let path = dir.join("adm.wav");
+    neowaves::wave::export_channels_audio_with_depth(
+        &chans,
+        sr,
+        &path,
+        Some(neowaves::wave::WavBitDepth::Pcm16),
+    )
+    .expect("export stereo");
+
+    // Re-head the file as BW64 and append the ADM chunks.
+    let mut bytes = std::fs::read(&path).expect("read wav");
+    bytes[0..4].copy_from_slice(b"BW64");
+    let mut chna = 2u16.to_le_bytes().to_vec();
+    chna.extend_from_slice(&2u16.to_le_bytes());
+    for (index, uid, track) in [
+        (1u16, "ATU_00000001", "AT_00010001_01"),
+        (2, "ATU_00000002", "AT_00010002_01"),
+    ] {
+        chna.extend_from_slice(&index.to_le_bytes());
+        chna.extend_from_slice(uid.as_bytes());
+        chna.extend_from_slice(track.as_bytes());
+        chna.extend_from_slice(b"AP_00010002\0");
+    }
+    let axml = br#"<ebuCoreMain><audioFormatExtended><audioObject audioObjectID="AO_1001" audioObjectName="Ambience"><audioPackFormatIDRef>AP_00010002</audioPackFormatIDRef><audioTrackUIDRef>ATU_00000001</audioTrackUIDRef><audioTrackUIDRef>ATU_00000002</audioTrackUIDRef></audioObject></audioFormatExtended></ebuCoreMain>"#;
+    for (id, payload) in [(b"chna", chna.as_slice()), (b"axml", axml.as_slice())] {
+        bytes.extend_from_slice(id);
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(payload);
+        if payload.len() & 1 == 1 {
+            bytes.push(0);
+        }
+    }
+    let riff_size = (bytes.len() - 8) as u32;
+    bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    std::fs::write(&path, &bytes).expect("write bw64");
+
+    let lanes = neowaves::adm::read_adm_lanes(&path).expect("adm lanes");
+    assert_eq!(lanes[0].label().as_deref(), Some("Ambience / stereo"));
+    assert_eq!(lanes[1].channel.as_deref(), Some("FrontRight"));
+
+    let mut edited = chans.clone();
+    for ch in edited.iter_mut() {
+        ch.truncate(2_400);
+    }
+    neowaves::wave::overwrite_audio_from_channels(&edited, sr, &path, false)
+        .expect("overwrite bw64");
+    let chunks = neowaves::wav_stream::read_wave_metadata_chunks(&path, u32::MAX as u64)
+        .expect("walk chunks")
+        .expect("wave file");
+    let payload = |id: &[u8; 4]| {
+        chunks
+            .iter()
+            .find(|(chunk_id, _)| chunk_id == id)
+            .map(|(_, payload)| payload.clone())
+    };
+    assert_eq!(payload(b"chna"), Some(chna));
+    assert_eq!(payload(b"axml").as_deref(), Some(axml.as_slice()));
+    assert_eq!(neowaves::adm::read_adm_lanes(&path), Some(lanes));
+    let (decoded, _) = neowaves::audio_io::decode_audio_multi(&path).expect("decode");
+    assert_eq!(decoded[0].len(), 2_400);
+
+    let summary = neowaves::metadata::summarize_path(
+        &path,
+        Default::default(),
+        neowaves::metadata::ScanBudget::selected(),
+        None,
+    )
+    .expect("summary");
+    assert_eq!(summary.adm_objects[0].name, "Ambience");
+    assert_eq!(summary.adm_objects[0].channels, [1, 2]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn flac_stream_matches_full_decode_with_and_without_seek_table() {
+    use neowaves::flac_stream::{FlacStreamCursor, FlacStreamSource};
+    let dir = make_temp_dir("flac_stream");
+    let sr = 48_000;
+    let plain = dir.join("plain.flac");
+    neowaves::wave::export_channels_audio(&synth_stereo(sr, 3.0), sr, &plain).expect("export flac");
+    let (decoded, _) = neowaves::audio_io::decode_audio_multi(&plain).expect("decode flac");
+
+    // Same audio with a SEEKTABLE built from the frame index.
+    let layout = neowaves::flac_stream::read_flac_stream_layout(&plain)
+        .expect("layout")
+        .expect("streamable flac");
+    assert!(layout.seek_table.is_empty(), "encoder writes no SEEKTABLE");
+    let index = neowaves::flac_stream::build_flac_frame_index(&plain, &layout).expect("index");
+    assert!(index.len() > 4);
+    let bytes = std::fs::read(&plain).expect("read flac");
+    let mut seekable = b"fLaC".to_vec();
+    seekable.extend_from_slice(&[0x00, 0x00, 0x00, 34]);
+    seekable.extend_from_slice(&bytes[8..42]);
+    let table_len = (index.len() * 18) as u32;
+    seekable.push(0x80 | 3);
+    seekable.extend_from_slice(&table_len.to_be_bytes()[1..]);
+    for point in &index {
+        seekable.extend_from_slice(&point.sample.to_be_bytes());
+        seekable.extend_from_slice(&(point.offset - layout.audio_offset).to_be_bytes());
+        seekable.extend_from_slice(&4096u16.to_be_bytes());
+    }
+    seekable.extend_from_slice(&bytes[layout.audio_offset as usize..]);
+    let with_table = dir.join("seektable.flac");
+    std::fs::write(&with_table, seekable).expect("write seektable flac");
+    let table_layout = neowaves::flac_stream::read_flac_stream_layout(&with_table)
+        .expect("layout")
+        .expect("streamable flac");
+    assert_eq!(table_layout.seek_table.len(), index.len());
+
+    let seek_to = 100_003usize;
+    for path in [&plain, &with_table] {
+        let source = FlacStreamSource::open(
+            path,
+            Box::new(move || {
+                Some(FlacStreamCursor {
+                    play_frame: seek_to,
+                    loop_start: None,
+                })
+            }),
+        )
+        .expect("open flac stream");
+        assert_eq!(source.len(), decoded[0].len());
+        assert_eq!(source.channel_count(), 2);
+        assert!(source.is_frame_ready(0), "first chunk is primed on open");
+        let started = std::time::Instant::now();
+        while !(source.is_frame_ready(seek_to) && source.is_frame_ready(seek_to + 4_000)) {
+            assert!(
+                started.elapsed() < std::time::Duration::from_secs(10),
+                "stream worker never reached the seek target: {}",
+                path.display()
+            );
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        let reader = source.block_reader();
+        for frame in (0..64).chain(seek_to..seek_to + 4_000) {
+            assert!(reader.is_frame_ready(frame));
+            for (ch, channel) in decoded.iter().enumerate() {
+                assert_eq!(
+                    reader.sample_at_frame(frame, ch),
+                    channel[frame],
+                    "{} frame {frame} ch {ch}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    let proxy = neowaves::audio_io::build_proxy_preview(&with_table, 10_000)
+        .expect("flac proxy")
+        .expect("flac proxy available");
+    assert_eq!(proxy.total_source_frames, decoded[0].len());
+    assert_eq!(proxy.channels.len(), 2);
+    assert!(!proxy.is_full_resolution);
+    // Stereo proxies split the budget per channel: 5 000 points each.
+    let stride = decoded[0].len().div_ceil(5_000);
+    assert_eq!(proxy.channels[0][7], decoded[0][7 * stride]);
+    assert_eq!(proxy.channels[1][11], decoded[1][11 * stride]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,4 +1,5 @@
-//! End-to-end test for `--cli batch inspect` driving the real binary.
+//! End-to-end tests for `--cli batch inspect` (and `item inspect --loudness`)
+//! driving the real binary.
 
 use std::path::PathBuf;
 use std::process::Command;
@@ -176,3 +177,91 @@ fn cli_batch_inspect_reports_expected_severities() {
 
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn cli_loudness_range_check_and_report_with_histogram() {
+    let dir = make_temp_dir("lra");
+    let sr = 48_000u32;
+    // 6 s loud then 6 s 20 dB quieter: LRA near 20 LU. A steady tone: ~0 LU.
+    let mut dynamic = tone(sr, 6.0, 0.3);
+    dynamic.extend(tone(sr, 6.0, 0.03));
+    neowaves::wave::export_channels_audio(&[dynamic], sr, &dir.join("dynamic.wav"))
+        .expect("dynamic");
+    let steady = dir.join("steady.wav");
+    neowaves::wave::export_channels_audio(&[tone(sr, 6.0, 0.3)], sr, &steady).expect("steady");
+
+    let session = dir.join("lra.nwsess");
+    run_cli(&[
+        "session",
+        "new",
+        "--folder",
+        dir.to_str().unwrap(),
+        "--output",
+        session.to_str().unwrap(),
+    ]);
+
+    let loudness_csv = dir.join("loudness.csv");
+    let out = run_cli(&[
+        "batch",
+        "inspect",
+        "--session",
+        session.to_str().unwrap(),
+        "--no-loudness",
+        "--no-true-peak",
+        "--no-silence",
+        "--no-loop",
+        "--max-lra",
+        "15",
+        "--loudness-report",
+        loudness_csv.to_str().unwrap(),
+    ]);
+    let result = &out["result"];
+    assert_eq!(result["counts"]["warning"], 1, "{result}");
+    assert_eq!(result["config"]["max_lra_lu"], 15.0);
+    let rows = result["rows"].as_array().expect("rows");
+    let dynamic_row = rows
+        .iter()
+        .find(|r| r["path"].as_str().unwrap().ends_with("dynamic.wav"))
+        .expect("dynamic row");
+    assert!(
+        dynamic_row["lra_lu"].as_f64().unwrap() > 15.0,
+        "{dynamic_row}"
+    );
+    assert_eq!(dynamic_row["issues"][0]["kind"], "LoudnessRangeOver");
+
+    let csv = std::fs::read_to_string(&loudness_csv).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().ends_with("short_term_histogram"));
+    let bodies: Vec<&str> = lines.collect();
+    assert_eq!(bodies.len(), 2, "{csv}");
+    assert!(bodies.iter().all(|line| line.contains(':')), "{csv}");
+
+    let loudness_json = dir.join("steady_loudness.json");
+    let out = run_cli(&[
+        "item",
+        "inspect",
+        "--input",
+        steady.to_str().unwrap(),
+        "--loudness-report",
+        loudness_json.to_str().unwrap(),
+    ]);
+    let loudness = &out["result"]["loudness"];
+    assert!(
+        loudness["loudness_range_lu"].as_f64().unwrap() < 1.0,
+        "{loudness}"
+    );
+    let histogram = loudness["short_term_histogram"]
+        .as_array()
+        .expect("histogram");
+    let counted: u64 = histogram
+        .iter()
+        .map(|bin| bin["count"].as_u64().unwrap())
+        .sum();
+    assert_eq!(counted, loudness["short_term_blocks"].as_u64().unwrap());
+    let body: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&loudness_json).unwrap()).unwrap();
+    assert_eq!(body["files"].as_array().unwrap().len(), 1);
+    assert_eq!(body["histogram_bin_lu"], 1.0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -2487,6 +2487,7 @@ mod kittest_suite {
             (neowaves::ViewMode::Tempogram, "Tempogram", "Tempogram"),
             (neowaves::ViewMode::Chromagram, "Chromagram", "Chromagram"),
             (neowaves::ViewMode::World, "World (F0/Env)", "World"),
+            (neowaves::ViewMode::Loudness, "Loudness", "Loudness"),
             (neowaves::ViewMode::Waveform, "Wave", "Waveform"),
         ];
         for (mode, combo_value, debug_name) in cases {
@@ -0,0 +1,172 @@
+//! End-to-end test for `--cli batch catalog-export` and loading the catalog
+//! back as an external data source.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn make_temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "neowaves_library_catalog_cli_{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("library")).expect("create temp dir");
+    dir
+}
+
+fn tone(sr: u32, secs: f32) -> Vec<f32> {
+    let frames = ((sr as f32) * secs).max(1.0) as usize;
+    (0..frames)
+        .map(|i| ((i as f32 / sr as f32) * 440.0 * std::f32::consts::TAU).sin() * 0.4)
+        .collect()
+}
+
+fn run_cli(args: &[&str]) -> serde_json::Value {
+    let exe = env!("CARGO_BIN_EXE_neowaves");
+    let out = Command::new(exe)
+        .arg("--cli")
+        .args(args)
+        .output()
+        .expect("run neowaves --cli");
+    assert!(
+        out.status.success(),
+        "cli failed: {:?}\nstdout: {}\nstderr: {}",
+        args,
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    serde_json::from_slice(&out.stdout).expect("cli stdout is JSON")
+}
+
+#[test]
+fn cli_catalog_export_round_trips_through_external_sources() {
+    let dir = make_temp_dir();
+    let library = dir.join("library");
+    let sr = 48_000u32;
+    let rain = library.join("rain_roof.wav");
+    neowaves::wave::export_channels_audio(&[tone(sr, 0.5), tone(sr, 0.5)], sr, &rain)
+        .expect("rain");
+    neowaves::loop_markers::write_loop_markers(&rain, Some((1_000, 20_000))).expect("loop");
+    neowaves::wave::export_channels_audio(&[tone(sr, 0.25)], sr, &library.join("door.wav"))
+        .expect("door");
+    run_cli(&[
+        "item",
+        "metadata",
+        "template",
+        "apply",
+        "--input",
+        rain.to_str().unwrap(),
+        "--set",
+        "bext.description=Rain on tin roof",
+    ]);
+
+    let session = dir.join("catalog.nwsess");
+    run_cli(&[
+        "session",
+        "new",
+        "--folder",
+        library.to_str().unwrap(),
+        "--output",
+        session.to_str().unwrap(),
+    ]);
+
+    let catalog = dir.join("library.sqlite");
+    let out = run_cli(&[
+        "batch",
+        "catalog-export",
+        "--session",
+        session.to_str().unwrap(),
+        "--output",
+        catalog.to_str().unwrap(),
+    ]);
+    assert_eq!(out["result"]["entries"], 2, "{}", out["result"]);
+    assert_eq!(out["result"]["format"], "sqlite");
+    assert_eq!(out["result"]["schema_version"], 1);
+
+    let db = rusqlite::Connection::open(&catalog).expect("open catalog");
+    let version: String = db
+        .query_row(
+            "SELECT value FROM catalog_info WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(version, "1");
+    let (channels, lufs): (i64, Option<f64>) = db
+        .query_row(
+            "SELECT channels, lufs_i FROM files WHERE name = 'rain_roof.wav'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(channels, 2);
+    assert!(lufs.is_some(), "loudness measured");
+    let peaks: i64 = db
+        .query_row("SELECT COUNT(*) FROM peaks", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(peaks, 2);
+    let loop_end: i64 = db
+        .query_row(
+            "SELECT end_frame FROM markers WHERE kind = 'loop'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(loop_end, 20_000);
+    let description: String = db
+        .query_row(
+            "SELECT value FROM fields WHERE value = 'Rain on tin roof'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("bext description in fields");
+    assert_eq!(description, "Rain on tin roof");
+    drop(db);
+
+    // Existing output is kept unless --overwrite.
+    let exe = env!("CARGO_BIN_EXE_neowaves");
+    let again = Command::new(exe)
+        .args([
+            "--cli",
+            "batch",
+            "catalog-export",
+            "--session",
+            session.to_str().unwrap(),
+            "--output",
+            catalog.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run cli");
+    assert!(!again.status.success());
+
+    // The catalog loads as an external source keyed by file name.
+    let out = run_cli(&[
+        "external",
+        "source",
+        "add",
+        "--session",
+        session.to_str().unwrap(),
+        "--input",
+        catalog.to_str().unwrap(),
+    ]);
+    let after = &out["result"]["after"];
+    assert!(after["load_error"].is_null(), "{after}");
+    assert_eq!(after["headers"][0], "File");
+    assert_eq!(after["match_count"], 2, "{after}");
+
+    // CSV catalog: the same flat table.
+    let csv_out = dir.join("library.csv");
+    run_cli(&[
+        "batch",
+        "catalog-export",
+        "--session",
+        session.to_str().unwrap(),
+        "--output",
+        csv_out.to_str().unwrap(),
+        "--no-measure",
+    ]);
+    let csv = std::fs::read_to_string(&csv_out).unwrap();
+    assert!(csv.starts_with("File,Path,Format,"), "{csv}");
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains("rain_roof.wav,rain_roof.wav,wav,"));
+}
@@ -1,4 +1,7 @@
-//! End-to-end coverage for the read-only Metadata Inspector CLI.
+//! End-to-end coverage for the Metadata Inspector CLI (read-only inspection,
+//! `item metadata set` tag edits, `item metadata repair`, `item metadata
+//! chunk` and `item artwork` writes, `item metadata diff`, `item metadata
+//! template`, `item metadata cart`, and metadata full-text `list search`).
 
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
@@ -215,3 +218,623 @@ fn metadata_commands_are_lossless_and_do_not_modify_the_source() {
     );
     assert_eq!(file_sha256(&input), before_hash);
 }
+
+#[test]
+fn metadata_set_edits_tags_and_dry_run_leaves_the_file_alone() {
+    let dir = make_temp_dir("set");
+    let input = dir.join("source.flac");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &input)
+        .expect("write FLAC fixture");
+    let input_str = input.to_str().expect("UTF-8 fixture path");
+
+    let before_hash = file_sha256(&input);
+    let dry = run_cli(&[
+        "item",
+        "metadata",
+        "set",
+        "--input",
+        input_str,
+        "--set",
+        "title=Door",
+        "--dry-run",
+    ]);
+    assert_eq!(dry["result"]["after"]["title"], "Door");
+    assert_eq!(file_sha256(&input), before_hash);
+
+    let set = run_cli(&[
+        "item",
+        "metadata",
+        "set",
+        "--input",
+        input_str,
+        "--set",
+        "title=Door",
+        "--set",
+        "MOOD=tense",
+        "--set",
+        "isrc=JPX012600001",
+    ]);
+    assert_eq!(set["command"], "item.metadata.set");
+    assert_eq!(set["result"]["after"]["title"], "Door");
+    assert_eq!(set["result"]["after"]["isrc"], "JPX012600001");
+    assert_eq!(set["result"]["after"]["custom"]["MOOD"], "tense");
+
+    let removed = run_cli(&[
+        "item", "metadata", "set", "--input", input_str, "--remove", "MOOD",
+    ]);
+    assert_eq!(removed["result"]["before"]["custom"]["MOOD"], "tense");
+    assert!(removed["result"]["after"]["custom"].get("MOOD").is_none());
+    assert_eq!(removed["result"]["after"]["title"], "Door");
+
+    let wav = dir.join("source.wav");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &wav)
+        .expect("write WAV fixture");
+    let output = run_cli_raw(&[
+        "item",
+        "metadata",
+        "set",
+        "--input",
+        wav.to_str().expect("UTF-8 fixture path"),
+        "--set",
+        "title=x",
+    ]);
+    assert!(!output.status.success());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn artwork_set_resizes_and_strip_removes_it() {
+    let dir = make_temp_dir("artwork");
+    let input = dir.join("source.flac");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &input)
+        .expect("write FLAC fixture");
+    let input_str = input.to_str().expect("UTF-8 fixture path");
+    let cover = dir.join("cover.png");
+    image::RgbaImage::from_pixel(120, 60, image::Rgba([10, 120, 200, 255]))
+        .save(&cover)
+        .expect("write PNG fixture");
+
+    let set = run_cli(&[
+        "item",
+        "artwork",
+        "--input",
+        input_str,
+        "--set",
+        cover.to_str().expect("UTF-8 fixture path"),
+        "--max-size",
+        "40",
+        "--encode",
+        "jpeg",
+    ]);
+    assert_eq!(set["result"]["action"], "set");
+    assert_eq!(set["result"]["had_artwork"], false);
+    assert_eq!(set["result"]["artwork"]["mime"], "image/jpeg");
+    assert_eq!(set["result"]["artwork"]["width"], 40);
+    assert_eq!(set["result"]["artwork"]["height"], 20);
+
+    let exported = dir.join("exported.png");
+    let extract = run_cli(&[
+        "item",
+        "artwork",
+        "--input",
+        input_str,
+        "--output",
+        exported.to_str().expect("UTF-8 fixture path"),
+    ]);
+    assert_eq!(extract["result"]["artwork_found"], true);
+    let decoded = image::open(&exported).expect("decode exported artwork");
+    assert_eq!((decoded.width(), decoded.height()), (40, 20));
+
+    let strip = run_cli(&["item", "artwork", "--input", input_str, "--strip"]);
+    assert_eq!(strip["result"]["had_artwork"], true);
+    let extract = run_cli(&["item", "artwork", "--input", input_str]);
+    assert_eq!(extract["result"]["artwork_found"], false);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn metadata_repair_rebuilds_a_truncated_wav_into_a_copy() {
+    let dir = make_temp_dir("repair");
+    let input = dir.join("take.wav");
+    neowaves::wave::export_channels_audio(
+        &[tone(48_000, 0.05), tone(48_000, 0.05)],
+        48_000,
+        &input,
+    )
+    .expect("write WAV fixture");
+    // Cut the file mid-frame, as a recorder that lost power would.
+    let mut bytes = std::fs::read(&input).expect("read WAV fixture");
+    let data = bytes
+        .windows(4)
+        .position(|window| window == b"data")
+        .expect("data chunk");
+    bytes.truncate(data + 8 + 400 + 3);
+    std::fs::write(&input, &bytes).expect("write truncated fixture");
+    let input_str = input.to_str().expect("UTF-8 fixture path");
+    let before_hash = file_sha256(&input);
+
+    let dry = run_cli(&[
+        "item",
+        "metadata",
+        "repair",
+        "--input",
+        input_str,
+        "--dry-run",
+    ]);
+    assert_eq!(dry["command"], "item.metadata.repair");
+    assert!(dry["result"]["output"].is_null());
+    let codes: Vec<&str> = dry["result"]["changes"]
+        .as_array()
+        .expect("changes")
+        .iter()
+        .filter_map(|change| change["code"].as_str())
+        .collect();
+    assert!(codes.contains(&"wave.data_overruns_eof"), "{codes:?}");
+    assert!(codes.contains(&"wave.partial_frame"), "{codes:?}");
+    assert!(codes.contains(&"riff.size_fixed"), "{codes:?}");
+
+    let output = dir.join("take.repaired.wav");
+    let output_str = output.to_str().expect("UTF-8 output path");
+    let repaired = run_cli(&[
+        "item", "metadata", "repair", "--input", input_str, "--output", output_str,
+    ]);
+    assert_eq!(repaired["result"]["changes"], dry["result"]["changes"]);
+    assert_eq!(file_sha256(&input), before_hash);
+    let inspected = run_cli(&["item", "metadata", "inspect", "--input", output_str]);
+    assert_eq!(inspected["warnings"], serde_json::json!([]));
+
+    let again = run_cli_raw(&[
+        "item", "metadata", "repair", "--input", input_str, "--output", output_str,
+    ]);
+    assert!(!again.status.success(), "existing output needs --overwrite");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+fn chunk_ids(value: &serde_json::Value) -> Vec<String> {
+    value["chunks"]
+        .as_array()
+        .expect("chunks")
+        .iter()
+        .filter_map(|chunk| chunk["id"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn metadata_chunk_insert_move_export_and_remove() {
+    let dir = make_temp_dir("chunk");
+    let input = dir.join("take.wav");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &input)
+        .expect("write WAV fixture");
+    let input_str = input.to_str().expect("UTF-8 fixture path");
+    let before_hash = file_sha256(&input);
+
+    let listed = run_cli(&["item", "metadata", "chunk", "list", "--input", input_str]);
+    assert_eq!(listed["command"], "item.metadata.chunk.list");
+    let original = chunk_ids(&listed["result"]);
+    assert!(original.iter().any(|id| id == "data"), "{original:?}");
+
+    let copy = dir.join("take.gmeta.wav");
+    let copy_str = copy.to_str().expect("UTF-8 output path");
+    let inserted = run_cli(&[
+        "item", "metadata", "chunk", "insert", "--input", input_str, "--id", "gmet", "--hex",
+        "01 02 03", "--output", copy_str,
+    ]);
+    assert_eq!(file_sha256(&input), before_hash);
+    assert_eq!(
+        chunk_ids(&inserted["result"]).last().map(String::as_str),
+        Some("gmet")
+    );
+
+    let moved = run_cli(&[
+        "item",
+        "metadata",
+        "chunk",
+        "move",
+        "--input",
+        copy_str,
+        "--id",
+        "gmet",
+        "--before",
+        "data",
+        "--in-place",
+    ]);
+    let ids = chunk_ids(&moved["result"]);
+    let gmet = ids.iter().position(|id| id == "gmet").expect("gmet");
+    let data = ids.iter().position(|id| id == "data").expect("data");
+    assert_eq!(gmet + 1, data, "{ids:?}");
+    let backup = dir.join("take.gmeta.wav.bak");
+    assert_eq!(
+        moved["result"]["backup"].as_str(),
+        backup.to_str(),
+        "in-place writes keep a backup"
+    );
+    assert!(backup.is_file());
+    let inspected = run_cli(&["item", "metadata", "inspect", "--input", copy_str]);
+    assert_eq!(inspected["warnings"], serde_json::json!([]));
+
+    let payload = dir.join("gmet.bin");
+    run_cli(&[
+        "item",
+        "metadata",
+        "chunk",
+        "export",
+        "--input",
+        copy_str,
+        "--id",
+        "gmet",
+        "--output",
+        payload.to_str().expect("UTF-8 payload path"),
+    ]);
+    assert_eq!(
+        std::fs::read(&payload).expect("read payload"),
+        vec![1, 2, 3]
+    );
+
+    let removed = run_cli(&[
+        "item",
+        "metadata",
+        "chunk",
+        "remove",
+        "--input",
+        copy_str,
+        "--id",
+        "gmet",
+        "--in-place",
+    ]);
+    assert_eq!(chunk_ids(&removed["result"]), original);
+    let required = run_cli_raw(&[
+        "item",
+        "metadata",
+        "chunk",
+        "remove",
+        "--input",
+        copy_str,
+        "--id",
+        "data",
+        "--in-place",
+    ]);
+    assert!(!required.status.success(), "data cannot be removed");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn list_search_matches_metadata_text_with_field_qualifiers() {
+    let dir = make_temp_dir("fulltext");
+    let cache_home = dir.join("cache");
+    let library = dir.join("library");
+    std::fs::create_dir_all(&library).expect("create library dir");
+    for (name, fields) in [
+        (
+            "rain.flac",
+            &["comment=Heavy rain on tin roof", "title=Storm"][..],
+        ),
+        ("wind.flac", &["comment=Wind gust"][..]),
+    ] {
+        let path = library.join(name);
+        neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &path)
+            .expect("write FLAC fixture");
+        let mut args = vec!["item", "metadata", "set", "--input", path.to_str().unwrap()];
+        for field in fields {
+            args.extend(["--set", field]);
+        }
+        run_cli(&args);
+    }
+    let session = dir.join("library.nwsess");
+    let session_str = session.to_str().expect("UTF-8 session path");
+    run_cli(&[
+        "session",
+        "new",
+        "--folder",
+        library.to_str().unwrap(),
+        "--output",
+        session_str,
+    ]);
+
+    // Keep the shared summary cache (and its FTS index) inside the fixture.
+    let search = |query: &str| -> Vec<String> {
+        let output = Command::new(env!("CARGO_BIN_EXE_neowaves"))
+            .arg("--cli")
+            .args(["list", "search", "--session", session_str, "--query", query])
+            .env("XDG_CACHE_HOME", &cache_home)
+            .output()
+            .expect("run neowaves --cli");
+        assert!(
+            output.status.success(),
+            "list search {query:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let value: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("CLI stdout is JSON");
+        let mut names = value["result"]["rows"]
+            .as_array()
+            .expect("rows")
+            .iter()
+            .map(|row| {
+                Path::new(row["path"].as_str().expect("row path"))
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+    assert_eq!(search("heavy roof"), vec!["rain.flac"]);
+    assert_eq!(search("desc:gust"), vec!["wind.flac"]);
+    assert_eq!(search("tags:storm desc:\"tin roof\""), vec!["rain.flac"]);
+    assert!(search("desc:storm").is_empty());
+    // Plain substring search over list columns still applies.
+    assert_eq!(search(".flac"), vec!["rain.flac", "wind.flac"]);
+    assert!(cache_home
+        .join("neowaves")
+        .join("metadata-v1.sqlite3")
+        .is_file());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn metadata_diff_compares_a_file_with_its_backup_and_another_file() {
+    let dir = make_temp_dir("diff");
+    let input = dir.join("take.wav");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &input)
+        .expect("write WAV fixture");
+    let input_str = input.to_str().expect("UTF-8 fixture path");
+    let copy = dir.join("copy.wav");
+    std::fs::copy(&input, &copy).expect("copy fixture");
+    let copy_str = copy.to_str().expect("UTF-8 copy path");
+
+    let same = run_cli(&["item", "metadata", "diff", input_str, copy_str]);
+    assert_eq!(same["command"], "item.metadata.diff");
+    assert_eq!(same["result"]["identical"], true);
+    run_cli(&[
+        "item",
+        "metadata",
+        "diff",
+        input_str,
+        copy_str,
+        "--fail-on-diff",
+    ]);
+
+    let no_backup = run_cli_raw(&["item", "metadata", "diff", input_str]);
+    assert!(
+        !no_backup.status.success(),
+        "RIGHT is required without a .bak"
+    );
+
+    run_cli(&[
+        "item",
+        "metadata",
+        "chunk",
+        "insert",
+        "--input",
+        input_str,
+        "--id",
+        "gmet",
+        "--hex",
+        "01 02 03",
+        "--in-place",
+    ]);
+    let against_backup = run_cli(&["item", "metadata", "diff", input_str, "--only-changes"]);
+    let result = &against_backup["result"];
+    assert_eq!(result["identical"], false);
+    assert!(
+        result["left"]["label"]
+            .as_str()
+            .is_some_and(|label| label.ends_with("take.wav.bak")),
+        "{result}"
+    );
+    let nodes = result["nodes"].as_array().expect("nodes");
+    assert!(
+        nodes.iter().all(|node| node["kind"] != "unchanged"),
+        "{result}"
+    );
+    let added = nodes
+        .iter()
+        .find(|node| {
+            node["path"]
+                .as_str()
+                .is_some_and(|path| path.ends_with("/gmet"))
+        })
+        .expect("inserted chunk is listed");
+    assert_eq!(added["kind"], "added");
+    assert!(added["left"].is_null());
+    assert_eq!(result["node_counts"]["added"], 1);
+
+    let failed = run_cli_raw(&[
+        "item",
+        "metadata",
+        "diff",
+        copy_str,
+        input_str,
+        "--fail-on-diff",
+    ]);
+    assert!(!failed.status.success(), "--fail-on-diff exits non-zero");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn metadata_template_renders_tokens_into_bext_and_tags() {
+    let dir = make_temp_dir("template");
+    let wav = dir.join("take.wav");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &wav)
+        .expect("write WAV fixture");
+    let flac = dir.join("door.flac");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.05)], 48_000, &flac)
+        .expect("write FLAC fixture");
+    let wav_str = wav.to_str().expect("UTF-8 fixture path");
+    let flac_str = flac.to_str().expect("UTF-8 fixture path");
+    let library = dir.join("templates.json");
+    let library_str = library.to_str().expect("UTF-8 library path");
+
+    let listed = run_cli(&[
+        "item",
+        "metadata",
+        "template",
+        "list",
+        "--library",
+        library_str,
+    ]);
+    assert_eq!(listed["command"], "item.metadata.template.list");
+    assert_eq!(listed["result"]["templates"][0]["name"], "BWF from UCS");
+
+    let apply_args = [
+        "item",
+        "metadata",
+        "template",
+        "apply",
+        "--input",
+        wav_str,
+        "--input",
+        flac_str,
+        "--set",
+        "bext.description={name} {index:02}/{count}",
+        "--set",
+        "tag.title={name|upper}",
+    ];
+    let wav_hash = file_sha256(&wav);
+    let flac_hash = file_sha256(&flac);
+    let mut dry_args = apply_args.to_vec();
+    dry_args.push("--dry-run");
+    let dry = run_cli(&dry_args);
+    assert_eq!(dry["command"], "item.metadata.template.apply");
+    let files = dry["result"]["files"].as_array().expect("files");
+    assert_eq!(files[0]["status"], "planned");
+    assert_eq!(files[0]["fields"][0]["value"], "take 01/02");
+    assert_eq!(files[0]["fields"][1]["applies"], false);
+    assert_eq!(files[1]["fields"][1]["value"], "DOOR");
+    assert_eq!(file_sha256(&wav), wav_hash);
+    assert_eq!(file_sha256(&flac), flac_hash);
+
+    let applied = run_cli(&apply_args);
+    assert_eq!(
+        applied["result"]["written_paths"]
+            .as_array()
+            .expect("written paths")
+            .len(),
+        2
+    );
+    let tags = run_cli(&[
+        "item",
+        "metadata",
+        "set",
+        "--input",
+        flac_str,
+        "--set",
+        "genre=Foley",
+        "--dry-run",
+    ]);
+    assert_eq!(tags["result"]["before"]["title"], "DOOR");
+    let summary = run_cli(&["item", "metadata", "summary", "--input", wav_str]);
+    assert!(
+        summary["result"].to_string().contains("take 01/02"),
+        "{summary}"
+    );
+
+    let unknown = run_cli_raw(&[
+        "item",
+        "metadata",
+        "template",
+        "apply",
+        "--input",
+        wav_str,
+        "--set",
+        "bext.description={nope}",
+    ]);
+    assert!(!unknown.status.success(), "unknown tokens are rejected");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn metadata_cart_writes_fields_and_maps_post_timers_to_markers() {
+    use neowaves::markers::{read_markers, write_markers, MarkerEntry};
+
+    let dir = make_temp_dir("cart");
+    let wav = dir.join("spot.wav");
+    neowaves::wave::export_channels_audio(&[tone(48_000, 0.5)], 48_000, &wav)
+        .expect("write WAV fixture");
+    write_markers(
+        &wav,
+        48_000,
+        48_000,
+        &[
+            MarkerEntry {
+                sample: 2_400,
+                label: "INTe".to_string(),
+            },
+            MarkerEntry {
+                sample: 12_000,
+                label: "Tag".to_string(),
+            },
+        ],
+    )
+    .expect("write markers");
+    let wav_str = wav.to_str().expect("UTF-8 fixture path");
+
+    let empty = run_cli(&["item", "metadata", "cart", "--input", wav_str]);
+    assert_eq!(empty["command"], "item.metadata.cart");
+    assert_eq!(empty["result"]["changed"], false);
+    assert!(empty["result"]["after"].is_null());
+
+    let written = run_cli(&[
+        "item",
+        "metadata",
+        "cart",
+        "--input",
+        wav_str,
+        "--set",
+        "title=Spring Spot",
+        "--set",
+        "cut_id=C1042",
+        "--set",
+        "end_date=2026-12-31",
+        "--timers-from-markers",
+    ]);
+    let after = &written["result"]["after"];
+    assert_eq!(after["title"], "Spring Spot");
+    assert_eq!(after["post_timers"][0]["usage"], "INTe");
+    assert_eq!(after["post_timers"][0]["sample"], 2_400);
+    assert_eq!(after["post_timers"].as_array().map(Vec::len), Some(1));
+
+    let summary = run_cli(&["item", "metadata", "summary", "--input", wav_str]);
+    let summary_text = summary["result"].to_string();
+    assert!(summary_text.contains("cart.cut_id"), "{summary}");
+    assert!(summary_text.contains("C1042"), "{summary}");
+
+    let moved = run_cli(&[
+        "item",
+        "metadata",
+        "cart",
+        "--input",
+        wav_str,
+        "--timer",
+        "SEGs=19200",
+        "--markers-from-timers",
+    ]);
+    assert_eq!(moved["result"]["after"]["cut_id"], "C1042");
+    assert_eq!(moved["result"]["markers_written"], 1);
+    let markers = read_markers(&wav, 48_000, 48_000).expect("read markers");
+    let labels: Vec<(usize, &str)> = markers
+        .iter()
+        .map(|marker| (marker.sample, marker.label.as_str()))
+        .collect();
+    assert_eq!(labels, vec![(12_000, "Tag"), (19_200, "SEGs")]);
+
+    let bad = run_cli_raw(&[
+        "item",
+        "metadata",
+        "cart",
+        "--input",
+        wav_str,
+        "--set",
+        "color=red",
+    ]);
+    assert!(!bad.status.success(), "unknown cart fields are rejected");
+
+    let removed = run_cli(&["item", "metadata", "cart", "--input", wav_str, "--remove"]);
+    assert!(removed["result"]["after"].is_null());
+    assert!(neowaves::wave::read_wav_cart(&wav)
+        .expect("read cart")
+        .is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
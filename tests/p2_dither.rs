@@ -4,7 +4,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use neowaves::wave::{
     codec_export_options, decode_wav_multi, export_channels_audio_with_depth, f32_to_i16_sym,
-    set_codec_export_options, DitherMode, Quantizer, TpdfDither, WavBitDepth,
+    quantize_channels_dithered_in_place, set_codec_export_options, DitherMode, Quantizer,
+    TpdfDither, WavBitDepth, DITHER_AUTO_BLANK_HOLD,
 };
 
 // Tests below mutate the process-global codec export options; serialize them
@@ -117,6 +118,131 @@ fn noise_shaped_dither_moves_error_out_of_the_low_band() {
     );
 }
 
+/// Quantization error of a quiet 220 Hz sine at 16-bit through `mode`.
+fn quantization_error(mode: DitherMode, sr: u32, n: usize) -> Vec<f32> {
+    let mut q =
+        Quantizer::new(32768.0, i16::MIN as f64, i16::MAX as f64, 1, mode).with_sample_rate(sr);
+    (0..n)
+        .map(|i| {
+            let v = ((i as f32 / sr as f32) * 220.0 * std::f32::consts::TAU).sin() * 0.01;
+            q.quantize(0, v) as f32 / 32768.0 - v
+        })
+        .collect()
+}
+
+fn band_power(err: &[f32], sr: u32, lo: f32, hi: f32) -> f64 {
+    let mut total = 0.0;
+    let mut f = lo;
+    while f <= hi {
+        total += goertzel_power(err, sr as f32, f);
+        f += 250.0;
+    }
+    total
+}
+
+#[test]
+fn first_order_highpass_sits_between_flat_and_second_order() {
+    let sr = 48_000;
+    let low = |mode| band_power(&quantization_error(mode, sr, 32_768), sr, 500.0, 4_000.0);
+    let flat = low(DitherMode::Tpdf);
+    let first = low(DitherMode::TpdfHighpass);
+    let second = low(DitherMode::TpdfNoiseShaped);
+    assert!(
+        first < flat * 0.5,
+        "1st-order shaping should lower sub-4kHz error: flat={flat:e} first={first:e}"
+    );
+    assert!(
+        second < first,
+        "2nd-order shaping should be steeper than 1st-order: first={first:e} second={second:e}"
+    );
+}
+
+#[test]
+fn psychoacoustic_curve_dips_mid_band_and_parks_noise_high() {
+    for sr in [44_100, 48_000] {
+        let flat = quantization_error(DitherMode::Tpdf, sr, 32_768);
+        let shaped = quantization_error(DitherMode::TpdfShaped, sr, 32_768);
+        let flat_mid = band_power(&flat, sr, 2_000.0, 5_000.0);
+        let shaped_mid = band_power(&shaped, sr, 2_000.0, 5_000.0);
+        assert!(
+            shaped_mid < flat_mid * 0.1,
+            "{sr} Hz: 2-5 kHz error should drop by >10 dB: flat={flat_mid:e} shaped={shaped_mid:e}"
+        );
+        let flat_high = band_power(&flat, sr, 18_000.0, 20_000.0);
+        let shaped_high = band_power(&shaped, sr, 18_000.0, 20_000.0);
+        assert!(
+            shaped_high > flat_high * 10.0,
+            "{sr} Hz: noise should be parked near 18-20 kHz: flat={flat_high:e} shaped={shaped_high:e}"
+        );
+    }
+}
+
+#[test]
+fn auto_blank_writes_true_zeros_over_digital_silence() {
+    for mode in [
+        DitherMode::Tpdf,
+        DitherMode::TpdfHighpass,
+        DitherMode::TpdfNoiseShaped,
+        DitherMode::TpdfShaped,
+    ] {
+        let mut plain = Quantizer::new(32768.0, i16::MIN as f64, i16::MAX as f64, 1, mode)
+            .with_sample_rate(44_100);
+        let mut blank = Quantizer::new(32768.0, i16::MIN as f64, i16::MAX as f64, 1, mode)
+            .with_sample_rate(44_100)
+            .with_auto_blank(true);
+        // Signal, then a long run of digital silence, then signal again.
+        let tone = |i: usize| ((i as f32 / 44_100.0) * 1_000.0 * std::f32::consts::TAU).sin() * 0.2;
+        for i in 0..4_096 {
+            plain.quantize(0, tone(i));
+            blank.quantize(0, tone(i));
+        }
+        let hold = DITHER_AUTO_BLANK_HOLD as usize;
+        let silent_plain: Vec<i32> = (0..8_192).map(|_| plain.quantize(0, 0.0)).collect();
+        let silent_blank: Vec<i32> = (0..8_192).map(|_| blank.quantize(0, 0.0)).collect();
+        assert!(
+            silent_plain.iter().any(|&q| q != 0),
+            "{mode:?}: dither without auto-blank should toggle LSBs in silence"
+        );
+        assert!(
+            silent_blank[hold..].iter().all(|&q| q == 0),
+            "{mode:?}: auto-blank must hold exact zeros once silence is established"
+        );
+        let resumed: Vec<i32> = (0..4_096)
+            .map(|i| blank.quantize(0, tone(i) * 0.001))
+            .collect();
+        assert!(
+            resumed.iter().any(|&q| q != 0),
+            "{mode:?}: dither must resume when signal returns"
+        );
+    }
+}
+
+#[test]
+fn dithered_in_place_quantization_lands_on_the_16bit_grid() {
+    let mut chans = synth(48_000, 0.05, 0.3);
+    quantize_channels_dithered_in_place(
+        &mut chans,
+        WavBitDepth::Pcm16,
+        DitherMode::TpdfShaped,
+        false,
+        48_000,
+    );
+    for ch in &chans {
+        for &v in ch {
+            let code = v * 32768.0;
+            assert_eq!(code, code.round(), "sample {v} is off the 16-bit grid");
+        }
+    }
+}
+
+#[test]
+fn dither_mode_prefs_names_round_trip() {
+    for mode in DitherMode::ALL {
+        assert_eq!(DitherMode::from_prefs_name(mode.prefs_name()), Some(mode));
+    }
+    assert_eq!(DitherMode::from_prefs_name("bogus"), None);
+}
+
 #[test]
 fn dither_24bit_export_honors_flag() {
     let _guard = CODEC_OPTS_LOCK.lock().unwrap();
@@ -104,4 +104,58 @@ mod p3_audition {
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn playlist_plays_to_end_of_list_through_queued_handoffs() {
+        let sr = 48_000u32;
+        // Own folder: make_temp_dir() is shared with the test above.
+        let dir = std::env::temp_dir().join(format!("neowaves_p3_playlist_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create playlist dir");
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let p = dir.join(format!("cue_{i}.wav"));
+                neowaves::wave::export_channels_audio(&synth(sr, 200.0 + 100.0 * i as f32), sr, &p)
+                    .expect("export fixture");
+                p
+            })
+            .collect();
+        let mut cfg = StartupConfig::default();
+        cfg.open_folder = Some(dir.clone());
+        cfg.open_first = false;
+        let mut harness = harness_with_startup(cfg);
+        wait_until(&mut harness, "scan", |h| h.state().files.len() >= 3);
+
+        harness
+            .state_mut()
+            .test_set_playlist_options(false, false, 20);
+        assert!(harness.state_mut().test_set_list_multi_selection(&paths));
+        assert!(harness.state_mut().test_start_playlist_from_here());
+        // First row starts as soon as it is rendered; the second is queued.
+        wait_until(&mut harness, "first row queued behind", |h| {
+            h.state().test_playlist_progress() == Some((Some(0), 1, true))
+        });
+        assert!(harness.state_mut().test_playlist_simulate_handoff());
+        wait_until(&mut harness, "second row playing, third queued", |h| {
+            h.state().test_playlist_progress() == Some((Some(1), 2, true))
+        });
+        assert!(harness.state_mut().test_playlist_simulate_handoff());
+        wait_until(&mut harness, "last row playing", |h| {
+            h.state().test_playlist_progress() == Some((Some(2), 3, false))
+        });
+        // End of list: the playlist finishes instead of wrapping.
+        assert!(!harness.state_mut().test_playlist_simulate_stop(true));
+
+        // A stop mid-row ends a running playlist.
+        assert!(harness.state_mut().test_start_playlist_from_here());
+        wait_until(&mut harness, "restarted playlist", |h| {
+            h.state()
+                .test_playlist_progress()
+                .is_some_and(|(current, _, _)| current.is_some())
+        });
+        assert!(!harness.state_mut().test_playlist_simulate_stop(false));
+        assert_eq!(harness.state().test_playlist_progress(), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }